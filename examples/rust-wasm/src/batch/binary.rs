@@ -0,0 +1,76 @@
+use rayon::prelude::*;
+use wasm_bindgen::prelude::*;
+
+use super::{BatchOp2, WasmBatchProcessor};
+use crate::{install, WasmError};
+
+#[wasm_bindgen]
+impl WasmBatchProcessor {
+    /// Apply `op` to each pair `(a[i], b[i])`.
+    ///
+    /// `strict` follows [`WasmBatchProcessor::process_batch`]: division by
+    /// zero is an error instead of `±inf` or `NaN`.
+    #[wasm_bindgen]
+    pub fn process_binary(
+        &mut self,
+        a: &[f64],
+        b: &[f64],
+        op: BatchOp2,
+        strict: bool,
+    ) -> Result<Vec<f64>, WasmError> {
+        let timing = self.profile("process_binary", a.len());
+        if a.len() != b.len() {
+            return Err(WasmError::dimension("Operand b length", a.len(), b.len()));
+        }
+        if strict {
+            self.check_domain(op, a, |i| b[i])?;
+        }
+
+        self.zip_into_output(a, b, |x, y| op.apply(x, y))?;
+        timing.finish(Ok(self.output_buffer.clone()))
+    }
+
+    /// Apply `op` to each pair `(a[i], scalar)`, with the same `strict`
+    /// policy as [`WasmBatchProcessor::process_binary`]
+    #[wasm_bindgen]
+    pub fn process_scalar(
+        &mut self,
+        a: &[f64],
+        scalar: f64,
+        op: BatchOp2,
+        strict: bool,
+    ) -> Result<Vec<f64>, WasmError> {
+        let timing = self.profile("process_scalar", a.len());
+        if strict {
+            self.check_domain(op, a, |_| scalar)?;
+        }
+
+        self.map_into_output(a, |x| op.apply(x, scalar))?;
+        timing.finish(Ok(self.output_buffer.clone()))
+    }
+}
+
+impl WasmBatchProcessor {
+    /// Report the first pair `(a[i], b(i))` outside the domain of `op`
+    fn check_domain(
+        &self,
+        op: BatchOp2,
+        a: &[f64],
+        b: impl Fn(usize) -> f64 + Sync + Send,
+    ) -> Result<(), WasmError> {
+        let invalid = install(&self.runtime, || {
+            a.par_iter().enumerate().find_map_first(|(index, &x)| {
+                op.domain_error(x, b(index)).map(|reason| (index, reason))
+            })
+        })?;
+        match invalid {
+            Some((index, reason)) => Err(WasmError::OutOfDomain {
+                index,
+                values: vec![a[index], b(index)],
+                reason: reason.into(),
+                slice: None,
+            }),
+            None => Ok(()),
+        }
+    }
+}
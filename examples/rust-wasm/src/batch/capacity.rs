@@ -0,0 +1,171 @@
+use wasm_bindgen::prelude::*;
+
+use super::WasmBatchProcessor;
+use crate::WasmError;
+
+/// Output buffer capacity management.
+///
+/// By default the output buffer grows to fit any batch. WASM linear memory
+/// never shrinks, so a processor created with
+/// [`WasmBatchProcessor::with_strict_capacity`] instead rejects batches
+/// larger than its capacity; grow it deliberately with
+/// [`WasmBatchProcessor::reserve`].
+#[wasm_bindgen]
+impl WasmBatchProcessor {
+    /// Create a processor like [`WasmBatchProcessor::new`] whose output
+    /// buffer never grows implicitly: batches larger than its capacity are
+    /// an error
+    #[wasm_bindgen]
+    pub fn with_strict_capacity(
+        batch_size: usize,
+        num_threads: usize,
+    ) -> Result<WasmBatchProcessor, WasmError> {
+        let mut processor = WasmBatchProcessor::new(batch_size, num_threads)?;
+        processor.strict_capacity = true;
+        Ok(processor)
+    }
+
+    /// Create a processor on the global pool with room for `n_batches`
+    /// batches of `batch_size` elements, growing memory once up front.
+    ///
+    /// The output buffer reserves `batch_size * n_batches` elements. A
+    /// second block of the same size is then allocated and released: on
+    /// WASM the linear memory it grew stays behind in the allocator, ready
+    /// for wasm-bindgen's copies of the input arrays, so
+    /// `batch_size * n_batches * 16` bytes are claimed in one step rather
+    /// than a 64 KiB page at a time.
+    #[wasm_bindgen]
+    pub fn with_preallocated_memory(
+        batch_size: usize,
+        n_batches: usize,
+    ) -> Result<WasmBatchProcessor, WasmError> {
+        let elements = batch_size.checked_mul(n_batches).ok_or_else(|| {
+            WasmError::invalid(
+                "preallocation",
+                format!("{n_batches} batches of {batch_size} elements are too large"),
+            )
+        })?;
+        let allocation_error = |e| WasmError::ResourceUnavailable {
+            reason: format!(
+                "Failed to preallocate {n_batches} batches of {batch_size} elements: {e}"
+            ),
+        };
+
+        let mut processor = WasmBatchProcessor::new(batch_size, 0)?;
+        processor
+            .output_buffer
+            .try_reserve_exact(elements)
+            .map_err(allocation_error)?;
+
+        // Released after the output buffer is placed, so the allocator
+        // cannot hand the same block back for it; `black_box` keeps the
+        // optimiser from eliding an allocation nothing reads
+        let mut staging: Vec<f64> = Vec::new();
+        staging
+            .try_reserve_exact(elements)
+            .map_err(allocation_error)?;
+        drop(std::hint::black_box(staging));
+
+        Ok(processor)
+    }
+
+    /// Whether oversized batches are rejected rather than reallocating
+    #[wasm_bindgen(getter)]
+    pub fn strict_capacity(&self) -> bool {
+        self.strict_capacity
+    }
+
+    /// Number of elements the output buffer holds without reallocating
+    #[wasm_bindgen]
+    pub fn capacity(&self) -> usize {
+        self.output_buffer.capacity()
+    }
+
+    /// Grow the output buffer to hold at least `n` elements
+    #[wasm_bindgen]
+    pub fn reserve(&mut self, n: usize) {
+        let additional = n.saturating_sub(self.output_buffer.len());
+        self.output_buffer.reserve_exact(additional);
+    }
+
+    /// Grow the output buffer's capacity by at least `additional_bytes` in
+    /// a single reallocation.
+    ///
+    /// A capacity past `isize::MAX` bytes is `INVALID_ARGUMENT`, and one the
+    /// allocator cannot provide is `RESOURCE_UNAVAILABLE`; the buffer is
+    /// left as it was either way.
+    #[wasm_bindgen]
+    pub fn reserve_additional(&mut self, additional_bytes: usize) -> Result<(), WasmError> {
+        let size = std::mem::size_of::<f64>();
+        let spare = self.output_buffer.capacity() - self.output_buffer.len();
+        // No buffer may exceed `isize::MAX` bytes
+        let limit = isize::MAX as usize / size - self.output_buffer.len();
+        let additional = (additional_bytes / size + usize::from(additional_bytes % size != 0))
+            .checked_add(spare)
+            .filter(|&elements| elements <= limit)
+            .ok_or_else(|| {
+                WasmError::invalid(
+                    "additional_bytes",
+                    format!("{additional_bytes} more bytes would overflow the buffer's capacity"),
+                )
+            })?;
+        self.output_buffer
+            .try_reserve_exact(additional)
+            .map_err(|e| WasmError::ResourceUnavailable {
+                reason: format!("Failed to reserve {additional_bytes} more bytes: {e}"),
+            })
+    }
+
+    /// Discard the last output and release capacity beyond the `batch_size`
+    /// the processor was created with
+    #[wasm_bindgen]
+    pub fn shrink_to_fit(&mut self) {
+        self.output_buffer.clear();
+        self.output_buffer.shrink_to(self.batch_size);
+        self.output_buffer_f32.clear();
+        self.output_buffer_f32.shrink_to(self.batch_size);
+    }
+
+    /// Bytes reserved by the processor's buffers
+    #[wasm_bindgen]
+    pub fn memory_bytes(&self) -> usize {
+        (self.output_buffer.capacity() + self.window_size()) * std::mem::size_of::<f64>()
+            + self.output_buffer_f32.capacity() * std::mem::size_of::<f32>()
+    }
+
+    /// [`WasmBatchProcessor::memory_bytes`], to pair with
+    /// [`WasmBatchProcessor::used_bytes`]
+    #[wasm_bindgen]
+    pub fn allocated_bytes(&self) -> usize {
+        self.memory_bytes()
+    }
+
+    /// [`WasmBatchProcessor::memory_bytes`] under the name
+    /// [`memory_report`](crate::memory_report) looks for
+    #[wasm_bindgen]
+    pub fn heap_bytes(&self) -> usize {
+        self.memory_bytes()
+    }
+
+    /// Bytes of [`WasmBatchProcessor::allocated_bytes`] holding the last
+    /// outputs, with the streaming window counted in full
+    #[wasm_bindgen]
+    pub fn used_bytes(&self) -> usize {
+        (self.output_buffer.len() + self.window_size()) * std::mem::size_of::<f64>()
+            + self.output_buffer_f32.len() * std::mem::size_of::<f32>()
+    }
+}
+
+impl WasmBatchProcessor {
+    /// In strict mode, reject a batch of `len` elements that would not fit.
+    /// The `f32` buffer is held to the same element count.
+    pub(super) fn check_capacity(&self, len: usize) -> Result<(), WasmError> {
+        if self.strict_capacity && len > self.output_buffer.capacity() {
+            return Err(WasmError::CapacityExceeded {
+                requested: len,
+                capacity: self.output_buffer.capacity(),
+            });
+        }
+        Ok(())
+    }
+}
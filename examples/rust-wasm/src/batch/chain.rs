@@ -0,0 +1,109 @@
+use js_sys::Array;
+use serde::Deserialize;
+use wasm_bindgen::prelude::*;
+
+use super::{BatchOp, WasmBatchProcessor};
+use crate::WasmError;
+
+/// One step of a fused operation chain, tagged by `op` in JavaScript, e.g.
+/// `{ op: "sqrt" }`, `{ op: "mul", value: 2 }` or
+/// `{ op: "clamp", min: 0, max: 1 }`
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase", deny_unknown_fields)]
+pub enum ChainStep {
+    Square,
+    Sqrt,
+    Sin,
+    Cos,
+    Tan,
+    Exp,
+    Ln,
+    Abs,
+    Neg,
+    Reciprocal,
+    Sigmoid,
+    Tanh,
+    Relu,
+    Add { value: f64 },
+    Mul { value: f64 },
+    Pow { value: f64 },
+    Clamp { min: f64, max: f64 },
+}
+
+impl ChainStep {
+    fn apply(self, x: f64) -> f64 {
+        match self {
+            ChainStep::Square => BatchOp::Square.apply(x),
+            ChainStep::Sqrt => BatchOp::Sqrt.apply(x),
+            ChainStep::Sin => BatchOp::Sin.apply(x),
+            ChainStep::Cos => BatchOp::Cos.apply(x),
+            ChainStep::Tan => BatchOp::Tan.apply(x),
+            ChainStep::Exp => BatchOp::Exp.apply(x),
+            ChainStep::Ln => BatchOp::Ln.apply(x),
+            ChainStep::Abs => BatchOp::Abs.apply(x),
+            ChainStep::Neg => BatchOp::Neg.apply(x),
+            ChainStep::Reciprocal => BatchOp::Reciprocal.apply(x),
+            ChainStep::Sigmoid => BatchOp::Sigmoid.apply(x),
+            ChainStep::Tanh => BatchOp::Tanh.apply(x),
+            ChainStep::Relu => BatchOp::Relu.apply(x),
+            ChainStep::Add { value } => x + value,
+            ChainStep::Mul { value } => x * value,
+            ChainStep::Pow { value } => x.powf(value),
+            ChainStep::Clamp { min, max } => x.max(min).min(max),
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl WasmBatchProcessor {
+    /// Apply a chain of steps such as
+    /// `[{ op: "sqrt" }, { op: "mul", value: 2 }, { op: "sin" }]` to every
+    /// element in one pass, without intermediate arrays.
+    ///
+    /// Unary steps take the names accepted by
+    /// [`WasmBatchProcessor::process_batch_str`]; `add`, `mul` and `pow` take
+    /// a `value` and `clamp` takes `min` and `max`.
+    #[wasm_bindgen]
+    pub fn process_chain(&mut self, data: &[f64], ops: &JsValue) -> Result<Vec<f64>, WasmError> {
+        let timing = self.profile("process_chain", data.len());
+        let ops: &Array = ops
+            .dyn_ref()
+            .ok_or_else(|| WasmError::invalid("chain", "must be an array of steps"))?;
+
+        let steps = ops
+            .iter()
+            .enumerate()
+            .map(|(i, op)| {
+                let step: ChainStep = serde_wasm_bindgen::from_value(op).map_err(|error| {
+                    WasmError::invalid(format!("chain step {i}"), error.to_string())
+                })?;
+                if let ChainStep::Clamp { min, max } = step {
+                    if min.is_nan() || max.is_nan() || min > max {
+                        return Err(WasmError::invalid(
+                            format!("chain step {i}"),
+                            format!("clamp requires min <= max, got {min} and {max}"),
+                        ));
+                    }
+                }
+                Ok(step)
+            })
+            .collect::<Result<Vec<_>, WasmError>>()?;
+
+        timing.finish(self.apply_chain(data, &steps))
+    }
+}
+
+impl WasmBatchProcessor {
+    /// Fused application of `steps` to every element, for callers in Rust
+    pub fn apply_chain(
+        &mut self,
+        data: &[f64],
+        steps: &[ChainStep],
+    ) -> Result<Vec<f64>, WasmError> {
+        let timing = self.profile("apply_chain", data.len());
+        let result = self
+            .map_into_output(data, |x| steps.iter().fold(x, |x, step| step.apply(x)))
+            .map(|()| self.output_buffer.clone());
+        timing.finish(result)
+    }
+}
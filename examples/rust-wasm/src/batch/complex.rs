@@ -0,0 +1,126 @@
+use rayon::prelude::*;
+use wasm_bindgen::prelude::*;
+
+use super::{WasmBatchProcessor, MIN_PARALLEL_LEN};
+use crate::{install, WasmError};
+
+/// Operations over interleaved `(re, im)` pairs
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ComplexOp {
+    Magnitude,
+    Phase,
+    Conjugate,
+    Normalize,
+}
+
+impl ComplexOp {
+    const SUPPORTED: &'static str = "magnitude, phase, conjugate, normalize";
+
+    fn parse(name: &str) -> Result<ComplexOp, WasmError> {
+        match name.to_ascii_lowercase().as_str() {
+            "magnitude" => Ok(ComplexOp::Magnitude),
+            "phase" => Ok(ComplexOp::Phase),
+            "conjugate" => Ok(ComplexOp::Conjugate),
+            "normalize" => Ok(ComplexOp::Normalize),
+            _ => Err(WasmError::unsupported(
+                "complex operation",
+                name,
+                Self::SUPPORTED,
+            )),
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl WasmBatchProcessor {
+    /// Apply `op` to complex numbers stored as interleaved `(re, im)` pairs.
+    ///
+    /// `"magnitude"` and `"phase"` (in radians, from `atan2`) return one
+    /// value per pair. `"conjugate"` and `"normalize"` return interleaved
+    /// pairs; normalising leaves zero at zero.
+    #[wasm_bindgen]
+    pub fn process_complex(
+        &mut self,
+        interleaved: &[f64],
+        op: &str,
+    ) -> Result<Vec<f64>, WasmError> {
+        let timing = self.profile("process_complex", interleaved.len());
+        let op = ComplexOp::parse(op)?;
+        if interleaved.len() % 2 != 0 {
+            return Err(WasmError::invalid(
+                "interleaved complex data",
+                format!("needs an even length, got {}", interleaved.len()),
+            ));
+        }
+
+        match op {
+            ComplexOp::Magnitude => self.map_pairs(interleaved, |re, im| re.hypot(im))?,
+            ComplexOp::Phase => self.map_pairs(interleaved, |re, im| im.atan2(re))?,
+            ComplexOp::Conjugate => self.transform_pairs(interleaved, |re, im| (re, -im))?,
+            ComplexOp::Normalize => self.transform_pairs(interleaved, |re, im| {
+                let magnitude = re.hypot(im);
+                if magnitude == 0.0 {
+                    (re, im)
+                } else {
+                    (re / magnitude, im / magnitude)
+                }
+            })?,
+        }
+        timing.finish(Ok(self.output_buffer.clone()))
+    }
+}
+
+impl WasmBatchProcessor {
+    /// Write `f(re, im)` of every pair into the output buffer
+    fn map_pairs(
+        &mut self,
+        interleaved: &[f64],
+        f: impl Fn(f64, f64) -> f64 + Sync + Send,
+    ) -> Result<(), WasmError> {
+        self.check_capacity(interleaved.len() / 2)?;
+        let output = &mut self.output_buffer;
+        if interleaved.len() < MIN_PARALLEL_LEN {
+            output.clear();
+            output.extend(interleaved.chunks_exact(2).map(|pair| f(pair[0], pair[1])));
+        } else {
+            install(&self.runtime, || {
+                interleaved
+                    .par_chunks_exact(2)
+                    .map(|pair| f(pair[0], pair[1]))
+                    .collect_into_vec(output);
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Write the pair `f(re, im)` of every pair into the output buffer,
+    /// keeping the interleaved layout
+    fn transform_pairs(
+        &mut self,
+        interleaved: &[f64],
+        f: impl Fn(f64, f64) -> (f64, f64) + Sync + Send,
+    ) -> Result<(), WasmError> {
+        self.check_capacity(interleaved.len())?;
+        let output = &mut self.output_buffer;
+        output.clear();
+        output.resize(interleaved.len(), 0.0);
+
+        let transform = |(out, pair): (&mut [f64], &[f64])| {
+            (out[0], out[1]) = f(pair[0], pair[1]);
+        };
+        if interleaved.len() < MIN_PARALLEL_LEN {
+            output
+                .chunks_exact_mut(2)
+                .zip(interleaved.chunks_exact(2))
+                .for_each(transform);
+        } else {
+            install(&self.runtime, || {
+                output
+                    .par_chunks_exact_mut(2)
+                    .zip(interleaved.par_chunks_exact(2))
+                    .for_each(transform);
+            })?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,84 @@
+use wasm_bindgen::prelude::*;
+
+use super::WasmBatchProcessor;
+use crate::WasmError;
+
+#[wasm_bindgen]
+impl WasmBatchProcessor {
+    /// Evaluate `coeffs[0] + coeffs[1] * x + coeffs[2] * x^2 + ...` for every
+    /// element with Horner's rule. No coefficients is the zero polynomial.
+    #[wasm_bindgen]
+    pub fn process_polynomial(
+        &mut self,
+        data: &[f64],
+        coeffs: &[f64],
+    ) -> Result<Vec<f64>, WasmError> {
+        let timing = self.profile("process_polynomial", data.len());
+        self.map_into_output(data, |x| {
+            coeffs.iter().rev().fold(0.0, |acc, &c| acc.mul_add(x, c))
+        })?;
+        timing.finish(Ok(self.output_buffer.clone()))
+    }
+
+    /// Interpolate every element linearly against the breakpoints
+    /// `(xs[i], ys[i])`, such as a sensor calibration curve.
+    ///
+    /// `xs` must be strictly increasing with at least two points. Inputs
+    /// outside `xs[0]..=xs[n - 1]` take the nearest end value with `clamp`,
+    /// and are extrapolated along the end segment otherwise.
+    #[wasm_bindgen]
+    pub fn process_piecewise_linear(
+        &mut self,
+        data: &[f64],
+        xs: &[f64],
+        ys: &[f64],
+        clamp: bool,
+    ) -> Result<Vec<f64>, WasmError> {
+        let timing = self.profile("process_piecewise_linear", data.len());
+        if xs.len() != ys.len() {
+            return Err(WasmError::dimension(
+                "Breakpoint ys length",
+                xs.len(),
+                ys.len(),
+            ));
+        }
+        if xs.len() < 2 {
+            return Err(WasmError::invalid(
+                "breakpoints",
+                "piecewise-linear mapping needs at least two",
+            ));
+        }
+        if xs[0].is_nan() {
+            return Err(WasmError::invalid("breakpoints", "xs[0] is NaN"));
+        }
+        if let Some(i) = (1..xs.len()).find(|&i| xs[i].is_nan() || xs[i] <= xs[i - 1]) {
+            return Err(WasmError::invalid(
+                "breakpoints",
+                format!(
+                    "must be strictly increasing: xs[{i}] = {} follows xs[{}] = {}",
+                    xs[i],
+                    i - 1,
+                    xs[i - 1]
+                ),
+            ));
+        }
+
+        let last = xs.len() - 1;
+        self.map_into_output(data, |x| {
+            if clamp {
+                if x <= xs[0] {
+                    return ys[0];
+                }
+                if x >= xs[last] {
+                    return ys[last];
+                }
+            }
+            // Segment `[xs[i], xs[i + 1]]`, using the end segments beyond
+            // the table
+            let i = xs.partition_point(|&b| b <= x).clamp(1, last) - 1;
+            let t = (x - xs[i]) / (xs[i + 1] - xs[i]);
+            ys[i] + t * (ys[i + 1] - ys[i])
+        })?;
+        timing.finish(Ok(self.output_buffer.clone()))
+    }
+}
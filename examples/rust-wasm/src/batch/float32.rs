@@ -0,0 +1,114 @@
+use rayon::prelude::*;
+use wasm_bindgen::prelude::*;
+
+use super::{BatchOp, BatchOp2, WasmBatchProcessor, MIN_PARALLEL_LEN};
+use crate::{install, WasmError};
+
+/// Single-precision variants for `Float32Array` data.
+///
+/// Values are computed in `f32` throughout and written to a separate `f32`
+/// output buffer, so a batch moves half the bytes of its `f64` equivalent.
+/// Strict-mode domain checks widen each value to `f64`, which is exact and
+/// does not affect the results.
+#[wasm_bindgen]
+impl WasmBatchProcessor {
+    /// [`WasmBatchProcessor::process_batch`] in single precision
+    #[wasm_bindgen]
+    pub fn process_batch_f32(
+        &mut self,
+        data: &[f32],
+        op: BatchOp,
+        strict: bool,
+    ) -> Result<Vec<f32>, WasmError> {
+        let timing = self.profile("process_batch_f32", data.len());
+        if strict {
+            let invalid = install(&self.runtime, || {
+                data.par_iter().enumerate().find_map_first(|(index, &x)| {
+                    op.domain_error(f64::from(x)).map(|reason| (index, reason))
+                })
+            })?;
+            if let Some((index, reason)) = invalid {
+                return Err(WasmError::OutOfDomain {
+                    index,
+                    values: vec![f64::from(data[index])],
+                    reason: reason.into(),
+                    slice: None,
+                });
+            }
+        }
+
+        self.check_capacity(data.len())?;
+        let output =
+            cleared_with_capacity(&mut self.output_buffer_f32, self.output_buffer.capacity());
+        if data.len() < MIN_PARALLEL_LEN {
+            output.clear();
+            output.extend(data.iter().map(|&x| op.apply(x)));
+        } else {
+            install(&self.runtime, || {
+                data.par_iter()
+                    .map(|&x| op.apply(x))
+                    .collect_into_vec(output);
+            })?;
+        }
+        timing.finish(Ok(self.output_buffer_f32.clone()))
+    }
+
+    /// [`WasmBatchProcessor::process_binary`] in single precision
+    #[wasm_bindgen]
+    pub fn process_binary_f32(
+        &mut self,
+        a: &[f32],
+        b: &[f32],
+        op: BatchOp2,
+        strict: bool,
+    ) -> Result<Vec<f32>, WasmError> {
+        let timing = self.profile("process_binary_f32", a.len());
+        if a.len() != b.len() {
+            return Err(WasmError::dimension("Operand b length", a.len(), b.len()));
+        }
+        if strict {
+            let invalid = install(&self.runtime, || {
+                a.par_iter()
+                    .zip(b)
+                    .enumerate()
+                    .find_map_first(|(index, (&x, &y))| {
+                        op.domain_error(f64::from(x), f64::from(y))
+                            .map(|reason| (index, reason))
+                    })
+            })?;
+            if let Some((index, reason)) = invalid {
+                return Err(WasmError::OutOfDomain {
+                    index,
+                    values: vec![f64::from(a[index]), f64::from(b[index])],
+                    reason: reason.into(),
+                    slice: None,
+                });
+            }
+        }
+
+        self.check_capacity(a.len())?;
+        let output =
+            cleared_with_capacity(&mut self.output_buffer_f32, self.output_buffer.capacity());
+        if a.len() < MIN_PARALLEL_LEN {
+            output.clear();
+            output.extend(a.iter().zip(b).map(|(&x, &y)| op.apply(x, y)));
+        } else {
+            install(&self.runtime, || {
+                a.par_iter()
+                    .zip(b)
+                    .map(|(&x, &y)| op.apply(x, y))
+                    .collect_into_vec(output);
+            })?;
+        }
+        timing.finish(Ok(self.output_buffer_f32.clone()))
+    }
+}
+
+/// `output` cleared and holding at least `capacity` elements, the capacity
+/// of the `f64` buffer, so a batch within the configured capacity never
+/// grows it
+fn cleared_with_capacity(output: &mut Vec<f32>, capacity: usize) -> &mut Vec<f32> {
+    output.clear();
+    output.reserve_exact(capacity);
+    output
+}
@@ -0,0 +1,60 @@
+use rayon::prelude::*;
+use wasm_bindgen::prelude::*;
+
+use super::WasmBatchProcessor;
+use crate::{install, WasmError};
+
+#[wasm_bindgen]
+impl WasmBatchProcessor {
+    /// Compute `x * scale + offset` for every element with a single rounding
+    /// step via `f64::mul_add`
+    #[wasm_bindgen]
+    pub fn fma_batch(
+        &mut self,
+        data: &[f64],
+        scale: f64,
+        offset: f64,
+    ) -> Result<Vec<f64>, WasmError> {
+        let timing = self.profile("fma_batch", data.len());
+        self.check_capacity(data.len())?;
+        let output = &mut self.output_buffer;
+        install(&self.runtime, || {
+            data.par_iter()
+                .map(|&x| x.mul_add(scale, offset))
+                .collect_into_vec(output);
+        })?;
+
+        timing.finish(Ok(self.output_buffer.clone()))
+    }
+
+    /// Compute `data[i] * scales[i] + offsets[i]` for every element
+    #[wasm_bindgen]
+    pub fn fma_batch_vec(
+        &mut self,
+        data: &[f64],
+        scales: &[f64],
+        offsets: &[f64],
+    ) -> Result<Vec<f64>, WasmError> {
+        let timing = self.profile("fma_batch_vec", data.len());
+        if scales.len() != data.len() || offsets.len() != data.len() {
+            let (what, actual) = if scales.len() != data.len() {
+                ("Scales length", scales.len())
+            } else {
+                ("Offsets length", offsets.len())
+            };
+            return Err(WasmError::dimension(what, data.len(), actual));
+        }
+        self.check_capacity(data.len())?;
+
+        let output = &mut self.output_buffer;
+        install(&self.runtime, || {
+            data.par_iter()
+                .zip(scales)
+                .zip(offsets)
+                .map(|((&x, &scale), &offset)| x.mul_add(scale, offset))
+                .collect_into_vec(output);
+        })?;
+
+        timing.finish(Ok(self.output_buffer.clone()))
+    }
+}
@@ -0,0 +1,216 @@
+use rayon::prelude::*;
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    install,
+    profile::{OpScope, OpStats, Profiler},
+    WasmError, WasmRuntime,
+};
+
+mod binary;
+mod capacity;
+mod chain;
+mod complex;
+mod curve;
+mod float32;
+mod fma;
+mod ops;
+mod output;
+mod sliced;
+mod window;
+
+pub use chain::ChainStep;
+pub use ops::{BatchOp, BatchOp2};
+
+/// Batches shorter than this are processed on the calling thread
+const MIN_PARALLEL_LEN: usize = 4096;
+
+/// Element-wise operations over batches of `f64` values, reusing one output
+/// buffer between calls
+#[wasm_bindgen]
+pub struct WasmBatchProcessor {
+    runtime: WasmRuntime,
+    profiler: Profiler,
+    /// Capacity requested at construction, kept by `shrink_to_fit`
+    batch_size: usize,
+    /// Reject batches larger than the output buffer instead of growing it
+    strict_capacity: bool,
+    output_buffer: Vec<f64>,
+    /// Output of the `*_f32` methods, allocated on first use with the
+    /// capacity of `output_buffer`
+    output_buffer_f32: Vec<f32>,
+    /// Ring buffer for `push_sample`, allocated once per processor
+    window: window::SampleWindow,
+}
+
+#[wasm_bindgen]
+impl WasmBatchProcessor {
+    /// Create a processor sized for `batch_size` elements, on the shared
+    /// runtime of `num_threads` workers (see [`WasmRuntime::shared`]), with a
+    /// 32-sample streaming window
+    #[wasm_bindgen(constructor)]
+    pub fn new(batch_size: usize, num_threads: usize) -> Result<WasmBatchProcessor, WasmError> {
+        Ok(WasmBatchProcessor::with_runtime(
+            batch_size,
+            &WasmRuntime::shared(num_threads)?,
+        ))
+    }
+
+    /// Like `new`, but sharing `runtime`'s thread pool
+    #[wasm_bindgen]
+    pub fn with_runtime(batch_size: usize, runtime: &WasmRuntime) -> WasmBatchProcessor {
+        WasmBatchProcessor {
+            runtime: runtime.clone(),
+            profiler: Profiler::default(),
+            batch_size,
+            strict_capacity: false,
+            output_buffer: Vec::with_capacity(batch_size),
+            output_buffer_f32: Vec::new(),
+            window: window::SampleWindow::new(window::DEFAULT_WINDOW_SIZE),
+        }
+    }
+
+    /// The runtime whose thread pool this processor uses
+    #[wasm_bindgen(getter)]
+    pub fn runtime(&self) -> WasmRuntime {
+        self.runtime.clone()
+    }
+
+    /// Workers available to this processor, which change if its runtime is
+    /// resized
+    #[wasm_bindgen]
+    pub fn current_num_threads(&self) -> usize {
+        self.runtime.num_threads()
+    }
+
+    /// Time this processor's operations for `last_op_stats`, forgetting
+    /// the last operation's stats either way
+    #[wasm_bindgen]
+    pub fn set_profiling(&mut self, enabled: bool) {
+        self.profiler.set_enabled(enabled);
+    }
+
+    /// What the last operation run while profiling did, as `{ operation,
+    /// wall_ms, input_elements, pool_threads, bytes_allocated }`, or
+    /// `undefined` if none has run.
+    ///
+    /// `wall_ms` covers the operation inside the module, not copying its
+    /// arguments and result across the JavaScript boundary,
+    /// `pool_threads` is the size of the pool its parallel work ran on, not
+    /// how many of those workers took a share, and `bytes_allocated` is the
+    /// capacity of the buffers it returned.
+    #[wasm_bindgen]
+    pub fn last_op_stats(&self) -> JsValue {
+        self.last_op()
+            .map_or(JsValue::UNDEFINED, |stats| stats.to_js())
+    }
+
+    /// Apply `op` to every element.
+    ///
+    /// With `strict`, inputs outside an operation's domain (`ln` of a
+    /// non-positive value, `reciprocal` of zero) are an error; otherwise
+    /// they give the IEEE result (`NaN` or `±inf`).
+    #[wasm_bindgen]
+    pub fn process_batch(
+        &mut self,
+        data: &[f64],
+        op: BatchOp,
+        strict: bool,
+    ) -> Result<Vec<f64>, WasmError> {
+        let timing = self.profile("process_batch", data.len());
+        if strict {
+            self.check_unary_domain(op, data)?;
+        }
+
+        self.map_into_output(data, |x| op.apply(x))?;
+        timing.finish(Ok(self.output_buffer.clone()))
+    }
+
+    /// [`WasmBatchProcessor::process_batch`] with the operation given by name
+    #[wasm_bindgen]
+    pub fn process_batch_str(
+        &mut self,
+        data: &[f64],
+        operation: &str,
+        strict: bool,
+    ) -> Result<Vec<f64>, WasmError> {
+        let timing = self.profile("process_batch_str", data.len());
+        timing.finish(self.process_batch(data, BatchOp::parse(operation)?, strict))
+    }
+}
+
+impl WasmBatchProcessor {
+    /// Stats of the last operation run while profiling, as
+    /// [`WasmBatchProcessor::last_op_stats`] reports them
+    pub fn last_op(&self) -> Option<OpStats> {
+        self.profiler.last()
+    }
+
+    /// Profile `operation` on `input_elements` values until the scope is
+    /// finished or dropped
+    fn profile(&self, operation: &'static str, input_elements: usize) -> OpScope {
+        self.profiler.scope(operation, input_elements)
+    }
+
+    /// Report the first element outside the domain of `op`
+    fn check_unary_domain(&self, op: BatchOp, data: &[f64]) -> Result<(), WasmError> {
+        let invalid = install(&self.runtime, || {
+            data.par_iter()
+                .enumerate()
+                .find_map_first(|(index, &x)| op.domain_error(x).map(|reason| (index, reason)))
+        })?;
+        match invalid {
+            Some((index, reason)) => Err(WasmError::OutOfDomain {
+                index,
+                values: vec![data[index]],
+                reason: reason.into(),
+                slice: None,
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Write `f` of every element into the output buffer, on the calling
+    /// thread for short batches
+    fn map_into_output(
+        &mut self,
+        data: &[f64],
+        f: impl Fn(f64) -> f64 + Sync + Send,
+    ) -> Result<(), WasmError> {
+        self.check_capacity(data.len())?;
+        let output = &mut self.output_buffer;
+        if data.len() < MIN_PARALLEL_LEN {
+            output.clear();
+            output.extend(data.iter().map(|&x| f(x)));
+        } else {
+            install(&self.runtime, || {
+                data.par_iter().map(|&x| f(x)).collect_into_vec(output);
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Write `f` of every pair of elements into the output buffer, on the
+    /// calling thread for short batches
+    fn zip_into_output(
+        &mut self,
+        a: &[f64],
+        b: &[f64],
+        f: impl Fn(f64, f64) -> f64 + Sync + Send,
+    ) -> Result<(), WasmError> {
+        self.check_capacity(a.len())?;
+        let output = &mut self.output_buffer;
+        if a.len() < MIN_PARALLEL_LEN {
+            output.clear();
+            output.extend(a.iter().zip(b).map(|(&x, &y)| f(x, y)));
+        } else {
+            install(&self.runtime, || {
+                a.par_iter()
+                    .zip(b)
+                    .map(|(&x, &y)| f(x, y))
+                    .collect_into_vec(output);
+            })?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,178 @@
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+use wasm_bindgen::prelude::*;
+
+use crate::WasmError;
+
+/// Element types the batch operations compute in: `f64`, and `f32` for the
+/// `*_f32` methods
+pub(crate) trait Float:
+    Copy
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+{
+    const ONE: Self;
+    const ZERO: Self;
+
+    fn sqrt(self) -> Self;
+    fn sin(self) -> Self;
+    fn cos(self) -> Self;
+    fn tan(self) -> Self;
+    fn exp(self) -> Self;
+    fn ln(self) -> Self;
+    fn abs(self) -> Self;
+    fn recip(self) -> Self;
+    fn tanh(self) -> Self;
+    fn min(self, other: Self) -> Self;
+    fn max(self, other: Self) -> Self;
+    fn powf(self, exponent: Self) -> Self;
+    fn atan2(self, other: Self) -> Self;
+    fn hypot(self, other: Self) -> Self;
+}
+
+macro_rules! impl_float {
+    ($($t:ty),*) => {$(
+        impl Float for $t {
+            const ONE: Self = 1.0;
+            const ZERO: Self = 0.0;
+
+            fn sqrt(self) -> Self { <$t>::sqrt(self) }
+            fn sin(self) -> Self { <$t>::sin(self) }
+            fn cos(self) -> Self { <$t>::cos(self) }
+            fn tan(self) -> Self { <$t>::tan(self) }
+            fn exp(self) -> Self { <$t>::exp(self) }
+            fn ln(self) -> Self { <$t>::ln(self) }
+            fn abs(self) -> Self { <$t>::abs(self) }
+            fn recip(self) -> Self { <$t>::recip(self) }
+            fn tanh(self) -> Self { <$t>::tanh(self) }
+            fn min(self, other: Self) -> Self { <$t>::min(self, other) }
+            fn max(self, other: Self) -> Self { <$t>::max(self, other) }
+            fn powf(self, exponent: Self) -> Self { <$t>::powf(self, exponent) }
+            fn atan2(self, other: Self) -> Self { <$t>::atan2(self, other) }
+            fn hypot(self, other: Self) -> Self { <$t>::hypot(self, other) }
+        }
+    )*};
+}
+
+impl_float!(f32, f64);
+
+/// Element-wise operations supported by
+/// [`WasmBatchProcessor::process_batch`](super::WasmBatchProcessor::process_batch)
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BatchOp {
+    Square,
+    Sqrt,
+    Sin,
+    Cos,
+    Tan,
+    Exp,
+    Ln,
+    Abs,
+    Neg,
+    Reciprocal,
+    Sigmoid,
+    Tanh,
+    Relu,
+}
+
+impl BatchOp {
+    const SUPPORTED: &'static str =
+        "square, sqrt, sin, cos, tan, exp, ln, abs, neg, reciprocal, sigmoid, tanh, relu";
+
+    pub(crate) fn parse(name: &str) -> Result<BatchOp, WasmError> {
+        match name.to_ascii_lowercase().as_str() {
+            "square" => Ok(BatchOp::Square),
+            "sqrt" => Ok(BatchOp::Sqrt),
+            "sin" => Ok(BatchOp::Sin),
+            "cos" => Ok(BatchOp::Cos),
+            "tan" => Ok(BatchOp::Tan),
+            "exp" => Ok(BatchOp::Exp),
+            "ln" => Ok(BatchOp::Ln),
+            "abs" => Ok(BatchOp::Abs),
+            "neg" => Ok(BatchOp::Neg),
+            "reciprocal" => Ok(BatchOp::Reciprocal),
+            "sigmoid" => Ok(BatchOp::Sigmoid),
+            "tanh" => Ok(BatchOp::Tanh),
+            "relu" => Ok(BatchOp::Relu),
+            _ => Err(WasmError::unsupported(
+                "batch operation",
+                name,
+                Self::SUPPORTED,
+            )),
+        }
+    }
+
+    /// The operation on `x`, computed in `x`'s own precision
+    pub(crate) fn apply<T: Float>(self, x: T) -> T {
+        match self {
+            BatchOp::Square => x * x,
+            BatchOp::Sqrt => x.sqrt(),
+            BatchOp::Sin => x.sin(),
+            BatchOp::Cos => x.cos(),
+            BatchOp::Tan => x.tan(),
+            BatchOp::Exp => x.exp(),
+            BatchOp::Ln => x.ln(),
+            BatchOp::Abs => x.abs(),
+            BatchOp::Neg => -x,
+            BatchOp::Reciprocal => x.recip(),
+            BatchOp::Sigmoid => T::ONE / (T::ONE + (-x).exp()),
+            BatchOp::Tanh => x.tanh(),
+            BatchOp::Relu => x.max(T::ZERO),
+        }
+    }
+
+    /// Why `x` is rejected in strict mode, if it is
+    pub(super) fn domain_error(self, x: f64) -> Option<&'static str> {
+        match self {
+            BatchOp::Ln if x <= 0.0 => Some("ln is undefined for non-positive values"),
+            BatchOp::Reciprocal if x == 0.0 => Some("reciprocal of zero is undefined"),
+            _ => None,
+        }
+    }
+}
+
+/// Element-wise binary operations supported by
+/// [`WasmBatchProcessor::process_binary`](super::WasmBatchProcessor::process_binary)
+/// and [`WasmBatchProcessor::process_scalar`](super::WasmBatchProcessor::process_scalar)
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BatchOp2 {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Pow,
+    Min,
+    Max,
+    Atan2,
+    Hypot,
+}
+
+impl BatchOp2 {
+    /// The operation on `a` and `b`, computed in their own precision
+    pub(super) fn apply<T: Float>(self, a: T, b: T) -> T {
+        match self {
+            BatchOp2::Add => a + b,
+            BatchOp2::Sub => a - b,
+            BatchOp2::Mul => a * b,
+            BatchOp2::Div => a / b,
+            BatchOp2::Pow => a.powf(b),
+            BatchOp2::Min => a.min(b),
+            BatchOp2::Max => a.max(b),
+            BatchOp2::Atan2 => a.atan2(b),
+            BatchOp2::Hypot => a.hypot(b),
+        }
+    }
+
+    /// Why `(a, b)` is rejected in strict mode, if it is
+    pub(super) fn domain_error(self, _a: f64, b: f64) -> Option<&'static str> {
+        match self {
+            BatchOp2::Div if b == 0.0 => Some("division by zero is undefined"),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,59 @@
+use js_sys::Float64Array;
+use rayon::prelude::*;
+use wasm_bindgen::prelude::*;
+
+use super::{BatchOp, WasmBatchProcessor, MIN_PARALLEL_LEN};
+use crate::{install, WasmError};
+
+/// Copy-free alternatives to returning a clone of the output buffer
+#[wasm_bindgen]
+impl WasmBatchProcessor {
+    /// [`WasmBatchProcessor::process_batch`] writing into `out`, which must
+    /// be as long as `data`, instead of the internal buffer.
+    ///
+    /// Nothing is allocated on the Rust side; from JavaScript, wasm-bindgen
+    /// still copies `out` in and back out of linear memory.
+    #[wasm_bindgen]
+    pub fn process_batch_into(
+        &mut self,
+        data: &[f64],
+        op: BatchOp,
+        strict: bool,
+        out: &mut [f64],
+    ) -> Result<(), WasmError> {
+        let timing = self.profile("process_batch_into", data.len());
+        if out.len() != data.len() {
+            return Err(WasmError::dimension("Output length", data.len(), out.len()));
+        }
+        if strict {
+            self.check_unary_domain(op, data)?;
+        }
+
+        if data.len() < MIN_PARALLEL_LEN {
+            for (result, &x) in out.iter_mut().zip(data) {
+                *result = op.apply(x);
+            }
+        } else {
+            install(&self.runtime, || {
+                out.par_iter_mut()
+                    .zip(data)
+                    .for_each(|(result, &x)| *result = op.apply(x));
+            })?;
+        }
+        timing.finish(Ok(()))
+    }
+
+    /// A `Float64Array` aliasing the result of the last call that filled the
+    /// internal output buffer (`process_batch`, `process_binary`, ...),
+    /// without copying it.
+    ///
+    /// The view is invalidated by the next call into the processor, which
+    /// may overwrite or reallocate the buffer, and by any growth of WASM
+    /// memory, which detaches it. Read or copy it before calling back in.
+    #[wasm_bindgen]
+    pub fn last_output_view(&self) -> Float64Array {
+        // SAFETY: the view is handed straight to JavaScript, and no Rust code
+        // runs (so nothing can allocate or touch the buffer) before JS reads it.
+        unsafe { Float64Array::view(&self.output_buffer) }
+    }
+}
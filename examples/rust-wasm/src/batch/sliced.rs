@@ -0,0 +1,90 @@
+use js_sys::{Float64Array, Promise};
+use rayon::prelude::*;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::future_to_promise;
+
+use super::{BatchOp, WasmBatchProcessor};
+use crate::{sleep, CancellationToken, WasmError};
+
+#[wasm_bindgen]
+impl WasmBatchProcessor {
+    /// [`WasmBatchProcessor::process_batch`] in slices of `slice_size`
+    /// elements, yielding to the event loop between slices so a very large
+    /// batch does not block the calling thread in one go.
+    ///
+    /// The promise resolves with a `Float64Array` of every result, in input
+    /// order. It rejects with an `OUT_OF_DOMAIN` error naming the slice on
+    /// a strict-mode domain error, or with `CANCELLED` (counting the slices
+    /// already done) when `token` is cancelled; cancellation is checked
+    /// before each slice.
+    ///
+    /// The input is copied into a buffer from the runtime's buffer pool so
+    /// the promise can outlive this call, and slices run on Rayon's global
+    /// pool because the processor's own pool cannot be held across the
+    /// yields.
+    #[wasm_bindgen]
+    pub fn process_batch_async(
+        &self,
+        data: &[f64],
+        op: BatchOp,
+        strict: bool,
+        slice_size: usize,
+        token: &CancellationToken,
+    ) -> Promise {
+        let pool = self.runtime.buffer_pool().clone();
+        let mut copy = pool.take(data.len());
+        copy.copy_from_slice(data);
+        let data = copy;
+        let token = token.clone();
+
+        future_to_promise(async move {
+            if slice_size == 0 {
+                return Err(WasmError::invalid("slice size", "must be positive").into());
+            }
+
+            let slices = data.chunks(slice_size).len();
+            let result = Float64Array::new_with_length(data.len() as u32);
+            let mut output = pool.take(slice_size.min(data.len()));
+            for (index, slice) in data.chunks(slice_size).enumerate() {
+                if index > 0 {
+                    sleep(0).await?;
+                }
+                if token.is_cancelled() {
+                    return Err(WasmError::Cancelled {
+                        completed: index,
+                        total: slices,
+                    }
+                    .into());
+                }
+
+                let offset = index * slice_size;
+                if strict {
+                    let invalid = slice
+                        .par_iter()
+                        .enumerate()
+                        .find_map_first(|(i, &x)| op.domain_error(x).map(|reason| (i, reason)));
+                    if let Some((i, reason)) = invalid {
+                        return Err(WasmError::OutOfDomain {
+                            index: offset + i,
+                            values: vec![slice[i]],
+                            reason: reason.into(),
+                            slice: Some(index),
+                        }
+                        .into());
+                    }
+                }
+
+                slice
+                    .par_iter()
+                    .map(|&x| op.apply(x))
+                    .collect_into_vec(&mut output);
+                result
+                    .subarray(offset as u32, (offset + slice.len()) as u32)
+                    .copy_from(&output);
+            }
+            pool.recycle(output);
+            pool.recycle(data);
+            Ok(result.into())
+        })
+    }
+}
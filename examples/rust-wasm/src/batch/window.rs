@@ -0,0 +1,148 @@
+use wasm_bindgen::prelude::*;
+
+use super::WasmBatchProcessor;
+use crate::WasmError;
+
+/// Window size used by [`WasmBatchProcessor::new`]
+pub(super) const DEFAULT_WINDOW_SIZE: usize = 32;
+
+/// Reductions applied to a full window of samples
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum WindowOp {
+    Sum,
+    Mean,
+    Min,
+    Max,
+    Rms,
+    Variance,
+    StdDev,
+}
+
+impl WindowOp {
+    const SUPPORTED: &'static str = "sum, mean, min, max, rms, variance, std";
+
+    fn parse(name: &str) -> Result<WindowOp, WasmError> {
+        match name.to_ascii_lowercase().as_str() {
+            "sum" => Ok(WindowOp::Sum),
+            "mean" => Ok(WindowOp::Mean),
+            "min" => Ok(WindowOp::Min),
+            "max" => Ok(WindowOp::Max),
+            "rms" => Ok(WindowOp::Rms),
+            "variance" => Ok(WindowOp::Variance),
+            "std" => Ok(WindowOp::StdDev),
+            _ => Err(WasmError::unsupported(
+                "window operation",
+                name,
+                Self::SUPPORTED,
+            )),
+        }
+    }
+
+    /// Reduce a non-empty set of samples
+    fn apply(self, samples: &[f64]) -> f64 {
+        let n = samples.len() as f64;
+        let mean = || samples.iter().sum::<f64>() / n;
+        let variance = || {
+            let mean = mean();
+            samples.iter().map(|x| (x - mean) * (x - mean)).sum::<f64>() / n
+        };
+
+        match self {
+            WindowOp::Sum => samples.iter().sum(),
+            WindowOp::Mean => mean(),
+            WindowOp::Min => samples.iter().copied().fold(f64::INFINITY, f64::min),
+            WindowOp::Max => samples.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+            WindowOp::Rms => (samples.iter().map(|x| x * x).sum::<f64>() / n).sqrt(),
+            WindowOp::Variance => variance(),
+            WindowOp::StdDev => variance().sqrt(),
+        }
+    }
+}
+
+/// Fixed-capacity ring buffer of the most recent samples
+pub(super) struct SampleWindow {
+    samples: Box<[f64]>,
+    len: usize,
+    /// Slot the next sample is written to
+    next: usize,
+    op: WindowOp,
+}
+
+impl SampleWindow {
+    pub(super) fn new(size: usize) -> SampleWindow {
+        SampleWindow {
+            samples: vec![0.0; size].into_boxed_slice(),
+            len: 0,
+            next: 0,
+            op: WindowOp::Mean,
+        }
+    }
+
+    /// Samples currently held; order does not matter to any reduction
+    fn filled(&self) -> &[f64] {
+        &self.samples[..self.len]
+    }
+}
+
+#[wasm_bindgen]
+impl WasmBatchProcessor {
+    /// Create a processor like [`WasmBatchProcessor::new`] whose streaming
+    /// window holds `window_size` samples
+    #[wasm_bindgen]
+    pub fn with_window(
+        batch_size: usize,
+        num_threads: usize,
+        window_size: usize,
+    ) -> Result<WasmBatchProcessor, WasmError> {
+        if window_size == 0 {
+            return Err(WasmError::invalid("window size", "must be at least 1"));
+        }
+        let mut processor = WasmBatchProcessor::new(batch_size, num_threads)?;
+        processor.window = SampleWindow::new(window_size);
+        Ok(processor)
+    }
+
+    /// Number of samples in a full streaming window
+    #[wasm_bindgen(getter)]
+    pub fn window_size(&self) -> usize {
+        self.window.samples.len()
+    }
+
+    /// Select the reduction applied by [`WasmBatchProcessor::push_sample`]
+    /// and [`WasmBatchProcessor::flush`]: "sum", "mean" (the default),
+    /// "min", "max", "rms", "variance" or "std"
+    #[wasm_bindgen]
+    pub fn set_operation(&mut self, op: &str) -> Result<(), WasmError> {
+        self.window.op = WindowOp::parse(op)?;
+        Ok(())
+    }
+
+    /// Add a sample to the sliding window, evicting the oldest once full.
+    ///
+    /// Returns the current operation over the window once it is full, and
+    /// `undefined` while it is still filling.
+    #[wasm_bindgen]
+    pub fn push_sample(&mut self, sample: f64) -> Option<f64> {
+        let timing = self.profile("push_sample", 1);
+        let window = &mut self.window;
+        let size = window.samples.len();
+        window.samples[window.next] = sample;
+        window.next = (window.next + 1) % size;
+        window.len = (window.len + 1).min(size);
+
+        timing.finish((window.len == size).then(|| window.op.apply(window.filled())))
+    }
+
+    /// Apply the current operation to whatever the window holds, then empty
+    /// it so the next [`WasmBatchProcessor::push_sample`] starts afresh.
+    /// Returns `undefined` if the window is already empty.
+    #[wasm_bindgen]
+    pub fn flush(&mut self) -> Option<f64> {
+        let timing = self.profile("flush", self.window.len);
+        let window = &mut self.window;
+        let result = (window.len > 0).then(|| window.op.apply(window.filled()));
+        window.len = 0;
+        window.next = 0;
+        timing.finish(result)
+    }
+}
@@ -22,8 +22,15 @@ use rayon::prelude::*;
 use rayon::ThreadPoolBuilder;
 use std::{
     sync::atomic::{AtomicUsize, Ordering},
-    time::Instant,
+    time::{Duration, Instant},
 };
+use web_learning_rust_examples::{
+    WasmError, WasmImageProcessor, WasmMatrixProcessor, WasmParallelProcessor, WasmRuntime,
+};
+
+/// Counting allocator for the buffer pool and matrix scratch benchmarks
+#[path = "../../tests/common/mod.rs"]
+mod common;
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
@@ -464,6 +471,282 @@ fn benchmark_parallel_performance() {
             speedup
         );
     }
+
+    radix_sort_benchmark();
+    box_blur_benchmark();
+    activation_benchmark();
+    buffer_pool_benchmark();
+    matrix_scratch_benchmark();
+    ndjson_benchmark();
+}
+
+fn radix_sort_benchmark() {
+    const SIZE: usize = 10_000_000;
+
+    // xorshift64 keeps the benchmark deterministic without a `rand` dependency
+    let mut state = 0x9E37_79B9_7F4A_7C15_u64;
+    let data: Vec<u64> = (0..SIZE)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        })
+        .collect();
+
+    let processor = WasmParallelProcessor::new(0).expect("Global pool needs no setup");
+
+    let start = Instant::now();
+    let radix_sorted = processor
+        .parallel_radix_sort_u64(&data)
+        .expect("Sorting does not panic");
+    let radix_time = start.elapsed();
+
+    let mut comparison_sorted = data.clone();
+    let start = Instant::now();
+    comparison_sorted.par_sort_unstable();
+    let comparison_time = start.elapsed();
+
+    assert_eq!(radix_sorted, comparison_sorted);
+
+    println!(
+        "Sort {} u64 | par_sort_unstable: {:>8.2}ms | Radix: {:>8.2}ms | Speedup: {:.2}x",
+        SIZE,
+        comparison_time.as_secs_f64() * 1000.0,
+        radix_time.as_secs_f64() * 1000.0,
+        comparison_time.as_secs_f64() / radix_time.as_secs_f64()
+    );
+}
+
+fn box_blur_benchmark() {
+    let (width, height) = (1024, 768);
+    let image: Vec<u8> = (0..width * height * 4)
+        .map(|i| (i * 7 % 256) as u8)
+        .collect();
+    let mut processor = WasmImageProcessor::new(0).expect("Global pool needs no setup");
+
+    for radius in [2, 5, 10, 20] {
+        let start = Instant::now();
+        let naive = naive_box_blur(&image, width, height, radius);
+        let naive_time = start.elapsed();
+
+        let start = Instant::now();
+        let fast = processor
+            .fast_box_blur(&image, width as u32, height as u32, radius)
+            .expect("Image dimensions match");
+        let fast_time = start.elapsed();
+
+        assert_eq!(naive, fast);
+
+        println!(
+            "Box blur {width}x{height} r={radius:>2} | Naive: {:>8.2}ms | Summed-area: {:>8.2}ms | Speedup: {:.2}x",
+            naive_time.as_secs_f64() * 1000.0,
+            fast_time.as_secs_f64() * 1000.0,
+            naive_time.as_secs_f64() / fast_time.as_secs_f64()
+        );
+    }
+}
+
+fn activation_benchmark() {
+    const SIZE: usize = 1_000_000;
+
+    let data: Vec<f64> = (0..SIZE)
+        .map(|i| (i as f64 / SIZE as f64 - 0.5) * 20.0)
+        .collect();
+    let sequential = WasmParallelProcessor::new(1).expect("One worker is a valid pool");
+    let parallel = WasmParallelProcessor::new(0).expect("Global pool needs no setup");
+
+    type Activation = fn(&WasmParallelProcessor, &[f64]) -> Result<Vec<f64>, WasmError>;
+    let activations: [(&str, Activation); 4] = [
+        ("sigmoid", WasmParallelProcessor::parallel_sigmoid),
+        ("tanh", WasmParallelProcessor::parallel_tanh),
+        ("relu", WasmParallelProcessor::parallel_relu),
+        ("gelu", WasmParallelProcessor::parallel_gelu),
+    ];
+    for (name, activation) in activations {
+        let start = Instant::now();
+        let sequential_result = activation(&sequential, &data).expect("Activations do not panic");
+        let sequential_time = start.elapsed();
+
+        let start = Instant::now();
+        let parallel_result = activation(&parallel, &data).expect("Activations do not panic");
+        let parallel_time = start.elapsed();
+
+        assert_eq!(sequential_result, parallel_result);
+
+        println!(
+            "{name:<7} {SIZE} f64 | 1 thread: {:>8.2}ms | Parallel: {:>8.2}ms | Speedup: {:.2}x",
+            sequential_time.as_secs_f64() * 1000.0,
+            parallel_time.as_secs_f64() * 1000.0,
+            sequential_time.as_secs_f64() / parallel_time.as_secs_f64()
+        );
+    }
+}
+
+/// A blur, edge and threshold pipeline run per frame, with the runtime's
+/// buffer pool kept between frames and with it trimmed after each one
+fn buffer_pool_benchmark() {
+    const FRAMES: usize = 30;
+    let (width, height) = (640u32, 480u32);
+    let image: Vec<u8> = (0..width * height * 4)
+        .map(|i| (i * 13 % 256) as u8)
+        .collect();
+    let runtime = WasmRuntime::new(0).expect("Global pool needs no setup");
+    let mut processor = WasmImageProcessor::with_runtime(&runtime);
+    let frame = |processor: &mut WasmImageProcessor| {
+        let blurred = processor
+            .fast_box_blur(&image, width, height, 3)
+            .expect("Image dimensions match");
+        let edges = processor
+            .canny_edges(&blurred, width, height, 20.0, 60.0)
+            .expect("Thresholds are ordered");
+        processor
+            .adaptive_threshold(&edges, width, height, 15, 2)
+            .expect("Block size fits the image")
+    };
+    // Start the workers and fill the pool before counting
+    frame(&mut processor);
+
+    for (label, trim) in [("pooled", false), ("trimmed", true)] {
+        let mut elapsed = Duration::ZERO;
+        let allocations = common::allocations_during(|| {
+            let start = Instant::now();
+            for _ in 0..FRAMES {
+                frame(&mut processor);
+                if trim {
+                    runtime.trim();
+                }
+            }
+            elapsed = start.elapsed();
+        });
+
+        println!(
+            "Pipeline {width}x{height} {label:<7} | {:>6.1} allocations/frame | {:>8.2}ms/frame",
+            allocations as f64 / FRAMES as f64,
+            elapsed.as_secs_f64() * 1000.0 / FRAMES as f64
+        );
+    }
+    let stats = runtime.buffer_pool_stats();
+    println!(
+        "Buffer pool | {} hits | {} misses",
+        stats.hits, stats.misses
+    );
+}
+
+/// Many small linear systems solved into one output buffer, with the matrix
+/// processor's scratch reset before every solve, which frees its buffer from
+/// the runtime's pool, and kept there between solves
+fn matrix_scratch_benchmark() {
+    const SYSTEMS: usize = 10_000;
+    const N: usize = 8;
+    let systems: Vec<(Vec<f64>, Vec<f64>)> = (0..SYSTEMS)
+        .map(|s| {
+            let a = (0..N * N)
+                .map(|k| {
+                    if k / N == k % N {
+                        N as f64
+                    } else {
+                        ((k * 7 + s) % 13) as f64 * 0.1 - 0.6
+                    }
+                })
+                .collect();
+            let b = (0..N).map(|i| (i + s % 5) as f64).collect();
+            (a, b)
+        })
+        .collect();
+    let processor = WasmMatrixProcessor::new(0).expect("Global pool needs no setup");
+    let mut solution = [0.0; N];
+
+    for (label, reset) in [("reset", true), ("warm", false)] {
+        let mut elapsed = Duration::ZERO;
+        let allocations = common::allocations_during(|| {
+            let start = Instant::now();
+            for (a, b) in &systems {
+                if reset {
+                    processor.scratch_reset();
+                }
+                processor
+                    .solve_linear_system_into(a, b, N, &mut solution)
+                    .expect("Diagonally dominant systems are not singular");
+            }
+            elapsed = start.elapsed();
+        });
+
+        println!(
+            "Solve {SYSTEMS} {N}x{N} systems, scratch {label:<5} | {:>6.3} allocations/solve | {:>8.2}ms",
+            allocations as f64 / SYSTEMS as f64,
+            elapsed.as_secs_f64() * 1000.0
+        );
+    }
+    println!("Matrix scratch | {} bytes held", processor.scratch_bytes());
+}
+
+/// One numeric field out of a million NDJSON lines, on one thread and on
+/// the global pool
+fn ndjson_benchmark() {
+    const LINES: usize = 1_000_000;
+
+    let ndjson: String = (0..LINES)
+        .map(|i| {
+            format!(
+                "{{\"id\":{i},\"host\":\"node-{}\",\"metrics\":{{\"cpu\":{},\"mem\":[{i},{}]}}}}\n",
+                i % 64,
+                (i % 1000) as f64 / 10.0,
+                i * 3
+            )
+        })
+        .collect();
+    let sequential = WasmParallelProcessor::new(1).expect("One worker is a valid pool");
+    let parallel = WasmParallelProcessor::new(0).expect("Global pool needs no setup");
+
+    let start = Instant::now();
+    let sequential_values = sequential
+        .parallel_parse_ndjson_floats(&ndjson, "metrics.cpu")
+        .expect("Every line is valid JSON");
+    let sequential_time = start.elapsed();
+
+    let start = Instant::now();
+    let parallel_values = parallel
+        .parallel_parse_ndjson_floats(&ndjson, "metrics.cpu")
+        .expect("Every line is valid JSON");
+    let parallel_time = start.elapsed();
+
+    assert_eq!(sequential_values, parallel_values);
+
+    println!(
+        "NDJSON {LINES} lines ({:.1} MB) | 1 thread: {:>8.2}ms | Parallel: {:>8.2}ms | Speedup: {:.2}x",
+        ndjson.len() as f64 / 1e6,
+        sequential_time.as_secs_f64() * 1000.0,
+        parallel_time.as_secs_f64() * 1000.0,
+        sequential_time.as_secs_f64() / parallel_time.as_secs_f64()
+    );
+}
+
+/// Direct box blur summing the whole window for every pixel
+fn naive_box_blur(rgba: &[u8], width: usize, height: usize, radius: usize) -> Vec<u8> {
+    let mut output = vec![0u8; rgba.len()];
+
+    output
+        .par_chunks_exact_mut(4)
+        .enumerate()
+        .for_each(|(index, pixel)| {
+            let (x, y) = (index % width, index / width);
+            let (x0, x1) = (x.saturating_sub(radius), (x + radius + 1).min(width));
+            let (y0, y1) = (y.saturating_sub(radius), (y + radius + 1).min(height));
+            let count = ((x1 - x0) * (y1 - y0)) as f64;
+
+            for (channel, value) in pixel.iter_mut().enumerate() {
+                let mut sum = 0u32;
+                for row in y0..y1 {
+                    for column in x0..x1 {
+                        sum += rgba[(row * width + column) * 4 + channel] as u32;
+                    }
+                }
+                *value = (sum as f64 / count).round() as u8;
+            }
+        });
+
+    output
 }
 
 fn run_benchmarks_json() -> serde_json::Value {
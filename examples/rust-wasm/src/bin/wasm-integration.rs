@@ -19,6 +19,10 @@ use clap::Parser;
  */
 use rayon::prelude::*;
 use std::time::Instant;
+use web_learning_rust_examples::{
+    process_memory_bytes, BatchOp, ChainStep, MemoryEfficientProcessor, WasmBatchProcessor,
+    WasmImageProcessor,
+};
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
@@ -75,6 +79,8 @@ fn run_interactive_examples(args: &Args) {
     matrix_operations_example(args.matrix_size);
     image_processing_simulation(args.matrix_size);
     batch_processing_example();
+    chain_fusion_example();
+    single_precision_example();
     memory_management_example();
 
     println!("\n✅ All WebAssembly integration examples completed!");
@@ -211,6 +217,12 @@ fn image_processing_simulation(size: usize) {
     let brightness_duration = start.elapsed();
 
     println!("Brightness adjustment completed in {brightness_duration:?}");
+
+    let start = Instant::now();
+    let edge_pixels = parallel_edge_detection(&image_data, size);
+    let edge_duration = start.elapsed();
+
+    println!("Canny edge detection found {edge_pixels} edge pixels in {edge_duration:?}");
 }
 
 fn run_image_processing_json(size: usize) -> serde_json::Value {
@@ -225,14 +237,32 @@ fn run_image_processing_json(size: usize) -> serde_json::Value {
     parallel_brightness_adjustment(&mut image_data, 1.2);
     let brightness_duration = start.elapsed();
 
+    let start = Instant::now();
+    let edge_pixels = parallel_edge_detection(&image_data, size);
+    let edge_duration = start.elapsed();
+
     serde_json::json!({
         "image_size": format!("{}x{}", size, size),
         "pixel_count": size * size,
         "grayscale_time_ms": grayscale_duration.as_millis(),
-        "brightness_time_ms": brightness_duration.as_millis()
+        "brightness_time_ms": brightness_duration.as_millis(),
+        "edge_detection_time_ms": edge_duration.as_millis(),
+        "edge_pixels": edge_pixels
     })
 }
 
+fn parallel_edge_detection(rgba_data: &[u8], size: usize) -> usize {
+    let mut processor = WasmImageProcessor::new(0).expect("Global pool needs no setup");
+    let edges = processor
+        .canny_edges(rgba_data, size as u32, size as u32, 20.0, 60.0)
+        .expect("Image dimensions and thresholds are valid");
+
+    edges
+        .chunks_exact(4)
+        .filter(|pixel| pixel[0] == 255)
+        .count()
+}
+
 fn parallel_grayscale_conversion(rgba_data: &mut [u8]) {
     rgba_data.par_chunks_exact_mut(4).for_each(|pixel| {
         let r = pixel[0] as f32;
@@ -313,59 +343,132 @@ fn process_batch_operation(data: &[f64], operation: &str) -> Vec<f64> {
     }
 }
 
+// Fused operation chains versus one call per operation
+fn chain_fusion_example() {
+    println!("\n=== Fused Operation Chain ===");
+
+    let size = 1_000_000;
+    let data: Vec<f64> = (0..size).map(|i| i as f64 * 0.001).collect();
+    let mut processor = WasmBatchProcessor::new(size, 0).expect("processor");
+
+    // sqrt -> * 2 -> sin as three calls: three passes and three output copies
+    let start = Instant::now();
+    let roots = processor
+        .process_batch(&data, BatchOp::Sqrt, false)
+        .unwrap();
+    let doubled = processor.fma_batch(&roots, 2.0, 0.0).unwrap();
+    let separate = processor
+        .process_batch(&doubled, BatchOp::Sin, false)
+        .unwrap();
+    let separate_duration = start.elapsed();
+
+    // The same chain in a single pass and a single output copy
+    let steps = [
+        ChainStep::Sqrt,
+        ChainStep::Mul { value: 2.0 },
+        ChainStep::Sin,
+    ];
+    let start = Instant::now();
+    let fused = processor.apply_chain(&data, &steps).unwrap();
+    let fused_duration = start.elapsed();
+
+    let max_difference = separate
+        .iter()
+        .zip(&fused)
+        .map(|(a, b)| (a - b).abs())
+        .fold(0.0, f64::max);
+
+    println!(
+        "Three separate calls on {size} items: {separate_duration:?} (3 passes, 3 output vectors)"
+    );
+    println!("Fused chain on {size} items: {fused_duration:?} (1 pass, 1 output vector)");
+    println!(
+        "Speedup: {:.2}x, max difference {max_difference:e}",
+        separate_duration.as_secs_f64() / fused_duration.as_secs_f64()
+    );
+}
+
+fn single_precision_example() {
+    println!("\n=== Single-Precision Batches ===");
+
+    let size = 1_000_000;
+    let runs = 5;
+    let data: Vec<f64> = (0..size).map(|i| i as f64 * 0.001).collect();
+    let data_f32: Vec<f32> = data.iter().map(|&x| x as f32).collect();
+    let mut processor = WasmBatchProcessor::new(size, 0).expect("processor");
+
+    // Alternate the two paths so neither gets a warmer cache or a quieter
+    // machine, and keep each one's best run; the first run of each warms
+    // its output buffer
+    let mut wide_best = std::time::Duration::MAX;
+    let mut narrow_best = std::time::Duration::MAX;
+    let mut wide = Vec::new();
+    let mut narrow = Vec::new();
+    for _ in 0..runs {
+        let start = Instant::now();
+        wide = processor
+            .process_batch(&data, BatchOp::Sqrt, false)
+            .unwrap();
+        wide_best = wide_best.min(start.elapsed());
+
+        let start = Instant::now();
+        narrow = processor
+            .process_batch_f32(&data_f32, BatchOp::Sqrt, false)
+            .unwrap();
+        narrow_best = narrow_best.min(start.elapsed());
+    }
+
+    let max_difference = wide
+        .iter()
+        .zip(&narrow)
+        .map(|(&w, &n)| (w - f64::from(n)).abs() / w.max(1.0))
+        .fold(0.0, f64::max);
+
+    // Input read, output buffer written, then copied out to the caller
+    let wide_bytes = (data.len() + 2 * wide.len()) * std::mem::size_of::<f64>();
+    let narrow_bytes = (data_f32.len() + 2 * narrow.len()) * std::mem::size_of::<f32>();
+    println!("f64 sqrt on {size} items: {wide_best:?} (best of {runs}), {wide_bytes} bytes moved");
+    println!(
+        "f32 sqrt on {size} items: {narrow_best:?} (best of {runs}), {narrow_bytes} bytes moved"
+    );
+    println!(
+        "f32 moves {:.2}x the bytes in {:.2}x the time, within {max_difference:.1e} of f64",
+        narrow_bytes as f64 / wide_bytes as f64,
+        narrow_best.as_secs_f64() / wide_best.as_secs_f64()
+    );
+}
+
 // Memory management patterns for WebAssembly
 fn memory_management_example() {
     println!("\n=== Memory Management ===");
 
     let buffer_size = 100_000;
-    let mut processor = MemoryEfficientProcessor::new(buffer_size);
+    let mut processor =
+        MemoryEfficientProcessor::new(buffer_size, 0).expect("Global pool needs no setup");
+
+    // One output buffer for every size; each call writes a prefix of it
+    let mut output = vec![0.0; 10000];
 
     // Test with different data sizes
     for size in [1000, 5000, 10000] {
         let data: Vec<f64> = (0..size).map(|i| i as f64 * 0.01).collect();
 
         let start = Instant::now();
-        let result = processor.process_inplace(&data);
+        let result = &mut output[..size];
+        processor
+            .process_into(&data, BatchOp::Sqrt, result)
+            .expect("The output matches the input length");
+        // A second pass for the doubling, which has no BatchOp of its own
+        result.par_iter_mut().for_each(|value| *value *= 2.0);
         let duration = start.elapsed();
 
         println!("Memory-efficient processing of {size} items completed in {duration:?}");
         println!("Result checksum: {:.2}", result.iter().sum::<f64>());
     }
 
-    println!("Memory usage stats: allocated buffer size: {buffer_size}");
-}
-
-struct MemoryEfficientProcessor {
-    buffer: Vec<f64>,
-    scratch_space: Vec<f64>,
-}
-
-impl MemoryEfficientProcessor {
-    fn new(max_size: usize) -> Self {
-        Self {
-            buffer: Vec::with_capacity(max_size),
-            scratch_space: Vec::with_capacity(max_size),
-        }
-    }
-
-    fn process_inplace(&mut self, data: &[f64]) -> Vec<f64> {
-        // Reuse allocated buffers to avoid GC pressure
-        self.buffer.clear();
-        self.buffer.extend_from_slice(data);
-
-        self.scratch_space.clear();
-        self.scratch_space.resize(data.len(), 0.0);
-
-        // Parallel processing with pre-allocated memory
-        self.buffer
-            .par_chunks_mut(1024) // Process in chunks
-            .zip(self.scratch_space.par_chunks_mut(1024))
-            .for_each(|(input_chunk, output_chunk)| {
-                for (input, output) in input_chunk.iter().zip(output_chunk.iter_mut()) {
-                    *output = input.sqrt() * 2.0;
-                }
-            });
-
-        self.scratch_space.clone()
-    }
+    println!(
+        "Memory usage stats: the buffer holds {} bytes, process resident set {} bytes",
+        processor.heap_bytes(),
+        process_memory_bytes()
+    );
 }
@@ -0,0 +1,237 @@
+//! A Bloom filter over `u64` keys, so that JavaScript can test membership
+//! against a large set without holding the set itself.
+
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use js_sys::Array;
+use rayon::prelude::*;
+use wasm_bindgen::prelude::*;
+use xxhash_rust::xxh3::xxh3_128;
+
+use crate::{install, WasmError, WasmRuntime};
+
+/// Leading bytes of a serialized filter
+const MAGIC: &[u8; 4] = b"WBF1";
+
+/// Serialized header: the magic, the hash count as a `u32` and the bit
+/// count as a `u64`, both little-endian
+const HEADER_LEN: usize = 4 + 4 + 8;
+
+/// Largest bit array a filter may have, 512 MiB
+const MAX_BITS: u64 = 1 << 32;
+
+/// Most hash functions a filter may use; the optimum for a false positive
+/// rate of 1e-9 is 30
+const MAX_HASHES: u32 = 64;
+
+/// Fraction of the configured false positive rate a filter is sized for.
+/// The rate a given set of keys actually sees varies around the expected
+/// one, and the headroom keeps that under the configured rate.
+const RATE_HEADROOM: f64 = 0.9;
+
+/// Probabilistic set of `u64` keys: a key that was inserted is always
+/// reported present, and one that was not is reported present at less than
+/// the configured false positive rate
+#[wasm_bindgen]
+pub struct WasmBloomFilter {
+    runtime: WasmRuntime,
+    num_bits: u64,
+    num_hashes: u32,
+    /// `num_bits` bits, least significant first within each byte
+    bits: Vec<AtomicU8>,
+}
+
+#[wasm_bindgen]
+impl WasmBloomFilter {
+    /// An empty filter sized for `expected_elements` keys at
+    /// `false_positive_rate`, using the global runtime.
+    ///
+    /// The hash count is the optimal `(m / n) ln 2` rounded, and the bit
+    /// count `m` the smallest for which that many hashes reach the rate,
+    /// less 10% headroom, once `expected_elements` keys are in. The rate must
+    /// lie strictly between 0 and 1.
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        expected_elements: u32,
+        false_positive_rate: f64,
+    ) -> Result<WasmBloomFilter, WasmError> {
+        WasmBloomFilter::with_runtime(
+            expected_elements,
+            false_positive_rate,
+            &WasmRuntime::new(0)?,
+        )
+    }
+
+    /// Like `new`, but sharing `runtime`'s thread pool
+    #[wasm_bindgen]
+    pub fn with_runtime(
+        expected_elements: u32,
+        false_positive_rate: f64,
+        runtime: &WasmRuntime,
+    ) -> Result<WasmBloomFilter, WasmError> {
+        if expected_elements == 0 {
+            return Err(WasmError::invalid("expected elements", "must be positive"));
+        }
+        if !(false_positive_rate > 0.0 && false_positive_rate < 1.0) {
+            return Err(WasmError::invalid(
+                "false positive rate",
+                format!("{false_positive_rate} is not strictly between 0 and 1"),
+            ));
+        }
+
+        let n = f64::from(expected_elements);
+        let ln2 = std::f64::consts::LN_2;
+        let target = false_positive_rate * RATE_HEADROOM;
+        let optimal_bits = -n * target.ln() / (ln2 * ln2);
+        let num_hashes = (optimal_bits / n * ln2).round().max(1.0);
+        // With k hashes, m bits give (1 - e^(-kn/m))^k false positives
+        let bits = -num_hashes * n / (-(target.powf(1.0 / num_hashes))).ln_1p();
+        if !(bits <= MAX_BITS as f64 && num_hashes <= f64::from(MAX_HASHES)) {
+            return Err(WasmError::invalid(
+                "false positive rate",
+                format!(
+                    "{expected_elements} elements at {false_positive_rate} need more than \
+                     {MAX_BITS} bits"
+                ),
+            ));
+        }
+        Ok(WasmBloomFilter::empty(
+            runtime,
+            bits.ceil() as u64,
+            num_hashes as u32,
+        ))
+    }
+
+    /// Bits in the filter
+    #[wasm_bindgen(getter)]
+    pub fn num_bits(&self) -> f64 {
+        self.num_bits as f64
+    }
+
+    /// Bits set per key
+    #[wasm_bindgen(getter)]
+    pub fn num_hashes(&self) -> u32 {
+        self.num_hashes
+    }
+
+    /// Add every key, one key per task; the bits are set atomically, so
+    /// keys sharing bits need no locking
+    #[wasm_bindgen]
+    pub fn parallel_insert_batch(&mut self, keys: &[u64]) -> Result<(), WasmError> {
+        install(&self.runtime, || {
+            keys.par_iter().for_each(|&key| {
+                for bit in self.positions(key) {
+                    self.bits[(bit / 8) as usize].fetch_or(1 << (bit % 8), Ordering::Relaxed);
+                }
+            });
+        })
+    }
+
+    /// Whether each key may be in the set, as an array of booleans: `false`
+    /// means it was never inserted, `true` that it was or is a false
+    /// positive
+    #[wasm_bindgen]
+    pub fn parallel_contains_batch(&self, keys: &[u64]) -> Result<Array, WasmError> {
+        let found = self.contains_batch(keys)?;
+        Ok(found.into_iter().map(JsValue::from_bool).collect())
+    }
+
+    /// The filter as bytes for [`WasmBloomFilter::deserialize`]: a header
+    /// of the magic `WBF1`, the hash count as a little-endian `u32` and the
+    /// bit count as a little-endian `u64`, then the bit array
+    #[wasm_bindgen]
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(HEADER_LEN + self.bits.len());
+        bytes.extend_from_slice(MAGIC);
+        bytes.extend_from_slice(&self.num_hashes.to_le_bytes());
+        bytes.extend_from_slice(&self.num_bits.to_le_bytes());
+        bytes.extend(self.bits.iter().map(|byte| byte.load(Ordering::Relaxed)));
+        bytes
+    }
+
+    /// A filter from the bytes [`WasmBloomFilter::serialize`] wrote, using
+    /// the global runtime. Anything else is an `INVALID_ENCODING` error.
+    #[wasm_bindgen]
+    pub fn deserialize(bytes: &[u8]) -> Result<WasmBloomFilter, WasmError> {
+        WasmBloomFilter::deserialize_with_runtime(bytes, &WasmRuntime::new(0)?)
+    }
+
+    /// Like `deserialize`, but sharing `runtime`'s thread pool
+    #[wasm_bindgen]
+    pub fn deserialize_with_runtime(
+        bytes: &[u8],
+        runtime: &WasmRuntime,
+    ) -> Result<WasmBloomFilter, WasmError> {
+        let Some(header) = bytes.get(..HEADER_LEN) else {
+            return Err(malformed(bytes.len(), "the header is cut short"));
+        };
+        if &header[..4] != MAGIC {
+            return Err(malformed(0, "expected the magic WBF1"));
+        }
+        let num_hashes = u32::from_le_bytes(header[4..8].try_into().expect("4 bytes"));
+        if !(1..=MAX_HASHES).contains(&num_hashes) {
+            return Err(malformed(4, format!("{num_hashes} hash functions")));
+        }
+        let num_bits = u64::from_le_bytes(header[8..].try_into().expect("8 bytes"));
+        if !(1..=MAX_BITS).contains(&num_bits) {
+            return Err(malformed(8, format!("{num_bits} bits")));
+        }
+        let body = &bytes[HEADER_LEN..];
+        let expected = ((num_bits + 7) / 8) as usize;
+        if body.len() != expected {
+            return Err(malformed(
+                HEADER_LEN + body.len().min(expected),
+                format!("{num_bits} bits take {expected} bytes, not {}", body.len()),
+            ));
+        }
+
+        let filter = WasmBloomFilter::empty(runtime, num_bits, num_hashes);
+        for (target, &byte) in filter.bits.iter().zip(body) {
+            target.store(byte, Ordering::Relaxed);
+        }
+        Ok(filter)
+    }
+}
+
+impl WasmBloomFilter {
+    fn empty(runtime: &WasmRuntime, num_bits: u64, num_hashes: u32) -> WasmBloomFilter {
+        WasmBloomFilter {
+            runtime: runtime.clone(),
+            num_bits,
+            num_hashes,
+            bits: (0..(num_bits + 7) / 8).map(|_| AtomicU8::new(0)).collect(),
+        }
+    }
+
+    /// [`WasmBloomFilter::parallel_contains_batch`] for callers in Rust, one
+    /// key per task
+    pub fn contains_batch(&self, keys: &[u64]) -> Result<Vec<bool>, WasmError> {
+        install(&self.runtime, || {
+            keys.par_iter().map(|&key| self.contains(key)).collect()
+        })
+    }
+
+    /// Whether every bit `key` hashes to is set
+    fn contains(&self, key: u64) -> bool {
+        self.positions(key).all(|bit| {
+            self.bits[(bit / 8) as usize].load(Ordering::Relaxed) & (1 << (bit % 8)) != 0
+        })
+    }
+
+    /// The bits for `key`, by double hashing: the two halves of one 128-bit
+    /// hash combined as `h1 + i * h2`
+    fn positions(&self, key: u64) -> impl Iterator<Item = u64> {
+        let hash = xxh3_128(&key.to_le_bytes());
+        let (h1, h2) = (hash as u64, (hash >> 64) as u64 | 1);
+        let num_bits = self.num_bits;
+        (0..u64::from(self.num_hashes)).map(move |i| h1.wrapping_add(i.wrapping_mul(h2)) % num_bits)
+    }
+}
+
+fn malformed(offset: usize, reason: impl Into<String>) -> WasmError {
+    WasmError::InvalidEncoding {
+        encoding: "Bloom filter".to_string(),
+        offset,
+        reason: reason.into(),
+    }
+}
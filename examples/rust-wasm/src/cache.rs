@@ -0,0 +1,216 @@
+use std::collections::{BTreeMap, HashMap};
+
+/// Byte buffers keyed by string, evicting the least recently used entries
+/// to stay within an entry count and a total byte budget
+#[derive(Debug)]
+pub(crate) struct LruCache {
+    entries: HashMap<String, Entry>,
+    /// Keys by last use; the first entry is the next to evict
+    recency: BTreeMap<u64, String>,
+    clock: u64,
+    bytes: usize,
+    max_entries: usize,
+    max_bytes: usize,
+    stats: CacheStats,
+}
+
+/// Running totals of cache activity, kept across [`LruCache::clear`]
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct CacheStats {
+    pub(crate) hits: u64,
+    pub(crate) misses: u64,
+    pub(crate) insertions: u64,
+    /// Entries dropped to meet the limits; clearing does not count
+    pub(crate) evictions: u64,
+}
+
+#[derive(Debug)]
+struct Entry {
+    data: Vec<u8>,
+    last_used: u64,
+}
+
+impl LruCache {
+    pub(crate) fn new(max_entries: usize, max_bytes: usize) -> LruCache {
+        LruCache {
+            entries: HashMap::new(),
+            recency: BTreeMap::new(),
+            clock: 0,
+            bytes: 0,
+            max_entries,
+            max_bytes,
+            stats: CacheStats::default(),
+        }
+    }
+
+    /// The value under `key`, marking it as most recently used
+    pub(crate) fn get(&mut self, key: &str) -> Option<&[u8]> {
+        let Some(entry) = self.entries.get_mut(key) else {
+            self.stats.misses += 1;
+            return None;
+        };
+        self.stats.hits += 1;
+        self.recency.remove(&entry.last_used);
+        self.clock += 1;
+        entry.last_used = self.clock;
+        self.recency.insert(self.clock, key.to_string());
+        Some(&entry.data)
+    }
+
+    /// Store `data` under `key` as the most recently used entry, evicting
+    /// older ones as needed. Data that could never fit is not cached.
+    pub(crate) fn insert(&mut self, key: String, data: Vec<u8>) {
+        if self.max_entries == 0 || data.len() > self.max_bytes {
+            return;
+        }
+
+        self.remove(&key);
+        self.stats.insertions += 1;
+        self.clock += 1;
+        self.bytes += data.len();
+        self.recency.insert(self.clock, key.clone());
+        self.entries.insert(
+            key,
+            Entry {
+                data,
+                last_used: self.clock,
+            },
+        );
+        self.evict();
+    }
+
+    pub(crate) fn contains(&self, key: &str) -> bool {
+        self.entries.contains_key(key)
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Total length of the cached values
+    pub(crate) fn bytes(&self) -> usize {
+        self.bytes
+    }
+
+    pub(crate) fn stats(&self) -> CacheStats {
+        self.stats
+    }
+
+    pub(crate) fn reset_stats(&mut self) {
+        self.stats = CacheStats::default();
+    }
+
+    /// Keys from least to most recently used
+    pub(crate) fn keys(&self) -> impl Iterator<Item = &String> {
+        self.recency.values()
+    }
+
+    /// Change the limits, evicting immediately to meet them
+    pub(crate) fn set_limits(&mut self, max_entries: usize, max_bytes: usize) {
+        self.max_entries = max_entries;
+        self.max_bytes = max_bytes;
+        self.evict();
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.entries.clear();
+        self.recency.clear();
+        self.bytes = 0;
+    }
+
+    fn remove(&mut self, key: &str) {
+        if let Some(entry) = self.entries.remove(key) {
+            self.recency.remove(&entry.last_used);
+            self.bytes -= entry.data.len();
+        }
+    }
+
+    fn evict(&mut self) {
+        while self.entries.len() > self.max_entries || self.bytes > self.max_bytes {
+            let Some((_, key)) = self.recency.pop_first() else {
+                break;
+            };
+            if let Some(entry) = self.entries.remove(&key) {
+                self.bytes -= entry.data.len();
+                self.stats.evictions += 1;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LruCache;
+
+    fn keys(cache: &LruCache) -> Vec<&str> {
+        cache.keys().map(String::as_str).collect()
+    }
+
+    #[test]
+    fn evicts_the_least_recently_used_entry() {
+        let mut cache = LruCache::new(2, 1024);
+        cache.insert("a".into(), vec![1]);
+        cache.insert("b".into(), vec![2]);
+        assert_eq!(cache.get("a"), Some(&[1][..]));
+
+        cache.insert("c".into(), vec![3]);
+        assert!(!cache.contains("b"), "b was used least recently");
+        assert_eq!(keys(&cache), ["a", "c"]);
+        assert_eq!(cache.stats().evictions, 1);
+    }
+
+    #[test]
+    fn reinserting_a_key_makes_it_most_recent() {
+        let mut cache = LruCache::new(2, 1024);
+        cache.insert("a".into(), vec![1]);
+        cache.insert("b".into(), vec![2]);
+        cache.insert("a".into(), vec![1, 1]);
+
+        cache.insert("c".into(), vec![3]);
+        assert_eq!(keys(&cache), ["a", "c"]);
+        assert_eq!(cache.get("a"), Some(&[1, 1][..]));
+        assert_eq!(cache.bytes(), 3);
+    }
+
+    #[test]
+    fn evicts_until_within_the_byte_limit() {
+        let mut cache = LruCache::new(10, 10);
+        cache.insert("a".into(), vec![0; 4]);
+        cache.insert("b".into(), vec![0; 4]);
+        cache.insert("c".into(), vec![0; 2]);
+        assert_eq!(cache.bytes(), 10);
+
+        // Making room for 7 bytes takes both of the oldest entries
+        cache.insert("d".into(), vec![0; 7]);
+        assert_eq!(keys(&cache), ["c", "d"]);
+        assert_eq!(cache.bytes(), 9);
+        assert_eq!(cache.stats().evictions, 2);
+    }
+
+    #[test]
+    fn values_larger_than_the_byte_limit_are_not_cached() {
+        let mut cache = LruCache::new(10, 10);
+        cache.insert("a".into(), vec![0; 4]);
+        cache.insert("big".into(), vec![0; 11]);
+        assert!(!cache.contains("big"));
+        assert_eq!(keys(&cache), ["a"], "nothing is evicted for it");
+        assert_eq!(cache.stats().insertions, 1);
+    }
+
+    #[test]
+    fn lowering_the_limits_evicts_immediately() {
+        let mut cache = LruCache::new(10, 100);
+        for (i, key) in ["a", "b", "c", "d"].into_iter().enumerate() {
+            cache.insert(key.into(), vec![0; 10 * (i + 1)]);
+        }
+        cache.get("a");
+
+        cache.set_limits(10, 50);
+        assert_eq!(keys(&cache), ["d", "a"]);
+        assert_eq!(cache.bytes(), 50);
+
+        cache.set_limits(1, 50);
+        assert_eq!(keys(&cache), ["a"]);
+        assert_eq!(cache.len(), 1);
+    }
+}
@@ -0,0 +1,38 @@
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+use wasm_bindgen::prelude::*;
+
+/// Flag shared with in-flight asynchronous work so JavaScript can abort it.
+///
+/// Work checks the token between units of work, so cancelling takes effect
+/// at the next check rather than immediately.
+#[wasm_bindgen]
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+#[wasm_bindgen]
+impl CancellationToken {
+    /// A token that has not been cancelled
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> CancellationToken {
+        crate::init::ensure_init();
+        CancellationToken::default()
+    }
+
+    /// Ask every operation holding this token to stop
+    #[wasm_bindgen]
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether [`CancellationToken::cancel`] has been called
+    #[wasm_bindgen(getter)]
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
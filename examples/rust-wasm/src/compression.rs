@@ -0,0 +1,340 @@
+use std::io::{self, Read, Write};
+
+use flate2::{
+    read::{DeflateDecoder, GzDecoder, ZlibDecoder},
+    write::{self, DeflateEncoder, GzEncoder, ZlibEncoder},
+    Compression,
+};
+use js_sys::Uint8Array;
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    cache_key,
+    stream::{output_limit, StreamTransform},
+    WasmError, WasmModule,
+};
+
+/// Container formats around a DEFLATE stream
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Codec {
+    /// Raw DEFLATE (RFC 1951)
+    Deflate,
+    /// gzip (RFC 1952)
+    Gzip,
+    /// zlib (RFC 1950)
+    Zlib,
+}
+
+impl Codec {
+    const SUPPORTED: &'static str = "deflate, gzip, zlib";
+
+    pub(crate) fn parse(name: &str) -> Result<Codec, WasmError> {
+        match name.to_ascii_lowercase().as_str() {
+            "deflate" => Ok(Codec::Deflate),
+            "gzip" => Ok(Codec::Gzip),
+            "zlib" => Ok(Codec::Zlib),
+            _ => Err(WasmError::unsupported(
+                "compression format",
+                name,
+                Self::SUPPORTED,
+            )),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Codec::Deflate => "deflate",
+            Codec::Gzip => "gzip",
+            Codec::Zlib => "zlib",
+        }
+    }
+
+    fn compress(self, data: &[u8], level: Compression) -> std::io::Result<Vec<u8>> {
+        match self {
+            Codec::Deflate => {
+                let mut encoder = DeflateEncoder::new(Vec::new(), level);
+                encoder.write_all(data)?;
+                encoder.finish()
+            }
+            Codec::Gzip => {
+                let mut encoder = GzEncoder::new(Vec::new(), level);
+                encoder.write_all(data)?;
+                encoder.finish()
+            }
+            Codec::Zlib => {
+                let mut encoder = ZlibEncoder::new(Vec::new(), level);
+                encoder.write_all(data)?;
+                encoder.finish()
+            }
+        }
+    }
+
+    /// Decompress `data`, reading at most one byte past `max_size` so an
+    /// oversized stream is detected without inflating all of it
+    fn decompress(self, data: &[u8], max_size: usize) -> std::io::Result<Vec<u8>> {
+        let limit = (max_size as u64).saturating_add(1);
+        let mut output = Vec::new();
+        match self {
+            Codec::Deflate => DeflateDecoder::new(data)
+                .take(limit)
+                .read_to_end(&mut output)?,
+            Codec::Gzip => GzDecoder::new(data).take(limit).read_to_end(&mut output)?,
+            Codec::Zlib => ZlibDecoder::new(data)
+                .take(limit)
+                .read_to_end(&mut output)?,
+        };
+        Ok(output)
+    }
+
+    /// An incremental compressor at the default level, writing at most
+    /// `max_output` bytes
+    pub(crate) fn encoder(self, max_output: usize) -> Box<dyn StreamTransform> {
+        let (output, level) = (CappedOutput::new(max_output), Compression::default());
+        match self {
+            Codec::Deflate => FlateStream::encoder(self, DeflateEncoder::new(output, level)),
+            Codec::Gzip => FlateStream::encoder(self, GzEncoder::new(output, level)),
+            Codec::Zlib => FlateStream::encoder(self, ZlibEncoder::new(output, level)),
+        }
+    }
+
+    /// An incremental decompressor, inflating at most `max_output` bytes
+    pub(crate) fn decoder(self, max_output: usize) -> Box<dyn StreamTransform> {
+        let output = CappedOutput::new(max_output);
+        match self {
+            Codec::Deflate => FlateStream::decoder(self, write::DeflateDecoder::new(output)),
+            Codec::Gzip => FlateStream::decoder(self, write::GzDecoder::new(output)),
+            Codec::Zlib => FlateStream::decoder(self, write::ZlibDecoder::new(output)),
+        }
+    }
+}
+
+/// DEFLATE-based compression.
+///
+/// Results go through the module's content-hash cache, so compressing or
+/// decompressing the same payload again is a lookup.
+#[wasm_bindgen]
+impl WasmModule {
+    /// Compress `input` as `"deflate"`, `"gzip"` or `"zlib"` at `level` 0
+    /// (store only) to 9 (smallest)
+    #[wasm_bindgen]
+    pub fn compress(
+        &mut self,
+        input: &Uint8Array,
+        format: &str,
+        level: u32,
+    ) -> Result<Uint8Array, WasmError> {
+        let codec = Codec::parse(format)?;
+        if level > 9 {
+            return Err(WasmError::invalid(
+                "compression level",
+                format!("must be between 0 and 9, got {level}"),
+            ));
+        }
+        if !self.is_initialized {
+            return Err(WasmError::NotInitialized);
+        }
+
+        let data = input.to_vec();
+        let key = cache_key(&format!("{}{level}", codec.name()), &data);
+        if let Some(cached) = self.processing_cache.borrow_mut().get(&key) {
+            return Ok(Uint8Array::from(cached));
+        }
+
+        let compressed = codec
+            .compress(&data, Compression::new(level))
+            .map_err(|e| WasmError::ResourceUnavailable {
+                reason: format!("Compression failed: {e}"),
+            })?;
+        let result = Uint8Array::from(&compressed[..]);
+        self.processing_cache.borrow_mut().insert(key, compressed);
+        Ok(result)
+    }
+
+    /// Decompress a `"deflate"`, `"gzip"` or `"zlib"` stream.
+    ///
+    /// Decompression stops with an error once the output would exceed
+    /// `max_size` bytes, which guards against compression bombs.
+    #[wasm_bindgen]
+    pub fn decompress(
+        &mut self,
+        input: &Uint8Array,
+        format: &str,
+        max_size: usize,
+    ) -> Result<Uint8Array, WasmError> {
+        let codec = Codec::parse(format)?;
+        if !self.is_initialized {
+            return Err(WasmError::NotInitialized);
+        }
+        let too_large = || {
+            WasmError::invalid(
+                "compressed data",
+                format!("decompresses to more than {max_size} bytes"),
+            )
+        };
+
+        let data = input.to_vec();
+        let key = cache_key(&format!("inflate_{}", codec.name()), &data);
+        if let Some(cached) = self.processing_cache.borrow_mut().get(&key) {
+            if cached.len() > max_size {
+                return Err(too_large());
+            }
+            return Ok(Uint8Array::from(cached));
+        }
+
+        let decompressed = codec.decompress(&data, max_size).map_err(|e| {
+            WasmError::invalid(
+                "compressed data",
+                format!("not a valid {} stream: {e}", codec.name()),
+            )
+        })?;
+        if decompressed.len() > max_size {
+            return Err(too_large());
+        }
+        let result = Uint8Array::from(&decompressed[..]);
+        self.processing_cache.borrow_mut().insert(key, decompressed);
+        Ok(result)
+    }
+}
+
+/// Output of a stream session's flate2 writer, which fails once more than
+/// `limit` bytes have been written to it in total
+struct CappedOutput {
+    buffer: Vec<u8>,
+    written: usize,
+    limit: usize,
+}
+
+impl CappedOutput {
+    fn new(limit: usize) -> CappedOutput {
+        CappedOutput {
+            buffer: Vec::new(),
+            written: 0,
+            limit,
+        }
+    }
+}
+
+/// The error a [`CappedOutput`] fails with, holding its limit
+#[derive(Debug)]
+struct OutputLimit(usize);
+
+impl std::fmt::Display for OutputLimit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "output passes the limit of {} bytes", self.0)
+    }
+}
+
+impl std::error::Error for OutputLimit {}
+
+impl Write for CappedOutput {
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        if data.len() > self.limit - self.written {
+            return Err(io::Error::new(
+                io::ErrorKind::Other,
+                OutputLimit(self.limit),
+            ));
+        }
+        self.written += data.len();
+        self.buffer.extend_from_slice(data);
+        Ok(data.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// A flate2 writer whose output collects in a [`CappedOutput`]
+trait FlateWriter: Write {
+    fn output(&mut self) -> &mut Vec<u8>;
+    fn finish_output(self) -> io::Result<Vec<u8>>;
+}
+
+macro_rules! flate_writer {
+    ($($writer:ty),*) => {
+        $(impl FlateWriter for $writer {
+            fn output(&mut self) -> &mut Vec<u8> {
+                &mut self.get_mut().buffer
+            }
+
+            fn finish_output(self) -> io::Result<Vec<u8>> {
+                self.finish().map(|output| output.buffer)
+            }
+        })*
+    };
+}
+
+flate_writer!(
+    DeflateEncoder<CappedOutput>,
+    GzEncoder<CappedOutput>,
+    ZlibEncoder<CappedOutput>,
+    write::DeflateDecoder<CappedOutput>,
+    write::GzDecoder<CappedOutput>,
+    write::ZlibDecoder<CappedOutput>
+);
+
+/// Compression or decompression as a stream transform, handing back the
+/// writer's output after every chunk
+struct FlateStream<W> {
+    codec: Codec,
+    decoding: bool,
+    writer: W,
+}
+
+impl<W: FlateWriter + 'static> FlateStream<W> {
+    fn encoder(codec: Codec, writer: W) -> Box<dyn StreamTransform> {
+        Box::new(FlateStream {
+            codec,
+            decoding: false,
+            writer,
+        })
+    }
+
+    fn decoder(codec: Codec, writer: W) -> Box<dyn StreamTransform> {
+        Box::new(FlateStream {
+            codec,
+            decoding: true,
+            writer,
+        })
+    }
+}
+
+impl<W: FlateWriter + 'static> StreamTransform for FlateStream<W> {
+    fn push(&mut self, chunk: &[u8]) -> Result<Vec<u8>, WasmError> {
+        let (codec, decoding) = (self.codec, self.decoding);
+        self.writer
+            .write_all(chunk)
+            .map_err(|e| stream_error(codec, decoding, e))?;
+        Ok(std::mem::take(self.writer.output()))
+    }
+
+    fn finish(self: Box<Self>) -> Result<Vec<u8>, WasmError> {
+        let FlateStream {
+            codec,
+            decoding,
+            writer,
+        } = *self;
+        writer
+            .finish_output()
+            .map_err(|e| stream_error(codec, decoding, e))
+    }
+}
+
+fn stream_error(codec: Codec, decoding: bool, error: io::Error) -> WasmError {
+    if let Some(limit) = error
+        .get_ref()
+        .and_then(|e| e.downcast_ref::<OutputLimit>())
+    {
+        return output_limit(limit.0);
+    }
+    if decoding {
+        WasmError::invalid(
+            "compressed data",
+            format!("not a valid {} stream: {error}", codec.name()),
+        )
+    } else {
+        WasmError::ResourceUnavailable {
+            reason: format!("Compression failed: {error}"),
+        }
+    }
+}
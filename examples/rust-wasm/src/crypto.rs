@@ -0,0 +1,168 @@
+use chacha20poly1305::{
+    aead::{Aead, KeyInit, Payload},
+    ChaCha20Poly1305, Nonce,
+};
+use js_sys::Uint8Array;
+use sha2::Sha256;
+use wasm_bindgen::prelude::*;
+use zeroize::Zeroizing;
+
+use crate::{WasmError, WasmModule};
+
+/// ChaCha20-Poly1305 key length in bytes
+const KEY_LEN: usize = 32;
+
+/// Nonce length; a fresh random nonce leads every ciphertext
+const NONCE_LEN: usize = 12;
+
+/// Poly1305 tag length, appended to the encrypted bytes
+const TAG_LEN: usize = 16;
+
+/// A 32-byte ChaCha20-Poly1305 key, held in WASM memory.
+///
+/// JavaScript only ever sees the handle, never the key bytes, and the bytes
+/// are overwritten with zeros when the key is dropped or `free`d.
+#[wasm_bindgen]
+pub struct WasmKey {
+    bytes: Zeroizing<[u8; KEY_LEN]>,
+}
+
+#[wasm_bindgen]
+impl WasmKey {
+    /// Import a key that already exists as raw bytes; anything but 32 of
+    /// them is a `DIMENSION_MISMATCH`.
+    ///
+    /// The bytes are copied straight into the key's own storage; clearing
+    /// `bytes` in JavaScript is up to the caller.
+    #[wasm_bindgen]
+    pub fn from_bytes(bytes: &Uint8Array) -> Result<WasmKey, WasmError> {
+        let mut key = WasmKey {
+            bytes: Zeroizing::new([0; KEY_LEN]),
+        };
+        if bytes.length() as usize != KEY_LEN {
+            return Err(WasmError::dimension(
+                "Key length in bytes",
+                KEY_LEN,
+                bytes.length() as usize,
+            ));
+        }
+        bytes.copy_to(&mut key.bytes[..]);
+        Ok(key)
+    }
+}
+
+impl WasmKey {
+    fn cipher(&self) -> ChaCha20Poly1305 {
+        // The cipher keeps its own copy, which it zeroes on drop
+        ChaCha20Poly1305::new((&*self.bytes).into())
+    }
+}
+
+/// Authenticated encryption with ChaCha20-Poly1305.
+///
+/// `encrypt` returns the 12-byte random nonce followed by the ciphertext
+/// and its 16-byte tag, which is exactly what `decrypt` expects. Errors
+/// are distinguished by code: input too short to hold a nonce and tag is
+/// an `INVALID_ARGUMENT`, and a tampered ciphertext, wrong key or wrong
+/// associated data is an `AUTHENTICATION_FAILED`. Copies of the plaintext
+/// made along the way are zeroed before they are freed.
+#[wasm_bindgen]
+impl WasmModule {
+    /// Encrypt `plaintext` under `key`, authenticating the optional
+    /// `associated_data` alongside it
+    #[wasm_bindgen]
+    pub fn encrypt(
+        &mut self,
+        plaintext: &Uint8Array,
+        key: &WasmKey,
+        associated_data: Option<Uint8Array>,
+    ) -> Result<Uint8Array, WasmError> {
+        let cipher = key.cipher();
+        let mut nonce = [0; NONCE_LEN];
+        getrandom::getrandom(&mut nonce).map_err(|e| WasmError::ResourceUnavailable {
+            reason: format!("Failed to generate a nonce: {e}"),
+        })?;
+
+        let aad = associated_data
+            .map(|data| data.to_vec())
+            .unwrap_or_default();
+        let plaintext = Zeroizing::new(plaintext.to_vec());
+        let payload = Payload {
+            msg: &plaintext,
+            aad: &aad,
+        };
+        // Encryption only fails for inputs far beyond what WASM memory holds
+        let ciphertext = cipher
+            .encrypt(Nonce::from_slice(&nonce), payload)
+            .map_err(|_| WasmError::invalid("plaintext", "is too long to encrypt"))?;
+
+        let mut sealed = Vec::with_capacity(NONCE_LEN + ciphertext.len());
+        sealed.extend_from_slice(&nonce);
+        sealed.extend_from_slice(&ciphertext);
+        Ok(Uint8Array::from(&sealed[..]))
+    }
+
+    /// Decrypt the output of [`WasmModule::encrypt`], checking it against
+    /// `key` and the same `associated_data`
+    #[wasm_bindgen]
+    pub fn decrypt(
+        &mut self,
+        ciphertext: &Uint8Array,
+        key: &WasmKey,
+        associated_data: Option<Uint8Array>,
+    ) -> Result<Uint8Array, WasmError> {
+        let cipher = key.cipher();
+        let sealed = ciphertext.to_vec();
+        if sealed.len() < NONCE_LEN + TAG_LEN {
+            return Err(WasmError::invalid(
+                "ciphertext",
+                format!(
+                    "{} bytes cannot hold the {NONCE_LEN}-byte nonce and {TAG_LEN}-byte tag",
+                    sealed.len()
+                ),
+            ));
+        }
+
+        let (nonce, encrypted) = sealed.split_at(NONCE_LEN);
+        let aad = associated_data
+            .map(|data| data.to_vec())
+            .unwrap_or_default();
+        let payload = Payload {
+            msg: encrypted,
+            aad: &aad,
+        };
+        let plaintext = cipher
+            .decrypt(Nonce::from_slice(nonce), payload)
+            .map(Zeroizing::new)
+            .map_err(|_| WasmError::AuthenticationFailed)?;
+        Ok(Uint8Array::from(&plaintext[..]))
+    }
+
+    /// Derive a key from `password` with PBKDF2-HMAC-SHA256.
+    ///
+    /// Use a random salt of at least 16 bytes, stored alongside the
+    /// ciphertext, and as many iterations as the page can afford; the
+    /// derivation runs synchronously, so very high counts belong in a worker.
+    /// The key stays in WASM memory; only a handle is returned.
+    #[wasm_bindgen]
+    pub fn derive_key(
+        password: String,
+        salt: &Uint8Array,
+        iterations: u32,
+    ) -> Result<WasmKey, WasmError> {
+        let password = Zeroizing::new(password);
+        if iterations == 0 {
+            return Err(WasmError::invalid("iterations", "must be positive"));
+        }
+        let mut key = WasmKey {
+            bytes: Zeroizing::new([0; KEY_LEN]),
+        };
+        pbkdf2::pbkdf2_hmac::<Sha256>(
+            password.as_bytes(),
+            &salt.to_vec(),
+            iterations,
+            &mut key.bytes[..],
+        );
+        Ok(key)
+    }
+}
@@ -0,0 +1,146 @@
+//! A processor for callers that cannot afford a fresh result per call, such
+//! as an animation loop: results go to a buffer the caller owns, or to one
+//! internal buffer that JavaScript reads through a view.
+
+use js_sys::Float64Array;
+use rayon::prelude::*;
+use wasm_bindgen::prelude::*;
+
+use crate::{install, BatchOp, WasmError, WasmRuntime};
+
+/// Elements per parallel task unless `set_chunk_size` says otherwise
+const DEFAULT_CHUNK_SIZE: usize = 1024;
+
+/// Applies a [`BatchOp`] without allocating once its buffer is large
+/// enough, in chunks of a configurable size
+#[wasm_bindgen]
+pub struct MemoryEfficientProcessor {
+    runtime: WasmRuntime,
+    chunk_size: usize,
+    /// Results of `process_view`, reused between calls
+    buffer: Vec<f64>,
+}
+
+#[wasm_bindgen]
+impl MemoryEfficientProcessor {
+    /// Create a processor whose internal buffer holds `capacity` elements
+    /// before it grows, on the shared runtime of `num_threads` workers (see
+    /// [`WasmRuntime::shared`])
+    #[wasm_bindgen(constructor)]
+    pub fn new(capacity: usize, num_threads: usize) -> Result<MemoryEfficientProcessor, WasmError> {
+        Ok(MemoryEfficientProcessor::with_runtime(
+            capacity,
+            &WasmRuntime::shared(num_threads)?,
+        ))
+    }
+
+    /// Like `new`, but sharing `runtime`'s thread pool
+    #[wasm_bindgen]
+    pub fn with_runtime(capacity: usize, runtime: &WasmRuntime) -> MemoryEfficientProcessor {
+        MemoryEfficientProcessor {
+            runtime: runtime.clone(),
+            chunk_size: DEFAULT_CHUNK_SIZE,
+            buffer: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// The runtime whose thread pool this processor uses
+    #[wasm_bindgen(getter)]
+    pub fn runtime(&self) -> WasmRuntime {
+        self.runtime.clone()
+    }
+
+    /// Elements each parallel task processes, 1024 by default
+    #[wasm_bindgen(getter)]
+    pub fn chunk_size(&self) -> usize {
+        self.chunk_size
+    }
+
+    /// Process `chunk_size` elements per parallel task. Smaller chunks
+    /// balance uneven work better and larger ones cost less scheduling;
+    /// results are the same either way. It must be positive.
+    #[wasm_bindgen]
+    pub fn set_chunk_size(&mut self, chunk_size: usize) -> Result<(), WasmError> {
+        if chunk_size == 0 {
+            return Err(WasmError::invalid("chunk size", "must be positive"));
+        }
+        self.chunk_size = chunk_size;
+        Ok(())
+    }
+
+    /// Bytes reserved by the internal buffer
+    #[wasm_bindgen]
+    pub fn heap_bytes(&self) -> usize {
+        self.buffer.capacity() * std::mem::size_of::<f64>()
+    }
+
+    /// Apply `op` to every element of `data`, writing the results to `out`,
+    /// which must be as long as `data`.
+    ///
+    /// Nothing is allocated or cloned on the Rust side; from JavaScript,
+    /// wasm-bindgen still copies `out` in and back out of linear memory.
+    /// Inputs outside an operation's domain give the IEEE result.
+    #[wasm_bindgen]
+    pub fn process_into(
+        &mut self,
+        data: &[f64],
+        op: BatchOp,
+        out: &mut [f64],
+    ) -> Result<(), WasmError> {
+        if out.len() != data.len() {
+            return Err(WasmError::dimension("Output length", data.len(), out.len()));
+        }
+        apply_chunked(&self.runtime, self.chunk_size, data, op, out)
+    }
+
+    /// Apply `op` to every element of `data` into the internal buffer and
+    /// return a `Float64Array` aliasing it, without copying.
+    ///
+    /// The view is invalidated by the next call into the processor, which
+    /// overwrites the buffer and reallocates it if `data` is longer than it
+    /// has room for, and by any growth of WASM memory, which detaches it.
+    /// Read or copy it before calling back into the module.
+    #[wasm_bindgen]
+    pub fn process_view(&mut self, data: &[f64], op: BatchOp) -> Result<Float64Array, WasmError> {
+        let results = self.process_in_place(data, op)?;
+        // SAFETY: the view is handed straight to JavaScript, and no Rust code
+        // runs (so nothing can allocate or touch the buffer) before JS reads it.
+        Ok(unsafe { Float64Array::view(results) })
+    }
+}
+
+impl MemoryEfficientProcessor {
+    /// [`MemoryEfficientProcessor::process_view`] for callers in Rust,
+    /// borrowing the internal buffer instead of viewing it
+    pub fn process_in_place(&mut self, data: &[f64], op: BatchOp) -> Result<&[f64], WasmError> {
+        self.buffer.clear();
+        self.buffer.resize(data.len(), 0.0);
+        apply_chunked(&self.runtime, self.chunk_size, data, op, &mut self.buffer)?;
+        Ok(&self.buffer)
+    }
+}
+
+/// `out[i] = op(data[i])`, `chunk_size` elements per task; a single chunk
+/// runs on the calling thread
+fn apply_chunked(
+    runtime: &WasmRuntime,
+    chunk_size: usize,
+    data: &[f64],
+    op: BatchOp,
+    out: &mut [f64],
+) -> Result<(), WasmError> {
+    let apply = |out: &mut [f64], data: &[f64]| {
+        for (result, &x) in out.iter_mut().zip(data) {
+            *result = op.apply(x);
+        }
+    };
+    if data.len() <= chunk_size {
+        apply(out, data);
+        return Ok(());
+    }
+    install(runtime, || {
+        out.par_chunks_mut(chunk_size)
+            .zip(data.par_chunks(chunk_size))
+            .for_each(|(out, data)| apply(out, data));
+    })
+}
@@ -0,0 +1,172 @@
+use std::cmp::Ordering;
+
+use rayon::prelude::*;
+use wasm_bindgen::prelude::*;
+
+use crate::{install, WasmError, WasmRuntime};
+
+/// Cosine-similarity search over word embeddings kept in WASM memory.
+///
+/// Embeddings are scaled to unit length as they are loaded, so a query costs
+/// one dot product per word.
+#[wasm_bindgen]
+pub struct WasmEmbeddingIndex {
+    runtime: WasmRuntime,
+    /// Values per embedding; 0 until embeddings are loaded
+    dim: usize,
+    /// Unit-length embeddings, `dim` values per word; zero vectors stay zero
+    vectors: Vec<f32>,
+}
+
+#[wasm_bindgen]
+impl WasmEmbeddingIndex {
+    /// An empty index using the global runtime
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Result<WasmEmbeddingIndex, WasmError> {
+        Ok(WasmEmbeddingIndex::with_runtime(&WasmRuntime::new(0)?))
+    }
+
+    /// An empty index sharing `runtime`'s thread pool
+    #[wasm_bindgen]
+    pub fn with_runtime(runtime: &WasmRuntime) -> WasmEmbeddingIndex {
+        WasmEmbeddingIndex {
+            runtime: runtime.clone(),
+            dim: 0,
+            vectors: Vec::new(),
+        }
+    }
+
+    /// Replace the index with `n_words` embeddings of `dim` values each,
+    /// stored back to back; word indices are positions in `data`.
+    ///
+    /// Each embedding is normalised in parallel. Values must be finite.
+    #[wasm_bindgen]
+    pub fn load_embeddings(
+        &mut self,
+        data: &[f32],
+        n_words: usize,
+        dim: usize,
+    ) -> Result<(), WasmError> {
+        if dim == 0 {
+            return Err(WasmError::invalid("dim", "must be positive"));
+        }
+        if u32::try_from(n_words).is_err() {
+            return Err(WasmError::invalid(
+                "n_words",
+                "word indices must fit in u32",
+            ));
+        }
+        match n_words.checked_mul(dim) {
+            Some(expected) if expected == data.len() => {}
+            expected => {
+                return Err(WasmError::dimension(
+                    format!("Data length of {n_words} embeddings of {dim} values"),
+                    expected.unwrap_or(usize::MAX),
+                    data.len(),
+                ))
+            }
+        }
+        if let Some(index) = install(&self.runtime, || {
+            data.par_iter().position_first(|x| !x.is_finite())
+        })? {
+            return Err(WasmError::invalid(
+                "embeddings",
+                format!(
+                    "value {} of word {} is not finite",
+                    data[index],
+                    index / dim
+                ),
+            ));
+        }
+
+        let mut vectors = data.to_vec();
+        install(&self.runtime, || {
+            vectors.par_chunks_mut(dim).for_each(|vector| {
+                normalize(vector);
+            });
+        })?;
+        self.dim = dim;
+        self.vectors = vectors;
+        Ok(())
+    }
+
+    /// Number of embeddings loaded
+    #[wasm_bindgen(getter)]
+    pub fn n_words(&self) -> usize {
+        self.vectors.len().checked_div(self.dim).unwrap_or(0)
+    }
+
+    /// Values per embedding, or 0 before any are loaded
+    #[wasm_bindgen(getter)]
+    pub fn dim(&self) -> usize {
+        self.dim
+    }
+
+    /// Indices of the `top_k` words most similar to `query` by cosine
+    /// similarity, most similar first and ties by index.
+    ///
+    /// Fewer are returned if the index holds fewer words. Zero embeddings
+    /// have similarity 0 to everything.
+    #[wasm_bindgen]
+    pub fn most_similar(&self, query: &[f32], top_k: u32) -> Result<Vec<u32>, WasmError> {
+        if self.dim == 0 {
+            return Err(WasmError::NotInitialized);
+        }
+        if query.len() != self.dim {
+            return Err(WasmError::dimension(
+                "Query length (embedding dimension)",
+                self.dim,
+                query.len(),
+            ));
+        }
+        if query.iter().any(|x| !x.is_finite()) {
+            return Err(WasmError::invalid("query", "values must be finite"));
+        }
+        let mut query = query.to_vec();
+        if !normalize(&mut query) {
+            return Err(WasmError::invalid(
+                "query",
+                "the zero vector has no direction to compare",
+            ));
+        }
+
+        let scores: Vec<f32> = install(&self.runtime, || {
+            self.vectors
+                .par_chunks(self.dim)
+                .map(|vector| vector.iter().zip(&query).map(|(a, b)| a * b).sum())
+                .collect()
+        })?;
+
+        let k = (top_k as usize).min(scores.len());
+        if k == 0 {
+            return Ok(Vec::new());
+        }
+        let by_score = |&a: &u32, &b: &u32| -> Ordering {
+            scores[b as usize]
+                .total_cmp(&scores[a as usize])
+                .then(a.cmp(&b))
+        };
+        let mut order: Vec<u32> = (0..scores.len() as u32).collect();
+        order.select_nth_unstable_by(k - 1, by_score);
+        order.truncate(k);
+        order.sort_unstable_by(by_score);
+        Ok(order)
+    }
+}
+
+/// Scale `vector` to unit length, returning false, and leaving it alone, if
+/// it is zero. The norm is taken in `f64` so large values cannot overflow.
+fn normalize(vector: &mut [f32]) -> bool {
+    let norm = vector
+        .iter()
+        .map(|&x| x as f64 * x as f64)
+        .sum::<f64>()
+        .sqrt();
+    if norm == 0.0 {
+        return false;
+    }
+    for x in vector.iter_mut() {
+        *x = (*x as f64 / norm) as f32;
+    }
+    true
+}
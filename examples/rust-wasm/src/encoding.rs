@@ -0,0 +1,238 @@
+use js_sys::Uint8Array;
+use wasm_bindgen::prelude::*;
+
+use crate::{WasmError, WasmModule};
+
+/// Input bytes copied out of JavaScript per step when encoding; a multiple
+/// of 3 so base64 groups never straddle two steps
+const ENCODE_CHUNK: usize = 3 * 16 * 1024;
+
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+const BASE64_URL: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+const HEX: &[u8; 16] = b"0123456789abcdef";
+
+/// Marks bytes outside an alphabet in a [`decode_table`]
+const INVALID: u8 = 0xFF;
+
+/// Binary-to-text encodings
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Encoding {
+    /// RFC 4648 base64 with `=` padding
+    Base64,
+    /// RFC 4648 URL-safe base64, written without padding
+    Base64Url,
+    /// Lowercase hexadecimal; decoding accepts either case
+    Hex,
+}
+
+impl Encoding {
+    const SUPPORTED: &'static str = "base64, base64url, hex";
+
+    fn parse(name: &str) -> Result<Encoding, WasmError> {
+        match name.to_ascii_lowercase().as_str() {
+            "base64" => Ok(Encoding::Base64),
+            "base64url" => Ok(Encoding::Base64Url),
+            "hex" => Ok(Encoding::Hex),
+            _ => Err(WasmError::unsupported("encoding", name, Self::SUPPORTED)),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Encoding::Base64 => "base64",
+            Encoding::Base64Url => "base64url",
+            Encoding::Hex => "hex",
+        }
+    }
+
+    /// Length of the encoding of `len` bytes
+    fn encoded_len(self, len: usize) -> usize {
+        match self {
+            Encoding::Base64 => (len + 2) / 3 * 4,
+            Encoding::Base64Url => len / 3 * 4 + [0, 2, 3][len % 3],
+            Encoding::Hex => len * 2,
+        }
+    }
+
+    /// Append the encoding of `bytes`; only the final chunk of an input may
+    /// have a length that is not a multiple of 3
+    fn encode_into(self, bytes: &[u8], out: &mut Vec<u8>) {
+        let alphabet = match self {
+            Encoding::Hex => {
+                for &byte in bytes {
+                    out.extend([HEX[(byte >> 4) as usize], HEX[(byte & 0xF) as usize]]);
+                }
+                return;
+            }
+            Encoding::Base64 => BASE64,
+            Encoding::Base64Url => BASE64_URL,
+        };
+
+        let groups = bytes.chunks_exact(3);
+        let rest = groups.remainder();
+        for group in groups {
+            let bits = u32::from_be_bytes([0, group[0], group[1], group[2]]);
+            out.extend([18, 12, 6, 0].map(|shift| alphabet[(bits >> shift & 0x3F) as usize]));
+        }
+        if !rest.is_empty() {
+            let bits = u32::from_be_bytes([0, rest[0], rest.get(1).copied().unwrap_or(0), 0]);
+            let digits = rest.len() + 1;
+            out.extend(
+                [18, 12, 6]
+                    .iter()
+                    .take(digits)
+                    .map(|shift| alphabet[(bits >> shift & 0x3F) as usize]),
+            );
+            if self == Encoding::Base64 {
+                out.extend(std::iter::repeat(b'=').take(4 - digits));
+            }
+        }
+    }
+
+    fn decode(self, input: &str) -> Result<Vec<u8>, WasmError> {
+        match self {
+            Encoding::Hex => decode_hex(input),
+            Encoding::Base64 => decode_base64(input, self, &BASE64_DECODE),
+            Encoding::Base64Url => decode_base64(input, self, &BASE64_URL_DECODE),
+        }
+    }
+}
+
+const BASE64_DECODE: [u8; 256] = decode_table(BASE64);
+const BASE64_URL_DECODE: [u8; 256] = decode_table(BASE64_URL);
+
+/// Map each byte to its 6-bit value in `alphabet`, or [`INVALID`]
+const fn decode_table(alphabet: &[u8; 64]) -> [u8; 256] {
+    let mut table = [INVALID; 256];
+    let mut i = 0;
+    while i < 64 {
+        table[alphabet[i] as usize] = i as u8;
+        i += 1;
+    }
+    table
+}
+
+/// Base64 and hex, for binary payloads too large for `btoa`.
+///
+/// `"base64"` is written with padding and must be padded when decoded.
+/// `"base64url"` is written without padding and, as RFC 4648 allows when
+/// the length is known, decodes with or without it. Decoding errors carry
+/// the byte offset of the first invalid character.
+#[wasm_bindgen]
+impl WasmModule {
+    /// Encode `input` as `"base64"`, `"base64url"` or `"hex"`
+    #[wasm_bindgen]
+    pub fn encode(&self, input: &Uint8Array, encoding: &str) -> Result<String, WasmError> {
+        let encoding = Encoding::parse(encoding)?;
+        let len = input.length() as usize;
+
+        // Copy the input out a chunk at a time and encode straight into the
+        // final buffer, rather than holding a full copy of the input too
+        let mut out = Vec::with_capacity(encoding.encoded_len(len));
+        let mut chunk = vec![0; ENCODE_CHUNK.min(len)];
+        for start in (0..len).step_by(ENCODE_CHUNK) {
+            let end = (start + ENCODE_CHUNK).min(len);
+            let chunk = &mut chunk[..end - start];
+            input.subarray(start as u32, end as u32).copy_to(chunk);
+            encoding.encode_into(chunk, &mut out);
+        }
+
+        Ok(String::from_utf8(out).expect("every alphabet is ASCII"))
+    }
+
+    /// Decode `input` from `"base64"`, `"base64url"` or `"hex"`
+    #[wasm_bindgen]
+    pub fn decode(&self, input: &str, encoding: &str) -> Result<Uint8Array, WasmError> {
+        let bytes = Encoding::parse(encoding)?.decode(input)?;
+        Ok(Uint8Array::from(&bytes[..]))
+    }
+}
+
+fn decode_hex(input: &str) -> Result<Vec<u8>, WasmError> {
+    let digit = |offset: usize| {
+        let byte = input.as_bytes()[offset];
+        (byte as char)
+            .to_digit(16)
+            .map(|value| value as u8)
+            .ok_or_else(|| invalid_character(input, offset, Encoding::Hex))
+    };
+
+    let pairs = input.len() / 2;
+    let mut out = Vec::with_capacity(pairs);
+    for pair in 0..pairs {
+        out.push(digit(2 * pair)? << 4 | digit(2 * pair + 1)?);
+    }
+    if input.len() % 2 != 0 {
+        digit(input.len() - 1)?;
+        return Err(invalid(
+            Encoding::Hex,
+            input.len(),
+            "input ends in the middle of a byte",
+        ));
+    }
+    Ok(out)
+}
+
+fn decode_base64(input: &str, encoding: Encoding, table: &[u8; 256]) -> Result<Vec<u8>, WasmError> {
+    let bytes = input.as_bytes();
+    let padding = bytes
+        .iter()
+        .rev()
+        .take(2)
+        .take_while(|&&byte| byte == b'=')
+        .count();
+    let data = &bytes[..bytes.len() - padding];
+
+    let mut out = Vec::with_capacity(data.len() / 4 * 3 + 2);
+    let (mut bits, mut count) = (0u32, 0u32);
+    for (offset, &byte) in data.iter().enumerate() {
+        let value = table[byte as usize];
+        if value == INVALID {
+            return Err(invalid_character(input, offset, encoding));
+        }
+        bits = bits << 6 | value as u32;
+        count += 6;
+        if count >= 8 {
+            count -= 8;
+            out.push((bits >> count) as u8);
+        }
+    }
+
+    match data.len() % 4 {
+        1 => Err(invalid(
+            encoding,
+            data.len() - 1,
+            "a single trailing character cannot encode a byte",
+        )),
+        0 if padding > 0 => Err(invalid(encoding, data.len(), "unexpected padding")),
+        rest if padding > 0 && rest + padding != 4 => Err(invalid(
+            encoding,
+            bytes.len(),
+            "padding does not complete the final group",
+        )),
+        rest if padding == 0 && rest != 0 && encoding == Encoding::Base64 => {
+            Err(invalid(encoding, bytes.len(), "missing padding"))
+        }
+        _ => Ok(out),
+    }
+}
+
+fn invalid(encoding: Encoding, offset: usize, reason: impl Into<String>) -> WasmError {
+    WasmError::InvalidEncoding {
+        encoding: encoding.name().to_string(),
+        offset,
+        reason: reason.into(),
+    }
+}
+
+/// Report the character starting at byte `offset` of `input`
+fn invalid_character(input: &str, offset: usize, encoding: Encoding) -> WasmError {
+    // Offsets always land on the first byte of a character, since every
+    // valid character is ASCII and decoding stops at the first invalid one
+    let character = input[offset..].chars().next().unwrap_or_default();
+    invalid(
+        encoding,
+        offset,
+        format!("unexpected character {character:?}"),
+    )
+}
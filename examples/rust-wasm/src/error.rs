@@ -0,0 +1,194 @@
+use js_sys::{Object, Reflect};
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+/// Error returned by every processor.
+///
+/// Crossing into JavaScript it becomes an `Error` named `WasmError` whose
+/// `message` is the display text, with a stable `code` string (such as
+/// `"DIMENSION_MISMATCH"`) to branch on and the variant's fields as a plain
+/// `details` object. Messages may be reworded; codes and detail fields are
+/// part of the API.
+#[derive(Clone, Debug, PartialEq, Serialize, thiserror::Error)]
+#[serde(tag = "code", content = "details", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum WasmError {
+    /// An input's length or shape does not match what the call requires
+    #[error("{what}: expected {expected}, got {actual}")]
+    DimensionMismatch {
+        what: String,
+        expected: usize,
+        actual: usize,
+    },
+
+    /// An argument is outside the values the call accepts
+    #[error("Invalid {name}: {reason}")]
+    InvalidArgument { name: String, reason: String },
+
+    /// In strict mode, an element lies outside an operation's domain.
+    /// `slice` is set, and `index` still counts from the start of the
+    /// input, when the work ran in slices
+    #[error("{}Element {index} ({}): {reason}", in_slice(.slice), join(.values))]
+    OutOfDomain {
+        index: usize,
+        values: Vec<f64>,
+        reason: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        slice: Option<usize>,
+    },
+
+    /// Text is not valid in the named encoding; `offset` is the byte offset
+    /// of the first offending character, or the input length if it ends early
+    #[error("Invalid {encoding} at byte {offset}: {reason}")]
+    InvalidEncoding {
+        encoding: String,
+        offset: usize,
+        reason: String,
+    },
+
+    /// Text is not valid JSON; `line` and `column` are 1-based
+    #[error("Invalid JSON at line {line}, column {column}: {reason}")]
+    InvalidJson {
+        line: usize,
+        column: usize,
+        reason: String,
+    },
+
+    /// A strict-capacity buffer is too small for the batch
+    #[error("Batch of {requested} elements exceeds the strict capacity of {capacity}")]
+    CapacityExceeded { requested: usize, capacity: usize },
+
+    /// Authenticated data failed verification: it was altered, or the key
+    /// or associated data differs from what it was sealed with
+    #[error("Authentication failed: the data was altered or the key is wrong")]
+    AuthenticationFailed,
+
+    /// The object was used before it was ready
+    #[error("Module not initialized")]
+    NotInitialized,
+
+    /// A `CancellationToken` stopped the operation part way through
+    #[error("Cancelled after {completed} of {total} steps")]
+    Cancelled { completed: usize, total: usize },
+
+    /// A matrix has no inverse
+    #[error("Matrix is singular: {reason}")]
+    Singular { reason: String },
+
+    /// An iterative algorithm ran out of iterations
+    #[error("{algorithm} did not converge")]
+    NotConverged { algorithm: String },
+
+    /// A named operation, mode or option is not recognised
+    #[error("Unknown {kind} '{op}', expected one of: {supported}")]
+    UnsupportedOperation {
+        kind: String,
+        op: String,
+        supported: String,
+    },
+
+    /// Threads or memory could not be obtained
+    #[error("{reason}")]
+    ResourceUnavailable { reason: String },
+
+    /// A bug: the operation panicked. The processor remains usable, but
+    /// the call's result is lost
+    #[error("Internal error: {message}")]
+    Internal { message: String },
+
+    /// An exception raised by JavaScript, passed through unchanged
+    #[error("JavaScript exception: {0:?}")]
+    #[serde(skip)]
+    Js(JsValue),
+}
+
+impl WasmError {
+    /// Shorthand for [`WasmError::DimensionMismatch`]
+    pub(crate) fn dimension(what: impl Into<String>, expected: usize, actual: usize) -> WasmError {
+        WasmError::DimensionMismatch {
+            what: what.into(),
+            expected,
+            actual,
+        }
+    }
+
+    /// Shorthand for [`WasmError::InvalidArgument`]
+    pub(crate) fn invalid(name: impl Into<String>, reason: impl Into<String>) -> WasmError {
+        WasmError::InvalidArgument {
+            name: name.into(),
+            reason: reason.into(),
+        }
+    }
+
+    /// Shorthand for [`WasmError::UnsupportedOperation`]
+    pub(crate) fn unsupported(kind: &str, op: &str, supported: &str) -> WasmError {
+        WasmError::UnsupportedOperation {
+            kind: kind.into(),
+            op: op.into(),
+            supported: supported.into(),
+        }
+    }
+
+    /// The stable `code` this error carries into JavaScript
+    pub fn code(&self) -> &'static str {
+        match self {
+            WasmError::DimensionMismatch { .. } => "DIMENSION_MISMATCH",
+            WasmError::InvalidArgument { .. } => "INVALID_ARGUMENT",
+            WasmError::OutOfDomain { .. } => "OUT_OF_DOMAIN",
+            WasmError::InvalidEncoding { .. } => "INVALID_ENCODING",
+            WasmError::InvalidJson { .. } => "INVALID_JSON",
+            WasmError::CapacityExceeded { .. } => "CAPACITY_EXCEEDED",
+            WasmError::AuthenticationFailed => "AUTHENTICATION_FAILED",
+            WasmError::NotInitialized => "NOT_INITIALIZED",
+            WasmError::Cancelled { .. } => "CANCELLED",
+            WasmError::Singular { .. } => "SINGULAR",
+            WasmError::NotConverged { .. } => "NOT_CONVERGED",
+            WasmError::UnsupportedOperation { .. } => "UNSUPPORTED_OPERATION",
+            WasmError::ResourceUnavailable { .. } => "RESOURCE_UNAVAILABLE",
+            WasmError::Internal { .. } => "INTERNAL",
+            WasmError::Js(_) => "JS_EXCEPTION",
+        }
+    }
+}
+
+/// `values` as a comma-separated list
+fn join(values: &[f64]) -> String {
+    values
+        .iter()
+        .map(f64::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// A `"Slice n: "` prefix naming the slice an error came from, if any
+fn in_slice(slice: &Option<usize>) -> String {
+    slice.map_or_else(String::new, |slice| format!("Slice {slice}: "))
+}
+
+impl From<JsValue> for WasmError {
+    fn from(value: JsValue) -> WasmError {
+        WasmError::Js(value)
+    }
+}
+
+impl From<WasmError> for JsValue {
+    fn from(error: WasmError) -> JsValue {
+        if let WasmError::Js(value) = error {
+            return value;
+        }
+
+        let js_error = js_sys::Error::new(&error.to_string());
+        js_error.set_name("WasmError");
+        let serializer = serde_wasm_bindgen::Serializer::json_compatible();
+        let details = error
+            .serialize(&serializer)
+            .and_then(|tagged| Reflect::get(&tagged, &"details".into()).map_err(Into::into))
+            .ok()
+            .filter(|details| !details.is_undefined())
+            .unwrap_or_else(|| Object::new().into());
+
+        // Setting properties on a fresh Error cannot fail
+        let _ = Reflect::set(&js_error, &"code".into(), &error.code().into());
+        let _ = Reflect::set(&js_error, &"details".into(), &details);
+        js_error.into()
+    }
+}
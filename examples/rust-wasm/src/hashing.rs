@@ -0,0 +1,129 @@
+use std::fmt::Write;
+
+use js_sys::Uint8Array;
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use wasm_bindgen::prelude::*;
+use xxhash_rust::xxh64::Xxh64;
+
+use crate::{stream::StreamTransform, WasmError, WasmModule};
+
+/// An in-progress digest
+pub(crate) enum Hasher {
+    Sha256(Sha256),
+    Sha1(Sha1),
+    Crc32(crc32fast::Hasher),
+    /// XXH64 with seed 0
+    Xxh64(Xxh64),
+}
+
+impl Hasher {
+    const SUPPORTED: &'static str = "sha256, sha1, crc32, xxh64";
+
+    pub(crate) fn parse(name: &str) -> Result<Hasher, WasmError> {
+        match name.to_ascii_lowercase().as_str() {
+            "sha256" => Ok(Hasher::Sha256(Sha256::new())),
+            "sha1" => Ok(Hasher::Sha1(Sha1::new())),
+            "crc32" => Ok(Hasher::Crc32(crc32fast::Hasher::new())),
+            "xxh64" => Ok(Hasher::Xxh64(Xxh64::new(0))),
+            _ => Err(WasmError::unsupported(
+                "hash algorithm",
+                name,
+                Self::SUPPORTED,
+            )),
+        }
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        match self {
+            Hasher::Sha256(hasher) => hasher.update(data),
+            Hasher::Sha1(hasher) => hasher.update(data),
+            Hasher::Crc32(hasher) => hasher.update(data),
+            Hasher::Xxh64(hasher) => hasher.update(data),
+        }
+    }
+
+    /// The digest bytes; checksums are big-endian, as `crc32` and `xxhsum`
+    /// print them
+    fn digest(self) -> Vec<u8> {
+        match self {
+            Hasher::Sha256(hasher) => hasher.finalize().to_vec(),
+            Hasher::Sha1(hasher) => hasher.finalize().to_vec(),
+            Hasher::Crc32(hasher) => hasher.finalize().to_be_bytes().to_vec(),
+            Hasher::Xxh64(hasher) => hasher.digest().to_be_bytes().to_vec(),
+        }
+    }
+
+    /// The digest as lowercase hex
+    fn finalize(self) -> String {
+        hex(&self.digest())
+    }
+}
+
+/// Hashing as a stream transform: chunks produce no output, and finishing
+/// yields the raw digest bytes
+impl StreamTransform for Hasher {
+    fn push(&mut self, chunk: &[u8]) -> Result<Vec<u8>, WasmError> {
+        self.update(chunk);
+        Ok(Vec::new())
+    }
+
+    fn finish(self: Box<Self>) -> Result<Vec<u8>, WasmError> {
+        Ok(self.digest())
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::new(), |mut hex, byte| {
+        let _ = write!(hex, "{byte:02x}");
+        hex
+    })
+}
+
+/// Digests and checksums, returned as lowercase hex.
+///
+/// `hash` digests a whole buffer. For data read in chunks, such as a
+/// `File` stream, `hash_init` opens a handle that `hash_update` feeds and
+/// `hash_finalize` closes, so the whole input never has to be in memory.
+#[wasm_bindgen]
+impl WasmModule {
+    /// Digest `input` with `"sha256"`, `"sha1"`, `"crc32"` or `"xxh64"`
+    #[wasm_bindgen]
+    pub fn hash(&self, input: &Uint8Array, algorithm: &str) -> Result<String, WasmError> {
+        let mut hasher = Hasher::parse(algorithm)?;
+        hasher.update(&input.to_vec());
+        Ok(hasher.finalize())
+    }
+
+    /// Start a streaming digest, returning its handle
+    #[wasm_bindgen]
+    pub fn hash_init(&mut self, algorithm: &str) -> Result<u32, WasmError> {
+        let hasher = Hasher::parse(algorithm)?;
+        // Handles start at 1 so that 0 is never valid
+        self.next_hash_handle = self.next_hash_handle.wrapping_add(1).max(1);
+        self.hashers.insert(self.next_hash_handle, hasher);
+        Ok(self.next_hash_handle)
+    }
+
+    /// Feed the next chunk to an open streaming digest
+    #[wasm_bindgen]
+    pub fn hash_update(&mut self, handle: u32, chunk: &Uint8Array) -> Result<(), WasmError> {
+        let hasher = self.hashers.get_mut(&handle).ok_or_else(|| stale(handle))?;
+        hasher.update(&chunk.to_vec());
+        Ok(())
+    }
+
+    /// Finish a streaming digest and close its handle
+    #[wasm_bindgen]
+    pub fn hash_finalize(&mut self, handle: u32) -> Result<String, WasmError> {
+        let hasher = self.hashers.remove(&handle).ok_or_else(|| stale(handle))?;
+        Ok(hasher.finalize())
+    }
+}
+
+fn stale(handle: u32) -> WasmError {
+    WasmError::invalid(
+        "hash handle",
+        format!("{handle} is not open; it may already have been finalized"),
+    )
+}
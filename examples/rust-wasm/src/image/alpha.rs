@@ -0,0 +1,61 @@
+use rayon::prelude::*;
+use wasm_bindgen::prelude::*;
+
+use super::WasmImageProcessor;
+use crate::{install, WasmError};
+
+#[wasm_bindgen]
+impl WasmImageProcessor {
+    /// Convert straight alpha to premultiplied alpha by scaling RGB by
+    /// `alpha / 255`
+    #[wasm_bindgen]
+    pub fn premultiply_alpha(&mut self, rgba_data: &[u8]) -> Result<Vec<u8>, WasmError> {
+        let timing = self.profile("premultiply_alpha", rgba_data.len());
+        self.load(rgba_data);
+
+        let buffer = &mut self.buffer;
+        install(&self.runtime, || {
+            buffer.par_chunks_exact_mut(4).for_each(|pixel| {
+                let alpha = pixel[3] as f32 / 255.0;
+                for value in pixel.iter_mut().take(3) {
+                    *value = (*value as f32 * alpha).round() as u8;
+                }
+            });
+        })?;
+
+        timing.finish(Ok(self.buffer.clone()))
+    }
+
+    /// Convert premultiplied alpha back to straight alpha.
+    ///
+    /// Fully transparent pixels carry no colour and become `[0, 0, 0, 0]`.
+    /// Premultiplying loses precision as alpha drops, so only opaque pixels
+    /// survive a round trip exactly.
+    #[wasm_bindgen]
+    pub fn unpremultiply_alpha(&mut self, rgba_data: &[u8]) -> Result<Vec<u8>, WasmError> {
+        let timing = self.profile("unpremultiply_alpha", rgba_data.len());
+        if rgba_data.len() % 4 != 0 {
+            return Err(WasmError::invalid(
+                "pixel data",
+                format!("length must be a multiple of 4, got {}", rgba_data.len()),
+            ));
+        }
+        self.load(rgba_data);
+
+        let buffer = &mut self.buffer;
+        install(&self.runtime, || {
+            buffer.par_chunks_exact_mut(4).for_each(|pixel| {
+                if pixel[3] == 0 {
+                    pixel[..3].fill(0);
+                    return;
+                }
+                let scale = 255.0 / pixel[3] as f32;
+                for value in pixel.iter_mut().take(3) {
+                    *value = (*value as f32 * scale).round().min(255.0) as u8;
+                }
+            });
+        })?;
+
+        timing.finish(Ok(self.buffer.clone()))
+    }
+}
@@ -0,0 +1,101 @@
+use rayon::prelude::*;
+use wasm_bindgen::prelude::*;
+
+use super::WasmImageProcessor;
+use crate::{install, WasmError};
+
+#[wasm_bindgen]
+impl WasmImageProcessor {
+    /// One channel (0 = R, 1 = G, 2 = B, 3 = A) as a single-byte-per-pixel plane
+    #[wasm_bindgen]
+    pub fn extract_channel(&mut self, rgba: &[u8], channel: u8) -> Result<Vec<u8>, WasmError> {
+        let timing = self.profile("extract_channel", rgba.len());
+        if channel > 3 {
+            return Err(WasmError::invalid(
+                "channel index",
+                format!("must be 0-3, got {channel}"),
+            ));
+        }
+
+        timing.finish(install(&self.runtime, || {
+            rgba.par_chunks_exact(4)
+                .map(|pixel| pixel[channel as usize])
+                .collect()
+        }))
+    }
+
+    /// Interleave separate planes into RGBA; alpha defaults to opaque
+    #[wasm_bindgen]
+    pub fn merge_channels(
+        &mut self,
+        r: &[u8],
+        g: &[u8],
+        b: &[u8],
+        a: Option<Vec<u8>>,
+    ) -> Result<Vec<u8>, WasmError> {
+        let timing = self.profile("merge_channels", r.len());
+        let lengths_match = g.len() == r.len()
+            && b.len() == r.len()
+            && a.as_ref().map_or(true, |a| a.len() == r.len());
+        if !lengths_match {
+            return Err(WasmError::invalid(
+                "channel planes",
+                "all planes must have the same length",
+            ));
+        }
+
+        self.buffer.clear();
+        self.buffer.resize(r.len() * 4, 255);
+
+        let buffer = &mut self.buffer;
+        install(&self.runtime, || {
+            buffer
+                .par_chunks_exact_mut(4)
+                .enumerate()
+                .for_each(|(i, pixel)| {
+                    pixel[0] = r[i];
+                    pixel[1] = g[i];
+                    pixel[2] = b[i];
+                    if let Some(a) = &a {
+                        pixel[3] = a[i];
+                    }
+                });
+        })?;
+
+        timing.finish(Ok(self.buffer.clone()))
+    }
+
+    /// Reorder channels so output channel `i` takes input channel `mapping[i]`;
+    /// `[2, 1, 0, 3]` converts BGRA to RGBA and back
+    #[wasm_bindgen]
+    pub fn swap_channels(&mut self, rgba: &[u8], mapping: &[u8]) -> Result<Vec<u8>, WasmError> {
+        let timing = self.profile("swap_channels", rgba.len());
+        let mut sorted = mapping.to_vec();
+        sorted.sort_unstable();
+        if sorted != [0, 1, 2, 3] {
+            return Err(WasmError::invalid(
+                "channel mapping",
+                format!("must be a permutation of [0, 1, 2, 3], got {mapping:?}"),
+            ));
+        }
+        let mapping = [
+            mapping[0] as usize,
+            mapping[1] as usize,
+            mapping[2] as usize,
+            mapping[3] as usize,
+        ];
+        self.load(rgba);
+
+        let buffer = &mut self.buffer;
+        install(&self.runtime, || {
+            buffer.par_chunks_exact_mut(4).for_each(|pixel| {
+                let source = [pixel[0], pixel[1], pixel[2], pixel[3]];
+                for (value, &channel) in pixel.iter_mut().zip(&mapping) {
+                    *value = source[channel];
+                }
+            });
+        })?;
+
+        timing.finish(Ok(self.buffer.clone()))
+    }
+}
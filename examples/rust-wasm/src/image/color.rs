@@ -0,0 +1,229 @@
+use rayon::prelude::*;
+use wasm_bindgen::prelude::*;
+
+use super::WasmImageProcessor;
+use crate::{install, WasmError};
+
+/// Colour spaces understood by the conversion routines
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ColorSpace {
+    Rgb,
+    Hsl,
+    Hsv,
+    YCbCr,
+}
+
+impl ColorSpace {
+    const SUPPORTED: &'static str = "rgb, hsl, hsv, ycbcr";
+
+    fn parse(name: &str) -> Result<ColorSpace, WasmError> {
+        match name.to_ascii_lowercase().as_str() {
+            "rgb" => Ok(ColorSpace::Rgb),
+            "hsl" => Ok(ColorSpace::Hsl),
+            "hsv" => Ok(ColorSpace::Hsv),
+            "ycbcr" => Ok(ColorSpace::YCbCr),
+            _ => Err(WasmError::unsupported(
+                "colour space",
+                name,
+                Self::SUPPORTED,
+            )),
+        }
+    }
+
+    /// Convert RGB in `0..=255` into this space's natural units.
+    ///
+    /// HSL/HSV use hue in degrees and saturation/lightness/value in `0..=1`;
+    /// YCbCr uses the full-range JPEG encoding in `0..=255`.
+    pub(crate) fn encode(self, [r, g, b]: [f32; 3]) -> [f32; 3] {
+        match self {
+            ColorSpace::Rgb => [r, g, b],
+            ColorSpace::Hsl => {
+                let (hue, max, min) = hue_max_min(r / 255.0, g / 255.0, b / 255.0);
+                let lightness = (max + min) / 2.0;
+                let delta = max - min;
+                let saturation = if delta == 0.0 {
+                    0.0
+                } else {
+                    delta / (1.0 - (2.0 * lightness - 1.0).abs())
+                };
+                [hue, saturation, lightness]
+            }
+            ColorSpace::Hsv => {
+                let (hue, max, min) = hue_max_min(r / 255.0, g / 255.0, b / 255.0);
+                let saturation = if max == 0.0 { 0.0 } else { (max - min) / max };
+                [hue, saturation, max]
+            }
+            ColorSpace::YCbCr => [
+                0.299 * r + 0.587 * g + 0.114 * b,
+                128.0 - 0.168_736 * r - 0.331_264 * g + 0.5 * b,
+                128.0 + 0.5 * r - 0.418_688 * g - 0.081_312 * b,
+            ],
+        }
+    }
+
+    /// Convert from this space's natural units back to RGB in `0..=255`
+    pub(crate) fn decode(self, [a, b, c]: [f32; 3]) -> [f32; 3] {
+        match self {
+            ColorSpace::Rgb => [a, b, c],
+            ColorSpace::Hsl => {
+                let chroma = (1.0 - (2.0 * c - 1.0).abs()) * b;
+                hue_to_rgb(a, chroma, c - chroma / 2.0)
+            }
+            ColorSpace::Hsv => {
+                let chroma = c * b;
+                hue_to_rgb(a, chroma, c - chroma)
+            }
+            ColorSpace::YCbCr => [
+                a + 1.402 * (c - 128.0),
+                a - 0.344_136 * (b - 128.0) - 0.714_136 * (c - 128.0),
+                a + 1.772 * (b - 128.0),
+            ],
+        }
+    }
+
+    /// Scale natural units to the `0..=255` byte encoding
+    fn scale_to_bytes(self, [a, b, c]: [f32; 3]) -> [f32; 3] {
+        match self {
+            ColorSpace::Hsl | ColorSpace::Hsv => [a / 360.0 * 255.0, b * 255.0, c * 255.0],
+            ColorSpace::Rgb | ColorSpace::YCbCr => [a, b, c],
+        }
+    }
+
+    /// Inverse of [`ColorSpace::scale_to_bytes`]
+    fn scale_from_bytes(self, [a, b, c]: [f32; 3]) -> [f32; 3] {
+        match self {
+            ColorSpace::Hsl | ColorSpace::Hsv => [a / 255.0 * 360.0, b / 255.0, c / 255.0],
+            ColorSpace::Rgb | ColorSpace::YCbCr => [a, b, c],
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl WasmImageProcessor {
+    /// Convert RGBA-layout pixels between colour spaces using byte encodings.
+    ///
+    /// Hue is stored as `degrees / 360 * 255`, which loses precision; use
+    /// [`WasmImageProcessor::rgba_to_colorspace_f32`] when hue accuracy matters.
+    #[wasm_bindgen]
+    pub fn convert_colorspace(
+        &mut self,
+        rgba: &[u8],
+        from: &str,
+        to: &str,
+    ) -> Result<Vec<u8>, WasmError> {
+        let timing = self.profile("convert_colorspace", rgba.len());
+        let from = ColorSpace::parse(from)?;
+        let to = ColorSpace::parse(to)?;
+        self.load(rgba);
+
+        let buffer = &mut self.buffer;
+        install(&self.runtime, || {
+            buffer.par_chunks_exact_mut(4).for_each(|pixel| {
+                let source = [pixel[0] as f32, pixel[1] as f32, pixel[2] as f32];
+                let rgb = from.decode(from.scale_from_bytes(source));
+                let converted = to.scale_to_bytes(to.encode(rgb));
+                for (value, channel) in pixel.iter_mut().zip(converted) {
+                    *value = channel.round().clamp(0.0, 255.0) as u8;
+                }
+            });
+        })?;
+
+        timing.finish(Ok(self.buffer.clone()))
+    }
+
+    /// Convert RGBA pixels into `space`, returning four floats per pixel.
+    ///
+    /// Channels use the space's natural units (hue in degrees, saturation,
+    /// lightness and value in `0..=1`); alpha is passed through unchanged.
+    #[wasm_bindgen]
+    pub fn rgba_to_colorspace_f32(&self, rgba: &[u8], space: &str) -> Result<Vec<f32>, WasmError> {
+        let timing = self.profile("rgba_to_colorspace_f32", rgba.len());
+        let space = ColorSpace::parse(space)?;
+
+        timing.finish(install(&self.runtime, || {
+            rgba.par_chunks_exact(4)
+                .flat_map_iter(|pixel| {
+                    let [a, b, c] =
+                        space.encode([pixel[0] as f32, pixel[1] as f32, pixel[2] as f32]);
+                    [a, b, c, pixel[3] as f32]
+                })
+                .collect()
+        }))
+    }
+
+    /// Convert four-float pixels in `space` back to RGBA bytes
+    #[wasm_bindgen]
+    pub fn colorspace_f32_to_rgba(
+        &mut self,
+        pixels: &[f32],
+        space: &str,
+    ) -> Result<Vec<u8>, WasmError> {
+        let timing = self.profile("colorspace_f32_to_rgba", pixels.len());
+        let space = ColorSpace::parse(space)?;
+        if pixels.len() % 4 != 0 {
+            return Err(WasmError::invalid(
+                "pixel data",
+                format!("length must be a multiple of 4, got {}", pixels.len()),
+            ));
+        }
+
+        self.buffer.clear();
+        self.buffer.resize(pixels.len(), 0);
+
+        let buffer = &mut self.buffer;
+        install(&self.runtime, || {
+            buffer
+                .par_chunks_exact_mut(4)
+                .zip(pixels.par_chunks_exact(4))
+                .for_each(|(pixel, source)| {
+                    let rgb = space.decode([source[0], source[1], source[2]]);
+                    for (value, channel) in pixel.iter_mut().zip(rgb) {
+                        *value = channel.round().clamp(0.0, 255.0) as u8;
+                    }
+                    pixel[3] = source[3].round().clamp(0.0, 255.0) as u8;
+                });
+        })?;
+
+        timing.finish(Ok(self.buffer.clone()))
+    }
+}
+
+/// Hue in degrees plus the max and min of normalised RGB components
+fn hue_max_min(r: f32, g: f32, b: f32) -> (f32, f32, f32) {
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let delta = max - min;
+
+    let hue = if delta == 0.0 {
+        0.0
+    } else if max == r {
+        60.0 * ((g - b) / delta).rem_euclid(6.0)
+    } else if max == g {
+        60.0 * ((b - r) / delta + 2.0)
+    } else {
+        60.0 * ((r - g) / delta + 4.0)
+    };
+
+    (hue, max, min)
+}
+
+/// Shared HSL/HSV reconstruction from hue, chroma and the lightness offset
+fn hue_to_rgb(hue: f32, chroma: f32, offset: f32) -> [f32; 3] {
+    let sector = hue.rem_euclid(360.0) / 60.0;
+    let x = chroma * (1.0 - (sector % 2.0 - 1.0).abs());
+
+    let (r, g, b) = match sector as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+
+    [
+        (r + offset) * 255.0,
+        (g + offset) * 255.0,
+        (b + offset) * 255.0,
+    ]
+}
@@ -0,0 +1,170 @@
+use js_sys::{Int32Array, Object, Reflect, Uint32Array};
+use rayon::prelude::*;
+use wasm_bindgen::prelude::*;
+
+use super::WasmImageProcessor;
+use crate::{install, WasmError};
+
+#[wasm_bindgen]
+impl WasmImageProcessor {
+    /// Label connected blobs in a binary mask (one byte per pixel, non-zero is
+    /// foreground).
+    ///
+    /// Returns `{ num_labels, labels, bboxes }`: `labels` is a `Uint32Array`
+    /// with 0 for background and `1..=num_labels` numbered in raster order,
+    /// and `bboxes` holds inclusive `[min_x, min_y, max_x, max_y]` per label.
+    /// Pixels touch along edges, or also at corners when `eight_connected`.
+    #[wasm_bindgen]
+    pub fn connected_components(
+        &mut self,
+        mask: &[u8],
+        width: u32,
+        height: u32,
+        eight_connected: bool,
+    ) -> Result<JsValue, WasmError> {
+        let timing = self.profile("connected_components", mask.len());
+        let expected = width as usize * height as usize;
+        if mask.len() != expected {
+            return Err(WasmError::dimension(
+                format!("Bytes for a {width}x{height} mask"),
+                expected,
+                mask.len(),
+            ));
+        }
+
+        let (num_labels, labels, bboxes) = install(&self.runtime, || {
+            label_components(mask, width as usize, height as usize, eight_connected)
+        })?;
+
+        let result = Object::new();
+        Reflect::set(&result, &"num_labels".into(), &num_labels.into())?;
+        Reflect::set(&result, &"labels".into(), &Uint32Array::from(&labels[..]))?;
+        Reflect::set(&result, &"bboxes".into(), &Int32Array::from(&bboxes[..]))?;
+        timing.finish(Ok(result.into()))
+    }
+}
+
+/// Two-pass union-find labeling.
+///
+/// Row bands are labelled independently in parallel, using the pixel index
+/// of each band-local root as a globally unique provisional label. A
+/// sequential pass then unions labels across band boundaries and renumbers
+/// them compactly while collecting bounding boxes.
+fn label_components(
+    mask: &[u8],
+    width: usize,
+    height: usize,
+    eight_connected: bool,
+) -> (u32, Vec<u32>, Vec<i32>) {
+    let mut labels = vec![0u32; mask.len()];
+    if mask.is_empty() {
+        return (0, labels, Vec::new());
+    }
+
+    let workers = rayon::current_num_threads();
+    let band_rows = (height + workers - 1) / workers;
+    let band_len = band_rows * width;
+
+    labels
+        .par_chunks_mut(band_len)
+        .zip(mask.par_chunks(band_len))
+        .enumerate()
+        .for_each(|(band, (labels, mask))| {
+            let offset = band * band_len;
+            let mut parent: Vec<u32> = (0..mask.len() as u32).collect();
+
+            for i in 0..mask.len() {
+                if mask[i] == 0 {
+                    continue;
+                }
+                let (x, y) = (i % width, i / width);
+                for neighbour in preceding_neighbours(x, y, width, eight_connected) {
+                    if mask[neighbour] != 0 {
+                        union(&mut parent, i as u32, neighbour as u32);
+                    }
+                }
+            }
+
+            for i in 0..mask.len() {
+                if mask[i] != 0 {
+                    labels[i] = (offset + find(&mut parent, i as u32) as usize) as u32 + 1;
+                }
+            }
+        });
+
+    // Provisional labels are pixel index + 1, so one slot per pixel suffices
+    let mut parent: Vec<u32> = (0..=mask.len() as u32).collect();
+    for row in (band_rows..height).step_by(band_rows) {
+        for x in 0..width {
+            let i = row * width + x;
+            if mask[i] == 0 {
+                continue;
+            }
+            // Only pixels in the row above can belong to the previous band
+            let reach = usize::from(eight_connected);
+            for above_x in x.saturating_sub(reach)..=(x + reach).min(width - 1) {
+                let above = i - width - x + above_x;
+                if mask[above] != 0 {
+                    union(&mut parent, labels[i], labels[above]);
+                }
+            }
+        }
+    }
+
+    let mut compact = vec![0u32; mask.len() + 1];
+    let mut bboxes: Vec<i32> = Vec::new();
+    let mut num_labels = 0u32;
+    for (i, label) in labels.iter_mut().enumerate() {
+        if *label == 0 {
+            continue;
+        }
+        let root = find(&mut parent, *label) as usize;
+        if compact[root] == 0 {
+            num_labels += 1;
+            compact[root] = num_labels;
+            bboxes.extend_from_slice(&[i32::MAX, i32::MAX, i32::MIN, i32::MIN]);
+        }
+        *label = compact[root];
+
+        let (x, y) = ((i % width) as i32, (i / width) as i32);
+        let bbox = &mut bboxes[(*label as usize - 1) * 4..*label as usize * 4];
+        bbox[0] = bbox[0].min(x);
+        bbox[1] = bbox[1].min(y);
+        bbox[2] = bbox[2].max(x);
+        bbox[3] = bbox[3].max(y);
+    }
+
+    (num_labels, labels, bboxes)
+}
+
+/// Indices of the already-visited neighbours of `(x, y)` in a raster scan
+fn preceding_neighbours(
+    x: usize,
+    y: usize,
+    width: usize,
+    eight_connected: bool,
+) -> impl Iterator<Item = usize> {
+    let i = y * width + x;
+    let left = (x > 0).then(|| i - 1);
+    let up = (y > 0).then(|| i - width);
+    let up_left = (eight_connected && x > 0 && y > 0).then(|| i - width - 1);
+    let up_right = (eight_connected && x + 1 < width && y > 0).then(|| i - width + 1);
+    [left, up, up_left, up_right].into_iter().flatten()
+}
+
+/// Root of `node`, halving the path on the way up
+fn find(parent: &mut [u32], mut node: u32) -> u32 {
+    while parent[node as usize] != node {
+        parent[node as usize] = parent[parent[node as usize] as usize];
+        node = parent[node as usize];
+    }
+    node
+}
+
+/// Merge two sets, keeping the smaller (earlier in raster order) root
+fn union(parent: &mut [u32], a: u32, b: u32) {
+    let (a, b) = (find(parent, a), find(parent, b));
+    if a != b {
+        parent[a.max(b) as usize] = a.min(b);
+    }
+}
@@ -0,0 +1,181 @@
+use rayon::prelude::*;
+use wasm_bindgen::prelude::*;
+
+use super::{check_dimensions, WasmImageProcessor};
+use crate::{install, WasmError};
+
+/// 8x8 Bayer threshold matrix used for ordered dithering
+const BAYER_8X8: [[u8; 8]; 8] = [
+    [0, 32, 8, 40, 2, 34, 10, 42],
+    [48, 16, 56, 24, 50, 18, 58, 26],
+    [12, 44, 4, 36, 14, 46, 6, 38],
+    [60, 28, 52, 20, 62, 30, 54, 22],
+    [3, 35, 11, 43, 1, 33, 9, 41],
+    [51, 19, 59, 27, 49, 17, 57, 25],
+    [15, 47, 7, 39, 13, 45, 5, 37],
+    [63, 31, 55, 23, 61, 29, 53, 21],
+];
+
+/// Bits per channel of the palette lookup table used by Floyd-Steinberg
+const PALETTE_LUT_BITS: u32 = 5;
+
+#[wasm_bindgen]
+impl WasmImageProcessor {
+    /// Reduce the image to `palette` (flat RGB triples) with Floyd-Steinberg
+    /// error diffusion.
+    ///
+    /// Error propagation forces a sequential scan, so the parallel part is the
+    /// nearest-colour lookup table built up front.
+    #[wasm_bindgen]
+    pub fn dither_floyd_steinberg(
+        &mut self,
+        rgba_data: &[u8],
+        width: u32,
+        height: u32,
+        palette: &[u8],
+    ) -> Result<Vec<u8>, WasmError> {
+        let timing = self.profile("dither_floyd_steinberg", rgba_data.len());
+        check_dimensions(rgba_data, width, height)?;
+        if palette.is_empty() || palette.len() % 3 != 0 {
+            return Err(WasmError::invalid(
+                "palette",
+                "must be a non-empty list of RGB triples",
+            ));
+        }
+        if palette.len() / 3 > 256 {
+            return Err(WasmError::invalid("palette", "cannot exceed 256 colours"));
+        }
+
+        let lut = install(&self.runtime, || build_palette_lut(palette))?;
+        let width = width as usize;
+        let height = height as usize;
+
+        // Working copy of the RGB channels that accumulates diffused error
+        let mut working: Vec<f32> = rgba_data
+            .chunks_exact(4)
+            .flat_map(|pixel| [pixel[0] as f32, pixel[1] as f32, pixel[2] as f32])
+            .collect();
+
+        self.load(rgba_data);
+        for y in 0..height {
+            for x in 0..width {
+                let index = y * width + x;
+                let old = [
+                    working[index * 3],
+                    working[index * 3 + 1],
+                    working[index * 3 + 2],
+                ];
+
+                let colour = lut[lut_index(old)] as usize;
+                let new = &palette[colour * 3..colour * 3 + 3];
+                self.buffer[index * 4..index * 4 + 3].copy_from_slice(new);
+
+                let error = [
+                    old[0] - new[0] as f32,
+                    old[1] - new[1] as f32,
+                    old[2] - new[2] as f32,
+                ];
+
+                let mut diffuse = |dx: isize, dy: usize, weight: f32| {
+                    let nx = x as isize + dx;
+                    let ny = y + dy;
+                    if nx < 0 || nx as usize >= width || ny >= height {
+                        return;
+                    }
+                    let target = (ny * width + nx as usize) * 3;
+                    for channel in 0..3 {
+                        working[target + channel] += error[channel] * weight;
+                    }
+                };
+
+                diffuse(1, 0, 7.0 / 16.0);
+                diffuse(-1, 1, 3.0 / 16.0);
+                diffuse(0, 1, 5.0 / 16.0);
+                diffuse(1, 1, 1.0 / 16.0);
+            }
+        }
+
+        timing.finish(Ok(self.buffer.clone()))
+    }
+
+    /// Quantise each RGB channel to `bits` bits using an 8x8 Bayer matrix.
+    ///
+    /// Every pixel is independent, so the whole image is processed in parallel.
+    #[wasm_bindgen]
+    pub fn dither_ordered(
+        &mut self,
+        rgba_data: &[u8],
+        width: u32,
+        height: u32,
+        bits: u8,
+    ) -> Result<Vec<u8>, WasmError> {
+        let timing = self.profile("dither_ordered", rgba_data.len());
+        check_dimensions(rgba_data, width, height)?;
+        if !(1..=8).contains(&bits) {
+            return Err(WasmError::invalid(
+                "bits per channel",
+                format!("must be between 1 and 8, got {bits}"),
+            ));
+        }
+
+        self.load(rgba_data);
+
+        let width = width as usize;
+        let levels = ((1u32 << bits) - 1) as f32;
+        let step = 255.0 / levels;
+        let buffer = &mut self.buffer;
+        install(&self.runtime, || {
+            buffer
+                .par_chunks_exact_mut(4)
+                .enumerate()
+                .for_each(|(index, pixel)| {
+                    let (x, y) = (index % width, index / width);
+                    let threshold = (BAYER_8X8[y % 8][x % 8] as f32 + 0.5) / 64.0 - 0.5;
+
+                    for value in pixel.iter_mut().take(3) {
+                        let level = (*value as f32 / step + threshold)
+                            .round()
+                            .clamp(0.0, levels);
+                        *value = (level * step).round() as u8;
+                    }
+                });
+        })?;
+
+        timing.finish(Ok(self.buffer.clone()))
+    }
+}
+
+/// Precompute the nearest palette entry for every cell of a quantised RGB cube
+fn build_palette_lut(palette: &[u8]) -> Vec<u8> {
+    let cells = 1usize << PALETTE_LUT_BITS;
+    let shift = 8 - PALETTE_LUT_BITS;
+    let centre = (1u32 << shift) / 2;
+
+    (0..cells * cells * cells)
+        .into_par_iter()
+        .map(|cell| {
+            let r = ((cell / (cells * cells)) as u32) << shift | centre;
+            let g = ((cell / cells % cells) as u32) << shift | centre;
+            let b = ((cell % cells) as u32) << shift | centre;
+
+            palette
+                .chunks_exact(3)
+                .enumerate()
+                .min_by_key(|(_, entry)| {
+                    let dr = r as i32 - entry[0] as i32;
+                    let dg = g as i32 - entry[1] as i32;
+                    let db = b as i32 - entry[2] as i32;
+                    dr * dr + dg * dg + db * db
+                })
+                .map_or(0, |(index, _)| index as u8)
+        })
+        .collect()
+}
+
+/// Map an error-adjusted RGB value to its lookup table cell
+fn lut_index(rgb: [f32; 3]) -> usize {
+    let cells = 1usize << PALETTE_LUT_BITS;
+    let shift = 8 - PALETTE_LUT_BITS;
+    let [r, g, b] = rgb.map(|c| (c.round().clamp(0.0, 255.0) as usize) >> shift);
+    (r * cells + g) * cells + b
+}
@@ -0,0 +1,273 @@
+use rayon::prelude::*;
+use wasm_bindgen::prelude::*;
+
+use super::{check_dimensions, luminance, WasmImageProcessor};
+use crate::{install, pool::BufferPool, WasmError};
+
+/// Binomial approximation of a Gaussian with sigma ≈ 1
+const GAUSSIAN_5: [f32; 5] = [1.0 / 16.0, 4.0 / 16.0, 6.0 / 16.0, 4.0 / 16.0, 1.0 / 16.0];
+
+/// Hysteresis classes
+const NONE: u8 = 0;
+const WEAK: u8 = 1;
+const EDGE: u8 = 2;
+
+#[wasm_bindgen]
+impl WasmImageProcessor {
+    /// Sobel gradient magnitude of the luminance, clamped to `0..=255` and
+    /// written to RGB. Alpha is preserved and borders repeat the edge pixels.
+    #[wasm_bindgen]
+    pub fn sobel_edges(
+        &mut self,
+        rgba: &[u8],
+        width: u32,
+        height: u32,
+    ) -> Result<Vec<u8>, WasmError> {
+        let timing = self.profile("sobel_edges", rgba.len());
+        check_dimensions(rgba, width, height)?;
+        let (width, height) = (width as usize, height as usize);
+        self.load(rgba);
+        if rgba.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let buffer = &mut self.buffer;
+        let pool = self.runtime.buffer_pool();
+        install(&self.runtime, || {
+            let plane = Plane::luminance(pool, rgba, width, height);
+            buffer
+                .par_chunks_exact_mut(width * 4)
+                .enumerate()
+                .for_each(|(y, row)| {
+                    for (x, pixel) in row.chunks_exact_mut(4).enumerate() {
+                        let (gx, gy) = plane.sobel(x, y);
+                        pixel[..3].fill(gx.hypot(gy).round().min(255.0) as u8);
+                    }
+                });
+            plane.recycle(pool);
+        })?;
+
+        timing.finish(Ok(self.buffer.clone()))
+    }
+
+    /// Canny edge detection: Gaussian smoothing, Sobel gradients, non-maximum
+    /// suppression and double-threshold hysteresis.
+    ///
+    /// `low` and `high` are gradient magnitudes on the same scale as
+    /// [`WasmImageProcessor::sobel_edges`] before clamping. Edge pixels become
+    /// white and everything else black; alpha is preserved.
+    #[wasm_bindgen]
+    pub fn canny_edges(
+        &mut self,
+        rgba: &[u8],
+        width: u32,
+        height: u32,
+        low: f32,
+        high: f32,
+    ) -> Result<Vec<u8>, WasmError> {
+        let timing = self.profile("canny_edges", rgba.len());
+        check_dimensions(rgba, width, height)?;
+        if !(0.0 < low && low < high) {
+            return Err(WasmError::invalid(
+                "Canny thresholds",
+                format!("must satisfy 0 < low < high, got low {low} and high {high}"),
+            ));
+        }
+        let (width, height) = (width as usize, height as usize);
+        self.load(rgba);
+        if rgba.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let buffer = &mut self.buffer;
+        let pool = self.runtime.buffer_pool();
+        install(&self.runtime, || {
+            let luminance = Plane::luminance(pool, rgba, width, height);
+            let smoothed = luminance.gaussian(pool);
+            luminance.recycle(pool);
+            let classes = suppress_and_classify(pool, &smoothed, low, high);
+            smoothed.recycle(pool);
+            let edges = hysteresis(classes, width, height);
+
+            buffer
+                .par_chunks_exact_mut(4)
+                .zip(edges.par_iter())
+                .for_each(|(pixel, &class)| {
+                    pixel[..3].fill(if class == EDGE { 255 } else { 0 });
+                });
+            pool.recycle(edges);
+        })?;
+
+        timing.finish(Ok(self.buffer.clone()))
+    }
+}
+
+/// Single-channel `f32` image with clamp-to-edge sampling
+struct Plane {
+    values: Vec<f32>,
+    width: usize,
+    height: usize,
+}
+
+impl Plane {
+    fn luminance(pool: &BufferPool, rgba: &[u8], width: usize, height: usize) -> Plane {
+        let mut values = pool.take(width * height);
+        values
+            .par_iter_mut()
+            .zip(rgba.par_chunks_exact(4))
+            .for_each(|(value, pixel)| *value = luminance(pixel));
+        Plane {
+            values,
+            width,
+            height,
+        }
+    }
+
+    /// Give the values back to `pool` for the next operation
+    fn recycle(self, pool: &BufferPool) {
+        pool.recycle(self.values);
+    }
+
+    fn at(&self, x: isize, y: isize) -> f32 {
+        let x = x.clamp(0, self.width as isize - 1) as usize;
+        let y = y.clamp(0, self.height as isize - 1) as usize;
+        self.values[y * self.width + x]
+    }
+
+    /// Separable 5x5 Gaussian blur, rows in parallel
+    fn gaussian(&self, pool: &BufferPool) -> Plane {
+        let pass = |source: &Plane, dx: isize, dy: isize| {
+            let mut values = pool.take(source.values.len());
+            values
+                .par_chunks_mut(source.width)
+                .enumerate()
+                .for_each(|(y, row)| {
+                    for (x, value) in row.iter_mut().enumerate() {
+                        *value = GAUSSIAN_5
+                            .iter()
+                            .enumerate()
+                            .map(|(k, weight)| {
+                                let offset = k as isize - 2;
+                                weight
+                                    * source.at(x as isize + offset * dx, y as isize + offset * dy)
+                            })
+                            .sum();
+                    }
+                });
+            Plane {
+                values,
+                width: source.width,
+                height: source.height,
+            }
+        };
+
+        let horizontal = pass(self, 1, 0);
+        let smoothed = pass(&horizontal, 0, 1);
+        horizontal.recycle(pool);
+        smoothed
+    }
+
+    /// Horizontal and vertical Sobel responses at `(x, y)`
+    fn sobel(&self, x: usize, y: usize) -> (f32, f32) {
+        let (x, y) = (x as isize, y as isize);
+        let p = |dx: isize, dy: isize| self.at(x + dx, y + dy);
+
+        let gx = p(1, -1) + 2.0 * p(1, 0) + p(1, 1) - p(-1, -1) - 2.0 * p(-1, 0) - p(-1, 1);
+        let gy = p(-1, 1) + 2.0 * p(0, 1) + p(1, 1) - p(-1, -1) - 2.0 * p(0, -1) - p(1, -1);
+        (gx, gy)
+    }
+}
+
+/// Thin gradients to local maxima along their direction, then classify each
+/// surviving pixel as weak or strong
+fn suppress_and_classify(pool: &BufferPool, plane: &Plane, low: f32, high: f32) -> Vec<u8> {
+    let (width, height) = (plane.width, plane.height);
+    let (mut gxs, mut gys) = (pool.take(width * height), pool.take(width * height));
+    gxs.par_iter_mut()
+        .zip(gys.par_iter_mut())
+        .enumerate()
+        .for_each(|(i, (gx, gy))| (*gx, *gy) = plane.sobel(i % width, i / width));
+    let gradient = |i: usize| (gxs[i], gys[i]);
+    let magnitude = |x: isize, y: isize| {
+        if x < 0 || y < 0 || x >= width as isize || y >= height as isize {
+            return 0.0;
+        }
+        let (gx, gy) = gradient(y as usize * width + x as usize);
+        gx.hypot(gy)
+    };
+
+    let mut classes = pool.take(width * height);
+    classes
+        .par_chunks_mut(width)
+        .enumerate()
+        .for_each(|(y, row)| {
+            for (x, class) in row.iter_mut().enumerate() {
+                *class = NONE;
+                let (gx, gy) = gradient(y * width + x);
+                let current = gx.hypot(gy);
+                if current < low {
+                    continue;
+                }
+
+                // Quantise the gradient direction to one of four neighbour pairs
+                let angle = gy.atan2(gx).to_degrees().rem_euclid(180.0);
+                let (dx, dy) = match angle {
+                    a if !(22.5..157.5).contains(&a) => (1, 0),
+                    a if a < 67.5 => (1, 1),
+                    a if a < 112.5 => (0, 1),
+                    _ => (-1, 1),
+                };
+                let (x, y) = (x as isize, y as isize);
+                if current < magnitude(x + dx, y + dy) || current < magnitude(x - dx, y - dy) {
+                    continue;
+                }
+
+                *class = if current >= high { EDGE } else { WEAK };
+            }
+        });
+
+    pool.recycle(gxs);
+    pool.recycle(gys);
+    classes
+}
+
+/// Promote weak pixels 8-connected to a strong one.
+///
+/// Each row band is flood-filled from its strong pixels in parallel; a final
+/// sequential flood seeded from edges on the band boundary rows carries edges
+/// across bands.
+fn hysteresis(mut classes: Vec<u8>, width: usize, height: usize) -> Vec<u8> {
+    let workers = rayon::current_num_threads();
+    let band_rows = (height + workers - 1) / workers;
+
+    classes.par_chunks_mut(band_rows * width).for_each(|band| {
+        let rows = band.len() / width;
+        let seeds = (0..band.len()).filter(|&i| band[i] == EDGE).collect();
+        flood(band, width, rows, seeds);
+    });
+
+    let seeds = (band_rows..height)
+        .step_by(band_rows)
+        .flat_map(|row| (row - 1) * width..(row + 1) * width)
+        .filter(|&i| classes[i] == EDGE)
+        .collect();
+    flood(&mut classes, width, height, seeds);
+
+    classes
+}
+
+/// Depth-first promotion of weak pixels reachable from `stack`
+fn flood(classes: &mut [u8], width: usize, height: usize, mut stack: Vec<usize>) {
+    while let Some(i) = stack.pop() {
+        let (x, y) = (i % width, i / width);
+        for ny in y.saturating_sub(1)..(y + 2).min(height) {
+            for nx in x.saturating_sub(1)..(x + 2).min(width) {
+                let neighbour = ny * width + nx;
+                if classes[neighbour] == WEAK {
+                    classes[neighbour] = EDGE;
+                    stack.push(neighbour);
+                }
+            }
+        }
+    }
+}
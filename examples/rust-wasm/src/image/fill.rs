@@ -0,0 +1,124 @@
+use std::collections::VecDeque;
+
+use rayon::prelude::*;
+use wasm_bindgen::prelude::*;
+
+use super::{check_dimensions, WasmImageProcessor};
+use crate::{install, WasmError};
+
+/// Where and how [`WasmImageProcessor::flood_fill`] paints
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FloodFill {
+    /// Column of the seed pixel
+    pub x: u32,
+    /// Row of the seed pixel
+    pub y: u32,
+    /// Largest difference from the seed pixel, per channel, that is filled
+    pub tolerance: u8,
+    color: [u8; 4],
+}
+
+#[wasm_bindgen]
+impl FloodFill {
+    /// Fill from `(x, y)` with `fill_color` (4 RGBA bytes)
+    #[wasm_bindgen(constructor)]
+    pub fn new(x: u32, y: u32, fill_color: &[u8], tolerance: u8) -> Result<FloodFill, WasmError> {
+        let color = fill_color
+            .try_into()
+            .map_err(|_| WasmError::dimension("Fill colour bytes (RGBA)", 4, fill_color.len()))?;
+        Ok(FloodFill {
+            x,
+            y,
+            tolerance,
+            color,
+        })
+    }
+
+    /// The RGBA colour painted over the region
+    #[wasm_bindgen(getter)]
+    pub fn fill_color(&self) -> Vec<u8> {
+        self.color.to_vec()
+    }
+}
+
+#[wasm_bindgen]
+impl WasmImageProcessor {
+    /// Paint-bucket fill: set every pixel 4-connected to the seed pixel
+    /// whose channels, alpha included, are each within `fill.tolerance` of
+    /// the seed pixel's to the fill colour.
+    ///
+    /// Candidate pixels are classified against the seed colour in parallel,
+    /// then the region is grown breadth-first from the seed.
+    #[wasm_bindgen]
+    pub fn flood_fill(
+        &mut self,
+        rgba_data: &[u8],
+        width: u32,
+        height: u32,
+        fill: &FloodFill,
+    ) -> Result<Vec<u8>, WasmError> {
+        let timing = self.profile("flood_fill", rgba_data.len());
+        check_dimensions(rgba_data, width, height)?;
+        let FloodFill {
+            x,
+            y,
+            tolerance,
+            color: fill,
+        } = *fill;
+        if x >= width || y >= height {
+            return Err(WasmError::invalid(
+                "seed",
+                format!("({x}, {y}) lies outside the {width}x{height} image"),
+            ));
+        }
+        let (width, height) = (width as usize, height as usize);
+        let seed_index = y as usize * width + x as usize;
+        self.load(rgba_data);
+
+        let mut candidates = install(&self.runtime, || {
+            let seed = pixel_u32(&rgba_data[seed_index * 4..seed_index * 4 + 4]);
+            rgba_data
+                .par_chunks_exact(4)
+                .map(|pixel| within_tolerance(pixel_u32(pixel), seed, tolerance))
+                .collect::<Vec<bool>>()
+        })?;
+
+        // Clearing a candidate as it is queued marks it visited
+        let mut queue = VecDeque::from([seed_index]);
+        candidates[seed_index] = false;
+        while let Some(i) = queue.pop_front() {
+            self.buffer[i * 4..i * 4 + 4].copy_from_slice(&fill);
+
+            let (px, py) = (i % width, i / width);
+            let neighbours = [
+                (px > 0).then(|| i - 1),
+                (px + 1 < width).then(|| i + 1),
+                (py > 0).then(|| i - width),
+                (py + 1 < height).then(|| i + width),
+            ];
+            for neighbour in neighbours.into_iter().flatten() {
+                if candidates[neighbour] {
+                    candidates[neighbour] = false;
+                    queue.push_back(neighbour);
+                }
+            }
+        }
+
+        timing.finish(Ok(self.buffer.clone()))
+    }
+}
+
+fn pixel_u32(pixel: &[u8]) -> u32 {
+    u32::from_le_bytes([pixel[0], pixel[1], pixel[2], pixel[3]])
+}
+
+/// Whether every byte of `a` is within `tolerance` of the same byte of `b`;
+/// an exact match is a single word comparison
+fn within_tolerance(a: u32, b: u32, tolerance: u8) -> bool {
+    a == b
+        || a.to_le_bytes()
+            .iter()
+            .zip(b.to_le_bytes())
+            .all(|(&x, y)| x.abs_diff(y) <= tolerance)
+}
@@ -0,0 +1,172 @@
+use rayon::prelude::*;
+use wasm_bindgen::prelude::*;
+
+use super::WasmImageProcessor;
+use crate::{install, WasmError, WasmRuntime};
+
+#[wasm_bindgen]
+impl WasmImageProcessor {
+    /// Apply an SVG `feColorMatrix`-style 4x5 matrix to every pixel.
+    ///
+    /// Rows produce R, G, B and A from `[r, g, b, a, 1]` with channels
+    /// normalised to `0..=1`, so the fifth column is an offset in that range.
+    #[wasm_bindgen]
+    pub fn apply_color_matrix(
+        &mut self,
+        rgba: &[u8],
+        matrix: &[f32],
+    ) -> Result<Vec<u8>, WasmError> {
+        let timing = self.profile("apply_color_matrix", rgba.len());
+        let matrix = color_matrix(matrix)?;
+        timing.finish(self.apply_matrix_copy(rgba, &matrix))
+    }
+
+    /// CSS `sepia(amount)`, with `amount` clamped to `0..=1`
+    #[wasm_bindgen]
+    pub fn sepia(&mut self, rgba: &[u8], amount: f32) -> Result<Vec<u8>, WasmError> {
+        let timing = self.profile("sepia", rgba.len());
+        timing.finish(self.apply_matrix_copy(rgba, &sepia_matrix(amount)))
+    }
+
+    /// CSS `invert(amount)`, with `amount` clamped to `0..=1`
+    #[wasm_bindgen]
+    pub fn invert(&mut self, rgba: &[u8], amount: f32) -> Result<Vec<u8>, WasmError> {
+        let timing = self.profile("invert", rgba.len());
+        timing.finish(self.apply_matrix_copy(rgba, &invert_matrix(amount)))
+    }
+
+    /// CSS `saturate(amount)`; values above 1 oversaturate, negatives clamp to 0
+    #[wasm_bindgen]
+    pub fn saturate(&mut self, rgba: &[u8], amount: f32) -> Result<Vec<u8>, WasmError> {
+        let timing = self.profile("saturate", rgba.len());
+        timing.finish(self.apply_matrix_copy(rgba, &saturate_matrix(amount)))
+    }
+
+    /// CSS `hue-rotate(degrees)`
+    #[wasm_bindgen]
+    pub fn hue_rotate(&mut self, rgba: &[u8], degrees: f32) -> Result<Vec<u8>, WasmError> {
+        let timing = self.profile("hue_rotate", rgba.len());
+        timing.finish(self.apply_matrix_copy(rgba, &hue_rotate_matrix(degrees)))
+    }
+}
+
+impl WasmImageProcessor {
+    /// Copy `rgba` into the working buffer, apply `matrix` and return a copy
+    fn apply_matrix_copy(&mut self, rgba: &[u8], matrix: &[f32; 20]) -> Result<Vec<u8>, WasmError> {
+        self.load(rgba);
+        apply_matrix(&self.runtime, &mut self.buffer, matrix)?;
+        Ok(self.buffer.clone())
+    }
+}
+
+/// Validate a user-supplied 4x5 colour matrix
+pub(super) fn color_matrix(values: &[f32]) -> Result<[f32; 20], WasmError> {
+    values
+        .try_into()
+        .map_err(|_| WasmError::dimension("Colour matrix entries (4x5)", 20, values.len()))
+}
+
+/// Spec matrix for CSS `sepia(amount)`
+pub(super) fn sepia_matrix(amount: f32) -> [f32; 20] {
+    let a = 1.0 - amount.clamp(0.0, 1.0);
+    rgb_matrix(
+        [
+            [0.393 + 0.607 * a, 0.769 - 0.769 * a, 0.189 - 0.189 * a],
+            [0.349 - 0.349 * a, 0.686 + 0.314 * a, 0.168 - 0.168 * a],
+            [0.272 - 0.272 * a, 0.534 - 0.534 * a, 0.131 + 0.869 * a],
+        ],
+        0.0,
+    )
+}
+
+/// Spec matrix for CSS `invert(amount)`
+pub(super) fn invert_matrix(amount: f32) -> [f32; 20] {
+    let a = amount.clamp(0.0, 1.0);
+    let scale = 1.0 - 2.0 * a;
+    rgb_matrix([[scale, 0.0, 0.0], [0.0, scale, 0.0], [0.0, 0.0, scale]], a)
+}
+
+/// Spec matrix for CSS `saturate(amount)`
+pub(super) fn saturate_matrix(amount: f32) -> [f32; 20] {
+    let s = amount.max(0.0);
+    rgb_matrix(
+        [
+            [0.213 + 0.787 * s, 0.715 - 0.715 * s, 0.072 - 0.072 * s],
+            [0.213 - 0.213 * s, 0.715 + 0.285 * s, 0.072 - 0.072 * s],
+            [0.213 - 0.213 * s, 0.715 - 0.715 * s, 0.072 + 0.928 * s],
+        ],
+        0.0,
+    )
+}
+
+/// Spec matrix for CSS `hue-rotate(degrees)`
+pub(super) fn hue_rotate_matrix(degrees: f32) -> [f32; 20] {
+    let (sin, cos) = degrees.to_radians().sin_cos();
+    rgb_matrix(
+        [
+            [
+                0.213 + cos * 0.787 - sin * 0.213,
+                0.715 - cos * 0.715 - sin * 0.715,
+                0.072 - cos * 0.072 + sin * 0.928,
+            ],
+            [
+                0.213 - cos * 0.213 + sin * 0.143,
+                0.715 + cos * 0.285 + sin * 0.140,
+                0.072 - cos * 0.072 - sin * 0.283,
+            ],
+            [
+                0.213 - cos * 0.213 - sin * 0.787,
+                0.715 - cos * 0.715 + sin * 0.715,
+                0.072 + cos * 0.928 + sin * 0.072,
+            ],
+        ],
+        0.0,
+    )
+}
+
+/// Spec matrix for CSS `contrast(amount)`; negatives clamp to 0
+pub(super) fn contrast_matrix(amount: f32) -> [f32; 20] {
+    let c = amount.max(0.0);
+    rgb_matrix([[c, 0.0, 0.0], [0.0, c, 0.0], [0.0, 0.0, c]], 0.5 - 0.5 * c)
+}
+
+/// Expand a 3x3 RGB matrix plus uniform offset into a 4x5 matrix that
+/// leaves alpha unchanged
+fn rgb_matrix(rgb: [[f32; 3]; 3], offset: f32) -> [f32; 20] {
+    let mut matrix = [0.0; 20];
+    for (row, coefficients) in rgb.iter().enumerate() {
+        matrix[row * 5..row * 5 + 3].copy_from_slice(coefficients);
+        matrix[row * 5 + 4] = offset;
+    }
+    matrix[18] = 1.0;
+    matrix
+}
+
+/// Apply a 4x5 colour matrix to an RGBA buffer in place
+pub(super) fn apply_matrix(
+    runtime: &WasmRuntime,
+    buffer: &mut [u8],
+    matrix: &[f32; 20],
+) -> Result<(), WasmError> {
+    install(runtime, || {
+        buffer
+            .par_chunks_exact_mut(4)
+            .for_each(|pixel| matrix_pixel(pixel, matrix));
+    })
+}
+
+/// Apply a 4x5 colour matrix to a single RGBA pixel
+pub(super) fn matrix_pixel(pixel: &mut [u8], matrix: &[f32; 20]) {
+    let input = [
+        pixel[0] as f32 / 255.0,
+        pixel[1] as f32 / 255.0,
+        pixel[2] as f32 / 255.0,
+        pixel[3] as f32 / 255.0,
+    ];
+
+    for (value, row) in pixel.iter_mut().zip(matrix.chunks_exact(5)) {
+        let output =
+            row[0] * input[0] + row[1] * input[1] + row[2] * input[2] + row[3] * input[3] + row[4];
+        *value = (output * 255.0).round().clamp(0.0, 255.0) as u8;
+    }
+}
@@ -0,0 +1,105 @@
+use rayon::prelude::*;
+use wasm_bindgen::prelude::*;
+
+use super::WasmImageProcessor;
+use crate::{install, WasmError};
+
+#[wasm_bindgen]
+impl WasmImageProcessor {
+    /// Dense Lucas-Kanade optical flow between two greyscale frames of one
+    /// byte per pixel.
+    ///
+    /// Returns `(dx, dy)` per pixel, interleaved, in pixels per frame. Each
+    /// pixel solves the 2x2 least-squares system `AᵀA v = Aᵀb` over the
+    /// `window_size x window_size` window around it, using central
+    /// differences of `frame1` for the spatial gradients and clamping at the
+    /// borders. Where the window has too little texture for a unique answer
+    /// (a flat region, or an edge with the aperture problem), the flow is
+    /// zero. Lucas-Kanade assumes small motion, about a pixel per frame.
+    #[wasm_bindgen]
+    pub fn optical_flow_lucas_kanade(
+        &self,
+        frame1: &[u8],
+        frame2: &[u8],
+        width: u32,
+        height: u32,
+        window_size: u32,
+    ) -> Result<Vec<f32>, WasmError> {
+        let timing = self.profile("optical_flow_lucas_kanade", frame1.len());
+        let (width, height) = (width as usize, height as usize);
+        if frame1.len() != width * height {
+            return Err(WasmError::dimension(
+                format!("Bytes in a {width}x{height} greyscale frame"),
+                width * height,
+                frame1.len(),
+            ));
+        }
+        if frame2.len() != frame1.len() {
+            return Err(WasmError::dimension(
+                "Second frame length",
+                frame1.len(),
+                frame2.len(),
+            ));
+        }
+        if window_size % 2 == 0 {
+            return Err(WasmError::invalid(
+                "window size",
+                format!("must be odd, got {window_size}"),
+            ));
+        }
+        let radius = (window_size / 2) as isize;
+
+        timing.finish(install(&self.runtime, || {
+            let at = |frame: &[u8], x: isize, y: isize| {
+                let x = x.clamp(0, width as isize - 1) as usize;
+                let y = y.clamp(0, height as isize - 1) as usize;
+                frame[y * width + x] as f64
+            };
+            // (Ix, Iy, It) per pixel
+            let gradients: Vec<(f64, f64, f64)> = (0..frame1.len())
+                .into_par_iter()
+                .map(|i| {
+                    let (x, y) = ((i % width) as isize, (i / width) as isize);
+                    (
+                        (at(frame1, x + 1, y) - at(frame1, x - 1, y)) / 2.0,
+                        (at(frame1, x, y + 1) - at(frame1, x, y - 1)) / 2.0,
+                        frame2[i] as f64 - frame1[i] as f64,
+                    )
+                })
+                .collect();
+            let gradient = |x: isize, y: isize| {
+                let x = x.clamp(0, width as isize - 1) as usize;
+                let y = y.clamp(0, height as isize - 1) as usize;
+                gradients[y * width + x]
+            };
+
+            let mut flow = vec![0.0f32; frame1.len() * 2];
+            flow.par_chunks_exact_mut(2)
+                .enumerate()
+                .for_each(|(i, vector)| {
+                    let (x, y) = ((i % width) as isize, (i / width) as isize);
+                    let (mut xx, mut xy, mut yy, mut xt, mut yt) = (0.0, 0.0, 0.0, 0.0, 0.0);
+                    for wy in y - radius..=y + radius {
+                        for wx in x - radius..=x + radius {
+                            let (ix, iy, it) = gradient(wx, wy);
+                            xx += ix * ix;
+                            xy += ix * iy;
+                            yy += iy * iy;
+                            xt += ix * it;
+                            yt += iy * it;
+                        }
+                    }
+
+                    // Solve [xx xy; xy yy] v = -[xt; yt] by Cramer's rule,
+                    // treating a near-singular matrix as no measurable motion
+                    let det = xx * yy - xy * xy;
+                    let trace = xx + yy;
+                    if trace > 0.0 && det > 1e-6 * trace * trace {
+                        vector[0] = ((xy * yt - yy * xt) / det) as f32;
+                        vector[1] = ((xy * xt - xx * yt) / det) as f32;
+                    }
+                });
+            flow
+        }))
+    }
+}
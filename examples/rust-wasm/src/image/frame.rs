@@ -0,0 +1,141 @@
+use js_sys::Uint8Array;
+use wasm_bindgen::prelude::*;
+
+use super::{
+    brightness_in_place, check_dimensions,
+    filters::{
+        apply_matrix, color_matrix, hue_rotate_matrix, invert_matrix, saturate_matrix, sepia_matrix,
+    },
+    grayscale_in_place, WasmImageProcessor,
+};
+use crate::WasmError;
+
+/// Zero-copy frame workflow.
+///
+/// `load_frame` copies pixels into a persistent WASM-side frame once; the
+/// `op_*` methods then mutate it in place, and JavaScript reads the result
+/// straight out of linear memory through `frame_view` or
+/// `frame_ptr`/`frame_len`.
+///
+/// Only `load_frame` reallocates the frame, but *any* allocation may grow
+/// WASM memory, which detaches every existing view. Re-acquire the view after
+/// each call into the module rather than caching it across calls.
+#[wasm_bindgen]
+impl WasmImageProcessor {
+    /// Copy an RGBA frame into the persistent frame buffer
+    #[wasm_bindgen]
+    pub fn load_frame(&mut self, rgba: &[u8], width: u32, height: u32) -> Result<(), WasmError> {
+        check_dimensions(rgba, width, height)?;
+
+        self.frame.clear();
+        self.frame.extend_from_slice(rgba);
+        self.frame_width = width;
+        self.frame_height = height;
+        Ok(())
+    }
+
+    /// Width of the loaded frame in pixels
+    #[wasm_bindgen(getter)]
+    pub fn frame_width(&self) -> u32 {
+        self.frame_width
+    }
+
+    /// Height of the loaded frame in pixels
+    #[wasm_bindgen(getter)]
+    pub fn frame_height(&self) -> u32 {
+        self.frame_height
+    }
+
+    /// Address of the frame in WASM linear memory
+    #[wasm_bindgen]
+    pub fn frame_ptr(&self) -> *const u8 {
+        self.frame.as_ptr()
+    }
+
+    /// Length of the frame in bytes
+    #[wasm_bindgen]
+    pub fn frame_len(&self) -> usize {
+        self.frame.len()
+    }
+
+    /// A `Uint8Array` aliasing the frame without copying.
+    ///
+    /// The view is only valid until the next call into the module; see the
+    /// notes on this impl block.
+    #[wasm_bindgen]
+    pub fn frame_view(&self) -> Uint8Array {
+        // SAFETY: the view is handed straight to JavaScript, and no Rust code
+        // runs (so nothing can allocate or touch the frame) before JS reads it.
+        unsafe { Uint8Array::view(&self.frame) }
+    }
+
+    /// Grayscale the loaded frame in place
+    #[wasm_bindgen]
+    pub fn op_grayscale(&mut self) -> Result<(), WasmError> {
+        let timing = self.profile("op_grayscale", self.frame.len());
+        timing.finish(grayscale_in_place(&self.runtime, &mut self.frame))
+    }
+
+    /// Scale the loaded frame's RGB channels in place
+    #[wasm_bindgen]
+    pub fn op_brightness(&mut self, brightness: f32) -> Result<(), WasmError> {
+        let timing = self.profile("op_brightness", self.frame.len());
+        timing.finish(brightness_in_place(
+            &self.runtime,
+            &mut self.frame,
+            brightness,
+        ))
+    }
+
+    /// Apply a 4x5 colour matrix to the loaded frame in place
+    #[wasm_bindgen]
+    pub fn op_color_matrix(&mut self, matrix: &[f32]) -> Result<(), WasmError> {
+        let timing = self.profile("op_color_matrix", self.frame.len());
+        let matrix = color_matrix(matrix)?;
+        timing.finish(apply_matrix(&self.runtime, &mut self.frame, &matrix))
+    }
+
+    /// CSS `sepia(amount)` on the loaded frame
+    #[wasm_bindgen]
+    pub fn op_sepia(&mut self, amount: f32) -> Result<(), WasmError> {
+        let timing = self.profile("op_sepia", self.frame.len());
+        timing.finish(apply_matrix(
+            &self.runtime,
+            &mut self.frame,
+            &sepia_matrix(amount),
+        ))
+    }
+
+    /// CSS `invert(amount)` on the loaded frame
+    #[wasm_bindgen]
+    pub fn op_invert(&mut self, amount: f32) -> Result<(), WasmError> {
+        let timing = self.profile("op_invert", self.frame.len());
+        timing.finish(apply_matrix(
+            &self.runtime,
+            &mut self.frame,
+            &invert_matrix(amount),
+        ))
+    }
+
+    /// CSS `saturate(amount)` on the loaded frame
+    #[wasm_bindgen]
+    pub fn op_saturate(&mut self, amount: f32) -> Result<(), WasmError> {
+        let timing = self.profile("op_saturate", self.frame.len());
+        timing.finish(apply_matrix(
+            &self.runtime,
+            &mut self.frame,
+            &saturate_matrix(amount),
+        ))
+    }
+
+    /// CSS `hue-rotate(degrees)` on the loaded frame
+    #[wasm_bindgen]
+    pub fn op_hue_rotate(&mut self, degrees: f32) -> Result<(), WasmError> {
+        let timing = self.profile("op_hue_rotate", self.frame.len());
+        timing.finish(apply_matrix(
+            &self.runtime,
+            &mut self.frame,
+            &hue_rotate_matrix(degrees),
+        ))
+    }
+}
@@ -0,0 +1,235 @@
+use rayon::prelude::*;
+use wasm_bindgen::prelude::*;
+
+use super::{check_dimensions, WasmImageProcessor};
+use crate::{install, pool::BufferPool, WasmError};
+
+/// Minimum number of columns each task accumulates in the column pass
+const MIN_COLUMN_BAND: usize = 64;
+
+/// The rectangle [`WasmImageProcessor::box_sum`] adds up
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Rect {
+    /// Column of the top-left pixel
+    pub x: usize,
+    /// Row of the top-left pixel
+    pub y: usize,
+    /// Columns covered
+    pub width: usize,
+    /// Rows covered
+    pub height: usize,
+}
+
+#[wasm_bindgen]
+impl Rect {
+    #[wasm_bindgen(constructor)]
+    pub fn new(x: usize, y: usize, width: usize, height: usize) -> Rect {
+        Rect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl WasmImageProcessor {
+    /// Summed-area table of a one-byte-per-pixel image.
+    ///
+    /// The table is `(width + 1) x (height + 1)` with a zero first row and
+    /// column, so `table[y * (width + 1) + x]` is the sum of every pixel above
+    /// and left of `(x, y)`. Use [`WasmImageProcessor::box_sum`] to query it.
+    #[wasm_bindgen]
+    pub fn integral_image(
+        &mut self,
+        gray: &[u8],
+        width: usize,
+        height: usize,
+    ) -> Result<Vec<f64>, WasmError> {
+        let timing = self.profile("integral_image", gray.len());
+        let pixels = width
+            .checked_mul(height)
+            .filter(|_| table_len(width, height).is_some())
+            .ok_or_else(|| too_large(width, height))?;
+        if gray.len() != pixels {
+            return Err(WasmError::dimension(
+                format!("Bytes for a {width}x{height} image"),
+                pixels,
+                gray.len(),
+            ));
+        }
+
+        let pool = self.runtime.buffer_pool();
+        timing.finish(install(&self.runtime, || {
+            summed_area_table(pool, width, height, |i| gray[i] as f64)
+        }))
+    }
+
+    /// Sum of the pixels in `rect`, read from a table produced by
+    /// [`WasmImageProcessor::integral_image`] in four lookups
+    #[wasm_bindgen]
+    pub fn box_sum(
+        &self,
+        sat: &[f64],
+        width: usize,
+        height: usize,
+        rect: &Rect,
+    ) -> Result<f64, WasmError> {
+        let timing = self.profile("box_sum", sat.len());
+        let entries = table_len(width, height).ok_or_else(|| too_large(width, height))?;
+        if sat.len() != entries {
+            return Err(WasmError::dimension(
+                format!("Entries in a summed-area table for a {width}x{height} image"),
+                entries,
+                sat.len(),
+            ));
+        }
+        let Rect {
+            x,
+            y,
+            width: w,
+            height: h,
+        } = *rect;
+        let (x1, y1) = match (x.checked_add(w), y.checked_add(h)) {
+            (Some(x1), Some(y1)) if x1 <= width && y1 <= height => (x1, y1),
+            _ => {
+                return Err(WasmError::invalid(
+                    "box",
+                    format!("{w}x{h} at ({x}, {y}) lies outside the {width}x{height} image"),
+                ))
+            }
+        };
+
+        timing.finish(Ok(rectangle_sum(sat, width + 1, x, y, x1, y1)))
+    }
+
+    /// Box blur with a `(2 * radius + 1)` square window, clipped at the edges.
+    ///
+    /// Each channel, alpha included, is averaged through a summed-area table,
+    /// so the cost per pixel does not depend on `radius`.
+    #[wasm_bindgen]
+    pub fn fast_box_blur(
+        &mut self,
+        rgba: &[u8],
+        width: u32,
+        height: u32,
+        radius: usize,
+    ) -> Result<Vec<u8>, WasmError> {
+        let timing = self.profile("fast_box_blur", rgba.len());
+        check_dimensions(rgba, width, height)?;
+        let (width, height) = (width as usize, height as usize);
+        self.load(rgba);
+
+        let buffer = &mut self.buffer;
+        let pool = self.runtime.buffer_pool();
+        install(&self.runtime, || {
+            for channel in 0..4 {
+                let table =
+                    summed_area_table(pool, width, height, |i| rgba[i * 4 + channel] as f64);
+
+                buffer
+                    .par_chunks_exact_mut(4)
+                    .enumerate()
+                    .for_each(|(index, pixel)| {
+                        let (x, y) = (index % width, index / width);
+                        let (x0, x1) = (x.saturating_sub(radius), (x + radius + 1).min(width));
+                        let (y0, y1) = (y.saturating_sub(radius), (y + radius + 1).min(height));
+
+                        let sum = rectangle_sum(&table, width + 1, x0, y0, x1, y1);
+                        let count = ((x1 - x0) * (y1 - y0)) as f64;
+                        pixel[channel] = (sum / count).round() as u8;
+                    });
+                pool.recycle(table);
+            }
+        })?;
+
+        timing.finish(Ok(self.buffer.clone()))
+    }
+}
+
+/// Summed-area table with a zero first row and column, built from `value(i)`
+/// for each pixel index `i` of a `width x height` image in a buffer taken
+/// from `pool`.
+///
+/// Rows are prefix-summed in parallel, then columns are accumulated downwards
+/// in parallel bands of adjacent columns.
+pub(super) fn summed_area_table(
+    pool: &BufferPool,
+    width: usize,
+    height: usize,
+    value: impl Fn(usize) -> f64 + Sync,
+) -> Vec<f64> {
+    let stride = width + 1;
+    let mut table = pool.take(stride * (height + 1));
+    if width == 0 || height == 0 {
+        return table;
+    }
+
+    table[stride..]
+        .par_chunks_exact_mut(stride)
+        .enumerate()
+        .for_each(|(y, row)| {
+            let mut running = 0.0;
+            for (x, cell) in row[1..].iter_mut().enumerate() {
+                running += value(y * width + x);
+                *cell = running;
+            }
+        });
+
+    // Hand each band its own segment of every row so the bands can run
+    // independently
+    let workers = rayon::current_num_threads();
+    let band = ((stride + workers - 1) / workers).max(MIN_COLUMN_BAND);
+    let mut bands: Vec<Vec<&mut [f64]>> = (0..(stride + band - 1) / band)
+        .map(|_| Vec::new())
+        .collect();
+    for row in table[stride..].chunks_exact_mut(stride) {
+        for (segments, segment) in bands.iter_mut().zip(row.chunks_mut(band)) {
+            segments.push(segment);
+        }
+    }
+
+    bands.into_par_iter().for_each(|segments| {
+        let mut segments = segments.into_iter();
+        let Some(mut above) = segments.next() else {
+            return;
+        };
+        for segment in segments {
+            for (cell, &up) in segment.iter_mut().zip(above.iter()) {
+                *cell += up;
+            }
+            above = segment;
+        }
+    });
+
+    table
+}
+
+/// Entries in the summed-area table of a `width x height` image, if that
+/// fits in a `usize`
+fn table_len(width: usize, height: usize) -> Option<usize> {
+    width.checked_add(1)?.checked_mul(height.checked_add(1)?)
+}
+
+fn too_large(width: usize, height: usize) -> WasmError {
+    WasmError::invalid(
+        "image size",
+        format!("the summed-area table of a {width}x{height} image overflows its size"),
+    )
+}
+
+/// Sum over `[x0, x1) x [y0, y1)` from a padded summed-area table
+pub(super) fn rectangle_sum(
+    table: &[f64],
+    stride: usize,
+    x0: usize,
+    y0: usize,
+    x1: usize,
+    y1: usize,
+) -> f64 {
+    table[y1 * stride + x1] + table[y0 * stride + x0]
+        - table[y0 * stride + x1]
+        - table[y1 * stride + x0]
+}
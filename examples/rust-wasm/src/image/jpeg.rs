@@ -0,0 +1,671 @@
+use std::f32::consts::PI;
+
+use rayon::prelude::*;
+use wasm_bindgen::prelude::*;
+
+use super::{WasmImage, WasmImageProcessor};
+use crate::{install, WasmError};
+
+const SUPPORTED: &str = "baseline sequential (SOF0) YCbCr with 8-bit samples";
+
+/// Most pixels a frame header may declare. Decoding holds about 13 bytes
+/// per pixel at once, so this keeps a crafted header from asking for more
+/// memory than a WASM instance can have.
+const MAX_PIXELS: usize = 1 << 25;
+
+/// Natural (row-major) index of each coefficient in zigzag order
+const ZIGZAG: [usize; 64] = [
+    0, 1, 8, 16, 9, 2, 3, 10, 17, 24, 32, 25, 18, 11, 4, 5, 12, 19, 26, 33, 40, 48, 41, 34, 27, 20,
+    13, 6, 7, 14, 21, 28, 35, 42, 49, 56, 57, 50, 43, 36, 29, 22, 15, 23, 30, 37, 44, 51, 58, 59,
+    52, 45, 38, 31, 39, 46, 53, 60, 61, 54, 47, 55, 62, 63,
+];
+
+#[wasm_bindgen]
+impl WasmImageProcessor {
+    /// Decode a baseline JPEG into an RGBA [`WasmImage`], so pixels need not
+    /// be decoded in JavaScript and copied in.
+    ///
+    /// Only baseline sequential (SOF0) JPEGs with three YCbCr components are
+    /// supported, with any chroma subsampling and restart intervals; other
+    /// kinds, such as progressive or arithmetic-coded files, fail with
+    /// `UNSUPPORTED_OPERATION`. Damaged data fails with `INVALID_ENCODING`.
+    /// The Huffman-coded data is read sequentially, then blocks are
+    /// dequantised and inverse-transformed in parallel, one per task.
+    /// Subsampled chroma is interpolated bilinearly.
+    #[wasm_bindgen]
+    pub fn decode_jpeg(&self, jpeg_bytes: &[u8]) -> Result<WasmImage, WasmError> {
+        let timing = self.profile("decode_jpeg", jpeg_bytes.len());
+        timing.finish(install(&self.runtime, || {
+            let image = Decoder::default().read(jpeg_bytes)?;
+            let (width, height) = (image.frame.width as u32, image.frame.height as u32);
+            WasmImage::from_rgba(image.to_rgba(), width, height)
+        })?)
+    }
+}
+
+fn malformed(offset: usize, reason: impl Into<String>) -> WasmError {
+    WasmError::InvalidEncoding {
+        encoding: "JPEG".to_string(),
+        offset,
+        reason: reason.into(),
+    }
+}
+
+fn unsupported(feature: &str) -> WasmError {
+    WasmError::unsupported("JPEG feature", feature, SUPPORTED)
+}
+
+/// A canonical Huffman table, decoded a bit at a time as in the JPEG
+/// specification's `DECODE` procedure
+struct Huffman {
+    /// Largest code of each length, or -1 if there are none
+    max_code: [i32; 17],
+    /// Smallest code of each length
+    min_code: [i32; 17],
+    /// Index into `values` of the first code of each length
+    first_value: [i32; 17],
+    values: Vec<u8>,
+}
+
+impl Huffman {
+    fn new(counts: &[u8], values: Vec<u8>) -> Huffman {
+        let mut table = Huffman {
+            max_code: [-1; 17],
+            min_code: [0; 17],
+            first_value: [0; 17],
+            values,
+        };
+        let (mut code, mut index) = (0, 0);
+        for length in 1..=16 {
+            let count = counts[length - 1] as i32;
+            table.first_value[length] = index;
+            table.min_code[length] = code;
+            if count > 0 {
+                table.max_code[length] = code + count - 1;
+            }
+            code = (code + count) << 1;
+            index += count;
+        }
+        table
+    }
+
+    fn decode(&self, bits: &mut BitReader) -> Result<u8, WasmError> {
+        let mut code = 0;
+        for length in 1..=16 {
+            code = (code << 1) | bits.bit()? as i32;
+            if code <= self.max_code[length] {
+                let index = self.first_value[length] + code - self.min_code[length];
+                return Ok(self.values[index as usize]);
+            }
+        }
+        Err(malformed(bits.pos, "invalid Huffman code"))
+    }
+}
+
+/// Reads entropy-coded bits, removing the zero byte stuffed after each `0xFF`
+struct BitReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+    byte: u8,
+    remaining: u32,
+}
+
+impl<'a> BitReader<'a> {
+    fn new(data: &'a [u8], pos: usize) -> BitReader<'a> {
+        BitReader {
+            data,
+            pos,
+            byte: 0,
+            remaining: 0,
+        }
+    }
+
+    fn bit(&mut self) -> Result<u32, WasmError> {
+        if self.remaining == 0 {
+            self.byte = match self.data.get(self.pos..self.pos + 2) {
+                Some([0xFF, 0x00]) => {
+                    self.pos += 2;
+                    0xFF
+                }
+                _ => match self.data.get(self.pos) {
+                    Some(&byte) if byte != 0xFF => {
+                        self.pos += 1;
+                        byte
+                    }
+                    // A correct stream never reads into the next marker
+                    _ => return Err(malformed(self.pos, "entropy-coded data ends early")),
+                },
+            };
+            self.remaining = 8;
+        }
+        self.remaining -= 1;
+        Ok((self.byte >> self.remaining) as u32 & 1)
+    }
+
+    /// Read an `s`-bit magnitude and sign-extend it as JPEG's `EXTEND` does
+    fn signed(&mut self, s: u8) -> Result<i32, WasmError> {
+        let mut value = 0;
+        for _ in 0..s {
+            value = (value << 1) | self.bit()? as i32;
+        }
+        Ok(match s {
+            0 => 0,
+            _ if value < 1 << (s - 1) => value - (1 << s) + 1,
+            _ => value,
+        })
+    }
+
+    /// Skip the padding to the next byte and the `RSTn` marker after it
+    fn restart(&mut self) -> Result<(), WasmError> {
+        self.remaining = 0;
+        while self.data.get(self.pos + 1) == Some(&0xFF) {
+            self.pos += 1;
+        }
+        match self.data.get(self.pos..self.pos + 2) {
+            Some([0xFF, 0xD0..=0xD7]) => {
+                self.pos += 2;
+                Ok(())
+            }
+            _ => Err(malformed(self.pos, "missing restart marker")),
+        }
+    }
+}
+
+#[derive(Default)]
+struct Component {
+    id: u8,
+    h: usize,
+    v: usize,
+    quant_table: usize,
+    /// Blocks per row and column, padded to whole MCUs
+    blocks_wide: usize,
+    blocks_high: usize,
+    /// Quantised coefficients of each block, row-major, in natural order
+    coefficients: Vec<[i16; 64]>,
+}
+
+#[derive(Default)]
+struct Frame {
+    width: usize,
+    height: usize,
+    h_max: usize,
+    v_max: usize,
+    mcus_wide: usize,
+    mcus_high: usize,
+    components: Vec<Component>,
+}
+
+#[derive(Default)]
+struct Decoder {
+    quant_tables: [Option<[u16; 64]>; 4],
+    dc_tables: [Option<Huffman>; 4],
+    ac_tables: [Option<Huffman>; 4],
+    restart_interval: usize,
+    frame: Option<Frame>,
+}
+
+/// The decoded planes of a JPEG, before colour conversion
+struct Image {
+    frame: Frame,
+    /// One plane of samples per component, `blocks_wide * 8` wide
+    planes: Vec<Vec<u8>>,
+}
+
+impl Decoder {
+    /// Walk the markers, decoding each scan, then transform the blocks
+    fn read(mut self, data: &[u8]) -> Result<Image, WasmError> {
+        if !data.starts_with(&[0xFF, 0xD8]) {
+            return Err(malformed(0, "missing start-of-image marker"));
+        }
+        let mut pos = 2;
+        let mut scanned = false;
+        loop {
+            match data.get(pos) {
+                Some(0xFF) => {}
+                Some(_) => return Err(malformed(pos, "expected a marker")),
+                None => return Err(malformed(pos, "missing end-of-image marker")),
+            }
+            // Any number of 0xFF bytes may pad a marker
+            while data.get(pos + 1) == Some(&0xFF) {
+                pos += 1;
+            }
+            let Some(&marker) = data.get(pos + 1) else {
+                return Err(malformed(data.len(), "missing end-of-image marker"));
+            };
+            pos += 2;
+            if marker == 0xD9 {
+                break;
+            }
+
+            let start = pos;
+            let segment = segment(data, pos)?;
+            pos += 2 + segment.len();
+            match marker {
+                0xC0 => self.read_frame(segment, start)?,
+                0xC1 => return Err(unsupported("extended sequential (SOF1)")),
+                0xC2 => return Err(unsupported("progressive (SOF2)")),
+                0xC3 => return Err(unsupported("lossless (SOF3)")),
+                0xC5..=0xC7 | 0xCD..=0xCF => return Err(unsupported("hierarchical")),
+                0xC9..=0xCB => return Err(unsupported("arithmetic coding")),
+                0xC4 => self.read_huffman_tables(segment, start)?,
+                0xDB => self.read_quant_tables(segment, start)?,
+                0xDD => {
+                    let [high, low] = segment else {
+                        return Err(malformed(start, "restart interval must be 2 bytes"));
+                    };
+                    self.restart_interval = u16::from_be_bytes([*high, *low]) as usize;
+                }
+                0xDA => {
+                    pos = self.read_scan(data, segment, start, pos)?;
+                    scanned = true;
+                }
+                // APPn and comments carry nothing needed to decode
+                0xE0..=0xEF | 0xFE => {}
+                _ => {
+                    return Err(malformed(
+                        start - 2,
+                        format!("unexpected marker 0xFF{marker:02X}"),
+                    ))
+                }
+            }
+        }
+
+        let Some(frame) = self.frame.take().filter(|_| scanned) else {
+            return Err(malformed(pos, "no image data before end of image"));
+        };
+        let mut tables = Vec::with_capacity(frame.components.len());
+        for component in &frame.components {
+            tables.push(self.quant_tables[component.quant_table].ok_or_else(|| {
+                malformed(
+                    pos,
+                    format!("quantisation table {} is missing", component.quant_table),
+                )
+            })?);
+        }
+        let basis = idct_basis();
+        let planes = frame
+            .components
+            .iter()
+            .zip(&tables)
+            .map(|(component, table)| component.plane(table, &basis))
+            .collect();
+        Ok(Image { frame, planes })
+    }
+
+    fn read_quant_tables(&mut self, mut segment: &[u8], start: usize) -> Result<(), WasmError> {
+        while let Some((&spec, rest)) = segment.split_first() {
+            let (precision, id) = (spec >> 4, (spec & 0x0F) as usize);
+            let size = if precision == 0 { 64 } else { 128 };
+            if id > 3 || precision > 1 || rest.len() < size {
+                return Err(malformed(start, "invalid quantisation table"));
+            }
+            let mut table = [0u16; 64];
+            for (i, &index) in ZIGZAG.iter().enumerate() {
+                table[index] = match precision {
+                    0 => rest[i] as u16,
+                    _ => u16::from_be_bytes([rest[2 * i], rest[2 * i + 1]]),
+                };
+            }
+            self.quant_tables[id] = Some(table);
+            segment = &rest[size..];
+        }
+        Ok(())
+    }
+
+    fn read_huffman_tables(&mut self, mut segment: &[u8], start: usize) -> Result<(), WasmError> {
+        while segment.len() >= 17 {
+            let (class, id) = (segment[0] >> 4, (segment[0] & 0x0F) as usize);
+            let counts = &segment[1..17];
+            let total: usize = counts.iter().map(|&count| count as usize).sum();
+            if class > 1 || id > 3 || total > 256 || segment.len() < 17 + total {
+                return Err(malformed(start, "invalid Huffman table"));
+            }
+            let table = Huffman::new(counts, segment[17..17 + total].to_vec());
+            match class {
+                0 => self.dc_tables[id] = Some(table),
+                _ => self.ac_tables[id] = Some(table),
+            }
+            segment = &segment[17 + total..];
+        }
+        if !segment.is_empty() {
+            return Err(malformed(start, "invalid Huffman table"));
+        }
+        Ok(())
+    }
+
+    fn read_frame(&mut self, segment: &[u8], start: usize) -> Result<(), WasmError> {
+        if self.frame.is_some() {
+            return Err(malformed(start, "more than one frame"));
+        }
+        let [precision, h1, h0, w1, w0, count, specs @ ..] = segment else {
+            return Err(malformed(start, "frame header is too short"));
+        };
+        if *precision != 8 {
+            return Err(unsupported(&format!("{precision}-bit samples")));
+        }
+        if *count != 3 {
+            return Err(unsupported(&format!("{count} colour components")));
+        }
+        if specs.len() != 9 {
+            return Err(malformed(
+                start,
+                "frame header length does not match its components",
+            ));
+        }
+        let height = u16::from_be_bytes([*h1, *h0]) as usize;
+        let width = u16::from_be_bytes([*w1, *w0]) as usize;
+        if width == 0 || height == 0 {
+            return Err(unsupported("height defined after the scan (DNL)"));
+        }
+        if width * height > MAX_PIXELS {
+            return Err(WasmError::invalid(
+                "JPEG dimensions",
+                format!("{width}x{height} is more than {MAX_PIXELS} pixels"),
+            ));
+        }
+
+        let mut components: Vec<Component> = specs
+            .chunks_exact(3)
+            .map(|spec| Component {
+                id: spec[0],
+                h: (spec[1] >> 4) as usize,
+                v: (spec[1] & 0x0F) as usize,
+                quant_table: spec[2] as usize,
+                ..Component::default()
+            })
+            .collect();
+        if components
+            .iter()
+            .any(|c| !(1..=4).contains(&c.h) || !(1..=4).contains(&c.v) || c.quant_table > 3)
+        {
+            return Err(malformed(start, "invalid component sampling or table"));
+        }
+        let h_max = components.iter().map(|c| c.h).max().unwrap_or(1);
+        let v_max = components.iter().map(|c| c.v).max().unwrap_or(1);
+        if components
+            .iter()
+            .any(|c| h_max % c.h != 0 || v_max % c.v != 0)
+        {
+            return Err(unsupported("fractional chroma subsampling"));
+        }
+
+        let mcus_wide = (width + 8 * h_max - 1) / (8 * h_max);
+        let mcus_high = (height + 8 * v_max - 1) / (8 * v_max);
+        for component in &mut components {
+            component.blocks_wide = mcus_wide * component.h;
+            component.blocks_high = mcus_high * component.v;
+            let blocks = component.blocks_wide * component.blocks_high;
+            component
+                .coefficients
+                .try_reserve_exact(blocks)
+                .map_err(|e| WasmError::ResourceUnavailable {
+                    reason: format!("Failed to allocate a {width}x{height} JPEG: {e}"),
+                })?;
+            component.coefficients.resize(blocks, [0; 64]);
+        }
+        self.frame = Some(Frame {
+            width,
+            height,
+            h_max,
+            v_max,
+            mcus_wide,
+            mcus_high,
+            components,
+        });
+        Ok(())
+    }
+
+    /// Decode the scan whose header is `segment` and whose entropy-coded
+    /// data starts at `pos`, returning the position after it
+    fn read_scan(
+        &mut self,
+        data: &[u8],
+        segment: &[u8],
+        start: usize,
+        pos: usize,
+    ) -> Result<usize, WasmError> {
+        let Some(frame) = self.frame.as_mut() else {
+            return Err(malformed(start, "scan before frame header"));
+        };
+        let Some((&count, rest)) = segment.split_first() else {
+            return Err(malformed(start, "scan header is too short"));
+        };
+        let count = count as usize;
+        if !(1..=frame.components.len()).contains(&count) || rest.len() != 2 * count + 3 {
+            return Err(malformed(
+                start,
+                "scan header length does not match its components",
+            ));
+        }
+        if rest[2 * count..] != [0, 63, 0] {
+            return Err(unsupported(
+                "spectral selection or successive approximation",
+            ));
+        }
+
+        // (component index, DC table, AC table) for each component in the scan
+        let mut members = Vec::with_capacity(count);
+        for spec in rest[..2 * count].chunks_exact(2) {
+            let index = frame
+                .components
+                .iter()
+                .position(|c| c.id == spec[0])
+                .ok_or_else(|| {
+                    malformed(start, format!("scan names unknown component {}", spec[0]))
+                })?;
+            let (dc, ac) = ((spec[1] >> 4) as usize, (spec[1] & 0x0F) as usize);
+            let dc = self.dc_tables.get(dc).and_then(Option::as_ref);
+            let ac = self.ac_tables.get(ac).and_then(Option::as_ref);
+            let (Some(dc), Some(ac)) = (dc, ac) else {
+                return Err(malformed(start, "scan uses a missing Huffman table"));
+            };
+            members.push((index, dc, ac));
+        }
+
+        let mut bits = BitReader::new(data, pos);
+        let mut predictions = vec![0i32; count];
+        // A lone component is coded block by block over just its own area
+        let units = if count == 1 {
+            let c = &frame.components[members[0].0];
+            let wide = (frame.width * c.h / frame.h_max + 7) / 8;
+            let high = (frame.height * c.v / frame.v_max + 7) / 8;
+            (wide, high)
+        } else {
+            (frame.mcus_wide, frame.mcus_high)
+        };
+        for unit in 0..units.0 * units.1 {
+            if self.restart_interval > 0 && unit > 0 && unit % self.restart_interval == 0 {
+                bits.restart()?;
+                predictions.iter_mut().for_each(|p| *p = 0);
+            }
+            let (x, y) = (unit % units.0, unit / units.0);
+            for (member, &(index, dc, ac)) in members.iter().enumerate() {
+                let component = &mut frame.components[index];
+                let (h, v) = if count == 1 {
+                    (1, 1)
+                } else {
+                    (component.h, component.v)
+                };
+                for block_y in y * v..(y + 1) * v {
+                    for block_x in x * h..(x + 1) * h {
+                        let block =
+                            &mut component.coefficients[block_y * component.blocks_wide + block_x];
+                        decode_block(&mut bits, dc, ac, &mut predictions[member], block)?;
+                    }
+                }
+            }
+        }
+        Ok(bits.pos)
+    }
+}
+
+/// Decode one block's coefficients into natural order
+fn decode_block(
+    bits: &mut BitReader,
+    dc: &Huffman,
+    ac: &Huffman,
+    prediction: &mut i32,
+    block: &mut [i16; 64],
+) -> Result<(), WasmError> {
+    let size = dc.decode(bits)?;
+    if size > 11 {
+        return Err(malformed(bits.pos, "DC difference is too large"));
+    }
+    *prediction = prediction
+        .checked_add(bits.signed(size)?)
+        .filter(|dc| i16::try_from(*dc).is_ok())
+        .ok_or_else(|| malformed(bits.pos, "DC coefficient is out of range"))?;
+    block[0] = *prediction as i16;
+
+    let mut k = 1;
+    while k < 64 {
+        let symbol = ac.decode(bits)?;
+        let (run, size) = ((symbol >> 4) as usize, symbol & 0x0F);
+        if size == 0 {
+            if run != 15 {
+                break; // end of block
+            }
+            k += 16;
+            continue;
+        }
+        k += run;
+        if k > 63 || size > 10 {
+            return Err(malformed(bits.pos, "AC coefficients overrun the block"));
+        }
+        block[ZIGZAG[k]] = bits.signed(size)? as i16;
+        k += 1;
+    }
+    Ok(())
+}
+
+/// `basis[x][u]` is the weight of frequency `u` at sample `x` in the 1D
+/// inverse DCT
+fn idct_basis() -> [[f32; 8]; 8] {
+    let mut basis = [[0.0; 8]; 8];
+    for (x, row) in basis.iter_mut().enumerate() {
+        for (u, weight) in row.iter_mut().enumerate() {
+            let scale = if u == 0 { 0.5 / 2f32.sqrt() } else { 0.5 };
+            *weight = scale * ((2 * x + 1) as f32 * u as f32 * PI / 16.0).cos();
+        }
+    }
+    basis
+}
+
+impl Component {
+    /// Dequantise and inverse-transform every block in parallel, returning
+    /// the component's samples
+    fn plane(&self, table: &[u16; 64], basis: &[[f32; 8]; 8]) -> Vec<u8> {
+        let blocks: Vec<[u8; 64]> = self
+            .coefficients
+            .par_iter()
+            .map(|coefficients| {
+                let mut dequantised = [0.0f32; 64];
+                for ((value, &coefficient), &step) in
+                    dequantised.iter_mut().zip(coefficients).zip(table)
+                {
+                    *value = coefficient as f32 * step as f32;
+                }
+                idct(&dequantised, basis)
+            })
+            .collect();
+
+        let stride = self.blocks_wide * 8;
+        let mut plane = vec![0u8; stride * self.blocks_high * 8];
+        for (i, block) in blocks.iter().enumerate() {
+            let (x, y) = (i % self.blocks_wide * 8, i / self.blocks_wide * 8);
+            for (row, samples) in block.chunks_exact(8).enumerate() {
+                let at = (y + row) * stride + x;
+                plane[at..at + 8].copy_from_slice(samples);
+            }
+        }
+        plane
+    }
+}
+
+/// Separable 8x8 inverse DCT, level-shifted back to unsigned samples
+fn idct(coefficients: &[f32; 64], basis: &[[f32; 8]; 8]) -> [u8; 64] {
+    // Columns first: `columns[y * 8 + u]` sums the vertical frequencies
+    let mut columns = [0.0f32; 64];
+    for y in 0..8 {
+        for u in 0..8 {
+            columns[y * 8 + u] = (0..8).map(|v| basis[y][v] * coefficients[v * 8 + u]).sum();
+        }
+    }
+    let mut samples = [0u8; 64];
+    for y in 0..8 {
+        for x in 0..8 {
+            let value: f32 = (0..8).map(|u| basis[x][u] * columns[y * 8 + u]).sum();
+            samples[y * 8 + x] = (value + 128.0).round().clamp(0.0, 255.0) as u8;
+        }
+    }
+    samples
+}
+
+impl Image {
+    /// Component `index` at pixel `(x, y)`. Subsampled components are
+    /// interpolated bilinearly between sample centres, which for 2x
+    /// subsampling gives libjpeg's 3:1 "fancy upsampling" weights.
+    fn sample(&self, index: usize, x: usize, y: usize) -> f32 {
+        let frame = &self.frame;
+        let component = &frame.components[index];
+        let plane = &self.planes[index];
+        let stride = component.blocks_wide * 8;
+        if component.h == frame.h_max && component.v == frame.v_max {
+            return plane[y * stride + x] as f32;
+        }
+        // Interpolate only between samples inside the image, not the padding
+        let wide = (frame.width * component.h + frame.h_max - 1) / frame.h_max;
+        let high = (frame.height * component.v + frame.v_max - 1) / frame.v_max;
+        let scale_x = component.h as f32 / frame.h_max as f32;
+        let scale_y = component.v as f32 / frame.v_max as f32;
+        let locate = |pixel: usize, scale: f32, samples: usize| {
+            let position = ((pixel as f32 + 0.5) * scale - 0.5).clamp(0.0, (samples - 1) as f32);
+            let low = position as usize;
+            (low, (low + 1).min(samples - 1), position - low as f32)
+        };
+        let (x0, x1, fx) = locate(x, scale_x, wide);
+        let (y0, y1, fy) = locate(y, scale_y, high);
+        let at = |sx: usize, sy: usize| plane[sy * stride + sx] as f32;
+        let top = at(x0, y0) + (at(x1, y0) - at(x0, y0)) * fx;
+        let bottom = at(x0, y1) + (at(x1, y1) - at(x0, y1)) * fx;
+        top + (bottom - top) * fy
+    }
+
+    /// Upsample the chroma and convert to RGBA, one output row per task
+    fn to_rgba(&self) -> Vec<u8> {
+        let frame = &self.frame;
+        let mut rgba = vec![0u8; frame.width * frame.height * 4];
+        rgba.par_chunks_exact_mut(frame.width * 4)
+            .enumerate()
+            .for_each(|(y, row)| {
+                let sample = |component: usize, x: usize| self.sample(component, x, y);
+                for (x, pixel) in row.chunks_exact_mut(4).enumerate() {
+                    let luma = sample(0, x);
+                    let (cb, cr) = (sample(1, x) - 128.0, sample(2, x) - 128.0);
+                    let rgb = [
+                        luma + 1.402 * cr,
+                        luma - 0.344_136 * cb - 0.714_136 * cr,
+                        luma + 1.772 * cb,
+                    ];
+                    for (channel, value) in pixel.iter_mut().zip(rgb) {
+                        *channel = value.round().clamp(0.0, 255.0) as u8;
+                    }
+                    pixel[3] = 255;
+                }
+            });
+        rgba
+    }
+}
+
+/// The payload of the marker segment whose length field starts at `pos`
+fn segment(data: &[u8], pos: usize) -> Result<&[u8], WasmError> {
+    let length = match data.get(pos..pos + 2) {
+        Some(&[high, low]) => u16::from_be_bytes([high, low]) as usize,
+        _ => return Err(malformed(pos, "marker segment is truncated")),
+    };
+    if length < 2 {
+        return Err(malformed(pos, "marker segment length is too small"));
+    }
+    data.get(pos + 2..pos + length)
+        .ok_or_else(|| malformed(pos, "marker segment is truncated"))
+}
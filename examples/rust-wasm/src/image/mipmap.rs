@@ -0,0 +1,93 @@
+use rayon::prelude::*;
+use wasm_bindgen::prelude::*;
+
+use super::{check_dimensions, WasmImageProcessor};
+use crate::{install, WasmError};
+
+#[wasm_bindgen]
+impl WasmImageProcessor {
+    /// Build a mipmap pyramid from a power-of-two RGBA image.
+    ///
+    /// Each level halves the one before with a 2x2 box filter, rounding to
+    /// nearest and averaging alpha like the colour channels, until the width
+    /// or height reaches 1. The result opens with a little-endian `u32`
+    /// header: the level count, then `width`, `height` and the byte offset of
+    /// that level's pixels from the start of the result, for each level from
+    /// the full-size image down. The levels follow back to back.
+    #[wasm_bindgen]
+    pub fn generate_mipmaps(
+        &self,
+        rgba_data: &[u8],
+        width: u32,
+        height: u32,
+    ) -> Result<Vec<u8>, WasmError> {
+        let timing = self.profile("generate_mipmaps", rgba_data.len());
+        check_dimensions(rgba_data, width, height)?;
+        if !width.is_power_of_two() || !height.is_power_of_two() {
+            return Err(WasmError::invalid(
+                "mipmap base size",
+                format!("{width}x{height} must be a power of two in each dimension"),
+            ));
+        }
+
+        let mut sizes = vec![(width, height)];
+        while let Some(&(w, h)) = sizes.last().filter(|&&(w, h)| w > 1 && h > 1) {
+            sizes.push((w / 2, h / 2));
+        }
+        let header_len = 4 + 12 * sizes.len();
+        let total_len = header_len
+            + sizes
+                .iter()
+                .map(|&(w, h)| w as usize * h as usize * 4)
+                .sum::<usize>();
+        if u32::try_from(total_len).is_err() {
+            return Err(WasmError::invalid(
+                "mipmap base size",
+                format!("{width}x{height} is too large for 32-bit level offsets"),
+            ));
+        }
+
+        let mut out = Vec::with_capacity(total_len);
+        out.extend_from_slice(&(sizes.len() as u32).to_le_bytes());
+        let mut offset = header_len;
+        for &(w, h) in &sizes {
+            for field in [w, h, offset as u32] {
+                out.extend_from_slice(&field.to_le_bytes());
+            }
+            offset += w as usize * h as usize * 4;
+        }
+
+        out.extend_from_slice(rgba_data);
+        let mut previous_start = header_len;
+        for pair in sizes.windows(2) {
+            let (source_width, (w, h)) = (pair[0].0 as usize, pair[1]);
+            let level = install(&self.runtime, || {
+                downsample(&out[previous_start..], source_width, w as usize, h as usize)
+            })?;
+            previous_start = out.len();
+            out.extend_from_slice(&level);
+        }
+        timing.finish(Ok(out))
+    }
+}
+
+/// Halve an RGBA image `source_width` pixels wide into a `width x height`
+/// one, averaging each 2x2 block; output rows are filled in parallel
+fn downsample(source: &[u8], source_width: usize, width: usize, height: usize) -> Vec<u8> {
+    let mut level = vec![0u8; width * height * 4];
+    level
+        .par_chunks_exact_mut(width * 4)
+        .enumerate()
+        .for_each(|(y, row)| {
+            let top = &source[2 * y * source_width * 4..][..source_width * 4];
+            let bottom = &source[(2 * y + 1) * source_width * 4..][..source_width * 4];
+            for (x, pixel) in row.chunks_exact_mut(4).enumerate() {
+                for (c, value) in pixel.iter_mut().enumerate() {
+                    let at = |row: &[u8], dx: usize| row[(2 * x + dx) * 4 + c] as u32;
+                    let sum = at(top, 0) + at(top, 1) + at(bottom, 0) + at(bottom, 1);
+                    *value = ((sum + 2) / 4) as u8;
+                }
+            }
+        });
+    level
+}
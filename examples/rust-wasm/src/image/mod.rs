@@ -0,0 +1,239 @@
+use rayon::prelude::*;
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    install,
+    profile::{OpScope, OpStats, Profiler},
+    WasmError, WasmRuntime,
+};
+
+mod alpha;
+mod blend;
+mod channels;
+mod color;
+mod components;
+mod dither;
+mod edges;
+mod fill;
+mod filters;
+mod flow;
+mod frame;
+mod integral;
+mod jpeg;
+mod mipmap;
+mod noise;
+mod palette;
+mod pipeline;
+mod stats;
+mod threshold;
+mod thumbnail;
+mod wasm_image;
+
+pub use fill::FloodFill;
+pub use integral::Rect;
+pub use noise::NoiseParams;
+pub use wasm_image::WasmImage;
+
+/// Parallel image processor working on RGBA pixel buffers
+#[wasm_bindgen]
+pub struct WasmImageProcessor {
+    runtime: WasmRuntime,
+    profiler: Profiler,
+    buffer: Vec<u8>,
+    /// Persistent frame for the zero-copy `op_*` workflow; only
+    /// `load_frame` may reallocate it
+    frame: Vec<u8>,
+    frame_width: u32,
+    frame_height: u32,
+    /// Per-pixel ops queued by `pipeline_add`
+    pipeline: Vec<pipeline::PixelOp>,
+}
+
+#[wasm_bindgen]
+impl WasmImageProcessor {
+    /// Create a processor on the shared runtime of `num_threads` workers
+    /// (see [`WasmRuntime::shared`])
+    #[wasm_bindgen(constructor)]
+    pub fn new(num_threads: usize) -> Result<WasmImageProcessor, WasmError> {
+        Ok(WasmImageProcessor::with_runtime(&WasmRuntime::shared(
+            num_threads,
+        )?))
+    }
+
+    /// Create a processor sharing `runtime`'s thread pool
+    #[wasm_bindgen]
+    pub fn with_runtime(runtime: &WasmRuntime) -> WasmImageProcessor {
+        WasmImageProcessor {
+            runtime: runtime.clone(),
+            profiler: Profiler::default(),
+            buffer: Vec::new(),
+            frame: Vec::new(),
+            frame_width: 0,
+            frame_height: 0,
+            pipeline: Vec::new(),
+        }
+    }
+
+    /// The runtime whose thread pool this processor uses
+    #[wasm_bindgen(getter)]
+    pub fn runtime(&self) -> WasmRuntime {
+        self.runtime.clone()
+    }
+
+    /// Workers available to this processor, which change if its runtime is
+    /// resized
+    #[wasm_bindgen]
+    pub fn current_num_threads(&self) -> usize {
+        self.runtime.num_threads()
+    }
+
+    /// Time this processor's operations for `last_op_stats`, forgetting
+    /// the last operation's stats either way
+    #[wasm_bindgen]
+    pub fn set_profiling(&mut self, enabled: bool) {
+        self.profiler.set_enabled(enabled);
+    }
+
+    /// What the last operation run while profiling did, as `{ operation,
+    /// wall_ms, input_elements, pool_threads, bytes_allocated }`, or
+    /// `undefined` if none has run.
+    ///
+    /// `wall_ms` covers the operation inside the module, not copying its
+    /// arguments and result across the JavaScript boundary,
+    /// `pool_threads` is the size of the pool its parallel work ran on, not
+    /// how many of those workers took a share, and `bytes_allocated` is the
+    /// capacity of the buffers it returned.
+    #[wasm_bindgen]
+    pub fn last_op_stats(&self) -> JsValue {
+        self.last_op()
+            .map_or(JsValue::UNDEFINED, |stats| stats.to_js())
+    }
+
+    /// Bytes reserved by the processor's buffers: the output buffer, the
+    /// persistent frame and the queued pipeline with its lookup tables
+    #[wasm_bindgen]
+    pub fn heap_bytes(&self) -> usize {
+        let tables = self
+            .pipeline
+            .iter()
+            .filter(|op| matches!(op, pipeline::PixelOp::Lookup(_)))
+            .count();
+        self.buffer.capacity()
+            + self.frame.capacity()
+            + self.pipeline.capacity() * std::mem::size_of::<pipeline::PixelOp>()
+            + tables * 256
+    }
+
+    /// Convert RGBA pixels to grayscale using the standard luminance formula
+    #[wasm_bindgen]
+    pub fn grayscale(&mut self, rgba_data: &[u8]) -> Result<Vec<u8>, WasmError> {
+        let timing = self.profile("grayscale", rgba_data.len());
+        self.load(rgba_data);
+        grayscale_in_place(&self.runtime, &mut self.buffer)?;
+        timing.finish(Ok(self.buffer.clone()))
+    }
+
+    /// Scale the RGB channels by `brightness`, keeping alpha unchanged
+    #[wasm_bindgen]
+    pub fn adjust_brightness(
+        &mut self,
+        rgba_data: &[u8],
+        brightness: f32,
+    ) -> Result<Vec<u8>, WasmError> {
+        let timing = self.profile("adjust_brightness", rgba_data.len());
+        self.load(rgba_data);
+        brightness_in_place(&self.runtime, &mut self.buffer, brightness)?;
+        timing.finish(Ok(self.buffer.clone()))
+    }
+}
+
+impl WasmImageProcessor {
+    /// Stats of the last operation run while profiling, as
+    /// [`WasmImageProcessor::last_op_stats`] reports them
+    pub fn last_op(&self) -> Option<OpStats> {
+        self.profiler.last()
+    }
+
+    /// Profile `operation` on `input_elements` values until the scope is
+    /// finished or dropped
+    fn profile(&self, operation: &'static str, input_elements: usize) -> OpScope {
+        self.profiler.scope(operation, input_elements)
+    }
+
+    /// Copy the input into the reusable internal buffer
+    fn load(&mut self, rgba_data: &[u8]) {
+        self.buffer.clear();
+        self.buffer.extend_from_slice(rgba_data);
+    }
+}
+
+/// Replace RGB with Rec. 601 luminance, in place
+fn grayscale_in_place(runtime: &WasmRuntime, rgba: &mut [u8]) -> Result<(), WasmError> {
+    install(runtime, || {
+        rgba.par_chunks_exact_mut(4).for_each(grayscale_pixel);
+    })
+}
+
+/// Scale RGB by `brightness`, in place
+fn brightness_in_place(
+    runtime: &WasmRuntime,
+    rgba: &mut [u8],
+    brightness: f32,
+) -> Result<(), WasmError> {
+    install(runtime, || {
+        rgba.par_chunks_exact_mut(4)
+            .for_each(|pixel| brightness_pixel(pixel, brightness));
+    })
+}
+
+/// Replace a pixel's RGB with its Rec. 601 luminance
+fn grayscale_pixel(pixel: &mut [u8]) {
+    let gray = luminance(pixel) as u8;
+    pixel[0] = gray;
+    pixel[1] = gray;
+    pixel[2] = gray;
+}
+
+/// Scale a pixel's RGB by `brightness`
+fn brightness_pixel(pixel: &mut [u8], brightness: f32) {
+    for value in pixel.iter_mut().take(3) {
+        *value = (*value as f32 * brightness).clamp(0.0, 255.0) as u8;
+    }
+}
+
+/// Validate that an RGBA buffer matches the given dimensions
+fn check_dimensions(rgba_data: &[u8], width: u32, height: u32) -> Result<(), WasmError> {
+    let expected = image_len(width, height, 4)?;
+    if rgba_data.len() != expected {
+        return Err(WasmError::dimension(
+            format!("Bytes for a {width}x{height} RGBA image"),
+            expected,
+            rgba_data.len(),
+        ));
+    }
+    Ok(())
+}
+
+/// Bytes in a `width x height` image of `channels` bytes per pixel, or an
+/// error if that overflows `usize`
+fn image_len(width: u32, height: u32, channels: usize) -> Result<usize, WasmError> {
+    (width as usize)
+        .checked_mul(height as usize)
+        .and_then(|pixels| pixels.checked_mul(channels))
+        .ok_or_else(|| {
+            WasmError::invalid(
+                "image size",
+                format!("a {width}x{height} image overflows its size"),
+            )
+        })
+}
+
+/// Rec. 601 luma of an RGB(A) pixel
+fn luminance(pixel: &[u8]) -> f32 {
+    0.299 * pixel[0] as f32 + 0.587 * pixel[1] as f32 + 0.114 * pixel[2] as f32
+}
+
+/// Rec. 601 luma of an RGB(A) pixel rounded to a byte
+fn luma(pixel: &[u8]) -> u8 {
+    luminance(pixel).round() as u8
+}
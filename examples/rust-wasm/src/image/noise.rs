@@ -0,0 +1,257 @@
+use rayon::prelude::*;
+use wasm_bindgen::prelude::*;
+
+use super::{image_len, WasmImageProcessor};
+use crate::{install, WasmError};
+
+/// Shape of the fractal noise made by
+/// [`WasmImageProcessor::generate_perlin_noise`]
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct NoiseParams {
+    /// Size of the base noise cell in pixels
+    pub scale: f64,
+    /// Layers of noise summed together
+    pub octaves: u8,
+    /// Amplitude multiplier from one octave to the next
+    pub persistence: f64,
+    /// Frequency multiplier from one octave to the next
+    pub lacunarity: f64,
+    /// The same seed always yields the same noise
+    pub seed: u32,
+}
+
+#[wasm_bindgen]
+impl NoiseParams {
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        scale: f64,
+        octaves: u8,
+        persistence: f64,
+        lacunarity: f64,
+        seed: u32,
+    ) -> NoiseParams {
+        NoiseParams {
+            scale,
+            octaves,
+            persistence,
+            lacunarity,
+            seed,
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl WasmImageProcessor {
+    /// Fractal Perlin noise as a `width * height` greyscale image.
+    ///
+    /// Each of the `params.octaves` layers multiplies the frequency by
+    /// `params.lacunarity` and the amplitude by `params.persistence`.
+    #[wasm_bindgen]
+    pub fn generate_perlin_noise(
+        &self,
+        width: u32,
+        height: u32,
+        params: &NoiseParams,
+    ) -> Result<Vec<u8>, WasmError> {
+        let timing = self.profile("generate_perlin_noise", 0);
+        let fbm = FractalNoise::new(params)?;
+        let mut output = vec![0u8; image_len(width, height, 1)?];
+        let width = width as usize;
+        if width == 0 {
+            return Ok(output);
+        }
+
+        install(&self.runtime, || {
+            output
+                .par_chunks_mut(width)
+                .enumerate()
+                .for_each(|(y, row)| {
+                    for (x, value) in row.iter_mut().enumerate() {
+                        *value = (fbm.sample(x as f64, y as f64) * 255.0).round() as u8;
+                    }
+                });
+        })?;
+
+        timing.finish(Ok(output))
+    }
+
+    /// Fractal Perlin noise mapped through a colour gradient, as RGBA.
+    ///
+    /// `stops` holds at least two RGBA colours (four bytes each) spaced evenly
+    /// from noise value 0 to 1, with linear interpolation in between.
+    #[wasm_bindgen]
+    pub fn generate_perlin_noise_rgba(
+        &self,
+        width: u32,
+        height: u32,
+        params: &NoiseParams,
+        stops: &[u8],
+    ) -> Result<Vec<u8>, WasmError> {
+        let timing = self.profile("generate_perlin_noise_rgba", stops.len());
+        if stops.len() < 8 || stops.len() % 4 != 0 {
+            return Err(WasmError::invalid(
+                "gradient stops",
+                "needs at least two RGBA colour stops",
+            ));
+        }
+        image_len(width, height, 4)?;
+
+        let noise = self.generate_perlin_noise(width, height, params)?;
+        let gradient = gradient_table(stops);
+
+        timing.finish(install(&self.runtime, || {
+            noise
+                .par_iter()
+                .flat_map_iter(|&value| gradient[value as usize])
+                .collect()
+        }))
+    }
+}
+
+/// Seeded 2D Perlin noise summed over several octaves
+struct FractalNoise {
+    /// Permutation of `0..256`, repeated so lookups never wrap
+    permutation: [u8; 512],
+    gradients: [(f64, f64); 256],
+    scale: f64,
+    octaves: u8,
+    persistence: f64,
+    lacunarity: f64,
+}
+
+impl FractalNoise {
+    fn new(params: &NoiseParams) -> Result<FractalNoise, WasmError> {
+        let NoiseParams {
+            scale,
+            octaves,
+            persistence,
+            lacunarity,
+            seed,
+        } = *params;
+        if !scale.is_finite() || scale <= 0.0 {
+            return Err(WasmError::invalid(
+                "noise scale",
+                "must be a positive number",
+            ));
+        }
+        if octaves == 0 {
+            return Err(WasmError::invalid(
+                "octaves",
+                "noise needs at least one octave",
+            ));
+        }
+        if !persistence.is_finite() || !lacunarity.is_finite() || lacunarity <= 0.0 {
+            return Err(WasmError::invalid(
+                "noise parameters",
+                "persistence must be finite and lacunarity a positive number",
+            ));
+        }
+
+        // xorshift64, offset so that seed 0 does not stall the generator
+        let mut state = seed as u64 ^ 0x9E37_79B9_7F4A_7C15;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+
+        let mut shuffled: Vec<u8> = (0..=255).collect();
+        for i in (1..shuffled.len()).rev() {
+            shuffled.swap(i, (next() % (i as u64 + 1)) as usize);
+        }
+        let mut permutation = [0u8; 512];
+        for (i, entry) in permutation.iter_mut().enumerate() {
+            *entry = shuffled[i % 256];
+        }
+
+        let mut gradients = [(0.0, 0.0); 256];
+        for gradient in gradients.iter_mut() {
+            let angle = (next() >> 11) as f64 / (1u64 << 53) as f64 * std::f64::consts::TAU;
+            *gradient = (angle.cos(), angle.sin());
+        }
+
+        Ok(FractalNoise {
+            permutation,
+            gradients,
+            scale,
+            octaves,
+            persistence,
+            lacunarity,
+        })
+    }
+
+    /// Fractional Brownian motion at pixel `(x, y)`, normalised to `0..=1`
+    fn sample(&self, x: f64, y: f64) -> f64 {
+        let mut total = 0.0;
+        let mut amplitude = 1.0;
+        let mut max_amplitude = 0.0;
+        let mut frequency = 1.0 / self.scale;
+
+        for _ in 0..self.octaves {
+            total += self.noise(x * frequency, y * frequency) * amplitude;
+            max_amplitude += amplitude;
+            amplitude *= self.persistence;
+            frequency *= self.lacunarity;
+        }
+
+        // Unit gradients keep 2D Perlin noise within ±√½
+        let normalised = total / max_amplitude * std::f64::consts::SQRT_2;
+        if normalised.is_finite() {
+            (normalised * 0.5 + 0.5).clamp(0.0, 1.0)
+        } else {
+            0.5
+        }
+    }
+
+    /// Single-octave Perlin noise in roughly `-√½..=√½`
+    fn noise(&self, x: f64, y: f64) -> f64 {
+        let (x0, y0) = (x.floor(), y.floor());
+        let (dx, dy) = (x - x0, y - y0);
+        let cell_x = x0.rem_euclid(256.0) as usize;
+        let cell_y = y0.rem_euclid(256.0) as usize;
+
+        let corner = |ox: usize, oy: usize| {
+            let hash =
+                self.permutation[self.permutation[cell_x + ox] as usize + cell_y + oy] as usize;
+            let (gx, gy) = self.gradients[hash];
+            gx * (dx - ox as f64) + gy * (dy - oy as f64)
+        };
+
+        let (u, v) = (fade(dx), fade(dy));
+        let top = lerp(corner(0, 0), corner(1, 0), u);
+        let bottom = lerp(corner(0, 1), corner(1, 1), u);
+        lerp(top, bottom, v)
+    }
+}
+
+/// Perlin's quintic smoothstep `6t^5 - 15t^4 + 10t^3`
+fn fade(t: f64) -> f64 {
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+fn lerp(a: f64, b: f64, t: f64) -> f64 {
+    a + (b - a) * t
+}
+
+/// Expand evenly spaced RGBA stops into a 256-entry colour table
+fn gradient_table(stops: &[u8]) -> Vec<[u8; 4]> {
+    let segments = (stops.len() / 4 - 1) as f32;
+
+    (0..256)
+        .map(|value| {
+            let position = value as f32 / 255.0 * segments;
+            let index = (position.floor() as usize).min(segments as usize - 1);
+            let t = position - index as f32;
+            let from = &stops[index * 4..index * 4 + 4];
+            let to = &stops[index * 4 + 4..index * 4 + 8];
+
+            let mut color = [0u8; 4];
+            for (channel, (&a, &b)) in color.iter_mut().zip(from.iter().zip(to)) {
+                *channel = (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+            }
+            color
+        })
+        .collect()
+}
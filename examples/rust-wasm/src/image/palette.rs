@@ -0,0 +1,187 @@
+use rayon::prelude::*;
+use wasm_bindgen::prelude::*;
+
+use super::WasmImageProcessor;
+use crate::{install, parallel::SplitMix64, WasmError};
+
+/// Most pixels clustered; larger images are sampled at an even stride
+const MAX_SAMPLES: usize = 10_000;
+
+/// Most colours `extract_dominant_colors` returns
+const MAX_COLORS: u16 = 256;
+
+/// Seed for the k-means++ draws, fixed so a palette is reproducible
+const SEED: u64 = 0x5EED_C010_u64;
+
+type Rgba = [f32; 4];
+
+#[wasm_bindgen]
+impl WasmImageProcessor {
+    /// The `k` most dominant colours of an image, as flat RGBA, most common
+    /// first.
+    ///
+    /// Pixels are grouped by k-means over all four channels, seeded with
+    /// k-means++ and run for up to `max_iterations` rounds or until no pixel
+    /// changes cluster. Images over 10,000 pixels are sampled at an even
+    /// stride first. Each colour is its cluster's mean, and clusters are
+    /// ordered by size. An image with fewer than `k` distinct colours gives
+    /// one entry per colour. `k` must be from 1 to 256.
+    #[wasm_bindgen]
+    pub fn extract_dominant_colors(
+        &self,
+        rgba_data: &[u8],
+        k: u16,
+        max_iterations: u32,
+    ) -> Result<Vec<u8>, WasmError> {
+        let timing = self.profile("extract_dominant_colors", rgba_data.len());
+        if k == 0 || k > MAX_COLORS {
+            return Err(WasmError::invalid(
+                "k",
+                format!("must be from 1 to {MAX_COLORS}, got {k}"),
+            ));
+        }
+        if rgba_data.len() % 4 != 0 {
+            return Err(WasmError::invalid(
+                "pixel data",
+                format!("length must be a multiple of 4, got {}", rgba_data.len()),
+            ));
+        }
+
+        let pixels = rgba_data.len() / 4;
+        let stride = ((pixels + MAX_SAMPLES - 1) / MAX_SAMPLES).max(1);
+        let points: Vec<Rgba> = rgba_data
+            .chunks_exact(4)
+            .step_by(stride)
+            .map(|p| [p[0], p[1], p[2], p[3]].map(f32::from))
+            .collect();
+
+        let (centroids, sizes) = install(&self.runtime, || {
+            let mut centroids = seed_centroids(&points, usize::from(k));
+            let mut assignment = assign(&points, &centroids);
+            for _ in 0..max_iterations {
+                centroids = means(&points, &assignment, &centroids);
+                let next = assign(&points, &centroids);
+                if next == assignment {
+                    break;
+                }
+                assignment = next;
+            }
+            let sizes = cluster_sizes(&assignment, centroids.len());
+            (centroids, sizes)
+        })?;
+
+        let mut order: Vec<usize> = (0..centroids.len()).filter(|&c| sizes[c] > 0).collect();
+        order.sort_by_key(|&c| std::cmp::Reverse(sizes[c]));
+        timing.finish(Ok(order
+            .into_iter()
+            .flat_map(|c| centroids[c].map(|channel| channel.round() as u8))
+            .collect()))
+    }
+}
+
+/// Up to `k` initial centroids by k-means++: the first is a random point,
+/// and each next one a point drawn with probability proportional to its
+/// squared distance from the nearest centroid so far. Stops early once
+/// every point coincides with a centroid.
+fn seed_centroids(points: &[Rgba], k: usize) -> Vec<Rgba> {
+    let mut centroids = Vec::with_capacity(k);
+    if points.is_empty() {
+        return centroids;
+    }
+    let mut rng = SplitMix64::new(SEED);
+    centroids.push(points[rng.below(points.len())]);
+    let mut nearest: Vec<f32> = points
+        .par_iter()
+        .map(|p| distance2(p, &centroids[0]))
+        .collect();
+
+    while centroids.len() < k {
+        let total: f64 = nearest.par_iter().map(|&d| f64::from(d)).sum();
+        if total == 0.0 {
+            break;
+        }
+        // Uniform in [0, total) from the top 53 bits
+        let mut target = (rng.next() >> 11) as f64 / (1u64 << 53) as f64 * total;
+        let mut chosen = points.len() - 1;
+        for (i, &d) in nearest.iter().enumerate() {
+            target -= f64::from(d);
+            if target < 0.0 {
+                chosen = i;
+                break;
+            }
+        }
+        let centroid = points[chosen];
+        centroids.push(centroid);
+        nearest
+            .par_iter_mut()
+            .zip(points)
+            .for_each(|(d, p)| *d = d.min(distance2(p, &centroid)));
+    }
+    centroids
+}
+
+/// E-step: the index of each point's nearest centroid
+fn assign(points: &[Rgba], centroids: &[Rgba]) -> Vec<usize> {
+    points
+        .par_iter()
+        .map(|p| {
+            (0..centroids.len())
+                .min_by(|&a, &b| {
+                    distance2(p, &centroids[a]).total_cmp(&distance2(p, &centroids[b]))
+                })
+                .unwrap_or(0)
+        })
+        .collect()
+}
+
+/// M-step: each cluster's mean, keeping the old centroid of an empty one
+fn means(points: &[Rgba], assignment: &[usize], centroids: &[Rgba]) -> Vec<Rgba> {
+    let k = centroids.len();
+    let sums = points
+        .par_iter()
+        .zip(assignment)
+        .fold(
+            || vec![[0.0f64; 5]; k],
+            |mut sums, (p, &c)| {
+                for (sum, &channel) in sums[c].iter_mut().zip(p) {
+                    *sum += f64::from(channel);
+                }
+                sums[c][4] += 1.0;
+                sums
+            },
+        )
+        .reduce(
+            || vec![[0.0f64; 5]; k],
+            |mut total, sums| {
+                for (t, s) in total.iter_mut().zip(sums) {
+                    for (t, s) in t.iter_mut().zip(s) {
+                        *t += s;
+                    }
+                }
+                total
+            },
+        );
+
+    sums.iter()
+        .zip(centroids)
+        .map(|(sum, &old)| {
+            if sum[4] == 0.0 {
+                old
+            } else {
+                [0, 1, 2, 3].map(|channel| (sum[channel] / sum[4]) as f32)
+            }
+        })
+        .collect()
+}
+
+fn cluster_sizes(assignment: &[usize], k: usize) -> Vec<usize> {
+    let mut sizes = vec![0; k];
+    for &c in assignment {
+        sizes[c] += 1;
+    }
+    sizes
+}
+
+fn distance2(a: &Rgba, b: &Rgba) -> f32 {
+    a.iter().zip(b).map(|(x, y)| (x - y) * (x - y)).sum()
+}
@@ -0,0 +1,183 @@
+use js_sys::{Array, Float32Array};
+use rayon::prelude::*;
+use wasm_bindgen::prelude::*;
+
+use super::{
+    brightness_pixel,
+    filters::{color_matrix, contrast_matrix, invert_matrix, matrix_pixel},
+    grayscale_pixel, WasmImageProcessor,
+};
+use crate::{install, WasmError};
+
+/// Ops that need neighbouring pixels and therefore cannot be fused
+const SPATIAL_OPS: [&str; 4] = ["blur", "sharpen", "dither", "adaptive_threshold"];
+
+/// A per-pixel step of a fused filter pipeline
+pub(super) enum PixelOp {
+    Grayscale,
+    Brightness(f32),
+    Matrix([f32; 20]),
+    /// Per-channel RGB lookup table, e.g. for gamma
+    Lookup(Box<[u8; 256]>),
+}
+
+impl PixelOp {
+    const SUPPORTED: &'static str = "grayscale, brightness, contrast, gamma, invert, color_matrix";
+
+    /// Build an op from its name and JavaScript parameters; `step` numbers
+    /// the op in errors
+    fn parse(op: &str, params: &JsValue, step: usize) -> Result<PixelOp, WasmError> {
+        let name = format!("pipeline step {step} ('{op}')");
+        let invalid = |reason: String| WasmError::invalid(name.clone(), reason);
+        match op {
+            "grayscale" => {
+                if !(params.is_undefined() || params.is_null()) {
+                    return Err(invalid("grayscale takes no parameters".to_string()));
+                }
+                Ok(PixelOp::Grayscale)
+            }
+            "brightness" => Ok(PixelOp::Brightness(
+                number(params, "brightness").map_err(invalid)?,
+            )),
+            "contrast" => Ok(PixelOp::Matrix(contrast_matrix(
+                number(params, "contrast").map_err(invalid)?,
+            ))),
+            "gamma" => {
+                let gamma = number(params, "gamma").map_err(invalid)?;
+                if gamma <= 0.0 {
+                    return Err(invalid(format!("gamma must be positive, got {gamma}")));
+                }
+                Ok(PixelOp::Lookup(Box::new(gamma_table(gamma))))
+            }
+            "invert" => {
+                let amount = if params.is_undefined() {
+                    1.0
+                } else {
+                    number(params, "invert").map_err(invalid)?
+                };
+                Ok(PixelOp::Matrix(invert_matrix(amount)))
+            }
+            "color_matrix" => {
+                let values = matrix_values(params).map_err(invalid)?;
+                color_matrix(&values).map(PixelOp::Matrix)
+            }
+            _ if SPATIAL_OPS.contains(&op) => Err(invalid(
+                "reads neighbouring pixels and cannot be fused; run it before or after the pipeline"
+                    .to_string(),
+            )),
+            _ => Err(WasmError::unsupported("pipeline op", op, Self::SUPPORTED)),
+        }
+    }
+
+    fn apply(&self, pixel: &mut [u8]) {
+        match self {
+            PixelOp::Grayscale => grayscale_pixel(pixel),
+            PixelOp::Brightness(brightness) => brightness_pixel(pixel, *brightness),
+            PixelOp::Matrix(matrix) => matrix_pixel(pixel, matrix),
+            PixelOp::Lookup(table) => {
+                for value in pixel.iter_mut().take(3) {
+                    *value = table[*value as usize];
+                }
+            }
+        }
+    }
+}
+
+/// Fused per-pixel filter pipelines.
+///
+/// Queue ops with `pipeline_add`, then `pipeline_run` applies all of them to
+/// each pixel in a single parallel pass. Results are identical to calling the
+/// individual filters in sequence, since every step still rounds to bytes.
+/// Spatial ops such as blur are rejected rather than silently splitting the
+/// pass. The pipeline is kept after a run so it can be reused across frames.
+#[wasm_bindgen]
+impl WasmImageProcessor {
+    /// Start a new, empty pipeline
+    #[wasm_bindgen]
+    pub fn pipeline_begin(&mut self) {
+        self.pipeline.clear();
+    }
+
+    /// Append an op to the pipeline.
+    ///
+    /// `grayscale` takes no parameters; `brightness`, `contrast` and `gamma`
+    /// take a number; `invert` takes an optional amount (default 1);
+    /// `color_matrix` takes 20 numbers as an `Array` or `Float32Array`.
+    #[wasm_bindgen]
+    pub fn pipeline_add(&mut self, op: &str, params: &JsValue) -> Result<(), WasmError> {
+        let op = PixelOp::parse(op, params, self.pipeline.len() + 1)?;
+        self.pipeline.push(op);
+        Ok(())
+    }
+
+    /// Number of ops queued in the pipeline
+    #[wasm_bindgen(getter)]
+    pub fn pipeline_length(&self) -> usize {
+        self.pipeline.len()
+    }
+
+    /// Run every queued op over `rgba` in one parallel pass
+    #[wasm_bindgen]
+    pub fn pipeline_run(&mut self, rgba: &[u8]) -> Result<Vec<u8>, WasmError> {
+        let timing = self.profile("pipeline_run", rgba.len());
+        if rgba.len() % 4 != 0 {
+            return Err(WasmError::invalid(
+                "pixel data",
+                format!("length must be a multiple of 4, got {}", rgba.len()),
+            ));
+        }
+        self.load(rgba);
+
+        let ops = &self.pipeline;
+        let buffer = &mut self.buffer;
+        install(&self.runtime, || {
+            buffer.par_chunks_exact_mut(4).for_each(|pixel| {
+                for op in ops {
+                    op.apply(pixel);
+                }
+            });
+        })?;
+
+        timing.finish(Ok(self.buffer.clone()))
+    }
+}
+
+/// Read a single finite number parameter
+fn number(params: &JsValue, op: &str) -> Result<f32, String> {
+    match params.as_f64() {
+        Some(value) if value.is_finite() => Ok(value as f32),
+        _ => Err(format!("{op} expects a finite number")),
+    }
+}
+
+/// Read colour matrix entries from an `Array` or `Float32Array`
+fn matrix_values(params: &JsValue) -> Result<Vec<f32>, String> {
+    if let Some(values) = params.dyn_ref::<Float32Array>() {
+        return Ok(values.to_vec());
+    }
+    if !Array::is_array(params) {
+        return Err("color_matrix expects an Array or Float32Array of 20 numbers".to_string());
+    }
+
+    Array::from(params)
+        .iter()
+        .enumerate()
+        .map(|(i, value)| {
+            value
+                .as_f64()
+                .map(|value| value as f32)
+                .ok_or_else(|| format!("color_matrix entry {i} is not a number"))
+        })
+        .collect()
+}
+
+/// Lookup table for `255 * (v / 255) ^ (1 / gamma)`, so gamma > 1 brightens
+fn gamma_table(gamma: f32) -> [u8; 256] {
+    let mut table = [0; 256];
+    for (value, entry) in table.iter_mut().enumerate() {
+        *entry = (255.0 * (value as f32 / 255.0).powf(1.0 / gamma))
+            .round()
+            .clamp(0.0, 255.0) as u8;
+    }
+    table
+}
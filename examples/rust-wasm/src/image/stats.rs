@@ -0,0 +1,128 @@
+use rayon::prelude::*;
+use wasm_bindgen::prelude::*;
+
+use super::WasmImageProcessor;
+use crate::{
+    install,
+    parallel::{tree_merge, FIXED_CHUNK},
+    WasmError,
+};
+
+/// Running statistics of one channel, merged across threads
+#[derive(Clone, Copy, Debug)]
+struct Welford {
+    count: u64,
+    mean: f64,
+    /// Sum of squared differences from the mean
+    m2: f64,
+    min: u8,
+    max: u8,
+}
+
+impl Default for Welford {
+    fn default() -> Self {
+        Welford {
+            count: 0,
+            mean: 0.0,
+            m2: 0.0,
+            min: u8::MAX,
+            max: u8::MIN,
+        }
+    }
+}
+
+impl Welford {
+    fn push(&mut self, value: u8) {
+        let x = f64::from(value);
+        self.count += 1;
+        let delta = x - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (x - self.mean);
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+    }
+
+    /// Chan et al.'s combination of two partial results
+    fn merge(self, other: Welford) -> Welford {
+        if self.count == 0 {
+            return other;
+        }
+        if other.count == 0 {
+            return self;
+        }
+        let count = self.count + other.count;
+        let delta = other.mean - self.mean;
+        let weight = other.count as f64 / count as f64;
+        Welford {
+            count,
+            mean: self.mean + delta * weight,
+            m2: self.m2 + other.m2 + delta * delta * self.count as f64 * weight,
+            min: self.min.min(other.min),
+            max: self.max.max(other.max),
+        }
+    }
+
+    /// `[mean, std, min, max]`, with the population standard deviation
+    fn summary(&self) -> [f64; 4] {
+        let std = (self.m2.max(0.0) / self.count as f64).sqrt();
+        [self.mean, std, f64::from(self.min), f64::from(self.max)]
+    }
+}
+
+#[wasm_bindgen]
+impl WasmImageProcessor {
+    /// The mean, standard deviation, minimum and maximum of each channel,
+    /// as 16 values: `[r_mean, r_std, r_min, r_max, g_mean, …, a_max]`.
+    ///
+    /// All four channels are gathered in one parallel pass. Each task keeps
+    /// Welford's running mean and sum of squares, and the partial results
+    /// are combined pairwise, which stays accurate where summing squares
+    /// would not. The deviation is the population one, dividing by the
+    /// pixel count. An image needs at least one pixel.
+    ///
+    /// On a deterministic runtime (see [`crate::WasmRuntime::set_deterministic`])
+    /// the tasks take fixed runs of pixels and are combined in a fixed tree
+    /// order, so the result is the same bit for bit whatever the pool.
+    #[wasm_bindgen]
+    pub fn image_statistics(&self, rgba_data: &[u8]) -> Result<Vec<f64>, WasmError> {
+        let timing = self.profile("image_statistics", rgba_data.len());
+        if rgba_data.is_empty() || rgba_data.len() % 4 != 0 {
+            return Err(WasmError::invalid(
+                "pixel data",
+                format!(
+                    "length must be a positive multiple of 4, got {}",
+                    rgba_data.len()
+                ),
+            ));
+        }
+
+        let merge = |a: [Welford; 4], b: [Welford; 4]| [0, 1, 2, 3].map(|c| a[c].merge(b[c]));
+        let deterministic = self.runtime.deterministic();
+        let channels = install(&self.runtime, || {
+            if deterministic {
+                let partials: Vec<[Welford; 4]> = rgba_data
+                    .par_chunks(4 * FIXED_CHUNK)
+                    .map(|run| {
+                        run.chunks_exact(4)
+                            .fold([Welford::default(); 4], push_pixel)
+                    })
+                    .collect();
+                return tree_merge(&partials, &merge).unwrap_or_default();
+            }
+            rgba_data
+                .par_chunks_exact(4)
+                .fold(|| [Welford::default(); 4], push_pixel)
+                .reduce(|| [Welford::default(); 4], merge)
+        })?;
+
+        timing.finish(Ok(channels.iter().flat_map(Welford::summary).collect()))
+    }
+}
+
+/// Add one RGBA pixel to the running statistics of its channels
+fn push_pixel(mut channels: [Welford; 4], pixel: &[u8]) -> [Welford; 4] {
+    for (channel, &value) in channels.iter_mut().zip(pixel) {
+        channel.push(value);
+    }
+    channels
+}
@@ -0,0 +1,154 @@
+use rayon::prelude::*;
+use wasm_bindgen::prelude::*;
+
+use super::{
+    check_dimensions,
+    integral::{rectangle_sum, summed_area_table},
+    luma, WasmImageProcessor,
+};
+use crate::{install, WasmError};
+
+#[wasm_bindgen]
+impl WasmImageProcessor {
+    /// Binarise the image: pixels with luminance `>= value` become white,
+    /// everything else black. Alpha is preserved.
+    #[wasm_bindgen]
+    pub fn threshold(&mut self, rgba: &[u8], value: u8) -> Result<Vec<u8>, WasmError> {
+        let timing = self.profile("threshold", rgba.len());
+        self.load(rgba);
+
+        let buffer = &mut self.buffer;
+        install(&self.runtime, || {
+            buffer.par_chunks_exact_mut(4).for_each(|pixel| {
+                let level = if luma(pixel) >= value { 255 } else { 0 };
+                pixel[..3].fill(level);
+            });
+        })?;
+
+        timing.finish(Ok(self.buffer.clone()))
+    }
+
+    /// Otsu's optimal global threshold, suitable for passing to
+    /// [`WasmImageProcessor::threshold`].
+    ///
+    /// Maximises the between-class variance of the luminance histogram, where
+    /// the background class is every level below the returned value.
+    #[wasm_bindgen]
+    pub fn otsu_threshold(&mut self, rgba: &[u8]) -> Result<u8, WasmError> {
+        let timing = self.profile("otsu_threshold", rgba.len());
+        let histogram = install(&self.runtime, || luma_histogram(rgba))?;
+
+        let total: u64 = histogram.iter().sum();
+        let weighted_total: f64 = histogram
+            .iter()
+            .enumerate()
+            .map(|(level, &count)| level as f64 * count as f64)
+            .sum();
+
+        let mut best = (0u8, f64::MIN);
+        let mut background = 0u64;
+        let mut weighted_background = 0.0;
+        for level in 1..256 {
+            background += histogram[level - 1];
+            weighted_background += (level - 1) as f64 * histogram[level - 1] as f64;
+
+            let foreground = total - background;
+            if background == 0 || foreground == 0 {
+                continue;
+            }
+
+            let mean_background = weighted_background / background as f64;
+            let mean_foreground = (weighted_total - weighted_background) / foreground as f64;
+            let variance =
+                background as f64 * foreground as f64 * (mean_background - mean_foreground).powi(2);
+
+            if variance > best.1 {
+                best = (level as u8, variance);
+            }
+        }
+
+        timing.finish(Ok(best.0))
+    }
+
+    /// Binarise against the mean luminance of the `block_size` x `block_size`
+    /// neighbourhood minus `c`, using a summed-area table so every window
+    /// costs O(1).
+    #[wasm_bindgen]
+    pub fn adaptive_threshold(
+        &mut self,
+        rgba: &[u8],
+        width: u32,
+        height: u32,
+        block_size: usize,
+        c: i32,
+    ) -> Result<Vec<u8>, WasmError> {
+        let timing = self.profile("adaptive_threshold", rgba.len());
+        check_dimensions(rgba, width, height)?;
+        let (width, height) = (width as usize, height as usize);
+        if block_size % 2 == 0 || block_size >= width || block_size >= height {
+            return Err(WasmError::invalid(
+                "block size",
+                format!("{block_size} must be odd and smaller than both image dimensions"),
+            ));
+        }
+
+        self.load(rgba);
+
+        let radius = block_size / 2;
+        let buffer = &mut self.buffer;
+        let pool = self.runtime.buffer_pool();
+        install(&self.runtime, || {
+            let mut lumas: Vec<u8> = pool.take(width * height);
+            lumas
+                .par_iter_mut()
+                .zip(buffer.par_chunks_exact(4))
+                .for_each(|(value, pixel)| *value = luma(pixel));
+            let table = summed_area_table(pool, width, height, |i| lumas[i] as f64);
+
+            buffer
+                .par_chunks_exact_mut(4)
+                .enumerate()
+                .for_each(|(index, pixel)| {
+                    let (x, y) = (index % width, index / width);
+                    let (x0, x1) = (x.saturating_sub(radius), (x + radius + 1).min(width));
+                    let (y0, y1) = (y.saturating_sub(radius), (y + radius + 1).min(height));
+
+                    let sum = rectangle_sum(&table, width + 1, x0, y0, x1, y1);
+                    let count = ((x1 - x0) * (y1 - y0)) as f64;
+                    let local_threshold = sum / count - c as f64;
+
+                    let level = if lumas[index] as f64 > local_threshold {
+                        255
+                    } else {
+                        0
+                    };
+                    pixel[..3].fill(level);
+                });
+            pool.recycle(lumas);
+            pool.recycle(table);
+        })?;
+
+        timing.finish(Ok(self.buffer.clone()))
+    }
+}
+
+/// 256-bin histogram of pixel luminance
+fn luma_histogram(rgba: &[u8]) -> Vec<u64> {
+    rgba.par_chunks_exact(4)
+        .fold(
+            || vec![0u64; 256],
+            |mut counts, pixel| {
+                counts[luma(pixel) as usize] += 1;
+                counts
+            },
+        )
+        .reduce(
+            || vec![0u64; 256],
+            |mut total, counts| {
+                for (sum, count) in total.iter_mut().zip(counts) {
+                    *sum += count;
+                }
+                total
+            },
+        )
+}
@@ -0,0 +1,218 @@
+use js_sys::{Array, Reflect, Uint8Array, Uint8ClampedArray};
+use rayon::prelude::*;
+use wasm_bindgen::{prelude::*, JsCast};
+
+use super::{WasmImage, WasmImageProcessor};
+use crate::{install, WasmError};
+
+/// How a thumbnail handles a source whose aspect ratio differs from the target
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Fit {
+    /// Scale to fit inside the target, padding with transparent black
+    Contain,
+    /// Scale to fill the target, cropping the overflow around the centre
+    Cover,
+}
+
+impl Fit {
+    fn parse(name: &str) -> Result<Fit, WasmError> {
+        match name {
+            "contain" => Ok(Fit::Contain),
+            "cover" => Ok(Fit::Cover),
+            _ => Err(WasmError::unsupported("fit", name, "contain, cover")),
+        }
+    }
+}
+
+/// RGBA pixels copied out of a JavaScript image
+struct Source {
+    data: Vec<u8>,
+    width: usize,
+    height: usize,
+}
+
+#[wasm_bindgen]
+impl WasmImageProcessor {
+    /// Downscale many images to `target_w x target_h` thumbnails, one image
+    /// per task.
+    ///
+    /// Each entry may be a [`WasmImage`] or any `{ data, width, height }`
+    /// object such as an `ImageData`. Pixels are area-averaged, and `fit` is
+    /// `"contain"` to letterbox or `"cover"` to centre-crop. An entry that
+    /// cannot be read yields an `Error` in its slot instead of a
+    /// [`WasmImage`], leaving the rest of the batch intact.
+    #[wasm_bindgen]
+    pub fn batch_thumbnail(
+        &mut self,
+        images: Array,
+        target_w: usize,
+        target_h: usize,
+        fit: &str,
+    ) -> Result<Array, WasmError> {
+        let timing = self.profile("batch_thumbnail", images.length() as usize);
+        let fit = Fit::parse(fit)?;
+        if target_w == 0
+            || target_h == 0
+            || target_w > u32::MAX as usize
+            || target_h > u32::MAX as usize
+        {
+            return Err(WasmError::invalid(
+                "thumbnail size",
+                format!("{target_w}x{target_h} must be positive and fit in u32"),
+            ));
+        }
+
+        let sources: Vec<Result<Source, String>> =
+            images.iter().map(|entry| read_source(&entry)).collect();
+
+        let thumbnails: Vec<Result<Vec<u8>, String>> = install(&self.runtime, || {
+            sources
+                .par_iter()
+                .map(|source| {
+                    let source = source.as_ref().map_err(Clone::clone)?;
+                    Ok(thumbnail(source, target_w, target_h, fit))
+                })
+                .collect()
+        })?;
+
+        let entries = Array::new();
+        for (i, thumbnail) in thumbnails.into_iter().enumerate() {
+            let entry: JsValue = match thumbnail {
+                Ok(data) => WasmImage::from_rgba(data, target_w as u32, target_h as u32)?.into(),
+                Err(reason) => WasmError::invalid(format!("image {i}"), reason).into(),
+            };
+            entries.push(&entry);
+        }
+        timing.finish(Ok(entries))
+    }
+}
+
+/// Copy the pixels and dimensions out of a `{ data, width, height }` object
+fn read_source(entry: &JsValue) -> Result<Source, String> {
+    if !entry.is_object() {
+        return Err("expected an object with data, width and height".to_string());
+    }
+    let property = |name: &str| {
+        Reflect::get(entry, &name.into()).map_err(|_| format!("could not read '{name}'"))
+    };
+    let dimension = |name: &str| {
+        let value = property(name)?.as_f64();
+        match value {
+            Some(value) if value.fract() == 0.0 && (1.0..=u32::MAX as f64).contains(&value) => {
+                Ok(value as usize)
+            }
+            _ => Err(format!("'{name}' must be a positive integer")),
+        }
+    };
+
+    let (width, height) = (dimension("width")?, dimension("height")?);
+    let data = property("data")?;
+    let data = if let Some(array) = data.dyn_ref::<Uint8Array>() {
+        array.to_vec()
+    } else if let Some(array) = data.dyn_ref::<Uint8ClampedArray>() {
+        array.to_vec()
+    } else {
+        return Err("'data' must be a Uint8Array or Uint8ClampedArray".to_string());
+    };
+
+    let expected = width * height * 4;
+    if data.len() != expected {
+        return Err(format!(
+            "expected {expected} bytes for a {width}x{height} RGBA image, got {}",
+            data.len()
+        ));
+    }
+
+    Ok(Source {
+        data,
+        width,
+        height,
+    })
+}
+
+/// Resize one image to the target size according to `fit`
+fn thumbnail(source: &Source, target_w: usize, target_h: usize, fit: Fit) -> Vec<u8> {
+    let (width, height) = (source.width as f64, source.height as f64);
+    let scale_x = target_w as f64 / width;
+    let scale_y = target_h as f64 / height;
+
+    match fit {
+        Fit::Contain => {
+            let scale = scale_x.min(scale_y);
+            let inner_w = ((width * scale).round() as usize).clamp(1, target_w);
+            let inner_h = ((height * scale).round() as usize).clamp(1, target_h);
+            let inner = area_average(source, (0.0, 0.0, width, height), inner_w, inner_h);
+
+            let (left, top) = ((target_w - inner_w) / 2, (target_h - inner_h) / 2);
+            let mut canvas = vec![0; target_w * target_h * 4];
+            for (y, row) in inner.chunks_exact(inner_w * 4).enumerate() {
+                let start = ((top + y) * target_w + left) * 4;
+                canvas[start..start + row.len()].copy_from_slice(row);
+            }
+            canvas
+        }
+        Fit::Cover => {
+            let scale = scale_x.max(scale_y);
+            let (crop_w, crop_h) = (target_w as f64 / scale, target_h as f64 / scale);
+            let crop = (
+                (width - crop_w) / 2.0,
+                (height - crop_h) / 2.0,
+                crop_w,
+                crop_h,
+            );
+            area_average(source, crop, target_w, target_h)
+        }
+    }
+}
+
+/// Resample the `(x, y, w, h)` region of `source` to `out_w x out_h`, each
+/// output pixel averaging the source pixels it covers weighted by overlap
+fn area_average(
+    source: &Source,
+    (x, y, w, h): (f64, f64, f64, f64),
+    out_w: usize,
+    out_h: usize,
+) -> Vec<u8> {
+    let columns = coverage(x, w, out_w, source.width);
+    let rows = coverage(y, h, out_h, source.height);
+
+    let mut out = Vec::with_capacity(out_w * out_h * 4);
+    for row_span in &rows {
+        for column_span in &columns {
+            let mut sum = [0.0f64; 4];
+            let mut total = 0.0;
+            for &(sy, wy) in row_span {
+                for &(sx, wx) in column_span {
+                    let weight = wx * wy;
+                    let pixel = &source.data[(sy * source.width + sx) * 4..][..4];
+                    for (channel, &value) in sum.iter_mut().zip(pixel) {
+                        *channel += value as f64 * weight;
+                    }
+                    total += weight;
+                }
+            }
+            out.extend(sum.iter().map(|channel| (channel / total).round() as u8));
+        }
+    }
+    out
+}
+
+/// For each of `count` output cells spanning `[start, start + length)` in
+/// source coordinates, the source indices it overlaps and by how much
+fn coverage(start: f64, length: f64, count: usize, limit: usize) -> Vec<Vec<(usize, f64)>> {
+    let step = length / count as f64;
+    (0..count)
+        .map(|i| {
+            let (lo, hi) = (start + i as f64 * step, start + (i + 1) as f64 * step);
+            let first = lo.floor() as usize;
+            let last = (hi.ceil() as usize).clamp(first + 1, limit);
+            (first..last)
+                .map(|index| {
+                    let overlap = hi.min(index as f64 + 1.0) - lo.max(index as f64);
+                    (index, overlap.max(0.0))
+                })
+                .filter(|&(_, overlap)| overlap > 0.0)
+                .collect()
+        })
+        .collect()
+}
@@ -0,0 +1,174 @@
+use wasm_bindgen::prelude::*;
+
+use super::{check_dimensions, FloodFill, WasmImageProcessor};
+use crate::{profile::Footprint, WasmError};
+
+/// RGBA pixels bundled with their dimensions.
+///
+/// The length is validated once at construction, so the `*_image` variants
+/// of the spatial operations cannot be handed mismatched dimensions.
+#[wasm_bindgen]
+pub struct WasmImage {
+    data: Vec<u8>,
+    width: u32,
+    height: u32,
+}
+
+#[wasm_bindgen]
+impl WasmImage {
+    /// A fully transparent black `width x height` image
+    #[wasm_bindgen(constructor)]
+    pub fn new(width: u32, height: u32) -> Result<WasmImage, WasmError> {
+        crate::init::ensure_init();
+        let len = (width as usize)
+            .checked_mul(height as usize)
+            .and_then(|pixels| pixels.checked_mul(4))
+            .ok_or_else(|| {
+                WasmError::invalid("image size", format!("{width}x{height} is too large"))
+            })?;
+
+        Ok(WasmImage {
+            data: vec![0; len],
+            width,
+            height,
+        })
+    }
+
+    /// Wrap existing RGBA pixels, checking that they match the dimensions
+    #[wasm_bindgen]
+    pub fn from_rgba(data: Vec<u8>, width: u32, height: u32) -> Result<WasmImage, WasmError> {
+        check_dimensions(&data, width, height)?;
+        Ok(WasmImage {
+            data,
+            width,
+            height,
+        })
+    }
+
+    /// Width in pixels
+    #[wasm_bindgen(getter)]
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Height in pixels
+    #[wasm_bindgen(getter)]
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// A copy of the RGBA pixels
+    #[wasm_bindgen(getter)]
+    pub fn data(&self) -> Vec<u8> {
+        self.data.clone()
+    }
+}
+
+#[cfg(feature = "image-data")]
+#[wasm_bindgen]
+impl WasmImage {
+    /// Copy the pixels out of a canvas `ImageData`
+    #[wasm_bindgen]
+    pub fn from_image_data(image_data: &web_sys::ImageData) -> WasmImage {
+        WasmImage {
+            data: image_data.data().0,
+            width: image_data.width(),
+            height: image_data.height(),
+        }
+    }
+
+    /// Copy the pixels into a new canvas `ImageData`
+    #[wasm_bindgen]
+    pub fn to_image_data(&self) -> Result<web_sys::ImageData, WasmError> {
+        web_sys::ImageData::new_with_u8_clamped_array_and_sh(
+            wasm_bindgen::Clamped(&self.data),
+            self.width,
+            self.height,
+        )
+        .map_err(WasmError::from)
+    }
+}
+
+impl WasmImage {
+    /// Wrap pixels produced from an image of the same dimensions as `self`
+    fn with_data(&self, data: Vec<u8>) -> WasmImage {
+        WasmImage {
+            data,
+            width: self.width,
+            height: self.height,
+        }
+    }
+}
+
+impl Footprint for WasmImage {
+    fn heap_bytes(&self) -> usize {
+        self.data.capacity()
+    }
+}
+
+/// Dimension-safe variants of the spatial operations
+#[wasm_bindgen]
+impl WasmImageProcessor {
+    /// [`WasmImageProcessor::dither_floyd_steinberg`] on a [`WasmImage`]
+    #[wasm_bindgen]
+    pub fn dither_floyd_steinberg_image(
+        &mut self,
+        image: &WasmImage,
+        palette: &[u8],
+    ) -> Result<WasmImage, WasmError> {
+        let data = self.dither_floyd_steinberg(&image.data, image.width, image.height, palette)?;
+        Ok(image.with_data(data))
+    }
+
+    /// [`WasmImageProcessor::dither_ordered`] on a [`WasmImage`]
+    #[wasm_bindgen]
+    pub fn dither_ordered_image(
+        &mut self,
+        image: &WasmImage,
+        bits: u8,
+    ) -> Result<WasmImage, WasmError> {
+        let data = self.dither_ordered(&image.data, image.width, image.height, bits)?;
+        Ok(image.with_data(data))
+    }
+
+    /// [`WasmImageProcessor::adaptive_threshold`] on a [`WasmImage`]
+    #[wasm_bindgen]
+    pub fn adaptive_threshold_image(
+        &mut self,
+        image: &WasmImage,
+        block_size: usize,
+        c: i32,
+    ) -> Result<WasmImage, WasmError> {
+        let data =
+            self.adaptive_threshold(&image.data, image.width, image.height, block_size, c)?;
+        Ok(image.with_data(data))
+    }
+
+    /// [`WasmImageProcessor::fast_box_blur`] on a [`WasmImage`]
+    #[wasm_bindgen]
+    pub fn fast_box_blur_image(
+        &mut self,
+        image: &WasmImage,
+        radius: usize,
+    ) -> Result<WasmImage, WasmError> {
+        let data = self.fast_box_blur(&image.data, image.width, image.height, radius)?;
+        Ok(image.with_data(data))
+    }
+
+    /// [`WasmImageProcessor::flood_fill`] on a [`WasmImage`]
+    #[wasm_bindgen]
+    pub fn flood_fill_image(
+        &mut self,
+        image: &WasmImage,
+        fill: &FloodFill,
+    ) -> Result<WasmImage, WasmError> {
+        let data = self.flood_fill(&image.data, image.width, image.height, fill)?;
+        Ok(image.with_data(data))
+    }
+
+    /// [`WasmImageProcessor::load_frame`] from a [`WasmImage`]
+    #[wasm_bindgen]
+    pub fn load_frame_image(&mut self, image: &WasmImage) -> Result<(), WasmError> {
+        self.load_frame(&image.data, image.width, image.height)
+    }
+}
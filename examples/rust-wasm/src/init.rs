@@ -0,0 +1,193 @@
+//! One-time module setup: the panic hook, the console log level and build
+//! information.
+//!
+//! `init` is the explicit entry point, but every constructor that starts
+//! work calls [`ensure_init`] too, so a page that never calls it still gets
+//! the panic hook.
+
+use std::sync::{
+    atomic::{AtomicU8, Ordering},
+    Once,
+};
+
+use js_sys::{Array, Object, Reflect};
+use serde::Deserialize;
+use wasm_bindgen::prelude::*;
+
+use crate::{panic_is_contained, WasmError};
+
+/// The lowest level `console_log!` still writes, as a `LogLevel`
+static LOG_LEVEL: AtomicU8 = AtomicU8::new(LogLevel::Info as u8);
+
+/// Features this build may have been compiled with, as named in Cargo.toml
+const FEATURES: [(&str, bool); 5] = [
+    (
+        "console_error_panic_hook",
+        cfg!(feature = "console_error_panic_hook"),
+    ),
+    ("image-data", cfg!(feature = "image-data")),
+    ("wasm-threads", cfg!(feature = "wasm-threads")),
+    ("wee_alloc", cfg!(feature = "wee_alloc")),
+    ("tokio", cfg!(feature = "tokio")),
+];
+
+/// How important a console message is; messages below the level set by
+/// `init` are dropped
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum LogLevel {
+    Debug,
+    Info,
+    Warn,
+    Error,
+    /// Only as a filter: nothing is written
+    Off,
+}
+
+impl LogLevel {
+    const SUPPORTED: &'static str = "debug, info, warn, error, off";
+
+    fn parse(name: &str) -> Result<LogLevel, WasmError> {
+        match name.to_ascii_lowercase().as_str() {
+            "debug" => Ok(LogLevel::Debug),
+            "info" => Ok(LogLevel::Info),
+            "warn" => Ok(LogLevel::Warn),
+            "error" => Ok(LogLevel::Error),
+            "off" => Ok(LogLevel::Off),
+            _ => Err(WasmError::unsupported("log level", name, Self::SUPPORTED)),
+        }
+    }
+}
+
+/// Options accepted by [`init`]
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct InitOptions {
+    /// `"debug"`, `"info"` (the default), `"warn"`, `"error"` or `"off"`
+    log_level: Option<String>,
+}
+
+/// Set up the module: install the panic hook, once, and apply `options`.
+///
+/// `options` is `undefined` or `{ log_level }`, where `log_level` is one of
+/// `"debug"`, `"info"` (the default), `"warn"`, `"error"` or `"off"`. It may
+/// be called again to change the level. Calling it is optional, since every
+/// constructor sets up the panic hook itself, but it is the place to choose
+/// how much the module logs.
+#[wasm_bindgen]
+pub fn init(options: &JsValue) -> Result<(), WasmError> {
+    ensure_init();
+    let options = if options.is_undefined() || options.is_null() {
+        InitOptions::default()
+    } else {
+        // Going through a JSON value lets serde see, and reject, unknown keys
+        serde_wasm_bindgen::from_value(options.clone())
+            .and_then(|value: serde_json::Value| {
+                InitOptions::deserialize(value).map_err(serde::de::Error::custom)
+            })
+            .map_err(|e| WasmError::invalid("init options", e.to_string()))?
+    };
+    if let Some(level) = options.log_level {
+        LOG_LEVEL.store(LogLevel::parse(&level)? as u8, Ordering::Relaxed);
+    }
+    Ok(())
+}
+
+/// Run the one-time setup if nothing has yet.
+///
+/// Natively the default panic hook is kept, since there is no JavaScript to
+/// throw to and tests rely on panics unwinding normally.
+pub(crate) fn ensure_init() {
+    static INIT: Once = Once::new();
+
+    if cfg!(target_arch = "wasm32") {
+        INIT.call_once(install_panic_hook);
+    }
+}
+
+/// Install a panic hook that turns Rust panics into thrown JavaScript errors.
+///
+/// The hook logs the panic (with `console_error_panic_hook` when enabled) and
+/// then throws an `Error` carrying the panic message instead of letting the
+/// instance trap. The panicking call never returns, so any object it was
+/// borrowing stays borrowed; treat that object as unusable afterwards.
+///
+/// Panics inside a processor's parallel work are left to unwind instead, in
+/// builds where they can, so that the call fails with an `INTERNAL` error
+/// and the processor stays usable.
+fn install_panic_hook() {
+    std::panic::set_hook(Box::new(|info| {
+        #[cfg(feature = "console_error_panic_hook")]
+        console_error_panic_hook::hook(info);
+
+        if panic_is_contained() {
+            return;
+        }
+        let message = match info.payload().downcast_ref::<&str>() {
+            Some(message) => message.to_string(),
+            None => match info.payload().downcast_ref::<String>() {
+                Some(message) => message.clone(),
+                None => info.to_string(),
+            },
+        };
+        wasm_bindgen::throw_str(&message);
+    }));
+}
+
+/// Write `message` to the console at `level`, unless the level set by
+/// [`init`] filters it out; natively it goes to standard error
+pub(crate) fn log(level: LogLevel, message: &str) {
+    if (level as u8) < LOG_LEVEL.load(Ordering::Relaxed) {
+        return;
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        let message = JsValue::from(message);
+        match level {
+            LogLevel::Debug => web_sys::console::debug_1(&message),
+            LogLevel::Info => web_sys::console::log_1(&message),
+            LogLevel::Warn => web_sys::console::warn_1(&message),
+            LogLevel::Error | LogLevel::Off => web_sys::console::error_1(&message),
+        }
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    eprintln!("{message}");
+}
+
+/// What this module was built as: `{ name, version, target, profile,
+/// features }`.
+///
+/// `target` is the architecture and OS, such as `"wasm32-unknown"`,
+/// `profile` is `"debug"` or `"release"`, and `features` lists the Cargo
+/// features compiled in.
+#[wasm_bindgen]
+pub fn build_info() -> JsValue {
+    let features: Array = FEATURES
+        .iter()
+        .filter(|(_, enabled)| *enabled)
+        .map(|(name, _)| JsValue::from(*name))
+        .collect();
+    // `wasm32-unknown-unknown` reports no OS at all
+    let os = match std::env::consts::OS {
+        "" => "unknown",
+        os => os,
+    };
+    let target = format!("{}-{os}", std::env::consts::ARCH);
+    let profile = if cfg!(debug_assertions) {
+        "debug"
+    } else {
+        "release"
+    };
+
+    let result = Object::new();
+    for (name, value) in [
+        ("name", JsValue::from(env!("CARGO_PKG_NAME"))),
+        ("version", JsValue::from(env!("CARGO_PKG_VERSION"))),
+        ("target", JsValue::from(target)),
+        ("profile", JsValue::from(profile)),
+        ("features", features.into()),
+    ] {
+        // Defining a property on a fresh plain object cannot fail
+        let _ = Reflect::set(&result, &name.into(), &value);
+    }
+    result.into()
+}
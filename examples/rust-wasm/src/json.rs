@@ -0,0 +1,225 @@
+use js_sys::Uint8Array;
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use wasm_bindgen::prelude::*;
+
+use crate::{WasmError, WasmModule};
+
+/// A step of a [`WasmModule::process_json`] pipeline.
+///
+/// Paths name object keys separated by dots, such as `"user.address.city"`.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case", deny_unknown_fields)]
+enum JsonOp {
+    /// Keep only the values at `paths`, at the same nesting
+    Pick { paths: Vec<String> },
+    /// Move the value at `from` to `to`, replacing anything there
+    Rename { from: String, to: String },
+    /// Keep the elements of the array at `path` whose numeric `field`
+    /// satisfies every bound given
+    FilterArray {
+        path: String,
+        field: String,
+        gt: Option<f64>,
+        gte: Option<f64>,
+        lt: Option<f64>,
+        lte: Option<f64>,
+    },
+}
+
+impl JsonOp {
+    const SUPPORTED: &'static str = "pick, rename, filter_array";
+
+    /// Read one step of the operations list; `step` numbers it in errors
+    fn parse(value: Value, step: usize) -> Result<JsonOp, WasmError> {
+        let Some(op) = value.get("op").and_then(Value::as_str) else {
+            return Err(WasmError::invalid(
+                format!("JSON step {step}"),
+                "must be an object with a string 'op'",
+            ));
+        };
+        if !Self::SUPPORTED.split(", ").any(|name| name == op) {
+            return Err(WasmError::unsupported("JSON op", op, Self::SUPPORTED));
+        }
+
+        let name = format!("JSON step {step} ('{op}')");
+        let op =
+            JsonOp::deserialize(value).map_err(|e| WasmError::invalid(&name, e.to_string()))?;
+        if let JsonOp::FilterArray {
+            gt: None,
+            gte: None,
+            lt: None,
+            lte: None,
+            ..
+        } = op
+        {
+            return Err(WasmError::invalid(
+                name,
+                "needs at least one of gt, gte, lt or lte",
+            ));
+        }
+        Ok(op)
+    }
+
+    fn apply(&self, document: Value, step: usize) -> Result<Value, WasmError> {
+        match self {
+            JsonOp::Pick { paths } => {
+                let mut picked = Value::Object(Map::new());
+                for path in paths {
+                    if let Some(value) = get(&document, path) {
+                        // Picked paths only ever create objects, so this fits
+                        let _ = insert(&mut picked, path, value.clone());
+                    }
+                }
+                Ok(picked)
+            }
+            JsonOp::Rename { from, to } => {
+                let mut document = document;
+                if let Some(value) = take(&mut document, from) {
+                    insert(&mut document, to, value).map_err(|reason| {
+                        WasmError::invalid(format!("JSON step {step} ('rename')"), reason)
+                    })?;
+                }
+                Ok(document)
+            }
+            JsonOp::FilterArray {
+                path,
+                field,
+                gt,
+                gte,
+                lt,
+                lte,
+            } => {
+                let mut document = document;
+                let Some(Value::Array(items)) = get_mut(&mut document, path) else {
+                    return Err(WasmError::invalid(
+                        format!("JSON step {step} ('filter_array')"),
+                        format!("'{path}' is not an array"),
+                    ));
+                };
+                items.retain(|item| {
+                    get(item, field)
+                        .and_then(Value::as_f64)
+                        .is_some_and(|value| {
+                            gt.map_or(true, |bound| value > bound)
+                                && gte.map_or(true, |bound| value >= bound)
+                                && lt.map_or(true, |bound| value < bound)
+                                && lte.map_or(true, |bound| value <= bound)
+                        })
+                });
+                Ok(document)
+            }
+        }
+    }
+}
+
+/// JSON transforms and JSON/CBOR conversion, so payloads need not be
+/// parsed and re-serialised in JavaScript.
+///
+/// Output is compact, with object keys in sorted order. Malformed JSON
+/// input fails with an `INVALID_JSON` error whose details carry the 1-based
+/// `line` and `column` of the problem.
+#[wasm_bindgen]
+impl WasmModule {
+    /// Apply `operations` in order to the JSON document `input`, returning
+    /// compact JSON.
+    ///
+    /// Each operation is an object such as
+    /// `{ op: "pick", paths: ["a.b", "c"] }`,
+    /// `{ op: "rename", from: "x", to: "y" }` or
+    /// `{ op: "filter_array", path: "items", field: "score", gt: 0.5 }`.
+    /// `filter_array` accepts any of `gt`, `gte`, `lt` and `lte`, and drops
+    /// elements whose field is missing or not a number. Picking or renaming
+    /// a missing path does nothing.
+    #[wasm_bindgen]
+    pub fn process_json(&mut self, input: &str, operations: &JsValue) -> Result<String, WasmError> {
+        let operations: Vec<Value> = serde_wasm_bindgen::from_value(operations.clone())
+            .map_err(|e| WasmError::invalid("JSON operations", e.to_string()))?;
+        let operations = operations
+            .into_iter()
+            .enumerate()
+            .map(|(i, op)| JsonOp::parse(op, i + 1))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut document = parse_json(input)?;
+        for (i, op) in operations.iter().enumerate() {
+            document = op.apply(document, i + 1)?;
+        }
+        Ok(document.to_string())
+    }
+
+    /// Re-encode the JSON document `input` as CBOR
+    #[wasm_bindgen]
+    pub fn json_to_cbor(&self, input: &str) -> Result<Uint8Array, WasmError> {
+        let document = parse_json(input)?;
+        let mut cbor = Vec::new();
+        ciborium::into_writer(&document, &mut cbor)
+            .map_err(|e| WasmError::invalid("JSON document", e.to_string()))?;
+        Ok(Uint8Array::from(&cbor[..]))
+    }
+
+    /// Decode CBOR into compact JSON.
+    ///
+    /// Only CBOR with a JSON equivalent converts: map keys must be strings,
+    /// and byte strings or tags are rejected.
+    #[wasm_bindgen]
+    pub fn cbor_to_json(&self, input: &Uint8Array) -> Result<String, WasmError> {
+        let document: Value = ciborium::from_reader(&input.to_vec()[..])
+            .map_err(|e| WasmError::invalid("CBOR data", e.to_string()))?;
+        Ok(document.to_string())
+    }
+}
+
+fn parse_json(input: &str) -> Result<Value, WasmError> {
+    serde_json::from_str(input).map_err(|e| {
+        let (line, column) = (e.line(), e.column());
+        // serde_json appends the position, which the details already carry
+        let message = e.to_string();
+        let suffix = format!(" at line {line} column {column}");
+        WasmError::InvalidJson {
+            line,
+            column,
+            reason: message
+                .strip_suffix(&suffix)
+                .unwrap_or(&message)
+                .to_string(),
+        }
+    })
+}
+
+fn get<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.').try_fold(value, |value, key| value.get(key))
+}
+
+fn get_mut<'a>(value: &'a mut Value, path: &str) -> Option<&'a mut Value> {
+    path.split('.')
+        .try_fold(value, |value, key| value.get_mut(key))
+}
+
+/// Remove and return the value at `path`
+fn take(value: &mut Value, path: &str) -> Option<Value> {
+    let (parent, key) = match path.rsplit_once('.') {
+        Some((parent, key)) => (get_mut(value, parent)?, key),
+        None => (value, path),
+    };
+    parent.as_object_mut()?.remove(key)
+}
+
+/// Store `new` at `path`, creating missing objects along the way
+fn insert(value: &mut Value, path: &str, new: Value) -> Result<(), String> {
+    let mut keys = path.split('.').peekable();
+    let mut current = value;
+    while let Some(key) = keys.next() {
+        let Value::Object(map) = current else {
+            return Err(format!(
+                "cannot write '{path}' through a value that is not an object"
+            ));
+        };
+        if keys.peek().is_none() {
+            map.insert(key.to_string(), new);
+            return Ok(());
+        }
+        current = map.entry(key).or_insert_with(|| Value::Object(Map::new()));
+    }
+    Ok(())
+}
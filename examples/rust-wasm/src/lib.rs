@@ -1,28 +1,176 @@
-use js_sys::{Promise, Uint8Array};
-use std::collections::HashMap;
+use js_sys::{Function, Object, Promise, Reflect, Uint8Array};
+use std::{
+    any::Any,
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    panic::AssertUnwindSafe,
+    rc::Rc,
+};
 use wasm_bindgen::prelude::*;
 use wasm_bindgen_futures::future_to_promise;
+use xxhash_rust::xxh3::xxh3_128;
 
-// Import the `console.log` function from the browser
-#[wasm_bindgen]
-extern "C" {
-    #[wasm_bindgen(js_namespace = console)]
-    fn log(s: &str);
-}
+use cache::LruCache;
+use transform::{ByteTransform, TransformParams};
 
-// Macro for easier logging
+// Macro for easier logging: `console_log!(warn: "...")` picks a level,
+// plain `console_log!("...")` logs at info
 macro_rules! console_log {
-    ($($t:tt)*) => (log(&format_args!($($t)*).to_string()))
+    (debug: $($t:tt)*) => (console_log!(@ Debug, $($t)*));
+    (warn: $($t:tt)*) => (console_log!(@ Warn, $($t)*));
+    (error: $($t:tt)*) => (console_log!(@ Error, $($t)*));
+    (@ $level:ident, $($t:tt)*) => (
+        crate::init::log(crate::init::LogLevel::$level, &format_args!($($t)*).to_string())
+    );
+    ($($t:tt)*) => (console_log!(@ Info, $($t)*));
+}
+
+// A smaller allocator than the default, at some cost in speed
+#[cfg(all(feature = "wee_alloc", target_arch = "wasm32"))]
+#[global_allocator]
+static ALLOC: wee_alloc::WeeAlloc = wee_alloc::WeeAlloc::INIT;
+
+mod batch;
+mod bloom;
+mod cache;
+mod cancel;
+mod compression;
+mod crypto;
+mod efficient;
+mod embedding;
+mod encoding;
+mod error;
+mod hashing;
+mod image;
+mod init;
+mod json;
+mod matrix;
+mod memory;
+mod parallel;
+mod pool;
+mod profile;
+mod queue;
+mod runtime;
+mod stream;
+mod text;
+mod tfidf;
+#[cfg(all(feature = "wasm-threads", target_arch = "wasm32"))]
+mod threads;
+mod transform;
+
+pub use batch::{BatchOp, BatchOp2, ChainStep, WasmBatchProcessor};
+pub use bloom::WasmBloomFilter;
+pub use cancel::CancellationToken;
+pub use crypto::WasmKey;
+pub use efficient::MemoryEfficientProcessor;
+pub use embedding::WasmEmbeddingIndex;
+pub use error::WasmError;
+pub use image::{FloodFill, NoiseParams, Rect, WasmImage, WasmImageProcessor};
+pub use init::{build_info, init};
+pub use matrix::WasmMatrixProcessor;
+pub use memory::{
+    memory_report, process_memory_bytes, register_for_memory_report, reset_memory_peak,
+    unregister_from_memory_report,
+};
+pub use parallel::{sparse_histogram_get_count, Padding2d, WasmParallelProcessor};
+pub use pool::PoolStats;
+pub use profile::OpStats;
+pub use queue::WasmTaskQueue;
+pub use runtime::{hardware_concurrency, threading_support, WasmRuntime};
+pub use tfidf::WasmTFIDF;
+#[cfg(all(feature = "wasm-threads", target_arch = "wasm32"))]
+pub use threads::init_thread_pool;
+
+thread_local! {
+    /// Calls to [`install`] running on this thread, whose panics are caught
+    static CONTAINED: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Run `op` on the runtime's current pool, or on the global pool if it has
+/// none.
+///
+/// A panic inside `op`, on any worker, becomes [`WasmError::Internal`]
+/// rather than taking the module down, so the processor stays usable. Under
+/// WASM this needs a `panic=unwind` build, which the Makefile's wasm targets
+/// produce; a plain `panic=abort` build still traps, after the panic hook
+/// has thrown the message as a plain JavaScript error.
+///
+/// The size of the pool `op` ran on counts towards the operation being
+/// profiled, if any.
+fn install<R: Send>(runtime: &WasmRuntime, op: impl FnOnce() -> R + Send) -> Result<R, WasmError> {
+    let pool = runtime.current_pool();
+    CONTAINED.with(|depth| depth.set(depth.get() + 1));
+    // The closures only borrow their inputs and fill buffers they own, so
+    // nothing is observed half-updated once the panic is caught
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| match &pool {
+        Some(pool) => pool.install(op),
+        None => op(),
+    }));
+    CONTAINED.with(|depth| depth.set(depth.get() - 1));
+    let result = result.map_err(|payload| WasmError::Internal {
+        message: panic_message(&*payload),
+    })?;
+
+    profile::record_pool(match &pool {
+        Some(pool) => pool.current_num_threads(),
+        None => rayon::current_num_threads(),
+    });
+    Ok(result)
+}
+
+/// Whether a panic on this thread will be caught by [`install`]: either it
+/// is inside one, or it is a Rayon worker, which hands panics back to the
+/// thread that installed the work
+fn panic_is_contained() -> bool {
+    cfg!(panic = "unwind")
+        && (CONTAINED.with(Cell::get) > 0 || rayon::current_thread_index().is_some())
 }
 
+/// The message a panic was raised with
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    match payload.downcast_ref::<&str>() {
+        Some(message) => message.to_string(),
+        None => match payload.downcast_ref::<String>() {
+            Some(message) => message.clone(),
+            None => "unknown panic".to_string(),
+        },
+    }
+}
+
+/// Bytes transformed between yields by [`WasmModule::process_data_async`]
+const ASYNC_CHUNK: usize = 64 * 1024;
+
+/// Entries kept by [`WasmModule::new`]'s cache
+const DEFAULT_CACHE_ENTRIES: usize = 256;
+
+/// Bytes kept by [`WasmModule::new`]'s cache
+const DEFAULT_CACHE_BYTES: usize = 16 * 1024 * 1024;
+
 /// Main WASM module that provides data processing capabilities
 #[wasm_bindgen]
 pub struct WasmModule {
-    // Internal state
-    processing_cache: HashMap<String, Vec<u8>>,
+    // Internal state; pending async transforms hold a handle to the cache so
+    // they can store their result once they finish
+    processing_cache: Rc<RefCell<LruCache>>,
     is_initialized: bool,
+    /// Open streaming digests by handle
+    hashers: HashMap<u32, hashing::Hasher>,
+    next_hash_handle: u32,
+    /// Open stream sessions by handle
+    streams: HashMap<u32, stream::Session>,
+    next_stream_handle: u32,
+    /// Transforms `process_data` can run, by name
+    transforms: HashMap<String, Box<dyn ByteTransform>>,
+    /// Runs the parallel work of [`WasmModule::text_stats`]
+    runtime: WasmRuntime,
 }
 
+// Under `panic=unwind` wasm-bindgen catches panics at the export boundary
+// and needs this for `&self` methods. A caught panic can at worst leave the
+// cache missing an entry, or a stream or hasher part way through its input,
+// all of which stay memory safe.
+impl std::panic::RefUnwindSafe for WasmModule {}
+
 impl Default for WasmModule {
     fn default() -> Self {
         Self::new()
@@ -31,25 +179,80 @@ impl Default for WasmModule {
 
 #[wasm_bindgen]
 impl WasmModule {
-    /// Create a new instance of the WASM module
+    /// Create a new instance of the WASM module, caching up to 256 results
+    /// and 16 MiB
     #[wasm_bindgen(constructor)]
     pub fn new() -> WasmModule {
+        WasmModule::with_cache_limits(DEFAULT_CACHE_ENTRIES, DEFAULT_CACHE_BYTES)
+    }
+
+    /// Create a module whose cache holds at most `max_entries` results and
+    /// `max_bytes` bytes, evicting the least recently used first.
+    ///
+    /// WASM memory never shrinks, so a long-lived page should keep these
+    /// bounded. A result larger than `max_bytes` is not cached at all.
+    #[wasm_bindgen]
+    pub fn with_cache_limits(max_entries: usize, max_bytes: usize) -> WasmModule {
         console_log!("Creating new WasmModule instance");
 
-        // Set up panic hook for better error messages
-        std::panic::set_hook(Box::new(console_error_panic_hook::hook));
+        // Make sure panics surface as JavaScript errors
+        init::ensure_init();
+
+        let mut transforms = HashMap::new();
+        transform::register_builtin_transforms(&mut transforms);
 
         WasmModule {
-            processing_cache: HashMap::new(),
+            processing_cache: Rc::new(RefCell::new(LruCache::new(max_entries, max_bytes))),
             is_initialized: true,
+            hashers: HashMap::new(),
+            next_hash_handle: 0,
+            streams: HashMap::new(),
+            next_stream_handle: 0,
+            transforms,
+            runtime: WasmRuntime::global(),
         }
     }
 
-    /// Process input data and return transformed result
+    /// Create a module like [`WasmModule::new`] whose parallel work, such as
+    /// [`WasmModule::text_stats`], runs on `runtime`'s pool rather than the
+    /// global one
     #[wasm_bindgen]
-    pub fn process_data(&mut self, input: &Uint8Array) -> Result<Uint8Array, JsValue> {
+    pub fn with_runtime(runtime: &WasmRuntime) -> WasmModule {
+        WasmModule {
+            runtime: runtime.clone(),
+            ..WasmModule::new()
+        }
+    }
+
+    /// Process input data and return transformed result.
+    ///
+    /// `transform` names one of [`WasmModule::list_transforms`], configured
+    /// by the `params` object:
+    ///
+    /// - `"reverse"` reverses the byte order.
+    /// - `"xor"` XORs each byte with `params.key`, `0xAA` by default.
+    /// - `"rot_n"` adds `params.n` to each byte, wrapping; `256 - n` undoes it.
+    /// - `"rle"` writes `(count, byte)` pairs, which `"rle_decode"` expands.
+    /// - `"delta_encode"` keeps the first byte and replaces the rest with the
+    ///   wrapping difference from the byte before; `"delta_decode"` undoes it.
+    /// - `"delta_encode_u32"` and `"delta_decode_u32"` do the same for
+    ///   little-endian `u32`s, so the length must be a multiple of 4.
+    /// - `"varint_encode_u64"` rewrites little-endian `u64`s as LEB128
+    ///   varints, and `"varint_decode_u64"` reads them back.
+    ///
+    /// Called with the input alone, it reverses the bytes and XORs them with
+    /// `0xAA`, as it always has. The transforms are reversible
+    /// demonstrations, not obfuscation; use [`WasmModule::encrypt`] to
+    /// protect data. Results are cached per transform and params.
+    #[wasm_bindgen]
+    pub fn process_data(
+        &mut self,
+        input: &Uint8Array,
+        transform: Option<String>,
+        params: &JsValue,
+    ) -> Result<Uint8Array, WasmError> {
         if !self.is_initialized {
-            return Err(JsValue::from_str("Module not initialized"));
+            return Err(WasmError::NotInitialized);
         }
 
         // Convert JS Uint8Array to Rust Vec<u8>
@@ -57,109 +260,321 @@ impl WasmModule {
 
         console_log!("Processing {} bytes of data", input_data.len());
 
-        // Perform some processing (example: simple transformation)
-        let processed_data = self.transform_data(input_data)?;
+        let processed_data = match transform {
+            Some(name) => {
+                self.run_transform(&name, input_data, &TransformParams::from_js(params)?)?
+            }
+            None => self.transform_data(input_data)?,
+        };
 
         // Convert back to Uint8Array for JavaScript
         Ok(Uint8Array::from(&processed_data[..]))
     }
 
-    /// Asynchronous processing method that returns a Promise
+    /// Asynchronous processing method that returns a Promise.
+    ///
+    /// The input is copied once, into the buffer that becomes the result,
+    /// then transformed in 64 KiB chunks with a yield to the event loop
+    /// before each one, so large inputs do not block the page. After each
+    /// chunk `on_progress`, if given, is called with the bytes done so far
+    /// and the total; if it throws, the promise rejects with that error.
+    /// Results are cached, so repeating an input resolves without any work.
     #[wasm_bindgen]
-    pub fn process_data_async(&mut self, input: &Uint8Array) -> Promise {
+    pub fn process_data_async(
+        &mut self,
+        input: &Uint8Array,
+        on_progress: Option<Function>,
+    ) -> Promise {
         let input_data: Vec<u8> = input.to_vec();
-        let cache_key = format!("async_{}", input_data.len());
+        let cache_key = cache_key("async", &input_data);
 
         // Check cache first
-        if let Some(cached_result) = self.processing_cache.get(&cache_key) {
+        if let Some(cached_result) = self.processing_cache.borrow_mut().get(&cache_key) {
             console_log!("Returning cached result for key: {}", cache_key);
-            let result = Uint8Array::from(&cached_result[..]);
-            return Promise::resolve(&result.into());
+            let result = Uint8Array::from(cached_result);
+            return Promise::resolve(&JsValue::from(result));
         }
 
         // Create async processing future
+        let cache = Rc::clone(&self.processing_cache);
         let future = async move {
-            // Simulate async work
-            let processed = Self::async_transform(input_data).await?;
-            Ok(JsValue::from(Uint8Array::from(&processed[..])))
+            let processed = Self::async_transform(input_data, on_progress.as_ref()).await?;
+            let result = Uint8Array::from(&processed[..]);
+            cache.borrow_mut().insert(cache_key, processed);
+            Ok(JsValue::from(result))
         };
 
-        future_to_promise(future)
+        // A panic drops the future before it stores anything in the cache
+        future_to_promise(AssertUnwindSafe(future))
     }
 
     /// Get processing statistics
     #[wasm_bindgen(getter)]
     pub fn cache_size(&self) -> usize {
-        self.processing_cache.len()
+        self.processing_cache.borrow().len()
+    }
+
+    /// Total bytes held by cached results
+    #[wasm_bindgen(getter)]
+    pub fn cache_bytes(&self) -> usize {
+        self.processing_cache.borrow().bytes()
+    }
+
+    /// Bytes this module holds, for [`memory_report`]: the cached results.
+    /// Open digests and streams keep only small fixed state and are not
+    /// counted.
+    #[wasm_bindgen]
+    pub fn heap_bytes(&self) -> usize {
+        self.cache_bytes()
+    }
+
+    /// Change the cache limits, evicting least recently used results at
+    /// once if the cache is over them
+    #[wasm_bindgen]
+    pub fn set_cache_limits(&mut self, max_entries: usize, max_bytes: usize) {
+        self.processing_cache
+            .borrow_mut()
+            .set_limits(max_entries, max_bytes);
+    }
+
+    /// Keys of every cached result, least recently used first; for debugging
+    #[wasm_bindgen]
+    pub fn cache_keys(&self) -> Vec<String> {
+        self.processing_cache.borrow().keys().cloned().collect()
+    }
+
+    /// Whether a result is cached under `key`, without counting as a use;
+    /// for debugging
+    #[wasm_bindgen]
+    pub fn cache_contains(&self, key: &str) -> bool {
+        self.processing_cache.borrow().contains(key)
+    }
+
+    /// Cache activity as `{ hits, misses, insertions, evictions, entries,
+    /// bytes }`.
+    ///
+    /// Every cached path counts, including `compress` and `decompress`.
+    /// `entries` and `bytes` describe the cache now; the rest are totals
+    /// since the module was created or [`WasmModule::reset_stats`] was last
+    /// called, and carry on across `clear_cache`. Results too large to cache
+    /// are not counted as insertions, and `evictions` counts only entries
+    /// dropped to meet the limits.
+    #[wasm_bindgen]
+    pub fn cache_stats(&self) -> JsValue {
+        let cache = self.processing_cache.borrow();
+        let stats = cache.stats();
+        let result = Object::new();
+        for (name, value) in [
+            ("hits", stats.hits as f64),
+            ("misses", stats.misses as f64),
+            ("insertions", stats.insertions as f64),
+            ("evictions", stats.evictions as f64),
+            ("entries", cache.len() as f64),
+            ("bytes", cache.bytes() as f64),
+        ] {
+            // Defining a property on a fresh plain object cannot fail
+            let _ = Reflect::set(&result, &name.into(), &value.into());
+        }
+        result.into()
+    }
+
+    /// Zero the counters reported by [`WasmModule::cache_stats`], keeping
+    /// the cached results
+    #[wasm_bindgen]
+    pub fn reset_stats(&mut self) {
+        self.processing_cache.borrow_mut().reset_stats();
     }
 
-    /// Clear the processing cache
+    /// Clear the processing cache. The counters from
+    /// [`WasmModule::cache_stats`] are kept.
     #[wasm_bindgen]
     pub fn clear_cache(&mut self) {
         console_log!("Clearing processing cache");
-        self.processing_cache.clear();
+        self.processing_cache.borrow_mut().clear();
     }
 }
 
 impl WasmModule {
     /// Internal synchronous data transformation
-    fn transform_data(&mut self, mut data: Vec<u8>) -> Result<Vec<u8>, JsValue> {
-        // Example transformation: reverse and XOR with 0xAA
-        data.reverse();
-        for byte in &mut data {
-            *byte ^= 0xAA;
+    fn transform_data(&mut self, mut data: Vec<u8>) -> Result<Vec<u8>, WasmError> {
+        let cache_key = cache_key("sync", &data);
+        if let Some(cached_result) = self.processing_cache.borrow_mut().get(&cache_key) {
+            console_log!("Returning cached result for key: {}", cache_key);
+            return Ok(cached_result.to_vec());
         }
 
+        // Example transformation: reverse and XOR with 0xAA
+        let params = TransformParams::default();
+        self.apply_transform("reverse", &mut data, &params)?;
+        self.apply_transform("xor", &mut data, &params)?;
+
         // Cache the result
-        let cache_key = format!("sync_{}", data.len());
-        self.processing_cache.insert(cache_key, data.clone());
+        self.processing_cache
+            .borrow_mut()
+            .insert(cache_key, data.clone());
 
         Ok(data)
     }
 
-    /// Internal asynchronous data transformation
-    async fn async_transform(mut data: Vec<u8>) -> Result<Vec<u8>, JsValue> {
-        // Simulate async processing delay using a simple Promise
-        let promise = Promise::new(&mut |resolve, _reject| {
-            // Try browser environment first (setTimeout via Window)
-            if let Ok(window) = js_sys::global().dyn_into::<web_sys::Window>() {
-                let _ = window.set_timeout_with_callback_and_timeout_and_arguments_0(
-                    &resolve, 10, // 10ms delay
-                );
-            } else {
-                // Node.js environment - use setTimeout from global object
-                let global = js_sys::global();
-                if let Ok(set_timeout) = js_sys::Reflect::get(&global, &"setTimeout".into()) {
-                    let set_timeout: js_sys::Function = set_timeout.dyn_into().unwrap();
-                    let _ = set_timeout.call2(&global, &resolve, &10.into());
-                } else {
-                    // Fallback: resolve immediately if no setTimeout available
-                    let _ = resolve.call0(&JsValue::UNDEFINED);
-                }
-            }
-        });
+    /// Run the registered transform `name`, caching the result
+    fn run_transform(
+        &mut self,
+        name: &str,
+        mut data: Vec<u8>,
+        params: &TransformParams,
+    ) -> Result<Vec<u8>, WasmError> {
+        let cache_key = cache_key(&format!("{name}{}", params.tag()), &data);
+        if let Some(cached_result) = self.processing_cache.borrow_mut().get(&cache_key) {
+            console_log!("Returning cached result for key: {}", cache_key);
+            return Ok(cached_result.to_vec());
+        }
+
+        self.apply_transform(name, &mut data, params)?;
+        self.processing_cache
+            .borrow_mut()
+            .insert(cache_key, data.clone());
+        Ok(data)
+    }
 
-        wasm_bindgen_futures::JsFuture::from(promise).await?;
+    /// Internal asynchronous data transformation, adding each byte's index
+    async fn async_transform(
+        mut data: Vec<u8>,
+        on_progress: Option<&Function>,
+    ) -> Result<Vec<u8>, WasmError> {
+        let total = data.len();
+        for (index, chunk) in data.chunks_mut(ASYNC_CHUNK).enumerate() {
+            sleep(0).await?;
 
-        // Perform transformation
-        for (i, byte) in data.iter_mut().enumerate() {
-            *byte = byte.wrapping_add(i as u8);
+            let offset = index * ASYNC_CHUNK;
+            for (i, byte) in chunk.iter_mut().enumerate() {
+                *byte = byte.wrapping_add((offset + i) as u8);
+            }
+            if let Some(on_progress) = on_progress {
+                let done = (offset + chunk.len()) as f64;
+                on_progress.call2(&JsValue::NULL, &done.into(), &(total as f64).into())?;
+            }
         }
 
         Ok(data)
     }
 }
 
-/// Utility function for memory management
+/// Cache key for `data` under the transform named `kind`.
+///
+/// The key embeds a 128-bit xxh3 hash of the content, so only identical
+/// inputs share an entry.
+fn cache_key(kind: &str, data: &[u8]) -> String {
+    format!("{kind}_{:032x}", xxh3_128(data))
+}
+
+/// Resolve after `millis` milliseconds via the host's `setTimeout`, letting
+/// the event loop run in between; `sleep(0)` just yields to it
+async fn sleep(millis: i32) -> Result<(), WasmError> {
+    let promise = Promise::new(&mut |resolve, reject| {
+        // Try browser environment first (setTimeout via Window)
+        if let Ok(window) = js_sys::global().dyn_into::<web_sys::Window>() {
+            let _ = window.set_timeout_with_callback_and_timeout_and_arguments_0(&resolve, millis);
+            return;
+        }
+
+        // Node.js or worker environment - use setTimeout from global object
+        match global_set_timeout() {
+            Ok(Some(set_timeout)) => {
+                let _ = set_timeout.call2(&js_sys::global(), &resolve, &millis.into());
+            }
+            // Fallback: resolve immediately if no setTimeout available
+            Ok(None) => {
+                let _ = resolve.call0(&JsValue::UNDEFINED);
+            }
+            Err(error) => {
+                let _ = reject.call1(&JsValue::UNDEFINED, &error.into());
+            }
+        }
+    });
+
+    wasm_bindgen_futures::JsFuture::from(promise).await?;
+    Ok(())
+}
+
+/// Look up `globalThis.setTimeout`, if the host provides one
+fn global_set_timeout() -> Result<Option<js_sys::Function>, WasmError> {
+    let set_timeout = js_sys::Reflect::get(&js_sys::global(), &"setTimeout".into())?;
+    if set_timeout.is_undefined() {
+        return Ok(None);
+    }
+
+    set_timeout
+        .dyn_into::<js_sys::Function>()
+        .map(Some)
+        .map_err(|_| WasmError::invalid("globalThis.setTimeout", "not a function"))
+}
+
+/// Install a panic hook that turns Rust panics into thrown JavaScript errors.
+///
+/// Kept for existing callers; [`init`] does this and more, and every
+/// constructor already does it, so there is no need to call it.
 #[wasm_bindgen]
-pub fn get_memory_usage() -> usize {
-    // This is a simplified example
-    // In practice, you'd use more sophisticated memory tracking
-    std::mem::size_of::<WasmModule>()
+pub fn init_panic_handler() {
+    init::ensure_init();
 }
 
-/// Initialize panic hook and logging
+/// Initialize panic hook and logging when the module is instantiated.
+///
+/// Not named `main`, which wasm-bindgen-test's harness exports too.
 #[wasm_bindgen(start)]
-pub fn main() {
+pub fn start() {
+    init::ensure_init();
     console_log!("WASM module initialized");
 }
+
+// A `panic=abort` WASM build has no way to contain a panic to test
+#[cfg(all(test, any(not(target_arch = "wasm32"), panic = "unwind")))]
+mod tests {
+    use rayon::prelude::*;
+
+    use super::*;
+
+    /// Panic part way through parallel work on `runtime`, then check the
+    /// error and that the runtime still works
+    fn check_panic_is_contained(runtime: &WasmRuntime) {
+        let data: Vec<i32> = (0..10_000).collect();
+
+        let result = install(runtime, || {
+            data.par_iter()
+                .map(|&x| {
+                    if x == 5000 {
+                        panic!("bad element {x}")
+                    } else {
+                        x
+                    }
+                })
+                .sum::<i32>()
+        });
+        let error = result.unwrap_err();
+        assert_eq!(error.code(), "INTERNAL");
+        assert!(error.to_string().contains("bad element 5000"), "{error}");
+
+        // The runtime and its processors stay usable
+        assert_eq!(
+            install(runtime, || data.par_iter().sum::<i32>()),
+            Ok(49_995_000)
+        );
+        let processor = WasmParallelProcessor::with_runtime(runtime);
+        assert_eq!(processor.parallel_sum(&[1, 2, 3]), Ok(6));
+    }
+
+    #[test]
+    #[cfg(not(target_arch = "wasm32"))]
+    fn panic_in_parallel_work_is_an_internal_error() {
+        check_panic_is_contained(&WasmRuntime::new(2).unwrap());
+    }
+
+    /// Runs with `make test-wasm-bindgen`. The panic hook must stay out of
+    /// the way here, or the call would throw instead of returning the error.
+    #[wasm_bindgen_test::wasm_bindgen_test]
+    #[cfg(target_arch = "wasm32")]
+    fn panic_in_wasm_is_an_internal_error() {
+        init::ensure_init();
+        check_panic_is_contained(&WasmRuntime::new(0).unwrap());
+    }
+}
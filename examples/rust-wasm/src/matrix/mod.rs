@@ -0,0 +1,222 @@
+use rayon::prelude::*;
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    install,
+    profile::{OpScope, OpStats, Profiler},
+    WasmError, WasmRuntime,
+};
+
+mod scratch;
+mod solve;
+mod svd;
+
+use scratch::ScratchArena;
+
+/// Dense row-major matrix operations over `f64` data
+#[wasm_bindgen]
+pub struct WasmMatrixProcessor {
+    runtime: WasmRuntime,
+    profiler: Profiler,
+    /// Temporaries of the operations in progress, on loan from the
+    /// runtime's buffer pool
+    scratch: ScratchArena,
+}
+
+#[wasm_bindgen]
+impl WasmMatrixProcessor {
+    /// Create a processor on the shared runtime of `num_threads` workers
+    /// (see [`WasmRuntime::shared`])
+    #[wasm_bindgen(constructor)]
+    pub fn new(num_threads: usize) -> Result<WasmMatrixProcessor, WasmError> {
+        Ok(WasmMatrixProcessor::with_runtime(&WasmRuntime::shared(
+            num_threads,
+        )?))
+    }
+
+    /// Create a processor sharing `runtime`'s thread pool
+    #[wasm_bindgen]
+    pub fn with_runtime(runtime: &WasmRuntime) -> WasmMatrixProcessor {
+        WasmMatrixProcessor {
+            runtime: runtime.clone(),
+            profiler: Profiler::default(),
+            scratch: ScratchArena::new(runtime.buffer_pool()),
+        }
+    }
+
+    /// The runtime whose thread pool this processor uses
+    #[wasm_bindgen(getter)]
+    pub fn runtime(&self) -> WasmRuntime {
+        self.runtime.clone()
+    }
+
+    /// Workers available to this processor, which change if its runtime is
+    /// resized
+    #[wasm_bindgen]
+    pub fn current_num_threads(&self) -> usize {
+        self.runtime.num_threads()
+    }
+
+    /// Time this processor's operations for `last_op_stats`, forgetting
+    /// the last operation's stats either way
+    #[wasm_bindgen]
+    pub fn set_profiling(&mut self, enabled: bool) {
+        self.profiler.set_enabled(enabled);
+    }
+
+    /// What the last operation run while profiling did, as `{ operation,
+    /// wall_ms, input_elements, pool_threads, bytes_allocated }`, or
+    /// `undefined` if none has run.
+    ///
+    /// `wall_ms` covers the operation inside the module, not copying its
+    /// arguments and result across the JavaScript boundary,
+    /// `pool_threads` is the size of the pool its parallel work ran on, not
+    /// how many of those workers took a share, and `bytes_allocated` is the
+    /// capacity of the buffers it returned.
+    #[wasm_bindgen]
+    pub fn last_op_stats(&self) -> JsValue {
+        self.last_op()
+            .map_or(JsValue::UNDEFINED, |stats| stats.to_js())
+    }
+
+    /// Bytes of temporaries this processor's operations took from the
+    /// runtime's buffer pool, which holds them for the next operation
+    #[wasm_bindgen]
+    pub fn scratch_bytes(&self) -> usize {
+        self.scratch.bytes()
+    }
+
+    /// Free the memory [`WasmMatrixProcessor::scratch_bytes`] reports, for
+    /// instance after one unusually large operation. Buffers other
+    /// processors keep in the runtime's pool are left alone; later
+    /// operations allocate again as needed.
+    #[wasm_bindgen]
+    pub fn scratch_reset(&self) {
+        self.scratch.release();
+    }
+
+    /// Multiply an `a_rows x a_cols` matrix by a `b_rows x b_cols` matrix.
+    ///
+    /// `b` is first transposed into scratch space so that every output
+    /// cell reads a row of `a` and a row of `bᵀ` contiguously.
+    #[wasm_bindgen]
+    pub fn multiply(
+        &self,
+        a: &[f64],
+        a_rows: usize,
+        a_cols: usize,
+        b: &[f64],
+        b_rows: usize,
+        b_cols: usize,
+    ) -> Result<Vec<f64>, WasmError> {
+        let timing = self.profile("multiply", a.len());
+        if a_cols != b_rows {
+            return Err(WasmError::dimension(
+                "Rows of b (columns of a)",
+                a_cols,
+                b_rows,
+            ));
+        }
+        check_shape(a, a_rows, a_cols)?;
+        check_shape(b, b_rows, b_cols)?;
+
+        // Empty inner dimensions pass the shape checks with any outer ones
+        let len = a_rows
+            .checked_mul(b_cols)
+            .ok_or_else(|| too_large(a_rows, b_cols))?;
+        let mut result = Vec::new();
+        result
+            .try_reserve_exact(len)
+            .map_err(|e| WasmError::ResourceUnavailable {
+                reason: format!("Failed to allocate a {a_rows}x{b_cols} product: {e}"),
+            })?;
+        result.resize(len, 0.0);
+        let mut b_transposed = self.scratch.take(b.len());
+        self.install(|| {
+            transpose_into(b, b_rows, b_cols, &mut b_transposed);
+            let b_transposed = &*b_transposed;
+            result
+                .par_chunks_mut(b_cols.max(1))
+                .zip(a.par_chunks(a_cols.max(1)))
+                .for_each(|(row, a_row)| {
+                    for (cell, b_column) in row.iter_mut().zip(b_transposed.chunks(a_cols.max(1))) {
+                        *cell = a_row.iter().zip(b_column).map(|(x, y)| x * y).sum();
+                    }
+                });
+        })?;
+
+        timing.finish(Ok(result))
+    }
+
+    /// Transpose a `rows x cols` matrix
+    #[wasm_bindgen]
+    pub fn transpose(
+        &self,
+        matrix: &[f64],
+        rows: usize,
+        cols: usize,
+    ) -> Result<Vec<f64>, WasmError> {
+        let timing = self.profile("transpose", matrix.len());
+        check_shape(matrix, rows, cols)?;
+
+        let mut result = vec![0.0; rows * cols];
+        self.install(|| transpose_into(matrix, rows, cols, &mut result))?;
+
+        timing.finish(Ok(result))
+    }
+}
+
+impl WasmMatrixProcessor {
+    /// Stats of the last operation run while profiling, as
+    /// [`WasmMatrixProcessor::last_op_stats`] reports them
+    pub fn last_op(&self) -> Option<OpStats> {
+        self.profiler.last()
+    }
+
+    /// Profile `operation` on `input_elements` values until the scope is
+    /// finished or dropped
+    fn profile(&self, operation: &'static str, input_elements: usize) -> OpScope {
+        self.profiler.scope(operation, input_elements)
+    }
+
+    /// Run `op` on this processor's thread pool, reporting a panic as
+    /// [`WasmError::Internal`]
+    fn install<R: Send>(&self, op: impl FnOnce() -> R + Send) -> Result<R, WasmError> {
+        install(&self.runtime, op)
+    }
+}
+
+/// Write the transpose of a `rows x cols` matrix to `out`, one output row
+/// per task
+fn transpose_into(matrix: &[f64], rows: usize, cols: usize, out: &mut [f64]) {
+    out.par_chunks_mut(rows.max(1))
+        .enumerate()
+        .for_each(|(j, column)| {
+            for (i, cell) in column.iter_mut().enumerate() {
+                *cell = matrix[i * cols + j];
+            }
+        });
+}
+
+/// Validate that a flat matrix holds exactly `rows * cols` elements
+fn check_shape(matrix: &[f64], rows: usize, cols: usize) -> Result<(), WasmError> {
+    let expected = rows
+        .checked_mul(cols)
+        .ok_or_else(|| too_large(rows, cols))?;
+    if matrix.len() != expected {
+        return Err(WasmError::dimension(
+            format!("Data length of a {rows}x{cols} matrix"),
+            expected,
+            matrix.len(),
+        ));
+    }
+    Ok(())
+}
+
+/// The error for a `rows x cols` matrix whose length overflows `usize`
+fn too_large(rows: usize, cols: usize) -> WasmError {
+    WasmError::invalid(
+        "matrix size",
+        format!("a {rows}x{cols} matrix overflows its size"),
+    )
+}
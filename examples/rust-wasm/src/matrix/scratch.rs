@@ -0,0 +1,107 @@
+use std::{
+    mem::{self, size_of},
+    ops::{Deref, DerefMut},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex, MutexGuard, PoisonError,
+    },
+};
+
+use crate::pool::BufferPool;
+
+/// Temporaries for a processor's matrix operations, taken from its
+/// runtime's buffer pool and given back when each operation ends, so that
+/// solving many small systems in a loop stops allocating and processors on
+/// one runtime reuse each other's buffers.
+///
+/// The arena remembers which buffers it gave back, so that resetting it
+/// frees those and leaves the rest of the pool warm.
+#[derive(Debug)]
+pub(super) struct ScratchArena {
+    pool: Arc<BufferPool>,
+    /// Bytes currently taken by operations on this processor
+    leased: AtomicUsize,
+    /// Capacities of the buffers this arena gave back to the pool and has
+    /// not taken again
+    returned: Mutex<Vec<usize>>,
+}
+
+impl ScratchArena {
+    pub(super) fn new(pool: &Arc<BufferPool>) -> ScratchArena {
+        ScratchArena {
+            pool: Arc::clone(pool),
+            leased: AtomicUsize::new(0),
+            returned: Mutex::default(),
+        }
+    }
+
+    /// `len` zeroes from the pool, returned to it when the lease is
+    /// dropped, including on an error path
+    pub(super) fn take(&self, len: usize) -> Scratch<'_> {
+        let buffer = self.pool.take(len);
+        let capacity = buffer.capacity();
+        {
+            let mut returned = self.returned();
+            if let Some(index) = returned.iter().position(|&held| held == capacity) {
+                returned.swap_remove(index);
+            }
+        }
+        self.leased
+            .fetch_add(capacity * size_of::<f64>(), Ordering::Relaxed);
+        Scratch {
+            arena: self,
+            buffer,
+        }
+    }
+
+    /// Bytes of this arena's buffers, on loan or waiting in the pool
+    pub(super) fn bytes(&self) -> usize {
+        let returned: usize = self.returned().iter().sum();
+        self.leased.load(Ordering::Relaxed) + returned * size_of::<f64>()
+    }
+
+    /// Free the buffers this arena gave back to the pool; the next
+    /// operation allocates again. Other processors' buffers stay pooled.
+    pub(super) fn release(&self) {
+        let mut returned = self.returned();
+        self.pool.discard::<f64>(&returned);
+        returned.clear();
+    }
+
+    fn returned(&self) -> MutexGuard<'_, Vec<usize>> {
+        self.returned.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// A buffer on loan from a [`ScratchArena`]
+pub(super) struct Scratch<'a> {
+    arena: &'a ScratchArena,
+    buffer: Vec<f64>,
+}
+
+impl Deref for Scratch<'_> {
+    type Target = [f64];
+
+    fn deref(&self) -> &[f64] {
+        &self.buffer
+    }
+}
+
+impl DerefMut for Scratch<'_> {
+    fn deref_mut(&mut self) -> &mut [f64] {
+        &mut self.buffer
+    }
+}
+
+impl Drop for Scratch<'_> {
+    fn drop(&mut self) {
+        let buffer = mem::take(&mut self.buffer);
+        let capacity = buffer.capacity();
+        self.arena
+            .leased
+            .fetch_sub(capacity * size_of::<f64>(), Ordering::Relaxed);
+        if self.arena.pool.recycle(buffer) {
+            self.arena.returned().push(capacity);
+        }
+    }
+}
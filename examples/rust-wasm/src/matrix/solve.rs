@@ -0,0 +1,178 @@
+use rayon::prelude::*;
+use wasm_bindgen::prelude::*;
+
+use super::{check_shape, WasmMatrixProcessor};
+use crate::WasmError;
+
+/// Elimination steps touching fewer cells than this update the remaining
+/// rows on the calling thread, where a task would cost more than the work
+const MIN_PARALLEL_CELLS: usize = 4096;
+
+#[wasm_bindgen]
+impl WasmMatrixProcessor {
+    /// Solve `Ax = b` for a square `n x n` matrix using Gaussian elimination
+    /// with partial pivoting.
+    ///
+    /// Each elimination step updates the remaining rows in parallel once
+    /// the system is large enough; back substitution is inherently
+    /// sequential.
+    #[wasm_bindgen]
+    pub fn solve_linear_system(
+        &self,
+        a: &[f64],
+        b: &[f64],
+        n: usize,
+    ) -> Result<Vec<f64>, WasmError> {
+        let timing = self.profile("solve_linear_system", a.len());
+        let mut solution = vec![0.0; b.len()];
+        timing.finish(self.solve_square(a, b, n, &mut solution).map(|()| solution))
+    }
+
+    /// [`WasmMatrixProcessor::solve_linear_system`] writing the solution to
+    /// `out`, which must hold `n` values.
+    ///
+    /// Temporaries come from the processor's scratch space, so solving many
+    /// systems no larger than the first allocates nothing on the Rust side.
+    #[wasm_bindgen]
+    pub fn solve_linear_system_into(
+        &self,
+        a: &[f64],
+        b: &[f64],
+        n: usize,
+        out: &mut [f64],
+    ) -> Result<(), WasmError> {
+        let timing = self.profile("solve_linear_system_into", a.len());
+        if out.len() != n {
+            return Err(WasmError::dimension("Output length", n, out.len()));
+        }
+        timing.finish(self.solve_square(a, b, n, out))
+    }
+
+    /// Least-squares solution of an overdetermined `m x n` system via the
+    /// normal equations `AᵀAx = Aᵀb`
+    #[wasm_bindgen]
+    pub fn solve_least_squares(
+        &self,
+        a: &[f64],
+        b: &[f64],
+        m: usize,
+        n: usize,
+    ) -> Result<Vec<f64>, WasmError> {
+        let timing = self.profile("solve_least_squares", a.len());
+        check_shape(a, m, n)?;
+        if b.len() != m {
+            return Err(WasmError::dimension("Right-hand side length", m, b.len()));
+        }
+        if m < n {
+            return Err(WasmError::invalid(
+                "matrix shape",
+                "least squares requires at least as many rows as columns",
+            ));
+        }
+
+        // The normal equations AᵀA and Aᵀb, one row of each per task
+        let (mut normal, mut projected) = (self.scratch.take(n * n), self.scratch.take(n));
+        self.install(|| {
+            normal
+                .par_chunks_exact_mut(n.max(1))
+                .zip(projected.par_iter_mut())
+                .enumerate()
+                .for_each(|(i, (row, rhs))| {
+                    for (j, cell) in row.iter_mut().enumerate() {
+                        *cell = (0..m).map(|r| a[r * n + i] * a[r * n + j]).sum();
+                    }
+                    *rhs = (0..m).map(|r| a[r * n + i] * b[r]).sum();
+                });
+        })?;
+
+        let mut solution = vec![0.0; n];
+        let solved = self.solve_square(&normal, &projected, n, &mut solution);
+        timing.finish(solved.map(|()| solution))
+    }
+}
+
+impl WasmMatrixProcessor {
+    /// Check a square system and solve it into `out`, which holds `n` values
+    fn solve_square(
+        &self,
+        a: &[f64],
+        b: &[f64],
+        n: usize,
+        out: &mut [f64],
+    ) -> Result<(), WasmError> {
+        check_shape(a, n, n)?;
+        if b.len() != n {
+            return Err(WasmError::dimension("Right-hand side length", n, b.len()));
+        }
+
+        // Augmented matrix [A | b], one row per equation
+        let stride = n + 1;
+        let mut augmented = self.scratch.take(n * stride);
+        for ((target, row), &rhs) in augmented
+            .chunks_exact_mut(stride)
+            .zip(a.chunks_exact(n.max(1)))
+            .zip(b)
+        {
+            target[..n].copy_from_slice(row);
+            target[n] = rhs;
+        }
+        self.eliminate(&mut augmented, n, out)
+    }
+
+    /// Solve the augmented system `[A | b]`, `n` rows of `n + 1` values, by
+    /// Gaussian elimination with partial pivoting, overwriting `augmented`
+    /// and writing the `n` unknowns to `out`
+    fn eliminate(&self, augmented: &mut [f64], n: usize, out: &mut [f64]) -> Result<(), WasmError> {
+        let stride = n + 1;
+        let scale = augmented
+            .chunks_exact(stride)
+            .flat_map(|row| &row[..n])
+            .fold(0.0f64, |max, value| max.max(value.abs()));
+        let tolerance = scale * n as f64 * f64::EPSILON;
+
+        for k in 0..n {
+            let pivot_row = (k..n)
+                .max_by(|&i, &j| {
+                    augmented[i * stride + k]
+                        .abs()
+                        .total_cmp(&augmented[j * stride + k].abs())
+                })
+                .unwrap_or(k);
+            let pivot = augmented[pivot_row * stride + k];
+            if !pivot.is_finite() || pivot.abs() <= tolerance {
+                return Err(WasmError::Singular {
+                    reason: format!("zero pivot in column {k}"),
+                });
+            }
+
+            if pivot_row != k {
+                for column in 0..stride {
+                    augmented.swap(k * stride + column, pivot_row * stride + column);
+                }
+            }
+
+            let (upper, lower) = augmented.split_at_mut((k + 1) * stride);
+            let pivot_values = &upper[k * stride..];
+            let update = |row: &mut [f64]| {
+                let factor = row[k] / pivot;
+                if factor != 0.0 {
+                    for (cell, &value) in row[k..].iter_mut().zip(&pivot_values[k..]) {
+                        *cell -= factor * value;
+                    }
+                }
+            };
+            if lower.len() < MIN_PARALLEL_CELLS {
+                lower.chunks_exact_mut(stride).for_each(update);
+            } else {
+                self.install(|| lower.par_chunks_exact_mut(stride).for_each(update))?;
+            }
+        }
+
+        for i in (0..n).rev() {
+            let row = &augmented[i * stride..(i + 1) * stride];
+            let known: f64 = (i + 1..n).map(|j| row[j] * out[j]).sum();
+            out[i] = (row[n] - known) / row[i];
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,313 @@
+use js_sys::{Float64Array, Object, Reflect};
+use wasm_bindgen::prelude::*;
+
+use super::{check_shape, scratch::ScratchArena, WasmMatrixProcessor};
+use crate::WasmError;
+
+/// QR sweeps allowed per singular value before giving up
+const MAX_SWEEPS_PER_VALUE: usize = 75;
+
+#[wasm_bindgen]
+impl WasmMatrixProcessor {
+    /// Singular value decomposition `A = U * diag(sigma) * Vᵀ` of a row-major
+    /// 2x2 matrix.
+    ///
+    /// Returns `{ u, sigma, vt }` as `Float64Array`s, with `u` and `vt`
+    /// row-major and `sigma` in descending order.
+    #[wasm_bindgen]
+    pub fn svd_2x2(&self, matrix: &[f64]) -> Result<JsValue, WasmError> {
+        let timing = self.profile("svd_2x2", matrix.len());
+        timing.finish(svd_object(&Svd::compute(matrix, 2, &self.scratch)?))
+    }
+
+    /// Singular value decomposition of a row-major 3x3 matrix by Golub-Reinsch
+    /// (Householder bidiagonalisation followed by implicit-shift QR), in the
+    /// same `{ u, sigma, vt }` form as [`WasmMatrixProcessor::svd_2x2`]
+    #[wasm_bindgen]
+    pub fn svd_3x3(&self, matrix: &[f64]) -> Result<JsValue, WasmError> {
+        let timing = self.profile("svd_3x3", matrix.len());
+        timing.finish(svd_object(&Svd::compute(matrix, 3, &self.scratch)?))
+    }
+
+    /// Moore-Penrose pseudo-inverse of a row-major 2x2 matrix
+    #[wasm_bindgen]
+    pub fn pseudo_inverse_2x2(&self, matrix: &[f64]) -> Result<Vec<f64>, WasmError> {
+        let timing = self.profile("pseudo_inverse_2x2", matrix.len());
+        timing.finish(Ok(Svd::compute(matrix, 2, &self.scratch)?.pseudo_inverse()))
+    }
+
+    /// Moore-Penrose pseudo-inverse of a row-major 3x3 matrix
+    #[wasm_bindgen]
+    pub fn pseudo_inverse_3x3(&self, matrix: &[f64]) -> Result<Vec<f64>, WasmError> {
+        let timing = self.profile("pseudo_inverse_3x3", matrix.len());
+        timing.finish(Ok(Svd::compute(matrix, 3, &self.scratch)?.pseudo_inverse()))
+    }
+}
+
+fn svd_object(svd: &Svd) -> Result<JsValue, WasmError> {
+    let result = Object::new();
+    Reflect::set(&result, &"u".into(), &Float64Array::from(&svd.u[..]))?;
+    Reflect::set(
+        &result,
+        &"sigma".into(),
+        &Float64Array::from(&svd.sigma[..]),
+    )?;
+    Reflect::set(&result, &"vt".into(), &Float64Array::from(&svd.vt()[..]))?;
+    Ok(result.into())
+}
+
+/// SVD of a small square matrix; all matrices are row-major `n x n`
+struct Svd {
+    n: usize,
+    u: Vec<f64>,
+    sigma: Vec<f64>,
+    v: Vec<f64>,
+}
+
+impl Svd {
+    /// Decompose `matrix`, working in `arena` until the factors are sorted
+    fn compute(matrix: &[f64], n: usize, arena: &ScratchArena) -> Result<Svd, WasmError> {
+        check_shape(matrix, n, n)?;
+        if matrix.iter().any(|value| !value.is_finite()) {
+            return Err(WasmError::invalid("matrix", "entries must be finite"));
+        }
+
+        let mut scratch = arena.take(3 * n * n + n);
+        let (b, rest) = scratch.split_at_mut(n * n);
+        let (u, rest) = rest.split_at_mut(n * n);
+        let (v, work) = rest.split_at_mut(n * n);
+        b.copy_from_slice(matrix);
+        identity(u, n);
+        identity(v, n);
+        bidiagonalise(b, u, v, n, work);
+        diagonalise(b, u, v, n)?;
+
+        // Make the singular values non-negative, then sort them descending
+        for i in 0..n {
+            if b[i * n + i].is_sign_negative() {
+                b[i * n + i] = -b[i * n + i];
+                for row in 0..n {
+                    v[row * n + i] = -v[row * n + i];
+                }
+            }
+        }
+        let mut order: Vec<usize> = (0..n).collect();
+        order.sort_by(|&i, &j| b[j * n + j].total_cmp(&b[i * n + i]));
+        let permute =
+            |m: &[f64]| -> Vec<f64> { (0..n * n).map(|k| m[(k / n) * n + order[k % n]]).collect() };
+
+        Ok(Svd {
+            n,
+            sigma: order.iter().map(|&i| b[i * n + i]).collect(),
+            u: permute(u),
+            v: permute(v),
+        })
+    }
+
+    fn vt(&self) -> Vec<f64> {
+        let n = self.n;
+        (0..n * n).map(|k| self.v[(k % n) * n + k / n]).collect()
+    }
+
+    /// `V * diag(1 / sigma) * Uᵀ`, treating negligible singular values as zero
+    fn pseudo_inverse(&self) -> Vec<f64> {
+        let n = self.n;
+        let tolerance = self.sigma[0] * n as f64 * f64::EPSILON;
+        let inverse: Vec<f64> = self
+            .sigma
+            .iter()
+            .map(|&s| if s > tolerance { 1.0 / s } else { 0.0 })
+            .collect();
+
+        (0..n * n)
+            .map(|k| {
+                let (row, col) = (k / n, k % n);
+                (0..n)
+                    .map(|i| self.v[row * n + i] * inverse[i] * self.u[col * n + i])
+                    .sum()
+            })
+            .collect()
+    }
+}
+
+/// Overwrite the `n x n` matrix `m` with the identity
+fn identity(m: &mut [f64], n: usize) {
+    for (k, value) in m.iter_mut().enumerate() {
+        *value = if k / n == k % n { 1.0 } else { 0.0 };
+    }
+}
+
+/// Reduce `b` to upper bidiagonal form with Householder reflections, keeping
+/// `A = U * B * Vᵀ`; `work` holds `n` values for the reflectors
+fn bidiagonalise(b: &mut [f64], u: &mut [f64], v: &mut [f64], n: usize, work: &mut [f64]) {
+    for k in 0..n {
+        // Zero the column below the diagonal
+        let h = &mut work[..n - k];
+        for (value, row) in h.iter_mut().zip(k..n) {
+            *value = b[row * n + k];
+        }
+        if let Some(beta) = householder(h) {
+            for col in k..n {
+                let dot: f64 = (k..n).map(|row| h[row - k] * b[row * n + col]).sum();
+                for row in k..n {
+                    b[row * n + col] -= beta * dot * h[row - k];
+                }
+            }
+            reflect_columns(u, n, k, h, beta);
+        }
+
+        // Zero the row right of the superdiagonal
+        if k + 2 < n {
+            let h = &mut work[..n - k - 1];
+            h.copy_from_slice(&b[k * n + k + 1..(k + 1) * n]);
+            if let Some(beta) = householder(h) {
+                reflect_columns(b, n, k + 1, h, beta);
+                reflect_columns(v, n, k + 1, h, beta);
+            }
+        }
+    }
+}
+
+/// Turn `x` into the Householder vector `h`, returning `beta` such that
+/// `(I - beta h hᵀ) x` is a multiple of the first unit vector, or `None`
+/// (leaving `x` alone) if `x` is already zero
+fn householder(x: &mut [f64]) -> Option<f64> {
+    let norm = x.iter().map(|value| value * value).sum::<f64>().sqrt();
+    if norm == 0.0 {
+        return None;
+    }
+    x[0] -= if x[0] > 0.0 { -norm } else { norm };
+    Some(2.0 / x.iter().map(|value| value * value).sum::<f64>())
+}
+
+/// `M <- M * (I - beta h hᵀ)`, with the reflector acting on columns
+/// `offset..n`
+fn reflect_columns(m: &mut [f64], n: usize, offset: usize, h: &[f64], beta: f64) {
+    for row in m.chunks_exact_mut(n) {
+        let dot: f64 = h.iter().zip(&row[offset..]).map(|(a, b)| a * b).sum();
+        for (value, &hi) in row[offset..].iter_mut().zip(h) {
+            *value -= beta * dot * hi;
+        }
+    }
+}
+
+/// Drive the superdiagonal of the bidiagonal `b` to zero with implicit
+/// Wilkinson-shift QR sweeps (Golub and Van Loan, Algorithm 8.6.2), applying
+/// every rotation to `U` or `V` as well
+fn diagonalise(b: &mut [f64], u: &mut [f64], v: &mut [f64], n: usize) -> Result<(), WasmError> {
+    let at = |row: usize, col: usize| row * n + col;
+    let norm = b.iter().fold(0.0f64, |max, value| max.max(value.abs()));
+    let eps = f64::EPSILON;
+
+    for _ in 0..MAX_SWEEPS_PER_VALUE * n {
+        for i in 0..n - 1 {
+            if b[at(i, i + 1)].abs() <= eps * (b[at(i, i)].abs() + b[at(i + 1, i + 1)].abs()) {
+                b[at(i, i + 1)] = 0.0;
+            }
+        }
+
+        // Find the trailing unreduced block lo..=hi
+        let mut hi = n - 1;
+        while hi > 0 && b[at(hi - 1, hi)] == 0.0 {
+            hi -= 1;
+        }
+        if hi == 0 {
+            return Ok(());
+        }
+        let mut lo = hi - 1;
+        while lo > 0 && b[at(lo - 1, lo)] != 0.0 {
+            lo -= 1;
+        }
+
+        // A zero on the diagonal decouples the block: chase its
+        // superdiagonal entry out of the matrix instead of sweeping
+        if let Some(i) = (lo..=hi).find(|&i| b[at(i, i)].abs() <= eps * norm) {
+            b[at(i, i)] = 0.0;
+            if i < hi {
+                for j in i + 1..=hi {
+                    let (c, s) = givens(b[at(j, j)], b[at(i, j)]);
+                    rotate_rows(b, n, j, i, c, s);
+                    rotate_columns(u, n, j, i, c, s);
+                    b[at(i, j)] = 0.0;
+                }
+            } else {
+                for j in (lo..hi).rev() {
+                    let (c, s) = givens(b[at(j, j)], b[at(j, hi)]);
+                    rotate_columns(b, n, j, hi, c, s);
+                    rotate_columns(v, n, j, hi, c, s);
+                    b[at(j, hi)] = 0.0;
+                }
+            }
+            continue;
+        }
+
+        // Wilkinson shift from the trailing 2x2 block of BᵀB
+        let (d1, d2, e1) = (b[at(hi - 1, hi - 1)], b[at(hi, hi)], b[at(hi - 1, hi)]);
+        let e0 = if hi - 1 > lo {
+            b[at(hi - 2, hi - 1)]
+        } else {
+            0.0
+        };
+        let (t11, t12, t22) = (d1 * d1 + e0 * e0, d1 * e1, d2 * d2 + e1 * e1);
+        let delta = (t11 - t22) / 2.0;
+        let mu = if t12 == 0.0 {
+            t22
+        } else {
+            t22 - t12 * t12 / (delta + delta.signum() * delta.hypot(t12))
+        };
+
+        let mut y = b[at(lo, lo)] * b[at(lo, lo)] - mu;
+        let mut z = b[at(lo, lo)] * b[at(lo, lo + 1)];
+        for k in lo..hi {
+            let (c, s) = givens(y, z);
+            rotate_columns(b, n, k, k + 1, c, s);
+            rotate_columns(v, n, k, k + 1, c, s);
+            if k > lo {
+                b[at(k - 1, k + 1)] = 0.0;
+            }
+
+            let (c, s) = givens(b[at(k, k)], b[at(k + 1, k)]);
+            rotate_rows(b, n, k, k + 1, c, s);
+            rotate_columns(u, n, k, k + 1, c, s);
+            b[at(k + 1, k)] = 0.0;
+
+            if k + 1 < hi {
+                y = b[at(k, k + 1)];
+                z = b[at(k, k + 2)];
+            }
+        }
+    }
+
+    Err(WasmError::NotConverged {
+        algorithm: "SVD".into(),
+    })
+}
+
+/// Rotation `(c, s)` that maps the pair `(a, b)` to `(r, 0)` under
+/// [`rotate_rows`] and [`rotate_columns`]
+fn givens(a: f64, b: f64) -> (f64, f64) {
+    let r = a.hypot(b);
+    if r == 0.0 {
+        (1.0, 0.0)
+    } else {
+        (a / r, -b / r)
+    }
+}
+
+/// Replace columns `(j1, j2)` by `(c * j1 - s * j2, s * j1 + c * j2)`
+fn rotate_columns(m: &mut [f64], n: usize, j1: usize, j2: usize, c: f64, s: f64) {
+    for row in m.chunks_exact_mut(n) {
+        let (x1, x2) = (row[j1], row[j2]);
+        row[j1] = c * x1 - s * x2;
+        row[j2] = s * x1 + c * x2;
+    }
+}
+
+/// Replace rows `(i1, i2)` by `(c * i1 - s * i2, s * i1 + c * i2)`
+fn rotate_rows(m: &mut [f64], n: usize, i1: usize, i2: usize, c: f64, s: f64) {
+    for col in 0..n {
+        let (x1, x2) = (m[i1 * n + col], m[i2 * n + col]);
+        m[i1 * n + col] = c * x1 - s * x2;
+        m[i2 * n + col] = s * x1 + c * x2;
+    }
+}
@@ -0,0 +1,139 @@
+//! What the module's memory holds: the size of WASM linear memory, its peak,
+//! and the buffers of processors registered for the report.
+//!
+//! Processors are held through `WeakRef`s, so registering one does not keep
+//! it alive; one that has been collected or freed drops out of the report.
+
+use std::{
+    cell::RefCell,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use js_sys::{Function, Object, Reflect, WeakRef};
+use wasm_bindgen::prelude::*;
+
+use crate::WasmError;
+
+/// Largest size sampled since the last reset
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    /// Processors in the report, by the name they were registered under
+    static REGISTERED: RefCell<Vec<(String, WeakRef)>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Bytes of WASM linear memory, which only ever grows.
+///
+/// Natively, as in the CLI binaries, it is the resident set size from
+/// `/proc/self/status` on Linux and 0 elsewhere.
+#[wasm_bindgen]
+pub fn process_memory_bytes() -> usize {
+    let bytes = current_bytes();
+    PEAK.fetch_max(bytes, Ordering::Relaxed);
+    bytes
+}
+
+/// Start tracking the peak afresh from the current size
+#[wasm_bindgen]
+pub fn reset_memory_peak() {
+    PEAK.store(current_bytes(), Ordering::Relaxed);
+}
+
+/// Include `processor`'s buffers in [`memory_report`] under `name`,
+/// replacing any processor registered under it before.
+///
+/// Any object with a `heap_bytes()` method will do, such as a
+/// `WasmImageProcessor`, `WasmBatchProcessor`, `MemoryEfficientProcessor`,
+/// `WasmRuntime` or `WasmModule`.
+#[wasm_bindgen]
+pub fn register_for_memory_report(name: &str, processor: &Object) -> Result<(), WasmError> {
+    if heap_bytes_method(processor).is_none() {
+        return Err(WasmError::invalid(
+            "processor",
+            "has no heap_bytes() method to report",
+        ));
+    }
+    REGISTERED.with(|registered| {
+        let mut registered = registered.borrow_mut();
+        registered.retain(|(registered_name, _)| registered_name != name);
+        registered.push((name.to_string(), WeakRef::new(processor)));
+    });
+    Ok(())
+}
+
+/// Leave the processor registered under `name` out of [`memory_report`],
+/// returning whether there was one
+#[wasm_bindgen]
+pub fn unregister_from_memory_report(name: &str) -> bool {
+    REGISTERED.with(|registered| {
+        let mut registered = registered.borrow_mut();
+        let before = registered.len();
+        registered.retain(|(registered_name, _)| registered_name != name);
+        registered.len() < before
+    })
+}
+
+/// The module's memory, as `{ linear_memory_bytes, peak_bytes,
+/// processor_bytes, processors }`.
+///
+/// `linear_memory_bytes` is the size of WASM memory now, and `peak_bytes`
+/// the largest seen by a report, `process_memory_bytes` or
+/// `reset_memory_peak` since the last reset. `processors` maps each
+/// registered name to what its processor's `heap_bytes()` reports, and
+/// `processor_bytes` is their total. The buffers are part of linear
+/// memory, not extra to it.
+#[wasm_bindgen]
+pub fn memory_report() -> JsValue {
+    let linear = process_memory_bytes();
+    let processors = Object::new();
+    let mut total = 0.0;
+    REGISTERED.with(|registered| {
+        registered.borrow_mut().retain(|(name, processor)| {
+            // A processor that was collected, or freed so that the call
+            // throws, is forgotten
+            let Some(bytes) = processor.deref().and_then(|processor| {
+                let method = heap_bytes_method(&processor)?;
+                method.call0(&processor).ok()?.as_f64()
+            }) else {
+                return false;
+            };
+            total += bytes;
+            // Defining a property on a fresh plain object cannot fail
+            let _ = Reflect::set(&processors, &name.into(), &bytes.into());
+            true
+        });
+    });
+
+    let report = Object::new();
+    for (name, value) in [
+        ("linear_memory_bytes", JsValue::from(linear)),
+        ("peak_bytes", PEAK.load(Ordering::Relaxed).into()),
+        ("processor_bytes", total.into()),
+        ("processors", processors.into()),
+    ] {
+        let _ = Reflect::set(&report, &name.into(), &value);
+    }
+    report.into()
+}
+
+fn heap_bytes_method(processor: &Object) -> Option<Function> {
+    Reflect::get(processor, &"heap_bytes".into())
+        .ok()?
+        .dyn_into()
+        .ok()
+}
+
+#[cfg(target_arch = "wasm32")]
+fn current_bytes() -> usize {
+    core::arch::wasm32::memory_size(0) * 64 * 1024
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn current_bytes() -> usize {
+    let status = std::fs::read_to_string("/proc/self/status").unwrap_or_default();
+    status
+        .lines()
+        .find_map(|line| line.strip_prefix("VmRSS:"))
+        .and_then(|value| value.trim().trim_end_matches("kB").trim().parse().ok())
+        .map_or(0, |kib: usize| kib * 1024)
+}
@@ -0,0 +1,74 @@
+use std::f64::consts::PI;
+
+use rayon::prelude::*;
+use wasm_bindgen::prelude::*;
+
+use super::WasmParallelProcessor;
+use crate::WasmError;
+
+/// Neural-network activation functions, applied element-wise.
+///
+/// `NaN` inputs give `NaN`, and infinite inputs give the function's limit.
+#[wasm_bindgen]
+impl WasmParallelProcessor {
+    /// `1 / (1 + e^-x)`
+    #[wasm_bindgen]
+    pub fn parallel_sigmoid(&self, data: &[f64]) -> Result<Vec<f64>, WasmError> {
+        let timing = self.profile("parallel_sigmoid", data.len());
+        timing.finish(self.activate(data, |x| 1.0 / (1.0 + (-x).exp())))
+    }
+
+    /// Hyperbolic tangent
+    #[wasm_bindgen]
+    pub fn parallel_tanh(&self, data: &[f64]) -> Result<Vec<f64>, WasmError> {
+        let timing = self.profile("parallel_tanh", data.len());
+        timing.finish(self.activate(data, f64::tanh))
+    }
+
+    /// `max(0, x)`
+    #[wasm_bindgen]
+    pub fn parallel_relu(&self, data: &[f64]) -> Result<Vec<f64>, WasmError> {
+        let timing = self.profile("parallel_relu", data.len());
+        // Written out rather than `x.max(0.0)`, which would turn NaN into 0
+        timing.finish(self.activate(data, |x| if x < 0.0 { 0.0 } else { x }))
+    }
+
+    /// `x` for non-negative inputs, `alpha * x` below zero
+    #[wasm_bindgen]
+    pub fn parallel_leaky_relu(&self, data: &[f64], alpha: f64) -> Result<Vec<f64>, WasmError> {
+        let timing = self.profile("parallel_leaky_relu", data.len());
+        timing.finish(self.activate(data, |x| if x < 0.0 { alpha * x } else { x }))
+    }
+
+    /// `x` for non-negative inputs, `alpha * (e^x - 1)` below zero
+    #[wasm_bindgen]
+    pub fn parallel_elu(&self, data: &[f64], alpha: f64) -> Result<Vec<f64>, WasmError> {
+        let timing = self.profile("parallel_elu", data.len());
+        timing.finish(self.activate(data, |x| if x < 0.0 { alpha * x.exp_m1() } else { x }))
+    }
+
+    /// GELU by the tanh approximation
+    /// `0.5 * x * (1 + tanh(sqrt(2 / pi) * (x + 0.044715 * x^3)))`
+    #[wasm_bindgen]
+    pub fn parallel_gelu(&self, data: &[f64]) -> Result<Vec<f64>, WasmError> {
+        let timing = self.profile("parallel_gelu", data.len());
+        let scale = (2.0 / PI).sqrt();
+        timing.finish(self.activate(data, |x| {
+            // At -inf the formula is -inf * 0; the limit is 0
+            if x.is_infinite() {
+                return x.max(0.0);
+            }
+            0.5 * x * (1.0 + (scale * (x + 0.044715 * x * x * x)).tanh())
+        }))
+    }
+}
+
+impl WasmParallelProcessor {
+    fn activate(
+        &self,
+        data: &[f64],
+        f: impl Fn(f64) -> f64 + Sync + Send,
+    ) -> Result<Vec<f64>, WasmError> {
+        self.install(|| data.par_iter().map(|&x| f(x)).collect())
+    }
+}
@@ -0,0 +1,292 @@
+use rayon::prelude::*;
+use wasm_bindgen::prelude::*;
+
+use super::{reduce::sum, WasmParallelProcessor};
+use crate::WasmError;
+
+/// Gradient descent iterations before `parallel_arima_fit` gives up
+const MAX_ITERATIONS: usize = 2000;
+
+/// Relative loss improvement below which the fit is considered converged
+const TOLERANCE: f64 = 1e-12;
+
+#[wasm_bindgen]
+impl WasmParallelProcessor {
+    /// Fit an ARIMA(`p`, `d`, `q`) model by conditional sum of squares,
+    /// returning `[phi_1..phi_p, theta_1..theta_q]`.
+    ///
+    /// The series is differenced `d` times and centred on its mean, then
+    /// the mean squared one-step residual is minimised by gradient descent
+    /// with a backtracking step size, starting from all-zero coefficients.
+    /// Gradients are accumulated in parallel over time steps for pure AR
+    /// models and over coefficients otherwise, where the moving-average
+    /// recursion makes each coefficient's sensitivity sequential in time.
+    /// The fit is reproducible in deterministic mode, as
+    /// [`WasmParallelProcessor::parallel_sum_f64`].
+    #[wasm_bindgen]
+    pub fn parallel_arima_fit(
+        &self,
+        series: &[f64],
+        p: usize,
+        d: usize,
+        q: usize,
+    ) -> Result<Vec<f64>, WasmError> {
+        let timing = self.profile("parallel_arima_fit", series.len());
+        check_order(series, p, d, q)?;
+
+        let deterministic = self.deterministic();
+        timing.finish(self.install(|| {
+            let (w, _) = centred_difference(series, d, deterministic);
+            let model = Model {
+                w: &w,
+                p,
+                q,
+                deterministic,
+            };
+
+            let mut coeffs = vec![0.0; p + q];
+            let mut residuals = model.residuals(&coeffs);
+            let mut loss = model.loss(&residuals);
+            let mut step = 1.0;
+            for _ in 0..MAX_ITERATIONS {
+                let gradient = model.gradient(&coeffs, &residuals);
+                let slope: f64 = gradient.iter().map(|g| g * g).sum();
+                if slope == 0.0 {
+                    break;
+                }
+
+                // Halve the step until it gives a sufficient (Armijo)
+                // decrease, then try a larger one next iteration
+                let accepted = loop {
+                    let candidate: Vec<f64> = coeffs
+                        .iter()
+                        .zip(&gradient)
+                        .map(|(c, g)| c - step * g)
+                        .collect();
+                    let candidate_residuals = model.residuals(&candidate);
+                    let candidate_loss = model.loss(&candidate_residuals);
+                    if candidate_loss <= loss - 1e-4 * step * slope {
+                        break Some((candidate, candidate_residuals, candidate_loss));
+                    }
+                    step /= 2.0;
+                    if step < f64::EPSILON {
+                        break None;
+                    }
+                };
+                let Some((candidate, candidate_residuals, candidate_loss)) = accepted else {
+                    break;
+                };
+
+                let improvement = loss - candidate_loss;
+                coeffs = candidate;
+                residuals = candidate_residuals;
+                loss = candidate_loss;
+                step *= 2.0;
+                if improvement <= TOLERANCE * loss {
+                    break;
+                }
+            }
+            coeffs
+        }))
+    }
+
+    /// Forecast the next `n_steps` values of `series` from coefficients
+    /// produced by [`WasmParallelProcessor::parallel_arima_fit`] with the
+    /// same order.
+    ///
+    /// Past shocks are the model's one-step residuals over `series` and
+    /// future shocks are zero, so the forecasts are conditional means. They
+    /// are integrated back through the `d` differences, so they continue
+    /// the original series.
+    #[wasm_bindgen]
+    pub fn arima_forecast(
+        &self,
+        series: &[f64],
+        coeffs: &[f64],
+        p: usize,
+        d: usize,
+        q: usize,
+        n_steps: usize,
+    ) -> Result<Vec<f64>, WasmError> {
+        let timing = self.profile("arima_forecast", series.len());
+        check_order(series, p, d, q)?;
+        if coeffs.len() != p + q {
+            return Err(WasmError::dimension(
+                format!("Coefficient count for ARIMA({p}, {d}, {q})"),
+                p + q,
+                coeffs.len(),
+            ));
+        }
+
+        let deterministic = self.deterministic();
+        timing.finish(self.install(|| {
+            let (w, (mean, lasts)) = centred_difference(series, d, deterministic);
+            let model = Model {
+                w: &w,
+                p,
+                q,
+                deterministic,
+            };
+            let (phi, theta) = coeffs.split_at(p);
+
+            let mut history = w.clone();
+            let mut shocks = model.residuals(coeffs);
+            let mut forecasts = Vec::with_capacity(n_steps);
+            for _ in 0..n_steps {
+                let t = history.len();
+                let value = lagged(phi, &history, t) + lagged(theta, &shocks, t);
+                history.push(value);
+                shocks.push(0.0);
+                forecasts.push(value + mean);
+            }
+
+            // Undo each difference with a running sum seeded from the last
+            // observed value at that level
+            for last in lasts.into_iter().rev() {
+                let mut running = last;
+                for value in &mut forecasts {
+                    running += *value;
+                    *value = running;
+                }
+            }
+            forecasts
+        }))
+    }
+}
+
+fn check_order(series: &[f64], p: usize, d: usize, q: usize) -> Result<(), WasmError> {
+    if p + q == 0 {
+        return Err(WasmError::invalid(
+            "order",
+            "ARIMA needs at least one AR or MA term (p + q > 0)",
+        ));
+    }
+    // Each difference costs a value, the first `p` are conditioned on, and
+    // at least `p + q + 1` residuals must remain to fit against
+    let needed = d + 2 * p + q + 1;
+    if series.len() < needed {
+        return Err(WasmError::invalid(
+            "series",
+            format!(
+                "ARIMA({p}, {d}, {q}) needs at least {needed} values, got {}",
+                series.len()
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// Difference `series` `d` times and subtract the mean, returning the
+/// centred series along with the mean and the last value at each level
+/// before its difference
+fn centred_difference(
+    series: &[f64],
+    d: usize,
+    deterministic: bool,
+) -> (Vec<f64>, (f64, Vec<f64>)) {
+    let mut w = series.to_vec();
+    let mut lasts = Vec::with_capacity(d);
+    for _ in 0..d {
+        lasts.push(w[w.len() - 1]);
+        w = w.par_windows(2).map(|pair| pair[1] - pair[0]).collect();
+    }
+
+    let mean = sum(deterministic, w.len(), |i| w[i]) / w.len() as f64;
+    w.par_iter_mut().for_each(|value| *value -= mean);
+    (w, (mean, lasts))
+}
+
+/// `sum(coeffs[i] * values[t - 1 - i])`, treating values before the start
+/// as zero
+fn lagged(coeffs: &[f64], values: &[f64], t: usize) -> f64 {
+    coeffs
+        .iter()
+        .enumerate()
+        .filter_map(|(i, c)| t.checked_sub(i + 1).map(|lag| c * values[lag]))
+        .sum()
+}
+
+/// Conditional sum-of-squares objective for a centred, differenced series
+struct Model<'a> {
+    w: &'a [f64],
+    p: usize,
+    q: usize,
+    /// Sum in the fixed order of [`sum`]
+    deterministic: bool,
+}
+
+impl Model<'_> {
+    /// One-step residuals `e[t] = w[t] - phi . w[t-1..] - theta . e[t-1..]`,
+    /// zero for the first `p` steps that are conditioned on
+    fn residuals(&self, coeffs: &[f64]) -> Vec<f64> {
+        let (phi, theta) = coeffs.split_at(self.p);
+        let w = self.w;
+        if self.q == 0 {
+            return (0..w.len())
+                .into_par_iter()
+                .map(|t| {
+                    if t < self.p {
+                        0.0
+                    } else {
+                        w[t] - lagged(phi, w, t)
+                    }
+                })
+                .collect();
+        }
+
+        let mut residuals = vec![0.0; w.len()];
+        for t in self.p..w.len() {
+            residuals[t] = w[t] - lagged(phi, w, t) - lagged(theta, &residuals, t);
+        }
+        residuals
+    }
+
+    /// Mean squared residual over the fitted steps, infinite if the
+    /// recursion diverged
+    fn loss(&self, residuals: &[f64]) -> f64 {
+        let fitted = &residuals[self.p..];
+        let loss =
+            sum(self.deterministic, fitted.len(), |t| fitted[t] * fitted[t]) / fitted.len() as f64;
+        if loss.is_finite() {
+            loss
+        } else {
+            f64::INFINITY
+        }
+    }
+
+    /// Gradient of [`Model::loss`] with respect to `[phi, theta]`
+    fn gradient(&self, coeffs: &[f64], residuals: &[f64]) -> Vec<f64> {
+        let (p, w) = (self.p, self.w);
+        let theta = &coeffs[p..];
+        let scale = 2.0 / (w.len() - p) as f64;
+
+        if self.q == 0 {
+            // de[t]/dphi_i = -w[t - 1 - i] with no recursion
+            return (0..p)
+                .map(|i| {
+                    -scale
+                        * sum(self.deterministic, w.len() - p, |k| {
+                            residuals[p + k] * w[p + k - 1 - i]
+                        })
+                })
+                .collect();
+        }
+
+        // de[t]/dc = -x[t - 1 - lag] - theta . de[t-1..]/dc, where x is the
+        // series for an AR coefficient and the residuals for an MA one
+        (0..coeffs.len())
+            .into_par_iter()
+            .map(|k| {
+                let (source, lag) = if k < p { (w, k) } else { (residuals, k - p) };
+                let mut sensitivity = vec![0.0; w.len()];
+                let mut sum = 0.0;
+                for t in p..w.len() {
+                    let direct = t.checked_sub(lag + 1).map_or(0.0, |i| source[i]);
+                    sensitivity[t] = -direct - lagged(theta, &sensitivity, t);
+                    sum += residuals[t] * sensitivity[t];
+                }
+                scale * sum
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,186 @@
+use rayon::prelude::*;
+use wasm_bindgen::prelude::*;
+
+use super::WasmParallelProcessor;
+use crate::WasmError;
+
+/// Equal spans of `t` that `parallel_bezier_adaptive_sample` flattens in
+/// parallel
+const ADAPTIVE_SPANS: usize = 16;
+
+/// Deepest each span is halved, bounding the samples at 4096 per span
+const MAX_DEPTH: u32 = 12;
+
+type Point = [f64; 2];
+
+#[wasm_bindgen]
+impl WasmParallelProcessor {
+    /// Points on the Bézier curve of `degree` at each `t`, as flat `x, y`
+    /// pairs.
+    ///
+    /// `control_points` holds `degree + 1` finite points as `x, y` pairs.
+    /// Every `t` must be in [0, 1] and is evaluated on its own by De
+    /// Casteljau's algorithm, which stays stable at any degree.
+    #[wasm_bindgen]
+    pub fn parallel_bezier_sample(
+        &self,
+        control_points: &[f64],
+        degree: u8,
+        t_values: &[f64],
+    ) -> Result<Vec<f64>, WasmError> {
+        let timing = self.profile("parallel_bezier_sample", t_values.len());
+        let control = control_polygon(control_points, degree)?;
+        if let Some(index) = t_values.iter().position(|t| !(0.0..=1.0).contains(t)) {
+            return Err(WasmError::OutOfDomain {
+                index,
+                values: vec![t_values[index]],
+                reason: "t must be in [0, 1]".to_string(),
+                slice: None,
+            });
+        }
+
+        timing.finish(self.install(|| {
+            t_values
+                .par_iter()
+                .flat_map_iter(|&t| de_casteljau(&control, t))
+                .collect()
+        }))
+    }
+
+    /// Points along the Bézier curve of `degree`, as flat `x, y` pairs,
+    /// spaced so that the polyline through them strays at most `max_error`
+    /// from the curve.
+    ///
+    /// The curve is cut into 16 equal spans of `t`, flattened in parallel,
+    /// and each span halved until its control polygon lies within
+    /// `max_error` of its chord. Since a Bézier curve lies inside its
+    /// control polygon's hull, that bounds the error. Sharp bends get more
+    /// points and straight stretches fewer. The first point is the curve's
+    /// start and the last its end. `max_error` must be positive and finite,
+    /// and a span stops being halved after 12 levels whatever the error.
+    #[wasm_bindgen]
+    pub fn parallel_bezier_adaptive_sample(
+        &self,
+        control_points: &[f64],
+        degree: u8,
+        max_error: f64,
+    ) -> Result<Vec<f64>, WasmError> {
+        let timing = self.profile("parallel_bezier_adaptive_sample", control_points.len());
+        let control = control_polygon(control_points, degree)?;
+        if !max_error.is_finite() || max_error <= 0.0 {
+            return Err(WasmError::invalid(
+                "max error",
+                format!("must be positive and finite, got {max_error}"),
+            ));
+        }
+
+        timing.finish(self.install(|| {
+            let spans: Vec<Vec<f64>> = (0..ADAPTIVE_SPANS)
+                .into_par_iter()
+                .map(|span| {
+                    let t0 = span as f64 / ADAPTIVE_SPANS as f64;
+                    let t1 = (span + 1) as f64 / ADAPTIVE_SPANS as f64;
+                    let mut points = Vec::new();
+                    flatten(&sub_curve(&control, t0, t1), max_error, 0, &mut points);
+                    points
+                })
+                .collect();
+            let mut points = Vec::with_capacity(2 + spans.iter().map(Vec::len).sum::<usize>());
+            points.extend_from_slice(&control[0]);
+            for span in spans {
+                points.extend(span);
+            }
+            points
+        }))
+    }
+}
+
+/// `control_points` as points, checking there are `degree + 1` finite ones
+fn control_polygon(control_points: &[f64], degree: u8) -> Result<Vec<Point>, WasmError> {
+    let expected = (usize::from(degree) + 1) * 2;
+    if control_points.len() != expected {
+        return Err(WasmError::dimension(
+            format!("Control point coordinates for degree {degree}"),
+            expected,
+            control_points.len(),
+        ));
+    }
+    if let Some(index) = control_points.iter().position(|x| !x.is_finite()) {
+        return Err(WasmError::invalid(
+            "control points",
+            format!("coordinate {index} is {}", control_points[index]),
+        ));
+    }
+    Ok(control_points
+        .chunks_exact(2)
+        .map(|point| [point[0], point[1]])
+        .collect())
+}
+
+/// The point at `t`
+fn de_casteljau(control: &[Point], t: f64) -> Point {
+    let mut points = control.to_vec();
+    for level in (1..points.len()).rev() {
+        for i in 0..level {
+            points[i] = lerp(points[i], points[i + 1], t);
+        }
+    }
+    points[0]
+}
+
+/// The control polygons of the curve before and after `t`
+fn split(control: &[Point], t: f64) -> (Vec<Point>, Vec<Point>) {
+    let n = control.len();
+    let mut points = control.to_vec();
+    let mut left = Vec::with_capacity(n);
+    let mut right = vec![[0.0; 2]; n];
+    for level in (0..n).rev() {
+        left.push(points[0]);
+        right[level] = points[level];
+        for i in 0..level {
+            points[i] = lerp(points[i], points[i + 1], t);
+        }
+    }
+    (left, right)
+}
+
+/// The control polygon of the part of the curve from `t0` to `t1`
+fn sub_curve(control: &[Point], t0: f64, t1: f64) -> Vec<Point> {
+    let (before_t1, _) = split(control, t1);
+    if t0 == 0.0 {
+        return before_t1;
+    }
+    split(&before_t1, t0 / t1).1
+}
+
+/// Append the points after the start of `control` that keep the polyline
+/// within `max_error` of it
+fn flatten(control: &[Point], max_error: f64, depth: u32, points: &mut Vec<f64>) {
+    let (start, end) = (control[0], control[control.len() - 1]);
+    let interior = control.get(1..control.len() - 1).unwrap_or_default();
+    let flat = interior
+        .iter()
+        .all(|&p| distance_to_segment(p, start, end) <= max_error);
+    if flat || depth == MAX_DEPTH {
+        points.extend_from_slice(&end);
+        return;
+    }
+    let (left, right) = split(control, 0.5);
+    flatten(&left, max_error, depth + 1, points);
+    flatten(&right, max_error, depth + 1, points);
+}
+
+fn distance_to_segment(p: Point, a: Point, b: Point) -> f64 {
+    let (dx, dy) = (b[0] - a[0], b[1] - a[1]);
+    let length2 = dx * dx + dy * dy;
+    let t = if length2 == 0.0 {
+        0.0
+    } else {
+        (((p[0] - a[0]) * dx + (p[1] - a[1]) * dy) / length2).clamp(0.0, 1.0)
+    };
+    (p[0] - a[0] - t * dx).hypot(p[1] - a[1] - t * dy)
+}
+
+fn lerp(a: Point, b: Point, t: f64) -> Point {
+    [a[0] + (b[0] - a[0]) * t, a[1] + (b[1] - a[1]) * t]
+}
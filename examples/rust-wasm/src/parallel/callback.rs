@@ -0,0 +1,41 @@
+use js_sys::Function;
+use wasm_bindgen::prelude::*;
+
+use super::WasmParallelProcessor;
+use crate::WasmError;
+
+#[wasm_bindgen]
+impl WasmParallelProcessor {
+    /// Map each element through a JavaScript `callback(value, index)`, which
+    /// must return a number.
+    ///
+    /// JavaScript functions can only be called from the thread that owns
+    /// them, never from Rayon workers, so this runs sequentially on the
+    /// calling thread and gains nothing from the processor's pool. It suits
+    /// one-off operations with no built-in equivalent; prefer the built-in
+    /// methods for hot paths. If the callback throws, mapping stops and the
+    /// thrown value is returned unchanged.
+    #[wasm_bindgen]
+    pub fn parallel_map_js(
+        &self,
+        data: &[f64],
+        callback: &Function,
+    ) -> Result<Vec<f64>, WasmError> {
+        let timing = self.profile("parallel_map_js", data.len());
+        timing.finish(
+            data.iter()
+                .enumerate()
+                .map(|(index, &value)| {
+                    let result =
+                        callback.call2(&JsValue::UNDEFINED, &value.into(), &index.into())?;
+                    result.as_f64().ok_or_else(|| {
+                        WasmError::invalid(
+                            "callback result",
+                            format!("element {index} mapped to a non-number"),
+                        )
+                    })
+                })
+                .collect(),
+        )
+    }
+}
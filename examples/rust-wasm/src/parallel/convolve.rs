@@ -0,0 +1,188 @@
+use std::f64::consts::PI;
+
+use rayon::prelude::*;
+use wasm_bindgen::prelude::*;
+
+use super::WasmParallelProcessor;
+use crate::WasmError;
+
+/// Kernels up to this length are convolved directly, which beats the FFT's
+/// fixed overhead
+const DIRECT_KERNEL_MAX: usize = 16;
+
+/// Butterfly groups at least this long are split across tasks
+const PARALLEL_BUTTERFLIES: usize = 4096;
+
+#[wasm_bindgen]
+impl WasmParallelProcessor {
+    /// Full linear convolution of `signal` with `kernel`, returning
+    /// `signal.len() + kernel.len() - 1` values.
+    ///
+    /// Short kernels are convolved directly. Longer ones go through
+    /// zero-padded power-of-two FFTs: both forward transforms run
+    /// concurrently, and the spectrum product and butterflies run in
+    /// parallel.
+    #[wasm_bindgen]
+    pub fn parallel_fft_convolve(
+        &self,
+        signal: &[f64],
+        kernel: &[f64],
+    ) -> Result<Vec<f64>, WasmError> {
+        let timing = self.profile("parallel_fft_convolve", signal.len());
+        if signal.is_empty() || kernel.is_empty() {
+            return Err(WasmError::invalid(
+                "signal and kernel",
+                "must both be non-empty",
+            ));
+        }
+        let len = signal.len() + kernel.len() - 1;
+
+        if kernel.len() <= DIRECT_KERNEL_MAX {
+            return self.install(|| direct_convolve(signal, kernel));
+        }
+
+        timing.finish(self.install(|| {
+            let size = len.next_power_of_two();
+            let twiddles = twiddles(size);
+            let padded = |values: &[f64]| {
+                let mut buffer = vec![Complex::ZERO; size];
+                for (slot, &value) in buffer.iter_mut().zip(values) {
+                    slot.re = value;
+                }
+                buffer
+            };
+
+            let (mut a, mut b) = (padded(signal), padded(kernel));
+            rayon::join(
+                || fft(&mut a, &twiddles, false),
+                || fft(&mut b, &twiddles, false),
+            );
+            a.par_iter_mut().zip(&b).for_each(|(x, y)| *x = x.mul(*y));
+            fft(&mut a, &twiddles, true);
+
+            a[..len].iter().map(|value| value.re).collect()
+        }))
+    }
+}
+
+/// `O(n * k)` convolution, one output sample per task
+fn direct_convolve(signal: &[f64], kernel: &[f64]) -> Vec<f64> {
+    (0..signal.len() + kernel.len() - 1)
+        .into_par_iter()
+        .map(|i| {
+            let first = i.saturating_sub(kernel.len() - 1);
+            let last = i.min(signal.len() - 1);
+            (first..=last).map(|j| signal[j] * kernel[i - j]).sum()
+        })
+        .collect()
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Complex {
+    re: f64,
+    im: f64,
+}
+
+impl Complex {
+    const ZERO: Complex = Complex { re: 0.0, im: 0.0 };
+
+    fn add(self, other: Complex) -> Complex {
+        Complex {
+            re: self.re + other.re,
+            im: self.im + other.im,
+        }
+    }
+
+    fn sub(self, other: Complex) -> Complex {
+        Complex {
+            re: self.re - other.re,
+            im: self.im - other.im,
+        }
+    }
+
+    fn mul(self, other: Complex) -> Complex {
+        Complex {
+            re: self.re * other.re - self.im * other.im,
+            im: self.re * other.im + self.im * other.re,
+        }
+    }
+
+    fn conj(self) -> Complex {
+        Complex {
+            re: self.re,
+            im: -self.im,
+        }
+    }
+}
+
+/// `exp(-2πik / n)` for `k < n / 2`, each computed directly for accuracy
+fn twiddles(n: usize) -> Vec<Complex> {
+    (0..n / 2)
+        .into_par_iter()
+        .map(|k| {
+            let angle = -2.0 * PI * k as f64 / n as f64;
+            Complex {
+                re: angle.cos(),
+                im: angle.sin(),
+            }
+        })
+        .collect()
+}
+
+/// In-place iterative radix-2 FFT of a power-of-two-length buffer; the
+/// inverse is scaled by `1 / n`
+fn fft(buffer: &mut [Complex], twiddles: &[Complex], inverse: bool) {
+    let n = buffer.len();
+    let bits = n.trailing_zeros();
+    if n <= 1 {
+        return;
+    }
+    for i in 0..n {
+        let j = i.reverse_bits() >> (usize::BITS - bits);
+        if i < j {
+            buffer.swap(i, j);
+        }
+    }
+
+    let mut len = 2;
+    while len <= n {
+        let stride = n / len;
+        let twiddle = |k: usize| {
+            let w = twiddles[k * stride];
+            if inverse {
+                w.conj()
+            } else {
+                w
+            }
+        };
+        let butterfly = |(k, (lo, hi)): (usize, (&mut Complex, &mut Complex))| {
+            let t = hi.mul(twiddle(k));
+            *hi = lo.sub(t);
+            *lo = lo.add(t);
+        };
+
+        buffer.par_chunks_mut(len).for_each(|group| {
+            let (lo, hi) = group.split_at_mut(len / 2);
+            if len / 2 >= PARALLEL_BUTTERFLIES {
+                lo.par_iter_mut()
+                    .zip(hi.par_iter_mut())
+                    .enumerate()
+                    .for_each(butterfly);
+            } else {
+                lo.iter_mut()
+                    .zip(hi.iter_mut())
+                    .enumerate()
+                    .for_each(butterfly);
+            }
+        });
+        len *= 2;
+    }
+
+    if inverse {
+        let scale = 1.0 / n as f64;
+        buffer.par_iter_mut().for_each(|value| {
+            value.re *= scale;
+            value.im *= scale;
+        });
+    }
+}
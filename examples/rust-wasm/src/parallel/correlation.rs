@@ -0,0 +1,125 @@
+use rayon::prelude::*;
+use wasm_bindgen::prelude::*;
+
+use super::WasmParallelProcessor;
+use crate::WasmError;
+
+#[wasm_bindgen]
+impl WasmParallelProcessor {
+    /// Pearson correlation between every pair of features of a row-major
+    /// `n_samples x n_features` data matrix.
+    ///
+    /// Returns the symmetric `n_features x n_features` matrix, row-major,
+    /// with ones on the diagonal. A feature that is constant has no defined
+    /// correlation, so its row and column are `NaN`.
+    #[wasm_bindgen]
+    pub fn parallel_correlation_matrix(
+        &self,
+        data: &[f64],
+        n_samples: usize,
+        n_features: usize,
+    ) -> Result<Vec<f64>, WasmError> {
+        let timing = self.profile("parallel_correlation_matrix", data.len());
+        let columns = self.centered_columns(data, n_samples, n_features)?;
+        let norms: Vec<f64> = columns
+            .iter()
+            .map(|column| dot(column, column).sqrt())
+            .collect();
+
+        timing.finish(self.symmetric_matrix(n_features, |i, j| {
+            if i == j && norms[i] > 0.0 {
+                1.0
+            } else {
+                // NaN when either norm is zero
+                dot(&columns[i], &columns[j]) / (norms[i] * norms[j])
+            }
+        }))
+    }
+
+    /// Sample covariance (normalised by `n_samples - 1`) between every pair
+    /// of features, laid out as in
+    /// [`WasmParallelProcessor::parallel_correlation_matrix`]
+    #[wasm_bindgen]
+    pub fn parallel_covariance_matrix(
+        &self,
+        data: &[f64],
+        n_samples: usize,
+        n_features: usize,
+    ) -> Result<Vec<f64>, WasmError> {
+        let timing = self.profile("parallel_covariance_matrix", data.len());
+        let columns = self.centered_columns(data, n_samples, n_features)?;
+        let scale = 1.0 / (n_samples - 1) as f64;
+
+        timing
+            .finish(self.symmetric_matrix(n_features, |i, j| dot(&columns[i], &columns[j]) * scale))
+    }
+}
+
+impl WasmParallelProcessor {
+    /// Each feature of the data matrix as a contiguous column with its mean
+    /// subtracted
+    fn centered_columns(
+        &self,
+        data: &[f64],
+        n_samples: usize,
+        n_features: usize,
+    ) -> Result<Vec<Vec<f64>>, WasmError> {
+        if n_features == 0 {
+            return Err(WasmError::invalid(
+                "n_features",
+                "the data needs at least one feature",
+            ));
+        }
+        if n_samples.checked_mul(n_features) != Some(data.len()) {
+            return Err(WasmError::dimension(
+                format!("Values in {n_samples} samples of {n_features} features"),
+                n_samples.saturating_mul(n_features),
+                data.len(),
+            ));
+        }
+        if n_samples < 2 {
+            return Err(WasmError::invalid(
+                "n_samples",
+                format!("{n_samples} samples cannot estimate a covariance; need at least 2"),
+            ));
+        }
+
+        self.install(|| {
+            (0..n_features)
+                .into_par_iter()
+                .map(|feature| {
+                    let column: Vec<f64> = data[feature..]
+                        .iter()
+                        .step_by(n_features)
+                        .copied()
+                        .collect();
+                    let mean = column.iter().sum::<f64>() / n_samples as f64;
+                    column.into_iter().map(|value| value - mean).collect()
+                })
+                .collect()
+        })
+    }
+
+    /// An `n x n` symmetric matrix whose upper triangle is computed in
+    /// parallel by `entry(i, j)` with `i <= j` and mirrored below
+    fn symmetric_matrix(
+        &self,
+        n: usize,
+        entry: impl Fn(usize, usize) -> f64 + Sync,
+    ) -> Result<Vec<f64>, WasmError> {
+        let pairs: Vec<(usize, usize)> = (0..n).flat_map(|i| (i..n).map(move |j| (i, j))).collect();
+        let values: Vec<f64> =
+            self.install(|| pairs.par_iter().map(|&(i, j)| entry(i, j)).collect())?;
+
+        let mut matrix = vec![0.0; n * n];
+        for (&(i, j), value) in pairs.iter().zip(values) {
+            matrix[i * n + j] = value;
+            matrix[j * n + i] = value;
+        }
+        Ok(matrix)
+    }
+}
+
+fn dot(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
@@ -0,0 +1,127 @@
+use rayon::prelude::*;
+use wasm_bindgen::prelude::*;
+
+use super::WasmParallelProcessor;
+use crate::WasmError;
+
+/// Values varint-encoded per task by
+/// [`WasmParallelProcessor::parallel_varint_delta_encode`]
+const VARINT_CHUNK: usize = 16 * 1024;
+
+#[wasm_bindgen]
+impl WasmParallelProcessor {
+    /// Delta-encode `data`: the first value as-is, then each value minus the
+    /// one before it, with wrapping arithmetic
+    #[wasm_bindgen]
+    pub fn parallel_delta_encode(&self, data: &[i64]) -> Result<Vec<i64>, WasmError> {
+        let timing = self.profile("parallel_delta_encode", data.len());
+        let mut deltas = vec![0; data.len()];
+        self.install(|| {
+            deltas.par_iter_mut().enumerate().for_each(|(i, delta)| {
+                *delta = match i {
+                    0 => data[0],
+                    _ => data[i].wrapping_sub(data[i - 1]),
+                };
+            })
+        })?;
+        timing.finish(Ok(deltas))
+    }
+
+    /// Invert [`WasmParallelProcessor::parallel_delta_encode`]; a running sum,
+    /// so sequential
+    #[wasm_bindgen]
+    pub fn delta_decode(encoded: &[i64]) -> Vec<i64> {
+        encoded
+            .iter()
+            .scan(0i64, |total, &delta| {
+                *total = total.wrapping_add(delta);
+                Some(*total)
+            })
+            .collect()
+    }
+
+    /// Delta-encode `data` and pack the deltas as zigzag LEB128 varints, so
+    /// small steps in either direction take one or two bytes.
+    ///
+    /// The first value is stored as its delta from zero. Chunks of values are
+    /// encoded in parallel, each taking its first delta from the last value
+    /// of the chunk before, and concatenated in order.
+    #[wasm_bindgen]
+    pub fn parallel_varint_delta_encode(&self, data: &[u64]) -> Result<Vec<u8>, WasmError> {
+        let timing = self.profile("parallel_varint_delta_encode", data.len());
+        let chunks: Vec<Vec<u8>> = self.install(|| {
+            data.par_chunks(VARINT_CHUNK)
+                .enumerate()
+                .map(|(index, chunk)| {
+                    let mut previous = match index {
+                        0 => 0,
+                        _ => data[index * VARINT_CHUNK - 1],
+                    };
+                    let mut bytes = Vec::with_capacity(chunk.len() * 2);
+                    for &value in chunk {
+                        write_varint(&mut bytes, zigzag(value.wrapping_sub(previous) as i64));
+                        previous = value;
+                    }
+                    bytes
+                })
+                .collect()
+        })?;
+        timing.finish(Ok(chunks.concat()))
+    }
+
+    /// Invert [`WasmParallelProcessor::parallel_varint_delta_encode`]
+    #[wasm_bindgen]
+    pub fn varint_delta_decode(encoded: &[u8]) -> Result<Vec<u64>, WasmError> {
+        let mut values = Vec::with_capacity(encoded.len());
+        let (mut previous, mut pos) = (0u64, 0);
+        while pos < encoded.len() {
+            let (zigzagged, len) = read_varint(&encoded[pos..]).ok_or_else(|| {
+                WasmError::invalid(
+                    "varint data",
+                    format!("the value starting at byte {pos} is truncated or longer than 64 bits"),
+                )
+            })?;
+            previous = previous.wrapping_add(unzigzag(zigzagged) as u64);
+            values.push(previous);
+            pos += len;
+        }
+        Ok(values)
+    }
+}
+
+/// Interleave signed values so small magnitudes map to small codes:
+/// 0, -1, 1, -2, ... become 0, 1, 2, 3, ...
+fn zigzag(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+fn unzigzag(code: u64) -> i64 {
+    (code >> 1) as i64 ^ -((code & 1) as i64)
+}
+
+/// Append `value` as LEB128: seven bits per byte, low bits first, with the
+/// high bit set on every byte but the last
+pub(crate) fn write_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+/// Read one LEB128 value, returning it with its length in bytes
+pub(crate) fn read_varint(bytes: &[u8]) -> Option<(u64, usize)> {
+    let mut value = 0u64;
+    for (i, &byte) in bytes.iter().enumerate().take(10) {
+        let bits = (byte & 0x7F) as u64;
+        // The tenth byte holds only the top bit of a u64
+        if i == 9 && bits > 1 {
+            return None;
+        }
+        value |= bits << (7 * i);
+        if byte & 0x80 == 0 {
+            return Some((value, i + 1));
+        }
+    }
+    None
+}
@@ -0,0 +1,145 @@
+use rayon::prelude::*;
+use wasm_bindgen::prelude::*;
+
+use super::WasmParallelProcessor;
+use crate::WasmError;
+
+/// Highest order `parallel_diff` accepts
+const MAX_DIFF_ORDER: u8 = 5;
+
+/// Elements integrated per task by `parallel_cumtrapz`
+const CUMTRAPZ_CHUNK: usize = 64 * 1024;
+
+#[wasm_bindgen]
+impl WasmParallelProcessor {
+    /// The `order`-th forward difference of `data`, from 1 to 5.
+    ///
+    /// The first difference is `y[i] = x[i + 1] - x[i]`, and each higher
+    /// order differences the one below, so the result has
+    /// `data.length - order` values. `order` must be less than the length.
+    #[wasm_bindgen]
+    pub fn parallel_diff(&self, data: &[f64], order: u8) -> Result<Vec<f64>, WasmError> {
+        let timing = self.profile("parallel_diff", data.len());
+        if order == 0 || order > MAX_DIFF_ORDER {
+            return Err(WasmError::invalid(
+                "order",
+                format!("must be from 1 to {MAX_DIFF_ORDER}, got {order}"),
+            ));
+        }
+        if usize::from(order) >= data.len() {
+            return Err(WasmError::invalid(
+                "order",
+                format!("{order} leaves nothing of {} values", data.len()),
+            ));
+        }
+
+        timing.finish(self.install(|| {
+            let mut diff = first_diff(data);
+            for _ in 1..order {
+                diff = first_diff(&diff);
+            }
+            diff
+        }))
+    }
+
+    /// The derivative of `data` sampled every `spacing`, one value per
+    /// sample, as numpy's `gradient` computes it.
+    ///
+    /// Interior values use the second-order central difference
+    /// `(x[i + 1] - x[i - 1]) / (2 * spacing)`, and the two ends one-sided
+    /// first differences. At least two values are needed, and `spacing`
+    /// must be finite and non-zero.
+    #[wasm_bindgen]
+    pub fn parallel_gradient(&self, data: &[f64], spacing: f64) -> Result<Vec<f64>, WasmError> {
+        let timing = self.profile("parallel_gradient", data.len());
+        check_spacing(spacing)?;
+        let n = data.len();
+        if n < 2 {
+            return Err(WasmError::DimensionMismatch {
+                what: "gradient input".to_string(),
+                expected: 2,
+                actual: n,
+            });
+        }
+
+        timing.finish(self.install(|| {
+            (0..n)
+                .into_par_iter()
+                .map(|i| match i {
+                    0 => (data[1] - data[0]) / spacing,
+                    _ if i == n - 1 => (data[i] - data[i - 1]) / spacing,
+                    _ => (data[i + 1] - data[i - 1]) / (2.0 * spacing),
+                })
+                .collect()
+        }))
+    }
+
+    /// The cumulative trapezoidal integral of `data` sampled every
+    /// `spacing`, one value per sample starting from 0.
+    ///
+    /// `y[i]` is the area under the samples up to `x[i]`, so the last value
+    /// is the whole integral. Chunks are integrated in parallel and then
+    /// offset by the areas before them. `spacing` must be finite and
+    /// non-zero.
+    #[wasm_bindgen]
+    pub fn parallel_cumtrapz(&self, data: &[f64], spacing: f64) -> Result<Vec<f64>, WasmError> {
+        let timing = self.profile("parallel_cumtrapz", data.len());
+        check_spacing(spacing)?;
+        if data.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut integral = vec![0.0; data.len()];
+        self.install(|| {
+            // Each chunk integrates from its own first sample, which the
+            // chunk before it ends on
+            integral
+                .par_chunks_mut(CUMTRAPZ_CHUNK)
+                .enumerate()
+                .for_each(|(chunk, out)| {
+                    let start = chunk * CUMTRAPZ_CHUNK;
+                    let mut area = 0.0;
+                    for (i, y) in out.iter_mut().enumerate().skip(1) {
+                        let x = start + i;
+                        area += (data[x - 1] + data[x]) * spacing / 2.0;
+                        *y = area;
+                    }
+                });
+
+            // The area between one chunk's last sample and the next's first
+            let offsets: Vec<f64> = integral
+                .chunks(CUMTRAPZ_CHUNK)
+                .enumerate()
+                .scan(0.0, |offset, (chunk, out)| {
+                    let start = *offset;
+                    let end = chunk * CUMTRAPZ_CHUNK + out.len();
+                    *offset += out[out.len() - 1];
+                    if let Some(&next) = data.get(end) {
+                        *offset += (data[end - 1] + next) * spacing / 2.0;
+                    }
+                    Some(start)
+                })
+                .collect();
+            integral
+                .par_chunks_mut(CUMTRAPZ_CHUNK)
+                .zip(offsets)
+                .for_each(|(out, offset)| out.iter_mut().for_each(|y| *y += offset));
+        })?;
+        timing.finish(Ok(integral))
+    }
+}
+
+/// `y[i] = x[i + 1] - x[i]`
+fn first_diff(data: &[f64]) -> Vec<f64> {
+    data.par_windows(2).map(|pair| pair[1] - pair[0]).collect()
+}
+
+fn check_spacing(spacing: f64) -> Result<(), WasmError> {
+    if !spacing.is_finite() || spacing == 0.0 {
+        return Err(WasmError::invalid(
+            "spacing",
+            format!("must be finite and non-zero, got {spacing}"),
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,240 @@
+use rayon::prelude::*;
+use wasm_bindgen::prelude::*;
+
+use super::{reduce::sum, WasmParallelProcessor};
+use crate::WasmError;
+
+#[wasm_bindgen]
+impl WasmParallelProcessor {
+    /// Euclidean distances between `n_points` points of `n_dims` coordinates
+    /// each, stored back to back.
+    ///
+    /// Returns the upper triangle including the zero diagonal, packed row by
+    /// row: pairs `(0, 0), (0, 1), ..., (0, n - 1), (1, 1), (1, 2), ...`,
+    /// giving `n_points * (n_points + 1) / 2` values. Distances are stored as
+    /// `f32` to halve the memory of the full matrix.
+    #[wasm_bindgen]
+    pub fn parallel_distance_matrix(
+        &self,
+        points: &[f64],
+        n_points: usize,
+        n_dims: usize,
+    ) -> Result<Vec<f32>, WasmError> {
+        let timing = self.profile("parallel_distance_matrix", points.len());
+        check_points(points, n_points, n_dims)?;
+        if n_points == 0 {
+            return Ok(Vec::new());
+        }
+
+        let point = |i: usize| &points[i * n_dims..(i + 1) * n_dims];
+        timing.finish(self.install(|| {
+            points
+                .par_chunks(n_dims)
+                .enumerate()
+                .flat_map_iter(|(i, row)| {
+                    (i..n_points).map(move |j| squared_distance(row, point(j)).sqrt() as f32)
+                })
+                .collect()
+        }))
+    }
+
+    /// Indices of the `k` nearest neighbours of each point, excluding the
+    /// point itself, nearest first.
+    ///
+    /// Points are laid out as in
+    /// [`WasmParallelProcessor::parallel_distance_matrix`]. The result holds
+    /// `k` indices per point, back to back; equidistant neighbours are ordered
+    /// by index.
+    #[wasm_bindgen]
+    pub fn parallel_nearest_neighbors(
+        &self,
+        points: &[f64],
+        n_points: usize,
+        n_dims: usize,
+        k: usize,
+    ) -> Result<Vec<u32>, WasmError> {
+        let timing = self.profile("parallel_nearest_neighbors", points.len());
+        check_points(points, n_points, n_dims)?;
+        if k >= n_points.max(1) {
+            return Err(WasmError::invalid(
+                "k",
+                format!(
+                    "{k} neighbours requested but each point has only {} others",
+                    n_points.saturating_sub(1)
+                ),
+            ));
+        }
+        if u32::try_from(n_points).is_err() {
+            return Err(WasmError::invalid(
+                "n_points",
+                "point indices must fit in u32",
+            ));
+        }
+        if k == 0 {
+            return Ok(Vec::new());
+        }
+
+        let point = |i: usize| &points[i * n_dims..(i + 1) * n_dims];
+        timing.finish(self.install(|| {
+            points
+                .par_chunks(n_dims)
+                .enumerate()
+                .flat_map_iter(|(i, row)| {
+                    let mut others: Vec<(f64, usize)> = (0..n_points)
+                        .filter(|&j| j != i)
+                        .map(|j| (squared_distance(row, point(j)), j))
+                        .collect();
+                    let by_distance = |a: &(f64, usize), b: &(f64, usize)| {
+                        a.0.total_cmp(&b.0).then(a.1.cmp(&b.1))
+                    };
+                    others.select_nth_unstable_by(k - 1, by_distance);
+                    others.truncate(k);
+                    others.sort_unstable_by(by_distance);
+                    others.into_iter().map(|(_, j)| j as u32)
+                })
+                .collect()
+        }))
+    }
+
+    /// Minkowski distance `(sum |a_i - b_i|^p)^(1/p)` between two points.
+    ///
+    /// `p = 1` is the Manhattan distance and `p = 2` the Euclidean one, both
+    /// computed without `powf`. Other values of `p` scale the differences by
+    /// the largest before raising them, so large `p` approaches the Chebyshev
+    /// distance instead of overflowing. `p` must be finite and at least 1,
+    /// below which the formula is not a metric.
+    #[wasm_bindgen]
+    pub fn parallel_minkowski_distance(
+        &self,
+        a: &[f64],
+        b: &[f64],
+        p: f64,
+    ) -> Result<f64, WasmError> {
+        let timing = self.profile("parallel_minkowski_distance", a.len());
+        check_minkowski_p(p)?;
+        if a.len() != b.len() {
+            return Err(WasmError::dimension("Coordinates in b", a.len(), b.len()));
+        }
+
+        let deterministic = self.deterministic();
+        let difference = |i: usize| (a[i] - b[i]).abs();
+        timing.finish(self.install(|| {
+            if p == 1.0 {
+                return sum(deterministic, a.len(), difference);
+            }
+            if p == 2.0 {
+                return sum(deterministic, a.len(), |i| difference(i) * difference(i)).sqrt();
+            }
+            let largest = a
+                .par_iter()
+                .zip(b)
+                .map(|(x, y)| (x - y).abs())
+                .reduce(|| 0.0, nan_max);
+            if largest == 0.0 || !largest.is_finite() {
+                return largest;
+            }
+            largest
+                * sum(deterministic, a.len(), |i| {
+                    (difference(i) / largest).powf(p)
+                })
+                .powf(1.0 / p)
+        }))
+    }
+
+    /// Minkowski distances between every pair of points, laid out as in
+    /// [`WasmParallelProcessor::parallel_distance_matrix`]; `p` is as for
+    /// [`WasmParallelProcessor::parallel_minkowski_distance`]
+    #[wasm_bindgen]
+    pub fn parallel_pairwise_minkowski(
+        &self,
+        points: &[f64],
+        n_points: usize,
+        n_dims: usize,
+        p: f64,
+    ) -> Result<Vec<f32>, WasmError> {
+        let timing = self.profile("parallel_pairwise_minkowski", points.len());
+        check_minkowski_p(p)?;
+        check_points(points, n_points, n_dims)?;
+        if n_points == 0 {
+            return Ok(Vec::new());
+        }
+
+        let point = |i: usize| &points[i * n_dims..(i + 1) * n_dims];
+        timing.finish(self.install(|| {
+            points
+                .par_chunks(n_dims)
+                .enumerate()
+                .flat_map_iter(|(i, row)| {
+                    (i..n_points).map(move |j| minkowski(row, point(j), p) as f32)
+                })
+                .collect()
+        }))
+    }
+}
+
+fn check_minkowski_p(p: f64) -> Result<(), WasmError> {
+    if p == f64::INFINITY {
+        return Err(WasmError::invalid(
+            "p",
+            format!("{p} must be finite; the limit is the Chebyshev distance"),
+        ));
+    }
+    if p.is_nan() || p < 1.0 {
+        return Err(WasmError::invalid(
+            "p",
+            format!("{p} must be at least 1 for the distance to be a metric"),
+        ));
+    }
+    Ok(())
+}
+
+/// Sequential [`WasmParallelProcessor::parallel_minkowski_distance`]
+fn minkowski(a: &[f64], b: &[f64], p: f64) -> f64 {
+    let differences = || a.iter().zip(b).map(|(x, y)| (x - y).abs());
+    if p == 1.0 {
+        return differences().sum();
+    }
+    if p == 2.0 {
+        return squared_distance(a, b).sqrt();
+    }
+    let largest = differences().fold(0.0, nan_max);
+    if largest == 0.0 || !largest.is_finite() {
+        return largest;
+    }
+    largest
+        * differences()
+            .map(|d| (d / largest).powf(p))
+            .sum::<f64>()
+            .powf(1.0 / p)
+}
+
+/// The larger of two values, or `NaN` if either is
+fn nan_max(x: f64, y: f64) -> f64 {
+    if x >= y || x.is_nan() {
+        x
+    } else {
+        y
+    }
+}
+
+/// Check that `points` holds exactly `n_points` points of `n_dims` coordinates
+fn check_points(points: &[f64], n_points: usize, n_dims: usize) -> Result<(), WasmError> {
+    if n_dims == 0 {
+        return Err(WasmError::invalid(
+            "n_dims",
+            "points need at least one dimension",
+        ));
+    }
+    if n_points.checked_mul(n_dims) != Some(points.len()) {
+        return Err(WasmError::dimension(
+            format!("Coordinates in {n_points} points of {n_dims} dimensions"),
+            n_points.saturating_mul(n_dims),
+            points.len(),
+        ));
+    }
+    Ok(())
+}
+
+fn squared_distance(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(x, y)| (x - y) * (x - y)).sum()
+}
@@ -0,0 +1,81 @@
+use std::cmp::Ordering;
+
+use rayon::prelude::*;
+use wasm_bindgen::prelude::*;
+
+use super::WasmParallelProcessor;
+use crate::WasmError;
+
+impl WasmParallelProcessor {
+    /// Index and value of the largest element, preferring the lowest index
+    /// among equals. `NaN`s are skipped; `None` if nothing else remains.
+    pub fn parallel_argmax(&self, data: &[f64]) -> Result<Option<(usize, f64)>, WasmError> {
+        let timing = self.profile("parallel_argmax", data.len());
+        timing.finish(self.arg_extreme(data, Ordering::Greater))
+    }
+
+    /// Index and value of the smallest element, with the same tie and `NaN`
+    /// rules as [`WasmParallelProcessor::parallel_argmax`]
+    pub fn parallel_argmin(&self, data: &[f64]) -> Result<Option<(usize, f64)>, WasmError> {
+        let timing = self.profile("parallel_argmin", data.len());
+        timing.finish(self.arg_extreme(data, Ordering::Less))
+    }
+
+    /// The element comparing as `wanted` against every other
+    fn arg_extreme(
+        &self,
+        data: &[f64],
+        wanted: Ordering,
+    ) -> Result<Option<(usize, f64)>, WasmError> {
+        self.install(|| {
+            data.par_iter()
+                .copied()
+                .enumerate()
+                .filter(|(_, value)| !value.is_nan())
+                .reduce_with(|a, b| {
+                    // Rayon keeps operands in order, so `a` has the lower index
+                    if b.1.partial_cmp(&a.1) == Some(wanted) {
+                        b
+                    } else {
+                        a
+                    }
+                })
+        })
+    }
+}
+
+/// JavaScript-friendly halves of the argmax and argmin tuples
+#[wasm_bindgen]
+impl WasmParallelProcessor {
+    /// Index of the largest element; see [`WasmParallelProcessor::parallel_argmax`]
+    #[wasm_bindgen]
+    pub fn argmax_index(&self, data: &[f64]) -> Result<Option<u32>, WasmError> {
+        let timing = self.profile("argmax_index", data.len());
+        timing.finish(Ok(self
+            .parallel_argmax(data)?
+            .map(|(index, _)| index as u32)))
+    }
+
+    /// Largest element, ignoring `NaN`s
+    #[wasm_bindgen]
+    pub fn argmax_value(&self, data: &[f64]) -> Result<Option<f64>, WasmError> {
+        let timing = self.profile("argmax_value", data.len());
+        timing.finish(Ok(self.parallel_argmax(data)?.map(|(_, value)| value)))
+    }
+
+    /// Index of the smallest element; see [`WasmParallelProcessor::parallel_argmin`]
+    #[wasm_bindgen]
+    pub fn argmin_index(&self, data: &[f64]) -> Result<Option<u32>, WasmError> {
+        let timing = self.profile("argmin_index", data.len());
+        timing.finish(Ok(self
+            .parallel_argmin(data)?
+            .map(|(index, _)| index as u32)))
+    }
+
+    /// Smallest element, ignoring `NaN`s
+    #[wasm_bindgen]
+    pub fn argmin_value(&self, data: &[f64]) -> Result<Option<f64>, WasmError> {
+        let timing = self.profile("argmin_value", data.len());
+        timing.finish(Ok(self.parallel_argmin(data)?.map(|(_, value)| value)))
+    }
+}
@@ -0,0 +1,90 @@
+use rayon::prelude::*;
+use wasm_bindgen::prelude::*;
+
+use super::{WasmParallelProcessor, HISTOGRAM_CHUNK};
+use crate::WasmError;
+
+/// Entries in a table of byte pairs
+const BIGRAMS: usize = 256 * 256;
+
+#[wasm_bindgen]
+impl WasmParallelProcessor {
+    /// Index of coincidence of the bytes: the chance that two bytes drawn
+    /// without replacement are equal, `sum(f * (f - 1)) / (n * (n - 1))`.
+    ///
+    /// Uniformly random bytes give about 1/256 (0.0039) and a single
+    /// repeated byte 1; text and other structured data fall in between.
+    /// Fewer than two bytes give 0.
+    #[wasm_bindgen]
+    pub fn parallel_index_of_coincidence(&self, data: &[u8]) -> Result<f64, WasmError> {
+        let timing = self.profile("parallel_index_of_coincidence", data.len());
+        if data.len() < 2 {
+            return timing.finish(Ok(0.0));
+        }
+        let counts = self.parallel_count_values(data)?;
+
+        let n = data.len() as f64;
+        let coincidences: f64 = counts
+            .iter()
+            .map(|&f| f64::from(f) * (f64::from(f) - 1.0))
+            .sum();
+        timing.finish(Ok(coincidences / (n * (n - 1.0))))
+    }
+
+    /// Pearson's chi-squared statistic of the byte counts against a uniform
+    /// distribution, `sum((f - n/256)² / (n/256))`.
+    ///
+    /// With 255 degrees of freedom, uniformly random data gives about 255;
+    /// values far above that show bias. Empty data gives 0.
+    #[wasm_bindgen]
+    pub fn parallel_chi_squared_uniformity(&self, data: &[u8]) -> Result<f64, WasmError> {
+        let timing = self.profile("parallel_chi_squared_uniformity", data.len());
+        if data.is_empty() {
+            return timing.finish(Ok(0.0));
+        }
+        let counts = self.parallel_count_values(data)?;
+
+        let expected = data.len() as f64 / 256.0;
+        let statistic: f64 = counts
+            .iter()
+            .map(|&f| (f64::from(f) - expected).powi(2) / expected)
+            .sum();
+        timing.finish(Ok(statistic))
+    }
+
+    /// Counts of each pair of consecutive bytes, as a 65536-entry table
+    /// indexed by `first * 256 + second`.
+    ///
+    /// Every task counts its chunks into a table of its own, taking the
+    /// pair that straddles each chunk's end, and the tables are summed at
+    /// the end.
+    #[wasm_bindgen]
+    pub fn parallel_bigram_frequency(&self, data: &[u8]) -> Result<Vec<u32>, WasmError> {
+        let timing = self.profile("parallel_bigram_frequency", data.len());
+        timing.finish(self.install(|| {
+            data.par_chunks(HISTOGRAM_CHUNK)
+                .enumerate()
+                .fold(
+                    || vec![0u32; BIGRAMS],
+                    |mut counts, (i, chunk)| {
+                        // Run one byte into the next chunk for the straddling pair
+                        let start = i * HISTOGRAM_CHUNK;
+                        let end = (start + chunk.len() + 1).min(data.len());
+                        for pair in data[start..end].windows(2) {
+                            counts[usize::from(pair[0]) << 8 | usize::from(pair[1])] += 1;
+                        }
+                        counts
+                    },
+                )
+                .reduce(
+                    || vec![0u32; BIGRAMS],
+                    |mut total, counts| {
+                        for (sum, count) in total.iter_mut().zip(counts) {
+                            *sum += count;
+                        }
+                        total
+                    },
+                )
+        }))
+    }
+}
@@ -0,0 +1,110 @@
+use rayon::prelude::*;
+use wasm_bindgen::prelude::*;
+
+use super::WasmParallelProcessor;
+use crate::WasmError;
+
+/// Mean radius of the Earth in metres (IUGG), the usual sphere for haversine
+/// distances
+const EARTH_RADIUS_M: f64 = 6_371_008.8;
+
+#[wasm_bindgen]
+impl WasmParallelProcessor {
+    /// Great-circle distances in metres between `(lat1[i], lon1[i])` and
+    /// `(lat2[i], lon2[i])`, in degrees, by the haversine formula.
+    ///
+    /// The Earth is treated as a sphere, so results can differ from
+    /// ellipsoidal (WGS 84) distances by up to about 0.5%.
+    #[wasm_bindgen]
+    pub fn parallel_haversine(
+        &self,
+        lat1: &[f64],
+        lon1: &[f64],
+        lat2: &[f64],
+        lon2: &[f64],
+    ) -> Result<Vec<f64>, WasmError> {
+        let timing = self.profile("parallel_haversine", lat1.len());
+        for (name, other) in [("lon1", lon1), ("lat2", lat2), ("lon2", lon2)] {
+            if other.len() != lat1.len() {
+                return Err(WasmError::dimension(
+                    format!("Length of {name} (length of lat1)"),
+                    lat1.len(),
+                    other.len(),
+                ));
+            }
+        }
+
+        timing.finish(self.install(|| {
+            lat1.par_iter()
+                .zip(lon1)
+                .zip(lat2)
+                .zip(lon2)
+                .map(|(((&lat1, &lon1), &lat2), &lon2)| {
+                    central_angle(haversine(lat1, lon1, lat2, lon2)) * EARTH_RADIUS_M
+                })
+                .collect()
+        }))
+    }
+
+    /// Index of the coordinate in `lats`/`lons` nearest to the query point,
+    /// preferring the lowest index among equals.
+    ///
+    /// Coordinates with a `NaN` component are skipped; it is an error if no
+    /// others remain.
+    #[wasm_bindgen]
+    pub fn parallel_nearest_coordinate(
+        &self,
+        query_lat: f64,
+        query_lon: f64,
+        lats: &[f64],
+        lons: &[f64],
+    ) -> Result<u32, WasmError> {
+        let timing = self.profile("parallel_nearest_coordinate", lats.len());
+        if lons.len() != lats.len() {
+            return Err(WasmError::dimension(
+                "Length of lons (length of lats)",
+                lats.len(),
+                lons.len(),
+            ));
+        }
+        if u32::try_from(lats.len()).is_err() {
+            return Err(WasmError::invalid(
+                "lats",
+                "coordinate indices must fit in u32",
+            ));
+        }
+
+        // The haversine grows with distance, so there is no need to take the
+        // arcsine of every candidate
+        let nearest = self.install(|| {
+            lats.par_iter()
+                .zip(lons)
+                .enumerate()
+                .map(|(i, (&lat, &lon))| (i, haversine(query_lat, query_lon, lat, lon)))
+                .filter(|(_, h)| !h.is_nan())
+                // Rayon keeps operands in order, so `a` has the lower index
+                .reduce_with(|a, b| if b.1 < a.1 { b } else { a })
+        })?;
+        timing.finish(match nearest {
+            Some((index, _)) => Ok(index as u32),
+            None => Err(WasmError::invalid(
+                "coordinates",
+                "no coordinate has a finite distance from the query point",
+            )),
+        })
+    }
+}
+
+/// The haversine of the central angle between two points given in degrees
+fn haversine(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
+    let (phi1, phi2) = (lat1.to_radians(), lat2.to_radians());
+    let half_dphi = (phi2 - phi1) / 2.0;
+    let half_dlambda = (lon2 - lon1).to_radians() / 2.0;
+    half_dphi.sin().powi(2) + phi1.cos() * phi2.cos() * half_dlambda.sin().powi(2)
+}
+
+/// The central angle in radians whose haversine is `h`; rounding can push
+/// `h` just past 1 for antipodal points
+fn central_angle(h: f64) -> f64 {
+    2.0 * h.min(1.0).sqrt().asin()
+}
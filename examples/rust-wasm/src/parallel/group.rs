@@ -0,0 +1,116 @@
+use rayon::prelude::*;
+use wasm_bindgen::prelude::*;
+
+use super::WasmParallelProcessor;
+use crate::WasmError;
+
+#[wasm_bindgen]
+impl WasmParallelProcessor {
+    /// `GROUP BY key SUM(value)`: sum `values[i]` into slot `keys[i]`
+    #[wasm_bindgen]
+    pub fn parallel_group_by_sum(
+        &self,
+        keys: &[u32],
+        values: &[f64],
+        num_groups: u32,
+    ) -> Result<Vec<f64>, WasmError> {
+        let timing = self.profile("parallel_group_by_sum", keys.len());
+        check_values(keys, values)?;
+        timing.finish(self.group_by(keys, num_groups, 0.0, |sum, i| *sum += values[i]))
+    }
+
+    /// `GROUP BY key COUNT(*)`: number of occurrences of each key
+    #[wasm_bindgen]
+    pub fn parallel_group_by_count(
+        &self,
+        keys: &[u32],
+        num_groups: u32,
+    ) -> Result<Vec<u32>, WasmError> {
+        let timing = self.profile("parallel_group_by_count", keys.len());
+        timing.finish(self.group_by(keys, num_groups, 0, |count, _| *count += 1))
+    }
+
+    /// `GROUP BY key MAX(value)`; groups with no rows hold `-Infinity`
+    #[wasm_bindgen]
+    pub fn parallel_group_by_max(
+        &self,
+        keys: &[u32],
+        values: &[f64],
+        num_groups: u32,
+    ) -> Result<Vec<f64>, WasmError> {
+        let timing = self.profile("parallel_group_by_max", keys.len());
+        check_values(keys, values)?;
+        timing.finish(
+            self.group_by(keys, num_groups, f64::NEG_INFINITY, |max, i| {
+                *max = max.max(values[i])
+            }),
+        )
+    }
+}
+
+impl WasmParallelProcessor {
+    /// Aggregate rows into `num_groups` slots without atomics.
+    ///
+    /// One pass buckets the row indices by key (a counting sort), then each
+    /// slot folds its own bucket in parallel, in row order, so the work is
+    /// linear in the rows and writes never overlap.
+    fn group_by<T: Copy + Send>(
+        &self,
+        keys: &[u32],
+        num_groups: u32,
+        identity: T,
+        update: impl Fn(&mut T, usize) + Sync,
+    ) -> Result<Vec<T>, WasmError> {
+        let num_groups = num_groups as usize;
+
+        // Rows per key, then where each key's bucket starts in `rows`
+        let mut starts = vec![0usize; num_groups];
+        for &key in keys {
+            let count = starts.get_mut(key as usize).ok_or_else(|| {
+                WasmError::invalid(
+                    "key",
+                    format!("{key} is out of range for {num_groups} groups"),
+                )
+            })?;
+            *count += 1;
+        }
+        let mut offset = 0;
+        for start in &mut starts {
+            offset += std::mem::replace(start, offset);
+        }
+
+        // Filling the buckets leaves `ends[key]` just past that key's rows
+        let mut ends = starts.clone();
+        let mut rows = vec![0usize; keys.len()];
+        for (i, &key) in keys.iter().enumerate() {
+            rows[ends[key as usize]] = i;
+            ends[key as usize] += 1;
+        }
+
+        let mut groups = vec![identity; num_groups];
+        self.install(|| {
+            groups
+                .par_iter_mut()
+                .zip(starts.par_iter().zip(&ends))
+                .for_each(|(slot, (&start, &end))| {
+                    for &i in &rows[start..end] {
+                        update(slot, i);
+                    }
+                });
+        })?;
+
+        Ok(groups)
+    }
+}
+
+/// Keys and values must describe the same rows
+fn check_values(keys: &[u32], values: &[f64]) -> Result<(), WasmError> {
+    if keys.len() != values.len() {
+        return Err(WasmError::dimension(
+            "Values length",
+            keys.len(),
+            values.len(),
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,149 @@
+use std::{cmp::Ordering, collections::HashMap};
+
+use rayon::prelude::*;
+use wasm_bindgen::prelude::*;
+
+use super::{WasmParallelProcessor, HISTOGRAM_CHUNK};
+use crate::WasmError;
+
+/// MessagePack markers written by `parallel_sparse_histogram`
+const FIXMAP: u8 = 0x80;
+const MAP16: u8 = 0xde;
+const MAP32: u8 = 0xdf;
+const INT64: u8 = 0xd3;
+const UINT32: u8 = 0xce;
+
+/// Encoded size of one entry: an int64 value and a uint32 count, each with
+/// its marker byte
+const ENTRY_LEN: usize = 1 + 8 + 1 + 4;
+
+#[wasm_bindgen]
+impl WasmParallelProcessor {
+    /// Count how often each distinct value occurs, for data with too many
+    /// distinct values for a dense histogram.
+    ///
+    /// Each task counts its chunk into a map of its own, and the maps are
+    /// merged pairwise up Rayon's reduction tree. The counts come back as a
+    /// MessagePack map from value to count, which any MessagePack decoder
+    /// reads. Entries are sorted by value and always written at full width
+    /// so that [`sparse_histogram_get_count`] can search them in place.
+    #[wasm_bindgen]
+    pub fn parallel_sparse_histogram(&self, data: &[i64]) -> Result<Vec<u8>, WasmError> {
+        let timing = self.profile("parallel_sparse_histogram", data.len());
+        if u32::try_from(data.len()).is_err() {
+            return Err(WasmError::invalid(
+                "data",
+                format!("{} values overflow 32-bit counts", data.len()),
+            ));
+        }
+
+        let counts = self.install(|| {
+            data.par_chunks(HISTOGRAM_CHUNK)
+                .map(|chunk| {
+                    let mut counts = HashMap::new();
+                    for &value in chunk {
+                        *counts.entry(value).or_insert(0u32) += 1;
+                    }
+                    counts
+                })
+                .reduce(HashMap::new, merge_counts)
+        })?;
+
+        let mut entries: Vec<(i64, u32)> = counts.into_iter().collect();
+        self.install(|| entries.par_sort_unstable_by_key(|&(value, _)| value))?;
+        timing.finish(Ok(encode(&entries)))
+    }
+}
+
+/// How often `key` occurs in a histogram from `parallel_sparse_histogram`,
+/// 0 if it never does.
+///
+/// The sorted, fixed-width entries are binary searched without decoding the
+/// map, in O(log n) time. Bytes that are not such a histogram are an
+/// `INVALID_ENCODING` error.
+#[wasm_bindgen]
+pub fn sparse_histogram_get_count(histogram: &[u8], key: i64) -> Result<u32, WasmError> {
+    let (header, len) = map_header(histogram)?;
+    let entries = &histogram[header..];
+    let expected = len.checked_mul(ENTRY_LEN);
+    if expected != Some(entries.len()) {
+        let end = expected.map_or(usize::MAX, |n| header.saturating_add(n));
+        return Err(malformed(
+            end.min(histogram.len()),
+            format!("{len} entries do not fit in {} bytes", entries.len()),
+        ));
+    }
+
+    let (mut low, mut high) = (0, len);
+    while low < high {
+        let mid = low + (high - low) / 2;
+        let entry = &entries[mid * ENTRY_LEN..(mid + 1) * ENTRY_LEN];
+        if entry[0] != INT64 || entry[9] != UINT32 {
+            return Err(malformed(
+                header + mid * ENTRY_LEN,
+                "expected an int64 value and a uint32 count",
+            ));
+        }
+        let value = i64::from_be_bytes(entry[1..9].try_into().expect("values are 8 bytes"));
+        match value.cmp(&key) {
+            Ordering::Less => low = mid + 1,
+            Ordering::Greater => high = mid,
+            Ordering::Equal => {
+                return Ok(u32::from_be_bytes(
+                    entry[10..].try_into().expect("counts are 4 bytes"),
+                ))
+            }
+        }
+    }
+    Ok(0)
+}
+
+/// Add the smaller map's counts into the larger one
+fn merge_counts(a: HashMap<i64, u32>, b: HashMap<i64, u32>) -> HashMap<i64, u32> {
+    let (mut total, counts) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+    for (value, count) in counts {
+        *total.entry(value).or_insert(0) += count;
+    }
+    total
+}
+
+/// A MessagePack map of `entries`, using the smallest map header
+fn encode(entries: &[(i64, u32)]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(5 + entries.len() * ENTRY_LEN);
+    match entries.len() {
+        n if n < 16 => bytes.push(FIXMAP | n as u8),
+        n if n <= usize::from(u16::MAX) => {
+            bytes.push(MAP16);
+            bytes.extend_from_slice(&(n as u16).to_be_bytes());
+        }
+        n => {
+            bytes.push(MAP32);
+            bytes.extend_from_slice(&(n as u32).to_be_bytes());
+        }
+    }
+    for &(value, count) in entries {
+        bytes.push(INT64);
+        bytes.extend_from_slice(&value.to_be_bytes());
+        bytes.push(UINT32);
+        bytes.extend_from_slice(&count.to_be_bytes());
+    }
+    bytes
+}
+
+/// The header's length in bytes and the number of entries it declares
+fn map_header(histogram: &[u8]) -> Result<(usize, usize), WasmError> {
+    match *histogram {
+        [marker, ..] if marker & 0xf0 == FIXMAP => Ok((1, usize::from(marker & 0x0f))),
+        [MAP16, a, b, ..] => Ok((3, usize::from(u16::from_be_bytes([a, b])))),
+        [MAP32, a, b, c, d, ..] => Ok((5, u32::from_be_bytes([a, b, c, d]) as usize)),
+        _ => Err(malformed(0, "expected a MessagePack map")),
+    }
+}
+
+fn malformed(offset: usize, reason: impl Into<String>) -> WasmError {
+    WasmError::InvalidEncoding {
+        encoding: "sparse histogram".to_string(),
+        offset,
+        reason: reason.into(),
+    }
+}
@@ -0,0 +1,163 @@
+use rayon::prelude::*;
+use wasm_bindgen::prelude::*;
+
+use super::WasmParallelProcessor;
+use crate::WasmError;
+
+#[wasm_bindgen]
+impl WasmParallelProcessor {
+    /// Extract a numeric top-level field from newline-delimited JSON objects.
+    ///
+    /// Returns one value per line. Lines that are not objects, lack the field,
+    /// or hold a non-numeric value for it yield `NaN`. Nested objects are
+    /// skipped, so only top-level keys match.
+    #[wasm_bindgen]
+    pub fn parallel_extract_field(
+        &self,
+        json_lines: &str,
+        field: &str,
+    ) -> Result<Vec<f64>, WasmError> {
+        let timing = self.profile("parallel_extract_field", json_lines.len());
+        if field.is_empty()
+            || field
+                .chars()
+                .any(|c| c == '"' || c == '\\' || c.is_control())
+        {
+            return Err(WasmError::invalid(
+                "field name",
+                "must be non-empty and free of quotes, backslashes and control characters",
+            ));
+        }
+
+        let lines: Vec<&str> = json_lines.lines().collect();
+        timing.finish(self.install(|| {
+            lines
+                .par_iter()
+                .map(|line| extract_number(line.as_bytes(), field.as_bytes()).unwrap_or(f64::NAN))
+                .collect()
+        }))
+    }
+}
+
+/// Scan one JSON object for `field`, returning its value if it is a number
+fn extract_number(line: &[u8], field: &[u8]) -> Option<f64> {
+    let mut scanner = Scanner {
+        bytes: line,
+        pos: 0,
+    };
+    scanner.expect(b'{')?;
+    if scanner.peek() == Some(b'}') {
+        return None;
+    }
+
+    loop {
+        let key = scanner.string()?;
+        scanner.expect(b':')?;
+        if key == field {
+            return scanner.number();
+        }
+        scanner.skip_value()?;
+
+        match scanner.next()? {
+            b',' => continue,
+            _ => return None,
+        }
+    }
+}
+
+/// Minimal cursor over a JSON document, just enough to find top-level keys
+struct Scanner<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Scanner<'a> {
+    /// Next non-whitespace byte, without consuming it
+    fn peek(&mut self) -> Option<u8> {
+        while let Some(&byte) = self.bytes.get(self.pos) {
+            if !byte.is_ascii_whitespace() {
+                return Some(byte);
+            }
+            self.pos += 1;
+        }
+        None
+    }
+
+    /// Consume the next non-whitespace byte
+    fn next(&mut self) -> Option<u8> {
+        let byte = self.peek()?;
+        self.pos += 1;
+        Some(byte)
+    }
+
+    fn expect(&mut self, byte: u8) -> Option<()> {
+        (self.next()? == byte).then_some(())
+    }
+
+    /// Raw contents of a string literal; escapes are left undecoded
+    fn string(&mut self) -> Option<&'a [u8]> {
+        self.expect(b'"')?;
+        let start = self.pos;
+        loop {
+            match *self.bytes.get(self.pos)? {
+                b'"' => break,
+                b'\\' => self.pos += 2,
+                _ => self.pos += 1,
+            }
+        }
+        let contents = &self.bytes[start..self.pos];
+        self.pos += 1;
+        Some(contents)
+    }
+
+    /// Parse a JSON number, or `None` if the value is anything else
+    fn number(&mut self) -> Option<f64> {
+        self.peek()?;
+        let start = self.pos;
+        while let Some(&byte) = self.bytes.get(self.pos) {
+            if !matches!(byte, b'0'..=b'9' | b'-' | b'+' | b'.' | b'e' | b'E') {
+                break;
+            }
+            self.pos += 1;
+        }
+
+        let literal = std::str::from_utf8(&self.bytes[start..self.pos]).ok()?;
+        // Rust's float parser also accepts a leading '+' or '.', JSON does not
+        if literal.starts_with('+') || literal.starts_with('.') {
+            return None;
+        }
+        let value = literal.parse().ok()?;
+        matches!(self.peek(), Some(b',' | b'}')).then_some(value)
+    }
+
+    /// Skip over any JSON value, tracking nesting and strings
+    fn skip_value(&mut self) -> Option<()> {
+        match self.peek()? {
+            b'"' => self.string().map(drop),
+            b'{' | b'[' => {
+                let mut depth = 0usize;
+                loop {
+                    match self.peek()? {
+                        b'"' => {
+                            self.string()?;
+                            continue;
+                        }
+                        b'{' | b'[' => depth += 1,
+                        b'}' | b']' => depth -= 1,
+                        _ => {}
+                    }
+                    self.pos += 1;
+                    if depth == 0 {
+                        return Some(());
+                    }
+                }
+            }
+            _ => {
+                while !matches!(self.peek()?, b',' | b'}') {
+                    self.pos += 1;
+                }
+                Some(())
+            }
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use js_sys::Array;
+use rayon::prelude::*;
+use wasm_bindgen::prelude::*;
+
+use super::{tokenize::strings, WasmParallelProcessor};
+use crate::WasmError;
+
+#[wasm_bindgen]
+impl WasmParallelProcessor {
+    /// Levenshtein distance between each query and the target at the same
+    /// index, one pair per task.
+    ///
+    /// The distance is the fewest single-character insertions, deletions
+    /// and substitutions turning one string into the other, counted in
+    /// Unicode code points. Both arrays must hold strings and be the same
+    /// length.
+    #[wasm_bindgen]
+    pub fn parallel_levenshtein_batch(
+        &self,
+        queries: &Array,
+        targets: &Array,
+    ) -> Result<Vec<u32>, WasmError> {
+        self.levenshtein_batch(&strings(queries, "queries")?, &strings(targets, "targets")?)
+    }
+
+    /// Index of the candidate with the smallest Levenshtein distance to
+    /// `query`, the first of any tied. `candidates` must be a non-empty
+    /// array of strings.
+    #[wasm_bindgen]
+    pub fn parallel_closest_match(
+        &self,
+        query: &str,
+        candidates: &Array,
+    ) -> Result<u32, WasmError> {
+        self.closest_match(query, &strings(candidates, "candidates")?)
+    }
+}
+
+impl WasmParallelProcessor {
+    /// [`WasmParallelProcessor::parallel_levenshtein_batch`] for callers in
+    /// Rust
+    pub fn levenshtein_batch(
+        &self,
+        queries: &[impl AsRef<str> + Sync],
+        targets: &[impl AsRef<str> + Sync],
+    ) -> Result<Vec<u32>, WasmError> {
+        let timing = self.profile("parallel_levenshtein_batch", queries.len());
+        if queries.len() != targets.len() {
+            return Err(WasmError::dimension(
+                "Targets for the queries",
+                queries.len(),
+                targets.len(),
+            ));
+        }
+
+        timing.finish(self.install(|| {
+            queries
+                .par_iter()
+                .zip(targets)
+                .map(|(query, target)| levenshtein(query.as_ref(), target.as_ref()))
+                .collect()
+        }))
+    }
+
+    /// [`WasmParallelProcessor::parallel_closest_match`] for callers in Rust
+    pub fn closest_match(
+        &self,
+        query: &str,
+        candidates: &[impl AsRef<str> + Sync],
+    ) -> Result<u32, WasmError> {
+        let timing = self.profile("parallel_closest_match", candidates.len());
+        if candidates.is_empty() {
+            return Err(WasmError::invalid("candidates", "must not be empty"));
+        }
+
+        let closest = self.install(|| {
+            candidates
+                .par_iter()
+                .enumerate()
+                .map(|(i, candidate)| (levenshtein(query, candidate.as_ref()), i))
+                .min()
+        })?;
+        timing.finish(Ok(closest.map_or(0, |(_, i)| i as u32)))
+    }
+}
+
+/// Levenshtein distance by the row-by-row dynamic programme, in O(m * n)
+/// time and O(min(m, n)) space
+fn levenshtein(a: &str, b: &str) -> u32 {
+    // The common prefix and suffix cost nothing
+    let prefix = a.chars().zip(b.chars()).take_while(|(x, y)| x == y).count();
+    let a: Vec<char> = a.chars().skip(prefix).collect();
+    let b: Vec<char> = b.chars().skip(prefix).collect();
+    let suffix = a
+        .iter()
+        .rev()
+        .zip(b.iter().rev())
+        .take_while(|(x, y)| x == y)
+        .count();
+    let (a, b) = (&a[..a.len() - suffix], &b[..b.len() - suffix]);
+    let (long, short) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+
+    // row[j] is the distance from the prefix of `long` so far to short[..j]
+    let mut row: Vec<u32> = (0..=short.len() as u32).collect();
+    for (i, &x) in long.iter().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i as u32 + 1;
+        for (j, &y) in short.iter().enumerate() {
+            let substitution = diagonal + u32::from(x != y);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[short.len()]
+}
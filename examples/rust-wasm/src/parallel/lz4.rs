@@ -0,0 +1,235 @@
+use rayon::prelude::*;
+use wasm_bindgen::prelude::*;
+
+use super::WasmParallelProcessor;
+use crate::WasmError;
+
+/// Identifies the container written by `parallel_lz4_compress`
+const MAGIC: [u8; 4] = *b"LZ4B";
+
+/// Uncompressed bytes per independently compressed block
+const BLOCK_SIZE: usize = 64 * 1024;
+
+/// Shortest match the LZ4 block format can encode
+const MIN_MATCH: usize = 4;
+
+/// A block's last match must start at least this far from its end...
+const MF_LIMIT: usize = 12;
+
+/// ...and its last bytes are always literals
+const LAST_LITERALS: usize = 5;
+
+/// Entries in the match finder's hash table, as a power of two
+const HASH_BITS: u32 = 12;
+
+#[wasm_bindgen]
+impl WasmParallelProcessor {
+    /// Compress `data` as independent 64 KiB LZ4 blocks, one task per block.
+    ///
+    /// The output is a 4-byte magic `"LZ4B"`, the original size and the
+    /// block count as little-endian `u32`s, then each block as its
+    /// compressed size (`u32`, little-endian) followed by the raw LZ4 block.
+    /// Matches never reach into another block, so blocks decompress
+    /// independently.
+    #[wasm_bindgen]
+    pub fn parallel_lz4_compress(&self, data: &[u8]) -> Result<Vec<u8>, WasmError> {
+        let timing = self.profile("parallel_lz4_compress", data.len());
+        let original_size = u32::try_from(data.len())
+            .map_err(|_| WasmError::invalid("LZ4 input", "must be smaller than 4 GiB"))?;
+        let blocks: Vec<Vec<u8>> =
+            self.install(|| data.par_chunks(BLOCK_SIZE).map(compress_block).collect())?;
+
+        let body: usize = blocks.iter().map(|block| 4 + block.len()).sum();
+        let mut out = Vec::with_capacity(12 + body);
+        out.extend_from_slice(&MAGIC);
+        out.extend_from_slice(&original_size.to_le_bytes());
+        out.extend_from_slice(&(blocks.len() as u32).to_le_bytes());
+        for block in &blocks {
+            out.extend_from_slice(&(block.len() as u32).to_le_bytes());
+            out.extend_from_slice(block);
+        }
+        timing.finish(Ok(out))
+    }
+
+    /// Decompress the output of
+    /// [`WasmParallelProcessor::parallel_lz4_compress`], block by block
+    #[wasm_bindgen]
+    pub fn lz4_decompress(compressed: &[u8]) -> Result<Vec<u8>, WasmError> {
+        let malformed = |reason: &str| WasmError::invalid("LZ4 data", reason);
+        let mut input = Reader(compressed);
+        if input.take(4) != Some(&MAGIC[..]) {
+            return Err(malformed("missing the LZ4B header"));
+        }
+        let original_size = input.u32().ok_or_else(|| malformed("truncated header"))? as usize;
+        let block_count = input.u32().ok_or_else(|| malformed("truncated header"))? as usize;
+        let expected_blocks =
+            original_size / BLOCK_SIZE + usize::from(original_size % BLOCK_SIZE != 0);
+        if block_count != expected_blocks {
+            return Err(malformed(&format!(
+                "{block_count} blocks cannot hold {original_size} bytes"
+            )));
+        }
+
+        // LZ4 expands at most about 255:1, which bounds a lying header
+        let mut out = Vec::with_capacity(original_size.min(compressed.len().saturating_mul(255)));
+        for index in 0..block_count {
+            let size = input
+                .u32()
+                .and_then(|size| input.take(size as usize))
+                .ok_or_else(|| malformed(&format!("block {index} is truncated")))?;
+            let expected = BLOCK_SIZE.min(original_size - index * BLOCK_SIZE);
+            decompress_block(size, expected, &mut out)
+                .map_err(|reason| WasmError::invalid(format!("LZ4 block {index}"), reason))?;
+        }
+        if !input.0.is_empty() {
+            return Err(malformed("trailing bytes after the last block"));
+        }
+        Ok(out)
+    }
+}
+
+/// Compress one block greedily, finding matches through a hash table of
+/// 4-byte sequences
+fn compress_block(input: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(input.len() / 2 + 16);
+    let mut table = vec![0u32; 1 << HASH_BITS];
+    let read =
+        |at: usize| u32::from_le_bytes([input[at], input[at + 1], input[at + 2], input[at + 3]]);
+    let hash = |sequence: u32| (sequence.wrapping_mul(2_654_435_761) >> (32 - HASH_BITS)) as usize;
+
+    let (mut anchor, mut pos) = (0, 0);
+    let match_end = input.len().saturating_sub(LAST_LITERALS);
+    while pos + MF_LIMIT <= input.len() {
+        let sequence = read(pos);
+        let slot = &mut table[hash(sequence)];
+        // Positions are stored plus one so that zero means empty
+        let candidate = (*slot as usize).checked_sub(1);
+        *slot = pos as u32 + 1;
+
+        match candidate {
+            Some(start) if pos - start <= u16::MAX as usize && read(start) == sequence => {
+                let mut length = MIN_MATCH;
+                while pos + length < match_end && input[start + length] == input[pos + length] {
+                    length += 1;
+                }
+                write_sequence(&mut out, &input[anchor..pos], Some((pos - start, length)));
+                pos += length;
+                anchor = pos;
+            }
+            _ => pos += 1,
+        }
+    }
+    write_sequence(&mut out, &input[anchor..], None);
+    out
+}
+
+/// Append a token, `literals` and, except for the final sequence, a match
+fn write_sequence(out: &mut Vec<u8>, literals: &[u8], found: Option<(usize, usize)>) {
+    let match_code = found.map_or(0, |(_, length)| length - MIN_MATCH);
+    out.push((literals.len().min(15) as u8) << 4 | match_code.min(15) as u8);
+    if literals.len() >= 15 {
+        write_length(out, literals.len() - 15);
+    }
+    out.extend_from_slice(literals);
+
+    if let Some((offset, _)) = found {
+        out.extend_from_slice(&(offset as u16).to_le_bytes());
+        if match_code >= 15 {
+            write_length(out, match_code - 15);
+        }
+    }
+}
+
+/// Lengths past a token's 4-bit field continue as bytes of 255 plus a
+/// final byte below 255
+fn write_length(out: &mut Vec<u8>, mut length: usize) {
+    while length >= 255 {
+        out.push(255);
+        length -= 255;
+    }
+    out.push(length as u8);
+}
+
+/// Decode one LZ4 block that must expand to exactly `expected` bytes,
+/// appending them to `out`
+fn decompress_block(block: &[u8], expected: usize, out: &mut Vec<u8>) -> Result<(), &'static str> {
+    let start = out.len();
+    let limit = start + expected;
+    let mut input = Reader(block);
+    loop {
+        let token = input.byte().ok_or("missing sequence token")?;
+        let literals = input
+            .length(token >> 4)
+            .and_then(|length| input.take(length))
+            .ok_or("literals run past the end of the block")?;
+        if out.len() + literals.len() > limit {
+            return Err("decompresses to more than its declared size");
+        }
+        out.extend_from_slice(literals);
+        if input.0.is_empty() {
+            break;
+        }
+
+        let offset = input.u16().ok_or("truncated match offset")? as usize;
+        if offset == 0 || offset > out.len() - start {
+            return Err("match offset points outside the block");
+        }
+        let length = input.length(token & 0xF).ok_or("truncated match length")? + MIN_MATCH;
+        if out.len() + length > limit {
+            return Err("decompresses to more than its declared size");
+        }
+        // Byte by byte, since a match may overlap the bytes it produces
+        let from = out.len() - offset;
+        for i in 0..length {
+            out.push(out[from + i]);
+        }
+    }
+
+    if out.len() != limit {
+        return Err("decompresses to less than its declared size");
+    }
+    Ok(())
+}
+
+/// Cursor over a byte slice whose reads return `None` at the end
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Option<&'a [u8]> {
+        if n > self.0.len() {
+            return None;
+        }
+        let (head, rest) = self.0.split_at(n);
+        self.0 = rest;
+        Some(head)
+    }
+
+    fn byte(&mut self) -> Option<u8> {
+        self.take(1).map(|bytes| bytes[0])
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        self.take(2)
+            .map(|bytes| u16::from_le_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        self.take(4)
+            .map(|bytes| u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    /// A length whose 4-bit token field is `nibble`, with any extension bytes
+    fn length(&mut self, nibble: u8) -> Option<usize> {
+        let mut length = nibble as usize;
+        if nibble == 15 {
+            loop {
+                let byte = self.byte()?;
+                length += byte as usize;
+                if byte != 255 {
+                    break;
+                }
+            }
+        }
+        Some(length)
+    }
+}
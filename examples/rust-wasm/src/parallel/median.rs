@@ -0,0 +1,96 @@
+use rayon::prelude::*;
+use wasm_bindgen::prelude::*;
+
+use super::WasmParallelProcessor;
+use crate::WasmError;
+
+/// Below this many candidates selection finishes sequentially
+const SEQUENTIAL_SELECT: usize = 64 * 1024;
+
+#[wasm_bindgen]
+impl WasmParallelProcessor {
+    /// Exact median in expected linear time, averaging the two middle values
+    /// for even lengths
+    #[wasm_bindgen]
+    pub fn parallel_median(&self, data: &[f64]) -> Result<f64, WasmError> {
+        let timing = self.profile("parallel_median", data.len());
+        if data.is_empty() {
+            return Err(WasmError::invalid(
+                "data",
+                "the median of an empty array is undefined",
+            ));
+        }
+        if self.install(|| data.par_iter().any(|x| x.is_nan()))? {
+            return Err(WasmError::invalid(
+                "data",
+                "the median is undefined for arrays containing NaN",
+            ));
+        }
+
+        let n = data.len();
+        timing.finish(self.install(|| {
+            if n % 2 == 1 {
+                return select(data.to_vec(), n / 2);
+            }
+
+            // The upper middle value is the lower one repeated, or else the
+            // smallest value above it
+            let lower = select(data.to_vec(), n / 2 - 1);
+            let at_most_lower = data.par_iter().filter(|&&x| x <= lower).count();
+            let upper = if at_most_lower > n / 2 {
+                lower
+            } else {
+                data.par_iter()
+                    .copied()
+                    .filter(|&x| x > lower)
+                    .reduce(|| f64::INFINITY, f64::min)
+            };
+            (lower + upper) / 2.0
+        }))
+    }
+
+    /// Median absolute deviation, the median of `|x - median(x)|`.
+    ///
+    /// For normally distributed data this is about `0.6745 * sigma`.
+    #[wasm_bindgen]
+    pub fn parallel_median_absolute_deviation(&self, data: &[f64]) -> Result<f64, WasmError> {
+        let timing = self.profile("parallel_median_absolute_deviation", data.len());
+        let median = self.parallel_median(data)?;
+        let deviations: Vec<f64> =
+            self.install(|| data.par_iter().map(|x| (x - median).abs()).collect())?;
+        timing.finish(self.parallel_median(&deviations))
+    }
+}
+
+/// The `k`-th smallest of `values` (which must be NaN-free), by repeatedly
+/// partitioning around a sampled pivot with parallel counts and filters
+fn select(mut values: Vec<f64>, mut k: usize) -> f64 {
+    loop {
+        if values.len() <= SEQUENTIAL_SELECT {
+            return *values.select_nth_unstable_by(k, f64::total_cmp).1;
+        }
+
+        let pivot = sample_pivot(&values);
+        let (less, equal) = values
+            .par_iter()
+            .map(|&x| ((x < pivot) as usize, (x == pivot) as usize))
+            .reduce(|| (0, 0), |(l1, e1), (l2, e2)| (l1 + l2, e1 + e2));
+
+        if k < less {
+            values = values.into_par_iter().filter(|&x| x < pivot).collect();
+        } else if k < less + equal {
+            return pivot;
+        } else {
+            k -= less + equal;
+            values = values.into_par_iter().filter(|&x| x > pivot).collect();
+        }
+    }
+}
+
+/// Median of nine evenly spaced values, a cheap guard against skewed splits
+/// on sorted or nearly sorted input
+fn sample_pivot(values: &[f64]) -> f64 {
+    let step = values.len() / 9;
+    let mut sample: Vec<f64> = (0..9).map(|i| values[i * step + step / 2]).collect();
+    *sample.select_nth_unstable_by(4, f64::total_cmp).1
+}
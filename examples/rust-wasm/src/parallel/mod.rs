@@ -0,0 +1,205 @@
+use rayon::prelude::*;
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    install,
+    profile::{OpScope, OpStats, Profiler},
+    WasmError, WasmRuntime,
+};
+
+mod activation;
+mod arima;
+mod bezier;
+mod callback;
+mod convolve;
+mod correlation;
+mod crc32c;
+mod delta;
+mod diff;
+mod distance;
+mod extrema;
+mod frequency;
+mod geo;
+mod group;
+mod histogram;
+mod json;
+mod levenshtein;
+mod lz4;
+mod median;
+mod ndjson;
+mod pad;
+mod poly;
+mod reduce;
+mod rolling;
+mod sample;
+mod search;
+mod similarity;
+mod sort;
+mod stats;
+mod tokenize;
+mod utf16;
+mod utf8;
+mod wavelet;
+
+pub(crate) use delta::{read_varint, write_varint};
+pub use histogram::sparse_histogram_get_count;
+pub use pad::Padding2d;
+pub(crate) use reduce::{tree_merge, FIXED_CHUNK};
+pub(crate) use sample::SplitMix64;
+pub(crate) use tokenize::tokenize;
+
+/// Elements handled per task when building histograms
+const HISTOGRAM_CHUNK: usize = 64 * 1024;
+
+/// General-purpose data-parallel operations over typed arrays
+#[wasm_bindgen]
+pub struct WasmParallelProcessor {
+    runtime: WasmRuntime,
+    profiler: Profiler,
+    /// Reproducible floating-point reductions, as well as when the runtime
+    /// asks for them
+    deterministic: bool,
+    vocabulary: Vec<String>,
+}
+
+#[wasm_bindgen]
+impl WasmParallelProcessor {
+    /// Create a processor on the shared runtime of `num_threads` workers
+    /// (see [`WasmRuntime::shared`])
+    #[wasm_bindgen(constructor)]
+    pub fn new(num_threads: usize) -> Result<WasmParallelProcessor, WasmError> {
+        Ok(WasmParallelProcessor::with_runtime(&WasmRuntime::shared(
+            num_threads,
+        )?))
+    }
+
+    /// Create a processor sharing `runtime`'s thread pool
+    #[wasm_bindgen]
+    pub fn with_runtime(runtime: &WasmRuntime) -> WasmParallelProcessor {
+        WasmParallelProcessor {
+            runtime: runtime.clone(),
+            profiler: Profiler::default(),
+            deterministic: false,
+            vocabulary: Vec::new(),
+        }
+    }
+
+    /// The runtime whose thread pool this processor uses
+    #[wasm_bindgen(getter)]
+    pub fn runtime(&self) -> WasmRuntime {
+        self.runtime.clone()
+    }
+
+    /// Workers available to this processor, which change if its runtime is
+    /// resized
+    #[wasm_bindgen]
+    pub fn current_num_threads(&self) -> usize {
+        self.runtime.num_threads()
+    }
+
+    /// Make this processor's floating-point reductions bit-for-bit
+    /// reproducible, across runs and whatever the number of threads, as
+    /// [`WasmRuntime::set_deterministic`] does for every processor on a
+    /// runtime.
+    ///
+    /// It covers `parallel_sum_f64`, `parallel_variance`,
+    /// `parallel_minkowski_distance`, the entropies and the ARIMA fit and
+    /// forecast. Operations that compute each output on its own, or only
+    /// count, are reproducible anyway. The cost is a few percent on large
+    /// inputs.
+    #[wasm_bindgen]
+    pub fn set_deterministic(&mut self, enabled: bool) {
+        self.deterministic = enabled;
+    }
+
+    /// Whether reductions are reproducible, set here or on the runtime
+    #[wasm_bindgen(getter)]
+    pub fn deterministic(&self) -> bool {
+        self.deterministic || self.runtime.deterministic()
+    }
+
+    /// Time this processor's operations for `last_op_stats`, forgetting
+    /// the last operation's stats either way
+    #[wasm_bindgen]
+    pub fn set_profiling(&mut self, enabled: bool) {
+        self.profiler.set_enabled(enabled);
+    }
+
+    /// What the last operation run while profiling did, as `{ operation,
+    /// wall_ms, input_elements, pool_threads, bytes_allocated }`, or
+    /// `undefined` if none has run.
+    ///
+    /// `wall_ms` covers the operation inside the module, not copying its
+    /// arguments and result across the JavaScript boundary,
+    /// `pool_threads` is the size of the pool its parallel work ran on, not
+    /// how many of those workers took a share, and `bytes_allocated` is the
+    /// capacity of the buffers it returned.
+    #[wasm_bindgen]
+    pub fn last_op_stats(&self) -> JsValue {
+        self.last_op()
+            .map_or(JsValue::UNDEFINED, |stats| stats.to_js())
+    }
+
+    /// Sum all values, wrapping on overflow like JavaScript's `| 0`
+    #[wasm_bindgen]
+    pub fn parallel_sum(&self, data: &[i32]) -> Result<i32, WasmError> {
+        let timing = self.profile("parallel_sum", data.len());
+        timing.finish(self.install(|| {
+            data.par_iter()
+                .copied()
+                .reduce(|| 0, |a, b| a.wrapping_add(b))
+        }))
+    }
+
+    /// Square every value
+    #[wasm_bindgen]
+    pub fn parallel_map_square(&self, data: &[i32]) -> Result<Vec<i32>, WasmError> {
+        let timing = self.profile("parallel_map_square", data.len());
+        timing.finish(self.install(|| data.par_iter().map(|&x| x.wrapping_mul(x)).collect()))
+    }
+
+    /// Count how often each byte value occurs, returning a 256-bin histogram
+    #[wasm_bindgen]
+    pub fn parallel_count_values(&self, data: &[u8]) -> Result<Vec<u32>, WasmError> {
+        let timing = self.profile("parallel_count_values", data.len());
+        timing.finish(self.install(|| {
+            data.par_chunks(HISTOGRAM_CHUNK)
+                .map(|chunk| {
+                    let mut counts = vec![0u32; 256];
+                    for &byte in chunk {
+                        counts[byte as usize] += 1;
+                    }
+                    counts
+                })
+                .reduce(
+                    || vec![0u32; 256],
+                    |mut total, counts| {
+                        for (sum, count) in total.iter_mut().zip(counts) {
+                            *sum += count;
+                        }
+                        total
+                    },
+                )
+        }))
+    }
+}
+
+impl WasmParallelProcessor {
+    /// Stats of the last operation run while profiling, as
+    /// [`WasmParallelProcessor::last_op_stats`] reports them
+    pub fn last_op(&self) -> Option<OpStats> {
+        self.profiler.last()
+    }
+
+    /// Profile `operation` on `input_elements` values until the scope is
+    /// finished or dropped
+    fn profile(&self, operation: &'static str, input_elements: usize) -> OpScope {
+        self.profiler.scope(operation, input_elements)
+    }
+
+    /// Run `op` on this processor's thread pool, reporting a panic as
+    /// [`WasmError::Internal`]
+    fn install<R: Send>(&self, op: impl FnOnce() -> R + Send) -> Result<R, WasmError> {
+        install(&self.runtime, op)
+    }
+}
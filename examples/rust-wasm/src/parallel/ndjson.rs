@@ -0,0 +1,336 @@
+use rayon::prelude::*;
+use wasm_bindgen::prelude::*;
+
+use super::WasmParallelProcessor;
+use crate::WasmError;
+
+/// Objects and arrays nested deeper than this are rejected rather than
+/// risk overflowing the stack, as serde_json does
+const MAX_DEPTH: usize = 128;
+
+#[wasm_bindgen]
+impl WasmParallelProcessor {
+    /// Extract a number at a dot-separated `field_path`, such as
+    /// `"metrics.cpu"`, from every line of newline-delimited JSON.
+    ///
+    /// Blank lines are skipped and every other line is fully parsed, one
+    /// line per task, giving one value per non-blank line. A line whose
+    /// value has nothing at the path, or something other than a number,
+    /// yields `NaN`; of duplicate keys the last wins, as in `JSON.parse`.
+    /// Any line that is not valid JSON fails the whole call with an
+    /// `INVALID_JSON` error naming the first such line.
+    #[wasm_bindgen]
+    pub fn parallel_parse_ndjson_floats(
+        &self,
+        ndjson: &str,
+        field_path: &str,
+    ) -> Result<Vec<f64>, WasmError> {
+        let timing = self.profile("parallel_parse_ndjson_floats", ndjson.len());
+        let path: Vec<&str> = field_path.split('.').collect();
+        if path.iter().any(|key| key.is_empty()) {
+            return Err(WasmError::invalid(
+                "field path",
+                format!("{field_path:?} has an empty key"),
+            ));
+        }
+
+        let lines: Vec<(usize, &str)> = ndjson
+            .split('\n')
+            .enumerate()
+            .filter(|(_, line)| !line.bytes().all(is_whitespace))
+            .collect();
+        let parsed = self.install(|| {
+            lines
+                .par_iter()
+                .map(|&(_, line)| parse_line(line, &path))
+                .collect::<Vec<_>>()
+        })?;
+
+        // Report the first bad line, whichever task found it
+        timing.finish(
+            parsed
+                .into_iter()
+                .zip(&lines)
+                .map(|(value, &(index, line))| {
+                    value.map_err(|error| WasmError::InvalidJson {
+                        line: index + 1,
+                        column: line
+                            .get(..error.offset)
+                            .map_or(error.offset, |before| before.chars().count())
+                            + 1,
+                        reason: error.reason.to_string(),
+                    })
+                })
+                .collect(),
+        )
+    }
+}
+
+/// Where and why a line is not valid JSON
+struct SyntaxError {
+    /// Byte offset into the line
+    offset: usize,
+    reason: &'static str,
+}
+
+/// Parse one line as a JSON value, returning the number at `path`, or `NaN`
+/// if there is none
+fn parse_line(line: &str, path: &[&str]) -> Result<f64, SyntaxError> {
+    let mut parser = Parser {
+        bytes: line.as_bytes(),
+        pos: 0,
+        path,
+        found: f64::NAN,
+    };
+    parser.value(0, Some(0))?;
+    if parser.peek().is_some() {
+        return Err(parser.error("unexpected text after the value"));
+    }
+    Ok(parser.found)
+}
+
+fn is_whitespace(byte: u8) -> bool {
+    matches!(byte, b' ' | b'\t' | b'\r' | b'\n')
+}
+
+/// Recursive descent over the JSON grammar (RFC 8259), validating the
+/// whole value while it looks for the one at `path`
+struct Parser<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+    path: &'a [&'a str],
+    /// The last value seen at `path`, `NaN` unless it was a number
+    found: f64,
+}
+
+impl<'a> Parser<'a> {
+    fn error(&self, reason: &'static str) -> SyntaxError {
+        SyntaxError {
+            offset: self.pos,
+            reason,
+        }
+    }
+
+    /// Next non-whitespace byte, without consuming it
+    fn peek(&mut self) -> Option<u8> {
+        while let Some(&byte) = self.bytes.get(self.pos) {
+            if !is_whitespace(byte) {
+                return Some(byte);
+            }
+            self.pos += 1;
+        }
+        None
+    }
+
+    /// Consume `byte` as the next non-whitespace byte
+    fn expect(&mut self, byte: u8, reason: &'static str) -> Result<(), SyntaxError> {
+        if self.peek() != Some(byte) {
+            return Err(self.error(reason));
+        }
+        self.pos += 1;
+        Ok(())
+    }
+
+    /// Parse one value `depth` containers deep. `matched` is how many keys
+    /// of the path lead here, or `None` once the value is off the path.
+    fn value(&mut self, depth: usize, matched: Option<usize>) -> Result<(), SyntaxError> {
+        if matched.is_some() {
+            // A later duplicate key replaces whatever an earlier one held
+            self.found = f64::NAN;
+        }
+        match self.peek() {
+            Some(b'{') => self.object(depth + 1, matched),
+            Some(b'[') => self.array(depth + 1),
+            Some(b'"') => self.string().map(drop),
+            Some(b'-' | b'0'..=b'9') => {
+                let number = self.number()?;
+                if matched == Some(self.path.len()) {
+                    self.found = number;
+                }
+                Ok(())
+            }
+            Some(b't') => self.literal(b"true"),
+            Some(b'f') => self.literal(b"false"),
+            Some(b'n') => self.literal(b"null"),
+            _ => Err(self.error("expected a value")),
+        }
+    }
+
+    fn object(&mut self, depth: usize, matched: Option<usize>) -> Result<(), SyntaxError> {
+        if depth > MAX_DEPTH {
+            return Err(self.error("nesting deeper than 128 levels"));
+        }
+        self.pos += 1;
+        if self.peek() == Some(b'}') {
+            self.pos += 1;
+            return Ok(());
+        }
+        loop {
+            if self.peek() != Some(b'"') {
+                return Err(self.error("expected a string key"));
+            }
+            let key = self.string()?;
+            let next = matched
+                .filter(|&i| i < self.path.len() && key_matches(key, self.path[i]))
+                .map(|i| i + 1);
+            self.expect(b':', "expected ':' after the key")?;
+            self.value(depth, next)?;
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b'}') => {
+                    self.pos += 1;
+                    return Ok(());
+                }
+                _ => return Err(self.error("expected ',' or '}'")),
+            }
+        }
+    }
+
+    fn array(&mut self, depth: usize) -> Result<(), SyntaxError> {
+        if depth > MAX_DEPTH {
+            return Err(self.error("nesting deeper than 128 levels"));
+        }
+        self.pos += 1;
+        if self.peek() == Some(b']') {
+            self.pos += 1;
+            return Ok(());
+        }
+        loop {
+            self.value(depth, None)?;
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b']') => {
+                    self.pos += 1;
+                    return Ok(());
+                }
+                _ => return Err(self.error("expected ',' or ']'")),
+            }
+        }
+    }
+
+    /// Raw contents of the string literal at the cursor, escapes checked
+    /// but not decoded
+    fn string(&mut self) -> Result<&'a [u8], SyntaxError> {
+        let bytes = self.bytes;
+        self.pos += 1;
+        let start = self.pos;
+        loop {
+            match bytes.get(self.pos) {
+                None => return Err(self.error("unterminated string")),
+                Some(b'"') => break,
+                Some(b'\\') => {
+                    self.pos += 1;
+                    match bytes.get(self.pos) {
+                        Some(b'"' | b'\\' | b'/' | b'b' | b'f' | b'n' | b'r' | b't') => {}
+                        Some(b'u')
+                            if bytes
+                                .get(self.pos + 1..self.pos + 5)
+                                .is_some_and(|hex| hex.iter().all(u8::is_ascii_hexdigit)) =>
+                        {
+                            self.pos += 4
+                        }
+                        _ => return Err(self.error("invalid escape")),
+                    }
+                }
+                Some(&byte) if byte < 0x20 => return Err(self.error("control character in string")),
+                Some(_) => {}
+            }
+            self.pos += 1;
+        }
+        self.pos += 1;
+        Ok(&bytes[start..self.pos - 1])
+    }
+
+    /// `-? (0 | [1-9][0-9]*) (. [0-9]+)? ([eE] [+-]? [0-9]+)?`
+    fn number(&mut self) -> Result<f64, SyntaxError> {
+        let start = self.pos;
+        self.skip(|byte| byte == b'-', 1);
+        match self.bytes.get(self.pos) {
+            Some(b'0') => self.pos += 1,
+            Some(b'1'..=b'9') => self.digits(),
+            _ => return Err(self.error("invalid number")),
+        }
+        if self.bytes.get(self.pos) == Some(&b'.') {
+            self.pos += 1;
+            self.require_digits()?;
+        }
+        if matches!(self.bytes.get(self.pos), Some(b'e' | b'E')) {
+            self.pos += 1;
+            self.skip(|byte| byte == b'+' || byte == b'-', 1);
+            self.require_digits()?;
+        }
+
+        let literal =
+            std::str::from_utf8(&self.bytes[start..self.pos]).expect("a JSON number is ASCII");
+        Ok(literal
+            .parse()
+            .expect("the JSON number grammar is a Rust float"))
+    }
+
+    fn require_digits(&mut self) -> Result<(), SyntaxError> {
+        if !self.bytes.get(self.pos).is_some_and(u8::is_ascii_digit) {
+            return Err(self.error("invalid number"));
+        }
+        self.digits();
+        Ok(())
+    }
+
+    fn digits(&mut self) {
+        self.skip(|byte| byte.is_ascii_digit(), usize::MAX);
+    }
+
+    /// Consume up to `limit` bytes while `wanted` holds
+    fn skip(&mut self, wanted: impl Fn(u8) -> bool, limit: usize) {
+        let mut taken = 0;
+        while taken < limit && self.bytes.get(self.pos).is_some_and(|&byte| wanted(byte)) {
+            self.pos += 1;
+            taken += 1;
+        }
+    }
+
+    fn literal(&mut self, word: &[u8]) -> Result<(), SyntaxError> {
+        if !self.bytes[self.pos..].starts_with(word) {
+            return Err(self.error("expected a value"));
+        }
+        self.pos += word.len();
+        Ok(())
+    }
+}
+
+/// Whether a raw key from [`Parser::string`] is `key` once decoded
+fn key_matches(raw: &[u8], key: &str) -> bool {
+    if !raw.contains(&b'\\') {
+        return raw == key.as_bytes();
+    }
+    // Compare as UTF-16, as JavaScript does, so that an escaped surrogate
+    // pair matches the character it encodes
+    let mut units = Vec::with_capacity(raw.len());
+    let mut i = 0;
+    while i < raw.len() {
+        if raw[i] != b'\\' {
+            let end = raw[i..]
+                .iter()
+                .position(|&byte| byte == b'\\')
+                .map_or(raw.len(), |n| i + n);
+            let text = std::str::from_utf8(&raw[i..end]).expect("keys come from a str");
+            units.extend(text.encode_utf16());
+            i = end;
+            continue;
+        }
+        units.push(match raw[i + 1] {
+            b'b' => 0x08,
+            b'f' => 0x0c,
+            b'n' => u16::from(b'\n'),
+            b'r' => u16::from(b'\r'),
+            b't' => u16::from(b'\t'),
+            b'u' => {
+                let hex = std::str::from_utf8(&raw[i + 2..i + 6]).expect("escapes are checked");
+                i += 4;
+                u16::from_str_radix(hex, 16).expect("escapes are checked")
+            }
+            other => u16::from(other),
+        });
+        i += 2;
+    }
+    units.into_iter().eq(key.encode_utf16())
+}
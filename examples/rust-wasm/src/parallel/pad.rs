@@ -0,0 +1,146 @@
+use rayon::prelude::*;
+use wasm_bindgen::prelude::*;
+
+use super::WasmParallelProcessor;
+use crate::WasmError;
+
+/// Zeros added on each side of a 2D tensor by
+/// [`WasmParallelProcessor::parallel_pad_2d`]
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Padding2d {
+    /// Rows of zeros above the tensor
+    pub top: usize,
+    /// Rows of zeros below it
+    pub bottom: usize,
+    /// Columns of zeros to its left
+    pub left: usize,
+    /// Columns of zeros to its right
+    pub right: usize,
+}
+
+#[wasm_bindgen]
+impl Padding2d {
+    #[wasm_bindgen(constructor)]
+    pub fn new(top: usize, bottom: usize, left: usize, right: usize) -> Padding2d {
+        Padding2d {
+            top,
+            bottom,
+            left,
+            right,
+        }
+    }
+}
+
+impl Padding2d {
+    /// Shape of a padded `rows x cols` tensor
+    fn padded_shape(&self, rows: usize, cols: usize) -> Result<(usize, usize), WasmError> {
+        let too_large = || {
+            WasmError::invalid(
+                "padding",
+                format!("padding a {rows}x{cols} tensor by {self:?} overflows its size"),
+            )
+        };
+        let out_rows = self
+            .top
+            .checked_add(rows)
+            .and_then(|n| n.checked_add(self.bottom))
+            .ok_or_else(too_large)?;
+        let out_cols = self
+            .left
+            .checked_add(cols)
+            .and_then(|n| n.checked_add(self.right))
+            .ok_or_else(too_large)?;
+        out_rows.checked_mul(out_cols).ok_or_else(too_large)?;
+        Ok((out_rows, out_cols))
+    }
+
+    /// Whether any side is wider than the tensor it pads
+    fn exceeds(&self, rows: usize, cols: usize) -> bool {
+        self.top > rows || self.bottom > rows || self.left > cols || self.right > cols
+    }
+}
+
+#[wasm_bindgen]
+impl WasmParallelProcessor {
+    /// Surround a row-major `rows x cols` tensor with zeros, giving a
+    /// `(top + rows + bottom) x (left + cols + right)` tensor.
+    ///
+    /// Padding wider than the tensor on any side, which includes any
+    /// padding of an empty tensor, gives a tensor of all zeros.
+    #[wasm_bindgen]
+    pub fn parallel_pad_2d(
+        &self,
+        data: &[f64],
+        rows: usize,
+        cols: usize,
+        padding: &Padding2d,
+    ) -> Result<Vec<f64>, WasmError> {
+        let timing = self.profile("parallel_pad_2d", data.len());
+        check_len(data, rows, cols, "Tensor")?;
+        let (out_rows, out_cols) = padding.padded_shape(rows, cols)?;
+
+        let mut padded = vec![0.0; out_rows * out_cols];
+        if cols > 0 && !padding.exceeds(rows, cols) {
+            let (top, left) = (padding.top, padding.left);
+            self.install(|| {
+                padded[top * out_cols..(top + rows) * out_cols]
+                    .par_chunks_exact_mut(out_cols)
+                    .zip(data.par_chunks_exact(cols))
+                    .for_each(|(out, row)| out[left..left + cols].copy_from_slice(row));
+            })?;
+        }
+
+        timing.finish(Ok(padded))
+    }
+
+    /// Recover the `rows x cols` tensor from the output of
+    /// [`WasmParallelProcessor::parallel_pad_2d`] called with the same
+    /// arguments.
+    ///
+    /// As there, padding wider than the tensor gives all zeros.
+    #[wasm_bindgen]
+    pub fn parallel_unpad_2d(
+        &self,
+        padded: &[f64],
+        rows: usize,
+        cols: usize,
+        padding: &Padding2d,
+    ) -> Result<Vec<f64>, WasmError> {
+        let timing = self.profile("parallel_unpad_2d", padded.len());
+        let (out_rows, out_cols) = padding.padded_shape(rows, cols)?;
+        check_len(padded, out_rows, out_cols, "Padded tensor")?;
+
+        let mut data = vec![0.0; rows * cols];
+        if cols > 0 && !padding.exceeds(rows, cols) {
+            let (top, left) = (padding.top, padding.left);
+            self.install(|| {
+                data.par_chunks_exact_mut(cols)
+                    .enumerate()
+                    .for_each(|(y, row)| {
+                        let start = (top + y) * out_cols + left;
+                        row.copy_from_slice(&padded[start..start + cols]);
+                    });
+            })?;
+        }
+
+        timing.finish(Ok(data))
+    }
+}
+
+fn check_len(data: &[f64], rows: usize, cols: usize, what: &str) -> Result<(), WasmError> {
+    let expected = rows.checked_mul(cols).ok_or_else(|| {
+        WasmError::invalid(
+            "shape",
+            format!("a {rows}x{cols} tensor overflows its size"),
+        )
+    })?;
+    if data.len() != expected {
+        return Err(WasmError::dimension(
+            format!("{what} length for {rows}x{cols}"),
+            expected,
+            data.len(),
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,70 @@
+use rayon::prelude::*;
+use wasm_bindgen::prelude::*;
+
+use super::WasmParallelProcessor;
+use crate::WasmError;
+
+#[wasm_bindgen]
+impl WasmParallelProcessor {
+    /// The polynomial with `coefficients`, highest power first, evaluated at
+    /// every point.
+    ///
+    /// Each point takes one pass of Horner's method, and the points are
+    /// split across the pool. `[1, -3, 2]` is `x² - 3x + 2`. With no
+    /// coefficients every value is 0.
+    #[wasm_bindgen]
+    pub fn parallel_polyeval(
+        &self,
+        coefficients: &[f64],
+        points: &[f64],
+    ) -> Result<Vec<f64>, WasmError> {
+        let timing = self.profile("parallel_polyeval", points.len());
+        timing.finish(self.install(|| {
+            points
+                .par_iter()
+                .map(|&x| horner(coefficients, x))
+                .collect()
+        }))
+    }
+
+    /// The Chebyshev series `c[0]·T0(x) + c[1]·T1(x) + …` evaluated at every
+    /// point.
+    ///
+    /// Unlike `parallel_polyeval`, coefficients are lowest degree first,
+    /// as numpy's `chebval` takes them. Each point is summed with Clenshaw's
+    /// recurrence, which is stable where expanding into powers of `x` is
+    /// not. The series is meant for points in [-1, 1] but any point is
+    /// evaluated. With no coefficients every value is 0.
+    #[wasm_bindgen]
+    pub fn parallel_polyeval_chebyshev(
+        &self,
+        coefficients: &[f64],
+        points: &[f64],
+    ) -> Result<Vec<f64>, WasmError> {
+        let timing = self.profile("parallel_polyeval_chebyshev", points.len());
+        timing.finish(self.install(|| {
+            points
+                .par_iter()
+                .map(|&x| clenshaw(coefficients, x))
+                .collect()
+        }))
+    }
+}
+
+/// `coefficients`, highest power first, at `x`
+fn horner(coefficients: &[f64], x: f64) -> f64 {
+    coefficients.iter().fold(0.0, |acc, &c| acc * x + c)
+}
+
+/// The Chebyshev series with `coefficients`, lowest degree first, at `x`
+fn clenshaw(coefficients: &[f64], x: f64) -> f64 {
+    let Some((&c0, rest)) = coefficients.split_first() else {
+        return 0.0;
+    };
+    // b[k] = c[k] + 2x·b[k + 1] - b[k + 2], down to k = 1
+    let (b1, b2) = rest
+        .iter()
+        .rev()
+        .fold((0.0, 0.0), |(b1, b2), &c| (c + 2.0 * x * b1 - b2, b1));
+    c0 + x * b1 - b2
+}
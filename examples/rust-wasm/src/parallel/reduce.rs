@@ -0,0 +1,82 @@
+use rayon::prelude::*;
+use wasm_bindgen::prelude::*;
+
+use super::WasmParallelProcessor;
+use crate::WasmError;
+
+/// Terms summed sequentially per task in deterministic mode
+pub(crate) const FIXED_CHUNK: usize = 4096;
+
+#[wasm_bindgen]
+impl WasmParallelProcessor {
+    /// Sum of all values.
+    ///
+    /// Floating-point addition is not associative, so the last bits can
+    /// differ between runs and thread counts unless the processor is
+    /// deterministic; see [`WasmParallelProcessor::set_deterministic`].
+    #[wasm_bindgen]
+    pub fn parallel_sum_f64(&self, data: &[f64]) -> Result<f64, WasmError> {
+        let timing = self.profile("parallel_sum_f64", data.len());
+        let deterministic = self.deterministic();
+        timing.finish(self.install(|| sum(deterministic, data.len(), |i| data[i])))
+    }
+
+    /// Population variance of the values, 0 for fewer than two.
+    ///
+    /// Two passes: the mean, then the mean squared deviation from it, which
+    /// avoids the cancellation of summing squares. Reproducible in
+    /// deterministic mode, as [`WasmParallelProcessor::parallel_sum_f64`].
+    #[wasm_bindgen]
+    pub fn parallel_variance(&self, data: &[f64]) -> Result<f64, WasmError> {
+        let timing = self.profile("parallel_variance", data.len());
+        if data.len() < 2 {
+            return timing.finish(Ok(0.0));
+        }
+        let deterministic = self.deterministic();
+        let n = data.len() as f64;
+        timing.finish(self.install(|| {
+            let mean = sum(deterministic, data.len(), |i| data[i]) / n;
+            sum(deterministic, data.len(), |i| {
+                (data[i] - mean) * (data[i] - mean)
+            }) / n
+        }))
+    }
+}
+
+/// Sum of `term(i)` for `i` in `0..len`.
+///
+/// Deterministically, each chunk of `FIXED_CHUNK` terms is summed in order
+/// and the partial sums are then added up a balanced tree. The chunks
+/// depend only on `len`, so every run adds the same numbers in the same
+/// order whatever the pool. Otherwise Rayon splits the work as it likes.
+pub(super) fn sum(
+    deterministic: bool,
+    len: usize,
+    term: impl Fn(usize) -> f64 + Sync + Send,
+) -> f64 {
+    if !deterministic {
+        return (0..len).into_par_iter().map(term).sum();
+    }
+    let chunks = (len + FIXED_CHUNK - 1) / FIXED_CHUNK;
+    let partials: Vec<f64> = (0..chunks)
+        .into_par_iter()
+        .map(|chunk| {
+            let start = chunk * FIXED_CHUNK;
+            (start..len.min(start + FIXED_CHUNK)).map(&term).sum()
+        })
+        .collect();
+    tree_merge(&partials, &|a, b| a + b).unwrap_or(0.0)
+}
+
+/// Combine partial results pairwise, splitting at the midpoint, in a fixed
+/// order; `None` when there are none
+pub(crate) fn tree_merge<T: Copy>(values: &[T], merge: &impl Fn(T, T) -> T) -> Option<T> {
+    match values {
+        [] => None,
+        [value] => Some(*value),
+        _ => {
+            let (left, right) = values.split_at(values.len() / 2);
+            Some(merge(tree_merge(left, merge)?, tree_merge(right, merge)?))
+        }
+    }
+}
@@ -0,0 +1,81 @@
+use rayon::prelude::*;
+use wasm_bindgen::prelude::*;
+
+use super::WasmParallelProcessor;
+use crate::WasmError;
+
+#[wasm_bindgen]
+impl WasmParallelProcessor {
+    /// Score each value against the `window` values ending at it:
+    /// `(x - mean) / std`, with the population standard deviation.
+    ///
+    /// The first `window - 1` values are scored against the shorter window
+    /// available so far, so the first value always scores 0, as does any
+    /// value whose window is constant. The rolling statistics depend on each
+    /// other and are computed sequentially with a sliding Welford update;
+    /// only the scoring runs in parallel. Values must be finite.
+    #[wasm_bindgen]
+    pub fn parallel_rolling_z_score(
+        &self,
+        data: &[f64],
+        window: usize,
+    ) -> Result<Vec<f64>, WasmError> {
+        let timing = self.profile("parallel_rolling_z_score", data.len());
+        if window == 0 {
+            return Err(WasmError::invalid("window", "must hold at least one value"));
+        }
+        if let Some(index) = self.install(|| data.par_iter().position_first(|x| !x.is_finite()))? {
+            return Err(WasmError::invalid(
+                "data",
+                format!("value {} at index {index} is not finite", data[index]),
+            ));
+        }
+
+        let stats = rolling_mean_std(data, window);
+        timing.finish(self.install(|| {
+            data.par_iter()
+                .zip(&stats)
+                .map(|(&x, &(mean, std))| if std == 0.0 { 0.0 } else { (x - mean) / std })
+                .collect()
+        }))
+    }
+}
+
+/// Mean and population standard deviation of the up to `window` values
+/// ending at each index
+fn rolling_mean_std(data: &[f64], window: usize) -> Vec<(f64, f64)> {
+    let mut stats = Vec::with_capacity(data.len());
+    let (mut mean, mut m2) = (0.0, 0.0);
+    // Equal values ending here; a window inside such a run is constant, which
+    // the floating-point updates would not reliably report as exactly so
+    let mut run = 0;
+    for (i, &x) in data.iter().enumerate() {
+        run = if i > 0 && data[i - 1] == x {
+            run + 1
+        } else {
+            1
+        };
+        if i < window {
+            let n = (i + 1) as f64;
+            let delta = x - mean;
+            mean += delta / n;
+            m2 += delta * (x - mean);
+        } else {
+            // Adding `x` and dropping `old` leaves the count unchanged
+            let old = data[i - window];
+            let previous = mean;
+            mean += (x - old) / window as f64;
+            m2 += (x - old) * (x - mean + old - previous);
+        }
+
+        let n = (i + 1).min(window);
+        if run >= n {
+            // Restart from exact values once the window is constant
+            mean = x;
+            m2 = 0.0;
+        }
+        // Rounding can leave the sum of squares slightly negative
+        stats.push((mean, (m2.max(0.0) / n as f64).sqrt()));
+    }
+    stats
+}
@@ -0,0 +1,109 @@
+use rayon::prelude::*;
+use wasm_bindgen::prelude::*;
+
+use super::WasmParallelProcessor;
+use crate::WasmError;
+
+/// Segments `parallel_sample_without_replacement` splits the population
+/// into, fixed so the sample does not depend on the number of workers
+const SAMPLE_SEGMENTS: usize = 64;
+
+#[wasm_bindgen]
+impl WasmParallelProcessor {
+    /// `k` distinct indices drawn uniformly from `0..n`, in random order.
+    ///
+    /// `0..n` is split into 64 segments. The number of draws from each
+    /// segment is decided up front, then every segment runs a partial
+    /// Fisher-Yates shuffle in parallel with its own splitmix64 stream seeded
+    /// from `seed ^ segment`. Results are reproducible for a given seed,
+    /// whatever the number of threads.
+    #[wasm_bindgen]
+    pub fn parallel_sample_without_replacement(
+        &self,
+        n: usize,
+        k: usize,
+        seed: u64,
+    ) -> Result<Vec<u32>, WasmError> {
+        let timing = self.profile("parallel_sample_without_replacement", n);
+        if k > n {
+            return Err(WasmError::invalid(
+                "k",
+                format!("cannot sample {k} distinct indices from {n}"),
+            ));
+        }
+        if n as u64 > u64::from(u32::MAX) + 1 {
+            return Err(WasmError::invalid(
+                "n",
+                format!("a population of {n} exceeds the u32 index range"),
+            ));
+        }
+
+        timing.finish(self.install(|| {
+            let mut indices: Vec<u32> = (0..n).into_par_iter().map(|i| i as u32).collect();
+            let segment_len = ((n + SAMPLE_SEGMENTS - 1) / SAMPLE_SEGMENTS).max(1);
+            // Complemented so it never coincides with a segment's stream
+            let mut rng = SplitMix64::new(!seed);
+
+            // Draw k times without replacement across segments to find how
+            // many indices each one contributes
+            let mut remaining: Vec<usize> = indices.chunks(segment_len).map(<[u32]>::len).collect();
+            let mut quotas = vec![0usize; remaining.len()];
+            let mut total = n;
+            for _ in 0..k {
+                let mut pick = rng.below(total);
+                let mut segment = 0;
+                while pick >= remaining[segment] {
+                    pick -= remaining[segment];
+                    segment += 1;
+                }
+                remaining[segment] -= 1;
+                quotas[segment] += 1;
+                total -= 1;
+            }
+
+            let mut sample: Vec<u32> = indices
+                .par_chunks_mut(segment_len)
+                .zip(quotas)
+                .enumerate()
+                .flat_map_iter(|(segment, (values, quota))| {
+                    let mut rng = SplitMix64::new(seed ^ segment as u64);
+                    for i in 0..quota {
+                        let j = i + rng.below(values.len() - i);
+                        values.swap(i, j);
+                    }
+                    values[..quota].to_vec()
+                })
+                .collect();
+
+            // Segments were concatenated in order, so mix the picks together
+            for i in (1..sample.len()).rev() {
+                sample.swap(i, rng.below(i + 1));
+            }
+            sample
+        }))
+    }
+}
+
+/// splitmix64 generator (Steele, Lea and Flood), cheap to seed per stream
+pub(crate) struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    pub(crate) fn new(seed: u64) -> SplitMix64 {
+        SplitMix64 { state: seed }
+    }
+
+    pub(crate) fn next(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform integer in `0..bound` by multiply-shift reduction
+    pub(crate) fn below(&mut self, bound: usize) -> usize {
+        ((self.next() as u128 * bound as u128) >> 64) as usize
+    }
+}
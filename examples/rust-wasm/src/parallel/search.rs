@@ -0,0 +1,146 @@
+use rayon::prelude::*;
+use std::collections::VecDeque;
+use wasm_bindgen::prelude::*;
+
+use super::WasmParallelProcessor;
+use crate::WasmError;
+
+#[wasm_bindgen]
+impl WasmParallelProcessor {
+    /// Find every occurrence of every pattern in each text chunk.
+    ///
+    /// Returns a JSON array of `{chunk_index, byte_offset, pattern_index}`
+    /// objects, including overlapping matches. Offsets are in UTF-8 bytes and
+    /// always fall on character boundaries because patterns are valid UTF-8.
+    #[wasm_bindgen]
+    pub fn parallel_multi_search(
+        &self,
+        text_chunks: Vec<JsValue>,
+        patterns: Vec<JsValue>,
+    ) -> Result<JsValue, WasmError> {
+        let timing = self.profile("parallel_multi_search", text_chunks.len());
+        let texts = strings_from_js(&text_chunks, "text chunk")?;
+        let patterns = strings_from_js(&patterns, "pattern")?;
+        if patterns.iter().any(String::is_empty) {
+            return Err(WasmError::invalid("patterns", "must not be empty strings"));
+        }
+
+        let automaton = AhoCorasick::new(&patterns);
+        let matches: Vec<serde_json::Value> = self.install(|| {
+            texts
+                .par_iter()
+                .enumerate()
+                .flat_map_iter(|(chunk_index, text)| {
+                    automaton.find_all(text.as_bytes()).into_iter().map(
+                        move |(byte_offset, pattern_index)| {
+                            serde_json::json!({
+                                "chunk_index": chunk_index,
+                                "byte_offset": byte_offset,
+                                "pattern_index": pattern_index,
+                            })
+                        },
+                    )
+                })
+                .collect()
+        })?;
+
+        timing.finish(Ok(JsValue::from_str(
+            &serde_json::Value::Array(matches).to_string(),
+        )))
+    }
+}
+
+/// Convert JS values to owned strings so they can cross thread boundaries
+fn strings_from_js(values: &[JsValue], what: &str) -> Result<Vec<String>, WasmError> {
+    values
+        .iter()
+        .enumerate()
+        .map(|(index, value)| {
+            value
+                .as_string()
+                .ok_or_else(|| WasmError::invalid(format!("{what} {index}"), "not a string"))
+        })
+        .collect()
+}
+
+/// Byte-level Aho-Corasick automaton with a fully expanded transition table
+struct AhoCorasick {
+    /// `transitions[state * 256 + byte]` is the next state
+    transitions: Vec<u32>,
+    /// Patterns ending at each state, including those reached via suffix links
+    outputs: Vec<Vec<usize>>,
+    pattern_lengths: Vec<usize>,
+}
+
+impl AhoCorasick {
+    fn new(patterns: &[String]) -> Self {
+        const NONE: u32 = u32::MAX;
+
+        let mut transitions = vec![NONE; 256];
+        let mut outputs = vec![Vec::new()];
+
+        // Build the trie
+        for (index, pattern) in patterns.iter().enumerate() {
+            let mut state = 0usize;
+            for &byte in pattern.as_bytes() {
+                let slot = state * 256 + byte as usize;
+                if transitions[slot] == NONE {
+                    transitions[slot] = outputs.len() as u32;
+                    transitions.resize(transitions.len() + 256, NONE);
+                    outputs.push(Vec::new());
+                }
+                state = transitions[slot] as usize;
+            }
+            outputs[state].push(index);
+        }
+
+        // Breadth-first pass computing failure links and completing the table
+        let mut failure = vec![0u32; outputs.len()];
+        let mut queue = VecDeque::new();
+        for slot in &mut transitions[..256] {
+            match *slot {
+                NONE => *slot = 0,
+                child => queue.push_back(child as usize),
+            }
+        }
+
+        while let Some(state) = queue.pop_front() {
+            let fallback = failure[state] as usize;
+            let inherited = outputs[fallback].clone();
+            outputs[state].extend(inherited);
+
+            for byte in 0..256 {
+                let slot = state * 256 + byte;
+                let via_failure = transitions[fallback * 256 + byte];
+                match transitions[slot] {
+                    NONE => transitions[slot] = via_failure,
+                    child => {
+                        failure[child as usize] = via_failure;
+                        queue.push_back(child as usize);
+                    }
+                }
+            }
+        }
+
+        AhoCorasick {
+            transitions,
+            outputs,
+            pattern_lengths: patterns.iter().map(String::len).collect(),
+        }
+    }
+
+    /// All `(start_offset, pattern_index)` matches in `text`, in end order
+    fn find_all(&self, text: &[u8]) -> Vec<(usize, usize)> {
+        let mut matches = Vec::new();
+        let mut state = 0usize;
+
+        for (position, &byte) in text.iter().enumerate() {
+            state = self.transitions[state * 256 + byte as usize] as usize;
+            for &pattern in &self.outputs[state] {
+                matches.push((position + 1 - self.pattern_lengths[pattern], pattern));
+            }
+        }
+
+        matches
+    }
+}
@@ -0,0 +1,80 @@
+use rayon::prelude::*;
+use wasm_bindgen::prelude::*;
+
+use super::WasmParallelProcessor;
+use crate::WasmError;
+
+#[wasm_bindgen]
+impl WasmParallelProcessor {
+    /// Jaccard similarity `|A ∩ B| / |A ∪ B|` of two sets packed as `u64` bitsets
+    #[wasm_bindgen]
+    pub fn parallel_jaccard_similarity(&self, a: &[u64], b: &[u64]) -> Result<f64, WasmError> {
+        let timing = self.profile("parallel_jaccard_similarity", a.len());
+        if a.len() != b.len() {
+            return Err(WasmError::dimension(
+                "Bitset b length in words",
+                a.len(),
+                b.len(),
+            ));
+        }
+
+        let (intersection, union) = self.install(|| {
+            a.par_iter()
+                .zip(b)
+                .map(|(&x, &y)| ((x & y).count_ones() as u64, (x | y).count_ones() as u64))
+                .reduce(|| (0, 0), |(i1, u1), (i2, u2)| (i1 + i2, u1 + u2))
+        })?;
+        if union == 0 {
+            return Err(WasmError::invalid(
+                "bitsets",
+                "Jaccard similarity is undefined for two empty sets",
+            ));
+        }
+
+        timing.finish(Ok(intersection as f64 / union as f64))
+    }
+
+    /// Pairwise Jaccard similarities of `n_sets` bitsets of `set_size` words
+    /// each, stored back to back.
+    ///
+    /// Returns the strict lower triangle packed row by row, i.e. pairs
+    /// `(1, 0), (2, 0), (2, 1), (3, 0), ...`, giving `n_sets * (n_sets - 1) / 2`
+    /// values. Pairs of empty sets yield `NaN`.
+    #[wasm_bindgen]
+    pub fn parallel_pairwise_jaccard(
+        &self,
+        sets: &[u64],
+        n_sets: usize,
+        set_size: usize,
+    ) -> Result<Vec<f32>, WasmError> {
+        let timing = self.profile("parallel_pairwise_jaccard", sets.len());
+        if n_sets.checked_mul(set_size) != Some(sets.len()) {
+            return Err(WasmError::dimension(
+                format!("Words in {n_sets} sets of {set_size} words"),
+                n_sets * set_size,
+                sets.len(),
+            ));
+        }
+
+        let set = |i: usize| &sets[i * set_size..(i + 1) * set_size];
+        timing.finish(self.install(|| {
+            (1..n_sets)
+                .into_par_iter()
+                .flat_map_iter(|i| {
+                    (0..i).map(move |j| {
+                        let (intersection, union) = set(i).iter().zip(set(j)).fold(
+                            (0u32, 0u32),
+                            |(intersection, union), (&x, &y)| {
+                                (
+                                    intersection + (x & y).count_ones(),
+                                    union + (x | y).count_ones(),
+                                )
+                            },
+                        );
+                        intersection as f32 / union as f32
+                    })
+                })
+                .collect()
+        }))
+    }
+}
@@ -0,0 +1,132 @@
+use rayon::prelude::*;
+use wasm_bindgen::prelude::*;
+
+use super::WasmParallelProcessor;
+use crate::WasmError;
+
+/// Number of buckets per radix pass (one byte of the key)
+const RADIX: usize = 256;
+
+#[wasm_bindgen]
+impl WasmParallelProcessor {
+    /// Sort unsigned 32-bit keys with a parallel LSD radix sort
+    #[wasm_bindgen]
+    pub fn parallel_radix_sort_u32(&self, data: &[u32]) -> Result<Vec<u32>, WasmError> {
+        let timing = self.profile("parallel_radix_sort_u32", data.len());
+        timing.finish(self.install(|| radix_sort(data)))
+    }
+
+    /// Sort unsigned 64-bit keys with a parallel LSD radix sort
+    #[wasm_bindgen]
+    pub fn parallel_radix_sort_u64(&self, data: &[u64]) -> Result<Vec<u64>, WasmError> {
+        let timing = self.profile("parallel_radix_sort_u64", data.len());
+        timing.finish(self.install(|| radix_sort(data)))
+    }
+}
+
+/// Keys that can be split into byte-sized radix digits
+trait RadixKey: Copy + Send + Sync {
+    const PASSES: usize;
+
+    /// The `pass`-th byte of the key, least significant first
+    fn digit(self, pass: usize) -> usize;
+}
+
+impl RadixKey for u32 {
+    const PASSES: usize = 4;
+
+    fn digit(self, pass: usize) -> usize {
+        (self >> (pass * 8)) as usize & (RADIX - 1)
+    }
+}
+
+impl RadixKey for u64 {
+    const PASSES: usize = 8;
+
+    fn digit(self, pass: usize) -> usize {
+        (self >> (pass * 8)) as usize & (RADIX - 1)
+    }
+}
+
+/// Output pointer shared by the scatter tasks
+struct ScatterTarget<K>(*mut K);
+
+// SAFETY: tasks only write through the pointer at disjoint offsets, see
+// `radix_sort`.
+unsafe impl<K: Send> Send for ScatterTarget<K> {}
+unsafe impl<K: Send> Sync for ScatterTarget<K> {}
+
+impl<K> ScatterTarget<K> {
+    fn get(&self) -> *mut K {
+        self.0
+    }
+}
+
+/// LSD radix sort, one byte per pass, on the current Rayon pool.
+///
+/// Each pass splits the input into one chunk per worker, counts digits per
+/// chunk in parallel, turns the counts into per-chunk write offsets with a
+/// sequential prefix sum, then scatters every chunk in parallel. Passes where
+/// every key shares the same digit are skipped.
+fn radix_sort<K: RadixKey>(data: &[K]) -> Vec<K> {
+    let len = data.len();
+    let mut source = data.to_vec();
+    if len < 2 {
+        return source;
+    }
+    let mut target = source.clone();
+    let workers = rayon::current_num_threads();
+    let chunk_size = (len + workers - 1) / workers;
+
+    for pass in 0..K::PASSES {
+        let histograms: Vec<[usize; RADIX]> = source
+            .par_chunks(chunk_size)
+            .map(|chunk| {
+                let mut counts = [0usize; RADIX];
+                for &key in chunk {
+                    counts[key.digit(pass)] += 1;
+                }
+                counts
+            })
+            .collect();
+
+        let mut totals = [0usize; RADIX];
+        for counts in &histograms {
+            for (total, count) in totals.iter_mut().zip(counts) {
+                *total += count;
+            }
+        }
+        if totals.contains(&len) {
+            // Every key shares this digit, so the pass would only copy
+            continue;
+        }
+
+        let mut offsets = vec![[0usize; RADIX]; histograms.len()];
+        let mut next = 0;
+        for bucket in 0..RADIX {
+            for (offset, counts) in offsets.iter_mut().zip(&histograms) {
+                offset[bucket] = next;
+                next += counts[bucket];
+            }
+        }
+
+        let output = ScatterTarget(target.as_mut_ptr());
+        source
+            .par_chunks(chunk_size)
+            .zip(offsets)
+            .for_each(|(chunk, mut offset)| {
+                for &key in chunk {
+                    let slot = &mut offset[key.digit(pass)];
+                    // SAFETY: the prefix sum gives every (chunk, bucket) pair
+                    // its own range of `target`, so no two writes alias and
+                    // all of them stay below `len`.
+                    unsafe { output.get().add(*slot).write(key) };
+                    *slot += 1;
+                }
+            });
+
+        std::mem::swap(&mut source, &mut target);
+    }
+
+    source
+}
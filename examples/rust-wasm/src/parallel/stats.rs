@@ -0,0 +1,75 @@
+use rayon::prelude::*;
+use wasm_bindgen::prelude::*;
+
+use super::{reduce::sum, WasmParallelProcessor};
+use crate::WasmError;
+
+#[wasm_bindgen]
+impl WasmParallelProcessor {
+    /// Shannon entropy of the byte distribution, in bits per symbol
+    #[wasm_bindgen]
+    pub fn parallel_shannon_entropy(&self, data: &[u8]) -> Result<f64, WasmError> {
+        let timing = self.profile("parallel_shannon_entropy", data.len());
+        let probabilities = self.byte_probabilities(data)?;
+        let deterministic = self.deterministic();
+
+        timing.finish(self.install(|| {
+            -sum(deterministic, probabilities.len(), |i| {
+                let p = probabilities[i];
+                if p > 0.0 {
+                    p * p.log2()
+                } else {
+                    0.0
+                }
+            })
+        }))
+    }
+
+    /// Rényi entropy of order `alpha`, in bits per symbol.
+    ///
+    /// `alpha = 1` is the Shannon limit and delegates to
+    /// [`WasmParallelProcessor::parallel_shannon_entropy`].
+    #[wasm_bindgen]
+    pub fn parallel_renyi_entropy(&self, data: &[u8], alpha: f64) -> Result<f64, WasmError> {
+        let timing = self.profile("parallel_renyi_entropy", data.len());
+        if !alpha.is_finite() || alpha <= 0.0 {
+            return Err(WasmError::invalid("alpha", "must be finite and > 0"));
+        }
+        if alpha == 1.0 {
+            return self.parallel_shannon_entropy(data);
+        }
+        if data.is_empty() {
+            return Ok(0.0);
+        }
+
+        let probabilities = self.byte_probabilities(data)?;
+        let deterministic = self.deterministic();
+        let power_sum = self.install(|| {
+            sum(deterministic, probabilities.len(), |i| {
+                let p = probabilities[i];
+                if p > 0.0 {
+                    p.powf(alpha)
+                } else {
+                    0.0
+                }
+            })
+        })?;
+
+        timing.finish(Ok(power_sum.log2() / (1.0 - alpha)))
+    }
+}
+
+impl WasmParallelProcessor {
+    /// Normalised 256-bin byte histogram (all zeros for empty input)
+    fn byte_probabilities(&self, data: &[u8]) -> Result<Vec<f64>, WasmError> {
+        let counts = self.parallel_count_values(data)?;
+        let total = data.len().max(1) as f64;
+
+        self.install(|| {
+            counts
+                .par_iter()
+                .map(|&count| count as f64 / total)
+                .collect()
+        })
+    }
+}
@@ -0,0 +1,88 @@
+use std::collections::HashMap;
+
+use js_sys::Array;
+use rayon::prelude::*;
+use wasm_bindgen::prelude::*;
+
+use super::WasmParallelProcessor;
+use crate::WasmError;
+
+#[wasm_bindgen]
+impl WasmParallelProcessor {
+    /// Split each string on whitespace into lowercase tokens.
+    ///
+    /// Leading and trailing characters other than letters, digits and
+    /// apostrophes are stripped, and tokens left empty are dropped. Returns
+    /// one array of tokens per input string.
+    #[wasm_bindgen]
+    pub fn parallel_tokenize(&self, texts: &Array) -> Result<Array, WasmError> {
+        let timing = self.profile("parallel_tokenize", texts.length() as usize);
+        let texts = strings(texts, "texts")?;
+        let documents = self.install(|| tokenize_all(&texts))?;
+
+        timing.finish(Ok(documents
+            .iter()
+            .map(|tokens| tokens.iter().map(JsValue::from).collect::<Array>())
+            .collect()))
+    }
+
+    /// Tokenize like [`WasmParallelProcessor::parallel_tokenize`] and encode
+    /// every token as an index into a vocabulary, concatenating all strings.
+    ///
+    /// Indices are assigned in order of first occurrence. The vocabulary is
+    /// rebuilt on each call and can be read back with
+    /// [`WasmParallelProcessor::get_vocabulary`].
+    #[wasm_bindgen]
+    pub fn parallel_tokenize_flat(&mut self, texts: &Array) -> Result<Vec<u32>, WasmError> {
+        let timing = self.profile("parallel_tokenize_flat", texts.length() as usize);
+        let texts = strings(texts, "texts")?;
+        let documents = self.install(|| tokenize_all(&texts))?;
+
+        let mut ids: HashMap<&str, u32> = HashMap::new();
+        let mut vocabulary = Vec::new();
+        let encoded = documents
+            .iter()
+            .flatten()
+            .map(|token| {
+                *ids.entry(token).or_insert_with(|| {
+                    vocabulary.push(token.clone());
+                    vocabulary.len() as u32 - 1
+                })
+            })
+            .collect();
+
+        self.vocabulary = vocabulary;
+        timing.finish(Ok(encoded))
+    }
+
+    /// Vocabulary built by the last [`WasmParallelProcessor::parallel_tokenize_flat`] call
+    #[wasm_bindgen]
+    pub fn get_vocabulary(&self) -> Vec<JsValue> {
+        self.vocabulary.iter().map(JsValue::from).collect()
+    }
+}
+
+/// Copy a JavaScript array of strings out so it can be shared across
+/// threads, naming it `what` in errors
+pub(super) fn strings(texts: &Array, what: &str) -> Result<Vec<String>, WasmError> {
+    texts
+        .iter()
+        .enumerate()
+        .map(|(i, text)| {
+            text.as_string()
+                .ok_or_else(|| WasmError::invalid(format!("{what} element {i}"), "not a string"))
+        })
+        .collect()
+}
+
+fn tokenize_all(texts: &[String]) -> Vec<Vec<String>> {
+    texts.par_iter().map(|text| tokenize(text)).collect()
+}
+
+pub(crate) fn tokenize(text: &str) -> Vec<String> {
+    text.split_whitespace()
+        .map(|word| word.trim_matches(|c: char| !(c.is_alphanumeric() || c == '\'')))
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
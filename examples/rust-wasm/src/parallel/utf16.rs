@@ -0,0 +1,95 @@
+use rayon::prelude::*;
+use wasm_bindgen::prelude::*;
+
+use super::WasmParallelProcessor;
+use crate::WasmError;
+
+/// Code units decoded per task
+const UTF16_CHUNK: usize = 32 * 1024;
+
+#[wasm_bindgen]
+impl WasmParallelProcessor {
+    /// Decode UTF-16 code units, such as a Windows `wchar_t` string or a
+    /// Java `char[]`, into a string.
+    ///
+    /// Chunks are decoded in parallel and then joined. Chunk boundaries are
+    /// first moved forward past any surrogate pair they would split, so every
+    /// chunk decodes on its own. An unpaired surrogate is an
+    /// `INVALID_ENCODING` error whose offset is in bytes, twice the index of
+    /// the offending code unit.
+    #[wasm_bindgen]
+    pub fn parallel_decode_utf16(&self, data: &[u16]) -> Result<String, WasmError> {
+        let timing = self.profile("parallel_decode_utf16", data.len());
+        let chunks = surrogate_safe_chunks(data);
+        let decoded: Vec<Result<String, usize>> = self.install(|| {
+            chunks
+                .par_iter()
+                .map(|&(start, end)| decode_chunk(&data[start..end]).map_err(|i| start + i))
+                .collect()
+        })?;
+
+        // Chunks are in order, so the first error met is the earliest
+        let mut text = String::with_capacity(data.len());
+        for chunk in decoded {
+            match chunk {
+                Ok(chunk) => text.push_str(&chunk),
+                Err(index) => {
+                    return Err(WasmError::InvalidEncoding {
+                        encoding: "UTF-16".to_string(),
+                        offset: index * 2,
+                        reason: format!("unpaired surrogate 0x{:04X}", data[index]),
+                    })
+                }
+            }
+        }
+        timing.finish(Ok(text))
+    }
+
+    /// Encode `text` as UTF-16 code units, characters outside the Basic
+    /// Multilingual Plane as surrogate pairs
+    #[wasm_bindgen]
+    pub fn parallel_encode_utf16(&self, text: &str) -> Result<Vec<u16>, WasmError> {
+        let timing = self.profile("parallel_encode_utf16", text.len());
+        timing.finish(self.install(|| text.par_encode_utf16().collect()))
+    }
+}
+
+/// `(start, end)` of each chunk, none of which ends between the two halves
+/// of a surrogate pair
+fn surrogate_safe_chunks(data: &[u16]) -> Vec<(usize, usize)> {
+    let mut chunks = Vec::with_capacity(data.len() / UTF16_CHUNK + 1);
+    let mut start = 0;
+    while start < data.len() {
+        let mut end = (start + UTF16_CHUNK).min(data.len());
+        if end < data.len() && is_high_surrogate(data[end - 1]) && is_low_surrogate(data[end]) {
+            end += 1;
+        }
+        chunks.push((start, end));
+        start = end;
+    }
+    chunks
+}
+
+/// Decode one chunk, or return the index of its first unpaired surrogate
+fn decode_chunk(chunk: &[u16]) -> Result<String, usize> {
+    let mut text = String::with_capacity(chunk.len());
+    let mut index = 0;
+    for decoded in char::decode_utf16(chunk.iter().copied()) {
+        match decoded {
+            Ok(c) => {
+                text.push(c);
+                index += c.len_utf16();
+            }
+            Err(_) => return Err(index),
+        }
+    }
+    Ok(text)
+}
+
+fn is_high_surrogate(unit: u16) -> bool {
+    (0xD800..0xDC00).contains(&unit)
+}
+
+fn is_low_surrogate(unit: u16) -> bool {
+    (0xDC00..0xE000).contains(&unit)
+}
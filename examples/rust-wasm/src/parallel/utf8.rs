@@ -0,0 +1,70 @@
+use rayon::prelude::*;
+use wasm_bindgen::prelude::*;
+
+use super::WasmParallelProcessor;
+use crate::WasmError;
+
+/// Bytes validated per task; at least 4 so no character spans three chunks
+const UTF8_CHUNK: usize = 64 * 1024;
+
+#[wasm_bindgen]
+impl WasmParallelProcessor {
+    /// Whether `data` is valid UTF-8.
+    ///
+    /// Chunks are validated in parallel, each ignoring the continuation
+    /// bytes it starts with and the incomplete character it may end with.
+    /// A sequential pass then joins the trailing bytes of each chunk to the
+    /// leading bytes of the next and checks that they form one character.
+    #[wasm_bindgen]
+    pub fn parallel_utf8_validate(&self, data: &[u8]) -> Result<bool, WasmError> {
+        let timing = self.profile("parallel_utf8_validate", data.len());
+        let splits: Option<Vec<(usize, usize)>> =
+            self.install(|| data.par_chunks(UTF8_CHUNK).map(split_chunk).collect())?;
+        let Some(splits) = splits else {
+            return Ok(false);
+        };
+
+        let mut pending: &[u8] = &[];
+        for (chunk, &(head, tail)) in data.chunks(UTF8_CHUNK).zip(&splits) {
+            if !joins_into_char(pending, &chunk[..head]) {
+                return Ok(false);
+            }
+            pending = &chunk[tail..];
+        }
+        timing.finish(Ok(pending.is_empty()))
+    }
+}
+
+/// Validate the interior of a chunk, returning how many continuation bytes
+/// it starts with and where its trailing incomplete character begins, or
+/// `None` if the interior is invalid
+fn split_chunk(chunk: &[u8]) -> Option<(usize, usize)> {
+    let head = chunk
+        .iter()
+        .take(4)
+        .take_while(|&&byte| is_continuation(byte))
+        .count();
+    if head > 3 {
+        return None;
+    }
+
+    match std::str::from_utf8(&chunk[head..]) {
+        Ok(_) => Some((head, chunk.len())),
+        // Only an incomplete final character leaves `error_len` unset
+        Err(error) if error.error_len().is_none() => Some((head, head + error.valid_up_to())),
+        Err(_) => None,
+    }
+}
+
+/// Whether the up-to-3-byte `tail` of one chunk and the continuation bytes
+/// `head` starting the next are together empty or exactly one character
+fn joins_into_char(tail: &[u8], head: &[u8]) -> bool {
+    let mut joined = [0; 6];
+    joined[..tail.len()].copy_from_slice(tail);
+    joined[tail.len()..tail.len() + head.len()].copy_from_slice(head);
+    std::str::from_utf8(&joined[..tail.len() + head.len()]).is_ok()
+}
+
+fn is_continuation(byte: u8) -> bool {
+    byte & 0xC0 == 0x80
+}
@@ -0,0 +1,109 @@
+use rayon::prelude::*;
+use wasm_bindgen::prelude::*;
+
+use super::WasmParallelProcessor;
+use crate::WasmError;
+
+#[wasm_bindgen]
+impl WasmParallelProcessor {
+    /// Full multi-level Haar transform of a power-of-two length signal.
+    ///
+    /// Each level replaces the leading `n` values with `n / 2` pairwise
+    /// averages followed by `n / 2` half-differences, so the result starts
+    /// with the overall mean and continues from the coarsest to the finest
+    /// detail coefficients.
+    #[wasm_bindgen]
+    pub fn parallel_haar_transform(&self, data: &[f64]) -> Result<Vec<f64>, WasmError> {
+        let timing = self.profile("parallel_haar_transform", data.len());
+        check_power_of_two(data.len(), "Signal length")?;
+
+        let mut coefficients = data.to_vec();
+        self.install(|| haar_forward(&mut coefficients))?;
+        timing.finish(Ok(coefficients))
+    }
+
+    /// Invert [`WasmParallelProcessor::parallel_haar_transform`]
+    #[wasm_bindgen]
+    pub fn parallel_haar_inverse(&self, coeffs: &[f64]) -> Result<Vec<f64>, WasmError> {
+        let timing = self.profile("parallel_haar_inverse", coeffs.len());
+        check_power_of_two(coeffs.len(), "Coefficient count")?;
+
+        let mut signal = coeffs.to_vec();
+        self.install(|| {
+            let mut len = 2;
+            while len <= signal.len() {
+                let (averages, details) = signal[..len].split_at(len / 2);
+                let pairs: Vec<f64> = averages
+                    .par_iter()
+                    .zip(details)
+                    .flat_map_iter(|(&average, &detail)| [average + detail, average - detail])
+                    .collect();
+                signal[..len].copy_from_slice(&pairs);
+                len *= 2;
+            }
+        })?;
+        timing.finish(Ok(signal))
+    }
+
+    /// Standard 2D Haar decomposition of a row-major `rows x cols` matrix:
+    /// a full 1D transform along every row, then along every column
+    #[wasm_bindgen]
+    pub fn parallel_haar_transform_2d(
+        &self,
+        data: &[f64],
+        rows: usize,
+        cols: usize,
+    ) -> Result<Vec<f64>, WasmError> {
+        let timing = self.profile("parallel_haar_transform_2d", data.len());
+        check_power_of_two(rows, "Row count")?;
+        check_power_of_two(cols, "Column count")?;
+        if data.len() != rows * cols {
+            return Err(WasmError::dimension(
+                format!("Data length of a {rows}x{cols} matrix"),
+                rows * cols,
+                data.len(),
+            ));
+        }
+
+        timing.finish(self.install(|| {
+            let mut matrix = data.to_vec();
+            matrix.par_chunks_exact_mut(cols).for_each(haar_forward);
+
+            let mut columns: Vec<f64> = (0..rows * cols)
+                .into_par_iter()
+                .map(|i| matrix[(i % rows) * cols + i / rows])
+                .collect();
+            columns.par_chunks_exact_mut(rows).for_each(haar_forward);
+
+            matrix
+                .par_iter_mut()
+                .enumerate()
+                .for_each(|(i, value)| *value = columns[(i % cols) * rows + i / cols]);
+            matrix
+        }))
+    }
+}
+
+/// In-place multi-level Haar transform with parallel pairwise steps
+fn haar_forward(signal: &mut [f64]) {
+    let mut len = signal.len();
+    while len > 1 {
+        let (averages, details): (Vec<f64>, Vec<f64>) = signal[..len]
+            .par_chunks_exact(2)
+            .map(|pair| ((pair[0] + pair[1]) / 2.0, (pair[0] - pair[1]) / 2.0))
+            .unzip();
+        signal[..len / 2].copy_from_slice(&averages);
+        signal[len / 2..len].copy_from_slice(&details);
+        len /= 2;
+    }
+}
+
+fn check_power_of_two(len: usize, what: &str) -> Result<(), WasmError> {
+    if !len.is_power_of_two() {
+        return Err(WasmError::invalid(
+            what,
+            format!("must be a power of two, got {len}"),
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,185 @@
+//! Scratch buffers shared by the processors on a runtime, so that an
+//! operation called every animation frame stops going back to the allocator
+//! for its intermediate buffers.
+//!
+//! Buffers are shelved by size class, the power of two at or above the
+//! length asked for, and a recycled buffer serves any later request of its
+//! class. Only scratch buffers come back: a result handed to JavaScript is
+//! copied out and freed by wasm-bindgen.
+
+use std::{
+    collections::HashMap,
+    mem::size_of,
+    sync::{Mutex, MutexGuard, PoisonError},
+};
+
+/// Buffers kept of each size class and element type; more are freed
+const MAX_PER_CLASS: usize = 8;
+
+/// Bytes held across every shelf, past which recycled buffers are freed
+const MAX_HELD_BYTES: usize = 64 << 20;
+
+/// What a runtime's buffer pool has done since it was created
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PoolStats {
+    /// Buffers handed out from the pool
+    pub hits: u64,
+    /// Buffers the pool had to allocate
+    pub misses: u64,
+    /// Bytes of capacity held for reuse
+    pub held_bytes: usize,
+    /// Buffers held for reuse
+    pub held_buffers: usize,
+}
+
+/// Thread-safe pool of `Vec<u8>`, `Vec<f32>` and `Vec<f64>` buffers
+#[derive(Debug, Default)]
+pub(crate) struct BufferPool {
+    state: Mutex<State>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    shelves: Shelves,
+    stats: PoolStats,
+}
+
+/// Held buffers by element type, then by the log2 of their size class
+#[derive(Debug, Default)]
+pub(crate) struct Shelves {
+    bytes: HashMap<u32, Vec<Vec<u8>>>,
+    floats: HashMap<u32, Vec<Vec<f32>>>,
+    doubles: HashMap<u32, Vec<Vec<f64>>>,
+}
+
+/// Element types the pool keeps shelves for
+pub(crate) trait Pooled: Copy + Default + Send + 'static {
+    fn shelf(shelves: &mut Shelves) -> &mut HashMap<u32, Vec<Vec<Self>>>;
+}
+
+impl Pooled for u8 {
+    fn shelf(shelves: &mut Shelves) -> &mut HashMap<u32, Vec<Vec<u8>>> {
+        &mut shelves.bytes
+    }
+}
+
+impl Pooled for f32 {
+    fn shelf(shelves: &mut Shelves) -> &mut HashMap<u32, Vec<Vec<f32>>> {
+        &mut shelves.floats
+    }
+}
+
+impl Pooled for f64 {
+    fn shelf(shelves: &mut Shelves) -> &mut HashMap<u32, Vec<Vec<f64>>> {
+        &mut shelves.doubles
+    }
+}
+
+impl BufferPool {
+    /// A buffer of `len` zeroes, reusing a held one of the same size class
+    /// when there is one
+    pub(crate) fn take<T: Pooled>(&self, len: usize) -> Vec<T> {
+        if len == 0 {
+            return Vec::new();
+        }
+        let Some(class) = len.checked_next_power_of_two() else {
+            self.lock().stats.misses += 1;
+            return vec![T::default(); len];
+        };
+
+        let held = {
+            let mut state = self.lock();
+            let held = T::shelf(&mut state.shelves)
+                .get_mut(&class.ilog2())
+                .and_then(Vec::pop);
+            match &held {
+                Some(buffer) => {
+                    state.stats.hits += 1;
+                    state.stats.held_bytes -= buffer.capacity() * size_of::<T>();
+                    state.stats.held_buffers -= 1;
+                }
+                None => state.stats.misses += 1,
+            }
+            held
+        };
+
+        let mut buffer = held.unwrap_or_else(|| Vec::with_capacity(class));
+        buffer.clear();
+        buffer.resize(len, T::default());
+        buffer
+    }
+
+    /// Hold `buffer` for a later [`BufferPool::take`], or free it if the
+    /// pool is full. Returns whether it was held.
+    pub(crate) fn recycle<T: Pooled>(&self, buffer: Vec<T>) -> bool {
+        let capacity = buffer.capacity();
+        if capacity == 0 {
+            return false;
+        }
+        let bytes = capacity * size_of::<T>();
+
+        let mut state = self.lock();
+        if state.stats.held_bytes + bytes > MAX_HELD_BYTES {
+            return false;
+        }
+        // Shelved by the class it can fully serve
+        let shelf = T::shelf(&mut state.shelves)
+            .entry(capacity.ilog2())
+            .or_default();
+        if shelf.len() >= MAX_PER_CLASS {
+            return false;
+        }
+        shelf.push(buffer);
+        state.stats.held_bytes += bytes;
+        state.stats.held_buffers += 1;
+        true
+    }
+
+    /// Free one held buffer of each capacity in `capacities` that the pool
+    /// still has, returning the bytes released
+    pub(crate) fn discard<T: Pooled>(&self, capacities: &[usize]) -> usize {
+        let (discarded, released) = {
+            let mut state = self.lock();
+            let shelves = T::shelf(&mut state.shelves);
+            let mut discarded = Vec::new();
+            for &capacity in capacities {
+                let Some(shelf) = shelves.get_mut(&capacity.ilog2()) else {
+                    continue;
+                };
+                if let Some(index) = shelf.iter().position(|held| held.capacity() == capacity) {
+                    discarded.push(shelf.swap_remove(index));
+                }
+            }
+            let released = discarded.iter().map(Vec::capacity).sum::<usize>() * size_of::<T>();
+            state.stats.held_bytes -= released;
+            state.stats.held_buffers -= discarded.len();
+            (discarded, released)
+        };
+        // Freed outside the lock
+        drop(discarded);
+        released
+    }
+
+    /// Free every held buffer, returning the bytes released
+    pub(crate) fn trim(&self) -> usize {
+        let (shelves, released) = {
+            let mut state = self.lock();
+            state.stats.held_buffers = 0;
+            (
+                std::mem::take(&mut state.shelves),
+                std::mem::take(&mut state.stats.held_bytes),
+            )
+        };
+        // Freed outside the lock
+        drop(shelves);
+        released
+    }
+
+    pub(crate) fn stats(&self) -> PoolStats {
+        self.lock().stats
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
@@ -0,0 +1,278 @@
+//! Opt-in timing of processor operations, read back with `last_op_stats`.
+//!
+//! A profiled operation opens a scope on the calling thread for the length
+//! of the call, and work it installs on a thread pool notes the size of that
+//! pool. Rayon has no hook for when a worker picks up a job, so which of the
+//! workers took a share is not known. Finishing the scope with the
+//! operation's result records the wall time and the bytes the result holds.
+//! Timing starts and ends inside the module, so copying arguments and
+//! results across the JavaScript boundary is not included.
+//!
+//! Only processor methods are profiled. Associated functions with no
+//! processor to report to, such as `lz4_decompress` and `delta_decode`, are
+//! not.
+
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    mem::size_of,
+    sync::{Arc, Mutex, PoisonError},
+};
+
+use js_sys::{Object, Reflect};
+use wasm_bindgen::prelude::*;
+
+thread_local! {
+    /// Usage of the operation being profiled on this thread, if any
+    static OPEN: RefCell<Option<Usage>> = const { RefCell::new(None) };
+}
+
+/// What a processor's last profiled operation did
+#[derive(Clone, Debug, PartialEq)]
+pub struct OpStats {
+    /// The method called, such as `"parallel_sum"`
+    pub operation: &'static str,
+    /// Milliseconds from the call entering the module to it returning
+    pub wall_ms: f64,
+    /// Length of the operation's main input
+    pub input_elements: usize,
+    /// Workers in the largest pool its parallel work was installed on, or 0
+    /// if it ran none
+    pub pool_threads: usize,
+    /// Bytes held by the result, from the capacities of its buffers
+    pub bytes_allocated: usize,
+}
+
+impl OpStats {
+    /// The stats as `{ operation, wall_ms, input_elements, pool_threads,
+    /// bytes_allocated }`
+    pub(crate) fn to_js(&self) -> JsValue {
+        let result = Object::new();
+        for (name, value) in [
+            ("operation", JsValue::from(self.operation)),
+            ("wall_ms", JsValue::from(self.wall_ms)),
+            ("input_elements", JsValue::from(self.input_elements)),
+            ("pool_threads", JsValue::from(self.pool_threads)),
+            ("bytes_allocated", JsValue::from(self.bytes_allocated)),
+        ] {
+            // Defining a property on a fresh plain object cannot fail
+            let _ = Reflect::set(&result, &name.into(), &value);
+        }
+        result.into()
+    }
+}
+
+/// A processor's profiling switch and the stats of its last profiled
+/// operation
+#[derive(Debug, Default)]
+pub(crate) struct Profiler {
+    enabled: bool,
+    /// Shared with the scope of an operation in progress, which may need
+    /// the processor mutably
+    last: Arc<Mutex<Option<OpStats>>>,
+}
+
+impl Profiler {
+    /// Turn profiling on or off, forgetting the last stats either way
+    pub(crate) fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        *self.last.lock().unwrap_or_else(PoisonError::into_inner) = None;
+    }
+
+    /// Stats of the last operation run while profiling was on
+    pub(crate) fn last(&self) -> Option<OpStats> {
+        self.last
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Profile `operation` until the returned scope is finished or dropped.
+    ///
+    /// The scope does nothing if profiling is off, or if an outer operation
+    /// is already being profiled on this thread, which then counts this
+    /// one's work as its own.
+    pub(crate) fn scope(&self, operation: &'static str, input_elements: usize) -> OpScope {
+        let opened = self.enabled
+            && OPEN.with(|open| {
+                let mut open = open.borrow_mut();
+                if open.is_some() {
+                    return false;
+                }
+                *open = Some(Usage::default());
+                true
+            });
+        OpScope {
+            last: opened.then(|| Arc::clone(&self.last)),
+            operation,
+            input_elements,
+            started: if opened { now_ms() } else { 0.0 },
+            bytes: 0,
+        }
+    }
+}
+
+/// An operation being profiled; dropping it records its stats
+pub(crate) struct OpScope {
+    /// Where to record the stats, or `None` if this scope is not profiling
+    last: Option<Arc<Mutex<Option<OpStats>>>>,
+    operation: &'static str,
+    input_elements: usize,
+    started: f64,
+    /// Heap bytes of the result passed to `finish`
+    bytes: usize,
+}
+
+impl OpScope {
+    /// Record the operation's stats, counting the bytes `result` holds, and
+    /// pass it through
+    pub(crate) fn finish<T: Footprint>(mut self, result: T) -> T {
+        if self.last.is_some() {
+            self.bytes = result.heap_bytes();
+        }
+        result
+    }
+}
+
+impl Drop for OpScope {
+    fn drop(&mut self) {
+        let Some(last) = self.last.take() else {
+            return;
+        };
+        let usage = OPEN
+            .with(|open| open.borrow_mut().take())
+            .unwrap_or_default();
+        let stats = OpStats {
+            operation: self.operation,
+            wall_ms: now_ms() - self.started,
+            input_elements: self.input_elements,
+            pool_threads: usage.pool_threads,
+            bytes_allocated: self.bytes,
+        };
+        *last.lock().unwrap_or_else(PoisonError::into_inner) = Some(stats);
+    }
+}
+
+/// Parallel work counted so far towards the open operation
+#[derive(Default)]
+struct Usage {
+    pool_threads: usize,
+}
+
+/// Count work installed on a pool of `pool_threads` workers towards the
+/// operation being profiled on this thread, if any
+pub(crate) fn record_pool(pool_threads: usize) {
+    OPEN.with(|open| {
+        if let Some(usage) = open.borrow_mut().as_mut() {
+            usage.pool_threads = usage.pool_threads.max(pool_threads);
+        }
+    });
+}
+
+/// Milliseconds on a monotonic clock, for timing within one thread.
+///
+/// Under WASM this is `performance.now()` from whichever global scope is
+/// running, a window, a Web Worker or Node, falling back to `Date.now()`.
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn now_ms() -> f64 {
+    use wasm_bindgen::JsCast;
+
+    match Reflect::get(&js_sys::global(), &"performance".into()) {
+        Ok(performance) if performance.is_object() => {
+            performance.unchecked_into::<web_sys::Performance>().now()
+        }
+        _ => js_sys::Date::now(),
+    }
+}
+
+/// Milliseconds on a monotonic clock, for timing within one thread.
+///
+/// Natively this is the time since the clock was first read.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn now_ms() -> f64 {
+    use std::{sync::OnceLock, time::Instant};
+
+    static EPOCH: OnceLock<Instant> = OnceLock::new();
+    EPOCH.get_or_init(Instant::now).elapsed().as_secs_f64() * 1000.0
+}
+
+/// Heap bytes a result holds, counted from the capacities of its buffers.
+///
+/// Only the outermost buffers are counted: the text of a `Vec<String>`, say,
+/// is not. Values living in JavaScript's heap count as nothing.
+pub(crate) trait Footprint {
+    fn heap_bytes(&self) -> usize {
+        0
+    }
+}
+
+impl<T> Footprint for Vec<T> {
+    fn heap_bytes(&self) -> usize {
+        self.capacity() * size_of::<T>()
+    }
+}
+
+impl Footprint for String {
+    fn heap_bytes(&self) -> usize {
+        self.capacity()
+    }
+}
+
+impl<K, V> Footprint for HashMap<K, V> {
+    fn heap_bytes(&self) -> usize {
+        self.capacity() * size_of::<(K, V)>()
+    }
+}
+
+impl<T: Footprint> Footprint for Option<T> {
+    fn heap_bytes(&self) -> usize {
+        self.as_ref().map_or(0, Footprint::heap_bytes)
+    }
+}
+
+impl<T: Footprint, E> Footprint for Result<T, E> {
+    fn heap_bytes(&self) -> usize {
+        self.as_ref().map_or(0, Footprint::heap_bytes)
+    }
+}
+
+impl<A: Footprint, B: Footprint> Footprint for (A, B) {
+    fn heap_bytes(&self) -> usize {
+        self.0.heap_bytes() + self.1.heap_bytes()
+    }
+}
+
+impl<A: Footprint, B: Footprint, C: Footprint> Footprint for (A, B, C) {
+    fn heap_bytes(&self) -> usize {
+        self.0.heap_bytes() + self.1.heap_bytes() + self.2.heap_bytes()
+    }
+}
+
+impl<T, const N: usize> Footprint for [T; N] {}
+
+impl Footprint for JsValue {}
+
+impl Footprint for js_sys::Array {}
+
+macro_rules! no_heap {
+    ($($t:ty),*) => {
+        $(impl Footprint for $t {})*
+    };
+}
+
+no_heap!(
+    (),
+    bool,
+    u8,
+    u16,
+    u32,
+    u64,
+    usize,
+    i8,
+    i16,
+    i32,
+    i64,
+    isize,
+    f32,
+    f64
+);
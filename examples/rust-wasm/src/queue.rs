@@ -0,0 +1,310 @@
+use std::{
+    collections::BTreeMap,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex, PoisonError,
+    },
+};
+
+use rayon::prelude::*;
+use wasm_bindgen::prelude::*;
+
+use crate::{batch::BatchOp, install, CancellationToken, WasmError, WasmRuntime};
+
+/// Elements mapped between cancellation checks
+const TASK_CHUNK: usize = 16 * 1024;
+
+/// Where a submitted task has got to
+#[derive(Debug)]
+enum TaskState {
+    Pending,
+    Running,
+    Done(Vec<f64>),
+    Failed(WasmError),
+    Cancelled { completed: usize },
+}
+
+impl TaskState {
+    fn is_finished(&self) -> bool {
+        !matches!(self, TaskState::Pending | TaskState::Running)
+    }
+}
+
+/// A submitted task, shared with the job that runs it
+#[derive(Debug)]
+struct Task {
+    state: Arc<Mutex<TaskState>>,
+    token: CancellationToken,
+    total: usize,
+}
+
+impl Task {
+    fn state(&self) -> std::sync::MutexGuard<'_, TaskState> {
+        lock(&self.state)
+    }
+}
+
+/// Background jobs on a runtime's pool, submitted now and collected later.
+///
+/// `submit_expr` returns at once with a task id, `status` polls it and
+/// `take_result` hands the result over and forgets the task. On a runtime
+/// with workers the job runs on them, so the caller never blocks. Without
+/// any, as in a page that is not cross-origin isolated, it runs on the
+/// calling thread once the current JavaScript task has returned.
+///
+/// Finished tasks are kept until taken, up to the retention limit; past it
+/// the oldest are forgotten as new tasks are submitted.
+#[wasm_bindgen]
+pub struct WasmTaskQueue {
+    runtime: WasmRuntime,
+    /// Most finished tasks kept
+    retention: usize,
+    /// By id, which increases with submission order
+    tasks: BTreeMap<u32, Task>,
+    next_id: u32,
+}
+
+#[wasm_bindgen]
+impl WasmTaskQueue {
+    /// A queue keeping up to `retention` finished tasks, on the shared
+    /// runtime of `num_threads` workers (see [`WasmRuntime::shared`])
+    #[wasm_bindgen(constructor)]
+    pub fn new(retention: usize, num_threads: usize) -> Result<WasmTaskQueue, WasmError> {
+        WasmTaskQueue::with_runtime(retention, &WasmRuntime::shared(num_threads)?)
+    }
+
+    /// A queue keeping up to `retention` finished tasks, running them on
+    /// `runtime`'s thread pool
+    #[wasm_bindgen]
+    pub fn with_runtime(
+        retention: usize,
+        runtime: &WasmRuntime,
+    ) -> Result<WasmTaskQueue, WasmError> {
+        if retention == 0 {
+            return Err(WasmError::invalid("retention", "must be positive"));
+        }
+        Ok(WasmTaskQueue {
+            runtime: runtime.clone(),
+            retention,
+            tasks: BTreeMap::new(),
+            next_id: 1,
+        })
+    }
+
+    /// Most finished tasks kept before the oldest are forgotten
+    #[wasm_bindgen(getter)]
+    pub fn retention(&self) -> usize {
+        self.retention
+    }
+
+    /// Start applying `expr` to a copy of `data` and return the task's id.
+    ///
+    /// `expr` is one or more of the operations taken by
+    /// `WasmBatchProcessor.process_batch_str`, separated by `|` and applied
+    /// left to right, such as `"sqrt|sin"`. It is checked before the task
+    /// is queued, so an unknown operation throws here.
+    #[wasm_bindgen]
+    pub fn submit_expr(&mut self, data: &[f64], expr: &str) -> Result<u32, WasmError> {
+        let steps = parse_expr(expr)?;
+        self.evict_finished();
+
+        let id = self.next_id;
+        self.next_id = self.next_id.wrapping_add(1).max(1);
+        let task = Task {
+            state: Arc::new(Mutex::new(TaskState::Pending)),
+            token: CancellationToken::new(),
+            total: data.len(),
+        };
+        let (state, token) = (task.state.clone(), task.token.clone());
+        let runtime = self.runtime.clone();
+        let data = data.to_vec();
+        spawn(&self.runtime, move || {
+            run(&runtime, data, &steps, &state, &token);
+        });
+        self.tasks.insert(id, task);
+        Ok(id)
+    }
+
+    /// `"pending"`, `"running"`, `"done"`, `"error"` or `"cancelled"`.
+    ///
+    /// A task that has been taken, or forgotten past the retention limit,
+    /// is an `INVALID_ARGUMENT` error.
+    #[wasm_bindgen]
+    pub fn status(&self, id: u32) -> Result<String, WasmError> {
+        let status = match *self.task(id)?.state() {
+            TaskState::Pending => "pending",
+            TaskState::Running => "running",
+            TaskState::Done(_) => "done",
+            TaskState::Failed(_) => "error",
+            TaskState::Cancelled { .. } => "cancelled",
+        };
+        Ok(status.to_string())
+    }
+
+    /// The result of a finished task, which is then forgotten.
+    ///
+    /// A task that failed throws its error, and a cancelled one `CANCELLED`
+    /// with how many elements it had mapped; either is forgotten too. A
+    /// task that has not finished is a `RESOURCE_UNAVAILABLE` error and is
+    /// kept.
+    #[wasm_bindgen]
+    pub fn take_result(&mut self, id: u32) -> Result<Vec<f64>, WasmError> {
+        let task = self.task(id)?;
+        let total = task.total;
+        let state = {
+            let mut state = task.state();
+            if !state.is_finished() {
+                return Err(WasmError::ResourceUnavailable {
+                    reason: format!("task {id} has not finished"),
+                });
+            }
+            std::mem::replace(&mut *state, TaskState::Pending)
+        };
+        self.tasks.remove(&id);
+
+        match state {
+            TaskState::Done(result) => Ok(result),
+            TaskState::Failed(error) => Err(error),
+            TaskState::Cancelled { completed } => Err(WasmError::Cancelled { completed, total }),
+            TaskState::Pending | TaskState::Running => unreachable!("checked above"),
+        }
+    }
+
+    /// Cancel a task, returning whether it had yet to finish.
+    ///
+    /// A pending task never starts; a running one stops at its next check,
+    /// within a few thousand elements. Either way it then reports
+    /// `"cancelled"`.
+    #[wasm_bindgen]
+    pub fn cancel(&self, id: u32) -> Result<bool, WasmError> {
+        let task = self.task(id)?;
+        task.token.cancel();
+        let mut state = task.state();
+        match *state {
+            TaskState::Pending => *state = TaskState::Cancelled { completed: 0 },
+            TaskState::Running => {}
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    /// Tasks held, whether finished or not
+    #[wasm_bindgen(getter)]
+    pub fn task_count(&self) -> usize {
+        self.tasks.len()
+    }
+
+    /// Bytes held by finished results that have not been taken
+    #[wasm_bindgen]
+    pub fn retained_bytes(&self) -> usize {
+        self.tasks
+            .values()
+            .map(|task| match &*task.state() {
+                TaskState::Done(result) => result.capacity() * std::mem::size_of::<f64>(),
+                _ => 0,
+            })
+            .sum()
+    }
+}
+
+impl WasmTaskQueue {
+    fn task(&self, id: u32) -> Result<&Task, WasmError> {
+        self.tasks.get(&id).ok_or_else(|| {
+            WasmError::invalid(
+                "task id",
+                format!("no task {id}; it may have been taken or evicted"),
+            )
+        })
+    }
+
+    /// Forget the oldest finished tasks beyond the retention limit
+    fn evict_finished(&mut self) {
+        let finished: Vec<u32> = self
+            .tasks
+            .iter()
+            .filter(|(_, task)| task.state().is_finished())
+            .map(|(&id, _)| id)
+            .collect();
+        let excess = finished.len().saturating_sub(self.retention);
+        for id in &finished[..excess] {
+            self.tasks.remove(id);
+        }
+    }
+}
+
+/// The operations of a `|`-separated expression
+fn parse_expr(expr: &str) -> Result<Vec<BatchOp>, WasmError> {
+    if expr.trim().is_empty() {
+        return Err(WasmError::invalid("expression", "is empty"));
+    }
+    expr.split('|')
+        .map(|op| BatchOp::parse(op.trim()))
+        .collect()
+}
+
+/// Run a task unless it was cancelled while pending, mapping `data` in
+/// place and checking `token` between chunks
+fn run(
+    runtime: &WasmRuntime,
+    mut data: Vec<f64>,
+    steps: &[BatchOp],
+    state: &Mutex<TaskState>,
+    token: &CancellationToken,
+) {
+    {
+        let mut state = lock(state);
+        if !matches!(*state, TaskState::Pending) {
+            return;
+        }
+        *state = TaskState::Running;
+    }
+
+    let completed = AtomicUsize::new(0);
+    let result = install(runtime, || {
+        data.par_chunks_mut(TASK_CHUNK).try_for_each(|chunk| {
+            if token.is_cancelled() {
+                return Err(());
+            }
+            for x in chunk.iter_mut() {
+                *x = steps.iter().fold(*x, |x, op| op.apply(x));
+            }
+            completed.fetch_add(chunk.len(), Ordering::Relaxed);
+            Ok(())
+        })
+    });
+
+    *lock(state) = match result {
+        Ok(Ok(())) => TaskState::Done(data),
+        Ok(Err(())) => TaskState::Cancelled {
+            completed: completed.into_inner(),
+        },
+        Err(error) => TaskState::Failed(error),
+    };
+}
+
+/// Queue `job` on the runtime's pool, or on the calling thread once the
+/// current JavaScript task returns where there are no workers to run it
+fn spawn(runtime: &WasmRuntime, job: impl FnOnce() + Send + 'static) {
+    if let Some(pool) = runtime.current_pool() {
+        return pool.spawn(job);
+    }
+    #[cfg(target_arch = "wasm32")]
+    if !global_workers_running() {
+        return wasm_bindgen_futures::spawn_local(async move { job() });
+    }
+    rayon::spawn(job);
+}
+
+/// Whether Rayon's global pool has workers; without them a spawned job
+/// would never run
+#[cfg(target_arch = "wasm32")]
+fn global_workers_running() -> bool {
+    #[cfg(feature = "wasm-threads")]
+    return crate::threads::started();
+    #[cfg(not(feature = "wasm-threads"))]
+    false
+}
+
+fn lock(state: &Mutex<TaskState>) -> std::sync::MutexGuard<'_, TaskState> {
+    state.lock().unwrap_or_else(PoisonError::into_inner)
+}
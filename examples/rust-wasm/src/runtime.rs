@@ -0,0 +1,340 @@
+use std::{
+    any::Any,
+    collections::BTreeMap,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc, Mutex, OnceLock, PoisonError, RwLock,
+    },
+};
+
+use js_sys::{Object, Reflect};
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    panic_message,
+    pool::{BufferPool, PoolStats},
+    WasmError,
+};
+
+/// Numbers pools so their worker threads can be told apart by name
+static NEXT_POOL_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// Most threads an "auto" thread count will use, however many the browser
+/// reports
+const MAX_DETECTED_THREADS: usize = 64;
+
+/// The runtime shared by every processor constructed with 0 threads
+static GLOBAL: OnceLock<WasmRuntime> = OnceLock::new();
+
+/// The runtimes shared by processors constructed with a non-zero thread
+/// count, by that count
+static SHARED: Mutex<BTreeMap<usize, WasmRuntime>> = Mutex::new(BTreeMap::new());
+
+/// A thread pool that processors share, so creating several of them does
+/// not start a set of workers for each.
+///
+/// Under WASM every worker is a Web Worker with its own stack in shared
+/// memory, so processors never start a pool of their own: their
+/// `new(num_threads)` constructors use [`WasmRuntime::shared`], and
+/// `with_runtime` takes any runtime to share. Copies of a runtime, such
+/// as a processor's `runtime`, are handles to the same pool, and see it
+/// replaced by [`WasmRuntime::resize`].
+#[wasm_bindgen]
+#[derive(Clone)]
+pub struct WasmRuntime {
+    /// The current pool; `None` runs work on Rayon's global pool
+    pool: Arc<RwLock<Option<Arc<rayon::ThreadPool>>>>,
+    /// Whether floating-point reductions use a fixed order
+    deterministic: Arc<AtomicBool>,
+    /// Scratch buffers shared by the processors on this runtime
+    buffers: Arc<BufferPool>,
+}
+
+#[wasm_bindgen]
+impl WasmRuntime {
+    /// Start a pool of `num_threads` workers, or share the global runtime
+    /// when `num_threads` is 0.
+    ///
+    /// With the `wasm-threads` feature this fails with
+    /// `RESOURCE_UNAVAILABLE` until `initThreadPool()` has resolved, and
+    /// every processor constructor taking a thread count goes through it,
+    /// by way of [`WasmRuntime::shared`].
+    /// 0 is best effort, though: where the page cannot share memory with
+    /// workers at all, see [`threading_support`], it runs work on the
+    /// calling thread instead of failing.
+    #[wasm_bindgen(constructor)]
+    pub fn new(num_threads: usize) -> Result<WasmRuntime, WasmError> {
+        crate::init::ensure_init();
+        #[cfg(all(feature = "wasm-threads", target_arch = "wasm32"))]
+        crate::threads::check_started(num_threads)?;
+        if num_threads == 0 {
+            return Ok(WasmRuntime::global());
+        }
+        Ok(WasmRuntime {
+            pool: Arc::new(RwLock::new(Some(build_pool(num_threads)?))),
+            deterministic: Arc::default(),
+            buffers: Arc::default(),
+        })
+    }
+
+    /// The runtime behind every processor constructed with `num_threads`
+    /// threads: the global runtime for 0, otherwise one runtime per count,
+    /// started on first use and kept for the life of the module.
+    ///
+    /// Processors created with the same count therefore share one set of
+    /// workers, and resizing the runtime or making it deterministic affects
+    /// all of them. Call [`WasmRuntime::new`] for a pool of your own.
+    #[wasm_bindgen]
+    pub fn shared(num_threads: usize) -> Result<WasmRuntime, WasmError> {
+        if num_threads == 0 {
+            return WasmRuntime::new(0);
+        }
+        let mut shared = SHARED.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(runtime) = shared.get(&num_threads) {
+            return Ok(runtime.clone());
+        }
+        let runtime = WasmRuntime::new(num_threads)?;
+        shared.insert(num_threads, runtime.clone());
+        Ok(runtime)
+    }
+
+    /// The runtime behind every processor constructed with 0 threads.
+    ///
+    /// It is created on first use and runs work on Rayon's global pool,
+    /// which falls back to the calling thread where WASM threads are
+    /// unavailable, until it is resized.
+    #[wasm_bindgen]
+    pub fn global() -> WasmRuntime {
+        crate::init::ensure_init();
+        GLOBAL
+            .get_or_init(|| WasmRuntime {
+                pool: Arc::new(RwLock::new(None)),
+                deterministic: Arc::default(),
+                buffers: Arc::default(),
+            })
+            .clone()
+    }
+
+    /// Workers available to processors using this runtime
+    #[wasm_bindgen]
+    pub fn num_threads(&self) -> usize {
+        match self.current_pool() {
+            Some(pool) => pool.current_num_threads(),
+            None => rayon::current_num_threads(),
+        }
+    }
+
+    /// Whether both are handles to the same runtime, and so run work on the
+    /// same pool even across resizes
+    #[wasm_bindgen]
+    pub fn shares_pool_with(&self, other: &WasmRuntime) -> bool {
+        Arc::ptr_eq(&self.pool, &other.pool)
+    }
+
+    /// Make floating-point reductions on this runtime bit-for-bit
+    /// reproducible, across runs and whatever the number of threads.
+    ///
+    /// Affects every processor using the runtime; a processor can also be
+    /// made deterministic on its own. Sums, ARIMA fits and
+    /// `image_statistics` are then split into chunks fixed by the input
+    /// length rather than by how Rayon balances the pool, and the partial
+    /// results are combined in a fixed tree order. Integer results, such as
+    /// `text_stats` counts, are reproducible either way. That costs a little
+    /// load balancing and a buffer of one value per 4096 inputs, typically a
+    /// few percent on large inputs.
+    #[wasm_bindgen]
+    pub fn set_deterministic(&self, enabled: bool) {
+        self.deterministic.store(enabled, Ordering::Relaxed);
+    }
+
+    /// Whether [`WasmRuntime::set_deterministic`] is on
+    #[wasm_bindgen(getter)]
+    pub fn deterministic(&self) -> bool {
+        self.deterministic.load(Ordering::Relaxed)
+    }
+
+    /// What the runtime's buffer pool has done, as `{ hits, misses,
+    /// held_bytes, held_buffers }`.
+    ///
+    /// Processors on the runtime take their scratch buffers from the pool
+    /// and give them back when an operation finishes, so an operation run
+    /// again on an image or matrix of a similar size counts hits rather
+    /// than misses. Results returned to JavaScript are never pooled.
+    #[wasm_bindgen]
+    pub fn pool_stats(&self) -> JsValue {
+        let stats = self.buffer_pool_stats();
+        let result = Object::new();
+        for (name, value) in [
+            ("hits", JsValue::from(stats.hits as f64)),
+            ("misses", JsValue::from(stats.misses as f64)),
+            ("held_bytes", JsValue::from(stats.held_bytes)),
+            ("held_buffers", JsValue::from(stats.held_buffers)),
+        ] {
+            // Defining a property on a fresh plain object cannot fail
+            let _ = Reflect::set(&result, &name.into(), &value);
+        }
+        result.into()
+    }
+
+    /// Free every buffer the buffer pool holds, returning the bytes
+    /// released; it refills as operations run
+    #[wasm_bindgen]
+    pub fn trim(&self) -> usize {
+        self.buffers.trim()
+    }
+
+    /// Bytes held by the buffer pool, for [`crate::memory_report`]
+    #[wasm_bindgen]
+    pub fn heap_bytes(&self) -> usize {
+        self.buffer_pool_stats().held_bytes
+    }
+
+    /// Replace the pool with one of `num_threads` workers, or
+    /// [`hardware_concurrency`] of them when `num_threads` is 0.
+    ///
+    /// Every processor using this runtime switches to the new pool.
+    /// Operations already running finish on the old one, whose workers exit
+    /// once the last of them returns. On failure the current pool is kept.
+    #[wasm_bindgen]
+    pub fn resize(&self, num_threads: usize) -> Result<(), WasmError> {
+        let num_threads = match num_threads {
+            0 => hardware_concurrency(),
+            n => n,
+        };
+        let pool = build_pool(num_threads)?;
+        let old = self
+            .pool
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .replace(pool);
+        // Dropped outside the lock; in-flight operations hold their own handle
+        drop(old);
+        Ok(())
+    }
+}
+
+impl WasmRuntime {
+    /// The pool to run the next operation on. Callers keep the handle for
+    /// the whole operation, so a concurrent resize cannot pull it away.
+    pub(crate) fn current_pool(&self) -> Option<Arc<rayon::ThreadPool>> {
+        self.pool
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// The buffer pool's counters, as [`WasmRuntime::pool_stats`] reports
+    /// them
+    pub fn buffer_pool_stats(&self) -> PoolStats {
+        self.buffers.stats()
+    }
+
+    /// Scratch buffers shared by the processors on this runtime
+    pub(crate) fn buffer_pool(&self) -> &Arc<BufferPool> {
+        &self.buffers
+    }
+}
+
+/// Build a pool whose workers are named `wrt<pool>-<index>`, each on a Web
+/// Worker of its own with the `wasm-threads` feature
+fn build_pool(num_threads: usize) -> Result<Arc<rayon::ThreadPool>, WasmError> {
+    let id = NEXT_POOL_ID.fetch_add(1, Ordering::Relaxed);
+    let builder = rayon::ThreadPoolBuilder::new()
+        .num_threads(num_threads)
+        .thread_name(move |index| format!("wrt{id}-{index}"))
+        .panic_handler(report_task_panic);
+    #[cfg(all(feature = "wasm-threads", target_arch = "wasm32"))]
+    let pool = crate::threads::build_pool(builder);
+    #[cfg(not(all(feature = "wasm-threads", target_arch = "wasm32")))]
+    let pool = builder.build();
+    pool.map(Arc::new)
+        .map_err(|e| WasmError::ResourceUnavailable {
+            reason: format!("Failed to create thread pool: {e}"),
+        })
+}
+
+/// Log a panic in a task spawned onto a pool, which has no caller to hand
+/// it to, instead of letting Rayon abort the process
+pub(crate) fn report_task_panic(payload: Box<dyn Any + Send>) {
+    let message = format!("A thread pool task panicked: {}", panic_message(&*payload));
+    #[cfg(target_arch = "wasm32")]
+    web_sys::console::error_1(&message.into());
+    #[cfg(not(target_arch = "wasm32"))]
+    eprintln!("{message}");
+}
+
+/// Logical CPUs available for worker threads, as used when a runtime is
+/// resized to 0.
+///
+/// Under WASM this is `navigator.hardwareConcurrency` from the window or,
+/// inside a Web Worker, the worker's global scope; elsewhere, such as in
+/// Node without a `window`, it is 1. Natively it is the available
+/// parallelism. The result is clamped to `1..=64` to guard against odd
+/// user agent values.
+#[wasm_bindgen]
+pub fn hardware_concurrency() -> usize {
+    let detected = detect_hardware_concurrency().unwrap_or(1);
+    detected.clamp(1, MAX_DETECTED_THREADS)
+}
+
+/// What the host offers for running processors on several threads, as
+/// `{ shared_array_buffer, cross_origin_isolated, hardware_concurrency,
+/// recommended_threads }`.
+///
+/// Threads need `SharedArrayBuffer`, which browsers only provide to pages
+/// served with `Cross-Origin-Opener-Policy: same-origin` and
+/// `Cross-Origin-Embedder-Policy: require-corp`. `recommended_threads` is
+/// [`hardware_concurrency`] when both are available and 1 otherwise; pass
+/// 0 to a processor constructor to get the same choice without checking.
+#[wasm_bindgen]
+pub fn threading_support() -> JsValue {
+    let global = js_sys::global();
+    let shared_array_buffer = Reflect::has(&global, &"SharedArrayBuffer".into()).unwrap_or(false);
+    let cross_origin_isolated = Reflect::get(&global, &"crossOriginIsolated".into())
+        .ok()
+        .and_then(|isolated| isolated.as_bool())
+        .unwrap_or(false);
+    let hardware_concurrency = hardware_concurrency();
+    let recommended_threads = if shared_array_buffer && cross_origin_isolated {
+        hardware_concurrency
+    } else {
+        1
+    };
+
+    let result = Object::new();
+    for (name, value) in [
+        ("shared_array_buffer", JsValue::from(shared_array_buffer)),
+        (
+            "cross_origin_isolated",
+            JsValue::from(cross_origin_isolated),
+        ),
+        ("hardware_concurrency", JsValue::from(hardware_concurrency)),
+        ("recommended_threads", JsValue::from(recommended_threads)),
+    ] {
+        // Defining a property on a fresh plain object cannot fail
+        let _ = Reflect::set(&result, &name.into(), &value);
+    }
+    result.into()
+}
+
+#[cfg(target_arch = "wasm32")]
+fn detect_hardware_concurrency() -> Option<usize> {
+    let global = js_sys::global();
+    // Inside a Web Worker there is no window, only the worker's own scope
+    let count = match global.dyn_ref::<web_sys::Window>() {
+        Some(window) => window.navigator().hardware_concurrency(),
+        None => global
+            .dyn_ref::<web_sys::WorkerGlobalScope>()?
+            .navigator()
+            .hardware_concurrency(),
+    };
+    // A missing or non-numeric value arrives as NaN, which saturates to 0
+    Some(count as usize)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn detect_hardware_concurrency() -> Option<usize> {
+    std::thread::available_parallelism()
+        .ok()
+        .map(|count| count.get())
+}
@@ -0,0 +1,169 @@
+use js_sys::Uint8Array;
+use wasm_bindgen::prelude::*;
+
+use crate::{compression::Codec, hashing::Hasher, WasmError, WasmModule};
+
+/// An incremental transform behind a stream session
+pub(crate) trait StreamTransform {
+    /// Consume the next chunk, returning whatever output it completes
+    fn push(&mut self, chunk: &[u8]) -> Result<Vec<u8>, WasmError>;
+
+    /// Return the output still held back once the input has ended
+    fn finish(self: Box<Self>) -> Result<Vec<u8>, WasmError>;
+}
+
+/// An open stream session: its transform and the output it may still
+/// produce
+pub(crate) struct Session {
+    transform: Box<dyn StreamTransform>,
+    max_output: usize,
+    output_left: usize,
+}
+
+impl Session {
+    /// Feed `chunk` to the transform, returning the output it completes
+    fn push(&mut self, chunk: &[u8]) -> Result<Uint8Array, WasmError> {
+        let output = self.transform.push(chunk)?;
+        self.output_left = self
+            .output_left
+            .checked_sub(output.len())
+            .ok_or_else(|| output_limit(self.max_output))?;
+        Ok(Uint8Array::from(&output[..]))
+    }
+
+    /// End the input, returning the transform's remaining output
+    fn finish(self) -> Result<Uint8Array, WasmError> {
+        let output = self.transform.finish()?;
+        if output.len() > self.output_left {
+            return Err(output_limit(self.max_output));
+        }
+        Ok(Uint8Array::from(&output[..]))
+    }
+}
+
+/// The error for a session whose output would pass `max_output` bytes
+pub(crate) fn output_limit(max_output: usize) -> WasmError {
+    WasmError::invalid(
+        "stream output",
+        format!("exceeds the session's limit of {max_output} bytes"),
+    )
+}
+
+const SUPPORTED: &str = "xor, compress:<format>, decompress:<format>, hash:<algorithm>";
+
+/// Build the transform named by `begin_stream`. Compressors and
+/// decompressors stop as soon as their output passes `max_output`, before
+/// the rest of a chunk is inflated.
+fn parse_transform(name: &str, max_output: usize) -> Result<Box<dyn StreamTransform>, WasmError> {
+    match name.split_once(':') {
+        None if name.eq_ignore_ascii_case("xor") => Ok(Box::new(Xor)),
+        Some((kind, format)) if kind.eq_ignore_ascii_case("compress") => {
+            Ok(Codec::parse(format)?.encoder(max_output))
+        }
+        Some((kind, format)) if kind.eq_ignore_ascii_case("decompress") => {
+            Ok(Codec::parse(format)?.decoder(max_output))
+        }
+        Some((kind, algorithm)) if kind.eq_ignore_ascii_case("hash") => {
+            Ok(Box::new(Hasher::parse(algorithm)?))
+        }
+        _ => Err(WasmError::unsupported("stream transform", name, SUPPORTED)),
+    }
+}
+
+/// The byte-wise XOR with `0xAA` from `process_data`. Its reversal needs the
+/// whole input, so streams skip it.
+struct Xor;
+
+impl StreamTransform for Xor {
+    fn push(&mut self, chunk: &[u8]) -> Result<Vec<u8>, WasmError> {
+        Ok(chunk.iter().map(|byte| byte ^ 0xAA).collect())
+    }
+
+    fn finish(self: Box<Self>) -> Result<Vec<u8>, WasmError> {
+        Ok(Vec::new())
+    }
+}
+
+/// Chunk-by-chunk processing for inputs too large to hold at once, such as
+/// a `ReadableStream` from `fetch`.
+///
+/// `begin_stream` opens a session running one transform: `"xor"`,
+/// `"compress:<format>"` or `"decompress:<format>"` with a format accepted
+/// by `compress`, or `"hash:<algorithm>"` with an algorithm accepted by
+/// `hash`, which yields the raw digest bytes. `stream_push` returns the
+/// output each chunk completes, and `stream_finish` returns the rest and
+/// closes the session. A session that fails is closed, and `stream_abort`
+/// discards one early. A truncated gzip stream fails its trailer check on
+/// finish, but raw deflate and zlib data that stops early is not detected.
+#[wasm_bindgen]
+impl WasmModule {
+    /// Open a session running `transform`, returning its handle.
+    ///
+    /// The session fails once its output would exceed `max_output` bytes
+    /// in total, which guards a decompressing session against compression
+    /// bombs as `decompress`'s `max_size` does.
+    #[wasm_bindgen]
+    pub fn begin_stream(&mut self, transform: &str, max_output: usize) -> Result<u32, WasmError> {
+        let transform = parse_transform(transform, max_output)?;
+        // Handles start at 1 so that 0 is never valid
+        self.next_stream_handle = self.next_stream_handle.wrapping_add(1).max(1);
+        self.streams.insert(
+            self.next_stream_handle,
+            Session {
+                transform,
+                max_output,
+                output_left: max_output,
+            },
+        );
+        Ok(self.next_stream_handle)
+    }
+
+    /// Feed the next chunk to a session, returning any output produced
+    #[wasm_bindgen]
+    pub fn stream_push(
+        &mut self,
+        session: u32,
+        chunk: &Uint8Array,
+    ) -> Result<Uint8Array, WasmError> {
+        let output = self
+            .streams
+            .get_mut(&session)
+            .ok_or_else(|| closed(session))?
+            .push(&chunk.to_vec());
+        if output.is_err() {
+            self.streams.remove(&session);
+        }
+        output
+    }
+
+    /// End a session's input, returning its remaining output and closing it
+    #[wasm_bindgen]
+    pub fn stream_finish(&mut self, session: u32) -> Result<Uint8Array, WasmError> {
+        self.streams
+            .remove(&session)
+            .ok_or_else(|| closed(session))?
+            .finish()
+    }
+
+    /// Close a session without finishing it, discarding its state
+    #[wasm_bindgen]
+    pub fn stream_abort(&mut self, session: u32) -> Result<(), WasmError> {
+        self.streams
+            .remove(&session)
+            .map(drop)
+            .ok_or_else(|| closed(session))
+    }
+
+    /// Number of open stream sessions
+    #[wasm_bindgen(getter)]
+    pub fn open_streams(&self) -> usize {
+        self.streams.len()
+    }
+}
+
+fn closed(session: u32) -> WasmError {
+    WasmError::invalid(
+        "stream session",
+        format!("{session} is not open; it may already have been finished or aborted"),
+    )
+}
@@ -0,0 +1,158 @@
+use std::collections::HashMap;
+
+use js_sys::{Object, Reflect, Uint8Array};
+use rayon::prelude::*;
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+use crate::{install, parallel::tokenize, WasmError, WasmModule};
+
+/// Bytes of text per task in [`WasmModule::text_stats`], extended to the
+/// end of the line
+const TEXT_CHUNK: usize = 64 * 1024;
+
+/// Words reported by [`WasmModule::text_stats`]
+const TOP_WORDS: usize = 20;
+
+#[derive(Serialize)]
+struct TextStats {
+    bytes: usize,
+    chars: usize,
+    lines: usize,
+    words: usize,
+    top_words: Vec<WordCount>,
+}
+
+#[derive(Serialize)]
+struct WordCount {
+    word: String,
+    count: usize,
+}
+
+/// Counts for one run of whole lines
+#[derive(Default)]
+struct ChunkStats {
+    chars: usize,
+    newlines: usize,
+    words: usize,
+    frequencies: HashMap<String, usize>,
+}
+
+impl ChunkStats {
+    fn of(text: &str) -> ChunkStats {
+        let mut frequencies = HashMap::new();
+        for token in tokenize(text) {
+            *frequencies.entry(token).or_insert(0) += 1;
+        }
+        ChunkStats {
+            chars: text.chars().count(),
+            newlines: text.bytes().filter(|&byte| byte == b'\n').count(),
+            words: text.split_whitespace().count(),
+            frequencies,
+        }
+    }
+
+    fn merge(mut self, other: ChunkStats) -> ChunkStats {
+        self.chars += other.chars;
+        self.newlines += other.newlines;
+        self.words += other.words;
+        for (word, count) in other.frequencies {
+            *self.frequencies.entry(word).or_insert(0) += count;
+        }
+        self
+    }
+}
+
+/// Checks and statistics for text uploaded as bytes, such as a log file
+#[wasm_bindgen]
+impl WasmModule {
+    /// Whether `input` is valid UTF-8, as `{ valid }`, plus `error_offset`,
+    /// the byte offset of the first invalid or truncated sequence, when it
+    /// is not
+    #[wasm_bindgen]
+    pub fn validate_utf8(&self, input: &Uint8Array) -> JsValue {
+        let result = Object::new();
+        let error = std::str::from_utf8(&input.to_vec()).err();
+        // Defining properties on a fresh plain object cannot fail
+        let _ = Reflect::set(&result, &"valid".into(), &error.is_none().into());
+        if let Some(error) = error {
+            let offset = error.valid_up_to() as f64;
+            let _ = Reflect::set(&result, &"error_offset".into(), &offset.into());
+        }
+        result.into()
+    }
+
+    /// Count the bytes, characters, lines and words of UTF-8 `input`, and
+    /// find its 20 most frequent words.
+    ///
+    /// Returns `{ bytes, chars, lines, words, top_words }`. A final line
+    /// without a newline still counts, and `words` counts whitespace-separated
+    /// runs like `wc -w`. `top_words` holds `{ word, count }` objects, most
+    /// frequent first and ties alphabetical, over the lowercased tokens of
+    /// `WasmParallelProcessor.parallel_tokenize`. Runs of whole lines are
+    /// counted in parallel, on the module's runtime (see
+    /// [`WasmModule::with_runtime`]). Invalid UTF-8 is an `INVALID_ENCODING` error at
+    /// the offending byte.
+    #[wasm_bindgen]
+    pub fn text_stats(&self, input: &Uint8Array) -> Result<JsValue, WasmError> {
+        let bytes = input.to_vec();
+        let text = std::str::from_utf8(&bytes).map_err(|e| WasmError::InvalidEncoding {
+            encoding: "UTF-8".to_string(),
+            offset: e.valid_up_to(),
+            reason: match e.error_len() {
+                Some(_) => "invalid byte sequence",
+                None => "the text ends inside a character",
+            }
+            .to_string(),
+        })?;
+
+        let stats = install(&self.runtime, || {
+            line_chunks(text)
+                .par_iter()
+                .map(|chunk| ChunkStats::of(chunk))
+                .reduce(ChunkStats::default, ChunkStats::merge)
+        })?;
+
+        let mut top_words: Vec<WordCount> = stats
+            .frequencies
+            .into_iter()
+            .map(|(word, count)| WordCount { word, count })
+            .collect();
+        top_words.sort_unstable_by(|a, b| b.count.cmp(&a.count).then_with(|| a.word.cmp(&b.word)));
+        top_words.truncate(TOP_WORDS);
+
+        let unterminated = !text.is_empty() && !text.ends_with('\n');
+        let stats = TextStats {
+            bytes: text.len(),
+            chars: stats.chars,
+            lines: stats.newlines + unterminated as usize,
+            words: stats.words,
+            top_words,
+        };
+        Ok(stats
+            .serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+            .map_err(JsValue::from)?)
+    }
+}
+
+/// Split `text` into runs of about [`TEXT_CHUNK`] bytes, each ending at a
+/// newline or the end of the text
+fn line_chunks(text: &str) -> Vec<&str> {
+    let mut chunks = Vec::new();
+    let mut rest = text;
+    while !rest.is_empty() {
+        let start = TEXT_CHUNK.min(rest.len());
+        // A newline byte always ends a character, so splitting after it is safe
+        let end = match rest.as_bytes()[start..]
+            .iter()
+            .position(|&byte| byte == b'\n')
+        {
+            Some(newline) => start + newline + 1,
+            None => rest.len(),
+        };
+        let (chunk, tail) = rest.split_at(end);
+        chunks.push(chunk);
+        rest = tail;
+    }
+    chunks
+}
@@ -0,0 +1,148 @@
+use std::collections::{HashMap, HashSet};
+
+use js_sys::Array;
+use rayon::prelude::*;
+use wasm_bindgen::prelude::*;
+
+use crate::WasmError;
+
+/// TF-IDF vectoriser over a vocabulary of the most common terms.
+///
+/// Text is lowercased and split into terms at every character that is not
+/// alphanumeric. `fit` keeps the `max_features` terms found in the most
+/// documents, breaking ties alphabetically, and `transform` weights each
+/// document's term frequencies by their smoothed inverse document frequency
+/// `ln((1 + n) / (1 + df)) + 1`.
+#[wasm_bindgen]
+#[derive(Debug)]
+pub struct WasmTFIDF {
+    max_features: usize,
+    fitted: bool,
+    /// Column of each vocabulary term
+    columns: HashMap<String, usize>,
+    /// Vocabulary in column order
+    vocabulary: Vec<String>,
+    /// Inverse document frequency per column
+    idf: Vec<f32>,
+}
+
+#[wasm_bindgen]
+impl WasmTFIDF {
+    /// A vectoriser that keeps at most `max_features` terms
+    #[wasm_bindgen(constructor)]
+    pub fn new(max_features: usize) -> Result<WasmTFIDF, WasmError> {
+        crate::init::ensure_init();
+        if max_features == 0 {
+            return Err(WasmError::invalid("max features", "must be positive"));
+        }
+        Ok(WasmTFIDF {
+            max_features,
+            fitted: false,
+            columns: HashMap::new(),
+            vocabulary: Vec::new(),
+            idf: Vec::new(),
+        })
+    }
+
+    /// Learn the vocabulary and document frequencies from an array of
+    /// strings, replacing any earlier fit
+    #[wasm_bindgen]
+    pub fn fit(&mut self, documents: &Array) -> Result<(), WasmError> {
+        let documents = read_documents(documents)?;
+        let frequencies = documents
+            .par_iter()
+            .fold(HashMap::new, |mut frequencies, document| {
+                for term in tokenize(document).collect::<HashSet<String>>() {
+                    *frequencies.entry(term).or_insert(0) += 1;
+                }
+                frequencies
+            })
+            .reduce(HashMap::new, |mut total, frequencies| {
+                for (term, df) in frequencies {
+                    *total.entry(term).or_insert(0) += df;
+                }
+                total
+            });
+
+        let mut ranked: Vec<(String, usize)> = frequencies.into_iter().collect();
+        ranked.sort_unstable_by(|(a, a_df), (b, b_df)| b_df.cmp(a_df).then_with(|| a.cmp(b)));
+        ranked.truncate(self.max_features);
+
+        let n = documents.len() as f32;
+        self.idf = ranked
+            .iter()
+            .map(|&(_, df)| ((1.0 + n) / (1.0 + df as f32)).ln() + 1.0)
+            .collect();
+        self.vocabulary = ranked.into_iter().map(|(term, _)| term).collect();
+        self.columns = self.vocabulary.iter().cloned().zip(0..).collect();
+        self.fitted = true;
+        Ok(())
+    }
+
+    /// Vectorise an array of strings into a row-major matrix with one row
+    /// per document and [`WasmTFIDF::vocabulary_size`] columns.
+    ///
+    /// Term frequency is a term's count divided by the document's total
+    /// term count, so out-of-vocabulary terms still dilute the weights.
+    #[wasm_bindgen]
+    pub fn transform(&self, documents: &Array) -> Result<Vec<f32>, WasmError> {
+        if !self.fitted {
+            return Err(WasmError::NotInitialized);
+        }
+        let documents = read_documents(documents)?;
+        let width = self.vocabulary.len();
+        if width == 0 {
+            return Ok(Vec::new());
+        }
+
+        let mut matrix = vec![0.0; documents.len() * width];
+        matrix
+            .par_chunks_mut(width)
+            .zip(documents.par_iter())
+            .for_each(|(row, document)| {
+                let mut total = 0usize;
+                for term in tokenize(document) {
+                    total += 1;
+                    if let Some(&column) = self.columns.get(&term) {
+                        row[column] += 1.0;
+                    }
+                }
+                for (weight, idf) in row.iter_mut().zip(&self.idf) {
+                    *weight *= idf / total.max(1) as f32;
+                }
+            });
+        Ok(matrix)
+    }
+
+    /// The fitted vocabulary in column order
+    #[wasm_bindgen(getter)]
+    pub fn vocabulary(&self) -> Vec<String> {
+        self.vocabulary.clone()
+    }
+
+    /// Number of columns [`WasmTFIDF::transform`] produces
+    #[wasm_bindgen(getter)]
+    pub fn vocabulary_size(&self) -> usize {
+        self.vocabulary.len()
+    }
+}
+
+/// Copy the entries out of an array that must hold only strings
+fn read_documents(documents: &Array) -> Result<Vec<String>, WasmError> {
+    documents
+        .iter()
+        .enumerate()
+        .map(|(i, document)| {
+            document
+                .as_string()
+                .ok_or_else(|| WasmError::invalid(format!("document {i}"), "must be a string"))
+        })
+        .collect()
+}
+
+/// Lowercased runs of alphanumeric characters
+fn tokenize(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|term| !term.is_empty())
+        .map(str::to_lowercase)
+}
@@ -0,0 +1,45 @@
+// Web Worker side of the `wasm-threads` feature (see threads.rs), bundled by
+// wasm-bindgen as a snippet. The page and every worker load this file: the
+// page starts one worker per Rayon thread, and each worker instantiates the
+// package on the shared memory and then runs its thread.
+
+// Keep the workers referenced so they are not garbage collected
+const workers = [];
+
+function waitForMessage(target, type) {
+    return new Promise((resolve, reject) => {
+        target.addEventListener('message', function onMessage({ data }) {
+            if (data && data.type === type) {
+                target.removeEventListener('message', onMessage);
+                resolve(data);
+            }
+        });
+        target.addEventListener('error', reject, { once: true });
+    });
+}
+
+if (typeof WorkerGlobalScope !== 'undefined' && self instanceof WorkerGlobalScope) {
+    waitForMessage(self, 'wasm_threads_init').then(async ({ module, memory, thread }) => {
+        // Snippets live in <package>/snippets/<crate>/src/, so the package
+        // entry point is three directories up; this needs a bundler, as with
+        // wasm-bindgen-rayon
+        const pkg = await import('../../..');
+        await pkg.default({ module_or_path: module, memory });
+        postMessage({ type: 'wasm_threads_ready' });
+        pkg.runWorkerThread(thread);
+        close();
+    });
+}
+
+export function spawnWorker(module, memory, thread) {
+    const worker = new Worker(new URL('./threads.js', import.meta.url), { type: 'module' });
+    workers.push(worker);
+    worker.postMessage({ type: 'wasm_threads_init', module, memory, thread });
+    return waitForMessage(worker, 'wasm_threads_ready').catch((error) => {
+        // The worker never ran its thread; drop it so a retry can hand the
+        // thread to a new one
+        worker.terminate();
+        workers.splice(workers.indexOf(worker), 1);
+        throw error;
+    });
+}
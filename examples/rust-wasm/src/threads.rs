@@ -0,0 +1,229 @@
+//! Rayon threads on Web Workers, for builds with the `wasm-threads` feature.
+//!
+//! Rayon cannot spawn threads on `wasm32-unknown-unknown` by itself, so its
+//! pools are built with a spawn handler that starts a Web Worker per thread.
+//! Each worker instantiates this module on the same shared memory and then
+//! runs the thread it was handed, in the way wasm-bindgen-rayon does. The
+//! module must be built with `-C target-feature=+atomics,+bulk-memory` (and
+//! `-Z build-std`) so that its memory can be shared.
+//!
+//! Rayon blocks the calling thread until parallel work finishes, which the
+//! browser forbids on the main thread, so processors should be used from a
+//! worker of the page's own.
+
+use std::{
+    mem,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex, PoisonError,
+    },
+};
+
+use js_sys::{Promise, Reflect, SharedArrayBuffer, WebAssembly};
+use rayon::{ThreadBuilder, ThreadPoolBuilder};
+use wasm_bindgen::{prelude::*, JsCast};
+use wasm_bindgen_futures::{future_to_promise, JsFuture};
+
+use crate::{hardware_concurrency, runtime::report_task_panic, WasmError};
+
+/// Progress of `initThreadPool`. Only the page's thread takes the lock,
+/// so it is never contended and never blocks.
+static INIT: Mutex<Init> = Mutex::new(Init::Idle);
+
+/// Set once every worker of the global pool is running its thread
+static STARTED: AtomicBool = AtomicBool::new(false);
+
+enum Init {
+    /// Not called yet, or building the global pool failed
+    Idle,
+    /// Waiting for the workers to start
+    Starting,
+    /// The global pool exists, but the workers for these threads failed to
+    /// start and were dropped; a retry hands the threads to new workers
+    Failed(Vec<usize>),
+    Started,
+}
+
+#[wasm_bindgen(module = "/src/threads.js")]
+extern "C" {
+    /// Start a worker that runs the boxed `ThreadBuilder` at `thread`; the
+    /// promise resolves once the worker has instantiated the module
+    #[wasm_bindgen(js_name = spawnWorker)]
+    fn spawn_worker(module: JsValue, memory: JsValue, thread: usize) -> Promise;
+}
+
+/// Start `num_threads` Web Workers, or [`hardware_concurrency`] of them
+/// when `num_threads` is 0, as Rayon's global pool.
+///
+/// Await the promise before constructing any processor. It rejects with
+/// `RESOURCE_UNAVAILABLE` if the page cannot share memory with workers or
+/// a worker fails to start, and it may be called again after a rejection.
+/// Once the global pool is built its size is fixed, so a retry only starts
+/// new workers for the threads whose workers failed.
+#[wasm_bindgen(js_name = initThreadPool)]
+pub fn init_thread_pool(num_threads: usize) -> Promise {
+    if let Err(error) = check_shared_memory() {
+        return Promise::reject(&error.into());
+    }
+    let mut init = INIT.lock().unwrap_or_else(PoisonError::into_inner);
+    let threads = match mem::replace(&mut *init, Init::Starting) {
+        Init::Idle => match build_global_pool(num_threads) {
+            Ok(threads) => threads,
+            Err(error) => {
+                *init = Init::Idle;
+                return Promise::reject(&error.into());
+            }
+        },
+        Init::Failed(threads) => threads,
+        state @ (Init::Starting | Init::Started) => {
+            *init = state;
+            let error = WasmError::invalid(
+                "initThreadPool",
+                "the thread pool is already started or starting",
+            );
+            return Promise::reject(&error.into());
+        }
+    };
+    drop(init);
+
+    let total = threads.len();
+    let ready: Vec<_> = threads
+        .into_iter()
+        .map(|thread| (thread, JsFuture::from(spawn(thread))))
+        .collect();
+    future_to_promise(async move {
+        let mut failed = Vec::new();
+        let mut first_error = None;
+        for (thread, worker) in ready {
+            if let Err(error) = worker.await {
+                failed.push(thread);
+                first_error.get_or_insert(error);
+            }
+        }
+
+        let mut init = INIT.lock().unwrap_or_else(PoisonError::into_inner);
+        let Some(error) = first_error else {
+            *init = Init::Started;
+            STARTED.store(true, Ordering::Release);
+            return Ok(JsValue::UNDEFINED);
+        };
+        let reason = format!(
+            "{} of {total} Web Workers failed to start ({}); call initThreadPool() again to retry",
+            failed.len(),
+            describe(&error)
+        );
+        *init = Init::Failed(failed);
+        Err(WasmError::ResourceUnavailable { reason }.into())
+    })
+}
+
+/// Build Rayon's global pool, returning its threads for workers to run
+fn build_global_pool(num_threads: usize) -> Result<Vec<usize>, WasmError> {
+    let num_threads = match num_threads {
+        0 => hardware_concurrency(),
+        n => n,
+    };
+    let mut threads = Vec::with_capacity(num_threads);
+    ThreadPoolBuilder::new()
+        .num_threads(num_threads)
+        .panic_handler(report_task_panic)
+        .spawn_handler(|thread| {
+            threads.push(Box::into_raw(Box::new(thread)) as usize);
+            Ok(())
+        })
+        .build_global()
+        .map_err(|e| WasmError::ResourceUnavailable {
+            reason: format!("Failed to start the Web Worker thread pool: {e}"),
+        })?;
+    Ok(threads)
+}
+
+/// The message of a worker's error event, or the value itself
+fn describe(error: &JsValue) -> String {
+    Reflect::get(error, &"message".into())
+        .ok()
+        .and_then(|message| message.as_string())
+        .unwrap_or_else(|| format!("{error:?}"))
+}
+
+/// Fail unless `initThreadPool` has finished starting the workers.
+///
+/// A runtime of 0 threads is best effort: where workers cannot share this
+/// module's memory it runs on the calling thread, as Rayon's global pool
+/// falls back to it when threads cannot be spawned.
+pub(crate) fn check_started(num_threads: usize) -> Result<(), WasmError> {
+    if STARTED.load(Ordering::Acquire) {
+        return Ok(());
+    }
+    match check_shared_memory() {
+        Err(_) if num_threads == 0 => Ok(()),
+        Err(error) => Err(error),
+        Ok(()) => Err(WasmError::ResourceUnavailable {
+            reason: "The Web Worker thread pool is not running: call and await \
+                     initThreadPool() first"
+                .to_string(),
+        }),
+    }
+}
+
+/// Whether `initThreadPool` has finished starting the workers
+pub(crate) fn started() -> bool {
+    STARTED.load(Ordering::Acquire)
+}
+
+/// Build a pool whose threads each run on a new Web Worker.
+///
+/// Unlike `initThreadPool` this does not wait for the workers to start;
+/// work sent to the pool meanwhile waits in its queue.
+pub(crate) fn build_pool(
+    builder: ThreadPoolBuilder,
+) -> Result<rayon::ThreadPool, rayon::ThreadPoolBuildError> {
+    builder
+        .spawn_handler(|thread| {
+            // The worker reports its own failures; nothing waits on it here
+            let _ = spawn(Box::into_raw(Box::new(thread)) as usize);
+            Ok(())
+        })
+        .build()
+}
+
+/// Start a worker for the boxed `ThreadBuilder` at `thread`, returning the
+/// promise that it is ready
+fn spawn(thread: usize) -> Promise {
+    spawn_worker(wasm_bindgen::module(), wasm_bindgen::memory(), thread)
+}
+
+/// Workers need `SharedArrayBuffer`, which browsers only provide to
+/// cross-origin isolated pages, and a module built with shared memory
+fn check_shared_memory() -> Result<(), WasmError> {
+    let available = Reflect::has(&js_sys::global(), &"SharedArrayBuffer".into()).unwrap_or(false);
+    if !available {
+        return Err(WasmError::ResourceUnavailable {
+            reason: "SharedArrayBuffer is unavailable, so Web Workers cannot share this module's \
+                     memory. Serve the page cross-origin isolated, with the headers \
+                     `Cross-Origin-Opener-Policy: same-origin` and \
+                     `Cross-Origin-Embedder-Policy: require-corp`"
+                .to_string(),
+        });
+    }
+    let memory: WebAssembly::Memory = wasm_bindgen::memory().unchecked_into();
+    if !memory.buffer().is_instance_of::<SharedArrayBuffer>() {
+        return Err(WasmError::ResourceUnavailable {
+            reason: "This module's memory is not shared: build it with \
+                     `-C target-feature=+atomics,+bulk-memory` and `-Z build-std=panic_abort,std`"
+                .to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Run the Rayon thread handed to this worker until its pool shuts down
+#[doc(hidden)]
+#[wasm_bindgen(js_name = runWorkerThread)]
+pub fn run_worker_thread(thread: usize) {
+    // SAFETY: each box is leaked for one worker at a time, which calls this
+    // once with the address it was given. A worker that fails before then
+    // is terminated before its thread is handed to another.
+    let thread = unsafe { Box::from_raw(thread as *mut ThreadBuilder) };
+    thread.run();
+}
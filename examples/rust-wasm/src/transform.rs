@@ -0,0 +1,301 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    parallel::{read_varint, write_varint},
+    WasmError, WasmModule,
+};
+
+/// A named byte transform run by [`WasmModule::process_data`]
+pub(crate) trait ByteTransform {
+    fn apply(&self, data: &mut Vec<u8>, params: &TransformParams) -> Result<(), WasmError>;
+}
+
+/// Options passed to a transform as a JavaScript object; each transform
+/// reads only the ones it documents
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct TransformParams {
+    /// Byte XORed into the data by `"xor"`
+    key: Option<u8>,
+    /// Rotation applied to each byte by `"rot_n"`
+    n: Option<u8>,
+}
+
+impl TransformParams {
+    /// Read `params`, treating `undefined` and `null` as no options
+    pub(crate) fn from_js(params: &JsValue) -> Result<TransformParams, WasmError> {
+        if params.is_undefined() || params.is_null() {
+            return Ok(TransformParams::default());
+        }
+        // Going through a JSON value lets serde see, and reject, unknown keys
+        serde_wasm_bindgen::from_value(params.clone())
+            .and_then(|value: serde_json::Value| {
+                TransformParams::deserialize(value).map_err(serde::de::Error::custom)
+            })
+            .map_err(|e| WasmError::invalid("transform params", e.to_string()))
+    }
+
+    /// The options given, for telling cached results apart
+    pub(crate) fn tag(&self) -> String {
+        let mut tag = String::new();
+        if let Some(key) = self.key {
+            tag += &format!(":key={key}");
+        }
+        if let Some(n) = self.n {
+            tag += &format!(":n={n}");
+        }
+        tag
+    }
+}
+
+/// Add every built-in transform to `registry` under its name
+pub(crate) fn register_builtin_transforms(registry: &mut HashMap<String, Box<dyn ByteTransform>>) {
+    let builtins: [(&str, Box<dyn ByteTransform>); 11] = [
+        ("reverse", Box::new(Reverse)),
+        ("xor", Box::new(Xor)),
+        ("rot_n", Box::new(RotN)),
+        ("rle", Box::new(Rle)),
+        ("rle_decode", Box::new(RleDecode)),
+        ("delta_encode", Box::new(DeltaEncode)),
+        ("delta_decode", Box::new(DeltaDecode)),
+        ("delta_encode_u32", Box::new(DeltaEncodeU32)),
+        ("delta_decode_u32", Box::new(DeltaDecodeU32)),
+        ("varint_encode_u64", Box::new(VarintEncodeU64)),
+        ("varint_decode_u64", Box::new(VarintDecodeU64)),
+    ];
+    for (name, transform) in builtins {
+        registry.insert(name.to_string(), transform);
+    }
+}
+
+#[wasm_bindgen]
+impl WasmModule {
+    /// Names accepted by `process_data`, in sorted order
+    #[wasm_bindgen]
+    pub fn list_transforms(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.transforms.keys().cloned().collect();
+        names.sort();
+        names
+    }
+}
+
+impl WasmModule {
+    /// Run the registered transform `name` over `data`
+    pub(crate) fn apply_transform(
+        &self,
+        name: &str,
+        data: &mut Vec<u8>,
+        params: &TransformParams,
+    ) -> Result<(), WasmError> {
+        match self.transforms.get(name) {
+            Some(transform) => transform.apply(data, params),
+            None => Err(WasmError::unsupported(
+                "transform",
+                name,
+                &self.list_transforms().join(", "),
+            )),
+        }
+    }
+}
+
+/// Reverse the byte order
+struct Reverse;
+
+impl ByteTransform for Reverse {
+    fn apply(&self, data: &mut Vec<u8>, _: &TransformParams) -> Result<(), WasmError> {
+        data.reverse();
+        Ok(())
+    }
+}
+
+/// XOR every byte with `key`, `0xAA` by default; its own inverse
+struct Xor;
+
+impl ByteTransform for Xor {
+    fn apply(&self, data: &mut Vec<u8>, params: &TransformParams) -> Result<(), WasmError> {
+        let key = params.key.unwrap_or(0xAA);
+        for byte in data.iter_mut() {
+            *byte ^= key;
+        }
+        Ok(())
+    }
+}
+
+/// Add `n` to every byte, wrapping; rotating by `256 - n` undoes it
+struct RotN;
+
+impl ByteTransform for RotN {
+    fn apply(&self, data: &mut Vec<u8>, params: &TransformParams) -> Result<(), WasmError> {
+        let n = params
+            .n
+            .ok_or_else(|| WasmError::invalid("rot_n params", "'n' is required"))?;
+        for byte in data.iter_mut() {
+            *byte = byte.wrapping_add(n);
+        }
+        Ok(())
+    }
+}
+
+/// Run-length encode as `(count, byte)` pairs, splitting runs longer than 255
+struct Rle;
+
+impl ByteTransform for Rle {
+    fn apply(&self, data: &mut Vec<u8>, _: &TransformParams) -> Result<(), WasmError> {
+        let mut encoded = Vec::new();
+        let mut rest = &data[..];
+        while let Some(&byte) = rest.first() {
+            let run = rest.iter().take(255).take_while(|&&b| b == byte).count();
+            encoded.extend_from_slice(&[run as u8, byte]);
+            rest = &rest[run..];
+        }
+        *data = encoded;
+        Ok(())
+    }
+}
+
+/// Expand the pairs written by `"rle"`
+struct RleDecode;
+
+impl ByteTransform for RleDecode {
+    fn apply(&self, data: &mut Vec<u8>, _: &TransformParams) -> Result<(), WasmError> {
+        let invalid = |offset, reason: &str| WasmError::InvalidEncoding {
+            encoding: "RLE".to_string(),
+            offset,
+            reason: reason.to_string(),
+        };
+        if data.len() % 2 != 0 {
+            return Err(invalid(
+                data.len(),
+                "the data ends inside a (count, byte) pair",
+            ));
+        }
+        let mut decoded = Vec::with_capacity(data.len());
+        for (i, pair) in data.chunks_exact(2).enumerate() {
+            if pair[0] == 0 {
+                return Err(invalid(2 * i, "runs cannot be empty"));
+            }
+            decoded.resize(decoded.len() + pair[0] as usize, pair[1]);
+        }
+        *data = decoded;
+        Ok(())
+    }
+}
+
+/// Replace each byte after the first with its wrapping difference from the
+/// one before
+struct DeltaEncode;
+
+impl ByteTransform for DeltaEncode {
+    fn apply(&self, data: &mut Vec<u8>, _: &TransformParams) -> Result<(), WasmError> {
+        for i in (1..data.len()).rev() {
+            data[i] = data[i].wrapping_sub(data[i - 1]);
+        }
+        Ok(())
+    }
+}
+
+/// Invert `"delta_encode"` with a running sum
+struct DeltaDecode;
+
+impl ByteTransform for DeltaDecode {
+    fn apply(&self, data: &mut Vec<u8>, _: &TransformParams) -> Result<(), WasmError> {
+        for i in 1..data.len() {
+            data[i] = data[i].wrapping_add(data[i - 1]);
+        }
+        Ok(())
+    }
+}
+
+/// Like `"delta_encode"` over little-endian `u32`s
+struct DeltaEncodeU32;
+
+impl ByteTransform for DeltaEncodeU32 {
+    fn apply(&self, data: &mut Vec<u8>, _: &TransformParams) -> Result<(), WasmError> {
+        check_words(data, 4, "delta_encode_u32")?;
+        let mut previous = 0u32;
+        for chunk in data.chunks_exact_mut(4) {
+            let value = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+            chunk.copy_from_slice(&value.wrapping_sub(previous).to_le_bytes());
+            previous = value;
+        }
+        Ok(())
+    }
+}
+
+/// Invert `"delta_encode_u32"`
+struct DeltaDecodeU32;
+
+impl ByteTransform for DeltaDecodeU32 {
+    fn apply(&self, data: &mut Vec<u8>, _: &TransformParams) -> Result<(), WasmError> {
+        check_words(data, 4, "delta_decode_u32")?;
+        let mut total = 0u32;
+        for chunk in data.chunks_exact_mut(4) {
+            let delta = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+            total = total.wrapping_add(delta);
+            chunk.copy_from_slice(&total.to_le_bytes());
+        }
+        Ok(())
+    }
+}
+
+/// Rewrite little-endian `u64`s as LEB128 varints, so small values take
+/// fewer bytes
+struct VarintEncodeU64;
+
+impl ByteTransform for VarintEncodeU64 {
+    fn apply(&self, data: &mut Vec<u8>, _: &TransformParams) -> Result<(), WasmError> {
+        check_words(data, 8, "varint_encode_u64")?;
+        let mut encoded = Vec::with_capacity(data.len() / 4);
+        for chunk in data.chunks_exact(8) {
+            let value = u64::from_le_bytes(chunk.try_into().expect("chunks are 8 bytes"));
+            write_varint(&mut encoded, value);
+        }
+        *data = encoded;
+        Ok(())
+    }
+}
+
+/// Invert `"varint_encode_u64"`, rejecting a varint that is cut short or
+/// runs past 64 bits
+struct VarintDecodeU64;
+
+impl ByteTransform for VarintDecodeU64 {
+    fn apply(&self, data: &mut Vec<u8>, _: &TransformParams) -> Result<(), WasmError> {
+        let mut decoded = Vec::with_capacity(data.len() * 8);
+        let mut pos = 0;
+        while pos < data.len() {
+            let Some((value, len)) = read_varint(&data[pos..]) else {
+                let rest = &data[pos..];
+                let truncated = rest.len() < 10 && rest.iter().all(|byte| byte & 0x80 != 0);
+                return Err(WasmError::InvalidEncoding {
+                    encoding: "LEB128".to_string(),
+                    offset: pos,
+                    reason: if truncated {
+                        "the varint is cut off by the end of the data"
+                    } else {
+                        "the varint runs past 64 bits"
+                    }
+                    .to_string(),
+                });
+            };
+            decoded.extend_from_slice(&value.to_le_bytes());
+            pos += len;
+        }
+        *data = decoded;
+        Ok(())
+    }
+}
+
+/// Fail unless `data` splits evenly into `width`-byte values
+fn check_words(data: &[u8], width: usize, transform: &str) -> Result<(), WasmError> {
+    if data.len() % width != 0 {
+        return Err(WasmError::invalid(
+            format!("{transform} input"),
+            format!("length {} is not a multiple of {width}", data.len()),
+        ));
+    }
+    Ok(())
+}
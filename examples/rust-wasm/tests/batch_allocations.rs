@@ -0,0 +1,105 @@
+//! Allocation regression checks for the batch processor, using a counting
+//! global allocator; run natively with `cargo test`.
+#![cfg(not(target_arch = "wasm32"))]
+
+mod common;
+
+use common::{allocations_during, serial};
+use web_learning_rust_examples::{BatchOp, WasmBatchProcessor};
+
+#[test]
+fn process_batch_into_does_not_allocate() {
+    let _serial = serial();
+    let mut processor = WasmBatchProcessor::new(0, 0).unwrap();
+
+    // Cover both the sequential path and the parallel path
+    for len in [100, 1_000_000] {
+        let data: Vec<f64> = (0..len).map(|i| i as f64 * 0.001).collect();
+        let mut out = vec![0.0; len];
+
+        // Warm up the thread pool before counting
+        processor
+            .process_batch_into(&data, BatchOp::Sqrt, false, &mut out)
+            .unwrap();
+
+        let allocations = allocations_during(|| {
+            for _ in 0..10 {
+                processor
+                    .process_batch_into(&data, BatchOp::Sin, false, &mut out)
+                    .unwrap();
+            }
+        });
+        assert_eq!(allocations, 0, "{len} elements");
+        assert_eq!(out[len - 1], data[len - 1].sin());
+
+        // The cloning variant allocates a fresh vector for every call
+        let allocations = allocations_during(|| {
+            for _ in 0..10 {
+                drop(processor.process_batch(&data, BatchOp::Sin, false).unwrap());
+            }
+        });
+        assert!(allocations >= 10, "{len} elements: {allocations}");
+    }
+}
+
+#[test]
+fn preallocated_processor_does_not_grow() {
+    let _serial = serial();
+    let (batch_size, n_batches) = (10_000, 8);
+    let mut processor =
+        WasmBatchProcessor::with_preallocated_memory(batch_size, n_batches).unwrap();
+    let batches: Vec<Vec<f64>> = (0..n_batches)
+        .map(|b| {
+            (0..batch_size)
+                .map(|i| (b * batch_size + i) as f64)
+                .collect()
+        })
+        .collect();
+
+    let allocated = processor.allocated_bytes();
+    assert!(allocated >= batch_size * n_batches * std::mem::size_of::<f64>());
+    assert_eq!(processor.used_bytes(), processor.window_size() * 8);
+
+    // Warm up the thread pool before counting
+    let mut out = vec![0.0; batch_size];
+    processor
+        .process_batch_into(&batches[0], BatchOp::Sqrt, false, &mut out)
+        .unwrap();
+
+    let allocations = allocations_during(|| {
+        for batch in &batches {
+            processor
+                .process_batch_into(batch, BatchOp::Sqrt, false, &mut out)
+                .unwrap();
+        }
+    });
+    assert_eq!(allocations, 0);
+
+    for batch in &batches {
+        processor
+            .process_batch(batch, BatchOp::Sqrt, false)
+            .unwrap();
+    }
+    assert_eq!(processor.allocated_bytes(), allocated);
+    assert_eq!(
+        processor.used_bytes(),
+        (batch_size + processor.window_size()) * 8
+    );
+
+    // One batch as large as everything reserved still fits, where a plain
+    // `new(batch_size, 0)` would have to grow its output buffer
+    let everything = batches.concat();
+    let allocations = allocations_during(|| {
+        processor
+            .process_batch(&everything, BatchOp::Sqrt, false)
+            .unwrap();
+    });
+    assert_eq!(allocations, 1, "only the returned copy");
+    assert_eq!(processor.allocated_bytes(), allocated);
+
+    processor.reserve_additional(1 << 20).unwrap();
+    assert!(processor.allocated_bytes() >= allocated + (1 << 20));
+    let allocated = processor.allocated_bytes();
+    assert!(processor.reserve_additional(usize::MAX).is_err());
+    assert_eq!(processor.allocated_bytes(), allocated);
+}
@@ -0,0 +1,109 @@
+//! `WasmBloomFilter`: no false negatives, false positives below the
+//! configured rate, and a serialized form that round-trips.
+#![cfg(not(target_arch = "wasm32"))]
+
+use web_learning_rust_examples::{WasmBloomFilter, WasmRuntime};
+
+/// Distinct keys: an odd multiplier permutes `u64`, and `offset` separates
+/// members from non-members
+fn keys(n: u64, offset: u64) -> Vec<u64> {
+    (offset..offset + n)
+        .map(|i| i.wrapping_mul(0x9E37_79B9_7F4A_7C15))
+        .collect()
+}
+
+#[test]
+fn false_positives_stay_below_the_configured_rate() {
+    let runtime = WasmRuntime::new(4).unwrap();
+    let (members, others) = (keys(100_000, 0), keys(1_000_000, 1 << 40));
+
+    for rate in [0.1, 0.01, 0.001] {
+        let mut filter = WasmBloomFilter::with_runtime(100_000, rate, &runtime).unwrap();
+        filter.parallel_insert_batch(&members).unwrap();
+
+        let found = filter.contains_batch(&members).unwrap();
+        assert!(found.iter().all(|&present| present), "no false negatives");
+
+        let false_positives = filter
+            .contains_batch(&others)
+            .unwrap()
+            .into_iter()
+            .filter(|&present| present)
+            .count();
+        let observed = false_positives as f64 / others.len() as f64;
+        // Filters are sized with headroom under `rate`, well clear of the
+        // sampling noise of 1 M queries
+        assert!(observed < rate, "{observed} false positives at {rate}");
+    }
+}
+
+#[test]
+fn sizing_follows_the_optimal_formulas() {
+    let filter = WasmBloomFilter::new(1_000, 0.01).unwrap();
+    // Sized for 0.9 p: m = -n ln 0.9p / ln² 2 ≈ 9804 bits, k = (m / n) ln 2
+    // ≈ 6.8 hashes
+    assert_eq!(filter.num_hashes(), 7);
+    assert!(
+        (9_804.0..9_920.0).contains(&filter.num_bits()),
+        "{}",
+        filter.num_bits()
+    );
+
+    let filter = WasmBloomFilter::new(1, 0.5).unwrap();
+    assert_eq!(filter.num_hashes(), 1);
+    assert_eq!(filter.num_bits(), 2.0);
+}
+
+#[test]
+fn serialized_filters_round_trip() {
+    let mut filter = WasmBloomFilter::new(5_000, 0.01).unwrap();
+    let members = keys(5_000, 0);
+    filter.parallel_insert_batch(&members).unwrap();
+    let queries = keys(20_000, 0);
+
+    let bytes = filter.serialize();
+    assert_eq!(&bytes[..4], b"WBF1");
+    let copy = WasmBloomFilter::deserialize(&bytes).unwrap();
+    assert_eq!(copy.num_bits(), filter.num_bits());
+    assert_eq!(copy.num_hashes(), filter.num_hashes());
+    assert_eq!(
+        copy.contains_batch(&queries).unwrap(),
+        filter.contains_batch(&queries).unwrap()
+    );
+    assert_eq!(copy.serialize(), bytes);
+
+    let runtime = WasmRuntime::new(2).unwrap();
+    let copy = WasmBloomFilter::deserialize_with_runtime(&bytes, &runtime).unwrap();
+    assert_eq!(
+        copy.contains_batch(&queries).unwrap(),
+        filter.contains_batch(&queries).unwrap()
+    );
+}
+
+#[test]
+fn bad_arguments_and_bytes_are_rejected() {
+    for rate in [0.0, 1.0, -0.5, f64::NAN] {
+        let error = WasmBloomFilter::new(100, rate).err().unwrap();
+        assert_eq!(error.code(), "INVALID_ARGUMENT", "rate {rate}");
+    }
+    let error = WasmBloomFilter::new(0, 0.01).err().unwrap();
+    assert_eq!(error.code(), "INVALID_ARGUMENT");
+    let error = WasmBloomFilter::new(u32::MAX, 1e-12).err().unwrap();
+    assert_eq!(error.code(), "INVALID_ARGUMENT");
+
+    let bytes = WasmBloomFilter::new(100, 0.01).unwrap().serialize();
+    let mut wrong_magic = bytes.clone();
+    wrong_magic[0] = b'X';
+    let mut no_hashes = bytes.clone();
+    no_hashes[4..8].copy_from_slice(&0u32.to_le_bytes());
+    for malformed in [
+        &bytes[..10],
+        &bytes[..bytes.len() - 1],
+        &wrong_magic[..],
+        &no_hashes[..],
+        &[bytes.clone(), vec![0]].concat()[..],
+    ] {
+        let error = WasmBloomFilter::deserialize(malformed).err().unwrap();
+        assert_eq!(error.code(), "INVALID_ENCODING");
+    }
+}
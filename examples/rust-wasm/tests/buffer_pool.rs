@@ -0,0 +1,120 @@
+//! The runtime's buffer pool: processors reuse their scratch buffers across
+//! calls, which a counting global allocator makes visible.
+#![cfg(not(target_arch = "wasm32"))]
+
+mod common;
+
+use common::{allocations_during, serial};
+use web_learning_rust_examples::{PoolStats, WasmImageProcessor, WasmMatrixProcessor, WasmRuntime};
+
+const WIDTH: u32 = 96;
+const HEIGHT: u32 = 64;
+
+fn image() -> Vec<u8> {
+    (0..WIDTH * HEIGHT * 4)
+        .map(|i| ((i * 31) % 251) as u8)
+        .collect()
+}
+
+/// Blur, then edges, then a threshold, as an animation frame might
+fn pipeline(processor: &mut WasmImageProcessor, rgba: &[u8]) -> Vec<u8> {
+    let blurred = processor.fast_box_blur(rgba, WIDTH, HEIGHT, 2).unwrap();
+    let edges = processor
+        .canny_edges(&blurred, WIDTH, HEIGHT, 20.0, 60.0)
+        .unwrap();
+    processor
+        .adaptive_threshold(&edges, WIDTH, HEIGHT, 7, 2)
+        .unwrap()
+}
+
+#[test]
+fn a_chained_image_pipeline_allocates_less_once_pooled() {
+    let _serial = serial();
+    let runtime = WasmRuntime::new(2).unwrap();
+    let mut processor = WasmImageProcessor::with_runtime(&runtime);
+    let rgba = image();
+
+    // The first frame fills the pool and starts the workers
+    let expected = pipeline(&mut processor, &rgba);
+    let after_first = runtime.buffer_pool_stats();
+    assert!(after_first.misses > 0);
+    assert!(after_first.held_bytes > 0);
+
+    let pooled = allocations_during(|| {
+        for _ in 0..5 {
+            assert_eq!(pipeline(&mut processor, &rgba), expected);
+        }
+    });
+    let stats = runtime.buffer_pool_stats();
+    assert_eq!(stats.misses, after_first.misses, "every take is a hit");
+    assert!(stats.hits >= 5 * after_first.misses);
+
+    let unpooled = allocations_during(|| {
+        for _ in 0..5 {
+            assert_eq!(pipeline(&mut processor, &rgba), expected);
+            runtime.trim();
+        }
+    });
+    assert!(
+        pooled + 5 * after_first.misses as usize <= unpooled,
+        "pooled {pooled}, trimmed every frame {unpooled}"
+    );
+}
+
+#[test]
+fn trim_releases_what_the_pool_holds() {
+    let _serial = serial();
+    let runtime = WasmRuntime::new(1).unwrap();
+    let mut processor = WasmImageProcessor::with_runtime(&runtime);
+    assert_eq!(runtime.buffer_pool_stats(), PoolStats::default());
+
+    processor.sobel_edges(&image(), WIDTH, HEIGHT).unwrap();
+    let held = runtime.buffer_pool_stats();
+    assert_eq!(held.held_buffers, 1);
+    assert_eq!(held.held_bytes, runtime.heap_bytes());
+    assert!(held.held_bytes >= (WIDTH * HEIGHT) as usize * 4);
+
+    assert_eq!(runtime.trim(), held.held_bytes);
+    let trimmed = runtime.buffer_pool_stats();
+    assert_eq!((trimmed.held_bytes, trimmed.held_buffers), (0, 0));
+    assert_eq!((trimmed.hits, trimmed.misses), (held.hits, held.misses));
+}
+
+#[test]
+fn processors_on_one_runtime_share_the_pool() {
+    let _serial = serial();
+    let runtime = WasmRuntime::new(2).unwrap();
+    let matrix = WasmMatrixProcessor::with_runtime(&runtime);
+    let copy = WasmMatrixProcessor::with_runtime(&runtime.clone());
+
+    // An overdetermined system with a heavy diagonal
+    let (m, n) = (12, 5);
+    let a: Vec<f64> = (0..m * n)
+        .map(|k| {
+            if k % n == (k / n) % n {
+                10.0
+            } else {
+                (k % 7) as f64 * 0.1
+            }
+        })
+        .collect();
+    let b: Vec<f64> = (0..m).map(|i| i as f64).collect();
+
+    let first = matrix.solve_least_squares(&a, &b, m, n).unwrap();
+    let misses = runtime.buffer_pool_stats().misses;
+    assert_eq!(copy.solve_least_squares(&a, &b, m, n).unwrap(), first);
+    let stats = runtime.buffer_pool_stats();
+    assert_eq!(stats.misses, misses);
+    assert!(stats.hits >= 3, "{stats:?}");
+
+    // A singular system still gives its buffer back
+    let singular = vec![1.0, 2.0, 2.0, 4.0];
+    assert!(matrix
+        .solve_linear_system(&singular, &[1.0, 2.0], 2)
+        .is_err());
+    let held = runtime.buffer_pool_stats().held_buffers;
+    assert!(matrix
+        .solve_linear_system(&singular, &[1.0, 2.0], 2)
+        .is_err());
+    assert_eq!(runtime.buffer_pool_stats().held_buffers, held);
+}
@@ -0,0 +1,142 @@
+//! Deterministic mode must give bit-identical floating-point reductions
+//! whatever the number of threads and however often they run.
+#![cfg(not(target_arch = "wasm32"))]
+
+use web_learning_rust_examples::{WasmImageProcessor, WasmParallelProcessor, WasmRuntime};
+
+const THREADS: [usize; 3] = [1, 2, 8];
+
+/// Values spanning many magnitudes, so that the order of addition shows in
+/// the last bits
+fn values(n: usize) -> Vec<f64> {
+    (0..n)
+        .map(|i| {
+            let x = ((i * 7919) % 10007) as f64 - 5003.0;
+            x * 10f64.powi((i % 17) as i32 - 8)
+        })
+        .collect()
+}
+
+/// `run` on a deterministic processor of each thread count, twice each,
+/// as bit patterns
+fn bits_across_threads(run: impl Fn(&WasmParallelProcessor) -> f64) -> Vec<u64> {
+    THREADS
+        .iter()
+        .flat_map(|&threads| {
+            let mut processor = WasmParallelProcessor::new(threads).unwrap();
+            processor.set_deterministic(true);
+            [run(&processor).to_bits(), run(&processor).to_bits()]
+        })
+        .collect()
+}
+
+fn assert_all_equal(what: &str, bits: &[u64]) {
+    assert!(
+        bits.iter().all(|&b| b == bits[0]),
+        "{what} differs: {:?}",
+        bits.iter().map(|&b| f64::from_bits(b)).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn sums_are_bit_identical_across_thread_counts() {
+    let data = values(1_000_003);
+    assert_all_equal(
+        "sum",
+        &bits_across_threads(|p| p.parallel_sum_f64(&data).unwrap()),
+    );
+    assert_all_equal(
+        "variance",
+        &bits_across_threads(|p| p.parallel_variance(&data).unwrap()),
+    );
+}
+
+#[test]
+fn distances_and_entropies_are_bit_identical_across_thread_counts() {
+    let a = values(300_001);
+    let b: Vec<f64> = a.iter().rev().map(|x| x * 0.5).collect();
+    let bytes: Vec<u8> = (0..500_000u64).map(|i| (i * i % 253) as u8).collect();
+
+    for p in [1.0, 2.0, 3.5] {
+        assert_all_equal(
+            &format!("minkowski p={p}"),
+            &bits_across_threads(|proc| proc.parallel_minkowski_distance(&a, &b, p).unwrap()),
+        );
+    }
+    assert_all_equal(
+        "shannon entropy",
+        &bits_across_threads(|p| p.parallel_shannon_entropy(&bytes).unwrap()),
+    );
+    assert_all_equal(
+        "renyi entropy",
+        &bits_across_threads(|p| p.parallel_renyi_entropy(&bytes, 2.0).unwrap()),
+    );
+}
+
+#[test]
+fn arima_fits_are_bit_identical_across_thread_counts() {
+    // An AR(2) series long enough to take several chunks
+    let noise = values(20_000);
+    let mut series = vec![0.0, 0.0];
+    for (t, shock) in noise.iter().enumerate().skip(2) {
+        series.push(0.6 * series[t - 1] - 0.2 * series[t - 2] + shock * 1e-3);
+    }
+
+    for k in 0..2 {
+        assert_all_equal(
+            &format!("AR coefficient {k}"),
+            &bits_across_threads(|p| p.parallel_arima_fit(&series, 2, 1, 0).unwrap()[k]),
+        );
+    }
+}
+
+#[test]
+fn image_statistics_are_bit_identical_on_deterministic_runtimes() {
+    let rgba: Vec<u8> = (0..4 * 300_007u64).map(|i| (i * i % 251) as u8).collect();
+    let runs: Vec<Vec<u64>> = THREADS
+        .iter()
+        .map(|&threads| {
+            let runtime = WasmRuntime::new(threads).unwrap();
+            runtime.set_deterministic(true);
+            let processor = WasmImageProcessor::with_runtime(&runtime);
+            let stats = processor.image_statistics(&rgba).unwrap();
+            stats.iter().map(|x| x.to_bits()).collect()
+        })
+        .collect();
+    assert!(runs.iter().all(|run| *run == runs[0]), "{runs:?}");
+}
+
+#[test]
+fn the_runtime_switch_applies_to_its_processors() {
+    let data = values(200_000);
+    let bits: Vec<u64> = THREADS
+        .iter()
+        .map(|&threads| {
+            let runtime = WasmRuntime::new(threads).unwrap();
+            runtime.set_deterministic(true);
+            let processor = WasmParallelProcessor::with_runtime(&runtime);
+            assert!(processor.deterministic());
+            processor.parallel_sum_f64(&data).unwrap().to_bits()
+        })
+        .collect();
+    assert_all_equal("sum on deterministic runtimes", &bits);
+
+    let runtime = WasmRuntime::new(2).unwrap();
+    let processor = WasmParallelProcessor::with_runtime(&runtime);
+    assert!(!processor.deterministic());
+    runtime.set_deterministic(true);
+    assert!(processor.deterministic(), "copies share the switch");
+}
+
+#[test]
+fn deterministic_results_stay_accurate() {
+    let data: Vec<f64> = (1..=100_000).map(f64::from).collect();
+    let mut processor = WasmParallelProcessor::new(4).unwrap();
+    processor.set_deterministic(true);
+
+    assert_eq!(processor.parallel_sum_f64(&data).unwrap(), 5_000_050_000.0);
+    assert_eq!(processor.parallel_sum_f64(&[]).unwrap(), 0.0);
+    let variance = processor.parallel_variance(&data).unwrap();
+    assert!((variance - (100_000f64.powi(2) - 1.0) / 12.0).abs() < 1e-3);
+    assert_eq!(processor.parallel_variance(&[3.0]).unwrap(), 0.0);
+}
@@ -0,0 +1,124 @@
+//! Error codes and details reported by the processors; run natively with
+//! `cargo test`. The JavaScript form of these errors is covered in `web.rs`.
+#![cfg(not(target_arch = "wasm32"))]
+
+use serde_json::json;
+use web_learning_rust_examples::{
+    BatchOp, BatchOp2, WasmBatchProcessor, WasmError, WasmImageProcessor, WasmMatrixProcessor,
+};
+
+/// The `{ code, details }` pair an error carries into JavaScript
+fn tagged(error: &WasmError) -> serde_json::Value {
+    serde_json::to_value(error).unwrap()
+}
+
+#[test]
+fn dimension_mismatch_reports_expected_and_actual() {
+    let processor = WasmMatrixProcessor::new(0).unwrap();
+    let error = processor
+        .solve_linear_system(&[1.0, 2.0, 3.0, 4.0], &[1.0], 2)
+        .unwrap_err();
+
+    assert_eq!(error.code(), "DIMENSION_MISMATCH");
+    assert_eq!(
+        error.to_string(),
+        "Right-hand side length: expected 2, got 1"
+    );
+    assert_eq!(
+        tagged(&error),
+        json!({
+            "code": "DIMENSION_MISMATCH",
+            "details": { "what": "Right-hand side length", "expected": 2, "actual": 1 },
+        })
+    );
+}
+
+#[test]
+fn strict_domain_errors_carry_the_offending_values() {
+    let mut processor = WasmBatchProcessor::new(0, 0).unwrap();
+    let error = processor
+        .process_batch(&[4.0, -1.0], BatchOp::Ln, true)
+        .unwrap_err();
+    assert_eq!(error.code(), "OUT_OF_DOMAIN");
+    assert_eq!(tagged(&error)["details"]["index"], 1);
+    assert_eq!(tagged(&error)["details"]["values"], json!([-1.0]));
+
+    let error = processor
+        .process_binary(&[1.0, 2.0], &[1.0, 0.0], BatchOp2::Div, true)
+        .unwrap_err();
+    assert_eq!(tagged(&error)["details"]["values"], json!([2.0, 0.0]));
+}
+
+#[test]
+fn unknown_names_list_the_supported_ones() {
+    let mut processor = WasmBatchProcessor::new(0, 0).unwrap();
+    let error = processor
+        .process_batch_str(&[1.0], "cube", false)
+        .unwrap_err();
+
+    assert_eq!(error.code(), "UNSUPPORTED_OPERATION");
+    let details = &tagged(&error)["details"];
+    assert_eq!(details["kind"], "batch operation");
+    assert_eq!(details["op"], "cube");
+    assert!(details["supported"].as_str().unwrap().contains("sqrt"));
+}
+
+#[test]
+fn remaining_variants_have_distinct_codes() {
+    let mut strict = WasmBatchProcessor::with_strict_capacity(2, 0).unwrap();
+    let error = strict
+        .process_batch(&[1.0; 3], BatchOp::Abs, false)
+        .unwrap_err();
+    assert_eq!(
+        error,
+        WasmError::CapacityExceeded {
+            requested: 3,
+            capacity: 2
+        }
+    );
+    assert_eq!(error.code(), "CAPACITY_EXCEEDED");
+
+    let mut images = WasmImageProcessor::new(0).unwrap();
+    let error = images.extract_channel(&[0; 4], 4).unwrap_err();
+    assert_eq!(error.code(), "INVALID_ARGUMENT");
+    assert_eq!(tagged(&error)["details"]["name"], "channel index");
+
+    let matrices = WasmMatrixProcessor::new(0).unwrap();
+    let error = matrices
+        .solve_linear_system(&[1.0, 2.0, 2.0, 4.0], &[1.0, 2.0], 2)
+        .unwrap_err();
+    assert_eq!(error.code(), "SINGULAR");
+
+    // Variants without fields still serialize with an empty details slot
+    assert_eq!(
+        tagged(&WasmError::NotInitialized),
+        json!({ "code": "NOT_INITIALIZED" })
+    );
+    assert_eq!(
+        tagged(&WasmError::AuthenticationFailed),
+        json!({ "code": "AUTHENTICATION_FAILED" })
+    );
+    assert_eq!(
+        WasmError::AuthenticationFailed.code(),
+        "AUTHENTICATION_FAILED"
+    );
+}
+
+#[test]
+fn matrix_sizes_that_overflow_are_rejected() {
+    let processor = WasmMatrixProcessor::new(0).unwrap();
+    let error = processor
+        .solve_linear_system(&[], &[], 1 << 33)
+        .unwrap_err();
+    assert_eq!(error.code(), "INVALID_ARGUMENT");
+
+    // Empty inner dimensions leave the product's size to the outer ones
+    let error = processor
+        .multiply(&[], usize::MAX, 0, &[], 0, 2)
+        .unwrap_err();
+    assert_eq!(error.code(), "INVALID_ARGUMENT");
+    let error = processor
+        .multiply(&[], 1 << 40, 0, &[], 0, 1 << 20)
+        .unwrap_err();
+    assert_eq!(error.code(), "RESOURCE_UNAVAILABLE");
+}
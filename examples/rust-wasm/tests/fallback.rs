@@ -0,0 +1,195 @@
+//! Processors must give the same results on one thread as on many, since
+//! pages without cross-origin isolation run everything on the calling
+//! thread. A one-thread pool stands in for that fallback natively, next to
+//! the runtime with no pool of its own (`WasmRuntime::new(0)`) that
+//! processors constructed with 0 threads use.
+//!
+//! Every computing method of the batch and matrix processors is covered,
+//! except those taking or returning JavaScript values (`process_chain`,
+//! `process_batch_async`, `last_output_view`, `svd_2x2`, `svd_3x3`), which
+//! `tests/wasm.rs` runs in a browser; `apply_chain` and the pseudo-inverses
+//! cover the same code here. The streaming window (`push_sample`, `flush`)
+//! never touches the pool, and the capacity methods only report sizes.
+#![cfg(not(target_arch = "wasm32"))]
+
+use web_learning_rust_examples::{
+    BatchOp, BatchOp2, ChainStep, WasmBatchProcessor, WasmMatrixProcessor, WasmParallelProcessor,
+    WasmRuntime,
+};
+
+/// Processors on a one-thread runtime, a four-thread one and the runtime
+/// without a pool of its own
+struct Modes<T> {
+    single: T,
+    threaded: T,
+    fallback: T,
+}
+
+impl<T> Modes<T> {
+    fn new(mut make: impl FnMut(&WasmRuntime) -> T) -> Modes<T> {
+        Modes {
+            single: make(&WasmRuntime::new(1).unwrap()),
+            threaded: make(&WasmRuntime::new(4).unwrap()),
+            fallback: make(&WasmRuntime::new(0).unwrap()),
+        }
+    }
+
+    fn assert_same<R: PartialEq + std::fmt::Debug>(
+        &mut self,
+        what: &str,
+        mut run: impl FnMut(&mut T) -> R,
+    ) {
+        let single = run(&mut self.single);
+        assert_eq!(single, run(&mut self.threaded), "{what} with threads");
+        assert_eq!(single, run(&mut self.fallback), "{what} without a pool");
+    }
+}
+
+fn values(n: usize) -> Vec<f64> {
+    (0..n)
+        .map(|i| ((i * 7919) % 1000) as f64 / 10.0 - 50.0)
+        .collect()
+}
+
+#[test]
+fn parallel_processor_matches_across_modes() {
+    let mut modes = Modes::new(WasmParallelProcessor::with_runtime);
+    let ints: Vec<i32> = (0..200_000).map(|i| (i * 31 % 1009) - 500).collect();
+    let bytes: Vec<u8> = (0..300_000).map(|i| (i * 13 % 251) as u8).collect();
+    let keys: Vec<u32> = (0..100_000).map(|i| i * 17 % 1000).collect();
+    let floats = values(100_000);
+
+    modes.assert_same("sum", |p| p.parallel_sum(&ints));
+    modes.assert_same("map square", |p| p.parallel_map_square(&ints));
+    modes.assert_same("count values", |p| p.parallel_count_values(&bytes));
+    modes.assert_same("crc32c", |p| p.parallel_crc32c(&bytes));
+    modes.assert_same("bigrams", |p| p.parallel_bigram_frequency(&bytes));
+    modes.assert_same("radix sort", |p| p.parallel_radix_sort_u32(&keys));
+    modes.assert_same("argmax", |p| p.parallel_argmax(&floats));
+    modes.assert_same("group count", |p| {
+        p.parallel_group_by_count(&keys, 1000).unwrap()
+    });
+    modes.assert_same("group max", |p| {
+        p.parallel_group_by_max(&keys, &floats, 1000).unwrap()
+    });
+    modes.assert_same("sample", |p| {
+        p.parallel_sample_without_replacement(100_000, 5000, 42)
+            .unwrap()
+    });
+    modes.assert_same("diff", |p| p.parallel_diff(&floats, 3).unwrap());
+    modes.assert_same("gradient", |p| p.parallel_gradient(&floats, 0.5).unwrap());
+    modes.assert_same("polyeval", |p| {
+        p.parallel_polyeval(&floats[..50], &floats).unwrap()
+    });
+    modes.assert_same("bezier", |p| {
+        let control = [0.0, 0.0, 10.0, 40.0, 60.0, -30.0, 80.0, 5.0];
+        p.parallel_bezier_adaptive_sample(&control, 3, 0.01)
+            .unwrap()
+    });
+    modes.assert_same("sparse histogram", |p| {
+        let wide: Vec<i64> = ints.iter().map(|&x| i64::from(x) << 33).collect();
+        p.parallel_sparse_histogram(&wide).unwrap()
+    });
+}
+
+#[test]
+fn matrix_processor_matches_across_modes() {
+    let mut modes = Modes::new(WasmMatrixProcessor::with_runtime);
+    let a = values(96 * 80);
+    let b = values(80 * 64);
+    // Diagonally dominant, and large enough to eliminate in parallel
+    let n = 96;
+    let square: Vec<f64> = values(n * n)
+        .iter()
+        .enumerate()
+        .map(|(k, &x)| if k / n == k % n { 500.0 } else { x })
+        .collect();
+    let rhs = values(n);
+    let tall: Vec<f64> = a
+        .iter()
+        .enumerate()
+        .map(|(k, &x)| if k % 80 == (k / 80) % 80 { 500.0 } else { x })
+        .collect();
+
+    modes.assert_same("multiply", |m| m.multiply(&a, 96, 80, &b, 80, 64).unwrap());
+    modes.assert_same("transpose", |m| m.transpose(&a, 96, 80).unwrap());
+    modes.assert_same("solve", |m| {
+        m.solve_linear_system(&square, &rhs, n).unwrap()
+    });
+    modes.assert_same("solve into", |m| {
+        let mut out = vec![0.0; n];
+        m.solve_linear_system_into(&square, &rhs, n, &mut out)
+            .unwrap();
+        out
+    });
+    modes.assert_same("least squares", |m| {
+        m.solve_least_squares(&tall, &rhs, 96, 80).unwrap()
+    });
+    modes.assert_same("pseudo-inverse 2x2", |m| {
+        m.pseudo_inverse_2x2(&[4.0, 1.0, 2.0, 3.0]).unwrap()
+    });
+    modes.assert_same("pseudo-inverse 3x3", |m| {
+        m.pseudo_inverse_3x3(&[2.0, -1.0, 0.0, -1.0, 2.0, -1.0, 0.0, -1.0, 2.0])
+            .unwrap()
+    });
+}
+
+#[test]
+fn batch_processor_matches_across_modes() {
+    let mut modes = Modes::new(|runtime| WasmBatchProcessor::with_runtime(1024, runtime));
+    let data: Vec<f64> = values(50_000).iter().map(|x| x.abs() + 1.0).collect();
+    let other: Vec<f64> = data.iter().rev().copied().collect();
+    let floats: Vec<f32> = data.iter().map(|&x| x as f32).collect();
+    let other_floats: Vec<f32> = other.iter().map(|&x| x as f32).collect();
+
+    for op in [BatchOp::Square, BatchOp::Sqrt, BatchOp::Ln] {
+        modes.assert_same(&format!("{op:?}"), |p| {
+            p.process_batch(&data, op, true).unwrap()
+        });
+        modes.assert_same(&format!("{op:?} into"), |p| {
+            let mut out = vec![0.0; data.len()];
+            p.process_batch_into(&data, op, true, &mut out).unwrap();
+            out
+        });
+        modes.assert_same(&format!("{op:?} f32"), |p| {
+            p.process_batch_f32(&floats, op, true).unwrap()
+        });
+    }
+    modes.assert_same("by name", |p| {
+        p.process_batch_str(&data, "sigmoid", false).unwrap()
+    });
+    for op in [BatchOp2::Div, BatchOp2::Pow, BatchOp2::Atan2] {
+        modes.assert_same(&format!("{op:?}"), |p| {
+            p.process_binary(&data, &other, op, true).unwrap()
+        });
+        modes.assert_same(&format!("{op:?} scalar"), |p| {
+            p.process_scalar(&data, 1.5, op, true).unwrap()
+        });
+        modes.assert_same(&format!("{op:?} f32"), |p| {
+            p.process_binary_f32(&floats, &other_floats, op, true)
+                .unwrap()
+        });
+    }
+    modes.assert_same("complex", |p| {
+        p.process_complex(&data, "normalize").unwrap()
+    });
+    modes.assert_same("chain", |p| {
+        let steps = [
+            ChainStep::Ln,
+            ChainStep::Mul { value: 3.0 },
+            ChainStep::Clamp { min: 0.5, max: 9.0 },
+        ];
+        p.apply_chain(&data, &steps).unwrap()
+    });
+    modes.assert_same("polynomial", |p| {
+        p.process_polynomial(&data, &[1.0, -0.5, 0.25]).unwrap()
+    });
+    modes.assert_same("piecewise linear", |p| {
+        p.process_piecewise_linear(&data, &[0.0, 10.0, 60.0], &[0.0, 1.0, -1.0], true)
+            .unwrap()
+    });
+    modes.assert_same("fma", |p| p.fma_batch(&data, 2.0, -1.0).unwrap());
+    modes.assert_same("fma vec", |p| {
+        p.fma_batch_vec(&data, &other, &data).unwrap()
+    });
+}
@@ -0,0 +1,106 @@
+//! Levenshtein distances in bulk, checked against a plain full-table
+//! implementation.
+#![cfg(not(target_arch = "wasm32"))]
+
+use web_learning_rust_examples::WasmParallelProcessor;
+
+/// Wagner-Fischer over the whole `(m + 1) x (n + 1)` table
+fn reference(a: &str, b: &str) -> u32 {
+    let (a, b): (Vec<char>, Vec<char>) = (a.chars().collect(), b.chars().collect());
+    let mut table = vec![vec![0u32; b.len() + 1]; a.len() + 1];
+    for (i, row) in table.iter_mut().enumerate() {
+        row[0] = i as u32;
+    }
+    for (j, cell) in table[0].iter_mut().enumerate() {
+        *cell = j as u32;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let substitution = table[i - 1][j - 1] + u32::from(a[i - 1] != b[j - 1]);
+            table[i][j] = substitution
+                .min(table[i - 1][j] + 1)
+                .min(table[i][j - 1] + 1);
+        }
+    }
+    table[a.len()][b.len()]
+}
+
+/// Words from a small alphabet, so that pairs share prefixes and suffixes
+fn words(n: usize, seed: u64) -> Vec<String> {
+    let mut state = seed;
+    (0..n)
+        .map(|_| {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            let len = (state >> 59) as usize;
+            (0..len)
+                .map(|k| ['a', 'b', 'c', 'é'][(state >> (2 * k + 3)) as usize % 4])
+                .collect()
+        })
+        .collect()
+}
+
+#[test]
+fn known_distances() {
+    let processor = WasmParallelProcessor::new(2).unwrap();
+    let queries = ["kitten", "flaw", "", "abc", "same", "naïve", "saturday"];
+    let targets = ["sitting", "lawn", "abc", "", "same", "naive", "sunday"];
+    assert_eq!(
+        processor.levenshtein_batch(&queries, &targets).unwrap(),
+        [3, 2, 3, 3, 0, 1, 3]
+    );
+}
+
+#[test]
+fn batch_matches_the_sequential_distance_on_any_pool() {
+    let (queries, targets) = (words(2_000, 1), words(2_000, 2));
+    let expected: Vec<u32> = queries
+        .iter()
+        .zip(&targets)
+        .map(|(a, b)| reference(a, b))
+        .collect();
+
+    for threads in [1, 4] {
+        let processor = WasmParallelProcessor::new(threads).unwrap();
+        assert_eq!(
+            processor.levenshtein_batch(&queries, &targets).unwrap(),
+            expected,
+            "{threads} threads"
+        );
+    }
+}
+
+#[test]
+fn closest_match_takes_the_first_of_ties() {
+    let processor = WasmParallelProcessor::new(4).unwrap();
+    let candidates = ["sitting", "kitchen", "mitten", "bitten", "kitten!"];
+    assert_eq!(processor.closest_match("kitten", &candidates).unwrap(), 2);
+    assert_eq!(processor.closest_match("kitten", &["kitten"]).unwrap(), 0);
+
+    let candidates = words(5_000, 3);
+    let query = "abcabc";
+    let best = candidates
+        .iter()
+        .map(|candidate| reference(query, candidate))
+        .min()
+        .unwrap();
+    let index = processor.closest_match(query, &candidates).unwrap() as usize;
+    assert_eq!(reference(query, &candidates[index]), best);
+    assert!(candidates[..index]
+        .iter()
+        .all(|candidate| reference(query, candidate) > best));
+}
+
+#[test]
+fn bad_arguments_are_rejected() {
+    let processor = WasmParallelProcessor::new(1).unwrap();
+    let error = processor
+        .levenshtein_batch(&["a", "b"], &["a"])
+        .unwrap_err();
+    assert_eq!(error.code(), "DIMENSION_MISMATCH");
+
+    let none: [&str; 0] = [];
+    let error = processor.closest_match("a", &none).unwrap_err();
+    assert_eq!(error.code(), "INVALID_ARGUMENT");
+}
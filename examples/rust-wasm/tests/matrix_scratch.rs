@@ -0,0 +1,126 @@
+//! `WasmMatrixProcessor` draws its temporaries from a scratch arena, so a
+//! loop of small solves stops allocating once the arena has grown, checked
+//! with a counting global allocator.
+#![cfg(not(target_arch = "wasm32"))]
+
+mod common;
+
+use common::{allocations_during, serial};
+use web_learning_rust_examples::{WasmImageProcessor, WasmMatrixProcessor, WasmRuntime};
+
+/// A diagonally dominant `n x n` system varying with `seed`
+fn system(n: usize, seed: usize) -> (Vec<f64>, Vec<f64>) {
+    let a = (0..n * n)
+        .map(|k| {
+            if k / n == k % n {
+                2.0 * n as f64
+            } else {
+                ((k * 7 + seed) % 11) as f64 * 0.1 - 0.5
+            }
+        })
+        .collect();
+    let b = (0..n).map(|i| (i + seed) as f64).collect();
+    (a, b)
+}
+
+#[test]
+fn small_solves_stop_allocating_after_warm_up() {
+    let _serial = serial();
+    let processor = WasmMatrixProcessor::new(2).unwrap();
+    let systems: Vec<_> = (0..1_000).map(|seed| system(8, seed)).collect();
+    let expected: Vec<Vec<f64>> = systems
+        .iter()
+        .map(|(a, b)| processor.solve_linear_system(a, b, 8).unwrap())
+        .collect();
+    let mut out = [0.0; 8];
+
+    let allocations = allocations_during(|| {
+        for ((a, b), expected) in systems.iter().zip(&expected) {
+            processor
+                .solve_linear_system_into(a, b, 8, &mut out)
+                .unwrap();
+            assert_eq!(&out[..], &expected[..]);
+        }
+    });
+    assert_eq!(allocations, 0);
+}
+
+#[test]
+fn scratch_grows_to_the_largest_operation_and_resets() {
+    let _serial = serial();
+    let processor = WasmMatrixProcessor::new(2).unwrap();
+    assert_eq!(processor.scratch_bytes(), 0);
+
+    let (a, b) = system(16, 0);
+    let solution = processor.solve_linear_system(&a, &b, 16).unwrap();
+    let high_water = processor.scratch_bytes();
+    assert!(high_water >= 16 * 17 * 8);
+
+    // Repeating an operation reuses what it left; smaller ones add only
+    // their own buffers
+    processor.solve_linear_system(&a, &b, 16).unwrap();
+    assert_eq!(processor.scratch_bytes(), high_water);
+    let (small, rhs) = system(4, 1);
+    processor.solve_linear_system(&small, &rhs, 4).unwrap();
+    processor.multiply(&small, 4, 4, &small, 4, 4).unwrap();
+    processor
+        .pseudo_inverse_3x3(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 10.0])
+        .unwrap();
+    assert!(processor.scratch_bytes() < 2 * high_water);
+
+    processor.scratch_reset();
+    assert_eq!(processor.scratch_bytes(), 0);
+    assert_eq!(processor.solve_linear_system(&a, &b, 16).unwrap(), solution);
+}
+
+#[test]
+fn reset_frees_only_this_processors_buffers() {
+    let _serial = serial();
+    let runtime = WasmRuntime::new(2).unwrap();
+    let mut image = WasmImageProcessor::with_runtime(&runtime);
+    let matrix = WasmMatrixProcessor::with_runtime(&runtime);
+    let rgba = vec![128; 32 * 32 * 4];
+    image.sobel_edges(&rgba, 32, 32).unwrap();
+    let image_held = runtime.buffer_pool_stats().held_bytes;
+    assert!(image_held > 0);
+
+    let (a, b) = system(16, 2);
+    matrix.solve_linear_system(&a, &b, 16).unwrap();
+    matrix.multiply(&a, 16, 16, &a, 16, 16).unwrap();
+    let held = runtime.buffer_pool_stats().held_bytes;
+    assert_eq!(held, image_held + matrix.scratch_bytes());
+
+    matrix.scratch_reset();
+    assert_eq!(matrix.scratch_bytes(), 0);
+    assert_eq!(runtime.buffer_pool_stats().held_bytes, image_held);
+}
+
+#[test]
+fn scratch_contents_do_not_leak_between_operations() {
+    let _serial = serial();
+    let processor = WasmMatrixProcessor::new(2).unwrap();
+    let (a, b) = system(12, 3);
+    let expected = processor.multiply(&a, 12, 12, &a, 12, 12).unwrap();
+    let solution = processor.solve_linear_system(&a, &b, 12).unwrap();
+
+    // Leave other values behind, then repeat
+    let singular = vec![1.0; 144];
+    assert_eq!(
+        processor
+            .solve_linear_system(&singular, &b, 12)
+            .unwrap_err()
+            .code(),
+        "SINGULAR"
+    );
+    processor.solve_least_squares(&a, &b, 12, 12).unwrap();
+    assert_eq!(
+        processor.multiply(&a, 12, 12, &a, 12, 12).unwrap(),
+        expected
+    );
+    assert_eq!(processor.solve_linear_system(&a, &b, 12).unwrap(), solution);
+
+    let error = processor
+        .solve_linear_system_into(&a, &b, 12, &mut [0.0; 11])
+        .unwrap_err();
+    assert_eq!(error.code(), "DIMENSION_MISMATCH");
+}
@@ -0,0 +1,68 @@
+//! `heap_bytes` accounting for the processors that `memory_report` lists,
+//! and the native fallback for the process size.
+#![cfg(not(target_arch = "wasm32"))]
+
+use web_learning_rust_examples::{
+    process_memory_bytes, BatchOp, WasmBatchProcessor, WasmImageProcessor, WasmRuntime,
+};
+
+#[test]
+fn batch_processor_counts_every_buffer() {
+    let runtime = WasmRuntime::new(2).unwrap();
+    let mut batch = WasmBatchProcessor::with_runtime(1000, &runtime);
+    let initial = batch.heap_bytes();
+    assert_eq!(initial, batch.memory_bytes());
+    assert!(initial >= 1000 * 8, "the output buffer is preallocated");
+
+    batch.reserve(50_000);
+    assert!(batch.heap_bytes() >= 50_000 * 8);
+
+    // Single-precision output has a buffer of its own
+    batch
+        .process_batch_f32(&vec![1.0f32; 20_000], BatchOp::Sqrt, false)
+        .unwrap();
+    assert!(batch.heap_bytes() >= 50_000 * 8 + 20_000 * 4);
+
+    batch.shrink_to_fit();
+    assert!(batch.heap_bytes() < 50_000 * 8);
+}
+
+#[test]
+fn f32_buffer_takes_the_configured_capacity() {
+    let runtime = WasmRuntime::new(2).unwrap();
+    let mut batch = WasmBatchProcessor::with_runtime(1000, &runtime);
+    let f64_only = batch.memory_bytes();
+
+    // The first small batch reserves room for a full one
+    batch
+        .process_batch_f32(&[1.0f32; 10], BatchOp::Sqrt, false)
+        .unwrap();
+    let full = batch.memory_bytes();
+    assert!(full >= f64_only + 1000 * 4);
+
+    batch
+        .process_batch_f32(&vec![1.0f32; 1000], BatchOp::Sqrt, false)
+        .unwrap();
+    assert_eq!(batch.memory_bytes(), full);
+}
+
+#[test]
+fn image_processor_counts_its_output_buffer_and_frame() {
+    let mut image = WasmImageProcessor::with_runtime(&WasmRuntime::new(2).unwrap());
+    assert_eq!(image.heap_bytes(), 0);
+
+    let pixels = vec![128u8; 32 * 32 * 4];
+    image.grayscale(&pixels).unwrap();
+    let after_output = image.heap_bytes();
+    assert!(after_output >= pixels.len(), "{after_output}");
+
+    image.load_frame(&pixels, 32, 32).unwrap();
+    assert!(image.heap_bytes() >= after_output + pixels.len());
+}
+
+#[test]
+fn process_size_is_reported_natively() {
+    if cfg!(target_os = "linux") {
+        assert!(process_memory_bytes() > 0);
+    }
+}
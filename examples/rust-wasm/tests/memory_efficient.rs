@@ -0,0 +1,103 @@
+//! `MemoryEfficientProcessor` must not allocate once its buffer is large
+//! enough, checked with a counting global allocator.
+#![cfg(not(target_arch = "wasm32"))]
+
+mod common;
+
+use common::{allocations_during, serial};
+use web_learning_rust_examples::{BatchOp, MemoryEfficientProcessor, WasmRuntime};
+
+fn ramp(len: usize) -> Vec<f64> {
+    (0..len).map(|i| i as f64 * 0.01).collect()
+}
+
+#[test]
+fn process_into_does_not_allocate() {
+    let _serial = serial();
+    let mut processor = MemoryEfficientProcessor::new(0, 4).unwrap();
+
+    for len in [100, 1_000_000] {
+        let data = ramp(len);
+        let mut out = vec![0.0; len];
+
+        // Warm up the thread pool before counting
+        processor
+            .process_into(&data, BatchOp::Sqrt, &mut out)
+            .unwrap();
+
+        let allocations = allocations_during(|| {
+            for _ in 0..10 {
+                processor
+                    .process_into(&data, BatchOp::Sin, &mut out)
+                    .unwrap();
+            }
+        });
+        assert_eq!(allocations, 0, "{len} elements");
+        assert_eq!(out[len - 1], data[len - 1].sin());
+    }
+    assert_eq!(processor.heap_bytes(), 0, "the internal buffer is unused");
+}
+
+#[test]
+fn process_in_place_reuses_its_buffer() {
+    let _serial = serial();
+    let capacity = 200_000;
+    let mut processor = MemoryEfficientProcessor::new(capacity, 4).unwrap();
+    let heap_bytes = processor.heap_bytes();
+    assert!(heap_bytes >= capacity * 8);
+    let data = ramp(capacity);
+    processor.process_in_place(&data, BatchOp::Sqrt).unwrap();
+
+    let allocations = allocations_during(|| {
+        for len in [capacity, 1_000, capacity / 2] {
+            let results = processor
+                .process_in_place(&data[..len], BatchOp::Square)
+                .unwrap();
+            assert_eq!(results.len(), len);
+            assert_eq!(results[len - 1], data[len - 1] * data[len - 1]);
+        }
+    });
+    assert_eq!(allocations, 0);
+    assert_eq!(processor.heap_bytes(), heap_bytes);
+
+    // A longer input grows the buffer once
+    let longer = ramp(capacity * 2);
+    let allocations = allocations_during(|| {
+        processor.process_in_place(&longer, BatchOp::Abs).unwrap();
+    });
+    assert_eq!(allocations, 1);
+    assert!(processor.heap_bytes() >= capacity * 2 * 8);
+}
+
+#[test]
+fn chunk_size_does_not_change_results() {
+    let _serial = serial();
+    let runtime = WasmRuntime::new(4).unwrap();
+    let mut processor = MemoryEfficientProcessor::with_runtime(0, &runtime);
+    assert_eq!(processor.chunk_size(), 1024);
+    let data = ramp(50_001);
+    let expected: Vec<f64> = data.iter().map(|x| x.exp()).collect();
+
+    for chunk_size in [1, 7, 1024, 65_536] {
+        processor.set_chunk_size(chunk_size).unwrap();
+        assert_eq!(processor.chunk_size(), chunk_size);
+        let mut out = vec![0.0; data.len()];
+        processor
+            .process_into(&data, BatchOp::Exp, &mut out)
+            .unwrap();
+        assert_eq!(out, expected, "chunks of {chunk_size}");
+        assert_eq!(
+            processor.process_in_place(&data, BatchOp::Exp).unwrap(),
+            &expected[..]
+        );
+    }
+
+    let error = processor.set_chunk_size(0).unwrap_err();
+    assert_eq!(error.code(), "INVALID_ARGUMENT");
+    assert_eq!(processor.chunk_size(), 65_536);
+
+    let error = processor
+        .process_into(&data, BatchOp::Exp, &mut [0.0; 3])
+        .unwrap_err();
+    assert_eq!(error.code(), "DIMENSION_MISMATCH");
+}
@@ -0,0 +1,150 @@
+//! NDJSON field extraction, checked against serde_json on the same lines.
+#![cfg(not(target_arch = "wasm32"))]
+
+use serde_json::Value;
+use web_learning_rust_examples::WasmParallelProcessor;
+
+/// Lines of varied shape: the field present, missing, non-numeric, behind
+/// escapes, or under a duplicate key
+fn ndjson(n: usize) -> String {
+    (0..n)
+        .map(|i| {
+            // Quarters, which serde_json parses exactly too
+            let cpu = i as f64 * 0.25 - 1000.0;
+            match i % 7 {
+                0 => format!(r#"{{"id":{i},"metrics":{{"cpu":{cpu},"mem":[1,2,{{"cpu":9}}]}}}}"#),
+                1 => format!(r#"{{"id":{i},"metrics":{{"mem":{i}}}}}"#),
+                2 => format!(r#"{{"metrics":{{"cpu":"{cpu}"}},"note":"a \"quoted\" é"}}"#),
+                3 => format!(r#"  {{ "metrics" : {{ "cpu" : {cpu:e} }} , "ok" : true }}  "#),
+                4 => format!(r#"{{"metrics":{{"cpu":1}},"metrics":{{"cpu":{i}e2}}}}"#),
+                5 => format!(r#"{{"metrics":null,"cpu":{cpu}}}"#),
+                _ => format!(r#"[{{"metrics":{{"cpu":{cpu}}}}}]"#),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// The value at `path`, as `JSON.parse` then property access would give it
+fn reference(line: &str, path: &str) -> f64 {
+    let value: Value = serde_json::from_str(line).unwrap();
+    path.split('.')
+        .try_fold(&value, |value, key| value.get(key))
+        .and_then(Value::as_f64)
+        .unwrap_or(f64::NAN)
+}
+
+fn same(a: &[f64], b: &[f64]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).all(|(x, y)| x.to_bits() == y.to_bits())
+}
+
+#[test]
+fn matches_serde_json_on_100k_lines() {
+    let input = ndjson(100_000);
+    let expected: Vec<f64> = input
+        .lines()
+        .map(|line| reference(line, "metrics.cpu"))
+        .collect();
+    assert!(expected.iter().filter(|x| !x.is_nan()).count() > 40_000);
+
+    for threads in [1, 4] {
+        let processor = WasmParallelProcessor::new(threads).unwrap();
+        let values = processor
+            .parallel_parse_ndjson_floats(&input, "metrics.cpu")
+            .unwrap();
+        assert!(same(&values, &expected), "{threads} threads");
+    }
+}
+
+#[test]
+fn blank_lines_are_skipped_and_numbers_exact() {
+    let processor = WasmParallelProcessor::new(2).unwrap();
+    let input = "\n{\"a\":1}\r\n  \n\t\n{\"a\":{\"b\":2.5}}\n{}\n";
+    assert!(same(
+        &processor.parallel_parse_ndjson_floats(input, "a").unwrap(),
+        &[1.0, f64::NAN, f64::NAN]
+    ));
+    assert!(same(
+        &processor
+            .parallel_parse_ndjson_floats(input, "a.b")
+            .unwrap(),
+        &[f64::NAN, 2.5, f64::NAN]
+    ));
+    // Keys match once their escapes are decoded
+    let input = r#"{"\u0061":{"\ud83d\ude00":4,"b\n":5}}"#;
+    assert_eq!(
+        processor
+            .parallel_parse_ndjson_floats(input, "a.😀")
+            .unwrap(),
+        [4.0]
+    );
+    assert_eq!(
+        processor
+            .parallel_parse_ndjson_floats(input, "a.b\n")
+            .unwrap(),
+        [5.0]
+    );
+
+    // Numbers are correctly rounded
+    let digits = [
+        "-507.90000000000003",
+        "0.1",
+        "1e-320",
+        "2.5E+3",
+        "-0",
+        "1e400",
+    ];
+    let input: String = digits.iter().map(|x| format!("{{\"a\":{x}}}\n")).collect();
+    let values = processor.parallel_parse_ndjson_floats(&input, "a").unwrap();
+    let expected: Vec<f64> = digits.iter().map(|x| x.parse().unwrap()).collect();
+    assert!(same(&values, &expected));
+
+    assert_eq!(
+        processor
+            .parallel_parse_ndjson_floats(" \n\n", "a")
+            .unwrap(),
+        Vec::<f64>::new()
+    );
+}
+
+#[test]
+fn the_first_invalid_line_is_reported() {
+    let processor = WasmParallelProcessor::new(4).unwrap();
+    let cases = [
+        (r#"{"a":1,}"#, 8, "expected a string key"),
+        (r#"{"a" 1}"#, 6, "expected ':' after the key"),
+        (r#"{"a":01}"#, 7, "expected ',' or '}'"),
+        (r#"{"a":-}"#, 7, "invalid number"),
+        (r#"{"a":1.}"#, 8, "invalid number"),
+        (r#"{"é":"\x"}"#, 8, "invalid escape"),
+        (r#"{"a":"open}"#, 12, "unterminated string"),
+        (r#"{"a":[1 2]}"#, 9, "expected ',' or ']'"),
+        (r#"{"a":tru}"#, 6, "expected a value"),
+        (r#"{"a":1} {"b":2}"#, 9, "unexpected text after the value"),
+        ("{\"a\":\"tab\there\"}", 10, "control character in string"),
+    ];
+    for (bad, column, reason) in cases {
+        // Valid lines on both sides, and a second bad line after it
+        let input = format!("{{\"a\":1}}\n\n{bad}\n{{\"a\":2}}\n{{\n");
+        let error = processor
+            .parallel_parse_ndjson_floats(&input, "a")
+            .unwrap_err();
+        assert_eq!(error.code(), "INVALID_JSON", "{bad}");
+        assert_eq!(
+            error.to_string(),
+            format!("Invalid JSON at line 3, column {column}: {reason}"),
+            "{bad}"
+        );
+    }
+
+    let deep = format!("{}{}", "[".repeat(200), "]".repeat(200));
+    let error = processor
+        .parallel_parse_ndjson_floats(&deep, "a")
+        .unwrap_err();
+    assert_eq!(error.code(), "INVALID_JSON");
+
+    let error = processor
+        .parallel_parse_ndjson_floats("{}", "a..b")
+        .unwrap_err();
+    assert_eq!(error.code(), "INVALID_ARGUMENT");
+}
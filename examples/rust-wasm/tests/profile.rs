@@ -0,0 +1,120 @@
+//! Per-operation profiling through `set_profiling` and `last_op`, the
+//! native side of `last_op_stats`.
+#![cfg(not(target_arch = "wasm32"))]
+
+use web_learning_rust_examples::{
+    BatchOp, ChainStep, WasmBatchProcessor, WasmMatrixProcessor, WasmParallelProcessor, WasmRuntime,
+};
+
+#[test]
+fn nothing_is_recorded_until_profiling_is_on() {
+    let mut processor = WasmParallelProcessor::with_runtime(&WasmRuntime::new(2).unwrap());
+    processor.parallel_sum(&[1, 2, 3]).unwrap();
+    assert_eq!(processor.last_op(), None);
+
+    processor.set_profiling(true);
+    processor.parallel_sum(&[1, 2, 3]).unwrap();
+    assert!(processor.last_op().is_some());
+
+    processor.set_profiling(false);
+    assert_eq!(
+        processor.last_op(),
+        None,
+        "switching should forget the stats"
+    );
+    processor.parallel_sum(&[1, 2, 3]).unwrap();
+    assert_eq!(processor.last_op(), None);
+}
+
+#[test]
+fn stats_describe_the_last_call() {
+    let runtime = WasmRuntime::new(3).unwrap();
+    let mut processor = WasmParallelProcessor::with_runtime(&runtime);
+    processor.set_profiling(true);
+    let data: Vec<i32> = (0..100_000).collect();
+
+    processor.parallel_map_square(&data).unwrap();
+    let stats = processor.last_op().unwrap();
+    assert_eq!(stats.operation, "parallel_map_square");
+    assert_eq!(stats.input_elements, 100_000);
+    assert_eq!(stats.pool_threads, 3);
+    assert!(stats.bytes_allocated >= 100_000 * 4, "{stats:?}");
+    assert!(stats.wall_ms >= 0.0, "{stats:?}");
+
+    // A scalar result holds no buffers
+    processor.parallel_sum(&data[..10]).unwrap();
+    let stats = processor.last_op().unwrap();
+    assert_eq!(stats.operation, "parallel_sum");
+    assert_eq!(stats.input_elements, 10);
+    assert_eq!(stats.bytes_allocated, 0);
+
+    // Failing before any parallel work still replaces the stats
+    processor.parallel_diff(&[1.0], 1).unwrap_err();
+    let stats = processor.last_op().unwrap();
+    assert_eq!(stats.operation, "parallel_diff");
+    assert_eq!((stats.pool_threads, stats.bytes_allocated), (0, 0));
+}
+
+#[test]
+fn an_operation_built_on_another_is_recorded_once() {
+    let mut batch = WasmBatchProcessor::with_runtime(64, &WasmRuntime::new(2).unwrap());
+    batch.set_profiling(true);
+
+    batch
+        .process_batch_str(&[1.0, 4.0, 9.0], "sqrt", false)
+        .unwrap();
+    let stats = batch.last_op().unwrap();
+    assert_eq!(stats.operation, "process_batch_str");
+    assert_eq!(stats.input_elements, 3);
+
+    batch
+        .process_batch(&[1.0, 4.0], BatchOp::Square, true)
+        .unwrap();
+    assert_eq!(batch.last_op().unwrap().operation, "process_batch");
+}
+
+#[test]
+fn rust_entry_points_are_profiled() {
+    let runtime = WasmRuntime::new(2).unwrap();
+    let mut parallel = WasmParallelProcessor::with_runtime(&runtime);
+    parallel.set_profiling(true);
+    parallel.parallel_argmin(&[3.0, 1.0, 2.0]).unwrap();
+    let stats = parallel.last_op().unwrap();
+    assert_eq!(
+        (stats.operation, stats.input_elements),
+        ("parallel_argmin", 3)
+    );
+
+    let mut batch = WasmBatchProcessor::with_runtime(64, &runtime);
+    batch.set_profiling(true);
+    batch
+        .apply_chain(&[1.0, 4.0], &[ChainStep::Sqrt, ChainStep::Square])
+        .unwrap();
+    let stats = batch.last_op().unwrap();
+    assert_eq!((stats.operation, stats.input_elements), ("apply_chain", 2));
+    assert!(stats.bytes_allocated >= 2 * 8, "{stats:?}");
+
+    batch.push_sample(1.0);
+    assert_eq!(batch.last_op().unwrap().operation, "push_sample");
+    batch.flush();
+    assert_eq!(batch.last_op().unwrap().operation, "flush");
+}
+
+#[test]
+fn processors_keep_their_own_stats() {
+    let runtime = WasmRuntime::new(2).unwrap();
+    let mut matrix = WasmMatrixProcessor::with_runtime(&runtime);
+    let parallel = WasmParallelProcessor::with_runtime(&runtime);
+    matrix.set_profiling(true);
+
+    let a = vec![1.0; 40 * 30];
+    let b = vec![2.0; 30 * 20];
+    matrix.multiply(&a, 40, 30, &b, 30, 20).unwrap();
+    parallel.parallel_sum(&[1, 2, 3]).unwrap();
+
+    let stats = matrix.last_op().unwrap();
+    assert_eq!(stats.operation, "multiply");
+    assert_eq!(stats.input_elements, 40 * 30);
+    assert!(stats.bytes_allocated >= 40 * 20 * 8, "{stats:?}");
+    assert_eq!(parallel.last_op(), None);
+}
@@ -0,0 +1,113 @@
+//! Background tasks on `WasmTaskQueue`: submitting, polling, cancelling
+//! and collecting results.
+#![cfg(not(target_arch = "wasm32"))]
+
+use std::time::{Duration, Instant};
+
+use web_learning_rust_examples::{WasmRuntime, WasmTaskQueue};
+
+/// Poll until `id` has finished, failing the test after a few seconds
+fn wait(queue: &WasmTaskQueue, id: u32) -> String {
+    let deadline = Instant::now() + Duration::from_secs(10);
+    loop {
+        let status = queue.status(id).unwrap();
+        if status != "pending" && status != "running" {
+            return status;
+        }
+        assert!(Instant::now() < deadline, "task {id} never finished");
+        std::thread::sleep(Duration::from_millis(1));
+    }
+}
+
+#[test]
+fn submitted_tasks_finish_in_the_background() {
+    let mut queue = WasmTaskQueue::new(8, 2).unwrap();
+    let data: Vec<f64> = (0..50_000).map(f64::from).collect();
+
+    let ids: Vec<u32> = ["square", "sqrt", "square|sqrt", "neg | abs"]
+        .iter()
+        .map(|expr| queue.submit_expr(&data, expr).unwrap())
+        .collect();
+    for &id in &ids {
+        assert_eq!(wait(&queue, id), "done");
+    }
+    assert_eq!(queue.task_count(), 4);
+    assert!(queue.retained_bytes() >= 4 * 50_000 * 8);
+
+    let squares = queue.take_result(ids[0]).unwrap();
+    assert_eq!(squares[300], 90_000.0);
+    assert_eq!(queue.take_result(ids[1]).unwrap()[49], 7.0);
+    assert_eq!(queue.take_result(ids[2]).unwrap(), data);
+    assert_eq!(queue.take_result(ids[3]).unwrap(), data);
+
+    assert_eq!(queue.task_count(), 0, "taken tasks should be forgotten");
+    assert_eq!(queue.retained_bytes(), 0);
+    assert_eq!(
+        queue.take_result(ids[0]).unwrap_err().code(),
+        "INVALID_ARGUMENT"
+    );
+}
+
+#[test]
+fn a_cancelled_task_never_completes() {
+    // One worker, kept busy by a long task, so the second is still queued
+    let mut queue = WasmTaskQueue::with_runtime(4, &WasmRuntime::new(1).unwrap()).unwrap();
+    let long: Vec<f64> = (0..4_000_000).map(f64::from).collect();
+    let busy = queue
+        .submit_expr(&long, "sqrt|exp|ln|square|sin|cos|tanh")
+        .unwrap();
+    let queued = queue.submit_expr(&[1.0, 2.0], "square").unwrap();
+
+    assert!(queue.cancel(queued).unwrap());
+    assert_eq!(queue.status(queued).unwrap(), "cancelled");
+    let error = queue.take_result(queued).unwrap_err();
+    assert_eq!(error.code(), "CANCELLED");
+
+    assert_eq!(wait(&queue, busy), "done");
+    assert!(
+        !queue.cancel(busy).unwrap(),
+        "a finished task cannot be cancelled"
+    );
+    assert_eq!(queue.take_result(busy).unwrap().len(), long.len());
+}
+
+#[test]
+fn unfinished_and_unknown_tasks_are_errors() {
+    let mut queue = WasmTaskQueue::with_runtime(4, &WasmRuntime::new(1).unwrap()).unwrap();
+    assert_eq!(
+        queue.submit_expr(&[1.0], "square|cube").unwrap_err().code(),
+        "UNSUPPORTED_OPERATION"
+    );
+    assert_eq!(
+        queue.submit_expr(&[1.0], " ").unwrap_err().code(),
+        "INVALID_ARGUMENT"
+    );
+    assert_eq!(queue.status(99).unwrap_err().code(), "INVALID_ARGUMENT");
+
+    let long: Vec<f64> = (0..4_000_000).map(f64::from).collect();
+    let id = queue.submit_expr(&long, "sqrt|exp|ln|sin").unwrap();
+    match queue.take_result(id) {
+        Err(error) => assert_eq!(error.code(), "RESOURCE_UNAVAILABLE"),
+        Ok(_) => panic!("a four-million element task finished at once"),
+    }
+    assert_eq!(queue.task_count(), 1, "an unfinished task should be kept");
+    wait(&queue, id);
+}
+
+#[test]
+fn old_results_are_evicted_past_the_retention_limit() {
+    let mut queue = WasmTaskQueue::new(2, 1).unwrap();
+    let mut ids = Vec::new();
+    for i in 0..5 {
+        let id = queue.submit_expr(&[f64::from(i)], "square").unwrap();
+        wait(&queue, id);
+        ids.push(id);
+    }
+
+    // Each submission keeps at most two finished tasks besides its own
+    assert_eq!(queue.task_count(), 3);
+    assert!(queue.status(ids[0]).is_err());
+    assert!(queue.status(ids[1]).is_err());
+    assert_eq!(queue.take_result(ids[4]).unwrap(), [16.0]);
+    assert!(WasmTaskQueue::new(0, 1).is_err());
+}
@@ -0,0 +1,216 @@
+//! Thread pool sharing and resizing; run natively with `cargo test`, since
+//! WASM builds without threads cannot start a dedicated pool.
+#![cfg(not(target_arch = "wasm32"))]
+
+use std::{
+    sync::{Mutex, MutexGuard},
+    thread,
+};
+
+use web_learning_rust_examples::{
+    hardware_concurrency, BatchOp, WasmBatchProcessor, WasmImageProcessor, WasmMatrixProcessor,
+    WasmParallelProcessor, WasmRuntime,
+};
+
+/// Thread checks look at every thread in the process, so tests must not
+/// start pools concurrently
+static SERIAL: Mutex<()> = Mutex::new(());
+
+fn serial() -> MutexGuard<'static, ()> {
+    SERIAL.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+#[test]
+fn processors_from_one_runtime_share_its_pool() {
+    let _guard = serial();
+    let runtime = WasmRuntime::new(2).unwrap();
+    let matrix = WasmMatrixProcessor::with_runtime(&runtime);
+    let parallel = WasmParallelProcessor::with_runtime(&runtime);
+    let image = WasmImageProcessor::with_runtime(&runtime);
+    let mut batch = WasmBatchProcessor::with_runtime(16, &runtime);
+
+    for shared in [
+        matrix.runtime(),
+        parallel.runtime(),
+        image.runtime(),
+        batch.runtime(),
+    ] {
+        assert!(shared.shares_pool_with(&runtime));
+        assert_eq!(shared.num_threads(), 2);
+    }
+    assert!(matrix.runtime().shares_pool_with(&parallel.runtime()));
+
+    // Work still runs correctly on the shared pool
+    assert_eq!(parallel.parallel_sum(&[1, 2, 3, 4]).unwrap(), 10);
+    let product = matrix
+        .multiply(&[1.0, 2.0, 3.0, 4.0], 2, 2, &[1.0, 0.0, 0.0, 1.0], 2, 2)
+        .unwrap();
+    assert_eq!(product, [1.0, 2.0, 3.0, 4.0]);
+    let squares = batch
+        .process_batch(&[1.0, 2.0, 3.0], BatchOp::Square, false)
+        .unwrap();
+    assert_eq!(squares, [1.0, 4.0, 9.0]);
+}
+
+#[test]
+fn constructors_share_a_pool_per_thread_count() {
+    let _guard = serial();
+    let a = WasmMatrixProcessor::new(2).unwrap();
+    let b = WasmParallelProcessor::new(2).unwrap();
+    let c = WasmBatchProcessor::new(16, 2).unwrap();
+    assert!(a.runtime().shares_pool_with(&b.runtime()));
+    assert!(a.runtime().shares_pool_with(&c.runtime()));
+    assert!(a
+        .runtime()
+        .shares_pool_with(&WasmRuntime::shared(2).unwrap()));
+    assert_eq!(a.current_num_threads(), 2);
+
+    let other = WasmMatrixProcessor::new(3).unwrap();
+    assert!(!a.runtime().shares_pool_with(&other.runtime()));
+    assert!(!a.runtime().shares_pool_with(&WasmRuntime::global()));
+
+    // An explicit runtime is still a pool of its own
+    assert!(!a.runtime().shares_pool_with(&WasmRuntime::new(2).unwrap()));
+}
+
+#[test]
+fn zero_threads_uses_the_global_runtime() {
+    let _guard = serial();
+    let matrix = WasmMatrixProcessor::new(0).unwrap();
+    let image = WasmImageProcessor::new(0).unwrap();
+    assert!(matrix.runtime().shares_pool_with(&image.runtime()));
+    assert!(matrix.runtime().shares_pool_with(&WasmRuntime::global()));
+    assert_eq!(
+        WasmRuntime::global().num_threads(),
+        rayon::current_num_threads()
+    );
+}
+
+#[test]
+fn hardware_concurrency_is_the_clamped_available_parallelism() {
+    let available = thread::available_parallelism().map_or(1, |n| n.get());
+    assert_eq!(hardware_concurrency(), available.clamp(1, 64));
+}
+
+#[test]
+fn results_are_unchanged_across_a_resize() {
+    let _guard = serial();
+    let runtime = WasmRuntime::new(4).unwrap();
+    let parallel = WasmParallelProcessor::with_runtime(&runtime);
+    let matrix = WasmMatrixProcessor::with_runtime(&runtime);
+    assert_eq!(parallel.current_num_threads(), 4);
+
+    let data: Vec<i32> = (0..100_000).map(|i| i * 7 - 3).collect();
+    let a: Vec<f64> = (0..64 * 64).map(|i| (i % 17) as f64 - 8.0).collect();
+    let before = (
+        parallel.parallel_sum(&data).unwrap(),
+        parallel.parallel_map_square(&data).unwrap(),
+        matrix.multiply(&a, 64, 64, &a, 64, 64).unwrap(),
+    );
+
+    runtime.resize(2).unwrap();
+    assert_eq!(parallel.current_num_threads(), 2);
+    assert_eq!(matrix.current_num_threads(), 2);
+    let after = (
+        parallel.parallel_sum(&data).unwrap(),
+        parallel.parallel_map_square(&data).unwrap(),
+        matrix.multiply(&a, 64, 64, &a, 64, 64).unwrap(),
+    );
+    assert_eq!(before, after);
+
+    runtime.resize(0).unwrap();
+    assert_eq!(
+        parallel.current_num_threads(),
+        hardware_concurrency(),
+        "0 should mean auto"
+    );
+}
+
+#[test]
+fn operations_in_flight_finish_on_the_old_pool() {
+    let _guard = serial();
+    let runtime = WasmRuntime::new(2).unwrap();
+    let parallel = WasmParallelProcessor::with_runtime(&runtime);
+    let data: Vec<i32> = (0..2_000_000).collect();
+    let expected = data.iter().fold(0i32, |a, &b| a.wrapping_add(b));
+
+    let sums = thread::scope(|scope| {
+        let running: Vec<_> = (0..4)
+            .map(|_| scope.spawn(|| parallel.parallel_sum(&data).unwrap()))
+            .collect();
+        for threads in [3, 1, 2] {
+            runtime.resize(threads).unwrap();
+        }
+        running
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .collect::<Vec<_>>()
+    });
+    assert!(sums.iter().all(|&sum| sum == expected));
+    assert_eq!(parallel.current_num_threads(), 2);
+}
+
+/// Worker checks through `/proc`, which only Linux provides
+#[cfg(target_os = "linux")]
+mod workers {
+    use std::{
+        collections::HashSet,
+        thread,
+        time::{Duration, Instant},
+    };
+
+    use super::*;
+
+    /// Names of this process's threads
+    fn thread_names() -> HashSet<String> {
+        std::fs::read_dir("/proc/self/task")
+            .unwrap()
+            .filter_map(|task| std::fs::read_to_string(task.ok()?.path().join("comm")).ok())
+            .map(|name| name.trim_end().to_string())
+            .collect()
+    }
+
+    /// Names of workers started since `existing` was taken
+    fn new_workers(existing: &HashSet<String>) -> HashSet<String> {
+        thread_names()
+            .difference(existing)
+            .filter(|name| name.starts_with("wrt"))
+            .cloned()
+            .collect()
+    }
+
+    /// Poll until `done` holds; workers name themselves, and exit, on their
+    /// own threads, so neither is visible straight away
+    fn wait_until(what: &str, mut done: impl FnMut() -> bool) {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while !done() {
+            assert!(Instant::now() < deadline, "timed out waiting until {what}");
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    #[test]
+    fn resizing_stops_the_old_workers() {
+        let _guard = serial();
+        let existing = thread_names();
+        let runtime = WasmRuntime::new(3).unwrap();
+        let parallel = WasmParallelProcessor::with_runtime(&runtime);
+        assert_eq!(parallel.parallel_sum(&[1, 2, 3]).unwrap(), 6);
+
+        // Workers are named `wrt<pool>-<index>`
+        wait_until("the pool has started 3 workers", || {
+            new_workers(&existing).len() == 3
+        });
+        let old = new_workers(&existing);
+
+        runtime.resize(1).unwrap();
+        assert_eq!(parallel.parallel_sum(&[1, 2, 3]).unwrap(), 6);
+
+        wait_until("the old workers have exited", || {
+            thread_names().is_disjoint(&old)
+        });
+        wait_until("only the new worker remains", || {
+            new_workers(&existing).len() == 1
+        });
+    }
+}
@@ -0,0 +1,1624 @@
+//! The wasm-bindgen API surface as a page sees it: typed arrays across the
+//! boundary, errors as JavaScript values and promises driven by the event
+//! loop. Run them in a headless browser with
+//! `wasm-pack test --headless --chrome -- --test wasm`.
+//!
+//! Every processor is built on [`runtime`], which asks for two workers and
+//! falls back to the calling thread where the page cannot start them, so
+//! the same assertions hold with and without threads.
+#![cfg(target_arch = "wasm32")]
+
+use js_sys::{Array, Function, Object, Promise, Reflect, Uint8Array, JSON};
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use wasm_bindgen_test::*;
+use web_learning_rust_examples::{
+    build_info, hardware_concurrency, init, memory_report, process_memory_bytes,
+    register_for_memory_report, reset_memory_peak, sparse_histogram_get_count, threading_support,
+    unregister_from_memory_report, BatchOp, BatchOp2, CancellationToken, FloodFill,
+    MemoryEfficientProcessor, NoiseParams, Padding2d, Rect, WasmBatchProcessor, WasmBloomFilter,
+    WasmEmbeddingIndex, WasmError, WasmImage, WasmImageProcessor, WasmKey, WasmMatrixProcessor,
+    WasmModule, WasmParallelProcessor, WasmRuntime, WasmTFIDF, WasmTaskQueue,
+};
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+/// A 16x16 baseline JFIF with 4:2:0 chroma subsampling, as in the Node runner
+const JPEG_BASE64: &str = "/9j/4AAQSkZJRgABAQEAAQABAAD/2wBDAAMCAgICAgMCAgIDAwMDBAYEBAQEBAgGBgUGCQgKCgkICQkKDA8MCgsOCwkJDRENDg8QEBEQCgwSExIQEw8QEBD/2wBDAQMDAwQDBAgEBAgQCwkLEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBD/wAARCAAQABADASIAAhEBAxEB/8QAFgABAQEAAAAAAAAAAAAAAAAABwQF/8QAJBAAAQQBBAICAwAAAAAAAAAAAQIDBAYFBwgSExEiABQJMTL/xAAVAQEBAAAAAAAAAAAAAAAAAAAABv/EACMRAAECBQMFAAAAAAAAAAAAAAECEQMEBQYhABIxFRZhgeH/2gAMAwEAAhEDEQA/ABSm0mobc8HmExLUlRzzEWPkJWW+ulrsaUVAseUgslSlH9LKuPryIKuWPZdskzXmm3fX5m2nF4GlVxx/HOpx4ks51+MiU/Iaad7UcUo4tILoS4kqcWkezS0hO/HvuRp0rO6hWnWO1UisZVuFi4GFeyEpmGepa5S5SWVPuciFKRFLgSrwetnyPIB+Vb4N9mKhQMzo5po9XLdDs9d6ZVix2VEhiL9kuNPxw2gEKcDQ/rs8AuA8VAe0vdl7VOYn+27flGAUgmITjbhSmCg3BYlyeWDkMolvw4KOp1KM6iCNvngZHwetf//Z";
+
+/// A runtime with two workers, or the calling thread where the page cannot
+/// start them
+fn runtime() -> WasmRuntime {
+    WasmRuntime::new(2)
+        .or_else(|error| {
+            assert_eq!(error.code(), "RESOURCE_UNAVAILABLE");
+            WasmRuntime::new(0)
+        })
+        .expect("a runtime of 0 threads always starts")
+}
+
+fn parallel() -> WasmParallelProcessor {
+    WasmParallelProcessor::with_runtime(&runtime())
+}
+
+fn images() -> WasmImageProcessor {
+    WasmImageProcessor::with_runtime(&runtime())
+}
+
+/// A 2x2 RGBA image: red, green, blue and translucent grey
+fn rgba_2x2() -> Vec<u8> {
+    vec![
+        255, 0, 0, 255, 0, 255, 0, 255, 0, 0, 255, 255, 128, 128, 128, 128,
+    ]
+}
+
+#[track_caller]
+fn code<T>(result: Result<T, WasmError>) -> &'static str {
+    match result {
+        Ok(_) => panic!("expected an error"),
+        Err(error) => error.code(),
+    }
+}
+
+/// The `code` of a rejected promise or thrown JavaScript value
+fn js_code(error: &JsValue) -> String {
+    Reflect::get(error, &"code".into())
+        .unwrap()
+        .as_string()
+        .expect("a WasmError has a string code")
+}
+
+fn get(value: &JsValue, name: &str) -> JsValue {
+    Reflect::get(value, &name.into()).unwrap()
+}
+
+fn json(text: &str) -> JsValue {
+    JSON::parse(text).unwrap()
+}
+
+fn strings(values: &[&str]) -> Array {
+    values
+        .iter()
+        .map(|&value| JsValue::from_str(value))
+        .collect()
+}
+
+fn close(actual: &[f64], expected: &[f64]) {
+    assert_eq!(actual.len(), expected.len(), "{actual:?} vs {expected:?}");
+    for (a, e) in actual.iter().zip(expected) {
+        assert!((a - e).abs() < 1e-9, "{actual:?} vs {expected:?}");
+    }
+}
+
+/// Resolve on a later turn of the event loop, via the host's `setTimeout`
+async fn next_tick() {
+    let set_timeout: Function = get(&js_sys::global(), "setTimeout").unchecked_into();
+    let promise = Promise::new(&mut |resolve, _| {
+        set_timeout.call1(&JsValue::NULL, &resolve).unwrap();
+    });
+    JsFuture::from(promise).await.unwrap();
+}
+
+#[wasm_bindgen_test]
+fn constructors_reject_bad_arguments() {
+    init(&JsValue::UNDEFINED).unwrap();
+    assert_eq!(
+        code(init(&json(r#"{"log_level":"loud"}"#))),
+        "UNSUPPORTED_OPERATION"
+    );
+    init(&json(r#"{"log_level":"warn"}"#)).unwrap();
+
+    assert_eq!(
+        code(WasmBatchProcessor::with_window(4, 0, 0)),
+        "INVALID_ARGUMENT"
+    );
+    assert_eq!(
+        code(WasmImage::from_rgba(vec![0; 15], 2, 2)),
+        "DIMENSION_MISMATCH"
+    );
+    assert_eq!(code(WasmTFIDF::new(0)), "INVALID_ARGUMENT");
+    assert_eq!(code(WasmTaskQueue::new(0, 0)), "INVALID_ARGUMENT");
+    assert_eq!(code(WasmBloomFilter::new(0, 0.01)), "INVALID_ARGUMENT");
+    assert_eq!(code(WasmBloomFilter::new(10, 1.0)), "INVALID_ARGUMENT");
+    assert_eq!(code(WasmBloomFilter::new(10, f64::NAN)), "INVALID_ARGUMENT");
+    assert_eq!(
+        code(WasmBloomFilter::deserialize(b"WBF0")),
+        "INVALID_ENCODING"
+    );
+}
+
+#[wasm_bindgen_test]
+fn thread_counts_fall_back_to_the_calling_thread() {
+    // Asking for workers either gets them or says why not
+    for result in [
+        WasmParallelProcessor::new(2).map(|p| p.current_num_threads()),
+        WasmBatchProcessor::new(16, 2).map(|p| p.current_num_threads()),
+        WasmImageProcessor::new(2).map(|p| p.current_num_threads()),
+        WasmMatrixProcessor::new(2).map(|p| p.current_num_threads()),
+    ] {
+        match result {
+            Ok(threads) => assert_eq!(threads, 2),
+            Err(error) => assert_eq!(error.code(), "RESOURCE_UNAVAILABLE"),
+        }
+    }
+
+    // 0 threads never fails, and the work still gets done
+    let processor = WasmParallelProcessor::new(0).unwrap();
+    assert!(processor.current_num_threads() >= 1);
+    assert_eq!(processor.parallel_sum(&[1, 2, 3, 4]).unwrap(), 10);
+}
+
+#[wasm_bindgen_test]
+fn errors_cross_the_boundary_as_js_errors() {
+    let error = JsValue::from(
+        WasmParallelProcessor::new(0)
+            .unwrap()
+            .parallel_gradient(&[1.0], 1.0)
+            .unwrap_err(),
+    );
+    let js_error: &js_sys::Error = error.dyn_ref().expect("a JavaScript Error");
+    assert_eq!(js_error.name(), "WasmError");
+    assert_eq!(js_code(&error), "DIMENSION_MISMATCH");
+    assert!(get(&error, "details").is_object());
+
+    let error = JsValue::from(
+        parallel()
+            .parallel_parse_ndjson_floats("{\"a\": 1}\n{\"a\": }", "a")
+            .unwrap_err(),
+    );
+    assert_eq!(js_code(&error), "INVALID_JSON");
+    assert_eq!(get(&get(&error, "details"), "line").as_f64(), Some(2.0));
+}
+
+#[wasm_bindgen_test]
+fn runtime_reports_and_resizes() {
+    let global = WasmRuntime::global();
+    assert!(WasmRuntime::new(0).unwrap().shares_pool_with(&global));
+    assert!(global.num_threads() >= 1);
+    assert!(hardware_concurrency() >= 1);
+
+    let runtime = runtime();
+    runtime.set_deterministic(true);
+    assert!(runtime.deterministic());
+    runtime.set_deterministic(false);
+    assert!(get(&runtime.pool_stats(), "hits").as_f64().is_some());
+    runtime.trim();
+    assert_eq!(runtime.heap_bytes(), 0);
+    if let Err(error) = runtime.resize(1) {
+        assert_eq!(error.code(), "RESOURCE_UNAVAILABLE");
+    }
+
+    let support = threading_support();
+    assert!(get(&support, "shared_array_buffer").as_bool().is_some());
+    assert!(get(&support, "recommended_threads").as_f64().unwrap() >= 1.0);
+
+    let token = CancellationToken::new();
+    assert!(!token.is_cancelled());
+    token.cancel();
+    assert!(token.is_cancelled());
+}
+
+#[wasm_bindgen_test]
+fn parallel_reductions() {
+    let mut processor = parallel();
+    assert!(!processor.deterministic());
+    processor.set_deterministic(true);
+    assert!(processor.deterministic());
+    processor.set_profiling(true);
+
+    assert_eq!(processor.parallel_sum(&[1, 2, 3, 4]).unwrap(), 10);
+    assert_eq!(
+        get(&processor.last_op_stats(), "operation")
+            .as_string()
+            .unwrap(),
+        "parallel_sum"
+    );
+    assert_eq!(
+        processor.runtime().num_threads(),
+        processor.current_num_threads()
+    );
+    assert_eq!(
+        processor.parallel_map_square(&[1, -2, 3]).unwrap(),
+        [1, 4, 9]
+    );
+    let counts = processor.parallel_count_values(&[0, 1, 1, 255]).unwrap();
+    assert_eq!(
+        (counts.len(), counts[0], counts[1], counts[255]),
+        (256, 1, 2, 1)
+    );
+
+    assert_eq!(processor.parallel_sum_f64(&[1.0, 2.0, 3.5]).unwrap(), 6.5);
+    assert_eq!(processor.parallel_variance(&[1.0, 3.0]).unwrap(), 1.0);
+    assert_eq!(processor.parallel_median(&[3.0, 1.0, 2.0]).unwrap(), 2.0);
+    assert_eq!(
+        processor
+            .parallel_median_absolute_deviation(&[1.0, 2.0, 3.0, 4.0, 5.0])
+            .unwrap(),
+        1.0
+    );
+    assert_eq!(code(processor.parallel_median(&[])), "INVALID_ARGUMENT");
+
+    let data = [2.0, -1.0, 7.0, 7.0, -1.0];
+    assert_eq!(processor.argmax_index(&data).unwrap(), Some(2));
+    assert_eq!(processor.argmax_value(&data).unwrap(), Some(7.0));
+    assert_eq!(processor.argmin_index(&data).unwrap(), Some(1));
+    assert_eq!(processor.argmin_value(&data).unwrap(), Some(-1.0));
+    assert_eq!(processor.argmax_index(&[]).unwrap(), None);
+
+    assert_eq!(
+        processor.parallel_shannon_entropy(&[0, 1, 0, 1]).unwrap(),
+        1.0
+    );
+    assert_eq!(
+        processor
+            .parallel_renyi_entropy(&[0, 1, 0, 1], 2.0)
+            .unwrap(),
+        1.0
+    );
+    assert_eq!(
+        processor.parallel_index_of_coincidence(&[5, 5, 5]).unwrap(),
+        1.0
+    );
+    assert_eq!(processor.parallel_chi_squared_uniformity(&[]).unwrap(), 0.0);
+    let bigrams = processor.parallel_bigram_frequency(b"abab").unwrap();
+    assert_eq!(bigrams.len(), 1 << 16);
+    assert_eq!(bigrams[usize::from(b'a') << 8 | usize::from(b'b')], 2);
+}
+
+#[wasm_bindgen_test]
+fn parallel_elementwise() {
+    let processor = parallel();
+    close(&processor.parallel_sigmoid(&[0.0]).unwrap(), &[0.5]);
+    close(&processor.parallel_tanh(&[0.0]).unwrap(), &[0.0]);
+    close(&processor.parallel_relu(&[-1.0, 2.0]).unwrap(), &[0.0, 2.0]);
+    close(
+        &processor.parallel_leaky_relu(&[-2.0, 2.0], 0.1).unwrap(),
+        &[-0.2, 2.0],
+    );
+    close(
+        &processor.parallel_elu(&[0.0, 1.0], 1.0).unwrap(),
+        &[0.0, 1.0],
+    );
+    close(&processor.parallel_gelu(&[0.0]).unwrap(), &[0.0]);
+
+    close(
+        &processor.parallel_diff(&[1.0, 4.0, 9.0], 1).unwrap(),
+        &[3.0, 5.0],
+    );
+    close(
+        &processor.parallel_gradient(&[1.0, 4.0, 9.0], 1.0).unwrap(),
+        &[3.0, 4.0, 5.0],
+    );
+    close(
+        &processor.parallel_cumtrapz(&[1.0, 1.0, 1.0], 0.5).unwrap(),
+        &[0.0, 0.5, 1.0],
+    );
+    close(
+        &processor
+            .parallel_rolling_z_score(&[1.0, 1.0, 1.0], 2)
+            .unwrap(),
+        &[0.0; 3],
+    );
+
+    // x² - 3x + 2, and the Chebyshev series T₁(x) = x
+    close(
+        &processor
+            .parallel_polyeval(&[1.0, -3.0, 2.0], &[0.0, 1.0, 3.0])
+            .unwrap(),
+        &[2.0, 0.0, 2.0],
+    );
+    close(
+        &processor
+            .parallel_polyeval_chebyshev(&[0.0, 1.0], &[0.5])
+            .unwrap(),
+        &[0.5],
+    );
+    close(
+        &processor
+            .parallel_fft_convolve(&[1.0, 2.0], &[1.0, 1.0])
+            .unwrap(),
+        &[1.0, 3.0, 2.0],
+    );
+
+    let haar = processor
+        .parallel_haar_transform(&[4.0, 2.0, 5.0, 5.0])
+        .unwrap();
+    close(&haar, &[4.0, -1.0, 1.0, 0.0]);
+    close(
+        &processor.parallel_haar_inverse(&haar).unwrap(),
+        &[4.0, 2.0, 5.0, 5.0],
+    );
+    assert_eq!(
+        processor
+            .parallel_haar_transform_2d(&[1.0; 4], 2, 2)
+            .unwrap()[0],
+        1.0
+    );
+    assert_eq!(
+        code(processor.parallel_haar_transform(&[0.0; 6])),
+        "INVALID_ARGUMENT"
+    );
+
+    let padding = Padding2d::new(1, 0, 0, 1);
+    let padded = processor
+        .parallel_pad_2d(&[1.0, 2.0, 3.0, 4.0], 2, 2, &padding)
+        .unwrap();
+    close(&padded, &[0.0, 0.0, 0.0, 1.0, 2.0, 0.0, 3.0, 4.0, 0.0]);
+    close(
+        &processor
+            .parallel_unpad_2d(&padded, 2, 2, &padding)
+            .unwrap(),
+        &[1.0, 2.0, 3.0, 4.0],
+    );
+    let wide = Padding2d::new(0, 0, 3, 0);
+    close(
+        &processor.parallel_pad_2d(&[1.0, 2.0], 1, 2, &wide).unwrap(),
+        &[0.0; 5],
+    );
+    assert_eq!(
+        code(processor.parallel_pad_2d(&[1.0], 1, 1, &Padding2d::new(0, 0, usize::MAX, 0))),
+        "INVALID_ARGUMENT"
+    );
+
+    let doubled = Function::new_with_args("x, i", "return x * 2 + i");
+    close(
+        &processor.parallel_map_js(&[1.0, 1.0], &doubled).unwrap(),
+        &[2.0, 3.0],
+    );
+    let throws = Function::new_with_args("", "throw new RangeError('nope')");
+    assert_eq!(
+        code(processor.parallel_map_js(&[1.0], &throws)),
+        "JS_EXCEPTION"
+    );
+}
+
+#[wasm_bindgen_test]
+fn parallel_geometry_and_statistics() {
+    let processor = parallel();
+    let points = [0.0, 0.0, 3.0, 4.0, 0.0, 1.0];
+    let distances = processor.parallel_distance_matrix(&points, 3, 2).unwrap();
+    assert_eq!(distances.len(), 6);
+    assert_eq!(distances[1], 5.0);
+    assert_eq!(
+        processor
+            .parallel_nearest_neighbors(&points, 3, 2, 1)
+            .unwrap(),
+        [2, 2, 0]
+    );
+    assert_eq!(
+        processor
+            .parallel_minkowski_distance(&[0.0, 0.0], &[3.0, 4.0], 2.0)
+            .unwrap(),
+        5.0
+    );
+    assert_eq!(
+        processor
+            .parallel_pairwise_minkowski(&points, 3, 2, 1.0)
+            .unwrap()[1],
+        7.0
+    );
+
+    let samples = [1.0, 2.0, 2.0, 4.0, 3.0, 6.0];
+    close(
+        &processor
+            .parallel_correlation_matrix(&samples, 3, 2)
+            .unwrap(),
+        &[1.0, 1.0, 1.0, 1.0],
+    );
+    close(
+        &processor
+            .parallel_covariance_matrix(&samples, 3, 2)
+            .unwrap(),
+        &[1.0, 2.0, 2.0, 4.0],
+    );
+
+    let same = processor
+        .parallel_haversine(&[51.5], &[0.0], &[51.5], &[0.0])
+        .unwrap();
+    close(&same, &[0.0]);
+    assert_eq!(
+        processor
+            .parallel_nearest_coordinate(0.0, 0.0, &[10.0, 1.0, 5.0], &[10.0, 1.0, 5.0])
+            .unwrap(),
+        1
+    );
+
+    close(
+        &processor
+            .parallel_group_by_sum(&[0, 1, 0], &[1.0, 2.0, 3.0], 2)
+            .unwrap(),
+        &[4.0, 2.0],
+    );
+    assert_eq!(
+        processor.parallel_group_by_count(&[0, 1, 0], 3).unwrap(),
+        [2, 1, 0]
+    );
+    close(
+        &processor
+            .parallel_group_by_max(&[0, 1, 0], &[1.0, 2.0, 3.0], 2)
+            .unwrap(),
+        &[3.0, 2.0],
+    );
+    assert_eq!(
+        code(processor.parallel_group_by_count(&[5], 2)),
+        "INVALID_ARGUMENT"
+    );
+
+    let line = processor
+        .parallel_bezier_sample(&[0.0, 0.0, 2.0, 2.0], 1, &[0.0, 0.5, 1.0])
+        .unwrap();
+    close(&line, &[0.0, 0.0, 1.0, 1.0, 2.0, 2.0]);
+    let curve = processor
+        .parallel_bezier_adaptive_sample(&[0.0, 0.0, 1.0, 2.0, 2.0, 0.0], 2, 0.01)
+        .unwrap();
+    close(&curve[..2], &[0.0, 0.0]);
+    close(&curve[curve.len() - 2..], &[2.0, 0.0]);
+
+    let series: Vec<f64> = (0..64).map(|i| 0.8f64.powi(i % 8)).collect();
+    let coeffs = processor.parallel_arima_fit(&series, 1, 0, 0).unwrap();
+    assert_eq!(coeffs.len(), 1);
+    assert_eq!(
+        processor
+            .arima_forecast(&series, &coeffs, 1, 0, 0, 3)
+            .unwrap()
+            .len(),
+        3
+    );
+    assert_eq!(
+        code(processor.parallel_arima_fit(&series, 0, 1, 0)),
+        "INVALID_ARGUMENT"
+    );
+
+    let sample = processor
+        .parallel_sample_without_replacement(100, 10, 7)
+        .unwrap();
+    let mut distinct = sample.clone();
+    distinct.sort_unstable();
+    distinct.dedup();
+    assert_eq!(distinct.len(), 10);
+    assert!(sample.iter().all(|&i| i < 100));
+    assert_eq!(
+        sample,
+        processor
+            .parallel_sample_without_replacement(100, 10, 7)
+            .unwrap()
+    );
+
+    assert_eq!(
+        processor
+            .parallel_jaccard_similarity(&[0b1100], &[0b0110])
+            .unwrap(),
+        1.0 / 3.0
+    );
+    assert_eq!(
+        processor
+            .parallel_pairwise_jaccard(&[0b11, 0b01, 0b11], 3, 1)
+            .unwrap(),
+        [0.5, 1.0, 0.5]
+    );
+}
+
+#[wasm_bindgen_test]
+fn parallel_encodings() {
+    let processor = parallel();
+    assert_eq!(
+        processor.parallel_radix_sort_u32(&[3, 1, 2]).unwrap(),
+        [1, 2, 3]
+    );
+    assert_eq!(
+        processor.parallel_radix_sort_u64(&[u64::MAX, 0]).unwrap(),
+        [0, u64::MAX]
+    );
+
+    assert_eq!(
+        processor.parallel_crc32c(b"123456789").unwrap(),
+        0xE306_9283
+    );
+    let (a, b) = (
+        processor.parallel_crc32c(b"1234").unwrap(),
+        processor.parallel_crc32c(b"56789").unwrap(),
+    );
+    assert_eq!(WasmParallelProcessor::crc32c_combine(a, b, 5), 0xE306_9283);
+
+    let values = [5, 7, 6, i64::MIN];
+    let deltas = processor.parallel_delta_encode(&values).unwrap();
+    assert_eq!(WasmParallelProcessor::delta_decode(&deltas), values);
+    let varints = processor
+        .parallel_varint_delta_encode(&[1, 2, 300])
+        .unwrap();
+    assert_eq!(
+        WasmParallelProcessor::varint_delta_decode(&varints).unwrap(),
+        [1, 2, 300]
+    );
+    assert_eq!(
+        code(WasmParallelProcessor::varint_delta_decode(&[0x80])),
+        "INVALID_ARGUMENT"
+    );
+
+    let text = b"abcabcabcabcabcabcabcabc".repeat(20);
+    let packed = processor.parallel_lz4_compress(&text).unwrap();
+    assert_eq!(
+        WasmParallelProcessor::lz4_decompress(&packed).unwrap(),
+        text
+    );
+
+    let histogram = processor.parallel_sparse_histogram(&[-4, 9, -4]).unwrap();
+    assert_eq!(sparse_histogram_get_count(&histogram, -4).unwrap(), 2);
+    assert_eq!(sparse_histogram_get_count(&histogram, 0).unwrap(), 0);
+
+    let units = processor.parallel_encode_utf16("héllo 🌍").unwrap();
+    assert_eq!(units.len(), 8);
+    assert_eq!(processor.parallel_decode_utf16(&units).unwrap(), "héllo 🌍");
+    assert_eq!(
+        code(processor.parallel_decode_utf16(&[0xD800])),
+        "INVALID_ENCODING"
+    );
+    assert!(processor
+        .parallel_utf8_validate("日本語".as_bytes())
+        .unwrap());
+    assert!(!processor.parallel_utf8_validate(&[0x61, 0xFF]).unwrap());
+}
+
+#[wasm_bindgen_test]
+fn parallel_text() {
+    let mut processor = parallel();
+    let lines = "{\"x\": 1.5}\n{\"y\": 2}\n{\"x\": \"no\"}";
+    let fields = processor.parallel_extract_field(lines, "x").unwrap();
+    assert_eq!(fields[0], 1.5);
+    assert!(fields[1].is_nan() && fields[2].is_nan());
+    let nested = processor
+        .parallel_parse_ndjson_floats("{\"m\": {\"cpu\": 0.25}}\n\n{}", "m.cpu")
+        .unwrap();
+    assert_eq!(nested[0], 0.25);
+    assert!(nested[1].is_nan());
+
+    let tokens = processor
+        .parallel_tokenize(&strings(&["Hello, World!", ""]))
+        .unwrap();
+    assert_eq!(tokens.length(), 2);
+    assert_eq!(
+        JSON::stringify(&tokens).unwrap(),
+        r#"[["hello","world"],[]]"#
+    );
+    assert_eq!(
+        processor
+            .parallel_tokenize_flat(&strings(&["a b", "b c"]))
+            .unwrap(),
+        [0, 1, 1, 2]
+    );
+    let vocabulary: Vec<_> = processor
+        .get_vocabulary()
+        .iter()
+        .filter_map(JsValue::as_string)
+        .collect();
+    assert_eq!(vocabulary, ["a", "b", "c"]);
+
+    let matches = processor
+        .parallel_multi_search(
+            vec!["aaa".into(), "xab".into()],
+            vec!["aa".into(), "b".into()],
+        )
+        .unwrap();
+    let matches = JSON::parse(&matches.as_string().unwrap()).unwrap();
+    assert_eq!(Array::from(&matches).length(), 3);
+    assert_eq!(
+        code(processor.parallel_multi_search(vec!["text".into()], vec![42.into()])),
+        "INVALID_ARGUMENT"
+    );
+
+    let distances = processor
+        .parallel_levenshtein_batch(
+            &strings(&["kitten", "flaw"]),
+            &strings(&["sitting", "lawn"]),
+        )
+        .unwrap();
+    assert_eq!(distances, [3, 2]);
+    assert_eq!(
+        processor
+            .parallel_closest_match("kitten", &strings(&["dog", "sitting", "mitten"]))
+            .unwrap(),
+        2
+    );
+    assert_eq!(
+        code(processor.parallel_levenshtein_batch(&strings(&["a", "b"]), &strings(&["a"]))),
+        "DIMENSION_MISMATCH"
+    );
+}
+
+#[wasm_bindgen_test]
+fn batch_operations() {
+    let mut processor = WasmBatchProcessor::with_runtime(4, &runtime());
+    processor.set_profiling(true);
+    assert_eq!(
+        processor.runtime().num_threads(),
+        processor.current_num_threads()
+    );
+
+    close(
+        &processor
+            .process_batch(&[1.0, 4.0, 9.0], BatchOp::Sqrt, true)
+            .unwrap(),
+        &[1.0, 2.0, 3.0],
+    );
+    assert_eq!(
+        get(&processor.last_op_stats(), "input_elements").as_f64(),
+        Some(3.0)
+    );
+    close(
+        &processor.process_batch_str(&[-2.0], "abs", false).unwrap(),
+        &[2.0],
+    );
+    assert_eq!(
+        code(processor.process_batch_str(&[1.0], "cube", false)),
+        "UNSUPPORTED_OPERATION"
+    );
+    assert_eq!(
+        code(processor.process_batch(&[-1.0], BatchOp::Ln, true)),
+        "OUT_OF_DOMAIN"
+    );
+    assert!(processor
+        .process_batch(&[-1.0], BatchOp::Sqrt, false)
+        .unwrap()[0]
+        .is_nan());
+
+    close(
+        &processor
+            .process_binary(&[1.0, 2.0], &[3.0, 4.0], BatchOp2::Mul, true)
+            .unwrap(),
+        &[3.0, 8.0],
+    );
+    close(
+        &processor
+            .process_scalar(&[1.0, 2.0], 2.0, BatchOp2::Pow, true)
+            .unwrap(),
+        &[1.0, 4.0],
+    );
+    assert_eq!(
+        code(processor.process_binary(&[1.0], &[1.0, 2.0], BatchOp2::Add, true)),
+        "DIMENSION_MISMATCH"
+    );
+    assert_eq!(
+        processor
+            .process_batch_f32(&[4.0], BatchOp::Sqrt, true)
+            .unwrap(),
+        [2.0]
+    );
+    assert_eq!(
+        processor
+            .process_binary_f32(&[1.0], &[2.0], BatchOp2::Max, true)
+            .unwrap(),
+        [2.0]
+    );
+
+    close(
+        &processor.fma_batch(&[1.0, 2.0], 3.0, 1.0).unwrap(),
+        &[4.0, 7.0],
+    );
+    close(
+        &processor
+            .fma_batch_vec(&[1.0, 2.0, 3.0], &[2.0, 3.0, 4.0], &[1.0, 1.0, -1.0])
+            .unwrap(),
+        &[3.0, 7.0, 11.0],
+    );
+    close(
+        &processor
+            .process_polynomial(&[2.0], &[1.0, 2.0, 3.0])
+            .unwrap(),
+        &[17.0],
+    );
+    close(
+        &processor
+            .process_piecewise_linear(&[-1.0, 0.5, 2.0], &[0.0, 1.0], &[0.0, 10.0], true)
+            .unwrap(),
+        &[0.0, 5.0, 10.0],
+    );
+    close(
+        &processor.process_complex(&[3.0, 4.0], "magnitude").unwrap(),
+        &[5.0],
+    );
+    assert_eq!(
+        code(processor.process_complex(&[1.0, 2.0], "modulus")),
+        "UNSUPPORTED_OPERATION"
+    );
+
+    let chain = json(r#"[{"op": "sqrt"}, {"op": "mul", "value": 2}]"#);
+    close(
+        &processor.process_chain(&[4.0, 9.0], &chain).unwrap(),
+        &[4.0, 6.0],
+    );
+    assert_eq!(
+        code(processor.process_chain(&[1.0], &json(r#"[{"op": "cube"}]"#))),
+        "INVALID_ARGUMENT"
+    );
+
+    let mut out = [0.0; 2];
+    processor
+        .process_batch_into(&[1.0, 2.0], BatchOp::Square, true, &mut out)
+        .unwrap();
+    close(&out, &[1.0, 4.0]);
+    processor.process_batch(&[3.0], BatchOp::Neg, true).unwrap();
+    assert_eq!(processor.last_output_view().to_vec(), [-3.0]);
+    assert_eq!(
+        code(processor.process_batch_into(&[1.0, 2.0], BatchOp::Square, true, &mut [0.0])),
+        "DIMENSION_MISMATCH"
+    );
+}
+
+#[wasm_bindgen_test]
+fn preallocation_grows_memory_for_the_inputs_too() {
+    let memory_bytes = || {
+        wasm_bindgen::memory()
+            .unchecked_into::<js_sys::WebAssembly::Memory>()
+            .buffer()
+            .unchecked_into::<js_sys::ArrayBuffer>()
+            .byte_length() as usize
+    };
+    let elements = 1 << 22;
+    let before = memory_bytes();
+    let _processor = WasmBatchProcessor::with_preallocated_memory(elements, 1).unwrap();
+    // The output buffer and the released staging block, less whatever the
+    // allocator already had spare
+    let grown = memory_bytes() - before;
+    assert!(grown >= elements * 8 * 3 / 2, "grew {grown} bytes");
+}
+
+#[wasm_bindgen_test]
+fn batch_capacity_and_windows() {
+    let mut strict = WasmBatchProcessor::with_strict_capacity(2, 0).unwrap();
+    assert!(strict.strict_capacity());
+    assert_eq!(strict.capacity(), 2);
+    assert_eq!(
+        code(strict.process_batch(&[1.0; 3], BatchOp::Abs, false)),
+        "CAPACITY_EXCEEDED"
+    );
+
+    let mut growable = WasmBatchProcessor::with_preallocated_memory(8, 2).unwrap();
+    assert!(growable.memory_bytes() >= 16 * 8);
+    growable.reserve(32);
+    growable.reserve_additional(64).unwrap();
+    assert!(growable.allocated_bytes() >= growable.used_bytes());
+    let allocated = growable.allocated_bytes();
+    assert_eq!(
+        code(growable.reserve_additional(usize::MAX)),
+        "INVALID_ARGUMENT"
+    );
+    assert_eq!(growable.allocated_bytes(), allocated);
+    growable.shrink_to_fit();
+    assert!(growable.heap_bytes() >= growable.used_bytes());
+
+    let mut window = WasmBatchProcessor::with_window(4, 0, 2).unwrap();
+    assert_eq!(window.window_size(), 2);
+    window.set_operation("max").unwrap();
+    assert_eq!(window.push_sample(1.0), None);
+    assert_eq!(window.push_sample(3.0), Some(3.0));
+    assert_eq!(window.push_sample(2.0), Some(3.0));
+    assert_eq!(window.flush(), Some(3.0));
+    assert_eq!(window.flush(), None);
+    assert_eq!(code(window.set_operation("mode")), "UNSUPPORTED_OPERATION");
+}
+
+#[wasm_bindgen_test]
+async fn batch_async_resolves_and_rejects() {
+    let processor = WasmBatchProcessor::with_runtime(4, &runtime());
+    let data: Vec<f64> = (0..100).map(f64::from).collect();
+    let promise =
+        processor.process_batch_async(&data, BatchOp::Square, true, 7, &CancellationToken::new());
+    let squares = js_sys::Float64Array::from(JsFuture::from(promise).await.unwrap()).to_vec();
+    assert_eq!(squares, data.iter().map(|x| x * x).collect::<Vec<_>>());
+
+    let promise =
+        processor.process_batch_async(&[-1.0], BatchOp::Ln, true, 1, &CancellationToken::new());
+    assert_eq!(
+        js_code(&JsFuture::from(promise).await.unwrap_err()),
+        "OUT_OF_DOMAIN"
+    );
+
+    let token = CancellationToken::new();
+    let promise = processor.process_batch_async(&data, BatchOp::Square, true, 1, &token);
+    token.cancel();
+    assert_eq!(
+        js_code(&JsFuture::from(promise).await.unwrap_err()),
+        "CANCELLED"
+    );
+}
+
+#[wasm_bindgen_test]
+fn matrix_operations() {
+    let mut processor = WasmMatrixProcessor::with_runtime(&runtime());
+    processor.set_profiling(true);
+    assert_eq!(
+        processor.runtime().num_threads(),
+        processor.current_num_threads()
+    );
+
+    let product = processor
+        .multiply(&[1.0, 2.0, 3.0, 4.0], 2, 2, &[5.0, 6.0, 7.0, 8.0], 2, 2)
+        .unwrap();
+    close(&product, &[19.0, 22.0, 43.0, 50.0]);
+    assert_eq!(
+        get(&processor.last_op_stats(), "operation")
+            .as_string()
+            .unwrap(),
+        "multiply"
+    );
+    assert_eq!(
+        code(processor.multiply(&[1.0; 6], 2, 3, &[1.0; 4], 2, 2)),
+        "DIMENSION_MISMATCH"
+    );
+    close(
+        &processor
+            .transpose(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0], 2, 3)
+            .unwrap(),
+        &[1.0, 4.0, 2.0, 5.0, 3.0, 6.0],
+    );
+
+    close(
+        &processor
+            .solve_linear_system(&[2.0, 0.0, 0.0, 4.0], &[2.0, 8.0], 2)
+            .unwrap(),
+        &[1.0, 2.0],
+    );
+    let mut out = [0.0; 2];
+    processor
+        .solve_linear_system_into(&[1.0, 1.0, 0.0, 1.0], &[3.0, 1.0], 2, &mut out)
+        .unwrap();
+    close(&out, &[2.0, 1.0]);
+    assert_eq!(
+        code(processor.solve_linear_system(&[1.0, 2.0, 2.0, 4.0], &[1.0, 2.0], 2)),
+        "SINGULAR"
+    );
+    // 65536² wraps a 32-bit `usize` to 0
+    assert_eq!(
+        code(processor.solve_linear_system(&[], &[], 65_536)),
+        "INVALID_ARGUMENT"
+    );
+    assert_eq!(
+        code(processor.multiply(&[], 65_536, 0, &[], 0, 65_536)),
+        "INVALID_ARGUMENT"
+    );
+    // y = 1 + 2x through three exact points
+    let fit = processor
+        .solve_least_squares(&[1.0, 0.0, 1.0, 1.0, 1.0, 2.0], &[1.0, 3.0, 5.0], 3, 2)
+        .unwrap();
+    close(&fit, &[1.0, 2.0]);
+    assert!(processor.scratch_bytes() > 0);
+    processor.scratch_reset();
+    assert_eq!(processor.scratch_bytes(), 0);
+
+    let svd = processor.svd_2x2(&[3.0, 0.0, 0.0, 2.0]).unwrap();
+    close(
+        &js_sys::Float64Array::from(get(&svd, "sigma")).to_vec(),
+        &[3.0, 2.0],
+    );
+    let svd = processor
+        .svd_3x3(&[1.0, 0.0, 0.0, 0.0, 5.0, 0.0, 0.0, 0.0, 2.0])
+        .unwrap();
+    close(
+        &js_sys::Float64Array::from(get(&svd, "sigma")).to_vec(),
+        &[5.0, 2.0, 1.0],
+    );
+    close(
+        &processor.pseudo_inverse_2x2(&[2.0, 0.0, 0.0, 0.0]).unwrap(),
+        &[0.5, 0.0, 0.0, 0.0],
+    );
+    close(
+        &processor
+            .pseudo_inverse_3x3(&[2.0, 0.0, 0.0, 0.0, 4.0, 0.0, 0.0, 0.0, 1.0])
+            .unwrap(),
+        &[0.5, 0.0, 0.0, 0.0, 0.25, 0.0, 0.0, 0.0, 1.0],
+    );
+}
+
+#[wasm_bindgen_test]
+fn image_colour_filters() {
+    let mut processor = images();
+    processor.set_profiling(true);
+    assert_eq!(
+        processor.runtime().num_threads(),
+        processor.current_num_threads()
+    );
+    let rgba = rgba_2x2();
+
+    let grey = processor.grayscale(&rgba).unwrap();
+    assert_eq!(grey.len(), rgba.len());
+    assert!(grey.chunks(4).all(|p| p[0] == p[1] && p[1] == p[2]));
+    assert_eq!(
+        get(&processor.last_op_stats(), "operation")
+            .as_string()
+            .unwrap(),
+        "grayscale"
+    );
+    assert_eq!(
+        code(processor.extract_channel(&rgba, 4)),
+        "INVALID_ARGUMENT"
+    );
+    assert_eq!(
+        processor.adjust_brightness(&rgba, 0.0).unwrap()[..3],
+        [0, 0, 0]
+    );
+    assert_eq!(
+        processor.invert(&rgba, 1.0).unwrap()[..4],
+        [0, 255, 255, 255]
+    );
+    assert_eq!(processor.sepia(&rgba, 0.0).unwrap(), rgba);
+    assert_eq!(processor.saturate(&rgba, 1.0).unwrap(), rgba);
+    assert_eq!(processor.hue_rotate(&rgba, 0.0).unwrap(), rgba);
+    let identity = [
+        1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0,
+        1.0, 0.0,
+    ];
+    assert_eq!(
+        processor.apply_color_matrix(&rgba, &identity).unwrap(),
+        rgba
+    );
+    assert_eq!(
+        code(processor.apply_color_matrix(&rgba, &identity[..19])),
+        "DIMENSION_MISMATCH"
+    );
+
+    let premultiplied = processor.premultiply_alpha(&rgba).unwrap();
+    assert_eq!(premultiplied[12..], [64, 64, 64, 128]);
+    assert_eq!(
+        processor.unpremultiply_alpha(&premultiplied).unwrap()[..12],
+        rgba[..12]
+    );
+
+    let opaque = &rgba[..12];
+    assert_eq!(
+        processor.blend(opaque, opaque, "normal", 1.0).unwrap(),
+        opaque
+    );
+    assert_eq!(
+        code(processor.blend(&rgba, &rgba[..4], "normal", 1.0)),
+        "INVALID_ARGUMENT"
+    );
+
+    let red = processor.extract_channel(&rgba, 0).unwrap();
+    assert_eq!(red, [255, 0, 0, 128]);
+    let planes: Vec<_> = (1..4)
+        .map(|c| processor.extract_channel(&rgba, c).unwrap())
+        .collect();
+    let merged = processor
+        .merge_channels(&red, &planes[0], &planes[1], Some(planes[2].clone()))
+        .unwrap();
+    assert_eq!(merged, rgba);
+    let bgra = processor.swap_channels(&rgba, &[2, 1, 0, 3]).unwrap();
+    assert_eq!(bgra[..4], [0, 0, 255, 255]);
+    assert_eq!(
+        code(processor.swap_channels(&rgba, &[0, 0, 1, 2])),
+        "INVALID_ARGUMENT"
+    );
+
+    let ycbcr = processor.convert_colorspace(&rgba, "rgb", "ycbcr").unwrap();
+    let back = processor
+        .convert_colorspace(&ycbcr, "ycbcr", "rgb")
+        .unwrap();
+    assert!(back.iter().zip(&rgba).all(|(&a, &b)| a.abs_diff(b) <= 1));
+    let hsv = processor.rgba_to_colorspace_f32(&rgba, "hsv").unwrap();
+    let back = processor.colorspace_f32_to_rgba(&hsv, "hsv").unwrap();
+    assert!(back.iter().zip(&rgba).all(|(&a, &b)| a.abs_diff(b) <= 1));
+    assert_eq!(
+        code(processor.convert_colorspace(&rgba, "rgb", "lab")),
+        "UNSUPPORTED_OPERATION"
+    );
+
+    assert_eq!(processor.threshold(&rgba, 127).unwrap().len(), rgba.len());
+    let otsu = processor.otsu_threshold(&rgba).unwrap();
+    assert!(otsu < 255);
+    let stats = processor.image_statistics(&rgba).unwrap();
+    assert_eq!(stats.len(), 16);
+    assert_eq!((stats[2], stats[3]), (0.0, 255.0));
+    let palette = processor
+        .extract_dominant_colors(&[9, 9, 9, 255].repeat(4), 2, 10)
+        .unwrap();
+    assert_eq!(palette, [9, 9, 9, 255]);
+}
+
+#[wasm_bindgen_test]
+fn image_spatial_filters() {
+    let mut processor = images();
+    let (width, height) = (4u32, 4u32);
+    let rgba: Vec<u8> = (0..16u8)
+        .flat_map(|i| [i * 16, i * 16, i * 16, 255])
+        .collect();
+    let len = rgba.len();
+
+    assert_eq!(
+        processor.sobel_edges(&rgba, width, height).unwrap().len(),
+        len
+    );
+    assert_eq!(
+        processor
+            .canny_edges(&rgba, width, height, 20.0, 40.0)
+            .unwrap()
+            .len(),
+        len
+    );
+    assert_eq!(
+        processor
+            .fast_box_blur(&rgba, width, height, 1)
+            .unwrap()
+            .len(),
+        len
+    );
+    assert_eq!(
+        processor
+            .adaptive_threshold(&rgba, width, height, 3, 0)
+            .unwrap()
+            .len(),
+        len
+    );
+    assert_eq!(
+        code(processor.adaptive_threshold(&rgba, width, height, 4, 0)),
+        "INVALID_ARGUMENT"
+    );
+    assert_eq!(
+        processor
+            .dither_floyd_steinberg(&rgba, width, height, &[0, 0, 0, 255, 255, 255])
+            .unwrap()
+            .len(),
+        len
+    );
+    assert_eq!(
+        processor
+            .dither_ordered(&rgba, width, height, 1)
+            .unwrap()
+            .len(),
+        len
+    );
+    let filled = processor
+        .flood_fill(
+            &[0; 64],
+            4,
+            4,
+            &FloodFill::new(1, 1, &[255, 0, 0, 255], 0).unwrap(),
+        )
+        .unwrap();
+    assert!(filled.chunks(4).all(|p| p == [255, 0, 0, 255]));
+    assert_eq!(
+        code(processor.flood_fill(&[0; 64], 4, 4, &FloodFill::new(9, 1, &[0; 4], 0).unwrap())),
+        "INVALID_ARGUMENT"
+    );
+    assert_eq!(code(FloodFill::new(0, 0, &[0; 3], 0)), "DIMENSION_MISMATCH");
+    // 65536x16384 RGBA bytes wrap a 32-bit `usize` to 0
+    assert_eq!(
+        code(processor.flood_fill(
+            &[],
+            65_536,
+            16_384,
+            &FloodFill::new(0, 0, &[0; 4], 0).unwrap()
+        )),
+        "INVALID_ARGUMENT"
+    );
+
+    let gray = [1, 2, 3, 4, 5, 6];
+    let sat = processor.integral_image(&gray, 3, 2).unwrap();
+    assert_eq!(
+        processor
+            .box_sum(&sat, 3, 2, &Rect::new(0, 0, 3, 2))
+            .unwrap(),
+        21.0
+    );
+    assert_eq!(
+        processor
+            .box_sum(&sat, 3, 2, &Rect::new(1, 1, 2, 1))
+            .unwrap(),
+        11.0
+    );
+    // Coordinates and sizes that would wrap around are rejected, not wrapped
+    assert_eq!(
+        code(processor.box_sum(&sat, 3, 2, &Rect::new(1, 0, usize::MAX, 1))),
+        "INVALID_ARGUMENT"
+    );
+    assert_eq!(
+        code(processor.box_sum(&[], usize::MAX, 0, &Rect::default())),
+        "INVALID_ARGUMENT"
+    );
+    assert_eq!(
+        code(processor.integral_image(&[], usize::MAX, 2)),
+        "INVALID_ARGUMENT"
+    );
+
+    let mut mask = vec![0u8; 9];
+    mask[0] = 1;
+    mask[8] = 1;
+    let blobs = processor.connected_components(&mask, 3, 3, false).unwrap();
+    assert_eq!(get(&blobs, "num_labels").as_f64(), Some(2.0));
+
+    let mipmaps = processor.generate_mipmaps(&rgba, width, height).unwrap();
+    assert_eq!(u32::from_le_bytes(mipmaps[..4].try_into().unwrap()), 3);
+    let still = processor
+        .optical_flow_lucas_kanade(&[7; 16], &[7; 16], width, height, 3)
+        .unwrap();
+    assert_eq!(still, [0.0; 32]);
+
+    let params = NoiseParams::new(4.0, 2, 0.5, 2.0, 42);
+    let noise = processor.generate_perlin_noise(8, 4, &params).unwrap();
+    assert_eq!(noise.len(), 32);
+    assert_eq!(
+        noise,
+        processor.generate_perlin_noise(8, 4, &params).unwrap()
+    );
+    let stops = [0, 0, 64, 255, 255, 255, 255, 255];
+    assert_eq!(
+        processor
+            .generate_perlin_noise_rgba(8, 4, &params, &stops)
+            .unwrap()
+            .len(),
+        128
+    );
+    // Sizes that wrap a 32-bit `usize` are rejected rather than left empty
+    assert_eq!(
+        code(processor.generate_perlin_noise(65_536, 65_536, &params)),
+        "INVALID_ARGUMENT"
+    );
+    assert_eq!(
+        code(processor.generate_perlin_noise_rgba(65_536, 16_384, &params, &stops)),
+        "INVALID_ARGUMENT"
+    );
+
+    processor.pipeline_begin();
+    processor
+        .pipeline_add("grayscale", &JsValue::UNDEFINED)
+        .unwrap();
+    processor.pipeline_add("invert", &1.0.into()).unwrap();
+    assert_eq!(processor.pipeline_length(), 2);
+    let piped = processor.pipeline_run(&rgba).unwrap();
+    let grey = processor.grayscale(&rgba).unwrap();
+    let inverted = processor.invert(&grey, 1.0).unwrap();
+    assert_eq!(piped, inverted);
+    assert_eq!(
+        code(processor.pipeline_add("blur", &JsValue::UNDEFINED)),
+        "INVALID_ARGUMENT"
+    );
+}
+
+#[wasm_bindgen_test]
+fn image_frames_and_wasm_images() {
+    let mut processor = images();
+    let rgba = rgba_2x2();
+
+    processor.load_frame(&rgba, 2, 2).unwrap();
+    assert_eq!((processor.frame_width(), processor.frame_height()), (2, 2));
+    assert_eq!(processor.frame_len(), rgba.len());
+    assert!(!processor.frame_ptr().is_null());
+    processor.op_grayscale().unwrap();
+    processor.op_brightness(1.0).unwrap();
+    processor.op_sepia(0.0).unwrap();
+    processor.op_invert(0.0).unwrap();
+    processor.op_saturate(1.0).unwrap();
+    processor.op_hue_rotate(0.0).unwrap();
+    processor
+        .op_color_matrix(&[
+            1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0,
+            0.0, 1.0, 0.0,
+        ])
+        .unwrap();
+    assert_eq!(
+        processor.frame_view().to_vec(),
+        processor.grayscale(&rgba).unwrap()
+    );
+    assert!(processor.heap_bytes() >= rgba.len());
+    assert_eq!(
+        code(processor.load_frame(&rgba, 3, 2)),
+        "DIMENSION_MISMATCH"
+    );
+
+    let blank = WasmImage::new(3, 2).unwrap();
+    assert_eq!(
+        (blank.width(), blank.height(), blank.data().len()),
+        (3, 2, 24)
+    );
+    let image = WasmImage::from_rgba(rgba.clone(), 2, 2).unwrap();
+    processor.load_frame_image(&image).unwrap();
+    assert_eq!(processor.frame_view().to_vec(), rgba);
+    assert_eq!(
+        processor.fast_box_blur_image(&image, 0).unwrap().data(),
+        rgba
+    );
+    assert_eq!(
+        processor.dither_ordered_image(&image, 8).unwrap().width(),
+        2
+    );
+    assert_eq!(
+        processor
+            .dither_floyd_steinberg_image(&image, &[0, 0, 0])
+            .unwrap()
+            .height(),
+        2
+    );
+    assert_eq!(
+        processor
+            .adaptive_threshold_image(&image, 1, 0)
+            .unwrap()
+            .data()
+            .len(),
+        16
+    );
+    let filled = processor
+        .flood_fill_image(&image, &FloodFill::new(0, 0, &[1, 2, 3, 4], 0).unwrap())
+        .unwrap();
+    assert_eq!(filled.data()[..4], [1, 2, 3, 4]);
+
+    let plain = Object::new();
+    Reflect::set(&plain, &"data".into(), &Uint8Array::from(&rgba[..])).unwrap();
+    Reflect::set(&plain, &"width".into(), &2.into()).unwrap();
+    Reflect::set(&plain, &"height".into(), &2.into()).unwrap();
+    let thumbnails = processor
+        .batch_thumbnail(Array::of2(&image.into(), &plain), 1, 1, "cover")
+        .unwrap();
+    assert_eq!(thumbnails.length(), 2);
+    assert_eq!(
+        code(processor.batch_thumbnail(Array::new(), 1, 1, "stretch")),
+        "UNSUPPORTED_OPERATION"
+    );
+
+    let module = WasmModule::new();
+    let jpeg = module.decode(JPEG_BASE64, "base64").unwrap().to_vec();
+    let decoded = processor.decode_jpeg(&jpeg).unwrap();
+    assert_eq!(
+        (decoded.width(), decoded.height(), decoded.data().len()),
+        (16, 16, 1024)
+    );
+    assert_eq!(
+        code(processor.decode_jpeg(&jpeg[..100])),
+        "INVALID_ENCODING"
+    );
+    // Claim 65535x65535 in the frame header
+    let mut huge = jpeg.clone();
+    let sof = huge.windows(2).position(|w| w == [0xFF, 0xC0]).unwrap();
+    huge[sof + 5..sof + 9].fill(0xFF);
+    assert_eq!(code(processor.decode_jpeg(&huge)), "INVALID_ARGUMENT");
+}
+
+#[wasm_bindgen_test]
+fn wasm_image_converts_to_and_from_image_data() {
+    let image = WasmImage::from_rgba(rgba_2x2(), 2, 2).unwrap();
+    let image_data = image.to_image_data().unwrap();
+    assert_eq!((image_data.width(), image_data.height()), (2, 2));
+    assert_eq!(image_data.data().to_vec(), rgba_2x2());
+    assert_eq!(WasmImage::from_image_data(&image_data).data(), rgba_2x2());
+}
+
+#[wasm_bindgen_test]
+fn module_transforms_and_cache() {
+    let mut module = WasmModule::with_cache_limits(4, 1 << 20);
+    let input = Uint8Array::from(&[1u8, 2, 3, 4][..]);
+    assert_eq!(
+        module
+            .process_data(&input, None, &JsValue::UNDEFINED)
+            .unwrap()
+            .to_vec(),
+        [0xAE, 0xA9, 0xA8, 0xAB]
+    );
+    let rot = module
+        .process_data(&input, Some("rot_n".into()), &json(r#"{"n": 1}"#))
+        .unwrap();
+    assert_eq!(rot.to_vec(), [2, 3, 4, 5]);
+    let odd = Uint8Array::from(&[1u8, 2, 3][..]);
+    assert_eq!(
+        code(module.process_data(&odd, Some("delta_encode_u32".into()), &JsValue::UNDEFINED)),
+        "INVALID_ARGUMENT"
+    );
+    assert!(module.list_transforms().iter().any(|name| name == "rle"));
+
+    assert_eq!(module.cache_size(), 2);
+    assert!(module.cache_bytes() > 0 && module.heap_bytes() >= module.cache_bytes());
+    assert_eq!(module.cache_keys().len(), 2);
+    assert!(module
+        .cache_keys()
+        .iter()
+        .all(|key| module.cache_contains(key)));
+    assert_eq!(get(&module.cache_stats(), "insertions").as_f64(), Some(2.0));
+    module.reset_stats();
+    assert_eq!(get(&module.cache_stats(), "insertions").as_f64(), Some(0.0));
+    module.set_cache_limits(1, 1 << 20);
+    assert_eq!(module.cache_size(), 1);
+    module.clear_cache();
+    assert_eq!(module.cache_size(), 0);
+}
+
+#[wasm_bindgen_test]
+async fn module_async_processing_reports_progress() {
+    let mut module = WasmModule::new();
+    let input = Uint8Array::from(&[1u8, 2, 3, 4][..]);
+    let calls = Array::new();
+    let on_progress =
+        Function::new_with_args("done, total", "this.push([done, total])").bind0(&calls);
+    let result = JsFuture::from(module.process_data_async(&input, Some(on_progress)))
+        .await
+        .unwrap();
+    assert_eq!(Uint8Array::from(result).to_vec(), [1, 3, 5, 7]);
+    assert_eq!(JSON::stringify(&calls).unwrap(), "[[4,4]]");
+
+    let throws = Function::new_with_args("", "throw new Error('stop')");
+    let other = Uint8Array::from(&[9u8; 8][..]);
+    let error = JsFuture::from(module.process_data_async(&other, Some(throws)))
+        .await
+        .unwrap_err();
+    assert_eq!(error.dyn_into::<js_sys::Error>().unwrap().message(), "stop");
+}
+
+#[wasm_bindgen_test]
+fn module_hashing_encoding_and_crypto() {
+    let mut module = WasmModule::new();
+    let abc = Uint8Array::from(&b"abc"[..]);
+    let sha256 = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+    assert_eq!(module.hash(&abc, "sha256").unwrap(), sha256);
+    assert_eq!(code(module.hash(&abc, "md5")), "UNSUPPORTED_OPERATION");
+    let handle = module.hash_init("sha256").unwrap();
+    module
+        .hash_update(handle, &Uint8Array::from(&b"ab"[..]))
+        .unwrap();
+    module
+        .hash_update(handle, &Uint8Array::from(&b"c"[..]))
+        .unwrap();
+    assert_eq!(module.hash_finalize(handle).unwrap(), sha256);
+    assert_eq!(code(module.hash_finalize(handle)), "INVALID_ARGUMENT");
+
+    assert_eq!(module.encode(&abc, "base64").unwrap(), "YWJj");
+    assert_eq!(module.encode(&abc, "hex").unwrap(), "616263");
+    assert_eq!(module.decode("616263", "hex").unwrap().to_vec(), b"abc");
+    assert_eq!(code(module.decode("YW*j", "base64")), "INVALID_ENCODING");
+    assert_eq!(code(module.encode(&abc, "base32")), "UNSUPPORTED_OPERATION");
+
+    let text = Uint8Array::from(&b"the quick brown fox ".repeat(50)[..]);
+    for format in ["gzip", "zlib", "deflate"] {
+        let compressed = module.compress(&text, format, 6).unwrap();
+        assert!(compressed.length() < text.length());
+        assert_eq!(
+            module
+                .decompress(&compressed, format, 1 << 20)
+                .unwrap()
+                .to_vec(),
+            text.to_vec()
+        );
+    }
+    assert_eq!(code(module.compress(&text, "gzip", 10)), "INVALID_ARGUMENT");
+
+    let salt = Uint8Array::from(&b"salt"[..]);
+    let key = WasmModule::derive_key("password".into(), &salt, 1).unwrap();
+    assert_eq!(
+        code(WasmModule::derive_key("password".into(), &salt, 0)),
+        "INVALID_ARGUMENT"
+    );
+    // The derived key is opaque, so check it against the RFC 7914 vector
+    // by decrypting with the expected bytes
+    let expected = module
+        .decode(
+            "120fb6cffcf8b32c43e7225256c4f837a86548c92ccc35480805987cb70be17b",
+            "hex",
+        )
+        .unwrap();
+    let imported = WasmKey::from_bytes(&expected).unwrap();
+    let sealed = module
+        .encrypt(&Uint8Array::new_with_length(3), &key, None)
+        .unwrap();
+    assert_eq!(
+        module.decrypt(&sealed, &imported, None).unwrap().to_vec(),
+        [0; 3]
+    );
+    assert_eq!(
+        code(WasmKey::from_bytes(&Uint8Array::new_with_length(16))),
+        "DIMENSION_MISMATCH"
+    );
+
+    let plaintext = Uint8Array::from(&b"attack at dawn"[..]);
+    let aad = Uint8Array::from(&b"message 1"[..]);
+    let sealed = module.encrypt(&plaintext, &key, Some(aad.clone())).unwrap();
+    assert_eq!(sealed.length(), 12 + 14 + 16);
+    assert_eq!(
+        module.decrypt(&sealed, &key, Some(aad)).unwrap().to_vec(),
+        plaintext.to_vec()
+    );
+    assert_eq!(
+        code(module.decrypt(&sealed, &key, None)),
+        "AUTHENTICATION_FAILED"
+    );
+}
+
+#[wasm_bindgen_test]
+fn module_json_streams_and_text() {
+    let mut module = WasmModule::new();
+    assert_eq!(
+        module
+            .process_json(r#" {"b": [1, 2], "a": null} "#, &Array::new())
+            .unwrap(),
+        r#"{"a":null,"b":[1,2]}"#
+    );
+    let renamed = module
+        .process_json(
+            r#"{"a": {"b": 1}}"#,
+            &json(r#"[{"op": "rename", "from": "a.b", "to": "c"}]"#),
+        )
+        .unwrap();
+    assert_eq!(renamed, r#"{"a":{},"c":1}"#);
+    let error = module
+        .process_json("{\n  \"a\": ]\n}", &Array::new())
+        .unwrap_err();
+    assert_eq!(error.code(), "INVALID_JSON");
+
+    let cbor = module.json_to_cbor(r#"{"a":1}"#).unwrap();
+    assert_eq!(cbor.to_vec(), [0xA1, 0x61, 0x61, 0x01]);
+    assert_eq!(module.cbor_to_json(&cbor).unwrap(), r#"{"a":1}"#);
+    assert_eq!(
+        code(module.cbor_to_json(&Uint8Array::from(&[0xA1u8, 0x61][..]))),
+        "INVALID_ARGUMENT"
+    );
+
+    let session = module.begin_stream("hash:sha256", 32).unwrap();
+    assert_eq!(module.open_streams(), 1);
+    module
+        .stream_push(session, &Uint8Array::from(&b"ab"[..]))
+        .unwrap();
+    module
+        .stream_push(session, &Uint8Array::from(&b"c"[..]))
+        .unwrap();
+    let digest = module.stream_finish(session).unwrap();
+    assert_eq!(
+        module.encode(&digest, "hex").unwrap(),
+        module
+            .hash(&Uint8Array::from(&b"abc"[..]), "sha256")
+            .unwrap()
+    );
+    let short = module.begin_stream("hash:sha256", 31).unwrap();
+    assert_eq!(code(module.stream_finish(short)), "INVALID_ARGUMENT");
+    let aborted = module.begin_stream("compress:gzip", 1 << 20).unwrap();
+    module.stream_abort(aborted).unwrap();
+    assert_eq!(module.open_streams(), 0);
+    assert_eq!(
+        code(module.stream_push(aborted, &Uint8Array::new_with_length(1))),
+        "INVALID_ARGUMENT"
+    );
+    assert_eq!(
+        code(module.begin_stream("rot13", 1 << 20)),
+        "UNSUPPORTED_OPERATION"
+    );
+
+    let valid = module.validate_utf8(&Uint8Array::from("héllo".as_bytes()));
+    assert_eq!(JSON::stringify(&valid).unwrap(), r#"{"valid":true}"#);
+    let stats = module
+        .text_stats(&Uint8Array::from(&b"one two\ntwo\n"[..]))
+        .unwrap();
+    assert_eq!(get(&stats, "words").as_f64(), Some(3.0));
+    assert_eq!(get(&stats, "lines").as_f64(), Some(2.0));
+    let pooled = WasmModule::with_runtime(&runtime())
+        .text_stats(&Uint8Array::from(&b"one two\ntwo\n"[..]))
+        .unwrap();
+    assert_eq!(
+        JSON::stringify(&pooled).unwrap(),
+        JSON::stringify(&stats).unwrap()
+    );
+    assert_eq!(
+        code(module.text_stats(&Uint8Array::from(&[0x61u8, 0xFF][..]))),
+        "INVALID_ENCODING"
+    );
+}
+
+#[wasm_bindgen_test]
+async fn task_queue_runs_submitted_work() {
+    let mut queue = WasmTaskQueue::with_runtime(4, &runtime()).unwrap();
+    assert_eq!(queue.retention(), 4);
+    let squared = queue.submit_expr(&[1.0, 4.0, 9.0], "square").unwrap();
+    let rooted = queue.submit_expr(&[1.0, 4.0, 9.0], "sqrt|neg").unwrap();
+    let cancelled = queue.submit_expr(&[1.0], "exp").unwrap();
+    assert!(queue.cancel(cancelled).unwrap());
+    assert_eq!(queue.status(cancelled).unwrap(), "cancelled");
+    assert_eq!(
+        code(queue.submit_expr(&[1.0], "cube")),
+        "UNSUPPORTED_OPERATION"
+    );
+    assert_eq!(queue.task_count(), 3);
+
+    for _ in 0..1000 {
+        if queue.status(squared).unwrap() == "done" && queue.status(rooted).unwrap() == "done" {
+            break;
+        }
+        next_tick().await;
+    }
+    assert!(queue.retained_bytes() >= 2 * 3 * 8);
+    assert_eq!(queue.take_result(squared).unwrap(), [1.0, 16.0, 81.0]);
+    assert_eq!(queue.take_result(rooted).unwrap(), [-1.0, -2.0, -3.0]);
+    assert_eq!(code(queue.status(squared)), "INVALID_ARGUMENT");
+    assert_eq!(code(queue.take_result(cancelled)), "CANCELLED");
+}
+
+#[wasm_bindgen_test]
+fn tfidf_embeddings_and_bloom_filters() {
+    let mut tfidf = WasmTFIDF::new(3).unwrap();
+    assert_eq!(code(tfidf.transform(&strings(&["cat"]))), "NOT_INITIALIZED");
+    tfidf
+        .fit(&strings(&[
+            "The cat sat.",
+            "The cat ran, the dog ran!",
+            "A dog barked",
+            "the end",
+        ]))
+        .unwrap();
+    assert_eq!(tfidf.vocabulary(), ["the", "cat", "dog"]);
+    assert_eq!(tfidf.vocabulary_size(), 3);
+    let matrix = tfidf
+        .transform(&strings(&["cat cat fish", "bird"]))
+        .unwrap();
+    assert_eq!(matrix.len(), 6);
+    assert!(matrix[1] > 0.0 && matrix[3..] == [0.0; 3]);
+
+    let mut index = WasmEmbeddingIndex::with_runtime(&runtime());
+    assert_eq!(code(index.most_similar(&[1.0, 0.0], 1)), "NOT_INITIALIZED");
+    index
+        .load_embeddings(&[0.0, 0.0, 2.0, 0.0, 1.0, 0.1, -1.0, 0.0], 4, 2)
+        .unwrap();
+    assert_eq!((index.n_words(), index.dim()), (4, 2));
+    assert_eq!(index.most_similar(&[1.0, 0.0], 2).unwrap(), [1, 2]);
+    assert_eq!(
+        code(index.load_embeddings(&[0.0; 3], 2, 2)),
+        "DIMENSION_MISMATCH"
+    );
+    assert!(WasmEmbeddingIndex::new().is_ok());
+
+    let mut filter = WasmBloomFilter::with_runtime(100, 0.01, &runtime()).unwrap();
+    assert_eq!(filter.num_hashes(), 7);
+    assert!(filter.num_bits() > 900.0);
+    filter.parallel_insert_batch(&[1, 2, 3]).unwrap();
+    let found = filter.parallel_contains_batch(&[1, 2, 3]).unwrap();
+    assert!(found.iter().all(|value| value.as_bool() == Some(true)));
+    let copy = WasmBloomFilter::deserialize(&filter.serialize()).unwrap();
+    assert_eq!(copy.contains_batch(&[1, 2, 3]).unwrap(), [true; 3]);
+}
+
+#[wasm_bindgen_test]
+fn memory_efficient_processor_and_reports() {
+    let mut processor = MemoryEfficientProcessor::with_runtime(8, &runtime());
+    assert_eq!(processor.chunk_size(), 1024);
+    assert!(processor.runtime().num_threads() >= 1);
+    processor.set_chunk_size(2).unwrap();
+    assert_eq!(code(processor.set_chunk_size(0)), "INVALID_ARGUMENT");
+    let mut out = [0.0; 3];
+    processor
+        .process_into(&[1.0, 2.0, 3.0], BatchOp::Square, &mut out)
+        .unwrap();
+    close(&out, &[1.0, 4.0, 9.0]);
+    assert_eq!(
+        code(processor.process_into(&[1.0], BatchOp::Square, &mut out)),
+        "DIMENSION_MISMATCH"
+    );
+    assert_eq!(
+        processor
+            .process_view(&[4.0], BatchOp::Sqrt)
+            .unwrap()
+            .to_vec(),
+        [2.0]
+    );
+    assert_eq!(processor.heap_bytes(), 64);
+    assert!(MemoryEfficientProcessor::new(0, 0).is_ok());
+
+    let batch = WasmBatchProcessor::new(16, 0).unwrap();
+    register_for_memory_report("wasm-test batch", &JsValue::from(batch).unchecked_into()).unwrap();
+    assert_eq!(
+        code(register_for_memory_report("plain object", &Object::new())),
+        "INVALID_ARGUMENT"
+    );
+    reset_memory_peak();
+    assert!(process_memory_bytes() > 0);
+    let report = memory_report();
+    assert!(
+        get(&get(&report, "processors"), "wasm-test batch")
+            .as_f64()
+            .unwrap()
+            >= 128.0
+    );
+    assert!(get(&report, "peak_bytes").as_f64() >= get(&report, "linear_memory_bytes").as_f64());
+    assert!(unregister_from_memory_report("wasm-test batch"));
+    assert!(!unregister_from_memory_report("wasm-test batch"));
+
+    let info = build_info();
+    assert_eq!(
+        get(&info, "name").as_string().unwrap(),
+        env!("CARGO_PKG_NAME")
+    );
+    assert_eq!(
+        get(&info, "version").as_string().unwrap(),
+        env!("CARGO_PKG_VERSION")
+    );
+    assert_eq!(get(&info, "target").as_string().unwrap(), "wasm32-unknown");
+}
@@ -0,0 +1,184 @@
+//! Tests that exercise the JavaScript boundary; run them with
+//! `wasm-pack test --node -- --test web` (or `--headless --chrome`).
+#![cfg(target_arch = "wasm32")]
+
+use std::{cell::RefCell, panic::AssertUnwindSafe, rc::Rc};
+
+use js_sys::{Float64Array, Function, Reflect, Uint8Array, JSON};
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use wasm_bindgen_test::*;
+use web_learning_rust_examples::{
+    init_panic_handler, BatchOp, CancellationToken, WasmBatchProcessor, WasmError,
+    WasmMatrixProcessor, WasmModule,
+};
+
+/// Read a property of a JavaScript value, rendered as JSON
+fn json_property(value: &JsValue, name: &str) -> String {
+    let property = Reflect::get(value, &name.into()).unwrap();
+    JSON::stringify(&property).unwrap().into()
+}
+
+#[wasm_bindgen_test]
+fn invalid_arguments_surface_as_js_errors() {
+    init_panic_handler();
+
+    let processor = WasmMatrixProcessor::new(0).unwrap();
+    let error = processor
+        .solve_linear_system(&[1.0, 2.0, 3.0, 4.0], &[1.0], 2)
+        .expect_err("mismatched right-hand side should be rejected");
+    assert_eq!(
+        error,
+        WasmError::DimensionMismatch {
+            what: "Right-hand side length".to_string(),
+            expected: 2,
+            actual: 1,
+        }
+    );
+
+    let error = JsValue::from(error);
+    let js_error: &js_sys::Error = error.dyn_ref().expect("a JavaScript Error");
+    assert_eq!(js_error.name(), "WasmError");
+    assert_eq!(
+        js_error.message(),
+        "Right-hand side length: expected 2, got 1"
+    );
+    assert_eq!(json_property(&error, "code"), r#""DIMENSION_MISMATCH""#);
+    assert_eq!(
+        json_property(&error, "details"),
+        r#"{"what":"Right-hand side length","expected":2,"actual":1}"#
+    );
+}
+
+#[wasm_bindgen_test]
+async fn async_batch_matches_sync_batch_in_order() {
+    let mut processor = WasmBatchProcessor::new(0, 0).unwrap();
+    let data: Vec<f64> = (0..10_000).map(|i| i as f64 * 0.01).collect();
+
+    let promise =
+        processor.process_batch_async(&data, BatchOp::Sqrt, false, 999, &CancellationToken::new());
+    let result: Float64Array = JsFuture::from(promise).await.unwrap().unchecked_into();
+
+    let expected = processor
+        .process_batch(&data, BatchOp::Sqrt, false)
+        .unwrap();
+    assert_eq!(result.to_vec(), expected);
+}
+
+#[wasm_bindgen_test]
+async fn async_batch_rejects_with_the_failing_slice() {
+    let processor = WasmBatchProcessor::new(0, 0).unwrap();
+    let mut data = vec![1.0; 100];
+    data[25] = -1.0;
+
+    let promise =
+        processor.process_batch_async(&data, BatchOp::Ln, true, 10, &CancellationToken::new());
+    let error = JsFuture::from(promise).await.unwrap_err();
+    assert_eq!(json_property(&error, "code"), r#""OUT_OF_DOMAIN""#);
+    assert_eq!(
+        json_property(&error, "details"),
+        r#"{"index":25,"values":[-1],"reason":"ln is undefined for non-positive values","slice":2}"#
+    );
+    let error: &js_sys::Error = error.dyn_ref().unwrap();
+    assert_eq!(
+        error.message(),
+        "Slice 2: Element 25 (-1): ln is undefined for non-positive values"
+    );
+}
+
+#[wasm_bindgen_test]
+async fn cancelled_async_batch_stops_between_slices() {
+    let processor = WasmBatchProcessor::new(0, 0).unwrap();
+    let data = vec![4.0; 100];
+
+    let cancelled = CancellationToken::new();
+    cancelled.cancel();
+    let promise = processor.process_batch_async(&data, BatchOp::Sqrt, false, 10, &cancelled);
+    let error = JsFuture::from(promise).await.unwrap_err();
+    assert_eq!(json_property(&error, "code"), r#""CANCELLED""#);
+    assert_eq!(
+        json_property(&error, "details"),
+        r#"{"completed":0,"total":10}"#
+    );
+
+    // This timer is queued before the batch's first yield, so it fires
+    // after slice 0 has run and before slice 1 starts
+    let token = CancellationToken::new();
+    let promise = processor.process_batch_async(&data, BatchOp::Sqrt, false, 10, &token);
+    let canceller = token.clone();
+    let cancel = Closure::once_into_js(move || canceller.cancel());
+    let set_timeout: Function = Reflect::get(&js_sys::global(), &"setTimeout".into())
+        .unwrap()
+        .unchecked_into();
+    set_timeout
+        .call2(&JsValue::NULL, &cancel, &JsValue::from(0))
+        .unwrap();
+
+    let error = JsFuture::from(promise).await.unwrap_err();
+    assert_eq!(
+        json_property(&error, "details"),
+        r#"{"completed":1,"total":10}"#
+    );
+    assert!(token.is_cancelled());
+}
+
+#[wasm_bindgen_test]
+async fn async_processing_reports_progress_per_chunk_and_caches_the_result() {
+    let mut module = WasmModule::new();
+    let input: Vec<u8> = (0..150_000u32).map(|i| (i % 251) as u8).collect();
+    let expected: Vec<u8> = input
+        .iter()
+        .enumerate()
+        .map(|(i, &byte)| byte.wrapping_add(i as u8))
+        .collect();
+
+    let progress = Rc::new(RefCell::new(Vec::new()));
+    // Recording a call cannot be interrupted part way by a panic
+    let recorder = AssertUnwindSafe(Rc::clone(&progress));
+    let on_progress = Closure::<dyn FnMut(f64, f64)>::new(move |done, total| {
+        recorder.borrow_mut().push((done, total));
+    });
+    let on_progress: Function = on_progress.as_ref().clone().unchecked_into();
+
+    let promise =
+        module.process_data_async(&Uint8Array::from(&input[..]), Some(on_progress.clone()));
+    assert!(
+        progress.borrow().is_empty(),
+        "no chunk should run before the first yield"
+    );
+    let result: Uint8Array = JsFuture::from(promise).await.unwrap().unchecked_into();
+    assert_eq!(result.to_vec(), expected);
+    assert_eq!(
+        *progress.borrow(),
+        [
+            (65_536.0, 150_000.0),
+            (131_072.0, 150_000.0),
+            (150_000.0, 150_000.0)
+        ]
+    );
+
+    assert_eq!(module.cache_size(), 1);
+    assert!(module.cache_keys()[0].starts_with("async_"));
+
+    // A repeat is served from the cache without running any chunks
+    progress.borrow_mut().clear();
+    let promise = module.process_data_async(&Uint8Array::from(&input[..]), Some(on_progress));
+    let result: Uint8Array = JsFuture::from(promise).await.unwrap().unchecked_into();
+    assert_eq!(result.to_vec(), expected);
+    assert!(progress.borrow().is_empty());
+}
+
+#[wasm_bindgen_test]
+async fn async_processing_rejects_with_the_progress_callback_error() {
+    let mut module = WasmModule::new();
+    let on_progress = Function::new_with_args("done", "throw new RangeError(`stop at ${done}`)");
+
+    let input = Uint8Array::new_with_length(100_000);
+    let error = JsFuture::from(module.process_data_async(&input, Some(on_progress)))
+        .await
+        .unwrap_err();
+    let error: js_sys::Error = error.unchecked_into();
+    assert_eq!(error.name(), "RangeError");
+    assert_eq!(error.message(), "stop at 65536");
+    assert_eq!(module.cache_size(), 0);
+}
@@ -60,7 +60,7 @@ async function main() {
 
         // Test 3: Memory usage and cache management
         console.log('\n=== Test 3: Memory Usage and Cache Management ===');
-        console.log('Memory usage estimate:', wasm.get_memory_usage(), 'bytes');
+        console.log('WASM linear memory:', wasm.memory_report().linear_memory_bytes, 'bytes');
         console.log('Current cache size:', wasmModule.cache_size);
 
         // Process more data to fill cache
@@ -113,7 +113,7 @@ async function main() {
         console.log('\n✅ All WebAssembly tests completed successfully!');
         console.log('\nModule Statistics:');
         console.log(`- Final cache size: ${wasmModule.cache_size}`);
-        console.log(`- Memory usage estimate: ${wasm.get_memory_usage()} bytes`);
+        console.log(`- WASM linear memory: ${wasm.memory_report().linear_memory_bytes} bytes`);
 
     } catch (error) {
         console.error('❌ Error running WebAssembly tests:', error);
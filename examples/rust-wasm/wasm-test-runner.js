@@ -100,6 +100,24 @@ class WasmTester {
             }
         }
     }
+
+    // Expect fn to throw a WasmError with this code, and return it so its
+    // message and details can be checked too
+    assertThrows(fn, code, message) {
+        let error;
+        try {
+            fn();
+        } catch (caught) {
+            error = caught;
+        }
+        if (error === undefined) {
+            throw new Error(message || `Expected a ${code} error`);
+        }
+        if (error.code !== code) {
+            throw new Error(`${message || 'Unexpected error'}: expected ${code}, got ${error.code} (${error.message})`);
+        }
+        return error;
+    }
 }
 
 async function main() {
@@ -159,11 +177,31 @@ async function main() {
             tester.assertEqual(afterClearCacheSize, 0, 'Cache size should be 0 after clearing');
         });
 
-        // Test 5: Memory usage function
-        tester.test('Memory Usage Function', () => {
-            const memUsage = tester.wasm.get_memory_usage();
-            tester.assert(typeof memUsage === 'number', 'Memory usage should be a number');
-            tester.assert(memUsage > 0, 'Memory usage should be positive');
+        // Test 5: Memory report
+        tester.test('Memory Report', () => {
+            const batch = new tester.wasm.WasmBatchProcessor(1000, 0);
+            const image = new tester.wasm.WasmImageProcessor(0);
+            tester.wasm.register_for_memory_report('batch', batch);
+            tester.wasm.register_for_memory_report('image', image);
+            tester.wasm.register_for_memory_report('module', tester.wasmModule);
+            image.grayscale(new Uint8Array(64 * 64 * 4));
+
+            const report = tester.wasm.memory_report();
+            tester.assert(report.linear_memory_bytes > 0 && report.linear_memory_bytes % 65536 === 0, 'Linear memory should be whole pages');
+            tester.assert(report.peak_bytes >= report.linear_memory_bytes, 'The peak should cover the current size');
+            tester.assertEqual(report.processors.batch, batch.heap_bytes(), 'Each processor should report its heap_bytes');
+            tester.assert(report.processors.image >= 64 * 64 * 4, 'The image buffer should be counted');
+            tester.assertEqual(report.processor_bytes, report.processors.batch + report.processors.image + report.processors.module, 'The total should add up');
+
+            batch.free();
+            tester.assert(!('batch' in tester.wasm.memory_report().processors), 'A freed processor should drop out');
+            tester.assert(tester.wasm.unregister_from_memory_report('image'), 'Unregistering should find the processor');
+            tester.assert(!tester.wasm.unregister_from_memory_report('image'), 'Unregistering twice should find nothing');
+            tester.wasm.unregister_from_memory_report('module');
+
+            tester.assertThrows(() => tester.wasm.register_for_memory_report('plain', {}), 'INVALID_ARGUMENT', 'Objects without heap_bytes should be rejected');
+            tester.wasm.reset_memory_peak();
+            tester.assertEqual(tester.wasm.memory_report().peak_bytes, tester.wasm.process_memory_bytes(), 'A reset peak starts from the current size');
         });
 
         // Test 6: Empty data processing
@@ -243,6 +281,3026 @@ async function main() {
             tester.assert(wasmTime < jsTime * 10, 'WASM should not be more than 10x slower than JS');
         });
 
+        // Test 10: Dithering preserves average colour
+        tester.test('Image Dithering', () => {
+            const processor = new tester.wasm.WasmImageProcessor(0);
+            const width = 64;
+            const height = 64;
+            const grey = new Uint8Array(width * height * 4);
+            for (let i = 0; i < grey.length; i += 4) {
+                grey.set([128, 128, 128, 255], i);
+            }
+
+            const averageRed = (pixels) => {
+                let sum = 0;
+                for (let i = 0; i < pixels.length; i += 4) {
+                    sum += pixels[i];
+                }
+                return sum / (pixels.length / 4);
+            };
+
+            const palette = new Uint8Array([0, 0, 0, 255, 255, 255]);
+            const diffused = processor.dither_floyd_steinberg(grey, width, height, palette);
+            tester.assertEqual(diffused.length, grey.length, 'Dithered image should keep its size');
+            tester.assert(
+                diffused.every((value, i) => i % 4 === 3 || value === 0 || value === 255),
+                'Floyd-Steinberg output should only use palette colours'
+            );
+            tester.assert(Math.abs(averageRed(diffused) - 128) <= 1, 'Floyd-Steinberg should preserve average colour');
+
+            const ordered = processor.dither_ordered(grey, width, height, 1);
+            tester.assert(Math.abs(averageRed(ordered) - 128) <= 1, 'Ordered dithering should preserve average colour');
+
+            tester.assertThrows(() => processor.dither_ordered(grey, width, height + 1, 1), 'DIMENSION_MISMATCH', 'Mismatched dimensions should throw');
+        });
+
+        // Test 11: Colour space round trips
+        tester.test('Colour Space Round Trips', () => {
+            const processor = new tester.wasm.WasmImageProcessor(0);
+
+            // All corners of the RGB cube plus a deterministic pseudo-random sample
+            const samples = [];
+            for (let corner = 0; corner < 8; corner++) {
+                samples.push([corner & 1 ? 255 : 0, corner & 2 ? 255 : 0, corner & 4 ? 255 : 0]);
+            }
+            let seed = 12345;
+            const nextByte = () => {
+                seed = (seed * 1103515245 + 12345) & 0x7fffffff;
+                return seed & 0xff;
+            };
+            for (let i = 0; i < 10000; i++) {
+                samples.push([nextByte(), nextByte(), nextByte()]);
+            }
+
+            const rgba = new Uint8Array(samples.length * 4);
+            samples.forEach((rgb, i) => rgba.set([...rgb, 255], i * 4));
+
+            const assertWithinOne = (actual, space) => {
+                for (let i = 0; i < rgba.length; i++) {
+                    tester.assert(
+                        Math.abs(actual[i] - rgba[i]) <= 1,
+                        `${space} round trip differs at index ${i}: ${actual[i]} vs ${rgba[i]}`
+                    );
+                }
+            };
+
+            for (const space of ['hsl', 'hsv', 'ycbcr']) {
+                const converted = processor.rgba_to_colorspace_f32(rgba, space);
+                assertWithinOne(processor.colorspace_f32_to_rgba(converted, space), space);
+            }
+
+            const ycbcr = processor.convert_colorspace(rgba, 'rgb', 'ycbcr');
+            assertWithinOne(processor.convert_colorspace(ycbcr, 'ycbcr', 'rgb'), 'ycbcr bytes');
+
+            const message = String(tester.assertThrows(() => processor.convert_colorspace(rgba, 'rgb', 'lab'), 'UNSUPPORTED_OPERATION'));
+            tester.assert(message.includes('rgb, hsl, hsv, ycbcr'), 'Unknown spaces should list supported ones');
+        });
+
+        // Test 12: Entropy of byte distributions
+        tester.test('Shannon And Renyi Entropy', () => {
+            const processor = new tester.wasm.WasmParallelProcessor(0);
+
+            const uniform = new Uint8Array(256 * 64);
+            for (let i = 0; i < uniform.length; i++) {
+                uniform[i] = i % 256;
+            }
+
+            const counts = processor.parallel_count_values(uniform);
+            tester.assertEqual(counts.length, 256, 'Histogram should have 256 bins');
+            tester.assert(counts.every((count) => count === 64), 'Every byte should occur 64 times');
+
+            tester.assertEqual(processor.parallel_shannon_entropy(uniform), 8.0, 'Uniform entropy should be 8 bits');
+            tester.assertEqual(processor.parallel_shannon_entropy(new Uint8Array(1000)), 0, 'Constant data has no entropy');
+            tester.assert(
+                Math.abs(processor.parallel_renyi_entropy(uniform, 2) - 8.0) < 1e-12,
+                'Renyi entropy of a uniform distribution should be 8 bits'
+            );
+            tester.assertEqual(
+                processor.parallel_renyi_entropy(uniform, 1),
+                processor.parallel_shannon_entropy(uniform),
+                'Alpha = 1 should delegate to Shannon entropy'
+            );
+
+            tester.assertThrows(() => processor.parallel_renyi_entropy(uniform, 0), 'INVALID_ARGUMENT', 'Alpha <= 0 should throw');
+        });
+
+        // Test 13: Global, Otsu and adaptive thresholding
+        tester.test('Image Thresholding', () => {
+            const processor = new tester.wasm.WasmImageProcessor(0);
+
+            // Horizontal gradient covering every grey level once per row
+            const width = 256;
+            const height = 8;
+            const gradient = new Uint8Array(width * height * 4);
+            for (let y = 0; y < height; y++) {
+                for (let x = 0; x < width; x++) {
+                    gradient.set([x, x, x, 255], (y * width + x) * 4);
+                }
+            }
+
+            const otsu = processor.otsu_threshold(gradient);
+            tester.assertEqual(otsu, 128, 'Otsu threshold of a uniform gradient should split it in half');
+
+            const binary = processor.threshold(gradient, otsu);
+            tester.assertEqual(binary[127 * 4], 0, 'Levels below the threshold should be black');
+            tester.assertEqual(binary[128 * 4], 255, 'Levels at the threshold should be white');
+            tester.assertEqual(binary[3], 255, 'Alpha should be preserved');
+
+            // On a smooth gradient every pixel sits at its local mean, so a
+            // positive offset makes everything white
+            const adaptive = processor.adaptive_threshold(gradient, width, height, 5, 2);
+            tester.assert(
+                adaptive.every((value, i) => i % 4 === 3 || value === 255),
+                'Adaptive threshold with positive offset should whiten a smooth gradient'
+            );
+
+            tester.assertThrows(() => processor.adaptive_threshold(gradient, width, height, 4, 0), 'INVALID_ARGUMENT', 'Even block sizes should throw');
+        });
+
+        // Test 14: Aho-Corasick multi-pattern search
+        tester.test('Parallel Multi-Pattern Search', () => {
+            const processor = new tester.wasm.WasmParallelProcessor(0);
+            const search = (chunks, patterns) => JSON.parse(processor.parallel_multi_search(chunks, patterns));
+
+            // Overlapping patterns all report, ordered by end position
+            const overlapping = search(['ushers'], ['he', 'she', 'his', 'hers']);
+            tester.assertEqual(
+                JSON.stringify(overlapping),
+                JSON.stringify([
+                    { byte_offset: 1, chunk_index: 0, pattern_index: 1 },
+                    { byte_offset: 2, chunk_index: 0, pattern_index: 0 },
+                    { byte_offset: 2, chunk_index: 0, pattern_index: 3 },
+                ]),
+                'Overlapping matches should all be reported'
+            );
+
+            // Offsets are UTF-8 byte offsets on character boundaries
+            const unicode = search(['naïve café', 'é'], ['é', 'café']);
+            const offsets = unicode.map((m) => `${m.chunk_index}:${m.byte_offset}:${m.pattern_index}`);
+            tester.assertArrayEqual(offsets, ['0:7:1', '0:10:0', '1:0:0'], 'UTF-8 offsets should be byte based');
+
+            tester.assertThrows(() => processor.parallel_multi_search(['text'], ['ok', 42]), 'INVALID_ARGUMENT', 'Non-string patterns should throw');
+        });
+
+        // Test 15: Multi-pattern search benchmark
+        tester.test('Multi-Pattern Search Benchmark', () => {
+            const processor = new tester.wasm.WasmParallelProcessor(0);
+            const patterns = Array.from({ length: 100 }, (_, i) => `token${i}x`);
+            const line = 'lorem ipsum token42x dolor sit amet token7x consectetur adipiscing elit\n';
+            const chunk = line.repeat(Math.ceil((1024 * 1024) / line.length));
+            const chunks = Array.from({ length: 10 }, () => chunk);
+
+            const sequentialStart = performance.now();
+            let sequentialMatches = 0;
+            for (const text of chunks) {
+                sequentialMatches += JSON.parse(processor.parallel_multi_search([text], patterns)).length;
+            }
+            const sequentialTime = performance.now() - sequentialStart;
+
+            const parallelStart = performance.now();
+            const parallelMatches = JSON.parse(processor.parallel_multi_search(chunks, patterns)).length;
+            const parallelTime = performance.now() - parallelStart;
+
+            console.log(`   ~10 MB, 100 patterns: sequential ${sequentialTime.toFixed(1)}ms, parallel ${parallelTime.toFixed(1)}ms`);
+            console.log(`   Speedup: ${(sequentialTime / parallelTime).toFixed(2)}x`);
+            tester.assertEqual(parallelMatches, sequentialMatches, 'Both strategies should find the same matches');
+        });
+
+        // Test 16: Alpha compositing and blend modes
+        tester.test('Image Blending', () => {
+            const processor = new tester.wasm.WasmImageProcessor(0);
+            const base = new Uint8Array([200, 100, 50, 255, 0, 0, 0, 255]);
+            const overlay = new Uint8Array([100, 200, 250, 255, 255, 255, 255, 255]);
+
+            tester.assertArrayEqual(
+                Array.from(processor.blend(base, overlay, 'normal', 1)),
+                Array.from(overlay),
+                'A fully opaque overlay should replace the base exactly'
+            );
+
+            const transparent = new Uint8Array([100, 200, 250, 0, 255, 255, 255, 0]);
+            for (const mode of ['normal', 'multiply', 'screen', 'overlay', 'add']) {
+                tester.assertArrayEqual(
+                    Array.from(processor.blend(base, transparent, mode, 1)),
+                    Array.from(base),
+                    `A fully transparent overlay should leave the base unchanged (${mode})`
+                );
+            }
+
+            // Half opacity: (200 + 100) / 2 = 150, and 255 / 2 = 127.5 rounds up to 128
+            tester.assertArrayEqual(
+                Array.from(processor.blend(base, overlay, 'normal', 0.5)),
+                [150, 150, 150, 255, 128, 128, 128, 255],
+                'Half-opacity source-over should round to nearest'
+            );
+
+            // Both layers at alpha 128: alpha = 0.502 + 0.502 * 0.498 -> 192,
+            // red = (0.502 * 100 + 0.25 * 200) / 0.752 -> 133
+            tester.assertArrayEqual(
+                Array.from(processor.blend(new Uint8Array([200, 100, 50, 128]), new Uint8Array([100, 200, 250, 128]), 'normal', 1)),
+                [133, 167, 184, 192],
+                'Semi-transparent layers should use non-premultiplied source-over'
+            );
+
+            tester.assertArrayEqual(
+                Array.from(processor.blend(base, overlay, 'multiply', 1)),
+                [78, 78, 49, 255, 0, 0, 0, 255],
+                'Multiply should darken'
+            );
+
+            tester.assertThrows(() => processor.blend(base, overlay.subarray(0, 4), 'normal', 1), 'INVALID_ARGUMENT', 'Mismatched buffer lengths should throw');
+        });
+
+        // Test 17: Linear system solvers
+        tester.test('Linear System Solvers', () => {
+            const processor = new tester.wasm.WasmMatrixProcessor(0);
+            const close = (actual, expected, message) => {
+                tester.assertEqual(actual.length, expected.length, message);
+                expected.forEach((value, i) => tester.assert(Math.abs(actual[i] - value) < 1e-9, message));
+            };
+
+            const a = new Float64Array([2, 1, -1, -3, -1, 2, -2, 1, 2]);
+            const b = new Float64Array([8, -11, -3]);
+            close(processor.solve_linear_system(a, b, 3), [2, 3, -1], 'Gaussian elimination should solve Ax = b');
+
+            // Fit y = 1 + 2x through four collinear points
+            const design = new Float64Array([1, 0, 1, 1, 1, 2, 1, 3]);
+            const observed = new Float64Array([1, 3, 5, 7]);
+            close(processor.solve_least_squares(design, observed, 4, 2), [1, 2], 'Least squares should recover the line');
+
+            const singular = tester.assertThrows(() => processor.solve_linear_system(new Float64Array([1, 2, 2, 4]), new Float64Array([1, 2]), 2), 'SINGULAR', 'Singular matrices should throw');
+            tester.assert(String(singular).includes('singular'), 'The error should say the matrix is singular');
+        });
+
+        // Test 18: CSS filter colour matrices
+        tester.test('CSS Filter Matrices', () => {
+            const processor = new tester.wasm.WasmImageProcessor(0);
+            const pixel = new Uint8Array([200, 100, 50, 255, 255, 255, 255, 128]);
+
+            // saturate(0) -> 0.213 * 200 + 0.715 * 100 + 0.072 * 50 = 117.7, then brightness(1.5)
+            const desaturated = processor.saturate(pixel, 0);
+            tester.assertArrayEqual(Array.from(desaturated), [118, 118, 118, 255, 255, 255, 255, 128], 'saturate(0) should desaturate');
+            tester.assertArrayEqual(
+                Array.from(processor.adjust_brightness(desaturated, 1.5)),
+                [177, 177, 177, 255, 255, 255, 255, 128],
+                'saturate -> brightness should compose'
+            );
+
+            tester.assertArrayEqual(Array.from(processor.invert(pixel, 1)), [55, 155, 205, 255, 0, 0, 0, 128], 'invert(1) should invert RGB');
+            tester.assertArrayEqual(Array.from(processor.sepia(pixel, 1)), [165, 147, 114, 255, 255, 255, 239, 128], 'sepia(1) should match the spec matrix');
+            tester.assertArrayEqual(Array.from(processor.hue_rotate(pixel, 0)), Array.from(pixel), 'hue-rotate(0) should be the identity');
+
+            const identity = new Float32Array([1, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 1, 0]);
+            tester.assertArrayEqual(Array.from(processor.apply_color_matrix(pixel, identity)), Array.from(pixel), 'Identity matrix should not change pixels');
+
+            tester.assertThrows(() => processor.apply_color_matrix(pixel, new Float32Array(16)), 'DIMENSION_MISMATCH', 'Matrices without 20 entries should throw');
+        });
+
+        // Test 19: Persistent frame buffer with in-place ops
+        tester.test('In-Place Frame Processing', () => {
+            const processor = new tester.wasm.WasmImageProcessor(0);
+            const pixels = new Uint8Array([200, 100, 50, 255, 10, 20, 30, 128]);
+
+            processor.load_frame(pixels, 2, 1);
+            processor.op_grayscale();
+            processor.op_brightness(1.2);
+            const expected = processor.adjust_brightness(processor.grayscale(pixels), 1.2);
+            tester.assertArrayEqual(Array.from(processor.frame_view()), Array.from(expected), 'In-place ops should match the copying API');
+            tester.assertEqual(processor.frame_len(), pixels.length, 'Frame length should match the loaded image');
+            tester.assertEqual(processor.frame_width, 2, 'Frame width should be recorded');
+
+            processor.load_frame(pixels, 2, 1);
+            processor.op_invert(1);
+            tester.assertArrayEqual(Array.from(processor.frame_view()), Array.from(processor.invert(pixels, 1)), 'op_invert should match invert');
+
+            tester.assertThrows(() => processor.load_frame(pixels, 3, 1), 'DIMENSION_MISMATCH', 'Mismatched frame dimensions should throw');
+
+            // 1080p per-frame cost: copying API vs persistent frame
+            const width = 1920;
+            const height = 1080;
+            const frame = new Uint8Array(width * height * 4).map((_, i) => i % 256);
+            const frames = 10;
+
+            const copyStart = performance.now();
+            for (let i = 0; i < frames; i++) {
+                processor.adjust_brightness(processor.grayscale(frame), 1.2);
+            }
+            const copyTime = (performance.now() - copyStart) / frames;
+
+            const inPlaceStart = performance.now();
+            for (let i = 0; i < frames; i++) {
+                processor.load_frame(frame, width, height);
+                processor.op_grayscale();
+                processor.op_brightness(1.2);
+                processor.frame_view();
+            }
+            const inPlaceTime = (performance.now() - inPlaceStart) / frames;
+
+            console.log(`   1080p per frame: copying ${copyTime.toFixed(2)}ms, in-place ${inPlaceTime.toFixed(2)}ms`);
+            console.log(`   Speedup: ${(copyTime / inPlaceTime).toFixed(2)}x`);
+        });
+
+        // Test 20: Parallel radix sort
+        tester.test('Parallel Radix Sort', () => {
+            const processor = new tester.wasm.WasmParallelProcessor(0);
+
+            const small = new Uint32Array(100000).map((_, i) => (i * 2654435761) >>> 0);
+            const expected32 = small.slice().sort();
+            tester.assertArrayEqual(Array.from(processor.parallel_radix_sort_u32(small)), Array.from(expected32), 'u32 radix sort should match the standard sort');
+
+            const wide = new BigUint64Array(100000).map((_, i) => (BigInt(i) * 0x9E3779B97F4A7C15n) & 0xFFFFFFFFFFFFFFFFn);
+            const expected64 = wide.slice().sort();
+            tester.assertArrayEqual(Array.from(processor.parallel_radix_sort_u64(wide)), Array.from(expected64), 'u64 radix sort should match the standard sort');
+
+            tester.assertEqual(processor.parallel_radix_sort_u32(new Uint32Array(0)).length, 0, 'Empty input should sort to empty output');
+        });
+
+        // Test 21: Fused filter pipeline
+        tester.test('Filter Pipeline', () => {
+            const processor = new tester.wasm.WasmImageProcessor(0);
+            const pixels = new Uint8Array([200, 100, 50, 255, 10, 20, 30, 128]);
+
+            processor.pipeline_begin();
+            processor.pipeline_add('grayscale');
+            processor.pipeline_add('brightness', 1.2);
+            processor.pipeline_add('invert', 0.5);
+            tester.assertEqual(processor.pipeline_length, 3, 'Pipeline should hold three ops');
+
+            const chained = processor.invert(processor.adjust_brightness(processor.grayscale(pixels), 1.2), 0.5);
+            tester.assertArrayEqual(Array.from(processor.pipeline_run(pixels)), Array.from(chained), 'Fused pass should match chained filters');
+
+            processor.pipeline_begin();
+            processor.pipeline_add('gamma', 1);
+            processor.pipeline_add('contrast', 1);
+            processor.pipeline_add('color_matrix', [1, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 1, 0]);
+            tester.assertArrayEqual(Array.from(processor.pipeline_run(pixels)), Array.from(pixels), 'Identity steps should not change pixels');
+
+            processor.pipeline_begin();
+            processor.pipeline_add('contrast', 0);
+            tester.assertArrayEqual(Array.from(processor.pipeline_run(pixels)), [128, 128, 128, 255, 128, 128, 128, 128], 'contrast(0) should flatten to mid-grey');
+
+            const errorFor = (op, params, code) => String(tester.assertThrows(() => processor.pipeline_add(op, params), code));
+            tester.assert(errorFor('gamma', -1, 'INVALID_ARGUMENT').includes('step 2'), 'Errors should name the failing step');
+            tester.assert(errorFor('blur', 3, 'INVALID_ARGUMENT').includes('cannot be fused'), 'Spatial ops should be rejected');
+            tester.assert(errorFor('color_matrix', [1, 2, 3], 'DIMENSION_MISMATCH').includes('expected 20, got 3'), 'Matrix shape should be checked when added');
+            tester.assert(errorFor('sharpen_edges', undefined, 'UNSUPPORTED_OPERATION').includes('Unknown pipeline op'), 'Unknown ops should be rejected');
+            tester.assertEqual(processor.pipeline_length, 1, 'Rejected ops should not be queued');
+        });
+
+        // Test 22: Procedural Perlin noise
+        tester.test('Perlin Noise Generation', () => {
+            const processor = new tester.wasm.WasmImageProcessor(0);
+            const { NoiseParams } = tester.wasm;
+            const generate = (seed) => processor.generate_perlin_noise(64, 32, new NoiseParams(16, 4, 0.5, 2, seed));
+
+            const first = generate(42);
+            tester.assertEqual(first.length, 64 * 32, 'Noise should have one byte per pixel');
+            tester.assertArrayEqual(Array.from(generate(42)), Array.from(first), 'The same seed should reproduce the same noise');
+            tester.assert(Array.from(generate(43)).some((value, i) => value !== first[i]), 'A different seed should change the noise');
+
+            const stops = new Uint8Array([0, 0, 64, 255, 255, 255, 255, 255]);
+            const coloured = processor.generate_perlin_noise_rgba(64, 32, new NoiseParams(16, 4, 0.5, 2, 42), stops);
+            tester.assertEqual(coloured.length, 64 * 32 * 4, 'Coloured noise should be RGBA');
+            tester.assertEqual(coloured[2], Math.round(64 + (255 - 64) * first[0] / 255), 'Noise should map through the gradient');
+
+            tester.assertThrows(() => processor.generate_perlin_noise(8, 8, new NoiseParams(0, 1, 0.5, 2, 1)), 'INVALID_ARGUMENT', 'A zero scale should throw');
+        });
+
+        // Test 23: Bulk JSON field extraction
+        tester.test('Parallel JSON Field Extraction', () => {
+            const processor = new tester.wasm.WasmParallelProcessor(0);
+
+            const lines = [
+                '{"id": 1, "price": 9.5}',
+                '{"id": 2}',
+                '{"id": 3, "price": "12"}',
+                '{"id": 4, "meta": {"price": 1}, "price": -2e3}',
+                'not json',
+            ].join('\n');
+            const values = processor.parallel_extract_field(lines, 'price');
+            tester.assertEqual(values.length, 5, 'There should be one value per line');
+            tester.assertEqual(values[0], 9.5, 'Numeric fields should be extracted');
+            tester.assert(Number.isNaN(values[1]), 'Missing fields should be NaN');
+            tester.assert(Number.isNaN(values[2]), 'Non-numeric fields should be NaN');
+            tester.assertEqual(values[3], -2000, 'Only top-level keys should match');
+            tester.assert(Number.isNaN(values[4]), 'Malformed lines should be NaN');
+
+            tester.assertThrows(() => processor.parallel_extract_field(lines, 'pri"ce'), 'INVALID_ARGUMENT', 'Field names with quotes should throw');
+
+            // 100K objects: Rust scanner vs JSON.parse per line
+            const objects = Array.from({ length: 100000 }, (_, i) =>
+                JSON.stringify({ id: i, name: `item-${i}`, tags: ['a', 'b'], price: i * 0.25 })
+            ).join('\n');
+
+            const jsStart = performance.now();
+            const jsValues = objects.split('\n').map((line) => JSON.parse(line).price);
+            const jsTime = performance.now() - jsStart;
+
+            const wasmStart = performance.now();
+            const wasmValues = processor.parallel_extract_field(objects, 'price');
+            const wasmTime = performance.now() - wasmStart;
+
+            console.log(`   100K objects: JSON.parse ${jsTime.toFixed(1)}ms, parallel_extract_field ${wasmTime.toFixed(1)}ms`);
+            tester.assertArrayEqual(Array.from(wasmValues), jsValues, 'Extraction should agree with JSON.parse');
+        });
+
+        // Test 24: Connected-component labeling
+        tester.test('Connected Components', () => {
+            const processor = new tester.wasm.WasmImageProcessor(0);
+
+            // Two 2x2 squares separated by a gap on an 8x4 mask
+            const width = 8;
+            const height = 4;
+            const squares = new Uint8Array(width * height);
+            for (const [x, y] of [[1, 1], [2, 1], [1, 2], [2, 2], [5, 0], [6, 0], [5, 1], [6, 1]]) {
+                squares[y * width + x] = 255;
+            }
+            const blobs = processor.connected_components(squares, width, height, false);
+            tester.assertEqual(blobs.num_labels, 2, 'Two separated squares should give two labels');
+            tester.assert(blobs.labels instanceof Uint32Array, 'Labels should be a Uint32Array');
+            tester.assertEqual(blobs.labels[5], 1, 'Labels should be numbered in raster order');
+            tester.assertEqual(blobs.labels[1 * width + 1], 2, 'The lower square should be the second label');
+            tester.assertArrayEqual(Array.from(blobs.bboxes), [5, 0, 6, 1, 1, 1, 2, 2], 'Bounding boxes should be min/max x/y');
+
+            // A diagonal line only connects through corners
+            const diagonal = new Uint8Array([255, 0, 0, 0, 255, 0, 0, 0, 255]);
+            tester.assertEqual(processor.connected_components(diagonal, 3, 3, false).num_labels, 3, '4-connectivity should split a diagonal');
+            const joined = processor.connected_components(diagonal, 3, 3, true);
+            tester.assertEqual(joined.num_labels, 1, '8-connectivity should join a diagonal');
+            tester.assertArrayEqual(Array.from(joined.bboxes), [0, 0, 2, 2], 'The diagonal should span the whole mask');
+
+            tester.assertThrows(() => processor.connected_components(diagonal, 4, 3, false), 'DIMENSION_MISMATCH', 'Mismatched mask dimensions should throw');
+        });
+
+        // Test 25: Fused multiply-add batches
+        tester.test('Fused Multiply-Add Batch', () => {
+            const processor = new tester.wasm.WasmBatchProcessor(1024, 0);
+            const data = new Float64Array([0, 1.5, -2.25, 1e300, Math.PI]);
+
+            tester.assertArrayEqual(Array.from(processor.fma_batch(data, 1, 0)), Array.from(data), 'fma_batch(x, 1, 0) should be the identity');
+            tester.assertArrayEqual(Array.from(processor.fma_batch(data, 2, 1)), Array.from(data, (x) => x * 2 + 1), 'fma_batch should scale and offset');
+            tester.assertArrayEqual(
+                Array.from(processor.fma_batch_vec(new Float64Array([1, 2, 3]), new Float64Array([2, 3, 4]), new Float64Array([1, 1, -1]))),
+                [3, 7, 11],
+                'fma_batch_vec should use per-element scales and offsets'
+            );
+
+            // (1 + 2^-27)(1 - 2^-27) = 1 - 2^-54 rounds to 1 when multiplied alone
+            const x = 1 + 2 ** -27;
+            const scale = 1 - 2 ** -27;
+            tester.assertEqual(x * scale - 1, 0, 'Separate multiply-then-add should lose the low bits');
+            tester.assertEqual(processor.fma_batch(new Float64Array([x]), scale, -1)[0], -(2 ** -54), 'mul_add should round only once');
+
+            tester.assertThrows(() => processor.fma_batch_vec(data, new Float64Array(2), new Float64Array(5)), 'DIMENSION_MISMATCH', 'Mismatched lengths should throw');
+        });
+
+        // Test 26: Jaccard similarity over bitsets
+        tester.test('Jaccard Similarity', () => {
+            const processor = new tester.wasm.WasmParallelProcessor(0);
+            const mask = 0xFFFFFFFFFFFFFFFFn;
+
+            for (const word of [1n, 0xDEADBEEFn, 0x8000000000000001n, mask]) {
+                const x = new BigUint64Array([word, word * 3n & mask, 0n]);
+                const complement = x.map((value) => ~value & mask);
+                tester.assertEqual(processor.parallel_jaccard_similarity(x, x), 1, 'jaccard(x, x) should be 1');
+                tester.assertEqual(processor.parallel_jaccard_similarity(x, complement), 0, 'jaccard(x, !x) should be 0');
+            }
+
+            // {0, 1} vs {1, 2}: one shared element out of three
+            const a = new BigUint64Array([0b011n]);
+            const b = new BigUint64Array([0b110n]);
+            tester.assert(Math.abs(processor.parallel_jaccard_similarity(a, b) - 1 / 3) < 1e-12, 'Partial overlap should be |A ∩ B| / |A ∪ B|');
+
+            const sets = new BigUint64Array([0b011n, 0b110n, 0b011n]);
+            const pairs = processor.parallel_pairwise_jaccard(sets, 3, 1);
+            tester.assertEqual(pairs.length, 3, 'Three sets should give three pairs');
+            tester.assertArrayEqual(Array.from(pairs), [Math.fround(1 / 3), 1, Math.fround(1 / 3)], 'Pairs should be the packed lower triangle');
+
+            tester.assertThrows(() => processor.parallel_jaccard_similarity(a, new BigUint64Array(2)), 'DIMENSION_MISMATCH', 'Mismatched lengths should throw');
+            tester.assertThrows(() => processor.parallel_jaccard_similarity(new BigUint64Array(1), new BigUint64Array(1)), 'INVALID_ARGUMENT', 'Two empty sets should throw');
+        });
+
+        // Test 27: Summed-area tables and box blur
+        tester.test('Integral Image and Box Blur', () => {
+            const processor = new tester.wasm.WasmImageProcessor(0);
+            const gray = new Uint8Array([1, 2, 3, 4, 5, 6]); // 3x2
+
+            const sat = processor.integral_image(gray, 3, 2);
+            tester.assertArrayEqual(Array.from(sat), [0, 0, 0, 0, 0, 1, 3, 6, 0, 5, 12, 21], 'Table should be padded with a zero row and column');
+            tester.assertEqual(processor.box_sum(sat, 3, 2, new tester.wasm.Rect(0, 0, 3, 2)), 21, 'Whole-image box should sum every pixel');
+            tester.assertEqual(processor.box_sum(sat, 3, 2, new tester.wasm.Rect(1, 1, 2, 1)), 11, 'Sub-rectangle sums should be exact');
+
+            const rgba = new Uint8Array([0, 0, 0, 255, 90, 90, 90, 255, 30, 60, 90, 0]); // 3x1
+            tester.assertArrayEqual(
+                Array.from(processor.fast_box_blur(rgba, 3, 1, 1)),
+                [45, 45, 45, 255, 40, 50, 60, 170, 60, 75, 90, 128],
+                'Box blur should average clipped windows'
+            );
+            tester.assertArrayEqual(Array.from(processor.fast_box_blur(rgba, 3, 1, 0)), Array.from(rgba), 'Radius 0 should be the identity');
+
+            tester.assertThrows(() => processor.box_sum(sat, 3, 2, new tester.wasm.Rect(2, 0, 2, 1)), 'INVALID_ARGUMENT', 'Boxes outside the image should throw');
+            tester.assertThrows(() => processor.box_sum(sat, 3, 2, new tester.wasm.Rect(1, 0, 2 ** 32 - 1, 1)), 'INVALID_ARGUMENT', 'Box sizes that wrap around should throw');
+            tester.assertThrows(() => processor.box_sum(new Float64Array(0), 2 ** 32 - 1, 0, new tester.wasm.Rect(0, 0, 0, 0)), 'INVALID_ARGUMENT', 'Tables too large to index should throw');
+        });
+
+        // Test 28: Dimension-safe WasmImage wrappers
+        tester.test('WasmImage Wrappers', () => {
+            const processor = new tester.wasm.WasmImageProcessor(0);
+            const pixels = new Uint8Array(4 * 3 * 4).map((_, i) => (i * 37) % 256);
+
+            const image = tester.wasm.WasmImage.from_rgba(pixels, 4, 3);
+            tester.assertEqual(image.width, 4, 'Width should be kept');
+            tester.assertEqual(image.height, 3, 'Height should be kept');
+
+            const blurred = processor.fast_box_blur_image(image, 1);
+            tester.assertEqual(blurred.width, 4, 'Results should carry the input dimensions');
+            tester.assertArrayEqual(Array.from(blurred.data), Array.from(processor.fast_box_blur(pixels, 4, 3, 1)), 'Image variant should match the slice variant');
+
+            const dithered = processor.dither_ordered_image(image, 1);
+            tester.assertArrayEqual(Array.from(dithered.data), Array.from(processor.dither_ordered(pixels, 4, 3, 1)), 'Dither variant should match the slice variant');
+
+            tester.assertEqual(new tester.wasm.WasmImage(2, 2).data.length, 16, 'new() should allocate width * height * 4 bytes');
+
+            tester.assertThrows(() => tester.wasm.WasmImage.from_rgba(pixels, 5, 3), 'DIMENSION_MISMATCH', 'Mismatched dimensions should be rejected at construction');
+        });
+
+        // Test 29: Keyed aggregation
+        tester.test('Group By Aggregation', () => {
+            const processor = new tester.wasm.WasmParallelProcessor(0);
+            const numGroups = 50;
+            const keys = new Uint32Array(10000).map((_, i) => (i * 7919) % numGroups);
+            const values = new Float64Array(10000).map((_, i) => (i % 13) - 4.5);
+
+            const sums = processor.parallel_group_by_sum(keys, values, numGroups);
+            const total = values.reduce((a, b) => a + b, 0);
+            tester.assertEqual(sums.length, numGroups, 'There should be one sum per group');
+            tester.assert(Math.abs(sums.reduce((a, b) => a + b, 0) - total) < 1e-6, 'Group sums should add up to the total');
+
+            const counts = processor.parallel_group_by_count(keys, numGroups);
+            tester.assertEqual(counts.reduce((a, b) => a + b, 0), keys.length, 'Group counts should add up to the row count');
+
+            const small = new Uint32Array([0, 2, 2, 0]);
+            const maxima = processor.parallel_group_by_max(small, new Float64Array([1, 5, 3, -2]), 3);
+            tester.assertArrayEqual(Array.from(maxima), [1, -Infinity, 5], 'Empty groups should hold -Infinity');
+
+            tester.assertThrows(() => processor.parallel_group_by_sum(small, new Float64Array(4), 2), 'INVALID_ARGUMENT', 'Out-of-range keys should throw');
+            tester.assertThrows(() => processor.parallel_group_by_sum(small, new Float64Array(3), 3), 'DIMENSION_MISMATCH', 'Mismatched lengths should throw');
+        });
+
+        // Test 30: Premultiplied alpha conversion
+        tester.test('Alpha Premultiplication', () => {
+            const processor = new tester.wasm.WasmImageProcessor(0);
+
+            const opaque = new Uint8Array(256 * 4).map((_, i) => (i % 4 === 3 ? 255 : (i * 53) % 256));
+            const roundTrip = processor.unpremultiply_alpha(processor.premultiply_alpha(opaque));
+            tester.assertArrayEqual(Array.from(roundTrip), Array.from(opaque), 'Opaque pixels should round-trip losslessly');
+
+            const mixed = new Uint8Array([200, 100, 50, 128, 10, 20, 30, 0]);
+            const premultiplied = processor.premultiply_alpha(mixed);
+            tester.assertArrayEqual(Array.from(premultiplied), [100, 50, 25, 128, 0, 0, 0, 0], 'RGB should be scaled by alpha');
+            tester.assertArrayEqual(
+                Array.from(processor.unpremultiply_alpha(new Uint8Array([10, 20, 30, 0]))),
+                [0, 0, 0, 0],
+                'Fully transparent pixels should be zeroed'
+            );
+        });
+
+        // Test 31: Sobel and Canny edge detection
+        tester.test('Edge Detection', () => {
+            const processor = new tester.wasm.WasmImageProcessor(0);
+            const size = 64;
+            const radius = 20;
+            const circle = new Uint8Array(size * size * 4);
+            for (let y = 0; y < size; y++) {
+                for (let x = 0; x < size; x++) {
+                    const inside = Math.hypot(x - 31.5, y - 31.5) < radius ? 255 : 0;
+                    circle.set([inside, inside, inside, 255], (y * size + x) * 4);
+                }
+            }
+            const countWhere = (rgba, predicate) => {
+                let count = 0;
+                for (let i = 0; i < rgba.length; i += 4) {
+                    if (predicate(rgba[i])) count++;
+                }
+                return count;
+            };
+
+            const sobel = processor.sobel_edges(circle, size, size);
+            const sobelCount = countWhere(sobel, (value) => value > 0);
+            tester.assert(sobelCount > 200 && sobelCount < 500, `Sobel response should hug the rim, got ${sobelCount}`);
+            tester.assertEqual(sobel[(32 * size + 32) * 4], 0, 'Flat interior should have no gradient');
+
+            // The circumference is 2πr ≈ 126 pixels; thin edges stay close to that
+            const canny = processor.canny_edges(circle, size, size, 50, 150);
+            const cannyCount = countWhere(canny, (value) => value === 255);
+            tester.assert(cannyCount > 100 && cannyCount < 250, `Canny should trace a thin rim, got ${cannyCount}`);
+            tester.assert(cannyCount < sobelCount, 'Non-maximum suppression should thin the Sobel response');
+
+            tester.assertThrows(() => processor.canny_edges(circle, size, size, 150, 50), 'INVALID_ARGUMENT', 'Thresholds must satisfy 0 < low < high');
+        });
+
+        // Test 32: Haar wavelet transform
+        tester.test('Haar Wavelet Transform', () => {
+            const processor = new tester.wasm.WasmParallelProcessor(0);
+
+            tester.assertArrayEqual(Array.from(processor.parallel_haar_transform(new Float64Array([4, 2, 5, 5]))), [4, -1, 1, 0], 'Coefficients should be mean then coarse-to-fine details');
+
+            const signal = new Float64Array(4096).map((_, i) => Math.sin(i * 0.37) * Math.cos(i * 0.011));
+            const restored = processor.parallel_haar_inverse(processor.parallel_haar_transform(signal));
+            const maxError = signal.reduce((max, value, i) => Math.max(max, Math.abs(value - restored[i])), 0);
+            tester.assert(maxError < 1e-14, `Round trip should be accurate to 1e-14, got ${maxError}`);
+
+            const matrix = new Float64Array([1, 2, 3, 4, 5, 6, 7, 8]);
+            tester.assertArrayEqual(
+                Array.from(processor.parallel_haar_transform_2d(matrix, 2, 4)),
+                [4.5, -1, -0.5, -0.5, -2, 0, 0, 0],
+                '2D transform should apply rows then columns'
+            );
+
+            tester.assertThrows(() => processor.parallel_haar_transform(new Float64Array(6)), 'INVALID_ARGUMENT', 'Non-power-of-two lengths should throw');
+        });
+
+        // Test 33: Channel extraction, merging and swizzling
+        tester.test('Channel Operations', () => {
+            const processor = new tester.wasm.WasmImageProcessor(0);
+            const rgba = new Uint8Array([10, 20, 30, 40, 50, 60, 70, 80]);
+
+            tester.assertArrayEqual(Array.from(processor.extract_channel(rgba, 1)), [20, 60], 'Green plane should be extracted');
+            tester.assertArrayEqual(Array.from(processor.extract_channel(rgba, 3)), [40, 80], 'Alpha plane should be extracted');
+
+            const planes = [0, 1, 2, 3].map((channel) => processor.extract_channel(rgba, channel));
+            tester.assertArrayEqual(Array.from(processor.merge_channels(planes[0], planes[1], planes[2], planes[3])), Array.from(rgba), 'Merging extracted planes should restore the image');
+            tester.assertArrayEqual(Array.from(processor.merge_channels(planes[0], planes[1], planes[2])), [10, 20, 30, 255, 50, 60, 70, 255], 'Missing alpha should default to opaque');
+
+            const bgra = processor.swap_channels(rgba, new Uint8Array([2, 1, 0, 3]));
+            tester.assertArrayEqual(Array.from(bgra), [30, 20, 10, 40, 70, 60, 50, 80], 'BGRA swizzle should swap red and blue');
+            tester.assertArrayEqual(Array.from(processor.swap_channels(bgra, new Uint8Array([2, 1, 0, 3]))), Array.from(rgba), 'Swizzling twice should restore RGBA');
+
+            tester.assertThrows(() => processor.extract_channel(rgba, 4), 'INVALID_ARGUMENT', 'Channel indices above 3 should throw');
+            tester.assertThrows(() => processor.merge_channels(planes[0], planes[1], new Uint8Array(3)), 'INVALID_ARGUMENT', 'Mismatched planes should throw');
+            tester.assertThrows(() => processor.swap_channels(rgba, new Uint8Array([0, 0, 1, 2])), 'INVALID_ARGUMENT', 'Non-permutations should throw');
+
+            // BGRA -> RGBA on a 4K frame
+            const frame = new Uint8Array(3840 * 2160 * 4).map((_, i) => i % 256);
+            const start = performance.now();
+            processor.swap_channels(frame, new Uint8Array([2, 1, 0, 3]));
+            console.log(`   4K BGRA -> RGBA swizzle took ${(performance.now() - start).toFixed(1)}ms`);
+        });
+
+        // Test 34: Parallel text tokenization
+        tester.test('Parallel Tokenize', () => {
+            const processor = new tester.wasm.WasmParallelProcessor(0);
+            const texts = ['Hello, World!', "  It's the   world's END... ", '', '--- ***'];
+
+            const tokens = processor.parallel_tokenize(texts);
+            tester.assertEqual(tokens.length, 4, 'Should return one token array per string');
+            tester.assertArrayEqual(tokens[0], ['hello', 'world'], 'Punctuation should be stripped and case folded');
+            tester.assertArrayEqual(tokens[1], ["it's", 'the', "world's", 'end'], 'Apostrophes should be kept');
+            tester.assertArrayEqual(tokens[2], [], 'Empty strings should have no tokens');
+            tester.assertArrayEqual(tokens[3], [], 'Punctuation-only words should be dropped');
+
+            const encoded = processor.parallel_tokenize_flat(['the cat', 'The dog and the CAT']);
+            tester.assertArrayEqual(Array.from(encoded), [0, 1, 0, 2, 3, 0, 1], 'Tokens should be encoded by first occurrence');
+            tester.assertArrayEqual(processor.get_vocabulary(), ['the', 'cat', 'dog', 'and'], 'Vocabulary should list tokens by index');
+
+            tester.assertEqual(processor.parallel_tokenize([]).length, 0, 'Empty input should give no token arrays');
+            tester.assertEqual(processor.parallel_tokenize_flat([]).length, 0, 'Empty input should encode to nothing');
+            tester.assertEqual(processor.get_vocabulary().length, 0, 'Empty input should reset the vocabulary');
+
+            tester.assertThrows(() => processor.parallel_tokenize(['ok', 42]), 'INVALID_ARGUMENT', 'Non-string elements should throw');
+        });
+
+        // Test 35: Batch thumbnails
+        tester.test('Batch Thumbnail', () => {
+            const processor = new tester.wasm.WasmImageProcessor(0);
+
+            // 4x2 image, left half red and right half blue
+            const pixels = [];
+            for (let y = 0; y < 2; y++) {
+                for (let x = 0; x < 4; x++) {
+                    pixels.push(...(x < 2 ? [255, 0, 0, 255] : [0, 0, 255, 255]));
+                }
+            }
+            const wide = tester.wasm.WasmImage.from_rgba(new Uint8Array(pixels), 4, 2);
+            const plain = { data: new Uint8ClampedArray(pixels), width: 4, height: 2 };
+            const broken = { data: new Uint8Array(5), width: 4, height: 2 };
+
+            const contained = processor.batch_thumbnail([wide, plain, broken], 2, 2, 'contain');
+            tester.assertEqual(contained.length, 3, 'Should return one entry per image');
+            tester.assertArrayEqual(
+                Array.from(contained[0].data),
+                [255, 0, 0, 255, 0, 0, 255, 255, 0, 0, 0, 0, 0, 0, 0, 0],
+                'Contain should letterbox the scaled image'
+            );
+            tester.assertArrayEqual(Array.from(contained[1].data), Array.from(contained[0].data), 'Plain objects should match WasmImage input');
+            tester.assert(contained[2] instanceof Error, 'A bad entry should yield an Error without aborting the batch');
+
+            const covered = processor.batch_thumbnail([plain], 2, 2, 'cover');
+            tester.assertArrayEqual(
+                Array.from(covered[0].data),
+                [255, 0, 0, 255, 0, 0, 255, 255, 255, 0, 0, 255, 0, 0, 255, 255],
+                'Cover should crop around the centre'
+            );
+
+            const averaged = processor.batch_thumbnail([plain], 1, 1, 'cover');
+            tester.assertArrayEqual(Array.from(averaged[0].data), [128, 0, 128, 255], 'Pixels should be area-averaged');
+
+            tester.assertThrows(() => processor.batch_thumbnail([plain], 2, 2, 'stretch'), 'UNSUPPORTED_OPERATION', 'Unknown fit modes should throw');
+        });
+
+        // Test 36: Median and median absolute deviation
+        tester.test('Parallel Median', () => {
+            const processor = new tester.wasm.WasmParallelProcessor(0);
+
+            tester.assertEqual(processor.parallel_median(new Float64Array([5, 1, 3])), 3, 'Odd-length median should be the middle value');
+            tester.assertEqual(processor.parallel_median(new Float64Array([4, 1, 3, 2])), 2.5, 'Even-length median should average the middle values');
+            tester.assertEqual(processor.parallel_median(new Float64Array([7, 7, 1, 9])), 7, 'Repeated middle values should be handled');
+
+            const shuffled = Float64Array.from({ length: 200001 }, (_, i) => (i * 7919) % 200001);
+            tester.assertEqual(processor.parallel_median(shuffled), 100000, 'Large median should match the sorted middle');
+
+            // Box-Muller standard normal sample; MAD of N(0, 1) is about 0.6745
+            const normal = new Float64Array(100000);
+            for (let i = 0; i < normal.length; i++) {
+                const u = 1 - Math.random();
+                const v = Math.random();
+                normal[i] = Math.sqrt(-2 * Math.log(u)) * Math.cos(2 * Math.PI * v);
+            }
+            const mad = processor.parallel_median_absolute_deviation(normal);
+            tester.assert(Math.abs(mad - 0.6745) / 0.6745 < 0.02, `MAD of a standard normal sample should be about 0.674, got ${mad}`);
+
+            for (const invalid of [new Float64Array(0), new Float64Array([1, NaN, 2])]) {
+                tester.assertThrows(() => processor.parallel_median(invalid), 'INVALID_ARGUMENT', 'Empty or NaN input should throw');
+            }
+        });
+
+        // Test 37: Typed batch operations
+        tester.test('Batch Operations', () => {
+            const processor = new tester.wasm.WasmBatchProcessor(1024, 0);
+            const BatchOp = tester.wasm.BatchOp;
+            const data = Float64Array.from({ length: 5000 }, (_, i) => (i - 2500) / 397 + 0.001);
+            const positive = data.map(Math.abs);
+
+            const reference = [
+                [BatchOp.Square, 'square', (x) => x * x, data],
+                [BatchOp.Sqrt, 'sqrt', Math.sqrt, positive],
+                [BatchOp.Sin, 'sin', Math.sin, data],
+                [BatchOp.Cos, 'cos', Math.cos, data],
+                [BatchOp.Tan, 'tan', Math.tan, data],
+                [BatchOp.Exp, 'exp', Math.exp, data],
+                [BatchOp.Ln, 'ln', Math.log, positive],
+                [BatchOp.Abs, 'abs', Math.abs, data],
+                [BatchOp.Neg, 'neg', (x) => -x, data],
+                [BatchOp.Reciprocal, 'reciprocal', (x) => 1 / x, data],
+                [BatchOp.Sigmoid, 'sigmoid', (x) => 1 / (1 + Math.exp(-x)), data],
+                [BatchOp.Tanh, 'tanh', Math.tanh, data],
+                [BatchOp.Relu, 'relu', (x) => Math.max(x, 0), data],
+            ];
+            for (const [op, name, f, input] of reference) {
+                // Both the sequential (short) and parallel (long) paths
+                for (const values of [input.subarray(0, 16), input]) {
+                    const result = processor.process_batch(values, op, true);
+                    tester.assertEqual(result.length, values.length, `${name} should keep the length`);
+                    for (let i = 0; i < values.length; i++) {
+                        const expected = f(values[i]);
+                        const tolerance = 1e-12 * Math.max(1, Math.abs(expected));
+                        tester.assert(Math.abs(result[i] - expected) <= tolerance, `${name}(${values[i]}) should be ${expected}, got ${result[i]}`);
+                    }
+                }
+                tester.assertArrayEqual(
+                    Array.from(processor.process_batch_str(input.subarray(0, 16), name.toUpperCase(), true)),
+                    Array.from(processor.process_batch(input.subarray(0, 16), op, true)),
+                    `process_batch_str('${name}') should match the enum`
+                );
+            }
+
+            const edge = new Float64Array([0, -1, 2]);
+            const ln = processor.process_batch(edge, BatchOp.Ln, false);
+            tester.assertEqual(ln[0], -Infinity, 'Non-strict ln(0) should be -inf');
+            tester.assert(Number.isNaN(ln[1]), 'Non-strict ln(-1) should be NaN');
+            tester.assertEqual(processor.process_batch(new Float64Array([0, -0]), BatchOp.Reciprocal, false)[1], -Infinity, 'Non-strict 1/-0 should be -inf');
+
+            tester.assertThrows(() => processor.process_batch(edge, BatchOp.Ln, true), 'OUT_OF_DOMAIN', 'Strict ln of non-positive values should throw');
+            tester.assertThrows(() => processor.process_batch(edge, BatchOp.Reciprocal, true), 'OUT_OF_DOMAIN', 'Strict reciprocal of zero should throw');
+            tester.assertThrows(() => processor.process_batch_str(edge, 'cube', false), 'UNSUPPORTED_OPERATION', 'Unknown operation names should throw');
+        });
+
+        // Test 38: Sampling without replacement
+        tester.test('Sample Without Replacement', () => {
+            const processor = new tester.wasm.WasmParallelProcessor(0);
+
+            const sample = processor.parallel_sample_without_replacement(100000, 5000, 42n);
+            tester.assertEqual(sample.length, 5000, 'Should return k indices');
+            tester.assertEqual(new Set(sample).size, 5000, 'Indices should not repeat');
+            tester.assert(sample.every((index) => index < 100000), 'Indices should lie in 0..n');
+            tester.assertArrayEqual(
+                Array.from(processor.parallel_sample_without_replacement(100000, 5000, 42n)),
+                Array.from(sample),
+                'The same seed should reproduce the sample'
+            );
+
+            const everything = processor.parallel_sample_without_replacement(10, 10, 7n);
+            tester.assertArrayEqual(Array.from(everything).sort((a, b) => a - b), [0, 1, 2, 3, 4, 5, 6, 7, 8, 9], 'k = n should be a permutation');
+            tester.assertEqual(processor.parallel_sample_without_replacement(10, 0, 7n).length, 0, 'k = 0 should be empty');
+
+            tester.assertThrows(() => processor.parallel_sample_without_replacement(3, 4, 0n), 'INVALID_ARGUMENT', 'k > n should throw');
+        });
+
+        // Test 39: Fused operation chains
+        tester.test('Batch Operation Chain', () => {
+            const processor = new tester.wasm.WasmBatchProcessor(1024, 0);
+            const data = Float64Array.from({ length: 10000 }, (_, i) => i * 0.01);
+
+            const chained = processor.process_chain(data, [{ op: 'sqrt' }, { op: 'mul', value: 2 }, { op: 'sin' }]);
+            const expected = Array.from(data, (x) => Math.sin(Math.sqrt(x) * 2));
+            for (let i = 0; i < data.length; i++) {
+                tester.assert(Math.abs(chained[i] - expected[i]) < 1e-12, `Chain result ${i} should be ${expected[i]}, got ${chained[i]}`);
+            }
+
+            tester.assertArrayEqual(
+                Array.from(processor.process_chain(new Float64Array([-2, 0.5, 3]), [{ op: 'add', value: 1 }, { op: 'pow', value: 2 }, { op: 'clamp', min: 0, max: 4 }])),
+                [1, 2.25, 4],
+                'Scalar steps should apply in order'
+            );
+            tester.assertArrayEqual(Array.from(processor.process_chain(new Float64Array([1, 2]), [])), [1, 2], 'An empty chain should copy the input');
+
+            const errorFor = (ops, code) => String(tester.assertThrows(() => processor.process_chain(data, ops), code));
+            tester.assert(errorFor([{ op: 'sqrt' }, { op: 'cube' }], 'INVALID_ARGUMENT').includes('step 1'), 'Unknown ops should name the bad step');
+            tester.assert(errorFor([{ op: 'mul' }], 'INVALID_ARGUMENT').includes('step 0'), 'Missing values should name the bad step');
+            tester.assert(errorFor([{ op: 'abs' }, { op: 'abs' }, { op: 'clamp', min: 2, max: 1 }], 'INVALID_ARGUMENT').includes('step 2'), 'Inverted clamps should name the bad step');
+            tester.assertThrows(() => processor.process_chain(data, { op: 'sqrt' }), 'INVALID_ARGUMENT', 'A non-array chain should throw');
+        });
+
+        // Test 40: Small-matrix SVD and pseudo-inverse
+        tester.test('Matrix SVD', () => {
+            const processor = new tester.wasm.WasmMatrixProcessor(0);
+            const multiply = (a, b, n) => Array.from({ length: n * n }, (_, k) => {
+                let sum = 0;
+                for (let i = 0; i < n; i++) {
+                    sum += a[Math.floor(k / n) * n + i] * b[i * n + (k % n)];
+                }
+                return sum;
+            });
+            const transpose = (m, n) => Array.from({ length: n * n }, (_, k) => m[(k % n) * n + Math.floor(k / n)]);
+            const identity = (n) => Array.from({ length: n * n }, (_, k) => (Math.floor(k / n) === k % n ? 1 : 0));
+            const assertClose = (actual, expected, message) => {
+                actual.forEach((value, i) => tester.assert(Math.abs(value - expected[i]) < 1e-12, `${message} (entry ${i}: ${value} vs ${expected[i]})`));
+            };
+
+            const cases = [
+                [2, [3, 1, -2, 4]],
+                [2, [1, 2, 2, 4]],
+                [3, [2, -1, 0, -1, 2, -1, 0, -1, 2]],
+                [3, [1, 2, 3, 4, 5, 6, 7, 8, 10]],
+                [3, [0, 1, 0, 0, 0, 2, 0, 0, 0]],
+            ];
+            for (const [n, matrix] of cases) {
+                const { u, sigma, vt } = n === 2 ? processor.svd_2x2(new Float64Array(matrix)) : processor.svd_3x3(new Float64Array(matrix));
+                assertClose(multiply(transpose(u, n), u, n), identity(n), `U should be orthonormal for ${matrix}`);
+                assertClose(multiply(vt, transpose(vt, n), n), identity(n), `V should be orthonormal for ${matrix}`);
+                const sigmaMatrix = Array.from({ length: n * n }, (_, k) => (Math.floor(k / n) === k % n ? sigma[k % n] : 0));
+                assertClose(multiply(multiply(u, sigmaMatrix, n), vt, n), matrix, `U * Sigma * Vt should recover ${matrix}`);
+                tester.assert(Array.from(sigma).every((s, i) => s >= 0 && (i === 0 || s <= sigma[i - 1])), 'Singular values should be non-negative and descending');
+            }
+
+            assertClose(processor.pseudo_inverse_2x2(new Float64Array([1, 2, 3, 4])), [-2, 1, 1.5, -0.5], 'Pseudo-inverse of an invertible matrix is its inverse');
+            assertClose(processor.pseudo_inverse_2x2(new Float64Array([1, 2, 2, 4])), [0.04, 0.08, 0.08, 0.16], 'Pseudo-inverse of a rank-1 matrix');
+            const singular = [1, 2, 3, 2, 4, 6, 1, 0, 1];
+            const pinv = processor.pseudo_inverse_3x3(new Float64Array(singular));
+            assertClose(multiply(multiply(singular, pinv, 3), singular, 3), singular, 'A * A+ * A should equal A');
+
+            tester.assertThrows(() => processor.svd_3x3(new Float64Array(4)), 'DIMENSION_MISMATCH', 'Wrongly sized matrices should throw');
+        });
+
+        // Test 41: Binary and scalar batch operations
+        tester.test('Batch Binary Operations', () => {
+            const processor = new tester.wasm.WasmBatchProcessor(1024, 0);
+            const BatchOp2 = tester.wasm.BatchOp2;
+            const a = Float64Array.from({ length: 6000 }, (_, i) => (i % 97) / 7 - 6);
+            const b = Float64Array.from({ length: 6000 }, (_, i) => (i % 89) / 5 + 0.5);
+
+            const reference = [
+                [BatchOp2.Add, 'add', (x, y) => x + y],
+                [BatchOp2.Sub, 'sub', (x, y) => x - y],
+                [BatchOp2.Mul, 'mul', (x, y) => x * y],
+                [BatchOp2.Div, 'div', (x, y) => x / y],
+                [BatchOp2.Pow, 'pow', (x, y) => Math.pow(Math.abs(x), y)],
+                [BatchOp2.Min, 'min', Math.min],
+                [BatchOp2.Max, 'max', Math.max],
+                [BatchOp2.Atan2, 'atan2', Math.atan2],
+                [BatchOp2.Hypot, 'hypot', Math.hypot],
+            ];
+            const close = (actual, expected) => Math.abs(actual - expected) <= 1e-12 * Math.max(1, Math.abs(expected));
+            for (const [op, name, f] of reference) {
+                const lhs = name === 'pow' ? a.map(Math.abs) : a;
+                // Short inputs take the sequential path, long ones the parallel path
+                const short = processor.process_binary(lhs.subarray(0, 100), b.subarray(0, 100), op, true);
+                const long = processor.process_binary(lhs, b, op, true);
+                tester.assertArrayEqual(Array.from(short), Array.from(long.subarray(0, 100)), `${name} should agree between sequential and parallel paths`);
+                long.forEach((value, i) => tester.assert(close(value, f(lhs[i], b[i])), `${name}(${lhs[i]}, ${b[i]}) should be ${f(lhs[i], b[i])}, got ${value}`));
+
+                const scalar = processor.process_scalar(lhs, 2.5, op, true);
+                scalar.forEach((value, i) => tester.assert(close(value, f(lhs[i], 2.5)), `${name}(${lhs[i]}, 2.5) should be ${f(lhs[i], 2.5)}, got ${value}`));
+                tester.assertArrayEqual(
+                    Array.from(processor.process_scalar(lhs.subarray(0, 100), 2.5, op, true)),
+                    Array.from(scalar.subarray(0, 100)),
+                    `Scalar ${name} should agree between sequential and parallel paths`
+                );
+            }
+
+            const zeros = new Float64Array([1, -1, 0]);
+            tester.assertArrayEqual(Array.from(processor.process_binary(zeros, new Float64Array(3), BatchOp2.Div, false)).slice(0, 2), [Infinity, -Infinity], 'Non-strict division by zero should give inf');
+            tester.assert(Number.isNaN(processor.process_scalar(zeros, 0, BatchOp2.Div, false)[2]), 'Non-strict 0 / 0 should be NaN');
+
+            tester.assertThrows(() => processor.process_binary(zeros, new Float64Array([1, 0, 1]), BatchOp2.Div, true), 'OUT_OF_DOMAIN', 'Strict division by zero should throw');
+            tester.assertThrows(() => processor.process_scalar(zeros, 0, BatchOp2.Div, true), 'OUT_OF_DOMAIN', 'Strict scalar division by zero should throw');
+            tester.assertThrows(() => processor.process_binary(a, b.subarray(1), BatchOp2.Add, false), 'DIMENSION_MISMATCH', 'Mismatched lengths should throw');
+        });
+
+        // Test 42: Streaming window over pushed samples
+        tester.test('Streaming Window', () => {
+            const processor = tester.wasm.WasmBatchProcessor.with_window(16, 0, 3);
+            tester.assertEqual(processor.window_size, 3, 'Window size should be configurable');
+
+            tester.assertEqual(processor.push_sample(1), undefined, 'A filling window should yield nothing');
+            tester.assertEqual(processor.push_sample(2), undefined, 'A filling window should yield nothing');
+            tester.assertEqual(processor.push_sample(3), 2, 'A full window should yield its mean');
+            tester.assertEqual(processor.push_sample(7), 4, 'The oldest sample should be evicted');
+
+            processor.set_operation('max');
+            tester.assertEqual(processor.push_sample(0), 7, 'The operation should be switchable mid-stream');
+            processor.set_operation('sum');
+            tester.assertEqual(processor.flush(), 10, 'Flush should reduce the current window');
+            tester.assertEqual(processor.flush(), undefined, 'Flushing an empty window should yield nothing');
+
+            tester.assertEqual(processor.push_sample(5), undefined, 'The window should refill after a flush');
+            tester.assertEqual(processor.flush(), 5, 'Flush should reduce a partial window');
+            processor.push_sample(1);
+            processor.push_sample(1);
+            tester.assertEqual(processor.push_sample(1), 3, 'Pushing after a flush should restart cleanly');
+
+            processor.set_operation('std');
+            processor.flush();
+            [2, 4, 4].forEach((x) => processor.push_sample(x));
+            processor.set_operation('variance');
+            tester.assert(Math.abs(processor.flush() - 8 / 9) < 1e-12, 'Variance should be the population variance');
+
+            tester.assertEqual(new tester.wasm.WasmBatchProcessor(16, 0).window_size, 32, 'The default window should hold 32 samples');
+
+            tester.assertThrows(() => processor.set_operation('mode'), 'UNSUPPORTED_OPERATION', 'Unknown window operations should throw');
+            tester.assertThrows(() => tester.wasm.WasmBatchProcessor.with_window(16, 0, 0), 'INVALID_ARGUMENT', 'A zero-sized window should throw');
+        });
+
+        // Test 43: Batch output without cloning
+        tester.test('Batch Output Views', () => {
+            const processor = new tester.wasm.WasmBatchProcessor(1024, 0);
+            const BatchOp = tester.wasm.BatchOp;
+            const data = Float64Array.from({ length: 10000 }, (_, i) => i);
+
+            const out = new Float64Array(data.length);
+            processor.process_batch_into(data, BatchOp.Square, true, out);
+            tester.assertArrayEqual(Array.from(out.subarray(0, 4)), [0, 1, 4, 9], 'Results should be written into the caller array');
+            tester.assertEqual(out[9999], 9999 * 9999, 'Every element should be written');
+
+            const returned = processor.process_batch(data, BatchOp.Sqrt, false);
+            const view = processor.last_output_view();
+            tester.assertEqual(view.length, data.length, 'The view should cover the last output');
+            tester.assertArrayEqual(Array.from(view), Array.from(returned), 'The view should alias the last output');
+
+            tester.assertThrows(() => processor.process_batch_into(data, BatchOp.Square, false, new Float64Array(3)), 'DIMENSION_MISMATCH', 'A short output array should throw');
+            tester.assertThrows(() => processor.process_batch_into(new Float64Array([0]), BatchOp.Ln, true, new Float64Array(1)), 'OUT_OF_DOMAIN', 'Strict domain errors should throw');
+        });
+
+        // Test 44: Zero padding of 2D tensors
+        tester.test('Tensor Padding', () => {
+            const processor = new tester.wasm.WasmParallelProcessor(0);
+
+            const { Padding2d } = tester.wasm;
+            tester.assertArrayEqual(
+                Array.from(processor.parallel_pad_2d(new Float64Array([1, 2, 3, 4]), 2, 2, new Padding2d(1, 0, 0, 1))),
+                [0, 0, 0, 1, 2, 0, 3, 4, 0],
+                'Padding should surround the tensor with zeros'
+            );
+
+            const shapes = [[1, 1], [2, 3], [4, 5], [0, 3]];
+            const paddings = [[0, 0, 0, 0], [1, 2, 3, 4], [0, 5, 5, 0], [8, 8, 8, 8]];
+            for (const [rows, cols] of shapes) {
+                const x = Float64Array.from({ length: rows * cols }, (_, i) => i + 1);
+                for (const [top, bottom, left, right] of paddings) {
+                    const padding = new Padding2d(top, bottom, left, right);
+                    const padded = processor.parallel_pad_2d(x, rows, cols, padding);
+                    tester.assertEqual(padded.length, (rows + top + bottom) * (cols + left + right), 'Padded size should include the padding');
+                    const restored = processor.parallel_unpad_2d(padded, rows, cols, padding);
+                    if (top > rows || bottom > rows || left > cols || right > cols) {
+                        tester.assert(padded.every((v) => v === 0), `Padding ${[top, bottom, left, right]} wider than ${rows}x${cols} should give all zeros`);
+                        tester.assert(restored.length === x.length && restored.every((v) => v === 0), `Unpadding ${[top, bottom, left, right]} from ${rows}x${cols} should give all zeros`);
+                    } else {
+                        tester.assertArrayEqual(Array.from(restored), Array.from(x), `unpad(pad(x)) should equal x for ${rows}x${cols} with padding ${[top, bottom, left, right]}`);
+                    }
+                }
+            }
+
+            const empty = processor.parallel_pad_2d(new Float64Array(0), 0, 0, new Padding2d(2, 1, 1, 2));
+            tester.assertEqual(empty.length, 9, 'An empty tensor should pad to the padding size');
+            tester.assert(empty.every((x) => x === 0), 'An empty tensor should pad to all zeros');
+
+            tester.assertThrows(() => processor.parallel_unpad_2d(new Float64Array(5), 1, 1, new Padding2d(1, 1, 1, 1)), 'DIMENSION_MISMATCH', 'A padded tensor of the wrong size should throw');
+        });
+
+        // Test 45: Batch processor capacity management
+        tester.test('Batch Capacity', () => {
+            const BatchOp = tester.wasm.BatchOp;
+            const small = new Float64Array(100).fill(4);
+            const large = new Float64Array(5000).fill(4);
+
+            const growable = new tester.wasm.WasmBatchProcessor(100, 0);
+            tester.assert(!growable.strict_capacity, 'Processors should grow by default');
+            tester.assert(growable.capacity() >= 100, 'Capacity should cover the batch size');
+            growable.process_batch(large, BatchOp.Sqrt, false);
+            tester.assert(growable.capacity() >= 5000, 'An oversized batch should grow the buffer');
+            tester.assertEqual(growable.memory_bytes(), (growable.capacity() + growable.window_size) * 8, 'memory_bytes should cover every buffer');
+            growable.shrink_to_fit();
+            tester.assert(growable.capacity() < 5000 && growable.capacity() >= 100, 'shrink_to_fit should drop back to the batch size');
+
+            const strict = tester.wasm.WasmBatchProcessor.with_strict_capacity(100, 0);
+            tester.assert(strict.strict_capacity, 'Strict capacity should be reported');
+            tester.assertEqual(strict.process_batch(small, BatchOp.Sqrt, false)[0], 2, 'Batches within capacity should succeed');
+
+            tester.assertThrows(() => strict.process_batch(large, BatchOp.Sqrt, false), 'CAPACITY_EXCEEDED', 'Oversized batches should be rejected');
+            tester.assertThrows(() => strict.process_scalar(large, 2, tester.wasm.BatchOp2.Mul, false), 'CAPACITY_EXCEEDED', 'Oversized scalar batches should be rejected');
+            tester.assertThrows(() => strict.fma_batch(large, 2, 1), 'CAPACITY_EXCEEDED', 'Oversized FMA batches should be rejected');
+            tester.assert(strict.capacity() < 5000, 'A rejected batch should not grow the buffer');
+
+            strict.reserve(5000);
+            tester.assert(strict.capacity() >= 5000, 'reserve should grow the buffer explicitly');
+            tester.assertEqual(strict.process_batch(large, BatchOp.Sqrt, false).length, 5000, 'Reserved capacity should admit larger batches');
+        });
+
+        // Test 46: FFT convolution
+        tester.test('Parallel FFT Convolve', () => {
+            const processor = new tester.wasm.WasmParallelProcessor(0);
+            const signal = new Float64Array(1024).map((_, i) => Math.sin(i * 0.37) + ((i * 7919) % 13) / 13);
+            const kernel = new Float64Array(32).map((_, i) => Math.cos(i * 0.21) / (i + 1));
+
+            const direct = new Float64Array(signal.length + kernel.length - 1);
+            for (let i = 0; i < signal.length; i++) {
+                for (let j = 0; j < kernel.length; j++) {
+                    direct[i + j] += signal[i] * kernel[j];
+                }
+            }
+
+            const result = processor.parallel_fft_convolve(signal, kernel);
+            tester.assertEqual(result.length, direct.length, 'Convolution length should be n + k - 1');
+            const maxError = result.reduce((max, value, i) => Math.max(max, Math.abs(value - direct[i])), 0);
+            tester.assert(maxError < 1e-10, `FFT and direct convolution should agree, max error ${maxError}`);
+
+            const short = processor.parallel_fft_convolve(new Float64Array([1, 2, 3]), new Float64Array([1, -1]));
+            tester.assertArrayEqual(Array.from(short), [1, 1, 1, -3], 'Short kernels should convolve directly');
+        });
+
+        // Test 47: Polynomial and piecewise-linear calibration
+        tester.test('Batch Calibration Curves', () => {
+            const processor = new tester.wasm.WasmBatchProcessor(16, 0);
+            const data = new Float64Array([-1, 0, 0.5, 1, 2, 3, 4]);
+
+            const poly = processor.process_polynomial(data, new Float64Array([1, 2, 3]));
+            tester.assertArrayEqual(Array.from(poly), Array.from(data, x => 1 + 2 * x + 3 * x * x), 'Polynomial should match 1 + 2x + 3x^2');
+
+            const xs = new Float64Array([0, 1, 3]);
+            const ys = new Float64Array([0, 10, 20]);
+            const clamped = processor.process_piecewise_linear(data, xs, ys, true);
+            tester.assertArrayEqual(Array.from(clamped), [0, 0, 5, 10, 15, 20, 20], 'Clamped mapping should hold the end values');
+            const extrapolated = processor.process_piecewise_linear(data, xs, ys, false);
+            tester.assertArrayEqual(Array.from(extrapolated), [-10, 0, 5, 10, 15, 20, 25], 'Unclamped mapping should extend the end segments');
+
+            const message = String(tester.assertThrows(() => processor.process_piecewise_linear(data, new Float64Array([0, 2, 2, 3]), new Float64Array(4), true), 'INVALID_ARGUMENT'));
+            tester.assert(message.includes('xs[2]'), `The first non-increasing breakpoint should be reported, got "${message}"`);
+        });
+
+        // Test 48: ARIMA fitting and forecasting
+        tester.test('Parallel ARIMA', () => {
+            const processor = new tester.wasm.WasmParallelProcessor(0);
+
+            // Seeded AR(1) with phi = 0.7 and standard normal shocks
+            let state = 12345;
+            const uniform = () => {
+                state = (Math.imul(state, 1103515245) + 12345) >>> 0;
+                return (state + 0.5) / 4294967296;
+            };
+            const normal = () => Math.sqrt(-2 * Math.log(uniform())) * Math.cos(2 * Math.PI * uniform());
+            const series = new Float64Array(100000);
+            for (let t = 1; t < series.length; t++) {
+                series[t] = 0.7 * series[t - 1] + normal();
+            }
+
+            const coeffs = processor.parallel_arima_fit(series, 1, 0, 0);
+            tester.assertEqual(coeffs.length, 1, 'AR(1) should have one coefficient');
+            tester.assert(Math.abs(coeffs[0] - 0.7) < 0.01, `Fitted phi should be near 0.7, got ${coeffs[0]}`);
+
+            const forecast = processor.arima_forecast(series, coeffs, 1, 0, 0, 3);
+            tester.assertEqual(forecast.length, 3, 'Forecast should cover the requested steps');
+            const mean = series.reduce((sum, x) => sum + x, 0) / series.length;
+            let expected = series[series.length - 1];
+            forecast.forEach((value, step) => {
+                expected = mean + coeffs[0] * (expected - mean);
+                tester.assert(Math.abs(value - expected) < 1e-9, `Step ${step} should decay towards the mean: ${value} vs ${expected}`);
+            });
+
+            tester.assertThrows(() => processor.parallel_arima_fit(series, 0, 1, 0), 'INVALID_ARGUMENT', 'p + q = 0 should be rejected');
+            tester.assertThrows(() => processor.parallel_arima_fit(series.subarray(0, 4), 2, 1, 1), 'INVALID_ARGUMENT', 'Short series should be rejected');
+        });
+
+        // Test 49: Single-precision and interleaved complex batches
+        tester.test('Batch f32 and Complex', () => {
+            const { BatchOp, BatchOp2 } = tester.wasm;
+            const processor = new tester.wasm.WasmBatchProcessor(16, 0);
+
+            const roots = processor.process_batch_f32(new Float32Array([4, 9, 2]), BatchOp.Sqrt, false);
+            tester.assert(roots instanceof Float32Array, 'f32 batches should return a Float32Array');
+            tester.assertArrayEqual(Array.from(roots), [2, 3, Math.fround(Math.SQRT2)], 'f32 sqrt should round to single precision');
+            const sums = processor.process_binary_f32(new Float32Array([1, 2]), new Float32Array([0.5, 0.25]), BatchOp2.Add, false);
+            tester.assertArrayEqual(Array.from(sums), [1.5, 2.25], 'f32 binary ops should add pairs');
+
+            const pairs = new Float64Array([3, 4, 0, -2, 0, 0]);
+            tester.assertArrayEqual(Array.from(processor.process_complex(pairs, 'magnitude')), [5, 2, 0], 'Magnitude should be |z|');
+            const phases = processor.process_complex(pairs, 'phase');
+            [Math.atan2(4, 3), -Math.PI / 2, 0].forEach((expected, i) => tester.assert(Math.abs(phases[i] - expected) < 1e-15, `Phase ${i} should be atan2(im, re)`));
+            tester.assertArrayEqual(Array.from(processor.process_complex(pairs, 'conjugate')), [3, -4, 0, 2, 0, -0], 'Conjugate should negate im');
+            tester.assertArrayEqual(Array.from(processor.process_complex(pairs, 'normalize')), [0.6, 0.8, 0, -1, 0, 0], 'Normalize should scale to unit length');
+
+            tester.assertThrows(() => processor.process_complex(new Float64Array(3), 'magnitude'), 'INVALID_ARGUMENT', 'Odd-length input should be rejected');
+            tester.assertThrows(() => processor.process_complex(pairs, 'modulus'), 'UNSUPPORTED_OPERATION', 'Unknown operations should be rejected');
+        });
+
+        // Test 50: Flood fill
+        tester.test('Flood Fill', () => {
+            const processor = new tester.wasm.WasmImageProcessor(0);
+            const red = [255, 0, 0, 255];
+            const fill = (x, y, tolerance) => new tester.wasm.FloodFill(x, y, new Uint8Array(red), tolerance);
+
+            const solid = new Uint8Array(8 * 6 * 4).fill(40);
+            const filled = processor.flood_fill(solid, 8, 6, fill(3, 2, 0));
+            for (let i = 0; i < filled.length; i += 4) {
+                tester.assertArrayEqual(Array.from(filled.subarray(i, i + 4)), red, `Pixel ${i / 4} should take the fill colour`);
+            }
+
+            // A 5x3 image split by a wall in column 2; the left side varies by up to 5
+            const image = new Uint8Array(5 * 3 * 4);
+            for (let p = 0; p < 15; p++) {
+                const x = p % 5;
+                const value = x === 2 ? 200 : 10 + (p % 2) * 5;
+                image.set([value, value, value, 255], p * 4);
+            }
+            const within = processor.flood_fill(image, 5, 3, fill(0, 0, 5));
+            for (let p = 0; p < 15; p++) {
+                const changed = within[p * 4] === 255 && within[p * 4 + 1] === 0;
+                tester.assertEqual(changed, p % 5 < 2, `Pixel ${p} should ${p % 5 < 2 ? '' : 'not '}be filled`);
+            }
+            const strict = processor.flood_fill(image, 5, 3, fill(0, 0, 4));
+            tester.assertEqual(strict[4], 15, 'Pixels outside the tolerance should be left alone');
+
+            tester.assertThrows(() => processor.flood_fill(solid, 8, 6, fill(8, 0, 0)), 'INVALID_ARGUMENT', 'Out-of-bounds seeds should be rejected');
+            tester.assertThrows(() => new tester.wasm.FloodFill(0, 0, new Uint8Array(3), 0), 'DIMENSION_MISMATCH', 'Fill colours must be 4 bytes');
+        });
+
+        // Test 51: Structured errors
+        tester.test('Structured Errors', () => {
+            const batch = new tester.wasm.WasmBatchProcessor(0, 0);
+            const domain = tester.assertThrows(() => batch.process_batch(new Float64Array([1, 0]), tester.wasm.BatchOp.Ln, true), 'OUT_OF_DOMAIN');
+            tester.assert(domain instanceof Error, 'Errors should be Error instances');
+            tester.assertEqual(domain.name, 'WasmError');
+            tester.assertEqual(domain.details.index, 1);
+            tester.assertArrayEqual(domain.details.values, [0]);
+            tester.assertEqual(JSON.stringify(JSON.parse(JSON.stringify(domain.details))), JSON.stringify(domain.details), 'Details should survive a JSON round trip');
+
+            const unknown = tester.assertThrows(() => batch.process_batch_str(new Float64Array([1]), 'cube', false), 'UNSUPPORTED_OPERATION');
+            tester.assertEqual(unknown.details.op, 'cube');
+
+            const matrix = new tester.wasm.WasmMatrixProcessor(0);
+            const mismatch = tester.assertThrows(() => matrix.solve_linear_system(new Float64Array([1, 2, 3, 4]), new Float64Array([1]), 2), 'DIMENSION_MISMATCH');
+            tester.assertEqual(mismatch.details.expected, 2);
+            tester.assertEqual(mismatch.details.actual, 1);
+        });
+
+        // Test 52: Distance matrix and nearest neighbours
+        tester.test('Distance Matrix', () => {
+            const processor = new tester.wasm.WasmParallelProcessor(0);
+            const n = 40;
+            const points = new Float64Array(n * 3);
+            for (let i = 0; i < points.length; i++) {
+                points[i] = Math.sin(i * 12.9898) * 43.758;
+            }
+
+            const matrix = processor.parallel_distance_matrix(points, n, 3);
+            tester.assertEqual(matrix.length, n * (n + 1) / 2, 'The upper triangle should include the diagonal');
+            const at = (i, j) => {
+                const [row, col] = i <= j ? [i, j] : [j, i];
+                return matrix[row * n - row * (row - 1) / 2 + (col - row)];
+            };
+            const distance = (i, j) => Math.hypot(...[0, 1, 2].map((d) => points[i * 3 + d] - points[j * 3 + d]));
+            for (let i = 0; i < n; i++) {
+                tester.assertEqual(at(i, i), 0, `Point ${i} should be at distance 0 from itself`);
+                for (let j = i + 1; j < n; j++) {
+                    tester.assert(Math.abs(at(i, j) - distance(i, j)) <= 1e-5 * distance(i, j), `Distance ${i}-${j} should match`);
+                }
+            }
+
+            const k = 4;
+            const neighbours = processor.parallel_nearest_neighbors(points, n, 3, k);
+            tester.assertEqual(neighbours.length, n * k);
+            for (let i = 0; i < n; i++) {
+                const expected = [...Array(n).keys()]
+                    .filter((j) => j !== i)
+                    .sort((a, b) => distance(i, a) - distance(i, b) || a - b)
+                    .slice(0, k);
+                tester.assertArrayEqual(Array.from(neighbours.subarray(i * k, (i + 1) * k)), expected, `Neighbours of point ${i} should match`);
+            }
+
+            tester.assertThrows(() => processor.parallel_distance_matrix(points, n, 4), 'DIMENSION_MISMATCH');
+            tester.assertThrows(() => processor.parallel_nearest_neighbors(points, n, 3, n), 'INVALID_ARGUMENT');
+        });
+
+        // Test 53: Cache keys follow content, not length
+        tester.test('Content-Keyed Cache', async () => {
+            const module = new tester.wasm.WasmModule();
+            const first = new Uint8Array([1, 2, 3, 4]);
+            const second = new Uint8Array([9, 8, 7, 6]);
+
+            tester.assertArrayEqual(Array.from(module.process_data(first)), [0xAE, 0xA9, 0xA8, 0xAB]);
+            tester.assertArrayEqual(Array.from(module.process_data(second)), [0xAC, 0xAD, 0xA2, 0xA3], 'A same-length input should not reuse the first result');
+            tester.assertEqual(module.cache_size, 2, 'Same-length inputs should get separate entries');
+            tester.assertArrayEqual(Array.from(module.process_data(first)), [0xAE, 0xA9, 0xA8, 0xAB], 'Repeated input should hit its own entry');
+            tester.assertEqual(module.cache_size, 2);
+
+            tester.assertArrayEqual(Array.from(await module.process_data_async(first)), [1, 3, 5, 7]);
+            tester.assertEqual(module.cache_size, 3, 'Async results should be cached once they complete');
+            tester.assertArrayEqual(Array.from(await module.process_data_async(second)), [9, 9, 9, 9], 'A same-length async input should not reuse the first result');
+            tester.assertArrayEqual(Array.from(await module.process_data_async(first)), [1, 3, 5, 7], 'Cached async results should match');
+            tester.assertEqual(module.cache_size, 4);
+
+            const keys = module.cache_keys();
+            tester.assertEqual(keys.length, 4);
+            tester.assertEqual(keys.filter((key) => key.startsWith('async_')).length, 2);
+            tester.assert(keys.every((key) => module.cache_contains(key)), 'Every listed key should be contained');
+            tester.assert(!module.cache_contains('sync_4'), 'Length-based keys should no longer exist');
+
+            module.clear_cache();
+            tester.assertEqual(module.cache_keys().length, 0);
+        });
+
+        // Test 54: Parallel UTF-8 validation
+        tester.test('Parallel UTF-8 Validation', () => {
+            const processor = new tester.wasm.WasmParallelProcessor(0);
+            const encoder = new TextEncoder();
+            const chunk = 64 * 1024;
+            const fatal = new TextDecoder('utf-8', { fatal: true });
+            const expected = (bytes) => {
+                try {
+                    fatal.decode(bytes);
+                    return true;
+                } catch (error) {
+                    return false;
+                }
+            };
+
+            tester.assert(processor.parallel_utf8_validate(new Uint8Array(0)), 'Empty input is valid');
+            tester.assert(processor.parallel_utf8_validate(encoder.encode('plain ascii '.repeat(20000))), 'ASCII should be valid');
+            tester.assert(processor.parallel_utf8_validate(encoder.encode('héllo wörld – 日本語 🎉 '.repeat(10000))), 'Mixed multibyte text should be valid');
+
+            // Slide a 2-, 3- and 4-byte character across the first chunk boundary
+            for (const char of ['é', '語', '🎉']) {
+                const width = encoder.encode(char).length;
+                for (let shift = 0; shift <= width; shift++) {
+                    const text = 'a'.repeat(chunk - shift) + char + 'b'.repeat(10);
+                    const bytes = encoder.encode(text);
+                    tester.assert(processor.parallel_utf8_validate(bytes), `'${char}' at boundary offset ${shift} should be valid`);
+
+                    // Truncating the character leaves stray bytes on one side
+                    const broken = new Uint8Array([...bytes.subarray(0, chunk - shift + width - 1), ...bytes.subarray(chunk - shift + width)]);
+                    tester.assertEqual(processor.parallel_utf8_validate(broken), false, `Truncated '${char}' at offset ${shift} should be invalid`);
+                }
+            }
+
+            const invalid = [
+                [0x80],
+                [0x61, 0xC3],
+                [0xC0, 0xAF],
+                [0xED, 0xA0, 0x80],
+                [0xF4, 0x90, 0x80, 0x80],
+                [0xFF],
+            ];
+            for (const bytes of invalid) {
+                tester.assertEqual(processor.parallel_utf8_validate(new Uint8Array(bytes)), false, `[${bytes}] should be invalid`);
+            }
+
+            // A stray continuation byte or lone lead byte anywhere near a boundary
+            const base = encoder.encode('ü'.repeat(chunk));
+            for (const at of [chunk - 3, chunk - 1, chunk, chunk + 1, 2 * chunk - 2]) {
+                for (const byte of [0x80, 0xC3, 0xE2, 0xF0]) {
+                    const corrupted = base.slice();
+                    corrupted[at] = byte;
+                    tester.assertEqual(processor.parallel_utf8_validate(corrupted), expected(corrupted), `Byte ${byte} at ${at} should match TextDecoder`);
+                }
+            }
+        });
+
+        // Test 55: Cache limits and LRU eviction
+        tester.test('Cache Limits', () => {
+            const module = tester.wasm.WasmModule.with_cache_limits(3, 100);
+            const input = (seed, length) => new Uint8Array(length).fill(seed);
+            const key = (seed, length) => {
+                module.clear_cache();
+                module.process_data(input(seed, length));
+                return module.cache_keys()[0];
+            };
+            const [a, b, c, d] = [1, 2, 3, 4].map((seed) => key(seed, 10));
+            module.clear_cache();
+
+            for (const seed of [1, 2, 3]) {
+                module.process_data(input(seed, 10));
+            }
+            tester.assertEqual(module.cache_bytes, 30, 'Bytes should sum the cached results');
+            tester.assertArrayEqual(module.cache_keys(), [a, b, c], 'Keys should be listed least recently used first');
+
+            module.process_data(input(1, 10));
+            tester.assertArrayEqual(module.cache_keys(), [b, c, a], 'A cache hit should refresh its entry');
+            module.process_data(input(4, 10));
+            tester.assertArrayEqual(module.cache_keys(), [c, a, d], 'The least recently used entry should be evicted at the entry limit');
+            tester.assert(!module.cache_contains(b));
+            tester.assertEqual(module.cache_bytes, 30);
+
+            module.process_data(input(5, 80));
+            const e = module.cache_keys()[2];
+            tester.assertArrayEqual(module.cache_keys(), [a, d, e], 'The byte limit should evict until the new entry fits');
+            tester.assertEqual(module.cache_bytes, 100);
+
+            module.process_data(input(6, 101));
+            tester.assertEqual(module.cache_size, 3, 'Oversized results should not be cached');
+            tester.assertEqual(module.cache_bytes, 100, 'Oversized results should not evict anything');
+
+            module.set_cache_limits(3, 85);
+            tester.assertArrayEqual(module.cache_keys(), [e], 'Lowering the limits should evict at once');
+            tester.assertEqual(module.cache_bytes, 80);
+            module.set_cache_limits(0, 50);
+            tester.assertEqual(module.cache_size, 0);
+            module.process_data(input(1, 10));
+            tester.assertEqual(module.cache_size, 0, 'A zero entry limit should disable caching');
+            tester.assertEqual(module.cache_bytes, 0);
+        });
+
+        // Test 56: Mapping through a JavaScript callback
+        tester.test('JS Callback Map', () => {
+            const processor = new tester.wasm.WasmParallelProcessor(0);
+            const data = new Float64Array(1000).map((_, i) => i * 0.5 - 100);
+
+            const doubled = processor.parallel_map_js(data, (x) => x * 2);
+            tester.assertArrayEqual(Array.from(doubled), Array.from(data, (x) => x * 2), 'Each element should be doubled');
+
+            const indices = processor.parallel_map_js(new Float64Array([7, 7, 7]), (_, i) => i);
+            tester.assertArrayEqual(Array.from(indices), [0, 1, 2], 'The callback should receive each index');
+            tester.assertEqual(processor.parallel_map_js(new Float64Array(0), () => 1).length, 0);
+
+            const calls = [];
+            // The callback's own error has no WasmError code
+            const thrown = tester.assertThrows(() => processor.parallel_map_js(new Float64Array([1, 2, 3]), (x) => {
+                calls.push(x);
+                if (x === 2) {
+                    throw new RangeError('two is not allowed');
+                }
+                return x;
+            }), undefined);
+            tester.assert(thrown instanceof RangeError, 'The thrown error should pass through unchanged');
+            tester.assertArrayEqual(calls, [1, 2], 'Mapping should stop at the first exception');
+
+            tester.assertThrows(() => processor.parallel_map_js(new Float64Array([1]), () => 'one'), 'INVALID_ARGUMENT', 'Non-number results should be rejected');
+        });
+
+        // Test 57: Compression round trips
+        tester.test('Compression', () => {
+            const module = new tester.wasm.WasmModule();
+            const random = new Uint8Array(64 * 1024);
+            let state = 0x2545F491;
+            for (let i = 0; i < random.length; i++) {
+                state ^= state << 13;
+                state ^= state >>> 17;
+                state ^= state << 5;
+                random[i] = state & 0xFF;
+            }
+            const repetitive = new TextEncoder().encode('the quick brown fox jumps over the lazy dog. '.repeat(100000));
+            tester.assert(repetitive.length > 4 * 1024 * 1024, 'The repetitive payload should be several megabytes');
+
+            const magic = { gzip: [0x1F, 0x8B], zlib: [0x78], deflate: [] };
+            for (const format of ['deflate', 'gzip', 'zlib']) {
+                for (const [name, data] of [['empty', new Uint8Array(0)], ['random', random], ['repetitive', repetitive]]) {
+                    for (const level of [0, 6, 9]) {
+                        const compressed = module.compress(data, format, level);
+                        tester.assertArrayEqual(Array.from(compressed.subarray(0, magic[format].length)), magic[format], `${format} should start with its header`);
+                        const restored = module.decompress(compressed, format, data.length);
+                        tester.assertEqual(restored.length, data.length, `${name} ${format} level ${level} should round-trip its length`);
+                        tester.assert(restored.every((byte, i) => byte === data[i]), `${name} ${format} level ${level} should round-trip its bytes`);
+                    }
+                }
+                const compressed = module.compress(repetitive, format, 9);
+                tester.assert(compressed.length * 100 < repetitive.length, `Repetitive ${format} data should compress well`);
+                tester.assert(module.compress(random, format, 9).length > random.length * 0.99, `Random ${format} data should not shrink`);
+            }
+
+            module.clear_cache();
+            const first = module.compress(repetitive, 'gzip', 6);
+            const size = module.cache_size;
+            const again = module.compress(repetitive, 'gzip', 6);
+            tester.assertEqual(module.cache_size, size, 'Recompressing the same payload should hit the cache');
+            tester.assertArrayEqual(Array.from(again), Array.from(first));
+
+            const corrupted = module.compress(random, 'zlib', 6).slice();
+            corrupted[corrupted.length >> 1] ^= 0xFF;
+            tester.assertThrows(() => module.decompress(corrupted, 'zlib', 1 << 20), 'INVALID_ARGUMENT', 'Corrupted streams should be rejected');
+            tester.assertThrows(() => module.decompress(first.subarray(0, 20), 'gzip', 1 << 30), 'INVALID_ARGUMENT', 'Truncated streams should be rejected');
+            tester.assertThrows(() => module.decompress(first, 'gzip', repetitive.length - 1), 'INVALID_ARGUMENT', 'Output beyond the size cap should be rejected');
+            tester.assertThrows(() => module.compress(random, 'gzip', 10), 'INVALID_ARGUMENT', 'Levels above 9 should be rejected');
+            tester.assertThrows(() => module.compress(random, 'brotli', 6), 'UNSUPPORTED_OPERATION');
+        });
+
+        // Test 58: Lucas-Kanade optical flow
+        tester.test('Optical Flow', () => {
+            const processor = new tester.wasm.WasmImageProcessor(0);
+            const [width, height] = [48, 40];
+            const frame = (shiftX, shiftY) => {
+                const pixels = new Uint8Array(width * height);
+                for (let y = 0; y < height; y++) {
+                    for (let x = 0; x < width; x++) {
+                        const [u, v] = [x - shiftX, y - shiftY];
+                        pixels[y * width + x] = Math.round(128 + 60 * Math.sin(u * 0.3) * Math.cos(v * 0.25) + 30 * Math.sin((u + v) * 0.2));
+                    }
+                }
+                return pixels;
+            };
+
+            const still = processor.optical_flow_lucas_kanade(frame(0, 0), frame(0, 0), width, height, 5);
+            tester.assertEqual(still.length, width * height * 2, 'There should be one vector per pixel');
+            tester.assert(still.every((value) => value === 0), 'Identical frames should produce zero flow everywhere');
+
+            for (const [dx, dy] of [[1, 0], [0, 1], [0.5, -0.5]]) {
+                const flow = processor.optical_flow_lucas_kanade(frame(0, 0), frame(dx, dy), width, height, 7);
+                let sum = [0, 0];
+                let count = 0;
+                for (let y = 8; y < height - 8; y++) {
+                    for (let x = 8; x < width - 8; x++) {
+                        const i = (y * width + x) * 2;
+                        sum = [sum[0] + flow[i], sum[1] + flow[i + 1]];
+                        count++;
+                    }
+                }
+                const [meanX, meanY] = [sum[0] / count, sum[1] / count];
+                tester.assert(Math.abs(meanX - dx) < 0.25 && Math.abs(meanY - dy) < 0.25, `A shift of (${dx}, ${dy}) should be recovered, got (${meanX.toFixed(3)}, ${meanY.toFixed(3)})`);
+            }
+
+            const flat = new Uint8Array(width * height).fill(90);
+            tester.assert(processor.optical_flow_lucas_kanade(flat, flat.map((v) => v + 5), width, height, 5).every((v) => v === 0), 'Textureless regions should report no motion');
+
+            tester.assertThrows(() => processor.optical_flow_lucas_kanade(flat, flat.subarray(1), width, height, 5), 'DIMENSION_MISMATCH');
+            tester.assertThrows(() => processor.optical_flow_lucas_kanade(flat, flat, width + 1, height, 5), 'DIMENSION_MISMATCH');
+            tester.assertThrows(() => processor.optical_flow_lucas_kanade(flat, flat, width, height, 4), 'INVALID_ARGUMENT');
+        });
+
+        // Test 59: Hashing and checksums
+        tester.test('Hashing', () => {
+            const module = new tester.wasm.WasmModule();
+            const bytes = (text) => new TextEncoder().encode(text);
+            const long = 'abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq';
+
+            // Known-answer vectors: FIPS 180 for SHA, the CRC-32 check value, and
+            // the reference XXH64 outputs with seed 0
+            const vectors = [
+                ['sha256', '', 'e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855'],
+                ['sha256', 'abc', 'ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad'],
+                ['sha256', long, '248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1'],
+                ['sha1', '', 'da39a3ee5e6b4b0d3255bfef95601890afd80709'],
+                ['sha1', 'abc', 'a9993e364706816aba3e25717850c26c9cd0d89d'],
+                ['sha1', long, '84983e441c3bd26ebaae4aa1f95129e5e54670f1'],
+                ['crc32', '', '00000000'],
+                ['crc32', '123456789', 'cbf43926'],
+                ['crc32', 'The quick brown fox jumps over the lazy dog', '414fa339'],
+                ['xxh64', '', 'ef46db3751d8e999'],
+                ['xxh64', 'abc', '44bc2cf5ad770999'],
+            ];
+            for (const [algorithm, text, expected] of vectors) {
+                tester.assertEqual(module.hash(bytes(text), algorithm), expected, `${algorithm}("${text}")`);
+                tester.assertEqual(module.hash(bytes(text), algorithm.toUpperCase()), expected, 'Algorithm names should be case-insensitive');
+            }
+
+            const million = new Uint8Array(1000000).fill(0x61);
+            tester.assertEqual(module.hash(million, 'sha256'), 'cdc76e5c9914fb9281a1c7e284d73e67f1809a48a497200e046d39ccc7112cd0', 'sha256 of a million a\'s');
+            tester.assertEqual(module.hash(million, 'sha1'), '34aa973cd4c4daa4f61eeb2bdbad27316534016f', 'sha1 of a million a\'s');
+
+            // Streaming in uneven chunks should match the one-shot digest
+            for (const algorithm of ['sha256', 'sha1', 'crc32', 'xxh64']) {
+                const handle = module.hash_init(algorithm);
+                for (let offset = 0; offset < million.length;) {
+                    const size = 1 + (offset * 7919) % 65536;
+                    module.hash_update(handle, million.subarray(offset, offset + size));
+                    offset += size;
+                }
+                tester.assertEqual(module.hash_finalize(handle), module.hash(million, algorithm), `Streaming ${algorithm} should match the one-shot digest`);
+            }
+
+            const first = module.hash_init('sha256');
+            const second = module.hash_init('crc32');
+            tester.assert(first !== second && first !== 0 && second !== 0, 'Open handles should be distinct and non-zero');
+            module.hash_update(first, bytes('ab'));
+            module.hash_update(second, bytes('1234'));
+            module.hash_update(first, bytes('c'));
+            module.hash_update(second, bytes('56789'));
+            tester.assertEqual(module.hash_finalize(second), 'cbf43926', 'Interleaved streams should not interfere');
+            tester.assertEqual(module.hash_finalize(first), 'ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad');
+
+            tester.assertThrows(() => module.hash(bytes('abc'), 'md5'), 'UNSUPPORTED_OPERATION');
+            tester.assertThrows(() => module.hash_init('md5'), 'UNSUPPORTED_OPERATION');
+            tester.assertThrows(() => module.hash_update(first, bytes('x')), 'INVALID_ARGUMENT', 'Finalized handles should be stale');
+            tester.assertThrows(() => module.hash_finalize(first), 'INVALID_ARGUMENT');
+            tester.assertThrows(() => module.hash_finalize(0), 'INVALID_ARGUMENT');
+        });
+
+        // Test 60: Base64 and hex
+        tester.test('Base64 and Hex', () => {
+            const module = new tester.wasm.WasmModule();
+            const bytes = (text) => new TextEncoder().encode(text);
+
+            // RFC 4648 section 10 test vectors
+            const vectors = [
+                ['', ''], ['f', 'Zg=='], ['fo', 'Zm8='], ['foo', 'Zm9v'],
+                ['foob', 'Zm9vYg=='], ['fooba', 'Zm9vYmE='], ['foobar', 'Zm9vYmFy'],
+            ];
+            for (const [text, expected] of vectors) {
+                tester.assertEqual(module.encode(bytes(text), 'base64'), expected, `base64("${text}")`);
+                tester.assertEqual(module.encode(bytes(text), 'base64url'), expected.replace(/=/g, ''), `base64url("${text}")`);
+                tester.assertArrayEqual(Array.from(module.decode(expected, 'base64')), Array.from(bytes(text)));
+                tester.assertArrayEqual(Array.from(module.decode(expected.replace(/=/g, ''), 'base64url')), Array.from(bytes(text)), 'base64url should decode without padding');
+                tester.assertArrayEqual(Array.from(module.decode(expected, 'base64url')), Array.from(bytes(text)), 'base64url should also accept padding');
+            }
+            tester.assertEqual(module.encode(new Uint8Array([0xFB, 0xFF, 0xBF]), 'base64'), '+/+/');
+            tester.assertEqual(module.encode(new Uint8Array([0xFB, 0xFF, 0xBF]), 'base64url'), '-_-_');
+            tester.assertEqual(module.encode(new Uint8Array([0x00, 0x7F, 0xAB, 0xFF]), 'hex'), '007fabff');
+            tester.assertArrayEqual(Array.from(module.decode('007FabFF', 'hex')), [0x00, 0x7F, 0xAB, 0xFF], 'Hex should decode either case');
+
+            // Several megabytes, crossing the internal chunk size at odd lengths
+            const large = new Uint8Array(3 * 1024 * 1024 + 2);
+            for (let i = 0; i < large.length; i++) {
+                large[i] = (i * 2654435761) >>> 24;
+            }
+            const encoded = module.encode(large, 'base64');
+            tester.assertEqual(encoded, Buffer.from(large).toString('base64'), 'Large base64 should match Node');
+            tester.assertEqual(module.encode(large, 'base64url'), Buffer.from(large).toString('base64url'), 'Large base64url should match Node');
+            tester.assertEqual(module.encode(large.subarray(0, 100001), 'hex'), Buffer.from(large.subarray(0, 100001)).toString('hex'));
+            for (const encoding of ['base64', 'base64url', 'hex']) {
+                const restored = module.decode(module.encode(large, encoding), encoding);
+                tester.assert(restored.length === large.length && restored.every((byte, i) => byte === large[i]), `${encoding} should round-trip`);
+            }
+
+            const failureOffset = (input, encoding) => tester.assertThrows(() => module.decode(input, encoding), 'INVALID_ENCODING').details.offset;
+            tester.assertEqual(failureOffset('Zm9v!mFy', 'base64'), 4, 'Invalid characters should report their offset');
+            tester.assertEqual(module.decode('Zm9vYmFy', 'base64url').length, 6, 'base64url without padding should decode');
+            tester.assertEqual(failureOffset('Zm+v', 'base64url'), 2, 'base64url should reject the standard alphabet');
+            tester.assertEqual(failureOffset('Zm9vYg', 'base64'), 6, 'base64 should require padding');
+            tester.assertEqual(failureOffset('Zm9vY', 'base64url'), 4, 'A lone trailing character cannot encode a byte');
+            tester.assertEqual(failureOffset('Zg=', 'base64'), 3, 'Short padding should be rejected');
+            tester.assertEqual(failureOffset('Zm9v====', 'base64'), 4, 'Excess padding should be rejected');
+            tester.assertEqual(failureOffset('Zg==Zg==', 'base64'), 2, 'Padding in the middle should be rejected');
+            tester.assertEqual(failureOffset('Zm9v Zg==', 'base64'), 4, 'Whitespace is not part of the alphabet');
+            tester.assertEqual(failureOffset('00ag', 'hex'), 3);
+            tester.assertEqual(failureOffset('abc', 'hex'), 3, 'Odd-length hex should be rejected');
+            tester.assertEqual(failureOffset('abé', 'hex'), 2, 'Non-ASCII characters should be reported');
+
+            tester.assertThrows(() => module.encode(large, 'base32'), 'UNSUPPORTED_OPERATION');
+        });
+
+        // Test 61: Parallel LZ4 compression
+        tester.test('Parallel LZ4', () => {
+            const processor = new tester.wasm.WasmParallelProcessor(0);
+            const { lz4_decompress } = tester.wasm.WasmParallelProcessor;
+            const sameBytes = (a, b) => a.length === b.length && a.every((byte, i) => byte === b[i]);
+
+            const zeros = new Uint8Array(1024 * 1024);
+            const packedZeros = processor.parallel_lz4_compress(zeros);
+            tester.assert(packedZeros.length < zeros.length * 0.1, `All-zero data should shrink below 10%, got ${packedZeros.length} bytes`);
+            tester.assertArrayEqual(Array.from(packedZeros.subarray(0, 4)), [0x4C, 0x5A, 0x34, 0x42], 'The header should start with the LZ4B magic');
+            const view = new DataView(packedZeros.buffer, packedZeros.byteOffset);
+            tester.assertEqual(view.getUint32(4, true), zeros.length, 'The header should hold the original size');
+            tester.assertEqual(view.getUint32(8, true), 16, 'A megabyte should be sixteen 64 KiB blocks');
+            tester.assert(sameBytes(lz4_decompress(packedZeros), zeros), 'Zeros should round-trip');
+
+            let state = 0x9E3779B9;
+            const random = new Uint8Array(200000).map(() => {
+                state ^= state << 13;
+                state ^= state >>> 17;
+                state ^= state << 5;
+                return state & 0xFF;
+            });
+            const words = ['lorem', 'ipsum', 'dolor', 'sit', 'amet', 'consectetur', 'adipiscing', 'elit'];
+            const text = new TextEncoder().encode(Array.from({ length: 60000 }, (_, i) => words[(i * 7 + (i >> 3)) % words.length]).join(' '));
+            const inputs = [new Uint8Array(0), new Uint8Array([7]), new Uint8Array(13).fill(1), random, text, new Uint8Array(65536 * 2 + 1).fill(42)];
+            for (const input of inputs) {
+                const packed = processor.parallel_lz4_compress(input);
+                tester.assert(sameBytes(lz4_decompress(packed), input), `${input.length} bytes should round-trip`);
+            }
+            tester.assert(processor.parallel_lz4_compress(text).length < text.length / 2, 'Repetitive text should compress');
+
+            const packed = processor.parallel_lz4_compress(text);
+            tester.assertThrows(() => lz4_decompress(packed.subarray(0, packed.length - 1)), 'INVALID_ARGUMENT', 'Truncated data should be rejected');
+            tester.assertThrows(() => lz4_decompress(new Uint8Array([1, 2, 3, 4, 0, 0, 0, 0, 0, 0, 0, 0])), 'INVALID_ARGUMENT', 'A wrong magic should be rejected');
+            const lying = packed.slice();
+            new DataView(lying.buffer).setUint32(4, text.length + 1, true);
+            tester.assertThrows(() => lz4_decompress(lying), 'INVALID_ARGUMENT', 'A wrong original size should be rejected');
+            // One block whose first sequence copies from before the block starts
+            const corrupt = new Uint8Array([0x4C, 0x5A, 0x34, 0x42, 8, 0, 0, 0, 1, 0, 0, 0, 3, 0, 0, 0, 0x04, 1, 0]);
+            tester.assertThrows(() => lz4_decompress(corrupt), 'INVALID_ARGUMENT', 'A corrupt block should be rejected');
+        });
+
+        // Test 62: TF-IDF vectorisation
+        tester.test('TF-IDF', () => {
+
+            const tfidf = new tester.wasm.WasmTFIDF(3);
+            tester.assertThrows(() => tfidf.transform(['cat']), 'NOT_INITIALIZED', 'transform before fit should fail');
+
+            const corpus = ['The cat sat.', 'The cat ran, the dog ran!', 'A dog barked', 'the end'];
+            tfidf.fit(corpus);
+            // "the" is in 3 documents, "cat" and "dog" in 2; "cat" wins the tie alphabetically
+            tester.assertArrayEqual(tfidf.vocabulary, ['the', 'cat', 'dog'], 'Vocabulary should keep the most common terms');
+            tester.assertEqual(tfidf.vocabulary_size, 3, 'Vocabulary size should match max_features');
+
+            const matrix = tfidf.transform(['cat cat fish', 'cat fish fish', 'bird']);
+            tester.assertEqual(matrix.length, 9, 'One row of vocabulary_size per document');
+            tester.assert(matrix[1] > matrix[4], 'A word seen twice should outscore a word seen once');
+            const idfCat = Math.log(5 / 3) + 1;
+            tester.assert(Math.abs(matrix[1] - idfCat * 2 / 3) < 1e-5, `cat weight should be tf * idf, got ${matrix[1]}`);
+            tester.assertArrayEqual(Array.from(matrix.subarray(6)), [0, 0, 0], 'Unknown words should give a zero row');
+            tester.assert(matrix[0] === 0 && matrix[2] === 0, 'Absent vocabulary terms should be zero');
+
+            tester.assertThrows(() => tfidf.transform(['ok', 7]), 'INVALID_ARGUMENT', 'Non-string documents should be rejected');
+            tester.assertThrows(() => new tester.wasm.WasmTFIDF(0), 'INVALID_ARGUMENT', 'Zero max_features should be rejected');
+        });
+
+        // Test 63: Authenticated encryption
+        tester.test('Encryption', () => {
+            const module = new tester.wasm.WasmModule();
+            const { derive_key } = tester.wasm.WasmModule;
+            const toHex = (bytes) => Array.from(bytes, (byte) => byte.toString(16).padStart(2, '0')).join('');
+            const encoder = new TextEncoder();
+
+            // RFC 7914 section 11 PBKDF2-HMAC-SHA256 vectors, truncated to 32
+            // bytes. Derived keys are opaque handles, so each is checked by
+            // decrypting what it sealed with a key imported from the vector.
+            const salt = encoder.encode('salt');
+            const fromHex = (hex) => new Uint8Array(hex.match(/../g).map((byte) => parseInt(byte, 16)));
+            const vectors = [
+                [1, '120fb6cffcf8b32c43e7225256c4f837a86548c92ccc35480805987cb70be17b'],
+                [2, 'ae4d0c95af6b46d32d0adff928f06dd02a303f8ef3c251dfd6e2d85a95474c43'],
+            ];
+            for (const [iterations, hex] of vectors) {
+                const derived = derive_key('password', salt, iterations);
+                tester.assert(!(derived instanceof Uint8Array), 'Derived keys should not expose their bytes');
+                const sealedVector = module.encrypt(encoder.encode('vector'), derived);
+                const imported = tester.wasm.WasmKey.from_bytes(fromHex(hex));
+                tester.assertArrayEqual(Array.from(module.decrypt(sealedVector, imported)), Array.from(encoder.encode('vector')), `PBKDF2 with ${iterations} iteration(s)`);
+                derived.free();
+                imported.free();
+            }
+            tester.assertThrows(() => derive_key('password', salt, 0), 'INVALID_ARGUMENT', 'Zero iterations should be rejected');
+
+            const key = derive_key('correct horse', encoder.encode('battery staple'), 1000);
+            const plaintext = encoder.encode('attack at dawn');
+            const aad = encoder.encode('message 1');
+            const sealed = module.encrypt(plaintext, key, aad);
+            tester.assertEqual(sealed.length, 12 + plaintext.length + 16, 'Output should be nonce, ciphertext and tag');
+            tester.assertArrayEqual(Array.from(module.decrypt(sealed, key, aad)), Array.from(plaintext), 'Round trip with associated data');
+            tester.assert(toHex(module.encrypt(plaintext, key, aad)) !== toHex(sealed), 'Each encryption should use a fresh nonce');
+
+            const empty = module.encrypt(new Uint8Array(0), key);
+            tester.assertEqual(module.decrypt(empty, key).length, 0, 'Empty plaintext should round-trip without associated data');
+
+            const tampered = sealed.slice();
+            tampered[14] ^= 1;
+            tester.assertThrows(() => module.decrypt(tampered, key, aad), 'AUTHENTICATION_FAILED', 'Tampered ciphertext should fail authentication');
+            tester.assertThrows(() => module.decrypt(sealed, key, encoder.encode('message 2')), 'AUTHENTICATION_FAILED', 'Different associated data should fail authentication');
+            tester.assertThrows(() => module.decrypt(sealed, derive_key('wrong', salt, 1)), 'AUTHENTICATION_FAILED', 'A wrong key should fail authentication');
+            tester.assertThrows(() => module.decrypt(sealed.subarray(0, 27), key, aad), 'INVALID_ARGUMENT', 'Truncated input should be rejected');
+            tester.assertThrows(() => tester.wasm.WasmKey.from_bytes(new Uint8Array(16)), 'DIMENSION_MISMATCH', 'A 16-byte key should be rejected');
+        });
+
+        // Test 64: Correlation and covariance matrices
+        tester.test('Correlation Matrix', () => {
+            const processor = new tester.wasm.WasmParallelProcessor(0);
+
+            // Features: x, 2x + 1 (perfectly correlated), -x (anti-correlated), noise
+            const nSamples = 200, nFeatures = 4;
+            const data = new Float64Array(nSamples * nFeatures);
+            let state = 12345;
+            for (let s = 0; s < nSamples; s++) {
+                state = (state * 1103515245 + 12345) % 2147483648;
+                const x = Math.sin(s * 0.37) * 10 + s * 0.01;
+                data.set([x, 2 * x + 1, -x, state / 2147483648], s * nFeatures);
+            }
+
+            const corr = processor.parallel_correlation_matrix(data, nSamples, nFeatures);
+            tester.assertEqual(corr.length, nFeatures * nFeatures, 'Matrix should be n_features x n_features');
+            for (let i = 0; i < nFeatures; i++) {
+                tester.assertEqual(corr[i * nFeatures + i], 1.0, `Diagonal ${i} should be exactly 1`);
+                for (let j = 0; j < nFeatures; j++) {
+                    tester.assert(Math.abs(corr[i * nFeatures + j] - corr[j * nFeatures + i]) <= 1e-14, `Entry (${i}, ${j}) should be symmetric`);
+                    tester.assert(Math.abs(corr[i * nFeatures + j]) <= 1 + 1e-12, `Entry (${i}, ${j}) should lie in [-1, 1]`);
+                }
+            }
+            tester.assert(Math.abs(corr[1] - 1) < 1e-12, 'A linear transform should correlate perfectly');
+            tester.assert(Math.abs(corr[2] + 1) < 1e-12, 'A negation should anti-correlate perfectly');
+            tester.assert(Math.abs(corr[3]) < 0.3, `Noise should be weakly correlated, got ${corr[3]}`);
+
+            // Sample covariance of [1, 2, 3, 4] with itself and with [2, 4, 6, 8]
+            const cov = processor.parallel_covariance_matrix(new Float64Array([1, 2, 2, 4, 3, 6, 4, 8]), 4, 2);
+            tester.assertArrayEqual(Array.from(cov).map((v) => +v.toFixed(12)), [5 / 3, 10 / 3, 10 / 3, 20 / 3].map((v) => +v.toFixed(12)), 'Covariance should use n - 1');
+
+            const constant = processor.parallel_correlation_matrix(new Float64Array([1, 5, 2, 5, 3, 5]), 3, 2);
+            tester.assert(Number.isNaN(constant[1]) && Number.isNaN(constant[3]), 'A constant feature should give NaN correlations');
+
+            tester.assertThrows(() => processor.parallel_correlation_matrix(new Float64Array(5), 2, 3), 'DIMENSION_MISMATCH', 'Wrong data length should be rejected');
+            tester.assertThrows(() => processor.parallel_covariance_matrix(new Float64Array(3), 1, 3), 'INVALID_ARGUMENT', 'A single sample should be rejected');
+        });
+
+        // Test 65: JSON transforms and CBOR
+        tester.test('JSON Pipeline and CBOR', () => {
+            const module = new tester.wasm.WasmModule();
+            const doc = JSON.stringify({
+                user: { name: 'Ada', address: { city: 'London', zip: 'N1' }, age: 36 },
+                x: 1,
+                items: [{ id: 1, score: 0.9 }, { id: 2, score: 0.2 }, { id: 3 }, { id: 4, score: 0.5 }, { id: 5, score: 0.75 }],
+            });
+            const run = (operations) => JSON.parse(module.process_json(doc, operations));
+
+            tester.assertEqual(
+                JSON.stringify(run([{ op: 'pick', paths: ['user.address.city', 'x', 'missing.path'] }])),
+                JSON.stringify({ user: { address: { city: 'London' } }, x: 1 }),
+                'pick should keep only the listed paths at their nesting'
+            );
+
+            const renamed = run([{ op: 'rename', from: 'x', to: 'y' }, { op: 'rename', from: 'user.name', to: 'profile.display_name' }]);
+            tester.assert(!('x' in renamed) && renamed.y === 1, 'rename should move a top-level key');
+            tester.assert(renamed.profile.display_name === 'Ada' && !('name' in renamed.user), 'rename should move between nested paths');
+
+            const filtered = run([{ op: 'filter_array', path: 'items', field: 'score', gt: 0.5 }]);
+            tester.assertArrayEqual(filtered.items.map((item) => item.id), [1, 5], 'filter_array gt should drop low and missing scores');
+            const ranged = run([{ op: 'filter_array', path: 'items', field: 'score', gte: 0.5, lt: 0.9 }]);
+            tester.assertArrayEqual(ranged.items.map((item) => item.id), [4, 5], 'filter_array should apply every bound');
+
+            const composed = run([
+                { op: 'filter_array', path: 'items', field: 'score', gt: 0.5 },
+                { op: 'rename', from: 'items', to: 'top.items' },
+                { op: 'pick', paths: ['top', 'user.name'] },
+            ]);
+            tester.assertEqual(
+                JSON.stringify(composed),
+                JSON.stringify({ top: { items: [{ id: 1, score: 0.9 }, { id: 5, score: 0.75 }] }, user: { name: 'Ada' } }),
+                'Steps should apply in order'
+            );
+            tester.assertEqual(module.process_json(' {"b": [1, 2], "a": null} ', []), '{"a":null,"b":[1,2]}', 'No steps should compact the JSON and sort keys');
+
+            const malformed = tester.assertThrows(() => module.process_json('{\n  "a": 1,\n  "b": ]\n}', []), 'INVALID_JSON', 'Malformed JSON should be INVALID_JSON');
+            tester.assertEqual(malformed.details.line, 3, 'The error should carry the line');
+            tester.assertEqual(malformed.details.column, 8, 'The error should carry the column');
+            tester.assertThrows(() => module.process_json(doc, [{ op: 'flatten' }]), 'UNSUPPORTED_OPERATION', 'Unknown ops should be rejected');
+            tester.assertThrows(() => module.process_json(doc, [{ op: 'rename', from: 'x' }]), 'INVALID_ARGUMENT', 'Missing parameters should be rejected');
+            tester.assertThrows(() => module.process_json(doc, [{ op: 'filter_array', path: 'user', field: 'age', gt: 1 }]), 'INVALID_ARGUMENT', 'filter_array on a non-array should be rejected');
+            tester.assertThrows(() => module.process_json(doc, [{ op: 'filter_array', path: 'items', field: 'score' }]), 'INVALID_ARGUMENT', 'filter_array without bounds should be rejected');
+
+            const cbor = module.json_to_cbor(doc);
+            tester.assert(cbor.length < doc.length, `CBOR should be smaller than JSON (${cbor.length} vs ${doc.length})`);
+            tester.assertEqual(module.cbor_to_json(cbor), module.process_json(doc, []), 'CBOR should round-trip to the same JSON');
+            tester.assertArrayEqual(Array.from(module.json_to_cbor('{"a":1}')), [0xA1, 0x61, 0x61, 0x01], 'CBOR should use the standard encoding');
+            tester.assertThrows(() => module.json_to_cbor('[1, 2'), 'INVALID_JSON', 'json_to_cbor should reject malformed JSON');
+            tester.assertThrows(() => module.cbor_to_json(new Uint8Array([0xA1, 0x61])), 'INVALID_ARGUMENT', 'Truncated CBOR should be rejected');
+        });
+
+        // Test 66: Streaming sessions
+        tester.test('Stream Sessions', () => {
+            const module = new tester.wasm.WasmModule();
+            const concat = (parts) => {
+                const out = new Uint8Array(parts.reduce((n, part) => n + part.length, 0));
+                let offset = 0;
+                for (const part of parts) {
+                    out.set(part, offset);
+                    offset += part.length;
+                }
+                return out;
+            };
+            // Push `data` through a fresh session in uneven chunks
+            const streamThrough = (transform, data, chunkSize = 7777, maxOutput = 1 << 30) => {
+                const session = module.begin_stream(transform, maxOutput);
+                const parts = [];
+                for (let start = 0; start < data.length; start += chunkSize) {
+                    parts.push(module.stream_push(session, data.subarray(start, start + chunkSize)));
+                }
+                parts.push(module.stream_finish(session));
+                return concat(parts);
+            };
+            const sameBytes = (a, b) => a.length === b.length && a.every((byte, i) => byte === b[i]);
+
+            const text = new TextEncoder().encode('The quick brown fox jumps over the lazy dog. '.repeat(5000));
+            const xored = streamThrough('xor', text);
+            tester.assert(sameBytes(xored, text.map((byte) => byte ^ 0xAA)), 'xor should apply per byte');
+
+            for (const format of ['deflate', 'gzip', 'zlib']) {
+                const compressed = streamThrough(`compress:${format}`, text);
+                tester.assert(compressed.length < text.length / 10, `${format} stream should compress repetitive text`);
+                tester.assert(sameBytes(module.decompress(compressed, format, text.length), text), `${format} stream output should decompress in one shot`);
+                tester.assert(sameBytes(streamThrough(`decompress:${format}`, compressed, 100), text), `${format} should round-trip through streams`);
+            }
+
+            const toHex = (bytes) => Array.from(bytes, (byte) => byte.toString(16).padStart(2, '0')).join('');
+            for (const algorithm of ['sha256', 'crc32', 'xxh64']) {
+                tester.assertEqual(toHex(streamThrough(`hash:${algorithm}`, text)), module.hash(text, algorithm), `hash:${algorithm} should match hash()`);
+            }
+
+            // Closed, unknown and failed sessions
+            const session = module.begin_stream('xor', 1 << 20);
+            tester.assertEqual(module.open_streams, 1, 'One session should be open');
+            module.stream_abort(session);
+            tester.assertEqual(module.open_streams, 0, 'Abort should close the session');
+            tester.assertThrows(() => module.stream_push(session, text), 'INVALID_ARGUMENT', 'Pushing to an aborted session should fail');
+            tester.assertThrows(() => module.stream_finish(session), 'INVALID_ARGUMENT', 'Finishing an aborted session should fail');
+            tester.assertThrows(() => module.stream_abort(session), 'INVALID_ARGUMENT', 'Aborting twice should fail');
+            tester.assertThrows(() => module.stream_push(9999, text), 'INVALID_ARGUMENT', 'Unknown sessions should fail');
+
+            const finished = module.begin_stream('hash:sha1', 1 << 20);
+            module.stream_finish(finished);
+            tester.assertThrows(() => module.stream_finish(finished), 'INVALID_ARGUMENT', 'Finishing twice should fail');
+
+            const corrupt = module.begin_stream('decompress:zlib', 1 << 20);
+            tester.assertThrows(() => module.stream_push(corrupt, new Uint8Array([1, 2, 3, 4, 5])), 'INVALID_ARGUMENT', 'Corrupt input should fail');
+            tester.assertEqual(module.open_streams, 0, 'A failed session should be closed');
+
+            const gzip = streamThrough('compress:gzip', text);
+            const truncated = module.begin_stream('decompress:gzip', 1 << 20);
+            module.stream_push(truncated, gzip.subarray(0, gzip.length - 4));
+            tester.assertThrows(() => module.stream_finish(truncated), 'INVALID_ARGUMENT', 'A truncated gzip stream should fail on finish');
+
+            // Output caps: a highly compressible chunk stops at the cap
+            const zeros = module.compress(new Uint8Array(1 << 20), 'gzip', 9);
+            tester.assert(sameBytes(streamThrough('decompress:gzip', zeros, zeros.length, 1 << 20), new Uint8Array(1 << 20)), 'Output up to the cap should be allowed');
+            const bomb = module.begin_stream('decompress:gzip', 1000);
+            const capped = tester.assertThrows(() => module.stream_push(bomb, zeros), 'INVALID_ARGUMENT', 'Inflating past the cap should fail');
+            tester.assert(capped.message.includes('1000 bytes'), `The error should name the cap, got "${capped.message}"`);
+            tester.assertEqual(module.open_streams, 0, 'A capped session should be closed');
+            tester.assertThrows(() => streamThrough('xor', text, 7777, text.length - 1), 'INVALID_ARGUMENT', 'The cap should hold for every transform');
+
+            tester.assertThrows(() => module.begin_stream('rot13', 1 << 20), 'UNSUPPORTED_OPERATION', 'Unknown transforms should be rejected');
+            tester.assertThrows(() => module.begin_stream('compress:brotli', 1 << 20), 'UNSUPPORTED_OPERATION', 'Unknown formats should be rejected');
+        });
+
+        // Test 67: Delta and varint encoding
+        tester.test('Delta Encoding', () => {
+            const processor = new tester.wasm.WasmParallelProcessor(0);
+            const { delta_decode, varint_delta_decode } = tester.wasm.WasmParallelProcessor;
+
+            const signed = BigInt64Array.from([5n, 7n, 7n, 3n, -10n, 9223372036854775807n, -9223372036854775808n]);
+            const deltas = processor.parallel_delta_encode(signed);
+            tester.assertEqual(deltas.join(','), '5,2,0,-4,-13,-9223372036854775799,1', 'Deltas should follow the previous value, wrapping on overflow');
+            tester.assertEqual(delta_decode(deltas).join(','), signed.join(','), 'Delta decoding should round-trip');
+            tester.assertEqual(processor.parallel_delta_encode(new BigInt64Array(0)).length, 0, 'Empty input should give empty deltas');
+
+            // Millisecond timestamps about a second apart, with jitter and one clock step back
+            let state = 7;
+            const start = 1700000000000n;
+            const count = 100000;
+            const timestamps = new BigUint64Array(count);
+            let now = start;
+            for (let i = 0; i < count; i++) {
+                state = (state * 1103515245 + 12345) % 2147483648;
+                now += BigInt(990 + (state % 21));
+                timestamps[i] = i === 5000 ? now - 3000n : now;
+            }
+            const packed = processor.parallel_varint_delta_encode(timestamps);
+            const ratio = packed.length / (count * 8);
+            tester.assert(ratio < 0.3, `Varint deltas should take under 30% of raw u64s, got ${(ratio * 100).toFixed(1)}%`);
+            console.log(`   ${count} timestamps: ${count * 8} bytes raw, ${packed.length} bytes as varint deltas (${(100 * ratio).toFixed(1)}%)`);
+            const unpacked = varint_delta_decode(packed);
+            tester.assertEqual(unpacked.length, count, 'Every timestamp should decode');
+            tester.assert(unpacked.every((value, i) => value === timestamps[i]), 'Varint deltas should round-trip across chunk boundaries');
+
+            const extremes = BigUint64Array.from([0n, 18446744073709551615n, 0n, 1n]);
+            tester.assertEqual(varint_delta_decode(processor.parallel_varint_delta_encode(extremes)).join(','), extremes.join(','), 'Extreme values should round-trip');
+            tester.assertArrayEqual(Array.from(processor.parallel_varint_delta_encode(BigUint64Array.from([1n, 3n, 2n]))), [2, 4, 1], 'Zigzag should map +1, +2, -1 to 2, 4, 1');
+
+            tester.assertThrows(() => varint_delta_decode(new Uint8Array([0x02, 0x80])), 'INVALID_ARGUMENT', 'A truncated varint should be rejected');
+        });
+
+        // Test 68: Argmax and argmin
+        tester.test('Argmax and Argmin', () => {
+            const processor = new tester.wasm.WasmParallelProcessor(0);
+
+            const tied = new Float64Array([1.0, 9.0, 9.0, 2.0]);
+            tester.assertEqual(processor.argmax_index(tied), 1, 'Ties should resolve to the lowest index');
+            tester.assertEqual(processor.argmax_value(tied), 9.0, 'argmax_value should return the maximum');
+            tester.assertEqual(processor.argmin_index(new Float64Array([3, -2, 5, -2])), 1, 'argmin ties should resolve to the lowest index');
+            tester.assertEqual(processor.argmin_value(new Float64Array([3, -2, 5, -2])), -2, 'argmin_value should return the minimum');
+
+            // Large input, so the reduction really splits, with the maximum repeated
+            const large = new Float64Array(1000000).map((_, i) => Math.sin(i));
+            large[123456] = 2;
+            large[654321] = 2;
+            large[777777] = -2;
+            tester.assertEqual(processor.argmax_index(large), 123456, 'The first of equal maxima should win across chunks');
+            tester.assertEqual(processor.argmin_index(large), 777777, 'The minimum should be found across chunks');
+
+            tester.assertEqual(processor.argmax_index(new Float64Array([NaN, 1, NaN, 3])), 3, 'NaN should be skipped');
+            tester.assertEqual(processor.argmax_index(new Float64Array(0)), undefined, 'Empty input has no argmax');
+            tester.assertEqual(processor.argmin_value(new Float64Array([NaN, NaN])), undefined, 'All-NaN input has no argmin');
+        });
+
+        // Test 69: Mipmap generation
+        tester.test('Mipmap Generation', () => {
+            const processor = new tester.wasm.WasmImageProcessor(0);
+            const readHeader = (mips) => {
+                const view = new DataView(mips.buffer, mips.byteOffset, mips.byteLength);
+                const count = view.getUint32(0, true);
+                return Array.from({ length: count }, (_, i) => [1, 2, 3].map((field) => view.getUint32(4 + 12 * i + 4 * (field - 1), true)));
+            };
+
+            // Red rises across and down, green mirrors it, blue is a checkerboard
+            // and the top-left pixel is transparent
+            const image = new Uint8Array(4 * 4 * 4);
+            for (let y = 0; y < 4; y++) {
+                for (let x = 0; x < 4; x++) {
+                    const i = (y * 4 + x) * 4;
+                    image[i] = 16 * x + 64 * y;
+                    image[i + 1] = 255 - image[i];
+                    image[i + 2] = (x + y) % 2 ? 255 : 0;
+                    image[i + 3] = x === 0 && y === 0 ? 0 : 255;
+                }
+            }
+
+            const mips = processor.generate_mipmaps(image, 4, 4);
+            const levels = readHeader(mips);
+            tester.assertEqual(JSON.stringify(levels), JSON.stringify([[4, 4, 40], [2, 2, 104], [1, 1, 120]]), 'Header should list each level with its offset');
+            tester.assertEqual(mips.length, 124, 'Levels should follow the header back to back');
+            tester.assertArrayEqual(mips.slice(40, 104), image, 'Level 0 should be the source image');
+            tester.assertArrayEqual(mips.slice(104, 120), new Uint8Array([
+                40, 215, 128, 191, 72, 183, 128, 255,
+                168, 87, 128, 255, 200, 55, 128, 255,
+            ]), 'The 2x2 level should hold the rounded average of each 2x2 block');
+            tester.assertArrayEqual(mips.slice(120), new Uint8Array([120, 135, 128, 239]), 'The 1x1 level should average the 2x2 level');
+
+            const wide = readHeader(processor.generate_mipmaps(new Uint8Array(8 * 2 * 4), 8, 2));
+            tester.assertEqual(JSON.stringify(wide), JSON.stringify([[8, 2, 28], [4, 1, 92]]), 'Halving should stop once the height reaches 1');
+
+            tester.assertThrows(() => processor.generate_mipmaps(new Uint8Array(3 * 4 * 4), 3, 4), 'INVALID_ARGUMENT', 'Sizes that are not powers of two should be rejected');
+            tester.assertThrows(() => processor.generate_mipmaps(new Uint8Array(10), 4, 4), 'DIMENSION_MISMATCH', 'A buffer of the wrong size should be rejected');
+        });
+
+        // Test 70: Cache statistics
+        tester.test('Cache Statistics', async () => {
+            const module = tester.wasm.WasmModule.with_cache_limits(2, 100);
+            const input = (seed, length) => new Uint8Array(length).fill(seed);
+            const counters = () => {
+                const { hits, misses, insertions, evictions } = module.cache_stats();
+                return [hits, misses, insertions, evictions];
+            };
+
+            tester.assertEqual(JSON.stringify(module.cache_stats()), JSON.stringify({ hits: 0, misses: 0, insertions: 0, evictions: 0, entries: 0, bytes: 0 }), 'A new module should report no activity');
+
+            module.process_data(input(1, 10));
+            module.process_data(input(1, 10));
+            module.process_data(input(2, 10));
+            tester.assertArrayEqual(counters(), [1, 2, 2, 0], 'Two misses should insert and a repeat should hit');
+            module.process_data(input(3, 10));
+            tester.assertArrayEqual(counters(), [1, 3, 3, 1], 'Exceeding the entry limit should count an eviction');
+            tester.assertEqual(module.cache_stats().entries, 2);
+            tester.assertEqual(module.cache_stats().bytes, 20);
+
+            await module.process_data_async(input(1, 10));
+            await module.process_data_async(input(1, 10));
+            tester.assertArrayEqual(counters(), [2, 4, 4, 2], 'Async processing should count against the same cache');
+
+            module.compress(input(4, 10), 'gzip', 6);
+            module.compress(input(4, 10), 'gzip', 6);
+            tester.assertArrayEqual(counters(), [3, 5, 5, 3], 'Compression should count against the same cache');
+            tester.assertEqual(module.cache_stats().bytes, module.cache_bytes, 'Bytes should match cache_bytes');
+
+            module.process_data(input(5, 101));
+            tester.assertArrayEqual(counters(), [3, 6, 5, 3], 'A result too large to cache should miss without inserting');
+            tester.assert(!module.cache_contains('no-such-key') && counters()[1] === 6, 'cache_contains should not count as a lookup');
+
+            module.clear_cache();
+            tester.assertArrayEqual(counters(), [3, 6, 5, 3], 'Clearing the cache should keep the counters');
+            tester.assertEqual(module.cache_stats().entries, 0);
+            tester.assertEqual(module.cache_stats().bytes, 0);
+
+            module.process_data(input(6, 10));
+            module.reset_stats();
+            tester.assertEqual(JSON.stringify(module.cache_stats()), JSON.stringify({ hits: 0, misses: 0, insertions: 0, evictions: 0, entries: 1, bytes: 10 }), 'Resetting should zero the counters but keep the cached results');
+            module.process_data(input(6, 10));
+            tester.assertArrayEqual(counters(), [1, 0, 0, 0], 'Counting should resume after a reset');
+        });
+
+        // Test 71: Neural-network activations
+        tester.test('Activation Functions', () => {
+            const processor = new tester.wasm.WasmParallelProcessor(0);
+            const close = (actual, expected, message) => {
+                tester.assertEqual(actual.length, expected.length, message);
+                actual.forEach((value, i) => tester.assert(Object.is(value, expected[i]) || Math.abs(value - expected[i]) < 1e-12, `${message}: got ${value} at ${i}, expected ${expected[i]}`));
+            };
+
+            tester.assertEqual(processor.parallel_sigmoid(new Float64Array([0]))[0], 0.5, 'sigmoid(0) should be 0.5');
+            tester.assertEqual(processor.parallel_relu(new Float64Array([-1]))[0], 0, 'relu(-1) should be 0');
+            tester.assertEqual(processor.parallel_tanh(new Float64Array([0]))[0], 0, 'tanh(0) should be 0');
+
+            const xs = new Float64Array([-2, -0.5, 0, 0.5, 2]);
+            close(processor.parallel_sigmoid(xs), Array.from(xs, (x) => 1 / (1 + Math.exp(-x))), 'sigmoid');
+            close(processor.parallel_tanh(xs), Array.from(xs, Math.tanh), 'tanh');
+            close(processor.parallel_relu(xs), [0, 0, 0, 0.5, 2], 'relu');
+            close(processor.parallel_leaky_relu(xs, 0.1), [-0.2, -0.05, 0, 0.5, 2], 'leaky_relu');
+            close(processor.parallel_elu(xs, 1.5), Array.from(xs, (x) => (x < 0 ? 1.5 * Math.expm1(x) : x)), 'elu');
+            const gelu = (x) => 0.5 * x * (1 + Math.tanh(Math.sqrt(2 / Math.PI) * (x + 0.044715 * x ** 3)));
+            close(processor.parallel_gelu(xs), Array.from(xs, gelu), 'gelu');
+
+            const special = new Float64Array([NaN, Infinity, -Infinity]);
+            close(processor.parallel_sigmoid(special), [NaN, 1, 0], 'sigmoid limits');
+            close(processor.parallel_tanh(special), [NaN, 1, -1], 'tanh limits');
+            close(processor.parallel_relu(special), [NaN, Infinity, 0], 'relu limits');
+            close(processor.parallel_leaky_relu(special, 0.1), [NaN, Infinity, -Infinity], 'leaky_relu limits');
+            close(processor.parallel_elu(special, 1.5), [NaN, Infinity, -1.5], 'elu limits');
+            close(processor.parallel_gelu(special), [NaN, Infinity, 0], 'gelu limits');
+
+            // Sequential JavaScript against the parallel WASM version on 1M elements
+            const data = new Float64Array(1000000).map((_, i) => (i / 1000000 - 0.5) * 20);
+            const sequentialStart = performance.now();
+            const sequentialResult = data.map(gelu);
+            const sequentialTime = performance.now() - sequentialStart;
+            const parallelStart = performance.now();
+            const parallelResult = processor.parallel_gelu(data);
+            const parallelTime = performance.now() - parallelStart;
+            console.log(`   1M gelu: sequential JS ${sequentialTime.toFixed(1)}ms, parallel WASM ${parallelTime.toFixed(1)}ms`);
+            close(parallelResult, sequentialResult, 'Large gelu');
+        });
+
+        // Test 72: Named transforms for process_data
+        tester.test('Named Transforms', () => {
+            const module = new tester.wasm.WasmModule();
+            const run = (data, transform, params) => Array.from(module.process_data(new Uint8Array(data), transform, params));
+            const data = [0, 1, 1, 1, 200, 255, 255, 7];
+
+            tester.assertArrayEqual(module.list_transforms(), ['delta_decode', 'delta_decode_u32', 'delta_encode', 'delta_encode_u32', 'reverse', 'rle', 'rle_decode', 'rot_n', 'varint_decode_u64', 'varint_encode_u64', 'xor'], 'All built-in transforms should be listed');
+            tester.assertArrayEqual(run(data), run(run(data, 'reverse'), 'xor'), 'Calling without a transform should reverse then XOR with 0xAA');
+
+            tester.assertArrayEqual(run(data, 'reverse'), [...data].reverse());
+            tester.assertArrayEqual(run(run(data, 'reverse'), 'reverse'), data, 'Reversing twice should round-trip');
+            tester.assertArrayEqual(run([0x0F, 0xF0], 'xor', { key: 0xFF }), [0xF0, 0x0F], 'xor should use the given key');
+            tester.assertArrayEqual(run(run(data, 'xor', { key: 0x5C }), 'xor', { key: 0x5C }), data, 'xor should round-trip');
+            tester.assertArrayEqual(run([0, 250], 'rot_n', { n: 10 }), [10, 4], 'rot_n should wrap');
+            tester.assertArrayEqual(run(run(data, 'rot_n', { n: 13 }), 'rot_n', { n: 243 }), data, 'rot_n should round-trip with 256 - n');
+
+            tester.assertArrayEqual(run(data, 'rle'), [1, 0, 3, 1, 1, 200, 2, 255, 1, 7], 'rle should write (count, byte) pairs');
+            tester.assertArrayEqual(run(new Array(300).fill(9), 'rle'), [255, 9, 45, 9], 'Runs longer than 255 should be split');
+            tester.assertArrayEqual(run(run(data, 'rle'), 'rle_decode'), data, 'rle should round-trip');
+            tester.assertThrows(() => run([3, 1, 2], 'rle_decode'), 'INVALID_ENCODING', 'A trailing half pair should be rejected');
+            tester.assertThrows(() => run([0, 1], 'rle_decode'), 'INVALID_ENCODING', 'Empty runs should be rejected');
+
+            tester.assertArrayEqual(run([10, 12, 11, 11], 'delta_encode'), [10, 2, 255, 0], 'Deltas should wrap');
+            tester.assertArrayEqual(run(run(data, 'delta_encode'), 'delta_decode'), data, 'Delta encoding should round-trip');
+            tester.assertArrayEqual(run([], 'rle'), [], 'Empty input should give empty output');
+
+            tester.assertThrows(() => run(data, 'rot13'), 'UNSUPPORTED_OPERATION', 'Unknown transforms should be rejected');
+            tester.assertThrows(() => run(data, 'rot_n'), 'INVALID_ARGUMENT', 'rot_n should require n');
+            tester.assertThrows(() => run(data, 'xor', { key: 256 }), 'INVALID_ARGUMENT', 'Keys should be bytes');
+            tester.assertThrows(() => run(data, 'xor', { kye: 1 }), 'INVALID_ARGUMENT', 'Unknown params should be rejected');
+
+            module.clear_cache();
+            run(data, 'xor', { key: 1 });
+            run(data, 'xor', { key: 2 });
+            tester.assertEqual(module.cache_size, 2, 'Results with different params should be cached apart');
+        });
+
+        // Test 73: Parallel CRC-32C
+        tester.test('Parallel CRC-32C', () => {
+            const processor = new tester.wasm.WasmParallelProcessor(0);
+            const { crc32c_combine } = tester.wasm.WasmParallelProcessor;
+            const encoder = new TextEncoder();
+
+            tester.assertEqual(processor.parallel_crc32c(new Uint8Array(0)), 0x00000000, 'The CRC-32C of nothing should be 0');
+            tester.assertEqual(processor.parallel_crc32c(encoder.encode('123456789')), 0xE3069283, 'The standard check value should match');
+            tester.assertEqual(processor.parallel_crc32c(new Uint8Array(32)), 0x8A9136AA, '32 zero bytes should match RFC 3720');
+            tester.assertEqual(processor.parallel_crc32c(new Uint8Array(32).fill(0xFF)), 0x62A8AB43, '32 0xFF bytes should match RFC 3720');
+
+            // Spans several 64 KiB chunks, so the parallel merge is exercised
+            let state = 12345;
+            const data = new Uint8Array(1000003).map(() => {
+                state = (state * 1103515245 + 12345) >>> 0;
+                return state >>> 24;
+            });
+            const whole = processor.parallel_crc32c(data);
+            for (const split of [0, 1, 7, 65536, 500000, data.length]) {
+                const a = data.subarray(0, split);
+                const b = data.subarray(split);
+                tester.assertEqual(crc32c_combine(processor.parallel_crc32c(a), processor.parallel_crc32c(b), b.length), whole, `crc32c(a || b) should equal combine(crc32c(a), crc32c(b)) splitting at ${split}`);
+            }
+            const fox = encoder.encode('The quick brown fox jumps over the lazy dog');
+            tester.assertEqual(crc32c_combine(processor.parallel_crc32c(fox.subarray(0, 10)), processor.parallel_crc32c(fox.subarray(10)), fox.length - 10), processor.parallel_crc32c(fox), 'Combining should work on short inputs');
+        });
+
+        // Test 74: Delta and varint transforms for numeric streams
+        tester.test('Numeric Stream Transforms', () => {
+            const module = new tester.wasm.WasmModule();
+            const run = (data, transform) => module.process_data(data, transform);
+            const bytes = (typed) => new Uint8Array(typed.buffer, typed.byteOffset, typed.byteLength);
+            const same = (a, b) => a.length === b.length && a.every((value, i) => value === b[i]);
+            let state = 2024;
+            const random = () => {
+                state = (state * 1103515245 + 12345) >>> 0;
+                return state;
+            };
+
+            tester.assertArrayEqual(Array.from(new Uint32Array(run(bytes(Uint32Array.from([100, 105, 103, 0xFFFFFFFF, 1])), 'delta_encode_u32').buffer)), [100, 5, 0xFFFFFFFE, 0xFFFFFF98, 2], 'u32 deltas should wrap');
+            tester.assertArrayEqual(Array.from(run(bytes(BigUint64Array.from([0n, 127n, 128n, 300n])), 'varint_encode_u64')), [0, 127, 0x80, 1, 0xAC, 2], 'varints should be LEB128');
+            tester.assertEqual(run(bytes(BigUint64Array.from([2n ** 64n - 1n])), 'varint_encode_u64').length, 10, 'The largest u64 should take 10 bytes');
+
+            for (let trial = 0; trial < 50; trial++) {
+                const length = random() % 200;
+                const monotone = new Uint32Array(length);
+                const arbitrary = new Uint32Array(length);
+                let t = random();
+                for (let i = 0; i < length; i++) {
+                    t = (t + random() % 1000) >>> 0;
+                    monotone[i] = t;
+                    arbitrary[i] = random();
+                }
+                for (const values of [monotone, arbitrary]) {
+                    const encoded = run(bytes(values), 'delta_encode_u32');
+                    tester.assert(same(run(encoded, 'delta_decode_u32'), bytes(values)), `u32 delta should round-trip (trial ${trial})`);
+                }
+
+                const monotone64 = new BigUint64Array(length);
+                const arbitrary64 = new BigUint64Array(length);
+                let t64 = 1700000000000n;
+                for (let i = 0; i < length; i++) {
+                    t64 += BigInt(random() % 5000);
+                    monotone64[i] = t64;
+                    arbitrary64[i] = (BigInt(random()) << 32n) | BigInt(random());
+                }
+                for (const values of [monotone64, arbitrary64]) {
+                    const encoded = run(bytes(values), 'varint_encode_u64');
+                    tester.assert(same(run(encoded, 'varint_decode_u64'), bytes(values)), `u64 varints should round-trip (trial ${trial})`);
+                }
+            }
+
+            // Delta-encoded millisecond timestamps: one absolute value, then steps of about a second
+            const timestamps = new BigUint64Array(10000).map((_, i) => (i === 0 ? 1700000000000n : 1000n + BigInt(random() % 50)));
+            const packed = run(bytes(timestamps), 'varint_encode_u64');
+            tester.assert(packed.length * 3.5 < timestamps.byteLength, `Small deltas should pack to 2 bytes each (got ${packed.length} from ${timestamps.byteLength})`);
+
+            tester.assertThrows(() => run(new Uint8Array(7), 'delta_encode_u32'), 'INVALID_ARGUMENT', 'u32 input must be whole words');
+            tester.assertThrows(() => run(new Uint8Array(12), 'varint_encode_u64'), 'INVALID_ARGUMENT', 'u64 input must be whole words');
+            const truncated = tester.assertThrows(() => run(new Uint8Array([5, 0x80, 0x80]), 'varint_decode_u64'), 'INVALID_ENCODING', 'A truncated varint should be rejected');
+            tester.assertEqual(truncated.details.offset, 1, 'The error should point at the truncated varint');
+            const overflow = tester.assertThrows(() => run(new Uint8Array([1, 2, ...new Array(10).fill(0xFF), 0x01]), 'varint_decode_u64'), 'INVALID_ENCODING', 'A varint longer than 10 bytes should be rejected');
+            tester.assertEqual(overflow.details.offset, 2, 'The error should point at the overlong varint');
+            tester.assert(/64 bits/.test(overflow.message) && /cut off/.test(truncated.message), 'Messages should tell overflow from truncation');
+
+            module.clear_cache();
+            module.reset_stats();
+            run(bytes(timestamps), 'varint_encode_u64');
+            run(bytes(timestamps), 'varint_encode_u64');
+            tester.assertEqual(module.cache_stats().hits, 1, 'A repeated payload should come from the cache');
+        });
+
+        // Test 75: Minkowski distances
+        tester.test('Minkowski Distance', () => {
+            const processor = new tester.wasm.WasmParallelProcessor(0);
+            const a = new Float64Array([1, 2, 3]);
+            const b = new Float64Array([4, -2, 3]);
+
+            tester.assertEqual(processor.parallel_minkowski_distance(a, b, 1), 7, 'p = 1 should give the Manhattan distance');
+            tester.assertEqual(processor.parallel_minkowski_distance(a, b, 2), 5, 'p = 2 should give the Euclidean distance');
+            tester.assert(Math.abs(processor.parallel_minkowski_distance(a, b, 3) - Math.cbrt(27 + 64)) < 1e-12, 'p = 3 should follow the formula');
+            tester.assert(Math.abs(processor.parallel_minkowski_distance(a, b, 1000) - 4) < 0.01, 'Large p should approach the Chebyshev distance');
+            tester.assert(Math.abs(processor.parallel_minkowski_distance(new Float64Array([0]), new Float64Array([1e300]), 50) - 1e300) < 1e288, 'Large p should not overflow');
+            tester.assertEqual(processor.parallel_minkowski_distance(a, a, 3), 0, 'A point should be at distance 0 from itself');
+
+            const n = 100000;
+            const x = new Float64Array(n).map((_, i) => Math.sin(i));
+            const y = new Float64Array(n).map((_, i) => Math.cos(i));
+            const manhattan = x.reduce((sum, value, i) => sum + Math.abs(value - y[i]), 0);
+            tester.assert(Math.abs(processor.parallel_minkowski_distance(x, y, 1) - manhattan) < 1e-6, 'Manhattan should match on large inputs');
+
+            for (const p of [0.5, Infinity, -Infinity, NaN]) {
+                tester.assertThrows(() => processor.parallel_minkowski_distance(a, b, p), 'INVALID_ARGUMENT', `p = ${p} should be rejected`);
+            }
+            tester.assertThrows(() => processor.parallel_minkowski_distance(a, new Float64Array(2), 2), 'DIMENSION_MISMATCH', 'Points of different lengths should be rejected');
+
+            // Points (0, 0), (3, 4) and (-1, 1)
+            const points = new Float64Array([0, 0, 3, 4, -1, 1]);
+            tester.assertArrayEqual(Array.from(processor.parallel_pairwise_minkowski(points, 3, 2, 1)), [0, 7, 2, 0, 7, 0], 'Pairwise Manhattan distances should fill the upper triangle');
+            tester.assertArrayEqual(Array.from(processor.parallel_pairwise_minkowski(points, 3, 2, 2)), Array.from(processor.parallel_distance_matrix(points, 3, 2)), 'Pairwise p = 2 should match the Euclidean distance matrix');
+            const chebyshev = processor.parallel_pairwise_minkowski(points, 3, 2, 500);
+            [0, 4, 1, 0, 4, 0].forEach((expected, i) => tester.assert(Math.abs(chebyshev[i] - expected) < 0.01, 'Pairwise large p should approach Chebyshev distances'));
+            tester.assertThrows(() => processor.parallel_pairwise_minkowski(points, 3, 2, Infinity), 'INVALID_ARGUMENT');
+        });
+
+        // Test 76: UTF-8 validation and text statistics
+        tester.test('Text Statistics', () => {
+            const module = new tester.wasm.WasmModule();
+            const encoder = new TextEncoder();
+
+            tester.assertEqual(JSON.stringify(module.validate_utf8(encoder.encode('héllo 🌍'))), '{"valid":true}', 'Valid text should have no error offset');
+            tester.assertEqual(JSON.stringify(module.validate_utf8(new Uint8Array([0x61, 0x62, 0xFF, 0x63]))), '{"valid":false,"error_offset":2}', 'An invalid byte should be located');
+            tester.assertEqual(module.validate_utf8(new Uint8Array([0x61, 0xE2, 0x82])).error_offset, 1, 'A truncated character should be located');
+            tester.assert(module.validate_utf8(new Uint8Array(0)).valid, 'Empty input is valid');
+
+            const stats = module.text_stats(encoder.encode('The cat saw the dog.\nthe DOG ran -- fast!\n\nCafé café'));
+            tester.assertEqual(stats.bytes, 54);
+            tester.assertEqual(stats.chars, 52, 'Characters should be counted, not bytes');
+            tester.assertEqual(stats.lines, 4, 'An unterminated last line should count');
+            tester.assertEqual(stats.words, 12, 'Words should be whitespace-separated runs');
+            tester.assertEqual(JSON.stringify(stats.top_words.slice(0, 3)), JSON.stringify([{ word: 'the', count: 3 }, { word: 'café', count: 2 }, { word: 'dog', count: 2 }]), 'Top words should be lowercased, most frequent first, ties alphabetical');
+            tester.assertEqual(module.text_stats(encoder.encode('one\ntwo\n')).lines, 2, 'A trailing newline should not add a line');
+            tester.assertEqual(JSON.stringify(module.text_stats(new Uint8Array(0))), '{"bytes":0,"chars":0,"lines":0,"words":0,"top_words":[]}');
+
+            // A log spanning many 64 KiB chunks
+            const levels = ['INFO', 'WARN', 'ERROR', 'DEBUG'];
+            const lines = Array.from({ length: 20000 }, (_, i) => `2024-01-01 ${levels[i % 4]} request ${i % 37} handled in ${i % 13}ms ✓`);
+            const log = lines.join('\n');
+            const logStats = module.text_stats(encoder.encode(log));
+            tester.assertEqual(logStats.lines, 20000);
+            tester.assertEqual(logStats.words, lines.length * 8);
+            tester.assertEqual(logStats.chars, [...log].length);
+            tester.assertEqual(logStats.bytes, encoder.encode(log).length);
+            tester.assertEqual(logStats.top_words.length, 20, 'At most 20 words should be reported');
+            tester.assertEqual(JSON.stringify(logStats.top_words.slice(0, 4)), JSON.stringify([
+                { word: '2024-01-01', count: 20000 }, { word: 'handled', count: 20000 }, { word: 'in', count: 20000 }, { word: 'request', count: 20000 },
+            ]), 'Counts should merge across chunks');
+
+            const error = tester.assertThrows(() => module.text_stats(new Uint8Array([0x6F, 0x6B, 0x0A, 0xC3, 0x28])), 'INVALID_ENCODING', 'Invalid UTF-8 should be an error');
+            tester.assertEqual(error.details.offset, 3, 'The error should point at the invalid byte');
+        });
+
+        // Test 77: Baseline JPEG decoding
+        tester.test('JPEG Decoding', () => {
+            const processor = new tester.wasm.WasmImageProcessor(0);
+            // A 16x16 baseline JFIF with 4:2:0 chroma subsampling
+            const jpeg = Buffer.from('/9j/4AAQSkZJRgABAQEAAQABAAD/2wBDAAMCAgICAgMCAgIDAwMDBAYEBAQEBAgGBgUGCQgKCgkICQkKDA8MCgsOCwkJDRENDg8QEBEQCgwSExIQEw8QEBD/2wBDAQMDAwQDBAgEBAgQCwkLEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBD/wAARCAAQABADASIAAhEBAxEB/8QAFgABAQEAAAAAAAAAAAAAAAAABwQF/8QAJBAAAQQBBAICAwAAAAAAAAAAAQIDBAYFBwgSExEiABQJMTL/xAAVAQEBAAAAAAAAAAAAAAAAAAAABv/EACMRAAECBQMFAAAAAAAAAAAAAAECEQMEBQYhABIxFRZhgeH/2gAMAwEAAhEDEQA/ABSm0mobc8HmExLUlRzzEWPkJWW+ulrsaUVAseUgslSlH9LKuPryIKuWPZdskzXmm3fX5m2nF4GlVxx/HOpx4ks51+MiU/Iaad7UcUo4tILoS4kqcWkezS0hO/HvuRp0rO6hWnWO1UisZVuFi4GFeyEpmGepa5S5SWVPuciFKRFLgSrwetnyPIB+Vb4N9mKhQMzo5po9XLdDs9d6ZVix2VEhiL9kuNPxw2gEKcDQ/rs8AuA8VAe0vdl7VOYn+27flGAUgmITjbhSmCg3BYlyeWDkMolvw4KOp1KM6iCNvngZHwetf//Z', 'base64');
+
+            const image = processor.decode_jpeg(jpeg);
+            tester.assertEqual(image.width, 16);
+            tester.assertEqual(image.height, 16);
+            const data = image.data;
+            tester.assertEqual(data.length, 16 * 16 * 4, 'Output should be RGBA');
+
+            // Reference pixels from libjpeg-compatible decoding, allowing for IDCT rounding
+            const reference = [[0, 0, [0, 2, 6]], [4, 0, [101, 130, 160]], [8, 8, [241, 230, 122]], [5, 10, [255, 236, 111]], [15, 15, [0, 1, 6]]];
+            for (const [x, y, rgb] of reference) {
+                const i = (y * 16 + x) * 4;
+                rgb.forEach((value, c) => tester.assert(Math.abs(data[i + c] - value) <= 2, `Pixel (${x}, ${y}) channel ${c} should be near ${value}, got ${data[i + c]}`));
+                tester.assertEqual(data[i + 3], 255, 'Alpha should be opaque');
+            }
+
+            tester.assertThrows(() => processor.decode_jpeg(new TextEncoder().encode('GIF89a')), 'INVALID_ENCODING', 'Non-JPEG data should be rejected');
+            tester.assertThrows(() => processor.decode_jpeg(jpeg.subarray(0, 450)), 'INVALID_ENCODING', 'Truncated entropy-coded data should be rejected');
+            tester.assertThrows(() => processor.decode_jpeg(jpeg.subarray(0, jpeg.length - 2)), 'INVALID_ENCODING', 'A missing end-of-image marker should be rejected');
+
+            const progressive = Buffer.from(jpeg);
+            progressive[progressive.indexOf(Buffer.from([0xFF, 0xC0])) + 1] = 0xC2;
+            tester.assertThrows(() => processor.decode_jpeg(progressive), 'UNSUPPORTED_OPERATION', 'Progressive JPEGs are not supported');
+
+            // A header claiming 65535x65535 must not be allocated for
+            const huge = Buffer.from(jpeg);
+            const sof = huge.indexOf(Buffer.from([0xFF, 0xC0]));
+            huge.fill(0xFF, sof + 5, sof + 9);
+            tester.assertThrows(() => processor.decode_jpeg(huge), 'INVALID_ARGUMENT', 'Oversized dimensions should be rejected');
+        });
+
+        // Test 78: Shared runtime across processors
+        tester.test('Shared Runtime', () => {
+            const { WasmRuntime, WasmMatrixProcessor, WasmParallelProcessor, WasmImageProcessor, WasmBatchProcessor } = tester.wasm;
+            const runtime = new WasmRuntime(0);
+            tester.assert(runtime.num_threads() >= 1, 'The runtime should report its workers');
+
+            const matrix = WasmMatrixProcessor.with_runtime(runtime);
+            const parallel = WasmParallelProcessor.with_runtime(runtime);
+            const image = WasmImageProcessor.with_runtime(runtime);
+            const batch = WasmBatchProcessor.with_runtime(16, runtime);
+            for (const processor of [matrix, parallel, image, batch]) {
+                tester.assert(processor.runtime.shares_pool_with(runtime), 'Processors from one runtime should share its pool');
+                tester.assertEqual(processor.runtime.num_threads(), runtime.num_threads());
+            }
+            tester.assert(matrix.runtime.shares_pool_with(parallel.runtime), 'Two processors should report the same pool');
+            tester.assert(new WasmImageProcessor(0).runtime.shares_pool_with(WasmRuntime.global()), 'Existing constructors should use the global runtime');
+
+            tester.assertEqual(parallel.parallel_sum(new Int32Array([1, 2, 3, 4])), 10);
+            tester.assertEqual(Array.from(matrix.multiply(new Float64Array([1, 2, 3, 4]), 2, 2, new Float64Array([1, 0, 0, 1]), 2, 2)).join(','), '1,2,3,4');
+        });
+
+        // Test 79: Haversine distances and nearest coordinate
+        tester.test('Geospatial Haversine', () => {
+            const processor = new tester.wasm.WasmParallelProcessor(0);
+            const f64 = (values) => new Float64Array(values);
+
+            // Nashville BNA -> Los Angeles LAX (the classic haversine example) and the
+            // Eiffel Tower -> Statue of Liberty, on the 6371008.8 m mean-radius sphere
+            const distances = processor.parallel_haversine(
+                f64([36.12, 48.8584, 10, 0]), f64([-86.67, 2.2945, 20, 0]),
+                f64([33.94, 40.6892, 10, 0]), f64([-118.40, -74.0445, 20, 180]),
+            );
+            tester.assert(Math.abs(distances[0] - 2886448.43) < 1, `BNA -> LAX should be 2886448 m, got ${distances[0]}`);
+            tester.assert(Math.abs(distances[1] - 5837423.89) < 1, `Paris -> New York should be 5837424 m, got ${distances[1]}`);
+            tester.assertEqual(distances[2], 0, 'A point is no distance from itself');
+            tester.assert(Math.abs(distances[3] - Math.PI * 6371008.8) < 1e-6, 'Antipodes should be half a circumference apart');
+
+            // Many pairs, checked against the same formula in JS
+            const n = 20000;
+            const lat1 = f64(Array.from({ length: n }, (_, i) => (i * 37) % 180 - 90));
+            const lon1 = f64(Array.from({ length: n }, (_, i) => (i * 53) % 360 - 180));
+            const lat2 = f64(Array.from({ length: n }, (_, i) => (i * 11) % 180 - 90));
+            const lon2 = f64(Array.from({ length: n }, (_, i) => (i * 7) % 360 - 180));
+            const bulk = processor.parallel_haversine(lat1, lon1, lat2, lon2);
+            const rad = Math.PI / 180;
+            for (let i = 0; i < n; i += 997) {
+                const h = Math.sin((lat2[i] - lat1[i]) * rad / 2) ** 2
+                    + Math.cos(lat1[i] * rad) * Math.cos(lat2[i] * rad) * Math.sin((lon2[i] - lon1[i]) * rad / 2) ** 2;
+                const expected = 2 * 6371008.8 * Math.asin(Math.sqrt(Math.min(h, 1)));
+                tester.assert(Math.abs(bulk[i] - expected) < 1e-3, `Pair ${i} should match the JS formula`);
+            }
+
+            // London, Paris, New York, Tokyo, with a NaN entry skipped
+            const lats = f64([51.5074, 48.8566, NaN, 40.7128, 35.6762]);
+            const lons = f64([-0.1278, 2.3522, 0, -74.0060, 139.6503]);
+            tester.assertEqual(processor.parallel_nearest_coordinate(50.85, 4.35, lats, lons), 1, 'Brussels is nearest Paris');
+            tester.assertEqual(processor.parallel_nearest_coordinate(42.36, -71.06, lats, lons), 3, 'Boston is nearest New York');
+            tester.assertEqual(processor.parallel_nearest_coordinate(0, 0, f64([1, 1]), f64([0, 0])), 0, 'Ties should go to the lowest index');
+
+            tester.assertThrows(() => processor.parallel_haversine(f64([1, 2]), f64([1, 2]), f64([1]), f64([1, 2])), 'DIMENSION_MISMATCH', 'Slices must have the same length');
+            tester.assertThrows(() => processor.parallel_nearest_coordinate(0, 0, f64([1, 2]), f64([1])), 'DIMENSION_MISMATCH');
+            tester.assertThrows(() => processor.parallel_nearest_coordinate(0, 0, f64([]), f64([])), 'INVALID_ARGUMENT', 'An empty set has no nearest coordinate');
+        });
+
+        // Test 80: Runtime resizing
+        tester.test('Runtime Resize', () => {
+            const { WasmRuntime, WasmMatrixProcessor, WasmParallelProcessor, WasmImageProcessor, WasmBatchProcessor } = tester.wasm;
+            const runtime = WasmRuntime.global();
+            const processors = [
+                WasmMatrixProcessor.with_runtime(runtime),
+                WasmParallelProcessor.with_runtime(runtime),
+                WasmImageProcessor.with_runtime(runtime),
+                WasmBatchProcessor.with_runtime(16, runtime),
+            ];
+            for (const processor of processors) {
+                tester.assertEqual(processor.current_num_threads(), runtime.num_threads(), 'Processors should report their runtime\'s workers');
+            }
+
+            // Without WASM threads no pool can be started; the current one is kept
+            const parallel = processors[1];
+            const before = parallel.parallel_sum(new Int32Array([5, 6, 7]));
+            const threads = runtime.num_threads();
+            for (const size of [2, 0]) {
+                tester.assertThrows(() => runtime.resize(size), 'RESOURCE_UNAVAILABLE', `Resizing to ${size} should fail without threads`);
+            }
+            tester.assertEqual(runtime.num_threads(), threads, 'A failed resize should keep the pool');
+            tester.assertEqual(parallel.parallel_sum(new Int32Array([5, 6, 7])), before, 'Processors should keep working');
+        });
+
+        // Test 81: Rolling z-scores
+        tester.test('Rolling Z-Score', () => {
+            const processor = new tester.wasm.WasmParallelProcessor(0);
+
+            const constant = processor.parallel_rolling_z_score(new Float64Array(1000).fill(3.7), 10);
+            tester.assert(constant.every((z) => z === 0), 'A constant sequence should score all zeros');
+
+            // A step from 0 to 1 at index 20 with a window of 5: the first 1 is
+            // sqrt(window - 1) deviations above its window's mean, then the score decays
+            const step = Float64Array.from({ length: 40 }, (_, i) => (i < 20 ? 0 : 1));
+            const scores = processor.parallel_rolling_z_score(step, 5);
+            tester.assert(scores.slice(0, 20).every((z) => z === 0), 'Scores before the step should be zero');
+            tester.assert(Math.abs(scores[20] - 2) < 1e-12, `The step should spike to 2, got ${scores[20]}`);
+            tester.assert(Math.abs(scores[21] - Math.sqrt(1.5)) < 1e-12, 'The spike should decay');
+            tester.assert(scores[20] > scores[21] && scores[21] > scores[22] && scores[22] > scores[23], 'The score should fall as the window fills');
+            tester.assert(scores.slice(24).every((z) => z === 0), 'Scores should return to zero once the window is constant');
+
+            // Against a direct computation, including the partial windows at the start
+            const n = 5000;
+            const window = 37;
+            const data = Float64Array.from({ length: n }, (_, i) => Math.sin(i * 0.37) * 100 + (i % 11) * 3 + 1e4);
+            const rolling = processor.parallel_rolling_z_score(data, window);
+            for (let i = 0; i < n; i += 41) {
+                const slice = data.slice(Math.max(0, i - window + 1), i + 1);
+                const mean = slice.reduce((a, b) => a + b, 0) / slice.length;
+                const std = Math.sqrt(slice.reduce((a, b) => a + (b - mean) ** 2, 0) / slice.length);
+                const expected = std === 0 ? 0 : (data[i] - mean) / std;
+                tester.assert(Math.abs(rolling[i] - expected) < 1e-6, `Score ${i} should be ${expected}, got ${rolling[i]}`);
+            }
+            tester.assertEqual(rolling[0], 0, 'The first value has nothing to deviate from');
+            tester.assertEqual(processor.parallel_rolling_z_score(new Float64Array(0), 3).length, 0);
+
+            tester.assertThrows(() => processor.parallel_rolling_z_score(data, 0), 'INVALID_ARGUMENT', 'A zero window should be rejected');
+            tester.assertThrows(() => processor.parallel_rolling_z_score(new Float64Array([1, NaN, 2]), 2), 'INVALID_ARGUMENT', 'NaN should be rejected');
+        });
+
+        // Test 82: Hardware concurrency detection
+        tester.test('Hardware Concurrency', () => {
+            const { hardware_concurrency } = tester.wasm;
+            // Node has no window or worker scope to ask
+            tester.assertEqual(hardware_concurrency(), 1, 'Without a navigator the count should be 1');
+
+            // Pose as a Web Worker whose navigator reports various counts
+            const hadNavigator = Object.getOwnPropertyDescriptor(globalThis, 'navigator');
+            globalThis.WorkerGlobalScope = class {
+                static [Symbol.hasInstance](value) {
+                    return value === globalThis;
+                }
+            };
+            try {
+                for (const [reported, expected] of [[12, 12], [1, 1], [500, 64], [0, 1]]) {
+                    Object.defineProperty(globalThis, 'navigator', { value: { hardwareConcurrency: reported }, configurable: true });
+                    tester.assertEqual(hardware_concurrency(), expected, `A reported ${reported} should give ${expected}`);
+                }
+            } finally {
+                delete globalThis.WorkerGlobalScope;
+                if (hadNavigator) {
+                    Object.defineProperty(globalThis, 'navigator', hadNavigator);
+                } else {
+                    delete globalThis.navigator;
+                }
+            }
+        });
+
+        // Test 83: Embedding similarity search
+        tester.test('Embedding Index', () => {
+            const index = new tester.wasm.WasmEmbeddingIndex();
+            tester.assertThrows(() => index.most_similar(new Float32Array(3), 1), 'NOT_INITIALIZED', 'Queries need embeddings loaded');
+
+            // Deterministic pseudo-random embeddings
+            const nWords = 2000;
+            const dim = 50;
+            let seed = 7;
+            const random = () => {
+                seed = (seed * 1103515245 + 12345) % 2147483648;
+                return seed / 2147483648 - 0.5;
+            };
+            const data = Float32Array.from({ length: nWords * dim }, random);
+            index.load_embeddings(data, nWords, dim);
+            tester.assertEqual(index.n_words, nWords);
+            tester.assertEqual(index.dim, dim);
+
+            const word = (i) => data.slice(i * dim, (i + 1) * dim);
+            tester.assertEqual(index.most_similar(word(0), 1)[0], 0, 'A word should be most similar to itself');
+            tester.assertEqual(index.most_similar(word(1234).map((x) => x * 40), 1)[0], 1234, 'Scaling the query should not change the ranking');
+
+            // Compare the top 10 with a direct cosine ranking
+            const query = Float32Array.from({ length: dim }, random);
+            const norm = (v) => Math.sqrt(v.reduce((a, x) => a + x * x, 0));
+            const cosine = (i) => word(i).reduce((a, x, j) => a + x * query[j], 0) / (norm(word(i)) * norm(query));
+            const expected = Array.from({ length: nWords }, (_, i) => i).sort((a, b) => cosine(b) - cosine(a)).slice(0, 10);
+            tester.assertEqual(Array.from(index.most_similar(query, 10)).join(','), expected.join(','), 'The top 10 should match a direct ranking');
+            tester.assertEqual(index.most_similar(query, 5000).length, nWords, 'top_k should be capped at the number of words');
+            tester.assertEqual(index.most_similar(query, 0).length, 0);
+
+            // Ties go to the lower index, and zero embeddings score 0
+            const small = new tester.wasm.WasmEmbeddingIndex();
+            small.load_embeddings(new Float32Array([0, 0, 2, 0, 1, 0, -1, 0]), 4, 2);
+            tester.assertEqual(Array.from(small.most_similar(new Float32Array([3, 0]), 4)).join(','), '1,2,0,3');
+
+            tester.assertThrows(() => index.most_similar(new Float32Array(dim + 1), 1), 'DIMENSION_MISMATCH', 'The query must match the embedding dimension');
+            tester.assertThrows(() => index.most_similar(new Float32Array(dim), 1), 'INVALID_ARGUMENT', 'A zero query has no direction');
+            tester.assertThrows(() => index.load_embeddings(new Float32Array(10), 3, 3), 'DIMENSION_MISMATCH');
+            tester.assertThrows(() => index.load_embeddings(new Float32Array([1, NaN]), 1, 2), 'INVALID_ARGUMENT');
+            tester.assertEqual(index.n_words, nWords, 'A failed load should keep the embeddings');
+        });
+
+        // Test 84: Finite differences, gradient and cumulative integration
+        tester.test('Numerical Differences', () => {
+            const processor = new tester.wasm.WasmParallelProcessor(0);
+            const close = (a, b, tolerance) => a.length === b.length && a.every((x, i) => Math.abs(x - b[i]) <= tolerance);
+
+            tester.assertEqual(Array.from(processor.parallel_diff(new Float64Array([1, 4, 9, 16, 25]), 1)).join(','), '3,5,7,9');
+            tester.assertEqual(Array.from(processor.parallel_diff(new Float64Array([1, 4, 9, 16, 25]), 2)).join(','), '2,2,2');
+            const cubes = Float64Array.from({ length: 20 }, (_, i) => i ** 3);
+            tester.assert(processor.parallel_diff(cubes, 3).every((d) => d === 6), 'The third difference of n^3 should be 6');
+            tester.assert(processor.parallel_diff(cubes, 4).every((d) => d === 0), 'The fourth difference of n^3 should be 0');
+            tester.assertEqual(processor.parallel_diff(cubes, 5).length, 15, 'Each order should drop one value');
+
+            // diff(cumsum(x)) recovers x after its first value
+            const n = 200000;
+            const x = Float64Array.from({ length: n }, (_, i) => Math.sin(i * 0.01) + (i % 7) * 0.1);
+            const cumsum = new Float64Array(n);
+            x.reduce((total, v, i) => (cumsum[i] = total + v), 0);
+            tester.assert(close(processor.parallel_diff(cumsum, 1), x.slice(1), 1e-6), 'diff(cumsum(x)) should recover x');
+
+            // Central differences are exact for quadratics inside the range
+            const spacing = 0.5;
+            const quadratic = Float64Array.from({ length: 100 }, (_, i) => (i * spacing) ** 2);
+            const gradient = processor.parallel_gradient(quadratic, spacing);
+            tester.assertEqual(gradient.length, 100);
+            tester.assert(close(gradient.slice(1, -1), Float64Array.from({ length: 98 }, (_, i) => 2 * (i + 1) * spacing), 1e-9), 'The gradient of x^2 should be 2x inside');
+            tester.assertEqual(gradient[0], (quadratic[1] - quadratic[0]) / spacing, 'The first value should be a forward difference');
+            tester.assertEqual(gradient[99], (quadratic[99] - quadratic[98]) / spacing, 'The last value should be a backward difference');
+
+            // Integrating a line is exact, across several chunks
+            const line = Float64Array.from({ length: n }, (_, i) => 3 * i * 0.001 + 1);
+            const integral = processor.parallel_cumtrapz(line, 0.001);
+            tester.assertEqual(integral.length, n);
+            tester.assertEqual(integral[0], 0, 'The integral should start at 0');
+            const expected = Float64Array.from({ length: n }, (_, i) => 1.5 * (i * 0.001) ** 2 + i * 0.001);
+            tester.assert(close(integral, expected, 1e-6), 'The integral of 3t + 1 should be 1.5t^2 + t');
+            tester.assert(close(processor.parallel_diff(integral, 1).map((d) => d / 0.001), line.slice(1).map((v, i) => (v + line[i]) / 2), 1e-6), 'Differencing the integral should give the trapezoid heights');
+            tester.assertEqual(processor.parallel_cumtrapz(new Float64Array(0), 1).length, 0);
+
+            tester.assertThrows(() => processor.parallel_diff(cubes, 0), 'INVALID_ARGUMENT', 'Order 0 should be rejected');
+            tester.assertThrows(() => processor.parallel_diff(cubes, 6), 'INVALID_ARGUMENT', 'Orders above 5 should be rejected');
+            tester.assertThrows(() => processor.parallel_diff(new Float64Array([1, 2, 3]), 3), 'INVALID_ARGUMENT', 'The order must be less than the length');
+            tester.assertThrows(() => processor.parallel_gradient(new Float64Array([1]), 1), 'DIMENSION_MISMATCH', 'A gradient needs two values');
+            tester.assertThrows(() => processor.parallel_cumtrapz(line, 0), 'INVALID_ARGUMENT', 'A zero spacing should be rejected');
+        });
+
+        // Test 85: Threading support and the best-effort fallback
+        tester.test('Threading Support', () => {
+            const support = tester.wasm.threading_support();
+            tester.assertEqual(support.shared_array_buffer, typeof SharedArrayBuffer !== 'undefined');
+            tester.assertEqual(support.cross_origin_isolated, globalThis.crossOriginIsolated === true);
+            tester.assertEqual(support.hardware_concurrency, tester.wasm.hardware_concurrency());
+            const expected = support.shared_array_buffer && support.cross_origin_isolated ? support.hardware_concurrency : 1;
+            tester.assertEqual(support.recommended_threads, expected, 'Threads should only be recommended where they can start');
+
+            // 0 threads never fails, and runs on the calling thread when
+            // workers are unavailable
+            const processor = new tester.wasm.WasmParallelProcessor(0);
+            tester.assert(processor.current_num_threads() >= 1, 'A best-effort processor should have a thread');
+            const data = Int32Array.from({ length: 10000 }, (_, i) => (i * 31) % 1009 - 500);
+            tester.assertEqual(processor.parallel_sum(data), data.reduce((a, b) => a + b, 0));
+            const sample = processor.parallel_sample_without_replacement(100000, 500, 9n);
+            tester.assertEqual(new Set(sample).size, 500, 'The fallback should still sample distinct indices');
+        });
+
+        // Test 86: UTF-16 decoding and encoding
+        tester.test('UTF-16 Decode and Encode', () => {
+            const processor = new tester.wasm.WasmParallelProcessor(0);
+            const units = (text) => Uint16Array.from({ length: text.length }, (_, i) => text.charCodeAt(i));
+            const roundTrip = (text, what) => {
+                tester.assertEqual(processor.parallel_decode_utf16(units(text)), text, `${what} should decode`);
+                tester.assertEqual(Array.from(processor.parallel_encode_utf16(text)).join(','), Array.from(units(text)).join(','), `${what} should encode`);
+            };
+
+            roundTrip('', 'Empty text');
+            roundTrip('Hello, world!'.repeat(10000), 'ASCII');
+            roundTrip('Grüße, Καλημέρα, こんにちは, مرحبا '.repeat(5000), 'BMP text');
+            roundTrip('😀🎉𝄞 emoji and 𠜎 CJK extension B '.repeat(5000), 'Supplementary plane text');
+
+            // Put a surrogate pair across every chunk boundary
+            const straddling = 'a' + '😀'.repeat(200000);
+            roundTrip(straddling, 'Pairs straddling chunk boundaries');
+
+            const errorOffset = (data) => tester.assertThrows(() => processor.parallel_decode_utf16(data), 'INVALID_ENCODING').details.offset;
+            tester.assertEqual(errorOffset(new Uint16Array([0x41, 0xd83d, 0x42])), 2, 'A high surrogate without its low half should be rejected');
+            tester.assertEqual(errorOffset(new Uint16Array([0x41, 0x42, 0xde00])), 4, 'A lone low surrogate should be rejected');
+            tester.assertEqual(errorOffset(new Uint16Array([0xd83d])), 0, 'Text ending inside a pair should be rejected');
+            const late = units('x'.repeat(100000) + '😀'.repeat(10));
+            late[late.length - 3] = 0x20;
+            tester.assertEqual(errorOffset(late), (late.length - 4) * 2, 'The error should point at the first unpaired surrogate');
+        });
+
+        // Test 87: Sparse histogram
+        tester.test('Sparse Histogram', () => {
+            const processor = new tester.wasm.WasmParallelProcessor(0);
+            const getCount = tester.wasm.sparse_histogram_get_count;
+
+            const n = 300000;
+            const data = BigInt64Array.from({ length: n }, (_, i) => BigInt((i * 7919) % 100003) * 1000003n - 20000000000n);
+            data[0] = -(2n ** 63n);
+            data[1] = 2n ** 63n - 1n;
+            const expected = new Map();
+            for (const value of data) {
+                expected.set(value, (expected.get(value) || 0) + 1);
+            }
+
+            const histogram = processor.parallel_sparse_histogram(data);
+            tester.assertEqual(histogram[0], 0xdf, 'Over 65535 distinct values should use a map32 header');
+            tester.assertEqual(new DataView(histogram.buffer, histogram.byteOffset).getUint32(1), expected.size, 'The header should count the distinct values');
+
+            let total = 0;
+            for (const [value, count] of expected) {
+                const found = getCount(histogram, value);
+                tester.assertEqual(found, count, `Count of ${value}`);
+                total += found;
+            }
+            tester.assertEqual(total, n, 'The counts should sum to the input length');
+            tester.assertEqual(getCount(histogram, 12345n), 0, 'An absent value should count 0');
+
+            const small = processor.parallel_sparse_histogram(new BigInt64Array([5n, -1n, 5n]));
+            tester.assertEqual(Array.from(small).join(','), [
+                0x82,
+                0xd3, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xce, 0, 0, 0, 1,
+                0xd3, 0, 0, 0, 0, 0, 0, 0, 5, 0xce, 0, 0, 0, 2,
+            ].join(','), 'A small histogram should be a fixmap sorted by value');
+            tester.assertEqual(getCount(processor.parallel_sparse_histogram(new BigInt64Array(0)), 0n), 0, 'An empty histogram should count 0');
+
+            for (const bytes of [[], [0x91, 0xc0], small.slice(0, 20)]) {
+                tester.assertThrows(() => getCount(new Uint8Array(bytes), 5n), 'INVALID_ENCODING', `${bytes.length} malformed bytes should be rejected`);
+            }
+        });
+
+        // Test 88: Operation profiling
+        tester.test('Operation Profiling', () => {
+            const processor = new tester.wasm.WasmParallelProcessor(0);
+            const data = Float64Array.from({ length: 100000 }, (_, i) => Math.sin(i));
+
+            processor.parallel_diff(data, 1);
+            tester.assertEqual(processor.last_op_stats(), undefined, 'Nothing should be recorded until profiling is on');
+
+            processor.set_profiling(true);
+            processor.parallel_diff(data, 1);
+            const stats = processor.last_op_stats();
+            tester.assertEqual(stats.operation, 'parallel_diff');
+            tester.assertEqual(stats.input_elements, data.length);
+            tester.assert(stats.pool_threads >= 1, 'The pool size should be reported');
+            tester.assert(stats.wall_ms >= 0 && Number.isFinite(stats.wall_ms), `Wall time should be a duration, got ${stats.wall_ms}`);
+            tester.assert(stats.bytes_allocated >= (data.length - 1) * 8, `The output should be counted, got ${stats.bytes_allocated}`);
+
+            processor.parallel_sum(new Int32Array([1, 2, 3]));
+            tester.assertEqual(processor.last_op_stats().operation, 'parallel_sum', 'Each call should replace the stats');
+            tester.assertEqual(processor.last_op_stats().bytes_allocated, 0);
+
+            const image = new tester.wasm.WasmImageProcessor(0);
+            image.set_profiling(true);
+            image.grayscale(new Uint8Array(64 * 4).fill(200));
+            tester.assertEqual(image.last_op_stats().operation, 'grayscale');
+            tester.assertEqual(image.last_op_stats().bytes_allocated, 64 * 4);
+
+            processor.set_profiling(false);
+            tester.assertEqual(processor.last_op_stats(), undefined, 'Turning profiling off should forget the stats');
+        });
+
+        // Test 89: Dominant colours
+        tester.test('Dominant Colours', () => {
+            const processor = new tester.wasm.WasmImageProcessor(0);
+
+            // Three red pixels and one blue one
+            const twoColours = new Uint8Array([
+                255, 0, 0, 255, 0, 0, 255, 255,
+                255, 0, 0, 255, 255, 0, 0, 255,
+            ]);
+            const palette = processor.extract_dominant_colors(twoColours, 2, 10);
+            tester.assertEqual(Array.from(palette).join(','), '255,0,0,255,0,0,255,255', 'k=2 should recover both colours, most common first');
+            tester.assertEqual(processor.extract_dominant_colors(twoColours, 5, 10).length, 8, 'Two distinct colours should give two entries');
+            tester.assertEqual(Array.from(processor.extract_dominant_colors(twoColours, 1, 10)).join(','), '191,0,64,255', 'k=1 should give the mean colour');
+
+            // A large image of three colour bands, with a little noise
+            const pixels = 400 * 300;
+            const image = new Uint8Array(pixels * 4);
+            const bands = [[30, 120, 200], [240, 200, 40], [20, 20, 20]];
+            for (let i = 0; i < pixels; i++) {
+                const band = bands[i < pixels / 2 ? 0 : i < pixels * 0.8 ? 1 : 2];
+                for (let c = 0; c < 3; c++) {
+                    image[i * 4 + c] = band[c] + ((i * 7 + c) % 5) - 2;
+                }
+                image[i * 4 + 3] = 255;
+            }
+            const colours = processor.extract_dominant_colors(image, 3, 20);
+            tester.assertEqual(colours.length, 12, 'k colours should be returned');
+            bands.forEach((band, b) => {
+                for (let c = 0; c < 3; c++) {
+                    tester.assert(Math.abs(colours[b * 4 + c] - band[c]) <= 2, `Band ${b} should be found in order of size, got ${Array.from(colours)}`);
+                }
+            });
+            tester.assertEqual(Array.from(processor.extract_dominant_colors(image, 3, 20)).join(','), Array.from(colours).join(','), 'The palette should be reproducible');
+
+            for (const k of [0, 257]) {
+                tester.assertThrows(() => processor.extract_dominant_colors(twoColours, k, 10), 'INVALID_ARGUMENT', `k=${k} should be rejected`);
+            }
+        });
+
+        // Test 90: Initialization and build info
+        tester.test('Init and Build Info', () => {
+            tester.wasm.init();
+            tester.wasm.init({ log_level: 'warn' });
+            tester.wasm.init({ log_level: 'info' });
+
+            tester.assertThrows(() => tester.wasm.init({ log_level: 'verbose' }), 'UNSUPPORTED_OPERATION', 'Unknown log levels should be rejected');
+            tester.assertThrows(() => tester.wasm.init({ colour: true }), 'INVALID_ARGUMENT', 'Unknown options should be rejected');
+
+            const info = tester.wasm.build_info();
+            tester.assert(/^\d+\.\d+\.\d+/.test(info.version), `Version should be semver, got ${info.version}`);
+            tester.assert(info.target.startsWith('wasm32'), `Target should be wasm32, got ${info.target}`);
+            tester.assert(['debug', 'release'].includes(info.profile), 'Profile should be debug or release');
+            tester.assert(Array.isArray(info.features), 'Features should be an array');
+        });
+
+        // Test 91: Polynomial evaluation
+        tester.test('Polynomial Evaluation', () => {
+            const processor = new tester.wasm.WasmParallelProcessor(0);
+
+            // x² - 3x + 2 = (x - 1)(x - 2)
+            const values = processor.parallel_polyeval(new Float64Array([1, -3, 2]), new Float64Array([0, 1, 2, 3]));
+            tester.assertEqual(Array.from(values).join(','), '2,0,0,2', 'Should vanish at the roots 1 and 2');
+            tester.assertEqual(Array.from(processor.parallel_polyeval(new Float64Array(0), new Float64Array([5, -1]))).join(','), '0,0', 'No coefficients should give 0');
+            tester.assertEqual(processor.parallel_polyeval(new Float64Array([1]), new Float64Array(0)).length, 0, 'No points should give nothing');
+
+            const points = new Float64Array(10000).map((_, i) => -1 + (2 * i) / 9999);
+            const many = processor.parallel_polyeval(new Float64Array([2, 0, -1, 0.5]), points);
+            tester.assert(points.every((x, i) => Math.abs(many[i] - (2 * x ** 3 - x + 0.5)) < 1e-12), 'Parallel results should match a direct evaluation');
+
+            // T0 + 2·T1 + 3·T2 = 1 + 2x + 3(2x² - 1)
+            const cheb = processor.parallel_polyeval_chebyshev(new Float64Array([1, 2, 3]), points);
+            tester.assert(points.every((x, i) => Math.abs(cheb[i] - (6 * x * x + 2 * x - 2)) < 1e-12), 'Chebyshev series should match its power form');
+            // T5(cos θ) = cos 5θ
+            const t5 = processor.parallel_polyeval_chebyshev(new Float64Array([0, 0, 0, 0, 0, 1]), new Float64Array([Math.cos(0.3)]));
+            tester.assert(Math.abs(t5[0] - Math.cos(1.5)) < 1e-12, `T5(cos 0.3) should be cos 1.5, got ${t5[0]}`);
+            tester.assertEqual(Array.from(processor.parallel_polyeval_chebyshev(new Float64Array(0), new Float64Array([0.5]))).join(','), '0', 'No coefficients should give 0');
+        });
+
+        // Test 92: Background task queue
+        tester.test('Task Queue', async () => {
+            const queue = new tester.wasm.WasmTaskQueue(4, 0);
+            const data = new Float64Array([1, 4, 9, 16]);
+
+            const squared = queue.submit_expr(data, 'square');
+            const rooted = queue.submit_expr(data, 'sqrt|neg');
+            const cancelled = queue.submit_expr(data, 'exp');
+            tester.assert(queue.cancel(cancelled), 'A pending task should be cancellable');
+            tester.assertEqual(queue.status(cancelled), 'cancelled');
+
+            const deadline = Date.now() + 5000;
+            while (['pending', 'running'].includes(queue.status(squared)) || ['pending', 'running'].includes(queue.status(rooted))) {
+                tester.assert(Date.now() < deadline, 'Tasks should finish');
+                await new Promise(resolve => setTimeout(resolve, 1));
+            }
+            tester.assertEqual(queue.status(squared), 'done');
+            tester.assert(queue.retained_bytes() >= 2 * 4 * 8, 'Finished results should be held');
+            tester.assertEqual(Array.from(queue.take_result(squared)).join(','), '1,16,81,256');
+            tester.assertEqual(Array.from(queue.take_result(rooted)).join(','), '-1,-2,-3,-4');
+
+            tester.assertThrows(() => queue.take_result(cancelled), 'CANCELLED', 'A cancelled task should have no result');
+            tester.assertEqual(queue.task_count, 0, 'Taken tasks should be forgotten');
+            tester.assertEqual(queue.retained_bytes(), 0, 'Taken results should be released');
+
+            tester.assertThrows(() => queue.submit_expr(data, 'square|cube'), 'UNSUPPORTED_OPERATION', 'Unknown operations should be rejected');
+        });
+
+        // Test 93: Image statistics
+        tester.test('Image Statistics', () => {
+            const processor = new tester.wasm.WasmImageProcessor(0);
+
+            const red = new Uint8Array(64 * 64 * 4);
+            for (let i = 0; i < red.length; i += 4) {
+                red[i] = 255;
+                red[i + 3] = 255;
+            }
+            const stats = processor.image_statistics(red);
+            tester.assertEqual(stats.length, 16, 'Four statistics for each of four channels');
+            tester.assertEqual(Array.from(stats).join(','), '255,0,255,255,0,0,0,0,0,0,0,0,255,0,255,255', 'A pure red image');
+
+            // Red alternates 0 and 200; green runs through 0..255
+            const pixels = 300 * 200;
+            const image = new Uint8Array(pixels * 4);
+            for (let i = 0; i < pixels; i++) {
+                image[i * 4] = i % 2 ? 200 : 0;
+                image[i * 4 + 1] = i % 256;
+                image[i * 4 + 2] = 7;
+                image[i * 4 + 3] = 255;
+            }
+            const mixed = processor.image_statistics(image);
+            tester.assert(Math.abs(mixed[0] - 100) < 1e-9 && Math.abs(mixed[1] - 100) < 1e-9, `Red should have mean and std 100, got ${mixed[0]} and ${mixed[1]}`);
+            tester.assertEqual(`${mixed[2]},${mixed[3]}`, '0,200');
+            const green = Array.from({ length: pixels }, (_, i) => i % 256);
+            const mean = green.reduce((a, b) => a + b) / pixels;
+            const std = Math.sqrt(green.reduce((a, b) => a + (b - mean) ** 2, 0) / pixels);
+            tester.assert(Math.abs(mixed[4] - mean) < 1e-9 && Math.abs(mixed[5] - std) < 1e-9, 'Green should match a direct computation');
+            tester.assertEqual(Array.from(mixed.slice(8, 12)).join(','), '7,0,7,7', 'A constant channel has no spread');
+
+            for (const bad of [new Uint8Array(0), new Uint8Array(6)]) {
+                tester.assertThrows(() => processor.image_statistics(bad), 'INVALID_ARGUMENT', `${bad.length} bytes should be rejected`);
+            }
+        });
+
+        // Test 94: Deterministic floating-point reductions
+        tester.test('Deterministic Reductions', () => {
+            const processor = new tester.wasm.WasmParallelProcessor(0);
+            tester.assert(!processor.deterministic, 'Processors should start out non-deterministic');
+            processor.set_deterministic(true);
+            tester.assert(processor.deterministic, 'The switch should be reported');
+
+            const data = new Float64Array(100003).map((_, i) => ((i * 7919) % 10007 - 5003) * 10 ** ((i % 17) - 8));
+            const sum = processor.parallel_sum_f64(data);
+            const direct = data.reduce((a, b) => a + b, 0);
+            tester.assert(Math.abs(sum - direct) <= 1e-9 * Math.abs(direct) + 1e-9, `Sum should be close to ${direct}, got ${sum}`);
+            tester.assert(Object.is(processor.parallel_sum_f64(data), sum), 'Repeated sums should be identical');
+
+            tester.assertEqual(processor.parallel_variance(new Float64Array([2, 4, 4, 4, 5, 5, 7, 9])), 4, 'Population variance');
+            tester.assertEqual(processor.parallel_variance(new Float64Array([1])), 0, 'One value has no variance');
+        });
+
+        // Test 95: Bézier curve sampling
+        tester.test('Bezier Sampling', () => {
+            const processor = new tester.wasm.WasmParallelProcessor(0);
+            // An arch from (0, 0) up over to (1, 0)
+            const cubic = new Float64Array([0, 0, 0, 1, 1, 1, 1, 0]);
+
+            const points = processor.parallel_bezier_sample(cubic, 3, new Float64Array([0, 0.25, 0.5, 1]));
+            const expected = [0, 0, 0.15625, 0.5625, 0.5, 0.75, 1, 0];
+            tester.assertEqual(points.length, 8, 'One point per t');
+            expected.forEach((value, i) => {
+                tester.assert(Math.abs(points[i] - value) < 1e-12, `Coordinate ${i} should be ${value}, got ${points[i]}`);
+            });
+
+            const many = new Float64Array(5000).map((_, i) => i / 4999);
+            const curve = processor.parallel_bezier_sample(cubic, 3, many);
+            many.forEach((t, i) => {
+                const u = 1 - t;
+                const x = 3 * u * t * t + t ** 3;
+                const y = 3 * u * u * t + 3 * u * t * t;
+                tester.assert(Math.abs(curve[2 * i] - x) < 1e-12 && Math.abs(curve[2 * i + 1] - y) < 1e-12, `Point at t=${t} should match the Bernstein form`);
+            });
+
+            const coarse = processor.parallel_bezier_adaptive_sample(cubic, 3, 0.01);
+            const fine = processor.parallel_bezier_adaptive_sample(cubic, 3, 0.0001);
+            tester.assert(fine.length > coarse.length, 'A tighter tolerance should give more points');
+            tester.assertEqual(`${coarse[0]},${coarse[1]}`, '0,0', 'Adaptive samples start at the curve start');
+            tester.assertEqual(`${coarse[coarse.length - 2]},${coarse[coarse.length - 1]}`, '1,0', 'Adaptive samples end at the curve end');
+            const line = processor.parallel_bezier_adaptive_sample(new Float64Array([0, 0, 1, 1, 2, 2]), 2, 0.01);
+            tester.assertEqual(line.length, 2 + 2 * 16, 'A straight curve needs one point per span');
+
+            const failures = [
+                () => processor.parallel_bezier_sample(cubic, 2, new Float64Array([0.5])),
+                () => processor.parallel_bezier_sample(cubic, 3, new Float64Array([0.5, 1.5])),
+                () => processor.parallel_bezier_adaptive_sample(cubic, 3, 0),
+            ];
+            for (const [i, fail] of failures.entries()) {
+                tester.assertThrows(fail, ['DIMENSION_MISMATCH', 'OUT_OF_DOMAIN', 'INVALID_ARGUMENT'][i], `Case ${i} should be rejected`);
+            }
+        });
+
+        // Test 96: Byte frequency analysis
+        tester.test('Byte Frequency Analysis', () => {
+            const processor = new tester.wasm.WasmParallelProcessor(0);
+
+            // xorshift32 bytes, close to uniform
+            const random = new Uint8Array(1 << 20);
+            let state = 2463534242;
+            for (let i = 0; i < random.length; i++) {
+                state ^= state << 13;
+                state ^= state >>> 17;
+                state ^= state << 5;
+                state >>>= 0;
+                random[i] = state >>> 24;
+            }
+            const ioc = processor.parallel_index_of_coincidence(random);
+            tester.assert(Math.abs(ioc - 0.00390625) < 0.0001, `Random bytes should have IoC near 1/256, got ${ioc}`);
+            const chi = processor.parallel_chi_squared_uniformity(random);
+            tester.assert(chi < 400, `Random bytes should look uniform, chi-squared ${chi}`);
+
+            const constant = new Uint8Array(1000).fill(65);
+            tester.assertEqual(processor.parallel_index_of_coincidence(constant), 1, 'One repeated byte has IoC 1');
+            tester.assertEqual(processor.parallel_chi_squared_uniformity(constant), 255 * 1000, 'All mass in one bin');
+            tester.assertEqual(processor.parallel_index_of_coincidence(new Uint8Array([7])), 0, 'One byte has no pairs');
+
+            const bigrams = processor.parallel_bigram_frequency(random);
+            tester.assertEqual(bigrams.length, 65536, 'One entry per byte pair');
+            tester.assertEqual(bigrams.reduce((a, b) => a + b, 0), random.length - 1, 'Every consecutive pair should be counted once, across chunk boundaries');
+            const small = processor.parallel_bigram_frequency(new Uint8Array([1, 2, 1, 2, 3]));
+            tester.assertEqual(small[1 * 256 + 2], 2, '(1, 2) occurs twice');
+            tester.assertEqual(small[2 * 256 + 1] + small[2 * 256 + 3], 2, '(2, 1) and (2, 3) once each');
+        });
+
+        // Test 97: Buffer pool
+        tester.test('Buffer Pool', () => {
+            const runtime = tester.wasm.WasmRuntime.global();
+            const processor = tester.wasm.WasmImageProcessor.with_runtime(runtime);
+            const [width, height] = [40, 30];
+            const rgba = new Uint8Array(width * height * 4).map((_, i) => (i * 37) % 256);
+
+            // The global runtime's pool may hold buffers from earlier tests
+            runtime.trim();
+            const empty = runtime.pool_stats();
+            tester.assertEqual(empty.held_bytes + empty.held_buffers, 0, 'Nothing is held after trimming');
+
+            const first = processor.canny_edges(rgba, width, height, 10, 40);
+            const filled = runtime.pool_stats();
+            tester.assert(filled.misses > empty.misses && filled.held_buffers > 0, 'The first run fills the pool');
+            tester.assertEqual(runtime.heap_bytes(), filled.held_bytes, 'heap_bytes reports the pool');
+
+            const second = processor.canny_edges(rgba, width, height, 10, 40);
+            const reused = runtime.pool_stats();
+            tester.assertEqual(reused.misses, filled.misses, 'A second run reuses every buffer');
+            tester.assert(reused.hits > filled.hits, `Expected hits, got ${reused.hits - filled.hits}`);
+            tester.assertEqual(Array.from(second).join(), Array.from(first).join(), 'Reused buffers give the same result');
+
+            tester.assertEqual(runtime.trim(), reused.held_bytes, 'trim releases everything held');
+            processor.free();
+        });
+
+        // Test 98: Levenshtein distance
+        tester.test('Levenshtein Distance', () => {
+            const processor = new tester.wasm.WasmParallelProcessor(0);
+
+            const distances = processor.parallel_levenshtein_batch(
+                ['kitten', 'flaw', '', 'same', 'naïve'],
+                ['sitting', 'lawn', 'abc', 'same', 'naive']
+            );
+            tester.assertEqual(Array.from(distances).join(), '3,2,3,0,1', 'Known distances');
+
+            const candidates = ['sitting', 'kitchen', 'mitten', 'bitten'];
+            tester.assertEqual(processor.parallel_closest_match('kitten', candidates), 2, 'mitten is the first candidate one edit away');
+
+            tester.assertThrows(() => processor.parallel_levenshtein_batch(['a', 'b'], ['a']), 'DIMENSION_MISMATCH', 'Arrays of different lengths should be rejected');
+            const notString = tester.assertThrows(() => processor.parallel_closest_match('a', ['b', 3]), 'INVALID_ARGUMENT');
+            tester.assert(notString.message.includes('candidates element 1'), 'A candidate that is not a string should be named');
+        });
+
+        // Test 99: Memory-efficient processor
+        tester.test('Memory-Efficient Processor', () => {
+            const processor = new tester.wasm.MemoryEfficientProcessor(4096, 0);
+            tester.assertEqual(processor.chunk_size, 1024, 'Default chunk size');
+            tester.assert(processor.heap_bytes() >= 4096 * 8, 'The buffer is preallocated');
+
+            const data = new Float64Array(3000).map((_, i) => i);
+            const out = new Float64Array(data.length);
+            processor.process_into(data, tester.wasm.BatchOp.Sqrt, out);
+            tester.assertEqual(out[2500], Math.sqrt(2500), 'process_into writes the caller buffer');
+
+            processor.set_chunk_size(100);
+            const view = processor.process_view(data, tester.wasm.BatchOp.Square);
+            tester.assertEqual(view.length, data.length, 'The view covers the results');
+            tester.assertEqual(view[1234], 1234 * 1234, 'The view reads the results');
+            const copy = view.slice();
+            processor.process_view(data, tester.wasm.BatchOp.Neg);
+            tester.assertEqual(copy[10], 100, 'A copy outlives the next call');
+
+            tester.assertThrows(() => processor.set_chunk_size(0), 'INVALID_ARGUMENT', 'A zero chunk size is rejected');
+            processor.free();
+        });
+
+        // Test 100: Matrix scratch arena
+        tester.test('Matrix Scratch Arena', () => {
+            const processor = new tester.wasm.WasmMatrixProcessor(0);
+            tester.assertEqual(processor.scratch_bytes(), 0, 'No scratch before the first operation');
+
+            const a = new Float64Array([4, 1, 0, 1, 3, 1, 0, 1, 2]);
+            const b = new Float64Array([1, 2, 3]);
+            const expected = processor.solve_linear_system(a, b, 3);
+            const held = processor.scratch_bytes();
+            tester.assert(held >= 3 * 4 * 8, 'The augmented matrix is kept');
+
+            const out = new Float64Array(3);
+            processor.solve_linear_system_into(a, b, 3, out);
+            tester.assertArrayEqual(Array.from(out), Array.from(expected), 'solve_linear_system_into writes the caller buffer');
+            tester.assertEqual(processor.scratch_bytes(), held, 'A same-sized solve reuses the scratch');
+
+            processor.scratch_reset();
+            tester.assertEqual(processor.scratch_bytes(), 0, 'scratch_reset frees the scratch');
+
+            tester.assertThrows(() => processor.solve_linear_system_into(a, b, 3, new Float64Array(2)), 'DIMENSION_MISMATCH', 'A short output is rejected');
+            processor.free();
+        });
+
+        // Test 101: Bloom filter
+        tester.test('Bloom Filter', () => {
+            const filter = new tester.wasm.WasmBloomFilter(1000, 0.01);
+            tester.assertEqual(filter.num_hashes, 7, 'Optimal hash count');
+            tester.assert(filter.num_bits >= 9586, 'Optimal bit count');
+
+            const members = new BigUint64Array(1000).map((_, i) => BigInt(i) * 7919n);
+            filter.parallel_insert_batch(members);
+            const found = filter.parallel_contains_batch(members);
+            tester.assert(found.every(present => present === true), 'No false negatives');
+
+            const others = new BigUint64Array(10000).map((_, i) => BigInt(i) * 7919n + 1n);
+            const positives = filter.parallel_contains_batch(others).filter(Boolean).length;
+            tester.assert(positives < 200, `Few false positives (${positives})`);
+
+            const copy = tester.wasm.WasmBloomFilter.deserialize(filter.serialize());
+            tester.assertArrayEqual(
+                copy.parallel_contains_batch(others),
+                filter.parallel_contains_batch(others),
+                'A deserialized copy answers the same'
+            );
+
+            tester.assertThrows(() => tester.wasm.WasmBloomFilter.deserialize(new Uint8Array([1, 2, 3])), 'INVALID_ENCODING', 'Malformed bytes are rejected');
+            copy.free();
+            filter.free();
+        });
+
+        // Test 102: NDJSON field extraction
+        tester.test('NDJSON Float Parsing', () => {
+            const processor = new tester.wasm.WasmParallelProcessor(0);
+            const lines = [];
+            for (let i = 0; i < 1000; i++) {
+                lines.push(i % 10 === 0
+                    ? JSON.stringify({ id: i, metrics: { mem: i } })
+                    : JSON.stringify({ id: i, metrics: { cpu: i / 8, tags: ['a', { cpu: 0 }] } }));
+            }
+            lines.splice(500, 0, '', '   ');
+            const values = processor.parallel_parse_ndjson_floats(lines.join('\n'), 'metrics.cpu');
+            tester.assertEqual(values.length, 1000, 'One value per non-blank line');
+            tester.assertEqual(values[7], 7 / 8, 'Nested field extracted');
+            tester.assert(Number.isNaN(values[10]), 'A missing field is NaN');
+
+            const error = tester.assertThrows(() => processor.parallel_parse_ndjson_floats('{"a":1}\n{"a":[1,}', 'a'), 'INVALID_JSON', 'Invalid JSON is rejected');
+            tester.assertEqual(error.details.line, 2, 'The bad line is named');
+            processor.free();
+        });
+
         await tester.runTests();
 
     } catch (error) {